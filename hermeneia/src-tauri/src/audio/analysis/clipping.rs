@@ -0,0 +1,244 @@
+// src-tauri/src/audio/analysis/clipping.rs
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::types::AudioData;
+use crate::error::{AnalysisError, AudioError, Result};
+use crate::profile;
+use crate::progress::{check_cancelled, NoProgress, ProgressSink};
+
+/// Settings for clipping detection
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ClippingOptions {
+    /// Level (dBFS) at or above which a sample counts as clipped
+    pub threshold_db: f64,
+    /// Shortest run of consecutive clipped samples on one channel to report
+    ///
+    /// Single full-scale samples turn up in perfectly healthy masters; flat
+    /// runs of several are what a clipped waveform looks like.
+    pub min_run_samples: usize,
+}
+
+impl Default for ClippingOptions {
+    fn default() -> Self {
+        Self {
+            threshold_db: -0.01,
+            min_run_samples: 3,
+        }
+    }
+}
+
+/// A stretch where at least one channel is clipped
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ClipRegion {
+    pub start_seconds: f64,
+    pub end_seconds: f64,
+}
+
+/// Clipping found in a piece of audio
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ClippingReport {
+    /// Samples (across all channels) inside a reported run
+    pub clipped_samples: usize,
+    /// Clipped stretches, earliest first; overlapping runs on different channels are merged
+    pub regions: Vec<ClipRegion>,
+}
+
+/// Find runs of samples stuck at (or near) full scale
+///
+/// Each channel is scanned on its own for runs of at least
+/// `min_run_samples` consecutive samples whose magnitude reaches the
+/// threshold. Runs are then merged across channels into time regions.
+///
+/// # Arguments
+/// * `audio` - The audio to scan
+/// * `options` - Threshold and minimum run length
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::{detect_clipping, AudioData, ClippingOptions};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut samples = vec![0.5f32; 1000];
+/// samples[500..510].fill(1.0);
+/// let audio = AudioData { samples, sample_rate: 1000, channels: 1 };
+///
+/// let report = detect_clipping(&audio, &ClippingOptions::default())?;
+/// assert_eq!(report.clipped_samples, 10);
+/// assert_eq!(report.regions.len(), 1);
+/// # Ok(())
+/// # }
+/// ```
+pub fn detect_clipping(audio: &AudioData, options: &ClippingOptions) -> Result<ClippingReport> {
+    detect_clipping_with_progress(audio, options, &mut NoProgress)
+}
+
+/// Detect like [`detect_clipping`], reporting the "analyze" stage per
+/// channel and stopping with [`AudioError::Cancelled`] when asked
+pub fn detect_clipping_with_progress(
+    audio: &AudioData,
+    options: &ClippingOptions,
+    progress: &mut dyn ProgressSink,
+) -> Result<ClippingReport> {
+    let _stage = profile::stage("analyze");
+    progress.stage("analyze");
+    if audio.channels == 0 || audio.sample_rate == 0 {
+        return Err(AnalysisError::EmptyFormat {
+            channels: audio.channels,
+            sample_rate: audio.sample_rate,
+        }
+        .into());
+    }
+    if !options.threshold_db.is_finite() {
+        return Err(AudioError::InvalidParameter(format!(
+            "Clipping threshold must be finite (got {})",
+            options.threshold_db
+        )));
+    }
+
+    let channels = audio.channels as usize;
+    let threshold = 10f64.powf(options.threshold_db / 20.0) as f32;
+    let min_run = options.min_run_samples.max(1);
+
+    // Runs as (start frame, end frame), collected per channel
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    let mut clipped_samples = 0;
+
+    for channel in 0..channels {
+        check_cancelled(progress)?;
+        progress.progress(channel as f64 / channels as f64);
+        let mut run_start: Option<usize> = None;
+        let samples = audio.channel(channel);
+
+        for (frame, &sample) in samples.enumerate() {
+            match (sample.abs() >= threshold, run_start) {
+                (true, None) => run_start = Some(frame),
+                (false, Some(start)) => {
+                    if frame - start >= min_run {
+                        runs.push((start, frame));
+                        clipped_samples += frame - start;
+                    }
+                    run_start = None;
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(start) = run_start {
+            let end = audio.frame_count();
+            if end - start >= min_run {
+                runs.push((start, end));
+                clipped_samples += end - start;
+            }
+        }
+    }
+
+    progress.progress(1.0);
+
+    runs.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in runs {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+
+    let sample_rate = audio.sample_rate as f64;
+    Ok(ClippingReport {
+        clipped_samples,
+        regions: merged
+            .into_iter()
+            .map(|(start, end)| ClipRegion {
+                start_seconds: start as f64 / sample_rate,
+                end_seconds: end as f64 / sample_rate,
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_audio_has_no_clipping() {
+        let audio = AudioData {
+            samples: (0..1000).map(|i| (i as f32 * 0.1).sin() * 0.9).collect(),
+            sample_rate: 1000,
+            channels: 1,
+        };
+        let report = detect_clipping(&audio, &ClippingOptions::default()).unwrap();
+        assert_eq!(report, ClippingReport::default());
+    }
+
+    #[test]
+    fn test_isolated_peaks_are_ignored() {
+        let mut samples = vec![0.2f32; 100];
+        samples[10] = 1.0;
+        samples[11] = -1.0;
+        samples[50] = 1.0;
+        let audio = AudioData {
+            samples,
+            sample_rate: 100,
+            channels: 1,
+        };
+        let report = detect_clipping(&audio, &ClippingOptions::default()).unwrap();
+        assert!(report.regions.is_empty());
+    }
+
+    #[test]
+    fn test_runs_merge_across_channels() {
+        // Stereo: left clips frames 10..20, right clips 15..25, right again at the very end
+        let mut samples = vec![0.1f32; 200];
+        for frame in 10..20 {
+            samples[frame * 2] = 1.0;
+        }
+        for frame in 15..25 {
+            samples[frame * 2 + 1] = -1.0;
+        }
+        for frame in 96..100 {
+            samples[frame * 2 + 1] = 1.0;
+        }
+        let audio = AudioData {
+            samples,
+            sample_rate: 100,
+            channels: 2,
+        };
+
+        let report = detect_clipping(&audio, &ClippingOptions::default()).unwrap();
+        assert_eq!(report.clipped_samples, 24);
+        assert_eq!(
+            report.regions,
+            vec![
+                ClipRegion {
+                    start_seconds: 0.1,
+                    end_seconds: 0.25
+                },
+                ClipRegion {
+                    start_seconds: 0.96,
+                    end_seconds: 1.0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_threshold_is_respected() {
+        let audio = AudioData {
+            samples: vec![0.9f32; 50],
+            sample_rate: 50,
+            channels: 1,
+        };
+        assert!(detect_clipping(&audio, &ClippingOptions::default())
+            .unwrap()
+            .regions
+            .is_empty());
+
+        let low = ClippingOptions {
+            threshold_db: -1.0,
+            ..Default::default()
+        };
+        assert_eq!(detect_clipping(&audio, &low).unwrap().clipped_samples, 50);
+    }
+}
@@ -0,0 +1,415 @@
+// src-tauri/src/audio/analysis/compare.rs
+
+use rustfft::num_complex::Complex;
+use rustfft::FftPlanner;
+use serde::{Deserialize, Serialize};
+
+use crate::audio::dsp::{db_to_linear, from_real, linear_to_db, to_real};
+use crate::audio::types::AudioData;
+use crate::error::{AnalysisError, AudioError, Result};
+use crate::profile;
+use crate::progress::{check_cancelled, NoProgress, ProgressSink};
+
+/// Only the opening stretch of each file is used to find the alignment
+const ALIGN_WINDOW_SECONDS: f64 = 30.0;
+
+/// Settings for [`compare_audio`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CompareOptions {
+    /// Largest offset (either direction) searched when aligning the two inputs
+    pub max_offset_seconds: f64,
+    /// Differences above this level (dBFS) count as a mismatch
+    pub tolerance_db: f64,
+    /// Granularity of the mismatch regions
+    pub region_seconds: f64,
+}
+
+impl Default for CompareOptions {
+    fn default() -> Self {
+        Self {
+            max_offset_seconds: 1.0,
+            // Just above the rounding error of a 16-bit round trip
+            tolerance_db: -90.0,
+            region_seconds: 0.1,
+        }
+    }
+}
+
+/// A stretch where the two inputs differ by more than the tolerance
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MismatchRegion {
+    /// Start, in seconds on the reference timeline
+    pub start_seconds: f64,
+    /// End, in seconds on the reference timeline
+    pub end_seconds: f64,
+    /// Largest sample difference inside the region (linear)
+    pub peak_difference: f32,
+}
+
+/// Result of comparing a candidate against a reference
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComparisonReport {
+    /// Frames the candidate is delayed by relative to the reference (negative = ahead)
+    pub offset_frames: i64,
+    pub offset_seconds: f64,
+    /// Frames present in both inputs after alignment
+    pub compared_frames: usize,
+    /// Frames only present in the reference (positive) or candidate (negative)
+    pub length_difference_frames: i64,
+    /// Largest absolute sample difference (linear)
+    pub peak_difference: f32,
+    pub peak_difference_db: f64,
+    /// RMS of the sample differences (linear)
+    pub rms_difference: f64,
+    pub rms_difference_db: f64,
+    pub mismatches: Vec<MismatchRegion>,
+}
+
+impl ComparisonReport {
+    /// True when the aligned samples match exactly and nothing is left over
+    pub fn is_identical(&self) -> bool {
+        self.offset_frames == 0 && self.length_difference_frames == 0 && self.peak_difference == 0.0
+    }
+}
+
+/// Align two versions of the same audio and measure how far apart they are
+///
+/// The offset between them is found by cross-correlating a mono mix of the
+/// first 30 seconds, so a candidate with extra encoder delay or a trimmed
+/// lead-in still lines up. The overlapping part is then compared sample by
+/// sample.
+///
+/// Both inputs must share a sample rate and channel count; convert one of
+/// them first if they don't.
+///
+/// # Arguments
+/// * `reference` - The original audio
+/// * `candidate` - The processed or round-tripped audio
+/// * `options` - Alignment range, mismatch tolerance and region size
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::{compare_audio, AudioData, CompareOptions};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let reference = AudioData {
+///     samples: (0..8000).map(|i| ((i * 7919) % 200) as f32 / 100.0 - 1.0).collect(),
+///     sample_rate: 8000,
+///     channels: 1,
+/// };
+/// let report = compare_audio(&reference, &reference.clone(), &CompareOptions::default())?;
+/// assert!(report.is_identical());
+/// # Ok(())
+/// # }
+/// ```
+pub fn compare_audio(
+    reference: &AudioData,
+    candidate: &AudioData,
+    options: &CompareOptions,
+) -> Result<ComparisonReport> {
+    compare_audio_with_progress(reference, candidate, options, &mut NoProgress)
+}
+
+/// Compare like [`compare_audio`], reporting the "analyze" stage per region
+/// and stopping with [`AudioError::Cancelled`] when asked
+pub fn compare_audio_with_progress(
+    reference: &AudioData,
+    candidate: &AudioData,
+    options: &CompareOptions,
+    progress: &mut dyn ProgressSink,
+) -> Result<ComparisonReport> {
+    let _stage = profile::stage("analyze");
+    progress.stage("analyze");
+    check_formats(reference, candidate)?;
+    if !(options.region_seconds.is_finite() && options.region_seconds > 0.0) {
+        return Err(AudioError::InvalidParameter(format!(
+            "Region length must be positive (got {}s)",
+            options.region_seconds
+        )));
+    }
+
+    let channels = reference.channels as usize;
+    let rate = reference.sample_rate as f64;
+    let aligned = Alignment::find(reference, candidate, options.max_offset_seconds);
+    let (offset, ref_start, compared) = (aligned.offset, aligned.ref_start, aligned.compared);
+    let (ref_frames, cand_frames) = (aligned.ref_frames, aligned.cand_frames);
+    let (reference_samples, candidate_samples) = aligned.slices(reference, candidate);
+
+    let tolerance = db_to_linear(options.tolerance_db) as f32;
+    let region_frames = ((options.region_seconds * rate).round() as usize).max(1);
+
+    let mut peak = 0.0f32;
+    let mut sum_squares = 0.0f64;
+    let mut mismatches: Vec<MismatchRegion> = Vec::new();
+    let region_count = compared.div_ceil(region_frames);
+
+    for (i, (ref_region, cand_region)) in reference_samples
+        .chunks(region_frames * channels)
+        .zip(candidate_samples.chunks(region_frames * channels))
+        .enumerate()
+    {
+        check_cancelled(progress)?;
+        progress.progress(i as f64 / region_count as f64);
+        let mut region_peak = 0.0f32;
+        for (&r, &c) in ref_region.iter().zip(cand_region) {
+            let diff = (to_real(r) - to_real(c)).abs();
+            region_peak = region_peak.max(from_real(diff));
+            #[allow(clippy::useless_conversion)]
+            let diff = f64::from(diff);
+            sum_squares += diff * diff;
+        }
+        peak = peak.max(region_peak);
+
+        if region_peak > tolerance {
+            let start = (ref_start + i * region_frames) as f64 / rate;
+            let end = (ref_start + i * region_frames + ref_region.len() / channels) as f64 / rate;
+            match mismatches.last_mut() {
+                // Extend the previous region when this one directly follows it
+                Some(last) if (last.end_seconds - start).abs() < 1e-9 => {
+                    last.end_seconds = end;
+                    last.peak_difference = last.peak_difference.max(region_peak);
+                }
+                _ => mismatches.push(MismatchRegion {
+                    start_seconds: start,
+                    end_seconds: end,
+                    peak_difference: region_peak,
+                }),
+            }
+        }
+    }
+
+    progress.progress(1.0);
+
+    let rms = if reference_samples.is_empty() {
+        0.0
+    } else {
+        (sum_squares / reference_samples.len() as f64).sqrt()
+    };
+
+    Ok(ComparisonReport {
+        offset_frames: offset,
+        offset_seconds: offset as f64 / rate,
+        compared_frames: compared,
+        length_difference_frames: ref_frames as i64 - cand_frames as i64,
+        peak_difference: peak,
+        peak_difference_db: linear_to_db(peak as f64),
+        rms_difference: rms,
+        rms_difference_db: linear_to_db(rms),
+        mismatches,
+    })
+}
+
+/// Both inputs must share a usable sample rate and channel count
+pub(super) fn check_formats(reference: &AudioData, candidate: &AudioData) -> Result<()> {
+    if reference.sample_rate != candidate.sample_rate || reference.channels != candidate.channels {
+        return Err(AnalysisError::FormatMismatch {
+            reference: format!("{} Hz/{} ch", reference.sample_rate, reference.channels),
+            candidate: format!("{} Hz/{} ch", candidate.sample_rate, candidate.channels),
+        }
+        .into());
+    }
+    if reference.channels == 0 || reference.sample_rate == 0 {
+        return Err(AnalysisError::EmptyFormat {
+            channels: reference.channels,
+            sample_rate: reference.sample_rate,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Where two inputs overlap once the candidate is shifted into line
+pub(super) struct Alignment {
+    /// Frames the candidate is delayed by (negative = ahead)
+    pub offset: i64,
+    /// First overlapping frame in each input
+    pub ref_start: usize,
+    pub cand_start: usize,
+    /// Frames from the start of the overlap to the end of each input
+    pub ref_frames: usize,
+    pub cand_frames: usize,
+    /// Frames present in both
+    pub compared: usize,
+}
+
+impl Alignment {
+    /// Search up to `max_offset_seconds` either way for the best alignment
+    pub fn find(reference: &AudioData, candidate: &AudioData, max_offset_seconds: f64) -> Self {
+        let rate = reference.sample_rate as f64;
+        let max_offset = (max_offset_seconds.max(0.0) * rate) as usize;
+        let offset = find_offset(reference, candidate, max_offset);
+        let (ref_start, cand_start) = if offset >= 0 {
+            (offset as usize, 0)
+        } else {
+            (0, (-offset) as usize)
+        };
+        let ref_frames = reference.frame_count().saturating_sub(ref_start);
+        let cand_frames = candidate.frame_count().saturating_sub(cand_start);
+        Self {
+            offset,
+            ref_start,
+            cand_start,
+            ref_frames,
+            cand_frames,
+            compared: ref_frames.min(cand_frames),
+        }
+    }
+
+    /// The overlapping samples of each input
+    pub fn slices<'a>(
+        &self,
+        reference: &'a AudioData,
+        candidate: &'a AudioData,
+    ) -> (&'a [f32], &'a [f32]) {
+        let channels = reference.channels as usize;
+        let (ref_end, cand_end) = (self.ref_start + self.compared, self.cand_start + self.compared);
+        (
+            &reference.samples[self.ref_start * channels..ref_end * channels],
+            &candidate.samples[self.cand_start * channels..cand_end * channels],
+        )
+    }
+}
+
+/// Lag (in frames) that best lines `candidate` up with `reference`
+///
+/// Positive means the reference has extra material at the start.
+fn find_offset(reference: &AudioData, candidate: &AudioData, max_offset: usize) -> i64 {
+    if max_offset == 0 {
+        return 0;
+    }
+
+    let window = (ALIGN_WINDOW_SECONDS * reference.sample_rate as f64) as usize + max_offset;
+    let a = mono_prefix(reference, window);
+    let b = mono_prefix(candidate, window);
+    if a.is_empty() || b.is_empty() {
+        return 0;
+    }
+
+    // Circular cross-correlation via FFT, padded so lags don't wrap into each other
+    let size = (a.len() + b.len()).next_power_of_two();
+    let mut planner = FftPlanner::<f64>::new();
+    let forward = planner.plan_fft_forward(size);
+    let inverse = planner.plan_fft_inverse(size);
+
+    let mut fa: Vec<Complex<f64>> = a.iter().map(|&x| Complex::new(x, 0.0)).collect();
+    fa.resize(size, Complex::default());
+    let mut fb: Vec<Complex<f64>> = b.iter().map(|&x| Complex::new(x, 0.0)).collect();
+    fb.resize(size, Complex::default());
+
+    forward.process(&mut fa);
+    forward.process(&mut fb);
+    for (x, y) in fa.iter_mut().zip(&fb) {
+        *x *= y.conj();
+    }
+    inverse.process(&mut fa);
+
+    // Index k holds lag k; index size - k holds lag -k
+    let max_lag = max_offset.min(size / 2 - 1);
+    let mut best = (0i64, fa[0].re);
+    for lag in 1..=max_lag {
+        for (signed, value) in [(lag as i64, fa[lag].re), (-(lag as i64), fa[size - lag].re)] {
+            if value > best.1 {
+                best = (signed, value);
+            }
+        }
+    }
+
+    // Silence or noise with no real peak: don't shift
+    if best.1 <= 0.0 {
+        0
+    } else {
+        best.0
+    }
+}
+
+/// Average channels together over the first `frames` frames
+fn mono_prefix(audio: &AudioData, frames: usize) -> Vec<f64> {
+    let channels = audio.channels as usize;
+    audio
+        .frames()
+        .take(frames)
+        .map(|frame| frame.iter().map(|&s| s as f64).sum::<f64>() / channels as f64)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic noise so cross-correlation has a single sharp peak
+    fn noise(frames: usize, channels: u16) -> AudioData {
+        let mut state = 0x1234_5678u32;
+        let samples = (0..frames * channels as usize)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state as f32 / u32::MAX as f32) * 2.0 - 1.0
+            })
+            .collect();
+        AudioData {
+            samples,
+            sample_rate: 8000,
+            channels,
+        }
+    }
+
+    #[test]
+    fn test_identical_inputs() {
+        let audio = noise(8000, 2);
+        let report = compare_audio(&audio, &audio.clone(), &CompareOptions::default()).unwrap();
+        assert!(report.is_identical());
+        assert!(report.mismatches.is_empty());
+        assert_eq!(report.compared_frames, 8000);
+        assert_eq!(report.peak_difference_db, f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_finds_delay_in_either_direction() {
+        let reference = noise(16000, 1);
+
+        // Candidate has 100 frames of extra lead-in
+        let mut delayed = reference.clone();
+        delayed.samples.splice(0..0, std::iter::repeat_n(0.0, 100));
+        let report = compare_audio(&reference, &delayed, &CompareOptions::default()).unwrap();
+        assert_eq!(report.offset_frames, -100);
+        assert_eq!(report.peak_difference, 0.0);
+        assert_eq!(report.length_difference_frames, 0);
+
+        // Candidate is missing the first 250 frames
+        let trimmed = AudioData {
+            samples: reference.samples[250..].to_vec(),
+            ..reference.clone()
+        };
+        let report = compare_audio(&reference, &trimmed, &CompareOptions::default()).unwrap();
+        assert_eq!(report.offset_frames, 250);
+        assert_eq!(report.peak_difference, 0.0);
+    }
+
+    #[test]
+    fn test_reports_mismatch_regions() {
+        let reference = noise(8000, 1);
+        let mut candidate = reference.clone();
+        // Damage 0.50s..0.55s and 0.80s..0.81s
+        for s in &mut candidate.samples[4000..4400] {
+            *s *= 0.5;
+        }
+        candidate.samples[6450] += 0.25;
+
+        let report = compare_audio(&reference, &candidate, &CompareOptions::default()).unwrap();
+        assert_eq!(report.offset_frames, 0);
+        assert_eq!(report.mismatches.len(), 2);
+        assert!((report.mismatches[0].start_seconds - 0.5).abs() < 1e-9);
+        assert!((report.mismatches[0].end_seconds - 0.6).abs() < 1e-9);
+        assert!((report.mismatches[1].start_seconds - 0.8).abs() < 1e-9);
+        assert!((report.mismatches[1].peak_difference - 0.25).abs() < 1e-6);
+        assert!(report.rms_difference > 0.0);
+    }
+
+    #[test]
+    fn test_format_mismatch_rejected() {
+        let mono = noise(100, 1);
+        let stereo = noise(100, 2);
+        assert!(compare_audio(&mono, &stereo, &CompareOptions::default()).is_err());
+    }
+}
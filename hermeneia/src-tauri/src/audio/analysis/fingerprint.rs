@@ -0,0 +1,451 @@
+// src-tauri/src/audio/analysis/fingerprint.rs
+
+use rustfft::num_complex::Complex;
+use rustfft::FftPlanner;
+use serde::{Deserialize, Serialize};
+
+use crate::audio::resample::resample_audio;
+use crate::audio::types::AudioData;
+use crate::error::{AnalysisError, AudioError, Result};
+use crate::profile;
+use crate::progress::{check_cancelled, NoProgress, ProgressSink};
+
+/// Algorithm version stored in every [`Fingerprint`]
+///
+/// Bump whenever a change would alter the items computed for the same
+/// audio; fingerprints of different versions refuse to compare.
+pub const FINGERPRINT_VERSION: u16 = 1;
+
+/// Audio is mixed to mono and resampled to this rate before analysis
+const ANALYSIS_RATE: u32 = 11025;
+
+/// Samples per spectrum (about 0.37 s)
+const FRAME_LEN: usize = 4096;
+
+/// Samples between spectra; two thirds of each frame overlaps the next
+const HOP_LEN: usize = FRAME_LEN / 3;
+
+/// Frequencies folded into the chroma vector; below and above this band
+/// there is little pitched content that survives lossy encoding
+const MIN_FREQ: f64 = 28.0;
+const MAX_FREQ: f64 = 3520.0;
+
+/// Taps of the filter smoothing the chroma vectors over time
+const SMOOTHING: [f64; 5] = [0.25, 0.75, 1.0, 0.75, 0.25];
+
+/// Chroma vectors quieter than this (after normalization) count as silence
+const MIN_CHROMA_NORM: f64 = 0.01;
+
+/// Fingerprints closer than this are considered the same recording
+const MATCH_SIMILARITY: f64 = 0.5;
+
+/// Settings for [`fingerprint_audio`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FingerprintOptions {
+    /// Only fingerprint this much audio from the start; `None` for all of it
+    pub max_seconds: Option<f64>,
+}
+
+impl Default for FingerprintOptions {
+    fn default() -> Self {
+        Self {
+            // Enough to tell recordings apart, and keeps long sermons cheap
+            max_seconds: Some(120.0),
+        }
+    }
+}
+
+/// A compact description of what a recording sounds like
+///
+/// Each item is a 32-bit hash of the pitch content around one moment of
+/// the audio (one item every [`Fingerprint::item_seconds`]). Re-encoding,
+/// resampling, gain changes and mild noise flip only a few bits, so two
+/// copies of the same recording stay close while different recordings
+/// differ in about half their bits.
+///
+/// The scheme follows Chromaprint (chroma features hashed by comparing
+/// neighbouring bins and frames) but is not bit-compatible with it, so
+/// these fingerprints can't be looked up in AcoustID.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    /// [`FINGERPRINT_VERSION`] of the algorithm that produced it
+    pub version: u16,
+    /// Length of the audio that was fingerprinted
+    pub duration_seconds: f64,
+    pub items: Vec<u32>,
+}
+
+impl Fingerprint {
+    /// Time between consecutive items
+    pub fn item_seconds() -> f64 {
+        HOP_LEN as f64 / ANALYSIS_RATE as f64
+    }
+}
+
+/// How closely two fingerprints agree at their best alignment
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FingerprintMatch {
+    /// 1.0 for identical items, around 0.0 for unrelated audio
+    pub similarity: f64,
+    /// Fraction of differing bits over the overlap (0.5 is chance)
+    pub bit_error_rate: f64,
+    /// How much later the same content starts in the candidate (negative = earlier)
+    pub offset_seconds: f64,
+    /// Length of audio the two fingerprints share at that offset
+    pub overlap_seconds: f64,
+}
+
+impl FingerprintMatch {
+    /// Whether the two fingerprints come from the same recording
+    pub fn is_same_recording(&self) -> bool {
+        self.similarity >= MATCH_SIMILARITY
+    }
+}
+
+/// Compute the acoustic fingerprint of a piece of audio
+///
+/// The audio is mixed to mono, resampled to 11025 Hz and cut into
+/// overlapping frames. Each frame's spectrum is folded into 12 pitch
+/// classes, smoothed over time and hashed into one 32-bit item.
+///
+/// Audio shorter than one frame (about 0.37 s) gives an empty fingerprint.
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::{
+///     compare_fingerprints, fingerprint_audio, AudioData, FingerprintOptions,
+/// };
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let audio = AudioData {
+///     samples: (0..80000u32)
+///         .map(|i| (i as f32 * 0.05 * (1 + i / 8000) as f32).sin())
+///         .collect(),
+///     sample_rate: 8000,
+///     channels: 1,
+/// };
+/// let fingerprint = fingerprint_audio(&audio, &FingerprintOptions::default())?;
+/// let result = compare_fingerprints(&fingerprint, &fingerprint.clone())?;
+/// assert!(result.is_same_recording());
+/// # Ok(())
+/// # }
+/// ```
+pub fn fingerprint_audio(audio: &AudioData, options: &FingerprintOptions) -> Result<Fingerprint> {
+    fingerprint_audio_with_progress(audio, options, &mut NoProgress)
+}
+
+/// Fingerprint like [`fingerprint_audio`], reporting the "analyze" stage
+/// per frame and stopping with [`AudioError::Cancelled`] when asked
+pub fn fingerprint_audio_with_progress(
+    audio: &AudioData,
+    options: &FingerprintOptions,
+    progress: &mut dyn ProgressSink,
+) -> Result<Fingerprint> {
+    if audio.channels == 0 || audio.sample_rate == 0 {
+        return Err(AnalysisError::EmptyFormat {
+            channels: audio.channels,
+            sample_rate: audio.sample_rate,
+        }
+        .into());
+    }
+    if let Some(max) = options.max_seconds {
+        if !(max.is_finite() && max > 0.0) {
+            return Err(AudioError::InvalidParameter(format!(
+                "Fingerprint length must be positive (got {}s)",
+                max
+            )));
+        }
+    }
+
+    let frames = match options.max_seconds {
+        Some(max) => audio.frame_count().min((max * audio.sample_rate as f64) as usize),
+        None => audio.frame_count(),
+    };
+    let channels = audio.channels as usize;
+    let mono = AudioData {
+        samples: audio
+            .frames()
+            .take(frames)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+            .collect(),
+        sample_rate: audio.sample_rate,
+        channels: 1,
+    };
+    let mono = resample_audio(&mono, ANALYSIS_RATE)?;
+
+    let _stage = profile::stage("analyze");
+    progress.stage("analyze");
+    let chroma = chroma_frames(&mono.samples, progress)?;
+    let smoothed = smooth(&chroma);
+    let items = smoothed.windows(2).map(|w| hash_frame(&w[0], &w[1])).collect();
+    progress.progress(1.0);
+
+    Ok(Fingerprint {
+        version: FINGERPRINT_VERSION,
+        duration_seconds: frames as f64 / audio.sample_rate as f64,
+        items,
+    })
+}
+
+/// Find the best alignment of two fingerprints and how well they agree there
+///
+/// Every offset at which the fingerprints overlap by at least half of the
+/// shorter one is tried, so a copy with a different lead-in or a cut
+/// ending still matches.
+pub fn compare_fingerprints(
+    reference: &Fingerprint,
+    candidate: &Fingerprint,
+) -> Result<FingerprintMatch> {
+    if reference.version != candidate.version {
+        return Err(AnalysisError::FormatMismatch {
+            reference: format!("fingerprint v{}", reference.version),
+            candidate: format!("fingerprint v{}", candidate.version),
+        }
+        .into());
+    }
+
+    let a = &reference.items;
+    let b = &candidate.items;
+    let min_overlap = (a.len().min(b.len()) / 2).max(1);
+    let mut best: Option<(i64, usize, u64)> = None;
+
+    // Candidate item i lines up with reference item i - offset
+    for offset in -(a.len() as i64) + 1..b.len() as i64 {
+        let (a_start, b_start) = if offset >= 0 {
+            (0, offset as usize)
+        } else {
+            ((-offset) as usize, 0)
+        };
+        let overlap = (a.len() - a_start).min(b.len() - b_start);
+        if overlap < min_overlap {
+            continue;
+        }
+        let errors: u64 = a[a_start..a_start + overlap]
+            .iter()
+            .zip(&b[b_start..b_start + overlap])
+            .map(|(x, y)| (x ^ y).count_ones() as u64)
+            .sum();
+        // Compare error rates without dividing: errors / overlap
+        let better = best.is_none_or(|(_, best_overlap, best_errors)| {
+            errors * (best_overlap as u64) < best_errors * (overlap as u64)
+        });
+        if better {
+            best = Some((offset, overlap, errors));
+        }
+    }
+
+    let item_seconds = Fingerprint::item_seconds();
+    Ok(match best {
+        Some((offset, overlap, errors)) => {
+            let bit_error_rate = errors as f64 / (overlap * 32) as f64;
+            FingerprintMatch {
+                similarity: (1.0 - 2.0 * bit_error_rate).max(0.0),
+                bit_error_rate,
+                offset_seconds: offset as f64 * item_seconds,
+                overlap_seconds: overlap as f64 * item_seconds,
+            }
+        }
+        // One of them is empty: nothing to go on
+        None => FingerprintMatch {
+            similarity: 0.0,
+            bit_error_rate: 0.5,
+            offset_seconds: 0.0,
+            overlap_seconds: 0.0,
+        },
+    })
+}
+
+/// Energy per pitch class of each frame, normalized to unit length
+fn chroma_frames(samples: &[f32], progress: &mut dyn ProgressSink) -> Result<Vec<[f64; 12]>> {
+    if samples.len() < FRAME_LEN {
+        return Ok(Vec::new());
+    }
+
+    let fft = FftPlanner::<f64>::new().plan_fft_forward(FRAME_LEN);
+    let window: Vec<f64> = (0..FRAME_LEN)
+        .map(|i| 0.5 - 0.5 * (2.0 * std::f64::consts::PI * i as f64 / FRAME_LEN as f64).cos())
+        .collect();
+    // Pitch class of each FFT bin inside the analysed band
+    let classes: Vec<Option<usize>> = (0..FRAME_LEN / 2)
+        .map(|bin| {
+            let freq = bin as f64 * ANALYSIS_RATE as f64 / FRAME_LEN as f64;
+            (MIN_FREQ..=MAX_FREQ).contains(&freq).then(|| {
+                let note = 12.0 * (freq / 440.0).log2() + 69.0;
+                (note.round() as i64).rem_euclid(12) as usize
+            })
+        })
+        .collect();
+
+    let count = (samples.len() - FRAME_LEN) / HOP_LEN + 1;
+    let mut buffer = vec![Complex::default(); FRAME_LEN];
+    let mut chroma = Vec::with_capacity(count);
+    for (i, start) in (0..count).map(|i| (i, i * HOP_LEN)) {
+        check_cancelled(progress)?;
+        progress.progress(i as f64 / count as f64);
+
+        for ((out, &s), &w) in buffer.iter_mut().zip(&samples[start..]).zip(&window) {
+            *out = Complex::new(s as f64 * w, 0.0);
+        }
+        fft.process(&mut buffer);
+
+        let mut vector = [0.0f64; 12];
+        for (value, class) in buffer.iter().zip(&classes) {
+            if let Some(class) = class {
+                vector[*class] += value.norm_sqr();
+            }
+        }
+        let norm = vector.iter().map(|v| v * v).sum::<f64>().sqrt();
+        if norm > MIN_CHROMA_NORM {
+            vector.iter_mut().for_each(|v| *v /= norm);
+        } else {
+            vector = [0.0; 12];
+        }
+        chroma.push(vector);
+    }
+    Ok(chroma)
+}
+
+/// Run the [`SMOOTHING`] filter along time, dropping the frames it can't cover
+fn smooth(chroma: &[[f64; 12]]) -> Vec<[f64; 12]> {
+    chroma
+        .windows(SMOOTHING.len())
+        .map(|window| {
+            let mut out = [0.0f64; 12];
+            for (frame, tap) in window.iter().zip(SMOOTHING) {
+                for (o, v) in out.iter_mut().zip(frame) {
+                    *o += v * tap;
+                }
+            }
+            out
+        })
+        .collect()
+}
+
+/// Hash one smoothed chroma vector (and the next) into 32 bits
+///
+/// Bits 0-11 compare each pitch class with its upper neighbour, bits 12-23
+/// whether each class grows into the next frame, and bits 24-31 compare
+/// classes a minor third apart.
+fn hash_frame(current: &[f64; 12], next: &[f64; 12]) -> u32 {
+    let mut item = 0u32;
+    for b in 0..12 {
+        if current[b] > current[(b + 1) % 12] {
+            item |= 1 << b;
+        }
+        if next[b] > current[b] {
+            item |= 1 << (12 + b);
+        }
+    }
+    for b in 0..8 {
+        if current[b] > current[b + 3] {
+            item |= 1 << (24 + b);
+        }
+    }
+    item
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A chord progression: three pitch classes per half second, chosen from `seed`
+    fn chords(seed: u32, seconds: usize, sample_rate: u32) -> AudioData {
+        let mut state = seed;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        };
+        let segment = sample_rate as usize / 2;
+        let mut samples = Vec::with_capacity(seconds * sample_rate as usize);
+        for _ in 0..seconds * 2 {
+            let notes: Vec<f64> = (0..3)
+                .map(|_| 440.0 * 2f64.powf((next() % 24) as f64 / 12.0 - 1.0))
+                .collect();
+            for i in 0..segment {
+                let t = i as f64 / sample_rate as f64;
+                let value: f64 = notes
+                    .iter()
+                    .map(|f| (2.0 * std::f64::consts::PI * f * t).sin())
+                    .sum();
+                samples.push((value / 4.0) as f32);
+            }
+        }
+        AudioData {
+            samples,
+            sample_rate,
+            channels: 1,
+        }
+    }
+
+    #[test]
+    fn test_same_recording_survives_processing() {
+        let original = chords(0x1234_5678, 20, 22050);
+        let reference = fingerprint_audio(&original, &FingerprintOptions::default()).unwrap();
+        assert!(!reference.items.is_empty());
+
+        // Quieter, a little noisy, at another rate and in stereo
+        let mut noise = 0x9e37_79b9u32;
+        let processed = AudioData {
+            samples: original
+                .samples
+                .iter()
+                .flat_map(|&s| {
+                    noise = noise.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                    let s = s * 0.5 + (noise as f32 / u32::MAX as f32 - 0.5) * 0.01;
+                    [s, s]
+                })
+                .collect(),
+            channels: 2,
+            ..original.clone()
+        };
+        let processed = resample_audio(&processed, 16000).unwrap();
+        let candidate = fingerprint_audio(&processed, &FingerprintOptions::default()).unwrap();
+
+        let result = compare_fingerprints(&reference, &candidate).unwrap();
+        assert!(result.is_same_recording(), "{:?}", result);
+        assert_eq!(result.offset_seconds, 0.0);
+    }
+
+    #[test]
+    fn test_different_recordings_do_not_match() {
+        let options = FingerprintOptions::default();
+        let a = fingerprint_audio(&chords(0x1234_5678, 20, 22050), &options).unwrap();
+        let b = fingerprint_audio(&chords(0x0bad_cafe, 20, 22050), &options).unwrap();
+        let result = compare_fingerprints(&a, &b).unwrap();
+        assert!(!result.is_same_recording(), "{:?}", result);
+    }
+
+    #[test]
+    fn test_finds_offset_of_trimmed_copy() {
+        let original = chords(0x1234_5678, 20, 22050);
+        let trimmed = AudioData {
+            samples: original.samples[22050 * 4..].to_vec(),
+            ..original.clone()
+        };
+        let options = FingerprintOptions::default();
+        let reference = fingerprint_audio(&original, &options).unwrap();
+        let candidate = fingerprint_audio(&trimmed, &options).unwrap();
+
+        let result = compare_fingerprints(&reference, &candidate).unwrap();
+        assert!(result.is_same_recording(), "{:?}", result);
+        // The candidate's content starts 4 s earlier, to within one item
+        assert!((result.offset_seconds + 4.0).abs() <= Fingerprint::item_seconds());
+    }
+
+    #[test]
+    fn test_versions_must_agree() {
+        let a = Fingerprint {
+            version: FINGERPRINT_VERSION,
+            duration_seconds: 0.0,
+            items: Vec::new(),
+        };
+        let b = Fingerprint {
+            version: FINGERPRINT_VERSION + 1,
+            ..a.clone()
+        };
+        assert!(compare_fingerprints(&a, &b).is_err());
+        assert_eq!(compare_fingerprints(&a, &a).unwrap().similarity, 0.0);
+    }
+}
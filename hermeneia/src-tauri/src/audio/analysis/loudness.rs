@@ -0,0 +1,351 @@
+// src-tauri/src/audio/analysis/loudness.rs
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::dsp::linear_to_db;
+use crate::audio::dsp::true_peak::true_peak;
+use crate::audio::types::AudioData;
+use crate::error::{AnalysisError, Result};
+use crate::profile;
+use crate::progress::{check_cancelled, NoProgress, ProgressSink};
+
+/// Gating block length (ITU-R BS.1770-4)
+const BLOCK_SECONDS: f64 = 0.4;
+
+/// Blocks overlap by 75%, so a new block starts every 100 ms
+const STEP_SECONDS: f64 = 0.1;
+
+/// Blocks quieter than this never count towards integrated loudness
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+
+/// Blocks more than this far below the ungated mean are dropped
+const RELATIVE_GATE_LU: f64 = 10.0;
+
+/// Loudness and peak measurements for a piece of audio
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LoudnessMeasurement {
+    /// Gated integrated loudness in LUFS (`None` for silence or audio under 400 ms)
+    pub integrated_lufs: Option<f64>,
+    /// Largest absolute sample value (linear, 1.0 = full scale)
+    pub sample_peak: f32,
+    /// `sample_peak` in dBFS (negative infinity for digital silence)
+    pub sample_peak_dbfs: f64,
+    /// Peak of the reconstructed signal between samples (BS.1770-4 Annex 2),
+    /// in dBTP; at least `sample_peak_dbfs`
+    pub true_peak_dbtp: f64,
+}
+
+/// Measure integrated loudness (ITU-R BS.1770-4 / EBU R128), sample peak
+/// and true peak
+///
+/// Each channel is K-weighted, split into overlapping 400 ms blocks and
+/// gated at -70 LUFS absolute and -10 LU relative. Surround channels in a
+/// 5.1 layout get the +1.5 dB weighting from the standard and the LFE is
+/// ignored.
+///
+/// # Arguments
+/// * `audio` - The audio to measure
+///
+/// # Returns
+/// Integrated loudness plus sample and true peak
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::{AudioData, measure_loudness};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// // One second of a -20 dBFS, 1 kHz tone in stereo
+/// let samples = (0..48000)
+///     .flat_map(|i| {
+///         let s = 0.1 * (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / 48000.0).sin();
+///         [s, s]
+///     })
+///     .collect();
+/// let audio = AudioData { samples, sample_rate: 48000, channels: 2 };
+///
+/// let loudness = measure_loudness(&audio)?;
+/// assert!((loudness.integrated_lufs.unwrap() + 20.0).abs() < 0.1);
+/// # Ok(())
+/// # }
+/// ```
+pub fn measure_loudness(audio: &AudioData) -> Result<LoudnessMeasurement> {
+    measure_loudness_with_progress(audio, &mut NoProgress)
+}
+
+/// Measure like [`measure_loudness`], reporting the "analyze" stage per
+/// channel and stopping with [`crate::AudioError::Cancelled`] when asked
+pub fn measure_loudness_with_progress(
+    audio: &AudioData,
+    progress: &mut dyn ProgressSink,
+) -> Result<LoudnessMeasurement> {
+    let _stage = profile::stage("analyze");
+    progress.stage("analyze");
+    if audio.channels == 0 || audio.sample_rate == 0 {
+        return Err(AnalysisError::EmptyFormat {
+            channels: audio.channels,
+            sample_rate: audio.sample_rate,
+        }
+        .into());
+    }
+
+    let sample_peak = audio
+        .samples
+        .iter()
+        .fold(0.0f32, |peak, s| peak.max(s.abs()));
+
+    Ok(LoudnessMeasurement {
+        integrated_lufs: integrated_loudness(audio, progress)?,
+        sample_peak,
+        sample_peak_dbfs: linear_to_db(sample_peak as f64),
+        true_peak_dbtp: linear_to_db(true_peak(audio) as f64),
+    })
+}
+
+/// Gated integrated loudness, or `None` when no block survives the gates
+fn integrated_loudness(
+    audio: &AudioData,
+    progress: &mut dyn ProgressSink,
+) -> Result<Option<f64>> {
+    let channels = audio.channels as usize;
+    let frames = audio.frame_count();
+    let block = (BLOCK_SECONDS * audio.sample_rate as f64).round() as usize;
+    let step = (STEP_SECONDS * audio.sample_rate as f64).round() as usize;
+
+    if frames < block || step == 0 {
+        progress.progress(1.0);
+        return Ok(None);
+    }
+
+    // Mean square of the K-weighted signal for every 100 ms step, per channel.
+    // A 400 ms block is then just the average of four consecutive steps.
+    let weights = channel_weights(channels);
+    let steps = frames / step;
+    let mut step_power = vec![0.0f64; steps];
+
+    for (ch, &weight) in weights.iter().enumerate() {
+        check_cancelled(progress)?;
+        progress.progress(ch as f64 / channels as f64);
+        if weight == 0.0 {
+            continue;
+        }
+        let mut filter = KWeighting::new(audio.sample_rate);
+        for (i, power) in step_power.iter_mut().enumerate() {
+            let start = i * step;
+            let sum: f64 = audio.samples[start * channels..(start + step) * channels]
+                .iter()
+                .skip(ch)
+                .step_by(channels)
+                .map(|&s| {
+                    let y = filter.process(s as f64);
+                    y * y
+                })
+                .sum();
+            *power += weight * sum / step as f64;
+        }
+    }
+
+    progress.progress(1.0);
+
+    let steps_per_block = block / step;
+    let blocks: Vec<f64> = step_power
+        .windows(steps_per_block)
+        .map(|w| w.iter().sum::<f64>() / steps_per_block as f64)
+        .collect();
+
+    let absolute_gate = lufs_to_power(ABSOLUTE_GATE_LUFS);
+    let above_absolute: Vec<f64> = blocks.into_iter().filter(|&p| p > absolute_gate).collect();
+    if above_absolute.is_empty() {
+        return Ok(None);
+    }
+
+    let ungated = mean(&above_absolute);
+    let relative_gate = lufs_to_power(power_to_lufs(ungated) - RELATIVE_GATE_LU);
+    let gated: Vec<f64> = above_absolute
+        .into_iter()
+        .filter(|&p| p > relative_gate)
+        .collect();
+
+    Ok(Some(power_to_lufs(mean(&gated))))
+}
+
+/// Per-channel weights from BS.1770 (5.1 surrounds at +1.5 dB, LFE excluded)
+fn channel_weights(channels: usize) -> Vec<f64> {
+    if channels == 6 {
+        // FL, FR, FC, LFE, BL, BR
+        vec![1.0, 1.0, 1.0, 0.0, 1.41, 1.41]
+    } else {
+        vec![1.0; channels]
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn power_to_lufs(power: f64) -> f64 {
+    -0.691 + 10.0 * power.log10()
+}
+
+fn lufs_to_power(lufs: f64) -> f64 {
+    10f64.powf((lufs + 0.691) / 10.0)
+}
+
+/// Direct form I biquad
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+impl Biquad {
+    fn new(b: [f64; 3], a: [f64; 2]) -> Self {
+        Self {
+            b,
+            a,
+            x: [0.0; 2],
+            y: [0.0; 2],
+        }
+    }
+
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+            - self.a[0] * self.y[0]
+            - self.a[1] * self.y[1];
+        self.x = [x, self.x[0]];
+        self.y = [y, self.y[0]];
+        y
+    }
+}
+
+/// The two-stage K-weighting filter (high shelf + high pass)
+///
+/// Coefficients are derived for the actual sample rate rather than using
+/// the 48 kHz table from the standard, so 44.1 kHz and 16 kHz material
+/// measure correctly too.
+#[derive(Debug, Clone, Copy)]
+struct KWeighting {
+    shelf: Biquad,
+    high_pass: Biquad,
+}
+
+impl KWeighting {
+    fn new(sample_rate: u32) -> Self {
+        let rate = sample_rate as f64;
+
+        // Stage 1: +4 dB high shelf modelling the head
+        let f0 = 1681.974450955533;
+        let gain_db = 3.999843853973347;
+        let q = 0.7071752369554196;
+        let k = (std::f64::consts::PI * f0 / rate).tan();
+        let vh = 10f64.powf(gain_db / 20.0);
+        let vb = vh.powf(0.4996667741545416);
+        let a0 = 1.0 + k / q + k * k;
+        let shelf = Biquad::new(
+            [
+                (vh + vb * k / q + k * k) / a0,
+                2.0 * (k * k - vh) / a0,
+                (vh - vb * k / q + k * k) / a0,
+            ],
+            [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        );
+
+        // Stage 2: RLB high pass at ~38 Hz
+        let f0 = 38.13547087602444;
+        let q = 0.5003270373238773;
+        let k = (std::f64::consts::PI * f0 / rate).tan();
+        let a0 = 1.0 + k / q + k * k;
+        let high_pass = Biquad::new(
+            [1.0, -2.0, 1.0],
+            [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        );
+
+        Self { shelf, high_pass }
+    }
+
+    fn process(&mut self, x: f64) -> f64 {
+        self.high_pass.process(self.shelf.process(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(freq: f32, amplitude: f32, seconds: f32, sample_rate: u32, channels: u16) -> AudioData {
+        let frames = (seconds * sample_rate as f32) as usize;
+        let samples = (0..frames)
+            .flat_map(|i| {
+                let t = i as f32 / sample_rate as f32;
+                let s = amplitude * (2.0 * std::f32::consts::PI * freq * t).sin();
+                std::iter::repeat_n(s, channels as usize)
+            })
+            .collect();
+        AudioData {
+            samples,
+            sample_rate,
+            channels,
+        }
+    }
+
+    #[test]
+    fn test_full_scale_tone_on_one_channel() {
+        // BS.1770 reference: a 0 dBFS 997 Hz sine in one channel reads -3.01 LUFS
+        let mut audio = tone(997.0, 1.0, 5.0, 48000, 2);
+        for frame in audio.samples.chunks_exact_mut(2) {
+            frame[1] = 0.0;
+        }
+        let lufs = measure_loudness(&audio).unwrap().integrated_lufs.unwrap();
+        assert!((lufs + 3.01).abs() < 0.05, "got {lufs}");
+    }
+
+    #[test]
+    fn test_sample_rate_independent() {
+        let at_48k = measure_loudness(&tone(1000.0, 0.1, 3.0, 48000, 1)).unwrap();
+        let at_44k = measure_loudness(&tone(1000.0, 0.1, 3.0, 44100, 1)).unwrap();
+        let diff = at_48k.integrated_lufs.unwrap() - at_44k.integrated_lufs.unwrap();
+        assert!(diff.abs() < 0.05, "diff {diff}");
+    }
+
+    #[test]
+    fn test_relative_gate_ignores_quiet_passages() {
+        // Loud section followed by a much quieter one: the quiet part is gated out
+        let mut audio = tone(1000.0, 0.5, 3.0, 48000, 1);
+        let quiet = tone(1000.0, 0.005, 3.0, 48000, 1);
+        let loud_only = measure_loudness(&audio).unwrap().integrated_lufs.unwrap();
+        audio.samples.extend(quiet.samples);
+        let mixed = measure_loudness(&audio).unwrap().integrated_lufs.unwrap();
+        // Ungated this would drop by ~3 LU; only the blocks straddling the
+        // transition should pull it down a little
+        assert!((loud_only - mixed).abs() < 0.5, "{loud_only} vs {mixed}");
+    }
+
+    #[test]
+    fn test_silence_and_short_audio_have_no_loudness() {
+        let silence = AudioData {
+            samples: vec![0.0; 48000],
+            sample_rate: 48000,
+            channels: 1,
+        };
+        let result = measure_loudness(&silence).unwrap();
+        assert_eq!(result.integrated_lufs, None);
+        assert_eq!(result.sample_peak, 0.0);
+        assert_eq!(result.sample_peak_dbfs, f64::NEG_INFINITY);
+
+        let short = tone(1000.0, 0.5, 0.2, 48000, 1);
+        assert_eq!(measure_loudness(&short).unwrap().integrated_lufs, None);
+    }
+
+    #[test]
+    fn test_sample_peak() {
+        let audio = AudioData {
+            samples: vec![0.1, -0.5, 0.25, 0.0],
+            sample_rate: 48000,
+            channels: 2,
+        };
+        let result = measure_loudness(&audio).unwrap();
+        assert_eq!(result.sample_peak, 0.5);
+        assert!((result.sample_peak_dbfs + 6.0206).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,26 @@
+// src-tauri/src/audio/analysis/mod.rs
+// Read-only measurements over decoded audio
+
+pub mod clipping;
+pub mod compare;
+pub mod fingerprint;
+pub mod loudness;
+pub mod quality;
+pub mod silence;
+
+// Re-export commonly used items
+pub use clipping::{
+    detect_clipping, detect_clipping_with_progress, ClipRegion, ClippingOptions, ClippingReport,
+};
+pub use compare::{
+    compare_audio, compare_audio_with_progress, CompareOptions, ComparisonReport, MismatchRegion,
+};
+pub use fingerprint::{
+    compare_fingerprints, fingerprint_audio, fingerprint_audio_with_progress, Fingerprint,
+    FingerprintMatch, FingerprintOptions,
+};
+pub use loudness::{measure_loudness, measure_loudness_with_progress, LoudnessMeasurement};
+pub use quality::{
+    measure_quality, measure_quality_with_progress, QualityOptions, QualityReport,
+};
+pub use silence::{detect_silence, detect_silence_with_progress, SilenceOptions, SilenceRegion};
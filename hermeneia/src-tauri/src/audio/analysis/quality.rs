@@ -0,0 +1,297 @@
+// src-tauri/src/audio/analysis/quality.rs
+// How far processing moved a file from its original
+
+use rustfft::num_complex::Complex;
+use rustfft::FftPlanner;
+use serde::{Deserialize, Serialize};
+
+use crate::audio::analysis::compare::{check_formats, Alignment};
+use crate::audio::types::AudioData;
+use crate::error::{AnalysisError, AudioError, Result};
+use crate::profile;
+use crate::progress::{check_cancelled, NoProgress, ProgressSink};
+
+/// Per-frame SNR is clamped to this range so silence in the output or a
+/// perfect frame doesn't swamp the average
+const SEGMENT_SNR_RANGE: (f64, f64) = (-10.0, 35.0);
+
+/// Reference frames quieter than this (RMS, dBFS) aren't scored
+const SILENCE_DBFS: f64 = -60.0;
+
+/// Power floor for the log spectra, so empty bins don't divide by zero
+const SPECTRAL_FLOOR: f64 = 1e-10;
+
+/// Settings for [`measure_quality`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QualityOptions {
+    /// Largest offset (either direction) searched when aligning the two inputs
+    pub max_offset_seconds: f64,
+    /// Length of the frames SNR and spectral distance are measured over
+    pub frame_seconds: f64,
+}
+
+impl Default for QualityOptions {
+    fn default() -> Self {
+        Self {
+            max_offset_seconds: 1.0,
+            frame_seconds: 0.03,
+        }
+    }
+}
+
+/// Objective scores for a processed file against its original
+///
+/// Higher SNR and lower spectral distance mean a gentler change. As a rough
+/// guide, a segmental SNR over 20 dB is hard to hear on speech, while under
+/// 5 dB the processing has reshaped it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QualityReport {
+    /// Frames the candidate is delayed by relative to the reference (negative = ahead)
+    pub offset_frames: i64,
+    /// Frames present in both inputs after alignment
+    pub compared_frames: usize,
+    /// Signal-to-noise ratio over the whole file, the difference counted as
+    /// noise; infinite when the two match
+    pub snr_db: f64,
+    /// Mean of the per-frame SNRs (each clamped to -10..35 dB), which
+    /// weighs quiet passages as much as loud ones
+    pub segmental_snr_db: f64,
+    /// Mean log-spectral distance: RMS across frequency of the level
+    /// difference between the two spectra, in dB
+    pub spectral_distance_db: f64,
+    /// Frames loud enough to be scored
+    pub scored_frames: usize,
+}
+
+/// Score how much `candidate` differs from `reference`
+///
+/// The inputs are aligned like [`compare_audio`](super::compare_audio) and
+/// measured over short frames, skipping those where the reference is
+/// silent (below -60 dBFS):
+///
+/// - **Segmental SNR** compares the difference to the signal frame by frame,
+///   so it catches damage to quiet speech that whole-file SNR would hide.
+/// - **Spectral distance** compares the frames' spectra and ignores phase,
+///   so it reflects EQ, noise reduction and codec artifacts more than small
+///   timing shifts.
+///
+/// Both inputs must share a sample rate and channel count.
+///
+/// # Errors
+/// [`AnalysisError::Unmeasurable`] when no frame of the reference is loud
+/// enough to score
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::{apply_gain, measure_quality, AudioData, QualityOptions};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let samples = (0..16000).map(|i| 0.5 * (i as f32 * 0.05).sin()).collect();
+/// let original = AudioData { samples, sample_rate: 16000, channels: 1 };
+/// let quieter = apply_gain(&original, -6.0)?;
+///
+/// let report = measure_quality(&original, &quieter, &QualityOptions::default())?;
+/// assert!(report.segmental_snr_db < 10.0);
+/// # Ok(())
+/// # }
+/// ```
+pub fn measure_quality(
+    reference: &AudioData,
+    candidate: &AudioData,
+    options: &QualityOptions,
+) -> Result<QualityReport> {
+    measure_quality_with_progress(reference, candidate, options, &mut NoProgress)
+}
+
+/// Score like [`measure_quality`], reporting the "analyze" stage and
+/// stopping with [`AudioError::Cancelled`] when asked
+pub fn measure_quality_with_progress(
+    reference: &AudioData,
+    candidate: &AudioData,
+    options: &QualityOptions,
+    progress: &mut dyn ProgressSink,
+) -> Result<QualityReport> {
+    let _stage = profile::stage("analyze");
+    progress.stage("analyze");
+    check_formats(reference, candidate)?;
+    if !(options.frame_seconds.is_finite() && options.frame_seconds > 0.0) {
+        return Err(AudioError::InvalidParameter(format!(
+            "Frame length must be positive (got {}s)",
+            options.frame_seconds
+        )));
+    }
+
+    let channels = reference.channels as usize;
+    let aligned = Alignment::find(reference, candidate, options.max_offset_seconds);
+    let (reference_samples, candidate_samples) = aligned.slices(reference, candidate);
+
+    // A power of two, so each frame is also one FFT
+    let frame_len = ((options.frame_seconds * reference.sample_rate as f64) as usize)
+        .next_power_of_two()
+        .max(16);
+    let silence = 10f64.powf(SILENCE_DBFS / 10.0);
+    let fft = FftPlanner::<f64>::new().plan_fft_forward(frame_len);
+    let window: Vec<f64> = (0..frame_len)
+        .map(|i| 0.5 - 0.5 * (2.0 * std::f64::consts::PI * i as f64 / frame_len as f64).cos())
+        .collect();
+    let mut ref_spectrum = vec![Complex::default(); frame_len];
+    let mut cand_spectrum = vec![Complex::default(); frame_len];
+
+    let mut signal_total = 0.0f64;
+    let mut noise_total = 0.0f64;
+    let mut segment_snr_sum = 0.0f64;
+    let mut distance_sum = 0.0f64;
+    let mut scored = 0usize;
+    let frame_count = aligned.compared.div_ceil(frame_len);
+
+    for (i, (ref_frame, cand_frame)) in reference_samples
+        .chunks(frame_len * channels)
+        .zip(candidate_samples.chunks(frame_len * channels))
+        .enumerate()
+    {
+        check_cancelled(progress)?;
+        progress.progress(i as f64 / frame_count as f64);
+
+        let mut signal = 0.0f64;
+        let mut noise = 0.0f64;
+        for (&r, &c) in ref_frame.iter().zip(cand_frame) {
+            let (r, c) = (r as f64, c as f64);
+            signal += r * r;
+            noise += (r - c) * (r - c);
+        }
+        signal_total += signal;
+        noise_total += noise;
+        if signal / (ref_frame.len() as f64) < silence {
+            continue;
+        }
+
+        let snr = if noise > 0.0 {
+            10.0 * (signal / noise).log10()
+        } else {
+            f64::INFINITY
+        };
+        segment_snr_sum += snr.clamp(SEGMENT_SNR_RANGE.0, SEGMENT_SNR_RANGE.1);
+
+        // Spectra of the mono mixes; a short last frame is zero-padded
+        for (spectrum, frame) in [(&mut ref_spectrum, ref_frame), (&mut cand_spectrum, cand_frame)]
+        {
+            spectrum.fill(Complex::default());
+            for ((bin, samples), &w) in spectrum.iter_mut().zip(frame.chunks(channels)).zip(&window)
+            {
+                let mono = samples.iter().map(|&s| s as f64).sum::<f64>() / channels as f64;
+                *bin = Complex::new(mono * w, 0.0);
+            }
+            fft.process(spectrum);
+        }
+        let bins = frame_len / 2 + 1;
+        let squared: f64 = ref_spectrum[..bins]
+            .iter()
+            .zip(&cand_spectrum[..bins])
+            .map(|(r, c)| {
+                let difference = 10.0
+                    * ((r.norm_sqr() + SPECTRAL_FLOOR) / (c.norm_sqr() + SPECTRAL_FLOOR)).log10();
+                difference * difference
+            })
+            .sum();
+        distance_sum += (squared / bins as f64).sqrt();
+        scored += 1;
+    }
+
+    progress.progress(1.0);
+    if scored == 0 {
+        return Err(AnalysisError::Unmeasurable.into());
+    }
+
+    Ok(QualityReport {
+        offset_frames: aligned.offset,
+        compared_frames: aligned.compared,
+        snr_db: if noise_total > 0.0 {
+            10.0 * (signal_total / noise_total).log10()
+        } else {
+            f64::INFINITY
+        },
+        segmental_snr_db: segment_snr_sum / scored as f64,
+        spectral_distance_db: distance_sum / scored as f64,
+        scored_frames: scored,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::apply_gain;
+
+    /// Deterministic noise, like the compare tests, so alignment is exact
+    fn noise(frames: usize, seed: u32, level: f32) -> AudioData {
+        let mut state = seed;
+        let samples = (0..frames)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                level * ((state as f32 / u32::MAX as f32) * 2.0 - 1.0)
+            })
+            .collect();
+        AudioData {
+            samples,
+            sample_rate: 16000,
+            channels: 1,
+        }
+    }
+
+    #[test]
+    fn test_identical_inputs_score_perfectly() {
+        let audio = noise(16000, 0x1234_5678, 0.5);
+        let report = measure_quality(&audio, &audio.clone(), &QualityOptions::default()).unwrap();
+        assert_eq!(report.snr_db, f64::INFINITY);
+        assert_eq!(report.segmental_snr_db, SEGMENT_SNR_RANGE.1);
+        assert_eq!(report.spectral_distance_db, 0.0);
+        assert_eq!(report.compared_frames, 16000);
+    }
+
+    #[test]
+    fn test_scores_fall_as_the_damage_grows() {
+        let reference = noise(32000, 0x1234_5678, 0.5);
+        let score = |level: f32| {
+            let hiss = noise(32000, 0x9e37_79b9, level);
+            let mut candidate = reference.clone();
+            for (s, n) in candidate.samples.iter_mut().zip(&hiss.samples) {
+                *s += n;
+            }
+            measure_quality(&reference, &candidate, &QualityOptions::default()).unwrap()
+        };
+        let light = score(0.005);
+        let heavy = score(0.1);
+        // 0.5 vs 0.005 amplitude is 40 dB apart, clamped to 35 per frame
+        assert!((light.snr_db - 40.0).abs() < 1.0, "{:?}", light);
+        assert!(light.segmental_snr_db > 34.0);
+        assert!(heavy.segmental_snr_db < 15.0 && heavy.segmental_snr_db > 13.0);
+        assert!(heavy.spectral_distance_db > light.spectral_distance_db);
+
+        // A level change leaves the spectrum's shape alone: the distance is
+        // the gain itself, in every bin
+        let quieter = apply_gain(&reference, -6.0).unwrap();
+        let report = measure_quality(&reference, &quieter, &QualityOptions::default()).unwrap();
+        assert!((report.spectral_distance_db - 6.0).abs() < 0.1, "{:?}", report);
+    }
+
+    #[test]
+    fn test_aligns_before_scoring_and_skips_silence() {
+        let mut reference = noise(16000, 0x1234_5678, 0.5);
+        // A silent second the scores should ignore
+        reference.samples.extend(std::iter::repeat_n(0.0, 16000));
+        let mut delayed = reference.clone();
+        delayed.samples.splice(0..0, std::iter::repeat_n(0.0, 80));
+
+        let report = measure_quality(&reference, &delayed, &QualityOptions::default()).unwrap();
+        assert_eq!(report.offset_frames, -80);
+        assert_eq!(report.snr_db, f64::INFINITY);
+        assert!(report.scored_frames < 16000 / 512 + 2);
+
+        let silence = AudioData {
+            samples: vec![0.0; 16000],
+            ..reference
+        };
+        assert!(measure_quality(&silence, &silence.clone(), &QualityOptions::default()).is_err());
+    }
+}
@@ -0,0 +1,213 @@
+// src-tauri/src/audio/analysis/silence.rs
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::types::AudioData;
+use crate::error::{AnalysisError, AudioError, Result};
+use crate::profile;
+use crate::progress::{check_cancelled, NoProgress, ProgressSink};
+
+/// Length of the RMS windows used to classify audio as silent
+const WINDOW_SECONDS: f64 = 0.01;
+
+/// Windows between progress reports (and cancellation checks)
+const PROGRESS_WINDOWS: usize = 4096;
+
+/// Settings for silence detection
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SilenceOptions {
+    /// Level (dBFS RMS) at or below which audio counts as silent
+    pub threshold_db: f64,
+    /// Shortest run of quiet audio reported as a silence
+    pub min_duration_seconds: f64,
+}
+
+impl Default for SilenceOptions {
+    fn default() -> Self {
+        Self {
+            threshold_db: -40.0,
+            min_duration_seconds: 0.5,
+        }
+    }
+}
+
+/// A stretch of silence, in seconds from the start of the audio
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SilenceRegion {
+    pub start_seconds: f64,
+    pub end_seconds: f64,
+}
+
+impl SilenceRegion {
+    /// Length of the region in seconds
+    pub fn duration_seconds(&self) -> f64 {
+        self.end_seconds - self.start_seconds
+    }
+}
+
+/// Find the silent stretches in a piece of audio
+///
+/// The audio is cut into 10 ms windows and the RMS of each window (across
+/// all channels) is compared with the threshold. Runs of quiet windows at
+/// least `min_duration_seconds` long are returned in order.
+///
+/// # Arguments
+/// * `audio` - The audio to scan
+/// * `options` - Threshold and minimum length
+///
+/// # Returns
+/// Silent regions, earliest first
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::{detect_silence, AudioData, SilenceOptions};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// // One second of tone, one second of silence, one second of tone
+/// let mut samples = vec![0.5f32; 16000];
+/// samples.extend(vec![0.0; 16000]);
+/// samples.extend(vec![0.5; 16000]);
+/// let audio = AudioData { samples, sample_rate: 16000, channels: 1 };
+///
+/// let silences = detect_silence(&audio, &SilenceOptions::default())?;
+/// assert_eq!(silences.len(), 1);
+/// assert!((silences[0].start_seconds - 1.0).abs() < 0.011);
+/// # Ok(())
+/// # }
+/// ```
+pub fn detect_silence(audio: &AudioData, options: &SilenceOptions) -> Result<Vec<SilenceRegion>> {
+    detect_silence_with_progress(audio, options, &mut NoProgress)
+}
+
+/// Detect like [`detect_silence`], reporting the "analyze" stage and
+/// stopping with [`AudioError::Cancelled`] when asked
+pub fn detect_silence_with_progress(
+    audio: &AudioData,
+    options: &SilenceOptions,
+    progress: &mut dyn ProgressSink,
+) -> Result<Vec<SilenceRegion>> {
+    let _stage = profile::stage("analyze");
+    progress.stage("analyze");
+    if audio.channels == 0 || audio.sample_rate == 0 {
+        return Err(AnalysisError::EmptyFormat {
+            channels: audio.channels,
+            sample_rate: audio.sample_rate,
+        }
+        .into());
+    }
+    if !options.min_duration_seconds.is_finite() || options.min_duration_seconds < 0.0 {
+        return Err(AudioError::InvalidParameter(format!(
+            "Minimum silence duration must be non-negative (got {})",
+            options.min_duration_seconds
+        )));
+    }
+
+    let channels = audio.channels as usize;
+    let window_frames = ((WINDOW_SECONDS * audio.sample_rate as f64).round() as usize).max(1);
+    let threshold = 10f64.powf(options.threshold_db / 20.0);
+    let sample_rate = audio.sample_rate as f64;
+
+    let mut regions = Vec::new();
+    let mut run_start: Option<usize> = None;
+    let mut push_run = |start: usize, end: usize| {
+        let region = SilenceRegion {
+            start_seconds: start as f64 / sample_rate,
+            end_seconds: end as f64 / sample_rate,
+        };
+        if region.duration_seconds() >= options.min_duration_seconds {
+            regions.push(region);
+        }
+    };
+
+    let window_count = audio.frame_count().div_ceil(window_frames);
+    for (i, window) in audio.samples.chunks(window_frames * channels).enumerate() {
+        if i % PROGRESS_WINDOWS == 0 {
+            check_cancelled(progress)?;
+            progress.progress(i as f64 / window_count as f64);
+        }
+        let mean_square =
+            window.iter().map(|&s| (s as f64) * (s as f64)).sum::<f64>() / window.len() as f64;
+        let silent = mean_square.sqrt() <= threshold;
+        let frame = i * window_frames;
+
+        match (silent, run_start) {
+            (true, None) => run_start = Some(frame),
+            (false, Some(start)) => {
+                push_run(start, frame);
+                run_start = None;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(start) = run_start {
+        push_run(start, audio.frame_count());
+    }
+    progress.progress(1.0);
+
+    Ok(regions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audio(parts: &[(f32, usize)]) -> AudioData {
+        let samples = parts
+            .iter()
+            .flat_map(|&(level, frames)| {
+                // Alternate sign so the signal has no DC and a well-defined RMS
+                (0..frames).map(move |i| if i % 2 == 0 { level } else { -level })
+            })
+            .collect();
+        AudioData {
+            samples,
+            sample_rate: 1000,
+            channels: 1,
+        }
+    }
+
+    #[test]
+    fn test_finds_gap_between_sounds() {
+        let audio = audio(&[(0.5, 1000), (0.001, 800), (0.5, 1000)]);
+        let regions = detect_silence(&audio, &SilenceOptions::default()).unwrap();
+        assert_eq!(
+            regions,
+            vec![SilenceRegion {
+                start_seconds: 1.0,
+                end_seconds: 1.8
+            }]
+        );
+    }
+
+    #[test]
+    fn test_short_gaps_are_ignored() {
+        let audio = audio(&[(0.5, 1000), (0.0, 300), (0.5, 1000)]);
+        assert!(detect_silence(&audio, &SilenceOptions::default())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_leading_and_trailing_silence() {
+        let audio = audio(&[(0.0, 600), (0.5, 1000), (0.0, 700)]);
+        let regions = detect_silence(&audio, &SilenceOptions::default()).unwrap();
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[0].start_seconds, 0.0);
+        assert_eq!(regions[1].end_seconds, 2.3);
+    }
+
+    #[test]
+    fn test_threshold_is_respected() {
+        // -30 dBFS noise floor: silent at -20, not silent at -40
+        let audio = audio(&[(0.0316, 2000)]);
+        let loose = SilenceOptions {
+            threshold_db: -20.0,
+            ..Default::default()
+        };
+        assert_eq!(detect_silence(&audio, &loose).unwrap().len(), 1);
+        assert!(detect_silence(&audio, &SilenceOptions::default())
+            .unwrap()
+            .is_empty());
+    }
+}
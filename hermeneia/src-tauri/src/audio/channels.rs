@@ -0,0 +1,187 @@
+// src-tauri/src/audio/channels.rs
+
+use crate::audio::dsp::{from_real, to_real, Real};
+use crate::audio::types::AudioData;
+use crate::error::{AudioError, Result};
+
+/// -3 dB, the usual weight for centre/surround channels in a fold-down
+const MINUS_3DB: Real = std::f64::consts::FRAC_1_SQRT_2 as Real;
+
+/// Change the channel count of interleaved audio
+///
+/// - Any → mono: channels are averaged
+/// - Mono → N: the single channel is copied to every output channel
+/// - 5.1 → stereo: ITU-R BS.775 fold-down (centre and surrounds at -3 dB, LFE dropped)
+/// - Other down-mixes: extra channels are folded into the outputs at -3 dB
+/// - Other up-mixes: existing channels are kept and new ones are silent
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::{AudioData, remix_channels};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let stereo = AudioData {
+///     samples: vec![1.0, 0.0, 0.5, 0.5],
+///     sample_rate: 44100,
+///     channels: 2,
+/// };
+///
+/// let mono = remix_channels(&stereo, 1)?;
+/// assert_eq!(mono.samples, vec![0.5, 0.5]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn remix_channels(audio: &AudioData, target_channels: u16) -> Result<AudioData> {
+    if target_channels == 0 || audio.channels == 0 {
+        return Err(AudioError::InvalidParameter(format!(
+            "Cannot remix {} channel(s) to {} channel(s)",
+            audio.channels, target_channels
+        )));
+    }
+
+    if audio.channels == target_channels {
+        return Ok(audio.clone());
+    }
+
+    Ok(AudioData {
+        samples: ChannelMixer::new(audio.channels, target_channels)?.mix(&audio.samples),
+        sample_rate: audio.sample_rate,
+        channels: target_channels,
+    })
+}
+
+/// Remixes interleaved audio chunk by chunk, the way [`remix_channels`]
+/// does a whole buffer, e.g. for a stream feeding an output device
+pub struct ChannelMixer {
+    source: usize,
+    /// `matrix[out][in]`, from [`mix_matrix`]
+    matrix: Vec<Vec<Real>>,
+}
+
+impl ChannelMixer {
+    pub fn new(source_channels: u16, target_channels: u16) -> Result<Self> {
+        if target_channels == 0 || source_channels == 0 {
+            return Err(AudioError::InvalidParameter(format!(
+                "Cannot remix {} channel(s) to {} channel(s)",
+                source_channels, target_channels
+            )));
+        }
+        let source = source_channels as usize;
+        Ok(Self {
+            source,
+            matrix: mix_matrix(source, target_channels as usize),
+        })
+    }
+
+    /// Remix the whole frames in `samples`; a trailing partial frame is dropped
+    pub fn mix(&self, samples: &[f32]) -> Vec<f32> {
+        let frames = samples.chunks_exact(self.source);
+        let mut mixed = Vec::with_capacity(frames.len() * self.matrix.len());
+        for frame in frames {
+            for weights in &self.matrix {
+                let sum: Real = frame.iter().zip(weights).map(|(&s, w)| to_real(s) * w).sum();
+                mixed.push(from_real(sum.clamp(-1.0, 1.0)));
+            }
+        }
+        mixed
+    }
+}
+
+/// Build a `target x source` gain matrix: `matrix[out][in]`
+fn mix_matrix(source: usize, target: usize) -> Vec<Vec<Real>> {
+    let mut matrix = vec![vec![0.0; source]; target];
+
+    if target == 1 {
+        matrix[0] = vec![1.0 / source as Real; source];
+    } else if source == 1 {
+        for row in &mut matrix {
+            row[0] = 1.0;
+        }
+    } else if source == 6 && target == 2 {
+        // FL, FR, FC, LFE, BL, BR
+        matrix[0] = vec![1.0, 0.0, MINUS_3DB, 0.0, MINUS_3DB, 0.0];
+        matrix[1] = vec![0.0, 1.0, MINUS_3DB, 0.0, 0.0, MINUS_3DB];
+    } else {
+        for input in 0..source {
+            if input < target {
+                matrix[input][input] = 1.0;
+            } else {
+                matrix[input % target][input] = MINUS_3DB;
+            }
+        }
+    }
+
+    matrix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audio(samples: Vec<f32>, channels: u16) -> AudioData {
+        AudioData {
+            samples,
+            sample_rate: 48000,
+            channels,
+        }
+    }
+
+    #[test]
+    fn test_stereo_to_mono_averages() {
+        let mono = remix_channels(&audio(vec![1.0, 0.0, -0.5, -0.5], 2), 1).unwrap();
+        assert_eq!(mono.channels, 1);
+        assert_eq!(mono.samples, vec![0.5, -0.5]);
+    }
+
+    #[test]
+    fn test_mono_to_stereo_duplicates() {
+        let stereo = remix_channels(&audio(vec![0.25, -0.75], 1), 2).unwrap();
+        assert_eq!(stereo.channels, 2);
+        assert_eq!(stereo.samples, vec![0.25, 0.25, -0.75, -0.75]);
+    }
+
+    #[test]
+    fn test_surround_fold_down_drops_lfe() {
+        // Only the LFE channel has signal
+        let surround = audio(vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0], 6);
+        let stereo = remix_channels(&surround, 2).unwrap();
+        assert_eq!(stereo.samples, vec![0.0, 0.0]);
+
+        // Centre goes to both sides at -3 dB
+        let surround = audio(vec![0.0, 0.0, 1.0, 0.0, 0.0, 0.0], 6);
+        let stereo = remix_channels(&surround, 2).unwrap();
+        assert!((stereo.samples[0] - from_real(MINUS_3DB)).abs() < 1e-6);
+        assert!((stereo.samples[1] - from_real(MINUS_3DB)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_mixer_matches_remix_in_chunks() {
+        let surround = audio((0..36).map(|i| (i % 7) as f32 / 10.0).collect(), 6);
+        let whole = remix_channels(&surround, 2).unwrap();
+
+        let mixer = ChannelMixer::new(6, 2).unwrap();
+        let mut chunked = mixer.mix(&surround.samples[..12]);
+        chunked.extend(mixer.mix(&surround.samples[12..]));
+        assert_eq!(chunked, whole.samples);
+        // Half a frame is left out rather than misaligning the rest
+        assert_eq!(mixer.mix(&surround.samples[..9]).len(), 2);
+    }
+
+    #[test]
+    fn test_upmix_adds_silent_channels() {
+        let quad = remix_channels(&audio(vec![0.1, 0.2], 2), 4).unwrap();
+        assert_eq!(quad.samples, vec![0.1, 0.2, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_output_is_clamped() {
+        let loud = audio(vec![1.0, 1.0, 1.0], 3);
+        let stereo = remix_channels(&loud, 2).unwrap();
+        assert!(stereo.samples.iter().all(|s| s.abs() <= 1.0));
+    }
+
+    #[test]
+    fn test_zero_channels_rejected() {
+        assert!(remix_channels(&audio(vec![0.0], 1), 0).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+// src-tauri/src/audio/chapters/audacity.rs
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::audio::markers::{sort_markers, Marker};
+use crate::audio::time::Timestamp;
+use crate::error::{DecodeError, Result};
+
+/// Parse an Audacity label track export
+///
+/// Each line is `start<TAB>end<TAB>label`, times in seconds. Labels whose
+/// start and end are equal are points, and become markers without an end.
+/// The `\` lines Audacity adds under labels with a frequency range are
+/// skipped, as are blank lines. Decimal commas, written by some locales,
+/// are accepted. Markers come back sorted by start.
+pub fn parse_audacity_labels(text: &str) -> Result<Vec<Marker>> {
+    let invalid = |line: usize, message: &str| {
+        DecodeError::Chapters(format!("label track line {}: {}", line + 1, message))
+    };
+    let mut markers = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('\\') {
+            continue;
+        }
+        let mut fields = line.splitn(3, '\t');
+        let (Some(start), Some(end)) = (fields.next(), fields.next()) else {
+            return Err(invalid(n, "expected start, end and label separated by tabs").into());
+        };
+        let start = parse_seconds(start).ok_or_else(|| invalid(n, "bad start time"))?;
+        let end = parse_seconds(end).ok_or_else(|| invalid(n, "bad end time"))?;
+        if end < start {
+            return Err(invalid(n, "label ends before it starts").into());
+        }
+        let marker = Marker::new(start, fields.next().unwrap_or_default().trim_end_matches('\r'));
+        markers.push(if end > start {
+            marker.with_end(end)
+        } else {
+            marker
+        });
+    }
+    sort_markers(&mut markers);
+    Ok(markers)
+}
+
+/// Markers as an Audacity label track, ready for File > Import > Labels
+///
+/// Markers without an end become point labels. Tabs and line breaks in
+/// titles, which the format can't hold, become spaces.
+pub fn format_audacity_labels(markers: &[Marker]) -> String {
+    let mut text = String::new();
+    for marker in markers {
+        let end = marker.end.unwrap_or(marker.start);
+        let title = marker.title.replace(['\t', '\r', '\n'], " ");
+        let _ = writeln!(
+            text,
+            "{:.6}\t{:.6}\t{}",
+            marker.start.as_seconds(),
+            end.as_seconds(),
+            title
+        );
+    }
+    text
+}
+
+/// Read an Audacity label track file
+pub fn read_audacity_labels<P: AsRef<Path>>(path: P) -> Result<Vec<Marker>> {
+    let text = std::fs::read_to_string(path)?;
+    parse_audacity_labels(text.trim_start_matches('\u{feff}'))
+}
+
+/// Write `markers` as an Audacity label track file
+pub fn write_audacity_labels<P: AsRef<Path>>(path: P, markers: &[Marker]) -> Result<()> {
+    std::fs::write(path, format_audacity_labels(markers))?;
+    Ok(())
+}
+
+fn parse_seconds(text: &str) -> Option<Timestamp> {
+    let seconds: f64 = text.trim().replace(',', ".").parse().ok()?;
+    (seconds.is_finite() && seconds >= 0.0).then(|| Timestamp::from_seconds(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ranges_points_and_spectral_lines() {
+        let text = "12.500000\t15.250000\tReading: John 1\n\
+                    \\\t100.000000\t4000.000000\n\
+                    3,000000\t3,000000\tIntro ends\r\n\
+                    \n\
+                    20.0\t21.0\t\n";
+        let markers = parse_audacity_labels(text).unwrap();
+        assert_eq!(markers.len(), 3);
+        assert_eq!(markers[0], Marker::new(Timestamp::from_seconds(3.0), "Intro ends"));
+        assert_eq!(
+            markers[1],
+            Marker::new(Timestamp::from_seconds(12.5), "Reading: John 1")
+                .with_end(Timestamp::from_seconds(15.25))
+        );
+        assert_eq!(markers[2].title, "");
+
+        assert!(parse_audacity_labels("1.0 2.0 spaces, not tabs").is_err());
+        assert!(parse_audacity_labels("5.0\t4.0\tbackwards").is_err());
+    }
+
+    #[test]
+    fn test_format_round_trips() {
+        let markers = vec![
+            Marker::new(Timestamp::from_seconds(1.25), "Point"),
+            Marker::new(Timestamp::from_seconds(2.0), "Sermon\tpart 1")
+                .with_end(Timestamp::from_seconds(62.5)),
+        ];
+        let text = format_audacity_labels(&markers);
+        assert_eq!(
+            text,
+            "1.250000\t1.250000\tPoint\n2.000000\t62.500000\tSermon part 1\n"
+        );
+        let back = parse_audacity_labels(&text).unwrap();
+        assert_eq!(back[0], markers[0]);
+        assert_eq!(back[1].end, markers[1].end);
+    }
+}
@@ -0,0 +1,252 @@
+// src-tauri/src/audio/chapters/cue.rs
+
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::markers::{sort_markers, Marker};
+use crate::audio::time::Timestamp;
+use crate::error::{DecodeError, Result};
+
+/// Cue sheet timestamps count CD frames, 75 per second
+const FRAMES_PER_SECOND: u32 = 75;
+
+/// A cue sheet describing one audio file
+///
+/// Each `TRACK` becomes a [`Marker`] starting at its `INDEX 01`; pregaps
+/// (`INDEX 00`) and `REM` comments are ignored. Cue sheets that span
+/// several `FILE`s are rejected, since markers belong to a single file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CueSheet {
+    /// The `FILE` the sheet refers to, as written in it
+    pub file: Option<String>,
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    pub tracks: Vec<Marker>,
+}
+
+impl CueSheet {
+    /// A sheet with one track per marker, for `file`
+    pub fn new(file: impl Into<String>, tracks: Vec<Marker>) -> Self {
+        Self {
+            file: Some(file.into()),
+            tracks,
+            ..Self::default()
+        }
+    }
+
+    /// Parse the text of a cue sheet
+    pub fn parse(text: &str) -> Result<Self> {
+        let invalid = |line: usize, message: &str| {
+            DecodeError::Chapters(format!("cue sheet line {}: {}", line + 1, message))
+        };
+        let mut sheet = CueSheet::default();
+        // Title and start of the track being read
+        type Pending = Option<(Option<String>, Option<Timestamp>)>;
+        let mut track: Pending = None;
+        let mut track_number = 0u32;
+
+        let finish = |sheet: &mut CueSheet, track: Pending, number: u32, line| -> Result<()> {
+            if let Some((title, start)) = track {
+                let start = start.ok_or_else(|| invalid(line, "track has no INDEX 01"))?;
+                let title = title.unwrap_or_else(|| format!("Track {:02}", number));
+                sheet.tracks.push(Marker::new(start, title));
+            }
+            Ok(())
+        };
+
+        for (n, line) in text.lines().enumerate() {
+            let (command, rest) = split_word(line.trim());
+            match command.to_ascii_uppercase().as_str() {
+                "FILE" => {
+                    if sheet.file.is_some() {
+                        return Err(invalid(n, "sheets spanning several files aren't supported")
+                            .into());
+                    }
+                    sheet.file = Some(quoted(rest).0);
+                }
+                "TITLE" => match &mut track {
+                    Some((title, _)) => *title = Some(quoted(rest).0),
+                    None => sheet.title = Some(quoted(rest).0),
+                },
+                "PERFORMER" if track.is_none() => sheet.performer = Some(quoted(rest).0),
+                "TRACK" => {
+                    finish(&mut sheet, track.take(), track_number, n)?;
+                    track_number = split_word(rest)
+                        .0
+                        .parse()
+                        .map_err(|_| invalid(n, "bad track number"))?;
+                    track = Some((None, None));
+                }
+                "INDEX" => {
+                    let (number, time) = split_word(rest);
+                    let Some((_, start)) = &mut track else {
+                        return Err(invalid(n, "INDEX outside a TRACK").into());
+                    };
+                    if number.parse::<u32>() == Ok(1) {
+                        let time = parse_time(time.trim())
+                            .ok_or_else(|| invalid(n, "bad INDEX time (expected mm:ss:ff)"))?;
+                        *start = Some(time);
+                    }
+                }
+                _ => {}
+            }
+        }
+        finish(&mut sheet, track, track_number, text.lines().count())?;
+
+        sort_markers(&mut sheet.tracks);
+        Ok(sheet)
+    }
+}
+
+impl fmt::Display for CueSheet {
+    /// The sheet in cue syntax, one `TRACK` per marker
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(title) = &self.title {
+            writeln!(f, "TITLE \"{}\"", unquote(title))?;
+        }
+        if let Some(performer) = &self.performer {
+            writeln!(f, "PERFORMER \"{}\"", unquote(performer))?;
+        }
+        let file = self.file.as_deref().unwrap_or("audio.wav");
+        writeln!(f, "FILE \"{}\" {}", unquote(file), file_type(file))?;
+        for (i, track) in self.tracks.iter().enumerate() {
+            writeln!(f, "  TRACK {:02} AUDIO", i + 1)?;
+            writeln!(f, "    TITLE \"{}\"", unquote(&track.title))?;
+            writeln!(f, "    INDEX 01 {}", format_time(track.start))?;
+        }
+        Ok(())
+    }
+}
+
+/// Read and parse a cue sheet file
+///
+/// Sheets are usually UTF-8, but older rippers wrote Latin-1; bytes that
+/// aren't valid UTF-8 are read as Latin-1.
+pub fn read_cue_sheet<P: AsRef<Path>>(path: P) -> Result<CueSheet> {
+    let bytes = std::fs::read(path)?;
+    let text = match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) => e.into_bytes().iter().map(|&b| b as char).collect(),
+    };
+    CueSheet::parse(text.trim_start_matches('\u{feff}'))
+}
+
+/// Write `sheet` as a cue sheet file
+pub fn write_cue_sheet<P: AsRef<Path>>(path: P, sheet: &CueSheet) -> Result<()> {
+    std::fs::write(path, sheet.to_string())?;
+    Ok(())
+}
+
+/// First word of `line` and the rest, trimmed
+fn split_word(line: &str) -> (&str, &str) {
+    match line.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim_start()),
+        None => (line, ""),
+    }
+}
+
+/// A possibly quoted argument and what follows it
+fn quoted(text: &str) -> (String, &str) {
+    match text.strip_prefix('"').and_then(|t| t.split_once('"')) {
+        Some((value, rest)) => (value.to_string(), rest.trim_start()),
+        None => {
+            let (word, rest) = split_word(text);
+            (word.to_string(), rest)
+        }
+    }
+}
+
+/// Cue syntax has no escapes, so quotes inside values become apostrophes
+fn unquote(value: &str) -> String {
+    value.replace('"', "'")
+}
+
+/// `mm:ss:ff`, minutes unbounded
+fn parse_time(text: &str) -> Option<Timestamp> {
+    let mut parts = text.split(':').map(|p| p.parse::<u64>().ok());
+    let (minutes, seconds, frames) = (parts.next()??, parts.next()??, parts.next()??);
+    if parts.next().is_some() || seconds >= 60 || frames >= FRAMES_PER_SECOND as u64 {
+        return None;
+    }
+    let total = (minutes * 60 + seconds) * FRAMES_PER_SECOND as u64 + frames;
+    Some(Timestamp::from_frames(total, FRAMES_PER_SECOND))
+}
+
+fn format_time(time: Timestamp) -> String {
+    let frames = time.to_frames(FRAMES_PER_SECOND);
+    let per_minute = 60 * FRAMES_PER_SECOND as u64;
+    format!(
+        "{:02}:{:02}:{:02}",
+        frames / per_minute,
+        frames % per_minute / FRAMES_PER_SECOND as u64,
+        frames % FRAMES_PER_SECOND as u64
+    )
+}
+
+/// File type keyword for the `FILE` line, from the file's extension
+fn file_type(file: &str) -> &'static str {
+    let extension = Path::new(file)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "mp3" => "MP3",
+        "aif" | "aiff" => "AIFF",
+        _ => "WAVE",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHEET: &str = "\
+REM GENRE Speech
+PERFORMER \"Pastor\"
+TITLE \"Sunday Service\"
+FILE \"service.flac\" WAVE
+  TRACK 01 AUDIO
+    TITLE \"Welcome\"
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    TITLE \"Reading\"
+    INDEX 00 04:58:00
+    INDEX 01 05:00:37
+  track 03 audio
+    index 01 61:02:74
+";
+
+    #[test]
+    fn test_parse_cue_sheet() {
+        let sheet = CueSheet::parse(SHEET).unwrap();
+        assert_eq!(sheet.file.as_deref(), Some("service.flac"));
+        assert_eq!(sheet.title.as_deref(), Some("Sunday Service"));
+        assert_eq!(sheet.performer.as_deref(), Some("Pastor"));
+
+        let titles: Vec<_> = sheet.tracks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["Welcome", "Reading", "Track 03"]);
+        assert_eq!(sheet.tracks[1].start.to_frames(75), 300 * 75 + 37);
+        assert_eq!(sheet.tracks[2].start.to_frames(75), (61 * 60 + 2) * 75 + 74);
+    }
+
+    #[test]
+    fn test_cue_sheet_round_trip() {
+        let sheet = CueSheet::parse(SHEET).unwrap();
+        let reparsed = CueSheet::parse(&sheet.to_string()).unwrap();
+        assert_eq!(reparsed, sheet);
+        assert!(sheet.to_string().contains("    INDEX 01 61:02:74\n"));
+    }
+
+    #[test]
+    fn test_rejects_bad_sheets() {
+        let missing_index = "FILE \"a.wav\" WAVE\n  TRACK 01 AUDIO\n    TITLE \"x\"\n";
+        assert!(CueSheet::parse(missing_index).is_err());
+        let bad_time = "FILE \"a.wav\" WAVE\n  TRACK 01 AUDIO\n    INDEX 01 00:61:00\n";
+        assert!(CueSheet::parse(bad_time).is_err());
+        let two_files = "FILE \"a.wav\" WAVE\nFILE \"b.wav\" WAVE\n";
+        assert!(CueSheet::parse(two_files).is_err());
+    }
+}
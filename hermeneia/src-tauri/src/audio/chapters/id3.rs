@@ -0,0 +1,361 @@
+// src-tauri/src/audio/chapters/id3.rs
+// MP3 chapters: ID3v2 `CHAP` frames (ID3v2 Chapter Frame Addendum)
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use super::{malformed, open, rewrite, unsupported, ByteReader};
+use crate::audio::markers::Marker;
+use crate::audio::time::Timestamp;
+use crate::error::Result;
+
+const HEADER_LEN: usize = 10;
+
+/// Header flags
+const FLAG_UNSYNC: u8 = 0x80;
+const FLAG_EXTENDED: u8 = 0x40;
+const FLAG_FOOTER: u8 = 0x10;
+
+/// `CHAP` offsets meaning "use the times instead"
+const NO_OFFSET: u32 = u32::MAX;
+
+/// An ID3v2 tag at the start of a file
+struct Tag {
+    /// Major version: 3 or 4
+    version: u8,
+    /// Frames, with unsynchronisation and the extended header removed
+    frames: Vec<u8>,
+    /// Where the audio after the tag starts
+    audio_start: u64,
+}
+
+impl Tag {
+    fn read(file: &mut File) -> Result<Option<Tag>> {
+        let mut header = [0u8; HEADER_LEN];
+        file.seek(SeekFrom::Start(0))?;
+        if file.read_exact(&mut header).is_err() || &header[..3] != b"ID3" {
+            return Ok(None);
+        }
+        let (version, flags) = (header[3], header[5]);
+        let size = syncsafe(&header[6..10]) as usize;
+        let mut body = vec![0; size];
+        file.read_exact(&mut body)
+            .map_err(|_| malformed("ID3 tag is cut short"))?;
+
+        if flags & FLAG_UNSYNC != 0 {
+            body = resync(&body);
+        }
+        if flags & FLAG_EXTENDED != 0 {
+            let mut reader = ByteReader::new(&body);
+            let raw = reader.bytes(4).ok_or_else(|| malformed("bad ID3 extended header"))?;
+            // v2.3 counts the bytes after the size field, v2.4 the whole header
+            let skip = match version {
+                3 => 4 + u32::from_be_bytes(raw.try_into().unwrap()) as usize,
+                _ => syncsafe(raw) as usize,
+            };
+            body = body.get(skip..).ok_or_else(|| malformed("bad ID3 extended header"))?.to_vec();
+        }
+        let footer = if flags & FLAG_FOOTER != 0 { HEADER_LEN } else { 0 };
+        Ok(Some(Tag {
+            version,
+            frames: body,
+            audio_start: (HEADER_LEN + size + footer) as u64,
+        }))
+    }
+}
+
+/// One frame inside a tag (or inside a `CHAP` frame)
+struct Frame<'a> {
+    id: [u8; 4],
+    /// The whole frame as stored, header included
+    raw: &'a [u8],
+    /// Frame content, or `None` when compressed or encrypted
+    data: Option<Vec<u8>>,
+}
+
+fn frames(data: &[u8], version: u8) -> Vec<Frame<'_>> {
+    let mut reader = ByteReader::new(data);
+    let mut frames = Vec::new();
+    while reader.remaining() >= HEADER_LEN {
+        let start = data.len() - reader.remaining();
+        let (Some(id), Some(size), Some(flags)) =
+            (reader.array::<4>(), reader.bytes(4), reader.array::<2>())
+        else {
+            break;
+        };
+        // Padding
+        if id[0] == 0 {
+            break;
+        }
+        let size = match version {
+            4 => syncsafe(size),
+            _ => u32::from_be_bytes(size.try_into().unwrap()),
+        } as usize;
+        let Some(body) = reader.bytes(size) else {
+            break;
+        };
+        let raw = &data[start..start + HEADER_LEN + size];
+        frames.push(Frame {
+            id,
+            raw,
+            data: frame_data(body, flags[1], version),
+        });
+    }
+    frames
+}
+
+/// Undo the per-frame format flags we understand
+fn frame_data(body: &[u8], format: u8, version: u8) -> Option<Vec<u8>> {
+    let mut body = ByteReader::new(body);
+    if version == 4 {
+        // Compressed or encrypted
+        if format & 0x0c != 0 {
+            return None;
+        }
+        if format & 0x40 != 0 {
+            body.u8()?; // group id
+        }
+        if format & 0x01 != 0 {
+            body.bytes(4)?; // data length indicator
+        }
+        let rest = body.rest();
+        Some(if format & 0x02 != 0 { resync(rest) } else { rest.to_vec() })
+    } else {
+        if format & 0xc0 != 0 {
+            return None;
+        }
+        if format & 0x20 != 0 {
+            body.u8()?;
+        }
+        Some(body.rest().to_vec())
+    }
+}
+
+pub(super) fn read(file: &mut File) -> Result<Vec<Marker>> {
+    let Some(tag) = Tag::read(file)? else {
+        return Ok(Vec::new());
+    };
+    if tag.version < 3 {
+        return Ok(Vec::new());
+    }
+
+    let mut markers = Vec::new();
+    for frame in frames(&tag.frames, tag.version) {
+        let (b"CHAP", Some(data)) = (&frame.id, &frame.data) else {
+            continue;
+        };
+        let mut reader = ByteReader::new(data);
+        let parsed = (|| {
+            let element = reader.until_nul()?;
+            let start = reader.u32_be()?;
+            let end = reader.u32_be()?;
+            reader.bytes(8)?; // byte offsets
+            Some((element, start, end))
+        })();
+        let Some((element, start, end)) = parsed else {
+            return Err(malformed("CHAP frame is cut short"));
+        };
+        let title = frames(reader.rest(), tag.version)
+            .into_iter()
+            .find(|f| &f.id == b"TIT2")
+            .and_then(|f| f.data)
+            .and_then(|text| decode_text(&text))
+            .unwrap_or_else(|| String::from_utf8_lossy(element).into_owned());
+        markers.push(
+            Marker::new(Timestamp::from_millis(start as u64), title)
+                .with_end(Timestamp::from_millis(end as u64)),
+        );
+    }
+    Ok(markers)
+}
+
+pub(super) fn write(path: &Path, markers: &[Marker]) -> Result<()> {
+    let mut file = open(path)?;
+    let tag = Tag::read(&mut file)?;
+    let version = tag.as_ref().map_or(4, |t| t.version);
+    if version < 3 {
+        return Err(unsupported("ID3v2.2-tagged MP3"));
+    }
+
+    // Everything but the old chapters, copied as stored
+    let mut body = Vec::new();
+    if let Some(tag) = &tag {
+        for frame in frames(&tag.frames, version) {
+            if &frame.id != b"CHAP" && &frame.id != b"CTOC" {
+                body.extend_from_slice(frame.raw);
+            }
+        }
+    }
+    body.extend(chapter_frames(markers, version)?);
+    if body.len() >= 1 << 28 {
+        return Err(malformed("ID3 tag would be too large"));
+    }
+    let audio_start = tag.map_or(0, |t| t.audio_start);
+
+    rewrite(path, |out| {
+        out.write_all(b"ID3")?;
+        out.write_all(&[version, 0, 0])?;
+        out.write_all(&to_syncsafe(body.len() as u32))?;
+        out.write_all(&body)?;
+        file.seek(SeekFrom::Start(audio_start))?;
+        io::copy(&mut file, out)?;
+        Ok(())
+    })
+}
+
+/// A `CHAP` frame per marker plus the `CTOC` listing them in order
+fn chapter_frames(markers: &[Marker], version: u8) -> Result<Vec<u8>> {
+    if markers.len() > u8::MAX as usize {
+        return Err(malformed("ID3 tables of contents hold at most 255 chapters"));
+    }
+    let millis = |time: Timestamp| u32::try_from(time.to_frames(1000)).unwrap_or(u32::MAX);
+    let mut frames = Vec::new();
+    let mut toc = b"toc\0".to_vec();
+    toc.push(0x03); // top level, ordered
+    toc.push(markers.len() as u8);
+
+    for (i, marker) in markers.iter().enumerate() {
+        let element = format!("chp{}", i);
+        toc.extend_from_slice(element.as_bytes());
+        toc.push(0);
+
+        let mut chap = element.into_bytes();
+        chap.push(0);
+        chap.extend_from_slice(&millis(marker.start).to_be_bytes());
+        chap.extend_from_slice(&millis(marker.end.unwrap_or(marker.start)).to_be_bytes());
+        chap.extend_from_slice(&NO_OFFSET.to_be_bytes());
+        chap.extend_from_slice(&NO_OFFSET.to_be_bytes());
+        let mut title = vec![3]; // UTF-8
+        title.extend_from_slice(marker.title.as_bytes());
+        push_frame(&mut chap, b"TIT2", &title, version);
+        push_frame(&mut frames, b"CHAP", &chap, version);
+    }
+    if !markers.is_empty() {
+        push_frame(&mut frames, b"CTOC", &toc, version);
+    }
+    Ok(frames)
+}
+
+fn push_frame(out: &mut Vec<u8>, id: &[u8; 4], body: &[u8], version: u8) {
+    out.extend_from_slice(id);
+    match version {
+        4 => out.extend_from_slice(&to_syncsafe(body.len() as u32)),
+        _ => out.extend_from_slice(&(body.len() as u32).to_be_bytes()),
+    }
+    out.extend_from_slice(&[0, 0]);
+    out.extend_from_slice(body);
+}
+
+/// Text frame content: an encoding byte, then the text
+fn decode_text(data: &[u8]) -> Option<String> {
+    let (&encoding, text) = data.split_first()?;
+    let utf16 = |text: &[u8], big_endian: bool| {
+        let units = text.chunks_exact(2).map(|c| {
+            let pair = [c[0], c[1]];
+            if big_endian {
+                u16::from_be_bytes(pair)
+            } else {
+                u16::from_le_bytes(pair)
+            }
+        });
+        String::from_utf16_lossy(&units.collect::<Vec<_>>())
+    };
+    let text = match encoding {
+        0 => text.iter().map(|&b| b as char).collect(),
+        1 => match text {
+            [0xff, 0xfe, rest @ ..] => utf16(rest, false),
+            [0xfe, 0xff, rest @ ..] => utf16(rest, true),
+            _ => utf16(text, true),
+        },
+        2 => utf16(text, true),
+        _ => String::from_utf8_lossy(text).into_owned(),
+    };
+    let text = text.trim_end_matches('\0').to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+fn syncsafe(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0, |size, &b| (size << 7) | (b & 0x7f) as u32)
+}
+
+fn to_syncsafe(size: u32) -> [u8; 4] {
+    [21, 14, 7, 0].map(|shift| ((size >> shift) & 0x7f) as u8)
+}
+
+/// Undo unsynchronisation: every `FF 00` was written for a plain `FF`
+fn resync(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut previous = 0u8;
+    for &b in data {
+        if !(previous == 0xff && b == 0) {
+            out.push(b);
+        }
+        previous = b;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("hermeneia_test_id3_{}.mp3", name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_chapters_replace_old_ones_and_keep_other_frames() {
+        // A v2.3 tag with a title frame, in front of some "audio"
+        let mut title = vec![0];
+        title.extend_from_slice(b"Sunday");
+        let mut body = Vec::new();
+        push_frame(&mut body, b"TIT2", &title, 3);
+        body.extend(chapter_frames(&[Marker::new(Timestamp::ZERO, "Old")], 3).unwrap());
+        let mut contents = b"ID3\x03\x00\x00".to_vec();
+        contents.extend_from_slice(&to_syncsafe(body.len() as u32));
+        contents.extend_from_slice(&body);
+        contents.extend_from_slice(b"\xff\xfbaudio");
+        let path = temp_file("replace", &contents);
+
+        let at = Timestamp::from_millis;
+        let markers = [
+            Marker::new(at(0), "Welcome").with_end(at(1500)),
+            Marker::new(at(1500), "Sermon").with_end(at(4000)),
+        ];
+        write(&path, &markers).unwrap();
+
+        let mut file = File::open(&path).unwrap();
+        assert_eq!(read(&mut file).unwrap(), markers);
+        let tag = Tag::read(&mut file).unwrap().unwrap();
+        assert_eq!(tag.version, 3);
+        let ids: Vec<_> = frames(&tag.frames, 3).iter().map(|f| f.id).collect();
+        assert_eq!(ids, vec![*b"TIT2", *b"CHAP", *b"CHAP", *b"CTOC"]);
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(bytes.ends_with(b"\xff\xfbaudio"));
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_untagged_file_gets_a_v24_tag() {
+        let path = temp_file("untagged", b"\xff\xfbaudio");
+        let markers = [Marker::new(Timestamp::ZERO, "Only").with_end(Timestamp::from_millis(10))];
+        write(&path, &markers).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(&bytes[..4], b"ID3\x04");
+        assert_eq!(read(&mut File::open(&path).unwrap()).unwrap(), markers);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_text_encodings() {
+        assert_eq!(decode_text(b"\x00caf\xe9").as_deref(), Some("café"));
+        assert_eq!(decode_text(b"\x01\xff\xfeh\x00i\x00\x00\x00").as_deref(), Some("hi"));
+        assert_eq!(decode_text(b"\x03"), None);
+        assert_eq!(resync(b"\xff\x00\xe0"), b"\xff\xe0");
+    }
+}
@@ -0,0 +1,268 @@
+// src-tauri/src/audio/chapters/mod.rs
+// Chapter markers in audio files, cue sheets and label tracks
+
+pub mod audacity;
+pub mod cue;
+mod id3;
+mod mp4;
+mod riff;
+mod vorbis;
+
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::audio::decoder::get_audio_info;
+use crate::audio::markers::{close_markers, sort_markers, Marker};
+use crate::audio::media_info::{self, HEADER_LEN};
+use crate::audio::reader_pool::release_reader;
+use crate::audio::time::Timestamp;
+use crate::error::{AudioError, DecodeError, Result};
+
+pub use audacity::{
+    format_audacity_labels, parse_audacity_labels, read_audacity_labels, write_audacity_labels,
+};
+pub use cue::{read_cue_sheet, write_cue_sheet, CueSheet};
+
+/// Read the chapters of an audio file, the tracks of a cue sheet or the
+/// labels of an Audacity label track
+///
+/// | File | Chapters from |
+/// |------|---------------|
+/// | `.cue` | `TRACK` entries |
+/// | `.txt` | Audacity labels |
+/// | MP4, M4A, M4B | QuickTime chapter track, else Nero `chpl` |
+/// | MP3 | ID3v2 `CHAP` frames |
+/// | WAV | `cue ` points with `labl`/`ltxt` names |
+/// | FLAC, Ogg Opus | `CHAPTERnnn` / `CHAPTERnnnNAME` comments |
+///
+/// Files of other kinds, and files without chapters, give an empty list.
+/// Markers come back sorted by start.
+///
+/// # Example
+/// ```no_run
+/// use hermeneia_lib::audio::chapters::read_chapters;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// for chapter in read_chapters("book.m4b")? {
+///     println!("{} {}", chapter.start, chapter.title);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn read_chapters<P: AsRef<Path>>(path: P) -> Result<Vec<Marker>> {
+    let path = path.as_ref();
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+    if extension.eq_ignore_ascii_case("cue") {
+        return Ok(read_cue_sheet(path)?.tracks);
+    }
+    if extension.eq_ignore_ascii_case("txt") {
+        return read_audacity_labels(path);
+    }
+
+    let mut file = open(path)?;
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    (&mut file).take(HEADER_LEN as u64).read_to_end(&mut header)?;
+    let mut markers = if header.starts_with(b"ID3") {
+        id3::read(&mut file)?
+    } else {
+        match media_info::sniff_container(&header) {
+            Some("MP4") => mp4::read(&mut file)?,
+            Some("WAV") => riff::read(&mut file)?,
+            Some("FLAC") => vorbis::read_flac(&mut file)?,
+            Some("Ogg") => vorbis::read_ogg(&mut file)?,
+            _ => Vec::new(),
+        }
+    };
+    sort_markers(&mut markers);
+    Ok(markers)
+}
+
+/// Store chapters in an audio file, replacing any it already has
+///
+/// Supports the formats Hermeneia exports: WAV (`cue ` and `LIST adtl`
+/// chunks), MP3 (ID3v2 `CHAP`/`CTOC` frames), FLAC and Ogg Opus
+/// (`CHAPTERnnn` comments). Other tags in the file are kept. Markers
+/// without an end run to the next marker, the last one to the end of the
+/// file. Use [`write_cue_sheet`] for formats that can't hold chapters.
+///
+/// The file is rewritten through a temporary copy next to it, so a failed
+/// write leaves the original untouched.
+pub fn write_chapters<P: AsRef<Path>>(path: P, markers: &[Marker]) -> Result<()> {
+    let path = path.as_ref();
+    let info = get_audio_info(path)?;
+    let mut markers = markers.to_vec();
+    sort_markers(&mut markers);
+    close_markers(&mut markers, Timestamp::from_seconds(info.duration_seconds));
+
+    // Pooled readers hold the file open, which blocks replacing it on Windows
+    release_reader(path);
+    match info.container.as_deref() {
+        Some("WAV") => riff::write(path, &markers),
+        Some("MPEG audio") => id3::write(path, &markers),
+        Some("FLAC") => vorbis::write_flac(path, &markers),
+        Some("Ogg") => vorbis::write_ogg_opus(path, &markers),
+        container => Err(unsupported(container.unwrap_or(&info.format))),
+    }
+}
+
+fn unsupported(container: &str) -> AudioError {
+    AudioError::UnsupportedFormat(format!(
+        "can't store chapters in {} files; write a cue sheet instead",
+        container
+    ))
+}
+
+fn open(path: &Path) -> Result<File> {
+    File::open(path).map_err(|e| AudioError::FileOpen {
+        path: path.to_string_lossy().to_string(),
+        source: e,
+    })
+}
+
+/// Replace `path` with what `write` produces, via a temporary file
+fn rewrite(path: &Path, write: impl FnOnce(&mut BufWriter<File>) -> Result<()>) -> Result<()> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".chapters-tmp");
+    let temp: PathBuf = path.with_file_name(name);
+
+    let result = File::create(&temp)
+        .map_err(AudioError::from)
+        .and_then(|file| {
+            let mut out = BufWriter::new(file);
+            write(&mut out)?;
+            out.flush()?;
+            Ok(())
+        })
+        .and_then(|()| std::fs::rename(&temp, path).map_err(AudioError::from));
+    if result.is_err() {
+        std::fs::remove_file(&temp).ok();
+    }
+    result
+}
+
+fn malformed(what: &str) -> AudioError {
+    DecodeError::Chapters(what.to_string()).into()
+}
+
+/// Bounds-checked reads from a byte slice
+///
+/// Every read returns `None` past the end, so parsers can `?` their way
+/// through untrusted data.
+pub(super) struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    pub(super) fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub(super) fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    pub(super) fn bytes(&mut self, n: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(n)?)?;
+        self.pos += n;
+        Some(bytes)
+    }
+
+    pub(super) fn rest(&mut self) -> &'a [u8] {
+        let rest = &self.data[self.pos..];
+        self.pos = self.data.len();
+        rest
+    }
+
+    pub(super) fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.bytes(N)?.try_into().ok()
+    }
+
+    pub(super) fn u8(&mut self) -> Option<u8> {
+        Some(self.array::<1>()?[0])
+    }
+
+    pub(super) fn u16_be(&mut self) -> Option<u16> {
+        self.array().map(u16::from_be_bytes)
+    }
+
+    pub(super) fn u32_be(&mut self) -> Option<u32> {
+        self.array().map(u32::from_be_bytes)
+    }
+
+    pub(super) fn u64_be(&mut self) -> Option<u64> {
+        self.array().map(u64::from_be_bytes)
+    }
+
+    pub(super) fn u32_le(&mut self) -> Option<u32> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    /// Bytes up to (not including) the next NUL, which is skipped
+    pub(super) fn until_nul(&mut self) -> Option<&'a [u8]> {
+        let len = self.data[self.pos..].iter().position(|&b| b == 0)?;
+        let bytes = self.bytes(len)?;
+        self.pos += 1;
+        Some(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::decoder::decode_audio_file;
+    use crate::audio::encoder::{encode_flac, encode_wav, DEFAULT_FLAC_COMPRESSION};
+    use crate::audio::types::AudioData;
+
+    #[test]
+    fn test_chapters_round_trip_through_wav() {
+        let path = std::env::temp_dir().join("hermeneia_test_chapters.wav");
+        let audio = AudioData {
+            samples: vec![0.0; 8000 * 3],
+            sample_rate: 8000,
+            channels: 1,
+        };
+        encode_wav(&audio, &path).unwrap();
+
+        let at = Timestamp::from_seconds;
+        let markers = vec![
+            Marker::new(at(1.5), "Sermon"),
+            Marker::new(at(0.0), "Welcome"),
+        ];
+        write_chapters(&path, &markers).unwrap();
+
+        let read = read_chapters(&path).unwrap();
+        assert_eq!(read.len(), 2);
+        assert_eq!((read[0].title.as_str(), read[0].start), ("Welcome", at(0.0)));
+        assert_eq!((read[1].title.as_str(), read[1].start), ("Sermon", at(1.5)));
+        // The last chapter was closed at the end of the file
+        assert_eq!(read[1].end, Some(at(3.0)));
+        // Still plays as the same audio
+        assert_eq!(get_audio_info(&path).unwrap().duration_seconds, 3.0);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_chapters_in_flac_keep_it_decodable() {
+        let path = std::env::temp_dir().join("hermeneia_test_chapters.flac");
+        let audio = AudioData {
+            samples: (0..8000).map(|i| (i as f32 * 0.05).sin() * 0.5).collect(),
+            sample_rate: 8000,
+            channels: 1,
+        };
+        encode_flac(&audio, &path, 16, DEFAULT_FLAC_COMPRESSION).unwrap();
+        let before = decode_audio_file(&path).unwrap().samples;
+
+        let markers = vec![Marker::new(Timestamp::from_seconds(0.25), "Reading")];
+        write_chapters(&path, &markers).unwrap();
+        let read = read_chapters(&path).unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].title, "Reading");
+        assert_eq!(read[0].start, Timestamp::from_seconds(0.25));
+        assert_eq!(decode_audio_file(&path).unwrap().samples, before);
+
+        std::fs::remove_file(path).ok();
+    }
+}
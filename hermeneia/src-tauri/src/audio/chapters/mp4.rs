@@ -0,0 +1,368 @@
+// src-tauri/src/audio/chapters/mp4.rs
+// MP4/M4B chapters: QuickTime chapter tracks and Nero `chpl` boxes
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+
+use super::{malformed, ByteReader};
+use crate::audio::markers::Marker;
+use crate::audio::time::Timestamp;
+use crate::error::Result;
+
+/// Largest `moov` box read into memory; real ones are well under 1 MB
+const MAX_MOOV_LEN: u64 = 64 * 1024 * 1024;
+
+/// Longest chapter title sample read
+const MAX_TITLE_LEN: u32 = 4096;
+
+/// Nero chapter times count 100 ns units
+const NERO_UNITS_PER_SECOND: u32 = 10_000_000;
+
+/// Child boxes of a container box's payload, as (type, payload)
+fn boxes(data: &[u8]) -> Vec<([u8; 4], &[u8])> {
+    let mut reader = ByteReader::new(data);
+    let mut boxes = Vec::new();
+    while let (Some(size), Some(kind)) = (reader.u32_be(), reader.array::<4>()) {
+        let payload = match size {
+            0 => Some(reader.rest()),
+            1 => reader
+                .u64_be()
+                .and_then(|size| size.checked_sub(16))
+                .and_then(|len| reader.bytes(len as usize)),
+            _ => (size as usize).checked_sub(8).and_then(|len| reader.bytes(len)),
+        };
+        let Some(payload) = payload else {
+            break;
+        };
+        boxes.push((kind, payload));
+    }
+    boxes
+}
+
+/// The payload of the first child of type `kind`
+fn child<'a>(data: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    boxes(data).into_iter().find(|(k, _)| k == kind).map(|(_, p)| p)
+}
+
+/// Follow a path of box types down from `data`
+fn descend<'a>(data: &'a [u8], path: &[&[u8; 4]]) -> Option<&'a [u8]> {
+    path.iter().try_fold(data, |data, kind| child(data, kind))
+}
+
+/// Find the top-level `moov` box and read it into memory
+fn read_moov(file: &mut File) -> Result<Option<Vec<u8>>> {
+    let len = file.metadata()?.len();
+    let mut offset = 0u64;
+    while offset + 8 <= len {
+        let mut header = [0u8; 16];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut header[..8])?;
+        let (header_len, size) = match u32::from_be_bytes(header[..4].try_into().unwrap()) {
+            0 => (8, len - offset),
+            1 => {
+                file.read_exact(&mut header[8..])?;
+                (16, u64::from_be_bytes(header[8..].try_into().unwrap()))
+            }
+            size => (8, size as u64),
+        };
+        if size < header_len {
+            return Err(malformed("MP4 box has an impossible size"));
+        }
+        if &header[4..8] == b"moov" {
+            if size > MAX_MOOV_LEN {
+                return Err(malformed("MP4 moov box is too large"));
+            }
+            let mut moov = vec![0; (size - header_len) as usize];
+            file.read_exact(&mut moov)
+                .map_err(|_| malformed("MP4 moov box is cut short"))?;
+            return Ok(Some(moov));
+        }
+        offset += size;
+    }
+    Ok(None)
+}
+
+pub(super) fn read(file: &mut File) -> Result<Vec<Marker>> {
+    let Some(moov) = read_moov(file)? else {
+        return Ok(Vec::new());
+    };
+    let tracks: Vec<&[u8]> = boxes(&moov)
+        .into_iter()
+        .filter(|(kind, _)| kind == b"trak")
+        .map(|(_, payload)| payload)
+        .collect();
+
+    // QuickTime chapters: a text track named by another track's `tref/chap`
+    let chapter_ids: Vec<u32> = tracks
+        .iter()
+        .filter_map(|trak| descend(trak, &[b"tref", b"chap"]))
+        .flat_map(|chap| chap.chunks_exact(4).map(|id| u32::from_be_bytes(id.try_into().unwrap())))
+        .collect();
+    for trak in &tracks {
+        if track_id(trak).is_some_and(|id| chapter_ids.contains(&id)) {
+            let markers = text_track(file, trak)?;
+            if !markers.is_empty() {
+                return Ok(markers);
+            }
+        }
+    }
+
+    match descend(&moov, &[b"udta", b"chpl"]) {
+        Some(chpl) => nero_chapters(chpl),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Track ID from `tkhd`
+fn track_id(trak: &[u8]) -> Option<u32> {
+    let mut tkhd = ByteReader::new(child(trak, b"tkhd")?);
+    let version = tkhd.u8()?;
+    tkhd.bytes(3)?; // flags
+    // Creation and modification times
+    tkhd.bytes(if version == 1 { 16 } else { 8 })?;
+    tkhd.u32_be()
+}
+
+/// Nero chapters: start times in 100 ns units and length-prefixed titles
+fn nero_chapters(chpl: &[u8]) -> Result<Vec<Marker>> {
+    let parse = || {
+        let mut reader = ByteReader::new(chpl);
+        let version = reader.u8()?;
+        reader.bytes(3)?; // flags
+        if version == 1 {
+            reader.bytes(4)?;
+        }
+        let count = reader.u8()?;
+        (0..count)
+            .map(|_| {
+                let start = reader.u64_be()?;
+                let len = reader.u8()? as usize;
+                let title = String::from_utf8_lossy(reader.bytes(len)?).into_owned();
+                Some(Marker::new(Timestamp::from_frames(start, NERO_UNITS_PER_SECOND), title))
+            })
+            .collect::<Option<Vec<_>>>()
+    };
+    parse().ok_or_else(|| malformed("MP4 chpl box is cut short"))
+}
+
+/// Sample table of a track, enough to locate and time its samples
+struct SampleTable {
+    timescale: u32,
+    /// (sample count, duration) runs from `stts`
+    durations: Vec<(u32, u32)>,
+    sizes: Vec<u32>,
+    /// (first chunk, samples per chunk) runs from `stsc`
+    chunk_runs: Vec<(u32, u32)>,
+    chunk_offsets: Vec<u64>,
+}
+
+impl SampleTable {
+    fn parse(trak: &[u8]) -> Option<SampleTable> {
+        let mdia = child(trak, b"mdia")?;
+        let mut mdhd = ByteReader::new(child(mdia, b"mdhd")?);
+        let version = mdhd.u8()?;
+        mdhd.bytes(3 + if version == 1 { 16 } else { 8 })?;
+        let timescale = mdhd.u32_be()?;
+        let stbl = descend(mdia, &[b"minf", b"stbl"])?;
+
+        // Every table starts with version/flags and an entry count
+        let table = |kind: &[u8; 4]| -> Option<(ByteReader<'_>, u32)> {
+            let mut reader = ByteReader::new(child(stbl, kind)?);
+            reader.bytes(4)?;
+            let count = reader.u32_be()?;
+            Some((reader, count))
+        };
+
+        let (mut stts, count) = table(b"stts")?;
+        let durations = (0..count)
+            .map(|_| Some((stts.u32_be()?, stts.u32_be()?)))
+            .collect::<Option<_>>()?;
+
+        let mut stsz = ByteReader::new(child(stbl, b"stsz")?);
+        stsz.bytes(4)?;
+        let uniform = stsz.u32_be()?;
+        let count = stsz.u32_be()?;
+        let sizes = if uniform != 0 {
+            vec![uniform; count as usize]
+        } else {
+            (0..count).map(|_| stsz.u32_be()).collect::<Option<_>>()?
+        };
+
+        let (mut stsc, count) = table(b"stsc")?;
+        let chunk_runs = (0..count)
+            .map(|_| {
+                let run = (stsc.u32_be()?, stsc.u32_be()?);
+                stsc.u32_be()?; // sample description
+                Some(run)
+            })
+            .collect::<Option<_>>()?;
+
+        let chunk_offsets = match table(b"stco") {
+            Some((mut stco, count)) => (0..count)
+                .map(|_| stco.u32_be().map(u64::from))
+                .collect::<Option<_>>()?,
+            None => {
+                let (mut co64, count) = table(b"co64")?;
+                (0..count).map(|_| co64.u64_be()).collect::<Option<_>>()?
+            }
+        };
+
+        Some(SampleTable {
+            timescale,
+            durations,
+            sizes,
+            chunk_runs,
+            chunk_offsets,
+        })
+    }
+
+    /// File offset of each sample
+    fn sample_offsets(&self) -> Vec<u64> {
+        let mut offsets = Vec::with_capacity(self.sizes.len());
+        let mut sizes = self.sizes.iter();
+        for (i, &chunk_offset) in self.chunk_offsets.iter().enumerate() {
+            let chunk = i as u32 + 1;
+            let per_chunk = self
+                .chunk_runs
+                .iter()
+                .rev()
+                .find(|(first, _)| *first <= chunk)
+                .map_or(0, |&(_, n)| n);
+            let mut offset = chunk_offset;
+            for size in sizes.by_ref().take(per_chunk as usize) {
+                offsets.push(offset);
+                offset += *size as u64;
+            }
+        }
+        offsets
+    }
+
+    /// Start time of each sample
+    fn sample_starts(&self) -> Vec<Timestamp> {
+        let mut starts = Vec::with_capacity(self.sizes.len());
+        let mut time = 0u64;
+        for &(count, duration) in &self.durations {
+            for _ in 0..count {
+                starts.push(Timestamp::from_frames(time, self.timescale));
+                time += duration as u64;
+            }
+        }
+        starts
+    }
+}
+
+/// Chapters from a QuickTime text track: one sample per chapter, each a
+/// 16-bit length and the title
+fn text_track(file: &mut File, trak: &[u8]) -> Result<Vec<Marker>> {
+    let table = SampleTable::parse(trak)
+        .filter(|t| t.timescale > 0)
+        .ok_or_else(|| malformed("MP4 chapter track has no usable sample table"))?;
+    let mut markers = Vec::new();
+    let offsets = table.sample_offsets();
+    for ((offset, size), start) in offsets.iter().zip(&table.sizes).zip(table.sample_starts()) {
+        let mut sample = vec![0; (*size).min(MAX_TITLE_LEN) as usize];
+        file.seek(SeekFrom::Start(*offset))?;
+        file.read_exact(&mut sample)
+            .map_err(|_| malformed("MP4 chapter title lies outside the file"))?;
+        let mut reader = ByteReader::new(&sample);
+        let len = reader.u16_be().unwrap_or_default() as usize;
+        let text = reader.bytes(len).unwrap_or_else(|| reader.rest());
+        let title = match text {
+            [0xfe, 0xff, rest @ ..] => {
+                let units: Vec<u16> = rest
+                    .chunks_exact(2)
+                    .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                    .collect();
+                String::from_utf16_lossy(&units)
+            }
+            _ => String::from_utf8_lossy(text).into_owned(),
+        };
+        markers.push(Marker::new(start, title));
+    }
+    Ok(markers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mp4_box(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut out = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        out.extend_from_slice(kind);
+        out.extend_from_slice(payload);
+        out
+    }
+
+    fn full_box(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        mp4_box(kind, &[&[0u8; 4][..], body].concat())
+    }
+
+    fn temp_file(name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("hermeneia_test_mp4_{}.m4b", name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_nero_chapters() {
+        let mut chpl = vec![1, 0, 0, 0, 0, 0, 0, 0, 2];
+        for (start, title) in [(0u64, "Intro"), (615_000_000, "Part One")] {
+            chpl.extend_from_slice(&start.to_be_bytes());
+            chpl.push(title.len() as u8);
+            chpl.extend_from_slice(title.as_bytes());
+        }
+        let moov = mp4_box(b"moov", &mp4_box(b"udta", &mp4_box(b"chpl", &chpl)));
+        let file = [mp4_box(b"ftyp", b"M4B \0\0\0\0"), moov].concat();
+        let path = temp_file("nero", &file);
+
+        let markers = read(&mut File::open(&path).unwrap()).unwrap();
+        assert_eq!(markers.len(), 2);
+        assert_eq!(markers[1].title, "Part One");
+        assert_eq!(markers[1].start, Timestamp::from_seconds(61.5));
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_quicktime_chapter_track() {
+        // Titles stored in the file before moov, one chunk per sample
+        let ftyp = mp4_box(b"ftyp", b"M4A \0\0\0\0");
+        let titles: Vec<Vec<u8>> = ["Opening", "Message"]
+            .iter()
+            .map(|t| [&(t.len() as u16).to_be_bytes()[..], t.as_bytes()].concat())
+            .collect();
+        let mdat = mp4_box(b"mdat", &titles.concat());
+        let first = (ftyp.len() + 8) as u32;
+        let second = first + titles[0].len() as u32;
+
+        let u32s = |values: &[u32]| values.iter().flat_map(|v| v.to_be_bytes()).collect::<Vec<_>>();
+        let stbl = [
+            full_box(b"stts", &u32s(&[2, 1, 1000, 1, 2500])),
+            full_box(b"stsz", &u32s(&[0, 2, titles[0].len() as u32, titles[1].len() as u32])),
+            full_box(b"stsc", &u32s(&[1, 1, 1, 1])),
+            full_box(b"stco", &u32s(&[2, first, second])),
+        ]
+        .concat();
+        let mdia = [
+            full_box(b"mdhd", &u32s(&[0, 0, 1000, 3500, 0])),
+            mp4_box(b"minf", &mp4_box(b"stbl", &stbl)),
+        ]
+        .concat();
+        let text_trak = [full_box(b"tkhd", &u32s(&[0, 0, 2])), mp4_box(b"mdia", &mdia)].concat();
+        let audio_trak = [
+            full_box(b"tkhd", &u32s(&[0, 0, 1])),
+            mp4_box(b"tref", &mp4_box(b"chap", &u32s(&[2]))),
+        ]
+        .concat();
+        let moov = mp4_box(
+            b"moov",
+            &[mp4_box(b"trak", &audio_trak), mp4_box(b"trak", &text_trak)].concat(),
+        );
+        let path = temp_file("quicktime", &[ftyp, mdat, moov].concat());
+
+        let markers = read(&mut File::open(&path).unwrap()).unwrap();
+        let titles: Vec<_> = markers.iter().map(|m| m.title.as_str()).collect();
+        assert_eq!(titles, vec!["Opening", "Message"]);
+        assert_eq!(markers[1].start, Timestamp::from_seconds(1.0));
+        std::fs::remove_file(path).ok();
+    }
+}
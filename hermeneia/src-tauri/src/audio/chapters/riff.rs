@@ -0,0 +1,247 @@
+// src-tauri/src/audio/chapters/riff.rs
+// WAV markers: `cue ` points named by a `LIST adtl` chunk
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use super::{malformed, open, rewrite, ByteReader};
+use crate::audio::markers::Marker;
+use crate::audio::time::Timestamp;
+use crate::error::Result;
+
+/// Bytes per entry in a `cue ` chunk
+const CUE_POINT_LEN: usize = 24;
+
+/// A chunk inside the RIFF file
+struct Chunk {
+    id: [u8; 4],
+    /// Where the chunk's data starts
+    offset: u64,
+    size: u32,
+}
+
+impl Chunk {
+    /// Size on disk, including the header and the pad byte after odd sizes
+    fn stored_len(&self) -> u64 {
+        8 + self.size as u64 + (self.size & 1) as u64
+    }
+
+    fn read(&self, file: &mut File) -> Result<Vec<u8>> {
+        let mut data = vec![0; self.size as usize];
+        file.seek(SeekFrom::Start(self.offset))?;
+        file.read_exact(&mut data)?;
+        Ok(data)
+    }
+
+    /// `LIST` chunks of type `adtl` hold marker names
+    fn is_label_list(&self, file: &mut File) -> Result<bool> {
+        if &self.id != b"LIST" || self.size < 4 {
+            return Ok(false);
+        }
+        let mut kind = [0; 4];
+        file.seek(SeekFrom::Start(self.offset))?;
+        file.read_exact(&mut kind)?;
+        Ok(&kind == b"adtl")
+    }
+}
+
+/// List the chunks after the `RIFF....WAVE` header
+fn chunks(file: &mut File) -> Result<Vec<Chunk>> {
+    let len = file.metadata()?.len();
+    let mut chunks = Vec::new();
+    let mut offset = 12;
+    while offset + 8 <= len {
+        let mut header = [0u8; 8];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut header)?;
+        let id = [header[0], header[1], header[2], header[3]];
+        // A size running past the end (e.g. an unfinished recording) is cut short
+        let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]])
+            .min((len - offset - 8).min(u32::MAX as u64) as u32);
+        let chunk = Chunk {
+            id,
+            offset: offset + 8,
+            size,
+        };
+        offset += chunk.stored_len();
+        chunks.push(chunk);
+    }
+    Ok(chunks)
+}
+
+pub(super) fn read(file: &mut File) -> Result<Vec<Marker>> {
+    let chunks = chunks(file)?;
+    let mut sample_rate = None;
+    let mut points = Vec::new();
+    let mut labels = HashMap::new();
+    let mut lengths = HashMap::new();
+
+    for chunk in &chunks {
+        match &chunk.id {
+            b"fmt " => {
+                let data = chunk.read(file)?;
+                sample_rate = data.get(4..8).map(|b| u32::from_le_bytes(b.try_into().unwrap()));
+            }
+            b"cue " => {
+                let data = chunk.read(file)?;
+                let mut reader = ByteReader::new(&data);
+                let count = reader.u32_le().ok_or_else(|| malformed("empty cue chunk"))?;
+                for _ in 0..count {
+                    let point = reader
+                        .bytes(CUE_POINT_LEN)
+                        .ok_or_else(|| malformed("cue chunk is cut short"))?;
+                    let mut point = ByteReader::new(point);
+                    let id = point.u32_le().unwrap_or_default();
+                    let sample_offset = point.bytes(16).and_then(|_| point.u32_le());
+                    points.push((id, sample_offset.unwrap_or_default()));
+                }
+            }
+            _ if chunk.is_label_list(file)? => {
+                let data = chunk.read(file)?;
+                let mut reader = ByteReader::new(&data[4..]);
+                while let (Some(id), Some(size)) = (reader.array::<4>(), reader.u32_le()) {
+                    let Some(body) = reader.bytes(size as usize) else {
+                        break;
+                    };
+                    if size & 1 == 1 {
+                        reader.u8();
+                    }
+                    let mut body = ByteReader::new(body);
+                    let Some(cue_id) = body.u32_le() else {
+                        continue;
+                    };
+                    match &id {
+                        b"labl" => {
+                            let text = body.rest();
+                            let text = text.split(|&b| b == 0).next().unwrap_or_default();
+                            labels.insert(cue_id, String::from_utf8_lossy(text).into_owned());
+                        }
+                        b"ltxt" => {
+                            if let Some(length) = body.u32_le() {
+                                lengths.insert(cue_id, length);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if points.is_empty() {
+        return Ok(Vec::new());
+    }
+    let sample_rate = sample_rate
+        .filter(|&rate| rate > 0)
+        .ok_or_else(|| malformed("WAV file has cue points but no sample rate"))?;
+    Ok(points
+        .into_iter()
+        .map(|(id, frame)| {
+            let start = Timestamp::from_frames(frame as u64, sample_rate);
+            let title = labels.remove(&id).unwrap_or_else(|| format!("Marker {}", id));
+            let marker = Marker::new(start, title);
+            match lengths.get(&id) {
+                Some(&length) => {
+                    let end = frame as u64 + length as u64;
+                    marker.with_end(Timestamp::from_frames(end, sample_rate))
+                }
+                None => marker,
+            }
+        })
+        .collect())
+}
+
+pub(super) fn write(path: &Path, markers: &[Marker]) -> Result<()> {
+    let mut file = open(path)?;
+    let chunks = chunks(&mut file)?;
+    let sample_rate = match chunks.iter().find(|c| &c.id == b"fmt ") {
+        Some(fmt) => {
+            let data = fmt.read(&mut file)?;
+            data.get(4..8).map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+        }
+        None => None,
+    }
+    .filter(|&rate| rate > 0)
+    .ok_or_else(|| malformed("WAV file has no sample rate"))?;
+
+    let mut kept = Vec::new();
+    for chunk in chunks {
+        if &chunk.id != b"cue " && !chunk.is_label_list(&mut file)? {
+            kept.push(chunk);
+        }
+    }
+    let (cue, labels) = marker_chunks(markers, sample_rate)?;
+    let riff_size = 4 + kept.iter().map(Chunk::stored_len).sum::<u64>()
+        + (8 + cue.len() as u64)
+        + (8 + labels.len() as u64);
+    let riff_size = u32::try_from(riff_size)
+        .map_err(|_| malformed("markers would push the WAV file past 4 GB"))?;
+
+    rewrite(path, |out| {
+        out.write_all(b"RIFF")?;
+        out.write_all(&riff_size.to_le_bytes())?;
+        out.write_all(b"WAVE")?;
+        for chunk in &kept {
+            file.seek(SeekFrom::Start(chunk.offset - 8))?;
+            let copied = io::copy(&mut (&mut file).take(8 + chunk.size as u64), out)?;
+            if copied != 8 + chunk.size as u64 {
+                return Err(malformed("WAV file changed while adding markers"));
+            }
+            if chunk.size & 1 == 1 {
+                out.write_all(&[0])?;
+            }
+        }
+        for (id, data) in [(b"cue ", &cue), (b"LIST", &labels)] {
+            out.write_all(id)?;
+            out.write_all(&(data.len() as u32).to_le_bytes())?;
+            out.write_all(data)?;
+        }
+        Ok(())
+    })
+}
+
+/// The `cue ` and `LIST adtl` chunk bodies for `markers`
+fn marker_chunks(markers: &[Marker], sample_rate: u32) -> Result<(Vec<u8>, Vec<u8>)> {
+    let frame = |time: Timestamp| {
+        u32::try_from(time.to_frames(sample_rate))
+            .map_err(|_| malformed("marker lies beyond what a WAV cue point can address"))
+    };
+
+    let mut cue = Vec::with_capacity(4 + markers.len() * CUE_POINT_LEN);
+    cue.extend_from_slice(&(markers.len() as u32).to_le_bytes());
+    let mut labels = b"adtl".to_vec();
+    for (i, marker) in markers.iter().enumerate() {
+        let id = i as u32 + 1;
+        let start = frame(marker.start)?;
+        cue.extend_from_slice(&id.to_le_bytes());
+        cue.extend_from_slice(&start.to_le_bytes()); // play order position
+        cue.extend_from_slice(b"data");
+        cue.extend_from_slice(&[0; 8]); // chunk and block start
+        cue.extend_from_slice(&start.to_le_bytes());
+
+        let mut text = marker.title.as_bytes().to_vec();
+        text.push(0);
+        push_sub_chunk(&mut labels, b"labl", id, &text);
+        if let Some(end) = marker.end {
+            let mut region = frame(end)?.saturating_sub(start).to_le_bytes().to_vec();
+            region.extend_from_slice(b"rgn ");
+            region.extend_from_slice(&[0; 8]); // country, language, dialect, code page
+            push_sub_chunk(&mut labels, b"ltxt", id, &region);
+        }
+    }
+    Ok((cue, labels))
+}
+
+fn push_sub_chunk(list: &mut Vec<u8>, id: &[u8; 4], cue_id: u32, body: &[u8]) {
+    let size = 4 + body.len();
+    list.extend_from_slice(id);
+    list.extend_from_slice(&(size as u32).to_le_bytes());
+    list.extend_from_slice(&cue_id.to_le_bytes());
+    list.extend_from_slice(body);
+    if size & 1 == 1 {
+        list.push(0);
+    }
+}
@@ -0,0 +1,481 @@
+// src-tauri/src/audio/chapters/vorbis.rs
+// FLAC and Ogg chapters: `CHAPTERnnn` / `CHAPTERnnnNAME` Vorbis comments
+
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use super::{malformed, open, rewrite, unsupported, ByteReader};
+use crate::audio::markers::Marker;
+use crate::audio::time::Timestamp;
+use crate::error::Result;
+
+/// FLAC metadata block types
+const FLAC_STREAMINFO: u8 = 0;
+const FLAC_VORBIS_COMMENT: u8 = 4;
+const FLAC_LAST_BLOCK: u8 = 0x80;
+
+/// Header packets are only looked for in the first pages of an Ogg stream
+const OGG_HEADER_PAGES: usize = 64;
+
+/// A Vorbis comment block: vendor string plus `KEY=value` entries
+struct Comments {
+    vendor: Vec<u8>,
+    entries: Vec<Vec<u8>>,
+    /// Bytes after the entries (Opus allows binary data there)
+    trailing: Vec<u8>,
+}
+
+impl Comments {
+    fn parse(data: &[u8]) -> Option<Comments> {
+        let mut reader = ByteReader::new(data);
+        let vendor_len = reader.u32_le()? as usize;
+        let vendor = reader.bytes(vendor_len)?.to_vec();
+        let count = reader.u32_le()?;
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            let len = reader.u32_le()? as usize;
+            entries.push(reader.bytes(len)?.to_vec());
+        }
+        Some(Comments {
+            vendor,
+            entries,
+            trailing: reader.rest().to_vec(),
+        })
+    }
+
+    fn empty() -> Comments {
+        Comments {
+            vendor: concat!("hermeneia ", env!("CARGO_PKG_VERSION")).as_bytes().to_vec(),
+            entries: Vec::new(),
+            trailing: Vec::new(),
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&(self.vendor.len() as u32).to_le_bytes());
+        out.extend_from_slice(&self.vendor);
+        out.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+        for entry in &self.entries {
+            out.extend_from_slice(&(entry.len() as u32).to_le_bytes());
+            out.extend_from_slice(entry);
+        }
+        out.extend_from_slice(&self.trailing);
+        out
+    }
+
+    fn chapters(&self) -> Vec<Marker> {
+        // (number, start, name) gathered from both kinds of entry
+        let mut chapters: Vec<(u32, Option<Timestamp>, Option<String>)> = Vec::new();
+        for entry in &self.entries {
+            let entry = String::from_utf8_lossy(entry);
+            let Some((key, value)) = entry.split_once('=') else {
+                continue;
+            };
+            let key = key.to_ascii_uppercase();
+            let Some(rest) = key.strip_prefix("CHAPTER") else {
+                continue;
+            };
+            let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+            let (number, suffix) = rest.split_at(digits);
+            let Ok(number) = number.parse::<u32>() else {
+                continue;
+            };
+            let index = match chapters.iter().position(|c| c.0 == number) {
+                Some(index) => index,
+                None => {
+                    chapters.push((number, None, None));
+                    chapters.len() - 1
+                }
+            };
+            match suffix {
+                "" => chapters[index].1 = parse_time(value),
+                "NAME" => chapters[index].2 = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        chapters
+            .into_iter()
+            .filter_map(|(number, start, name)| {
+                Some(Marker::new(start?, name.unwrap_or_else(|| format!("Chapter {}", number))))
+            })
+            .collect()
+    }
+
+    fn set_chapters(&mut self, markers: &[Marker]) {
+        self.entries.retain(|entry| {
+            let key = entry.split(|&b| b == b'=').next().unwrap_or_default();
+            !key.to_ascii_uppercase().starts_with(b"CHAPTER")
+        });
+        for (i, marker) in markers.iter().enumerate() {
+            let number = i + 1;
+            let start = format!("CHAPTER{:03}={}", number, format_time(marker.start));
+            self.entries.push(start.into_bytes());
+            let name = format!("CHAPTER{:03}NAME={}", number, marker.title);
+            self.entries.push(name.into_bytes());
+        }
+    }
+}
+
+/// `HH:MM:SS.sss`; the fraction may have any number of digits
+fn parse_time(text: &str) -> Option<Timestamp> {
+    let mut parts = text.trim().splitn(3, ':');
+    let (hours, minutes, seconds) = (parts.next()?, parts.next()?, parts.next()?);
+    let whole = hours.parse::<u64>().ok()? * 3600 + minutes.parse::<u64>().ok()? * 60;
+    let seconds = seconds.parse::<f64>().ok().filter(|s| (0.0..60.0).contains(s))?;
+    Some(Timestamp::from_seconds(whole as f64 + seconds))
+}
+
+fn format_time(time: Timestamp) -> String {
+    let millis = time.to_frames(1000);
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+/// A FLAC metadata block
+struct FlacBlock {
+    kind: u8,
+    data: Vec<u8>,
+}
+
+/// The metadata blocks of a FLAC file, and where the audio frames start
+fn flac_blocks(file: &mut File) -> Result<(Vec<FlacBlock>, u64)> {
+    file.seek(SeekFrom::Start(4))?;
+    let mut reader = BufReader::new(&mut *file);
+    let mut blocks = Vec::new();
+    let mut offset = 4u64;
+    loop {
+        let mut header = [0u8; 4];
+        reader
+            .read_exact(&mut header)
+            .map_err(|_| malformed("FLAC metadata is cut short"))?;
+        let len = u32::from_be_bytes([0, header[1], header[2], header[3]]) as usize;
+        let mut data = vec![0; len];
+        reader
+            .read_exact(&mut data)
+            .map_err(|_| malformed("FLAC metadata is cut short"))?;
+        offset += 4 + len as u64;
+        blocks.push(FlacBlock {
+            kind: header[0] & !FLAC_LAST_BLOCK,
+            data,
+        });
+        if header[0] & FLAC_LAST_BLOCK != 0 {
+            return Ok((blocks, offset));
+        }
+    }
+}
+
+pub(super) fn read_flac(file: &mut File) -> Result<Vec<Marker>> {
+    let (blocks, _) = flac_blocks(file)?;
+    Ok(blocks
+        .iter()
+        .find(|b| b.kind == FLAC_VORBIS_COMMENT)
+        .and_then(|b| Comments::parse(&b.data))
+        .map(|c| c.chapters())
+        .unwrap_or_default())
+}
+
+pub(super) fn write_flac(path: &Path, markers: &[Marker]) -> Result<()> {
+    let mut file = open(path)?;
+    let (mut blocks, audio_start) = flac_blocks(&mut file)?;
+    let index = match blocks.iter().position(|b| b.kind == FLAC_VORBIS_COMMENT) {
+        Some(index) => index,
+        None => {
+            // Right after STREAMINFO, which must stay first
+            let index = blocks.iter().position(|b| b.kind == FLAC_STREAMINFO).map_or(0, |i| i + 1);
+            let data = Comments::empty().to_bytes();
+            blocks.insert(index, FlacBlock {
+                kind: FLAC_VORBIS_COMMENT,
+                data,
+            });
+            index
+        }
+    };
+    let mut comments = Comments::parse(&blocks[index].data)
+        .ok_or_else(|| malformed("FLAC comment block is damaged"))?;
+    comments.set_chapters(markers);
+    blocks[index].data = comments.to_bytes();
+    if blocks[index].data.len() >= 1 << 24 {
+        return Err(malformed("FLAC comment block would be too large"));
+    }
+
+    rewrite(path, |out| {
+        out.write_all(b"fLaC")?;
+        let last = blocks.len() - 1;
+        for (i, block) in blocks.iter().enumerate() {
+            let flag = if i == last { FLAC_LAST_BLOCK } else { 0 };
+            let len = (block.data.len() as u32).to_be_bytes();
+            out.write_all(&[block.kind | flag, len[1], len[2], len[3]])?;
+            out.write_all(&block.data)?;
+        }
+        file.seek(SeekFrom::Start(audio_start))?;
+        io::copy(&mut file, out)?;
+        Ok(())
+    })
+}
+
+/// One Ogg page
+struct Page {
+    header_type: u8,
+    granule: u64,
+    serial: u32,
+    sequence: u32,
+    segments: Vec<u8>,
+    data: Vec<u8>,
+}
+
+impl Page {
+    /// Page header flag: the first packet continues from the previous page
+    const CONTINUED: u8 = 0x01;
+
+    fn read(reader: &mut impl Read) -> Result<Option<Page>> {
+        let mut header = [0u8; 27];
+        match reader.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+        if &header[..4] != b"OggS" {
+            return Err(malformed("lost sync in Ogg stream"));
+        }
+        let mut segments = vec![0; header[26] as usize];
+        reader.read_exact(&mut segments)?;
+        let mut data = vec![0; segments.iter().map(|&s| s as usize).sum()];
+        reader.read_exact(&mut data)?;
+        Ok(Some(Page {
+            header_type: header[5],
+            granule: u64::from_le_bytes(header[6..14].try_into().unwrap()),
+            serial: u32::from_le_bytes(header[14..18].try_into().unwrap()),
+            sequence: u32::from_le_bytes(header[18..22].try_into().unwrap()),
+            segments,
+            data,
+        }))
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(27 + self.segments.len() + self.data.len());
+        out.extend_from_slice(b"OggS");
+        out.push(0);
+        out.push(self.header_type);
+        out.extend_from_slice(&self.granule.to_le_bytes());
+        out.extend_from_slice(&self.serial.to_le_bytes());
+        out.extend_from_slice(&self.sequence.to_le_bytes());
+        out.extend_from_slice(&[0; 4]);
+        out.push(self.segments.len() as u8);
+        out.extend_from_slice(&self.segments);
+        out.extend_from_slice(&self.data);
+        let crc = ogg_crc(&out);
+        out[22..26].copy_from_slice(&crc.to_le_bytes());
+        out
+    }
+
+    /// Whether the page's last packet ends on it
+    fn ends_packet(&self) -> bool {
+        self.segments.last().is_some_and(|&s| s < 255)
+    }
+}
+
+/// Split a packet into pages starting at `sequence`
+fn paginate(packet: &[u8], serial: u32, sequence: u32) -> Vec<Page> {
+    // Lacing values; a multiple of 255 ends with an empty segment
+    let mut lacing = vec![255u8; packet.len() / 255];
+    lacing.push((packet.len() % 255) as u8);
+
+    let mut pages = Vec::new();
+    let mut offset = 0;
+    let count = lacing.len().div_ceil(255);
+    for (i, segments) in lacing.chunks(255).enumerate() {
+        let len: usize = segments.iter().map(|&s| s as usize).sum();
+        pages.push(Page {
+            header_type: if i == 0 { 0 } else { Page::CONTINUED },
+            // Pages where no packet ends carry no position
+            granule: if i + 1 == count { 0 } else { u64::MAX },
+            serial,
+            sequence: sequence + i as u32,
+            segments: segments.to_vec(),
+            data: packet[offset..offset + len].to_vec(),
+        });
+        offset += len;
+    }
+    pages
+}
+
+/// CRC-32 of an Ogg page (polynomial 0x04c11db7, no reflection)
+fn ogg_crc(data: &[u8]) -> u32 {
+    data.iter().fold(0u32, |crc, &byte| {
+        let mut crc = crc ^ ((byte as u32) << 24);
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 != 0 {
+                (crc << 1) ^ 0x04c1_1db7
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+/// The first two packets of the stream and the pages that carried them
+fn ogg_headers(reader: &mut impl Read) -> Result<(Vec<Vec<u8>>, Vec<Page>)> {
+    let mut packets: Vec<Vec<u8>> = Vec::new();
+    let mut pages = Vec::new();
+    let mut open_packet = false;
+    while packets.len() < 2 || open_packet {
+        if pages.len() == OGG_HEADER_PAGES {
+            return Err(malformed("Ogg header packets never end"));
+        }
+        let Some(page) = Page::read(reader)? else {
+            return Err(malformed("Ogg stream ends inside its headers"));
+        };
+        let mut offset = 0;
+        for &segment in &page.segments {
+            if !open_packet {
+                packets.push(Vec::new());
+            }
+            let packet = packets.last_mut().unwrap();
+            packet.extend_from_slice(&page.data[offset..offset + segment as usize]);
+            offset += segment as usize;
+            open_packet = segment == 255;
+        }
+        pages.push(page);
+    }
+    Ok((packets, pages))
+}
+
+pub(super) fn read_ogg(file: &mut File) -> Result<Vec<Marker>> {
+    file.seek(SeekFrom::Start(0))?;
+    let (packets, _) = ogg_headers(&mut BufReader::new(file))?;
+    let comments = if packets[0].starts_with(b"OpusHead") {
+        packets[1].strip_prefix(b"OpusTags")
+    } else if packets[0].starts_with(b"\x01vorbis") {
+        packets[1].strip_prefix(b"\x03vorbis")
+    } else {
+        None
+    };
+    Ok(comments
+        .and_then(Comments::parse)
+        .map(|c| c.chapters())
+        .unwrap_or_default())
+}
+
+pub(super) fn write_ogg_opus(path: &Path, markers: &[Marker]) -> Result<()> {
+    let mut reader = BufReader::new(open(path)?);
+    let (packets, pages) = ogg_headers(&mut reader)?;
+    if !packets[0].starts_with(b"OpusHead") {
+        return Err(unsupported("Ogg files other than Opus"));
+    }
+    // RFC 7845: OpusHead has a page to itself and OpusTags ends its last page
+    let serial = pages[0].serial;
+    let conforming = pages[0].data == packets[0]
+        && packets.len() == 2
+        && pages.iter().all(|p| p.serial == serial)
+        && pages.last().is_some_and(Page::ends_packet);
+    if !conforming {
+        return Err(malformed("Opus header pages aren't laid out as RFC 7845 requires"));
+    }
+    let mut comments = packets[1]
+        .strip_prefix(b"OpusTags")
+        .and_then(Comments::parse)
+        .ok_or_else(|| malformed("OpusTags header is damaged"))?;
+    comments.set_chapters(markers);
+    let mut tags = b"OpusTags".to_vec();
+    tags.extend(comments.to_bytes());
+
+    let tag_pages = paginate(&tags, serial, pages[1].sequence);
+    // Later pages of this stream shift by however many pages the tags grew
+    let shift = tag_pages.len() as i64 - (pages.len() - 1) as i64;
+
+    rewrite(path, |out| {
+        out.write_all(&pages[0].to_bytes())?;
+        for page in &tag_pages {
+            out.write_all(&page.to_bytes())?;
+        }
+        while let Some(mut page) = Page::read(&mut reader)? {
+            if page.serial == serial && shift != 0 {
+                page.sequence = (page.sequence as i64 + shift) as u32;
+            }
+            out.write_all(&page.to_bytes())?;
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn markers() -> Vec<Marker> {
+        vec![
+            Marker::new(Timestamp::ZERO, "Welcome"),
+            Marker::new(Timestamp::from_millis(3_723_456), "Sermon"),
+        ]
+    }
+
+    #[test]
+    fn test_comments_round_trip_and_keep_other_tags() {
+        let mut comments = Comments::empty();
+        comments.entries.push(b"TITLE=Sunday".to_vec());
+        comments.entries.push(b"chapter009=00:00:01.000".to_vec());
+        comments.set_chapters(&markers());
+
+        let parsed = Comments::parse(&comments.to_bytes()).unwrap();
+        assert_eq!(parsed.entries[0], b"TITLE=Sunday");
+        assert_eq!(parsed.entries[3], b"CHAPTER002=01:02:03.456");
+        assert_eq!(parsed.chapters(), markers());
+    }
+
+    #[test]
+    fn test_ogg_crc_matches_reference() {
+        // Same CRC as POSIX cksum (check value 0x765e7680) without its final inversion
+        assert_eq!(ogg_crc(b"123456789"), !0x765e_7680);
+        assert_eq!(ogg_crc(&[1]), 0x04c1_1db7);
+    }
+
+    #[test]
+    fn test_opus_tags_are_rewritten_in_place() {
+        let path = std::env::temp_dir().join("hermeneia_test_chapters.opus");
+        let mut head = b"OpusHead".to_vec();
+        head.extend_from_slice(&[1, 1, 0x38, 0x01, 0x80, 0xbb, 0, 0, 0, 0, 0]);
+        let mut tags = b"OpusTags".to_vec();
+        tags.extend(Comments::empty().to_bytes());
+        let mut bytes = Vec::new();
+        for page in paginate(&head, 7, 0).into_iter().chain(paginate(&tags, 7, 1)) {
+            bytes.extend(page.to_bytes());
+        }
+        // An "audio" page with a granule position
+        let mut audio = paginate(&[0xfc; 600], 7, 2);
+        audio[0].granule = 960;
+        bytes.extend(audio[0].to_bytes());
+        std::fs::write(&path, &bytes).unwrap();
+
+        // Enough chapter text to need a second tags page
+        let long: Vec<Marker> = (0..400)
+            .map(|i| Marker::new(Timestamp::from_millis(i * 1000), "x".repeat(150)))
+            .collect();
+        write_ogg_opus(&path, &long).unwrap();
+
+        let mut file = File::open(&path).unwrap();
+        assert_eq!(read_ogg(&mut file).unwrap(), long);
+        file.seek(SeekFrom::Start(0)).unwrap();
+        let mut reader = BufReader::new(file);
+        let mut pages = Vec::new();
+        while let Some(page) = Page::read(&mut reader).unwrap() {
+            pages.push(page);
+        }
+        let sequences: Vec<u32> = pages.iter().map(|p| p.sequence).collect();
+        assert_eq!(sequences, (0..pages.len() as u32).collect::<Vec<_>>());
+        assert_eq!(pages.len(), 4);
+        assert_eq!(pages.last().unwrap().granule, 960);
+        // Pages were rewritten with their new sequence numbers' checksums
+        let written = std::fs::read(&path).unwrap();
+        let audio_page = pages.last().unwrap().to_bytes();
+        assert!(written.ends_with(&audio_page));
+        std::fs::remove_file(path).ok();
+    }
+}
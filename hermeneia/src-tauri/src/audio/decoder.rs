@@ -1,16 +1,18 @@
 // src-tauri/src/audio/decoder.rs
 
 use symphonia::core::audio::AudioBufferRef;
-use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
-use symphonia::core::formats::FormatOptions;
-use symphonia::core::io::MediaSourceStream;
-use symphonia::core::meta::MetadataOptions;
-use symphonia::core::probe::Hint;
-use std::fs::File;
+use symphonia::core::codecs::{Decoder, DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::formats::{SeekMode, SeekTo};
+use symphonia::core::units::{Time, TimeBase};
 use std::path::Path;
 
+use crate::audio::{media_info, probe_cache};
+use crate::audio::reader_pool::{open_reader, PooledReader};
+use crate::audio::watchdog::{self, PROBE_TIMEOUT};
 use crate::audio::types::{AudioData, AudioInfo};
-use crate::error::{AudioError, Result};
+use crate::error::{AudioError, DecodeError, Result};
+use crate::profile;
+use crate::progress::{check_cancelled, NoProgress, ProgressSink};
 
 /// Decodes an audio file to PCM samples in memory
 /// 
@@ -35,37 +37,401 @@ use crate::error::{AudioError, Result};
 /// # }
 /// ```
 pub fn decode_audio_file<P: AsRef<Path>>(path: P) -> Result<AudioData> {
-    let path = path.as_ref();
-    let path_str = path.to_string_lossy().to_string();
+    decode_audio_file_with_progress(path, &mut NoProgress)
+}
+
+/// Decode an entire file like [`decode_audio_file`], reporting progress
+///
+/// `progress` gets the "decode" stage and the fraction decoded so far (0.0
+/// to 1.0); files whose container doesn't report a length only get the
+/// final 1.0. Stops with [`AudioError::Cancelled`] when it asks to.
+pub fn decode_audio_file_with_progress<P: AsRef<Path>>(
+    path: P,
+    progress: &mut dyn ProgressSink,
+) -> Result<AudioData> {
+    let mut track = open_audio_track(path.as_ref())?;
+    let _stage = profile::stage("decode");
+    progress.stage("decode");
+
+    // Decode all packets into a sample buffer
+    let mut samples = Vec::new();
+
+    // Get packets until end of stream
+    while let Ok(packet) = track.format.next_packet() {
+        // Skip packets from other tracks (e.g., video, album art)
+        if packet.track_id() != track.track_id {
+            continue;
+        }
+        check_cancelled(progress)?;
+
+        // Decode the packet
+        let decoded = track
+            .decoder
+            .decode(&packet)
+            .map_err(|e| DecodeError::Packet(e.to_string()))?;
+
+        // Convert decoded audio to f32 samples
+        convert_audio_buffer_to_f32(&decoded, &mut samples);
+
+        if let Some(n_frames) = track.n_frames.filter(|&n| n > 0) {
+            let done = (samples.len() / track.channels as usize) as f64 / n_frames as f64;
+            progress.progress(done.min(1.0));
+        }
+    }
+    progress.progress(1.0);
+
+    Ok(AudioData {
+        samples,
+        sample_rate: track.sample_rate,
+        channels: track.channels,
+    })
+}
+
+/// Decode only the samples between `start_seconds` and `end_seconds`
+///
+/// Seeks close to the start of the window instead of decoding everything
+/// before it, so cutting a clip from a multi-hour file only costs the clip.
+/// Formats that cannot seek are decoded from the beginning and the samples
+/// before the window are dropped.
+///
+/// # Arguments
+/// * `path` - Path to the audio file
+/// * `start_seconds` - Start of the window
+/// * `end_seconds` - End of the window (clamped to the end of the file)
+///
+/// # Returns
+/// AudioData containing just the requested window
+///
+/// # Example
+/// ```no_run
+/// use hermeneia_lib::audio::decode_audio_range;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// // Five seconds from the middle of a long recording
+/// let clip = decode_audio_range("sermon.mp3", 3600.0, 3605.0)?;
+/// println!("Decoded {} seconds", clip.duration_seconds());
+/// # Ok(())
+/// # }
+/// ```
+pub fn decode_audio_range<P: AsRef<Path>>(
+    path: P,
+    start_seconds: f64,
+    end_seconds: f64,
+) -> Result<AudioData> {
+    let mut clip: Option<AudioData> = None;
 
-    // Open the file
-    let file = File::open(path).map_err(|e| AudioError::FileOpen {
-        path: path_str.clone(),
-        source: e,
+    decode_audio_range_with(path, start_seconds, end_seconds, |chunk| {
+        match &mut clip {
+            Some(clip) => clip.samples.extend_from_slice(&chunk.samples),
+            None => clip = Some(chunk),
+        }
+        Ok(())
     })?;
 
-    // Create a media source stream (buffered reader)
-    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    clip.ok_or_else(|| {
+        DecodeError::EmptyRange {
+            start: start_seconds,
+            end: end_seconds,
+        }
+        .into()
+    })
+}
 
-    // Create a hint to help symphonia detect the format
-    let mut hint = Hint::new();
-    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
-        hint.with_extension(extension);
+/// Decode a time window and hand it to `on_chunk` one packet at a time
+///
+/// Same seeking behaviour as [`decode_audio_range`], but nothing is
+/// accumulated: each chunk can be written out (e.g. with a
+/// `WavStreamWriter`) and dropped, keeping memory use flat regardless of the
+/// window length.
+///
+/// # Returns
+/// Number of frames passed to `on_chunk`
+pub fn decode_audio_range_with<P, F>(
+    path: P,
+    start_seconds: f64,
+    end_seconds: f64,
+    mut on_chunk: F,
+) -> Result<u64>
+where
+    P: AsRef<Path>,
+    F: FnMut(AudioData) -> Result<()>,
+{
+    if !(start_seconds >= 0.0 && end_seconds > start_seconds) {
+        return Err(AudioError::InvalidParameter(format!(
+            "Invalid decode range {}s to {}s",
+            start_seconds, end_seconds
+        )));
+    }
+
+    let mut track = open_audio_track(path.as_ref())?;
+    let _stage = profile::stage("decode");
+    let channels = track.channels as usize;
+    let start_frame = (start_seconds * track.sample_rate as f64).round() as u64;
+    let end_frame = (end_seconds * track.sample_rate as f64).round() as u64;
+
+    // Accurate seeking lands on or before the requested time; packet
+    // timestamps tell us how much of the first packets to drop
+    if start_frame > 0 {
+        let seek = track.format.seek(
+            SeekMode::Accurate,
+            SeekTo::Time {
+                time: Time::from(start_seconds),
+                track_id: Some(track.track_id),
+            },
+        );
+        match seek {
+            Ok(_) => track.decoder.reset(),
+            Err(e) => tracing::debug!(error = %e, "Seek failed, decoding from the start"),
+        }
+    }
+
+    let mut buffer = Vec::new();
+    let mut delivered = 0u64;
+
+    while let Ok(packet) = track.format.next_packet() {
+        if packet.track_id() != track.track_id {
+            continue;
+        }
+
+        let packet_start = track.ts_to_frame(packet.ts());
+        if packet_start >= end_frame {
+            break;
+        }
+
+        let decoded = track
+            .decoder
+            .decode(&packet)
+            .map_err(|e| DecodeError::Packet(e.to_string()))?;
+
+        buffer.clear();
+        convert_audio_buffer_to_f32(&decoded, &mut buffer);
+
+        let frames = (buffer.len() / channels) as u64;
+        let from = start_frame.saturating_sub(packet_start).min(frames);
+        let to = (end_frame - packet_start).min(frames);
+        if from >= to {
+            continue;
+        }
+
+        delivered += to - from;
+        on_chunk(AudioData {
+            samples: buffer[from as usize * channels..to as usize * channels].to_vec(),
+            sample_rate: track.sample_rate,
+            channels: track.channels,
+        })?;
+    }
+
+    Ok(delivered)
+}
+
+/// Decode a whole file as a series of `chunk_seconds`-long pieces
+///
+/// Only about one chunk is held at a time, so a multi-hour recording can
+/// be processed without decoding it into one multi-gigabyte buffer. Every
+/// chunk but the last has exactly `chunk_seconds` worth of frames; the
+/// last has whatever remains. A packet that fails to decode ends the
+/// iteration with that error.
+///
+/// # Example
+/// ```no_run
+/// use hermeneia_lib::audio::decode_audio_chunks;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// // Loudest sample, a minute at a time
+/// let mut loudest = 0.0f32;
+/// for chunk in decode_audio_chunks("sermon.mp3", 60.0)? {
+///     let chunk = chunk?;
+///     loudest = chunk.samples.iter().fold(loudest, |max, s| max.max(s.abs()));
+/// }
+/// println!("Peak: {:.3}", loudest);
+/// # Ok(())
+/// # }
+/// ```
+pub fn decode_audio_chunks<P: AsRef<Path>>(path: P, chunk_seconds: f64) -> Result<AudioChunks> {
+    if !(chunk_seconds > 0.0 && chunk_seconds.is_finite()) {
+        return Err(AudioError::InvalidParameter(format!(
+            "Chunk length must be positive (got {}s)",
+            chunk_seconds
+        )));
+    }
+    let track = open_audio_track(path.as_ref())?;
+    let chunk_frames = ((chunk_seconds * track.sample_rate as f64).round() as usize).max(1);
+    Ok(AudioChunks {
+        track,
+        chunk_frames,
+        pending: Vec::new(),
+        finished: false,
+    })
+}
+
+/// Decode a whole file like [`decode_audio_chunks`], handing each chunk to
+/// `on_chunk`
+///
+/// Reports the "decode" stage and the fraction decoded so far to
+/// `progress`, and stops with [`AudioError::Cancelled`] when it asks to.
+///
+/// # Returns
+/// Number of frames passed to `on_chunk`
+pub fn decode_audio_chunks_with<P, F>(
+    path: P,
+    chunk_seconds: f64,
+    progress: &mut dyn ProgressSink,
+    mut on_chunk: F,
+) -> Result<u64>
+where
+    P: AsRef<Path>,
+    F: FnMut(AudioData) -> Result<()>,
+{
+    let chunks = decode_audio_chunks(path, chunk_seconds)?;
+    let _stage = profile::stage("decode");
+    progress.stage("decode");
+    let total = chunks.total_frames().filter(|&n| n > 0);
+    let mut delivered = 0u64;
+    for chunk in chunks {
+        check_cancelled(progress)?;
+        let chunk = chunk?;
+        delivered += chunk.frame_count() as u64;
+        on_chunk(chunk)?;
+        if let Some(total) = total {
+            progress.progress((delivered as f64 / total as f64).min(1.0));
+        }
+    }
+    progress.progress(1.0);
+    Ok(delivered)
+}
+
+/// Fixed-length chunks of a file's audio, from [`decode_audio_chunks`]
+pub struct AudioChunks {
+    track: AudioTrack,
+    chunk_frames: usize,
+    /// Decoded samples not yet handed out
+    pending: Vec<f32>,
+    finished: bool,
+}
+
+impl AudioChunks {
+    pub fn sample_rate(&self) -> u32 {
+        self.track.sample_rate
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.track.channels
+    }
+
+    /// Frames in the whole file, when the container says
+    pub fn total_frames(&self) -> Option<u64> {
+        self.track.n_frames
+    }
+
+    /// Frames in every chunk but the last
+    pub fn chunk_frames(&self) -> usize {
+        self.chunk_frames
+    }
+}
+
+impl Iterator for AudioChunks {
+    type Item = Result<AudioData>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk_samples = self.chunk_frames * self.track.channels as usize;
+        while !self.finished && self.pending.len() < chunk_samples {
+            let Ok(packet) = self.track.format.next_packet() else {
+                self.finished = true;
+                break;
+            };
+            if packet.track_id() != self.track.track_id {
+                continue;
+            }
+            match self.track.decoder.decode(&packet) {
+                Ok(decoded) => convert_audio_buffer_to_f32(&decoded, &mut self.pending),
+                Err(e) => {
+                    self.finished = true;
+                    self.pending.clear();
+                    return Some(Err(DecodeError::Packet(e.to_string()).into()));
+                }
+            }
+        }
+        if self.pending.is_empty() {
+            return None;
+        }
+
+        let rest = self.pending.split_off(chunk_samples.min(self.pending.len()));
+        Some(Ok(AudioData {
+            samples: std::mem::replace(&mut self.pending, rest),
+            sample_rate: self.track.sample_rate,
+            channels: self.track.channels,
+        }))
+    }
+}
+
+/// A probed file with a decoder ready for its first audio track
+pub(crate) struct AudioTrack {
+    pub(crate) format: PooledReader,
+    pub(crate) decoder: Box<dyn Decoder>,
+    pub(crate) track_id: u32,
+    pub(crate) sample_rate: u32,
+    pub(crate) channels: u16,
+    time_base: Option<TimeBase>,
+    pub(crate) n_frames: Option<u64>,
+}
+
+impl AudioTrack {
+    /// Convert a packet timestamp to a frame index at the track's sample rate
+    pub(crate) fn ts_to_frame(&self, ts: u64) -> u64 {
+        match self.time_base {
+            Some(tb) if !(tb.numer == 1 && tb.denom == self.sample_rate) => {
+                let time = tb.calc_time(ts);
+                ((time.seconds as f64 + time.frac) * self.sample_rate as f64).round() as u64
+            }
+            _ => ts,
+        }
     }
 
-    // Probe the media source to detect format
-    let probed = symphonia::default::get_probe()
-        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
-        .map_err(|e| AudioError::DecodeFailed(format!("Failed to probe format: {}", e)))?;
+    /// Count the track's frames by reading it to the end, for files whose
+    /// header doesn't say (VBR MP3s without a Xing header, streamed Oggs)
+    ///
+    /// Packets are only decoded when the container gives no duration for
+    /// them. Leaves the reader at the end; open the file again to read it.
+    pub(crate) fn count_frames(
+        &mut self,
+        mut cancelled: impl FnMut() -> Result<()>,
+    ) -> Result<u64> {
+        let mut frames = 0;
+        while let Ok(packet) = self.format.next_packet() {
+            cancelled()?;
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+            let end = if packet.dur() > 0 {
+                self.ts_to_frame(packet.ts() + packet.dur())
+            } else {
+                let decoded_frames = self
+                    .decoder
+                    .decode(&packet)
+                    .map_err(|e| DecodeError::Packet(e.to_string()))?
+                    .frames();
+                self.ts_to_frame(packet.ts()) + decoded_frames as u64
+            };
+            frames = frames.max(end);
+        }
+        Ok(frames)
+    }
+}
 
-    let mut format = probed.format;
+/// Open a file, detect its format and set up a decoder for the first audio track
+///
+/// The format reader comes from the reader pool, so a file probed by an
+/// earlier call isn't probed again. Probing and decoder setup each run
+/// under a [`PROBE_TIMEOUT`] watchdog.
+pub(crate) fn open_audio_track(path: &Path) -> Result<AudioTrack> {
+    let format = open_reader(path)?;
 
     // Find the default audio track (skip video/subtitle tracks)
     let track = format
         .tracks()
         .iter()
         .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
-        .ok_or_else(|| AudioError::DecodeFailed("No audio track found in file".to_string()))?;
+        .ok_or(DecodeError::NoAudioTrack)?;
 
     let track_id = track.id;
 
@@ -73,53 +439,43 @@ pub fn decode_audio_file<P: AsRef<Path>>(path: P) -> Result<AudioData> {
     let sample_rate = track
         .codec_params
         .sample_rate
-        .ok_or_else(|| AudioError::DecodeFailed("Sample rate not found".to_string()))?;
+        .ok_or(DecodeError::MissingInfo("Sample rate"))?;
 
     let channels = track
         .codec_params
         .channels
-        .ok_or_else(|| AudioError::DecodeFailed("Channel info not found".to_string()))?
+        .ok_or(DecodeError::MissingInfo("Channel info"))?
         .count() as u16;
 
-    // Create decoder for this track
-    let mut decoder = symphonia::default::get_codecs()
-        .make(&track.codec_params, &DecoderOptions::default())
-        .map_err(|e| AudioError::DecodeFailed(format!("Failed to create decoder: {}", e)))?;
+    let time_base = track.codec_params.time_base;
+    let n_frames = track.codec_params.n_frames;
 
-    // Decode all packets into a sample buffer
-    let mut samples = Vec::new();
-
-    loop {
-        // Get next packet
-        let packet = match format.next_packet() {
-            Ok(packet) => packet,
-            Err(_) => break, // End of stream
-        };
-
-        // Skip packets from other tracks (e.g., video, album art)
-        if packet.track_id() != track_id {
-            continue;
-        }
-
-        // Decode the packet
-        let decoded = decoder
-            .decode(&packet)
-            .map_err(|e| AudioError::DecodeFailed(format!("Decode error: {}", e)))?;
-
-        // Convert decoded audio to f32 samples
-        convert_audio_buffer_to_f32(&decoded, &mut samples);
-    }
+    // Create decoder for this track, under the same watchdog as the probe
+    let params = track.codec_params.clone();
+    let what = format!("setting up the decoder for {}", path.display());
+    let decoder = watchdog::with_timeout(&what, PROBE_TIMEOUT, move |_| {
+        symphonia::default::get_codecs()
+            .make(&params, &DecoderOptions::default())
+            .map_err(|e| DecodeError::Codec(e.to_string()).into())
+    })?;
 
-    Ok(AudioData {
-        samples,
+    Ok(AudioTrack {
+        format,
+        decoder,
+        track_id,
         sample_rate,
         channels,
+        time_base,
+        n_frames,
     })
 }
 
 /// Get audio file metadata without decoding all samples
 /// 
 /// Much faster than decode_audio_file() for just getting duration/info
+///
+/// Files probed earlier in the session are answered from the probe cache
+/// without being opened again.
 /// 
 /// # Example
 /// ```no_run
@@ -134,30 +490,18 @@ pub fn decode_audio_file<P: AsRef<Path>>(path: P) -> Result<AudioData> {
 /// ```
 pub fn get_audio_info<P: AsRef<Path>>(path: P) -> Result<AudioInfo> {
     let path = path.as_ref();
-    let path_str = path.to_string_lossy().to_string();
-
-    let file = File::open(path).map_err(|e| AudioError::FileOpen {
-        path: path_str.clone(),
-        source: e,
-    })?;
-
-    let mss = MediaSourceStream::new(Box::new(file), Default::default());
-
-    let mut hint = Hint::new();
-    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
-        hint.with_extension(extension);
-    }
-
-    let probed = symphonia::default::get_probe()
-        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
-        .map_err(|e| AudioError::DecodeFailed(format!("Failed to probe: {}", e)))?;
-
-    let format = probed.format;
-    let track = format
-        .tracks()
+    // A file probed earlier doesn't need to be opened at all
+    let (tracks, details) = match probe_cache::cached(path) {
+        Some(probe) => (probe.tracks.to_vec(), probe.details.clone()),
+        None => {
+            let reader = open_reader(path)?;
+            (reader.tracks().to_vec(), reader.details().clone())
+        }
+    };
+    let track = tracks
         .iter()
         .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
-        .ok_or_else(|| AudioError::DecodeFailed("No audio track".to_string()))?;
+        .ok_or(DecodeError::NoAudioTrack)?;
 
     let sample_rate = track.codec_params.sample_rate.unwrap_or(0);
     let channels = track
@@ -175,84 +519,283 @@ pub fn get_audio_info<P: AsRef<Path>>(path: P) -> Result<AudioInfo> {
         0.0
     };
 
+    let file_size_bytes = std::fs::metadata(path)
+        .map_err(|e| AudioError::FileOpen {
+            path: path.to_string_lossy().to_string(),
+            source: e,
+        })?
+        .len();
+    let bitrate_kbps = (duration_seconds > 0.0)
+        .then(|| (file_size_bytes as f64 * 8.0 / duration_seconds / 1000.0).round() as u32);
+    let container = details
+        .container
+        .or_else(|| media_info::stream_container(track.codec_params.codec));
+
     Ok(AudioInfo {
         duration_seconds,
         sample_rate,
         channels,
         format: format!("{:?}", track.codec_params.codec),
         bit_depth: track.codec_params.bits_per_sample.map(|b| b as u16),
+        container: container.map(String::from),
+        codec_profile: media_info::codec_profile(&track.codec_params),
+        bitrate_kbps,
+        file_size_bytes,
+        tags: details.tags,
     })
 }
 
-/// Convert symphonia's AudioBufferRef to Vec<f32>
+/// Convert symphonia's AudioBufferRef to interleaved f32 samples
 /// 
-/// Handles all sample formats (u8, i16, i32, f32, f64) and converts to f32
-fn convert_audio_buffer_to_f32(buffer: &AudioBufferRef, output: &mut Vec<f32>) {
+/// Handles all sample formats (u8, i16, i32, f32, f64) and converts to f32.
+/// Symphonia hands out planar buffers (one plane per channel), so samples
+/// are interleaved here to match the AudioData layout.
+pub(crate) fn convert_audio_buffer_to_f32(buffer: &AudioBufferRef, output: &mut Vec<f32>) {
     match buffer {
-        // Already f32 - just copy
-        AudioBufferRef::F32(buf) => {
-            for plane in buf.planes().planes() {
-                output.extend_from_slice(plane);
-            }
-        }
-        
+        // Already f32 - just interleave
+        AudioBufferRef::F32(buf) => interleave_planes(buf.planes().planes(), output, |&s| s),
+
         // Convert f64 → f32
         AudioBufferRef::F64(buf) => {
-            for plane in buf.planes().planes() {
-                output.extend(plane.iter().map(|&s| s as f32));
-            }
+            interleave_planes(buf.planes().planes(), output, |&s| s as f32)
         }
-        
+
         // Convert signed integers to f32 in range [-1.0, 1.0]
         AudioBufferRef::S8(buf) => {
-            for plane in buf.planes().planes() {
-                output.extend(plane.iter().map(|&s| s as f32 / 128.0));
-            }
+            interleave_planes(buf.planes().planes(), output, |&s| s as f32 / 128.0)
         }
         AudioBufferRef::S16(buf) => {
-            for plane in buf.planes().planes() {
-                output.extend(plane.iter().map(|&s| s as f32 / 32768.0));
-            }
-        }
-        AudioBufferRef::S24(buf) => {
-            for plane in buf.planes().planes() {
-                output.extend(plane.iter().map(|&s| s.inner() as f32 / 8388608.0));
-            }
+            interleave_planes(buf.planes().planes(), output, |&s| s as f32 / 32768.0)
         }
+        AudioBufferRef::S24(buf) => interleave_planes(buf.planes().planes(), output, |&s| {
+            s.inner() as f32 / 8388608.0
+        }),
         AudioBufferRef::S32(buf) => {
-            for plane in buf.planes().planes() {
-                output.extend(plane.iter().map(|&s| s as f32 / 2147483648.0));
-            }
+            interleave_planes(buf.planes().planes(), output, |&s| s as f32 / 2147483648.0)
         }
-        
+
         // Convert unsigned integers to f32
         AudioBufferRef::U8(buf) => {
-            for plane in buf.planes().planes() {
-                output.extend(plane.iter().map(|&s| (s as f32 - 128.0) / 128.0));
-            }
+            interleave_planes(buf.planes().planes(), output, |&s| (s as f32 - 128.0) / 128.0)
         }
-        AudioBufferRef::U16(buf) => {
-            for plane in buf.planes().planes() {
-                output.extend(plane.iter().map(|&s| (s as f32 - 32768.0) / 32768.0));
-            }
+        AudioBufferRef::U16(buf) => interleave_planes(buf.planes().planes(), output, |&s| {
+            (s as f32 - 32768.0) / 32768.0
+        }),
+        AudioBufferRef::U24(buf) => interleave_planes(buf.planes().planes(), output, |&s| {
+            (s.inner() as f32 - 8388608.0) / 8388608.0
+        }),
+        AudioBufferRef::U32(buf) => interleave_planes(buf.planes().planes(), output, |&s| {
+            (s as f32 - 2147483648.0) / 2147483648.0
+        }),
+    }
+}
+
+/// Interleave planar channel data into `output`, converting each sample
+///
+/// `[[L0, L1], [R0, R1]]` becomes `[L0, R0, L1, R1]`
+fn interleave_planes<T, F>(planes: &[&[T]], output: &mut Vec<f32>, convert: F)
+where
+    F: Fn(&T) -> f32,
+{
+    let frame_count = planes.first().map_or(0, |p| p.len());
+    output.reserve(frame_count * planes.len());
+
+    for frame_idx in 0..frame_count {
+        for plane in planes {
+            output.push(convert(&plane[frame_idx]));
         }
-        AudioBufferRef::U24(buf) => {
-            for plane in buf.planes().planes() {
-                output.extend(
-                    plane
-                        .iter()
-                        .map(|&s| (s.inner() as f32 - 8388608.0) / 8388608.0),
-                );
-            }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::encoder::encode_wav;
+
+    #[test]
+    fn test_interleave_planes() {
+        let left = [1i16, 2, 3];
+        let right = [-1i16, -2, -3];
+        let mut output = Vec::new();
+
+        interleave_planes(&[&left[..], &right[..]], &mut output, |&s| s as f32);
+
+        assert_eq!(output, vec![1.0, -1.0, 2.0, -2.0, 3.0, -3.0]);
+    }
+
+    #[test]
+    fn test_decode_preserves_stereo_interleaving() {
+        // Left channel positive, right channel negative
+        let frames = 4096;
+        let mut samples = Vec::with_capacity(frames * 2);
+        for _ in 0..frames {
+            samples.push(0.25);
+            samples.push(-0.75);
         }
-        AudioBufferRef::U32(buf) => {
-            for plane in buf.planes().planes() {
-                output.extend(
-                    plane
-                        .iter()
-                        .map(|&s| (s as f32 - 2147483648.0) / 2147483648.0),
-                );
-            }
+        let audio = AudioData {
+            samples,
+            sample_rate: 44100,
+            channels: 2,
+        };
+
+        let path = std::env::temp_dir().join("hermeneia_test_decode_interleave.wav");
+        encode_wav(&audio, &path).unwrap();
+
+        let decoded = decode_audio_file(&path).unwrap();
+        assert_eq!(decoded.channels, 2);
+        assert_eq!(decoded.frame_count(), frames);
+        for frame in decoded.samples.chunks(2) {
+            assert!((frame[0] - 0.25).abs() < 1e-6);
+            assert!((frame[1] + 0.75).abs() < 1e-6);
         }
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_decode_reports_progress() {
+        let audio = AudioData {
+            samples: vec![0.1; 44100],
+            sample_rate: 44100,
+            channels: 1,
+        };
+        let path = std::env::temp_dir().join("hermeneia_test_decode_progress.wav");
+        encode_wav(&audio, &path).unwrap();
+
+        let mut reports = Vec::new();
+        decode_audio_file_with_progress(&path, &mut |p| reports.push(p)).unwrap();
+        assert!(reports.len() > 1);
+        assert!(reports.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(reports.last(), Some(&1.0));
+
+        std::fs::remove_file(path).ok();
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_decode_range_matches_full_decode() {
+        // A ramp makes every frame distinguishable
+        let frames = 44100 * 3;
+        let samples: Vec<f32> = (0..frames * 2)
+            .map(|i| (i / 2) as f32 / frames as f32 * if i % 2 == 0 { 1.0 } else { -1.0 })
+            .collect();
+        let audio = AudioData {
+            samples,
+            sample_rate: 44100,
+            channels: 2,
+        };
+
+        let path = std::env::temp_dir().join("hermeneia_test_decode_range.wav");
+        encode_wav(&audio, &path).unwrap();
+
+        let clip = decode_audio_range(&path, 1.25, 2.0).unwrap();
+        let start = (1.25 * 44100.0) as usize * 2;
+        let end = (2.0 * 44100.0) as usize * 2;
+        assert_eq!(clip.channels, 2);
+        assert_eq!(clip.samples.len(), end - start);
+        assert_eq!(clip.samples, audio.samples[start..end]);
+
+        // Windows past the end are clamped to what the file has
+        let tail = decode_audio_range(&path, 2.5, 10.0).unwrap();
+        assert_eq!(tail.frame_count(), frames - (2.5 * 44100.0) as usize);
+
+        // Chunked delivery reports the same number of frames
+        let mut chunks = 0;
+        let delivered = decode_audio_range_with(&path, 0.5, 1.5, |_| {
+            chunks += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(delivered, 44100);
+        assert!(chunks > 1);
+
+        assert!(decode_audio_range(&path, 5.0, 6.0).is_err());
+        assert!(decode_audio_range(&path, 2.0, 1.0).is_err());
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_decode_chunks_have_fixed_length() {
+        let frames = 8000 * 5 / 2;
+        let audio = AudioData {
+            samples: (0..frames * 2).map(|i| (i % 400) as f32 / 400.0 - 0.5).collect(),
+            sample_rate: 8000,
+            channels: 2,
+        };
+        let path = std::env::temp_dir().join("hermeneia_test_decode_chunks.wav");
+        encode_wav(&audio, &path).unwrap();
+
+        let chunks: Vec<AudioData> = decode_audio_chunks(&path, 1.0)
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        let lengths: Vec<usize> = chunks.iter().map(AudioData::frame_count).collect();
+        assert_eq!(lengths, [8000, 8000, 4000]);
+        let joined: Vec<f32> = chunks.iter().flat_map(|c| c.samples.iter().copied()).collect();
+        assert_eq!(joined, decode_audio_file(&path).unwrap().samples);
+
+        let mut reports = Vec::new();
+        let mut count = 0;
+        let delivered = decode_audio_chunks_with(&path, 0.5, &mut |p| reports.push(p), |_| {
+            count += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!((delivered, count), (frames as u64, 5));
+        assert_eq!(reports.last(), Some(&1.0));
+
+        assert!(decode_audio_chunks(&path, 0.0).is_err());
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_audio_info_describes_the_file() {
+        // One second of mono at 8 kHz
+        let audio = AudioData {
+            samples: vec![0.0; 8000],
+            sample_rate: 8000,
+            channels: 1,
+        };
+        let path = std::env::temp_dir().join("hermeneia_test_decode_info.wav");
+        encode_wav(&audio, &path).unwrap();
+
+        let info = get_audio_info(&path).unwrap();
+        assert_eq!(info.container.as_deref(), Some("WAV"));
+        let size = std::fs::metadata(&path).unwrap().len();
+        assert_eq!(info.file_size_bytes, size);
+        assert_eq!(info.bitrate_kbps, Some((size as f64 * 8.0 / 1000.0).round() as u32));
+        assert_eq!(info.codec_profile, None);
+        assert_eq!(info.tags, Default::default());
+
+        // Answered from the probe cache the second time, with the same details
+        let cached = get_audio_info(&path).unwrap();
+        assert_eq!(cached.container, info.container);
+        assert_eq!(cached.bitrate_kbps, info.bitrate_kbps);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_count_frames_matches_the_header() {
+        let audio = AudioData {
+            samples: vec![0.1; 12345 * 2],
+            sample_rate: 8000,
+            channels: 2,
+        };
+        let path = std::env::temp_dir().join("hermeneia_test_decode_count.wav");
+        encode_wav(&audio, &path).unwrap();
+
+        let mut track = open_audio_track(&path).unwrap();
+        assert_eq!(track.count_frames(|| Ok(())).unwrap(), 12345);
+        assert_eq!(track.n_frames, Some(12345));
+        drop(track);
+
+        // Reopening rewinds, and the scan stops when asked to
+        let mut track = open_audio_track(&path).unwrap();
+        let cancelled = track.count_frames(|| Err(AudioError::Cancelled));
+        assert!(matches!(cancelled, Err(AudioError::Cancelled)));
+        drop(track);
+
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,376 @@
+// src-tauri/src/audio/dsp/denoise.rs
+// Spectral subtraction noise reduction
+
+use std::sync::Arc;
+
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+
+use crate::audio::dsp::{from_real, to_real, Real};
+use crate::audio::types::AudioData;
+use crate::error::{AudioError, Result};
+
+/// Analysis window length, rounded up to a power of two in samples
+const WINDOW_SECS: f64 = 0.032;
+
+/// How much more noise than estimated is taken out; the minimum tracker
+/// sits below the average noise level, so this makes up the difference
+const OVER_SUBTRACTION: Real = 3.0;
+
+/// Lowest gain a frequency bin is turned down to, so the background is
+/// lowered rather than gated into warbling "musical noise"
+const GAIN_FLOOR: Real = 0.1;
+
+/// How far back the noise estimate looks for a quiet moment; longer than
+/// a spoken phrase, short enough to follow a changing room
+const MEMORY_SECS: f64 = 1.5;
+
+/// The memory is kept as this many sub-windows, each holding its minimum
+const SUB_WINDOWS: usize = 6;
+
+/// Weight of the previous frame when smoothing each bin's power
+const POWER_SMOOTHING: Real = 0.7;
+
+/// Turn down steady background noise (hiss, hum, air conditioning)
+///
+/// `strength` runs from 0.0 (unchanged) to 1.0 (full reduction); see
+/// [`Denoiser`] for how it works. The output has the input's length and
+/// format.
+///
+/// # Example
+/// ```no_run
+/// use hermeneia_lib::audio::{decode_audio_file, denoise, encode_wav};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let audio = decode_audio_file("room_recording.wav")?;
+/// let cleaned = denoise(&audio, 0.7)?;
+/// encode_wav(&cleaned, "room_recording_clean.wav")?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn denoise(audio: &AudioData, strength: f32) -> Result<AudioData> {
+    audio.validate()?;
+    let mut denoiser = Denoiser::new(audio.sample_rate, audio.channels, strength)?;
+    let mut samples = denoiser.process(&audio.samples);
+    samples.extend(denoiser.flush());
+    Ok(AudioData {
+        samples,
+        sample_rate: audio.sample_rate,
+        channels: audio.channels,
+    })
+}
+
+/// Spectral subtraction denoiser for audio that arrives in chunks
+///
+/// Each channel is cut into half-overlapping windows of about 32 ms. In
+/// every frequency bin the noise level is tracked as the minimum of the
+/// smoothed power over the last 1.5 seconds (minimum statistics), and the
+/// bin is turned down by the share of its power the noise accounts for.
+/// Speech pauses often enough that the minimum finds the room between
+/// words; it stands well above that floor and passes almost untouched,
+/// while the gaps lose their hiss. Sounds that never stop, like mains hum,
+/// count as noise.
+///
+/// Output lags the input by half a window. [`Denoiser::flush`] returns the
+/// rest, so the total output is exactly as long as the input.
+pub struct Denoiser {
+    strength: Real,
+    channels: usize,
+    /// Frames between windows (half the window length)
+    hop: usize,
+    window: Vec<Real>,
+    fft: Arc<dyn Fft<Real>>,
+    ifft: Arc<dyn Fft<Real>>,
+    /// Windows per noise memory sub-window
+    sub_window_len: usize,
+    states: Vec<ChannelState>,
+    /// Interleaved input that doesn't yet fill a hop
+    pending: Vec<f32>,
+    /// Frames still to drop from the front of the output
+    latency: usize,
+    frames_in: u64,
+    frames_out: u64,
+    spectrum: Vec<Complex<Real>>,
+}
+
+/// Analysis state of one channel
+struct ChannelState {
+    /// The most recent window of input
+    input: Vec<Real>,
+    /// Overlap-add accumulator; the first hop is complete
+    output: Vec<Real>,
+    /// Smoothed power of each bin
+    power: Vec<Real>,
+    /// Lowest smoothed power in the current sub-window
+    current_min: Vec<Real>,
+    /// Minimum of each of the last sub-windows, oldest first
+    history: Vec<Vec<Real>>,
+    /// Windows seen in the current sub-window
+    filled: usize,
+}
+
+impl Denoiser {
+    /// A denoiser for interleaved audio at `sample_rate` with `channels`
+    ///
+    /// # Errors
+    /// [`AudioError::InvalidParameter`] if `strength` isn't between 0.0 and
+    /// 1.0 or the format is empty
+    pub fn new(sample_rate: u32, channels: u16, strength: f32) -> Result<Self> {
+        if !(0.0..=1.0).contains(&strength) {
+            return Err(AudioError::InvalidParameter(format!(
+                "Denoise strength must be between 0 and 1 (got {})",
+                strength
+            )));
+        }
+        if sample_rate == 0 || channels == 0 {
+            return Err(AudioError::InvalidParameter(format!(
+                "Cannot denoise {} channel(s) at {} Hz",
+                channels, sample_rate
+            )));
+        }
+
+        let size = ((sample_rate as f64 * WINDOW_SECS).ceil() as usize)
+            .next_power_of_two()
+            .max(64);
+        let hop = size / 2;
+        // Square-root Hann on both analysis and synthesis: the squares of
+        // half-overlapping windows sum to one, so unchanged bins rebuild the
+        // input exactly
+        let window = (0..size)
+            .map(|n| (std::f64::consts::PI * n as f64 / size as f64).sin() as Real)
+            .collect();
+        let hop_secs = hop as f64 / sample_rate as f64;
+        let sub_window_len = (MEMORY_SECS / hop_secs / SUB_WINDOWS as f64).ceil() as usize;
+
+        let mut planner = FftPlanner::<Real>::new();
+        let states = (0..channels)
+            .map(|_| ChannelState {
+                input: vec![0.0; size],
+                output: vec![0.0; size],
+                power: Vec::new(),
+                current_min: vec![Real::INFINITY; size],
+                history: Vec::with_capacity(SUB_WINDOWS),
+                filled: 0,
+            })
+            .collect();
+        Ok(Self {
+            strength: strength as Real,
+            channels: channels as usize,
+            hop,
+            window,
+            fft: planner.plan_fft_forward(size),
+            ifft: planner.plan_fft_inverse(size),
+            sub_window_len,
+            states,
+            pending: Vec::new(),
+            latency: hop,
+            frames_in: 0,
+            frames_out: 0,
+            spectrum: vec![Complex::default(); size],
+        })
+    }
+
+    /// Denoise the next chunk of interleaved samples
+    ///
+    /// Returns what's ready so far, which lags the input by up to a window.
+    pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        self.frames_in += (samples.len() / self.channels) as u64;
+        let mut pending = std::mem::take(&mut self.pending);
+        pending.extend_from_slice(samples);
+
+        let block = self.hop * self.channels;
+        let whole = pending.len() / block * block;
+        let mut output = Vec::with_capacity(whole);
+        for hop in pending[..whole].chunks(block) {
+            self.run_hop(hop, &mut output);
+        }
+        pending.drain(..whole);
+        self.pending = pending;
+        self.trim(output)
+    }
+
+    /// Everything still buffered, once the input has ended
+    pub fn flush(&mut self) -> Vec<f32> {
+        let block = self.hop * self.channels;
+        let mut tail = std::mem::take(&mut self.pending);
+        // Pad out the last hop, then push one more through so the final
+        // window's overlap is complete
+        tail.resize(block * 2, 0.0);
+        let mut output = Vec::with_capacity(tail.len());
+        for hop in tail.chunks(block) {
+            self.run_hop(hop, &mut output);
+        }
+        self.trim(output)
+    }
+
+    /// Take in one hop of interleaved input and give out one hop of output
+    fn run_hop(&mut self, hop: &[f32], output: &mut Vec<f32>) {
+        for channel in 0..self.channels {
+            let state = &mut self.states[channel];
+            state.input.copy_within(self.hop.., 0);
+            let fresh = hop.iter().skip(channel).step_by(self.channels);
+            for (slot, &sample) in state.input[self.hop..].iter_mut().zip(fresh) {
+                *slot = to_real(sample);
+            }
+
+            for ((bin, &sample), &weight) in
+                self.spectrum.iter_mut().zip(&state.input).zip(&self.window)
+            {
+                *bin = Complex::new(sample * weight, 0.0);
+            }
+            self.fft.process(&mut self.spectrum);
+            state.suppress(&mut self.spectrum, self.strength, self.sub_window_len);
+            self.ifft.process(&mut self.spectrum);
+
+            let scale = 1.0 / self.spectrum.len() as Real;
+            for ((out, bin), &weight) in
+                state.output.iter_mut().zip(&self.spectrum).zip(&self.window)
+            {
+                *out += bin.re * scale * weight;
+            }
+        }
+
+        for i in 0..self.hop {
+            output.extend(self.states.iter().map(|state| from_real(state.output[i])));
+        }
+        for state in &mut self.states {
+            state.output.copy_within(self.hop.., 0);
+            let len = state.output.len();
+            state.output[len - self.hop..].fill(0.0);
+        }
+    }
+
+    /// Drop the startup latency and anything past the end of the input
+    fn trim(&mut self, mut output: Vec<f32>) -> Vec<f32> {
+        let frames = output.len() / self.channels;
+        let skip = self.latency.min(frames);
+        output.drain(..skip * self.channels);
+        self.latency -= skip;
+
+        let frames = ((frames - skip) as u64).min(self.frames_in - self.frames_out);
+        output.truncate(frames as usize * self.channels);
+        self.frames_out += frames;
+        output
+    }
+}
+
+impl ChannelState {
+    /// Update the noise estimate from one window's spectrum and turn each
+    /// bin down by its share of noise
+    fn suppress(&mut self, spectrum: &mut [Complex<Real>], strength: Real, sub_window_len: usize) {
+        if self.power.is_empty() {
+            self.power = spectrum.iter().map(|bin| bin.norm_sqr()).collect();
+        }
+        for (i, bin) in spectrum.iter_mut().enumerate() {
+            let power = bin.norm_sqr();
+            self.power[i] = POWER_SMOOTHING * self.power[i] + (1.0 - POWER_SMOOTHING) * power;
+            self.current_min[i] = self.current_min[i].min(self.power[i]);
+            let noise = self
+                .history
+                .iter()
+                .fold(self.current_min[i], |noise, min| noise.min(min[i]));
+
+            let gain = if power > 0.0 {
+                (1.0 - OVER_SUBTRACTION * noise / power).max(GAIN_FLOOR)
+            } else {
+                GAIN_FLOOR
+            };
+            *bin *= 1.0 - strength * (1.0 - gain);
+        }
+
+        self.filled += 1;
+        if self.filled == sub_window_len {
+            if self.history.len() == SUB_WINDOWS {
+                self.history.remove(0);
+            }
+            self.history.push(self.current_min.clone());
+            self.current_min.fill(Real::INFINITY);
+            self.filled = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bursts of a 300 Hz tone with pauses, like phrases of speech, and the
+    /// same over white noise
+    fn noisy_tone(seconds: usize) -> (Vec<f32>, Vec<f32>) {
+        let mut seed = 7u32;
+        let clean: Vec<f32> = (0..16000 * seconds)
+            .map(|i| {
+                let on = (i / 6400) % 2 == 0;
+                let tone = (i as f32 / 16000.0 * 300.0 * std::f32::consts::TAU).sin() * 0.3;
+                if on { tone } else { 0.0 }
+            })
+            .collect();
+        let noisy = clean
+            .iter()
+            .map(|&s| {
+                seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                s + ((seed >> 8) as f32 / 16_777_216.0 - 0.5) * 0.1
+            })
+            .collect();
+        (clean, noisy)
+    }
+
+    fn error(a: &[f32], b: &[f32]) -> f64 {
+        a.iter().zip(b).map(|(x, y)| ((x - y) as f64).powi(2)).sum()
+    }
+
+    #[test]
+    fn test_denoise_reduces_noise() {
+        let (clean, noisy) = noisy_tone(4);
+        let audio = AudioData {
+            samples: noisy.clone(),
+            sample_rate: 16000,
+            channels: 1,
+        };
+        let denoised = denoise(&audio, 1.0).unwrap();
+        assert_eq!(denoised.samples.len(), noisy.len());
+
+        // Skip the first two seconds, while the noise estimate settles
+        let before = error(&noisy[32000..], &clean[32000..]);
+        let after = error(&denoised.samples[32000..], &clean[32000..]);
+        assert!(after < before * 0.5, "{} vs {}", after, before);
+    }
+
+    #[test]
+    fn test_zero_strength_leaves_audio_unchanged() {
+        let (_, noisy) = noisy_tone(1);
+        let audio = AudioData {
+            samples: noisy,
+            sample_rate: 16000,
+            channels: 1,
+        };
+        let denoised = denoise(&audio, 0.0).unwrap();
+        assert_eq!(denoised.samples.len(), audio.samples.len());
+        for (a, b) in denoised.samples.iter().zip(&audio.samples) {
+            assert!((a - b).abs() < 1e-5, "{} vs {}", a, b);
+        }
+
+        assert!(denoise(&audio, 1.5).is_err());
+        assert!(denoise(&audio, f32::NAN).is_err());
+    }
+
+    #[test]
+    fn test_chunked_matches_whole() {
+        let (_, noisy) = noisy_tone(1);
+        let stereo: Vec<f32> = noisy.iter().flat_map(|&s| [s, -s * 0.5]).collect();
+        let audio = AudioData {
+            samples: stereo.clone(),
+            sample_rate: 16000,
+            channels: 2,
+        };
+        let whole = denoise(&audio, 0.8).unwrap();
+
+        let mut denoiser = Denoiser::new(16000, 2, 0.8).unwrap();
+        let mut chunked = Vec::new();
+        for chunk in stereo.chunks(2 * 333) {
+            chunked.extend(denoiser.process(chunk));
+        }
+        chunked.extend(denoiser.flush());
+        assert_eq!(chunked, whole.samples);
+    }
+}
@@ -0,0 +1,113 @@
+// src-tauri/src/audio/dsp/gain.rs
+
+use crate::audio::dsp::{from_real, to_real, Real};
+use crate::audio::types::AudioData;
+use crate::error::{AudioError, Result};
+
+/// Convert a level in decibels to a linear amplitude factor
+pub fn db_to_linear(db: f64) -> f64 {
+    10f64.powf(db / 20.0)
+}
+
+/// Convert a linear amplitude factor to decibels
+///
+/// Zero maps to negative infinity.
+pub fn linear_to_db(linear: f64) -> f64 {
+    20.0 * linear.log10()
+}
+
+/// Apply a constant gain to every sample
+///
+/// Samples are not clamped, so a boost that pushes peaks past full scale
+/// survives intact in float output; integer encoders clip on the way out.
+/// Callers that care about headroom should check the peak first.
+///
+/// # Arguments
+/// * `audio` - The audio to scale
+/// * `gain_db` - Gain in decibels (negative to attenuate)
+///
+/// # Returns
+/// New AudioData with the gain applied
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::{AudioData, apply_gain};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let audio = AudioData {
+///     samples: vec![0.5, -0.5],
+///     sample_rate: 44100,
+///     channels: 1,
+/// };
+///
+/// let quieter = apply_gain(&audio, -6.0206)?;
+/// assert!((quieter.samples[0] - 0.25).abs() < 1e-4);
+/// # Ok(())
+/// # }
+/// ```
+pub fn apply_gain(audio: &AudioData, gain_db: f64) -> Result<AudioData> {
+    if !gain_db.is_finite() {
+        return Err(AudioError::InvalidParameter(format!(
+            "Gain must be a finite number of dB (got {})",
+            gain_db
+        )));
+    }
+
+    let factor = db_to_linear(gain_db) as Real;
+
+    Ok(AudioData {
+        samples: audio
+            .samples
+            .iter()
+            .map(|&s| from_real(to_real(s) * factor))
+            .collect(),
+        sample_rate: audio.sample_rate,
+        channels: audio.channels,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_db_round_trip() {
+        for db in [-60.0, -6.0, 0.0, 3.5, 12.0] {
+            assert!((linear_to_db(db_to_linear(db)) - db).abs() < 1e-9);
+        }
+        assert!((db_to_linear(-6.0206) - 0.5).abs() < 1e-4);
+        assert_eq!(linear_to_db(0.0), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_zero_gain_is_identity() {
+        let audio = AudioData {
+            samples: vec![0.1, -0.2, 0.3],
+            sample_rate: 48000,
+            channels: 1,
+        };
+        assert_eq!(apply_gain(&audio, 0.0).unwrap().samples, audio.samples);
+    }
+
+    #[test]
+    fn test_boost_is_not_clamped() {
+        let audio = AudioData {
+            samples: vec![0.8],
+            sample_rate: 48000,
+            channels: 1,
+        };
+        let boosted = apply_gain(&audio, 6.0206).unwrap();
+        assert!((boosted.samples[0] - 1.6).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_non_finite_gain_rejected() {
+        let audio = AudioData {
+            samples: vec![0.1],
+            sample_rate: 48000,
+            channels: 1,
+        };
+        assert!(apply_gain(&audio, f64::NAN).is_err());
+        assert!(apply_gain(&audio, f64::INFINITY).is_err());
+    }
+}
@@ -0,0 +1,157 @@
+// src-tauri/src/audio/dsp/limiter.rs
+
+use std::collections::VecDeque;
+
+use crate::audio::dsp::true_peak::frame_peaks;
+use crate::audio::dsp::{db_to_linear, from_real, to_real, Real};
+use crate::audio::types::AudioData;
+use crate::error::{AudioError, Result};
+
+/// How far ahead of a peak the gain starts coming down
+const LOOKAHEAD_SECONDS: f64 = 0.005;
+
+/// Time constant of the gain recovering after a peak
+const RELEASE_SECONDS: f64 = 0.1;
+
+/// Hold true peaks at or below `ceiling_dbtp`
+///
+/// A look-ahead limiter: the gain ramps down over the 5 ms before a peak
+/// that would pass the ceiling (measured on the reconstructed signal, see
+/// [`super::true_peak`]) and recovers over about 100 ms afterwards, so
+/// there are no clicks and no clipping. Audio that already fits is
+/// returned unchanged.
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::{limit_true_peak, true_peak, AudioData};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let samples = (0..48000).map(|i| 1.5 * (i as f32 * 0.05).sin()).collect();
+/// let audio = AudioData { samples, sample_rate: 48000, channels: 1 };
+///
+/// let limited = limit_true_peak(&audio, -1.0)?;
+/// assert!(true_peak(&limited) <= 0.9);
+/// # Ok(())
+/// # }
+/// ```
+pub fn limit_true_peak(audio: &AudioData, ceiling_dbtp: f64) -> Result<AudioData> {
+    if !ceiling_dbtp.is_finite() {
+        return Err(AudioError::InvalidParameter(format!(
+            "Peak ceiling must be a finite number of dBTP (got {})",
+            ceiling_dbtp
+        )));
+    }
+    let ceiling = db_to_linear(ceiling_dbtp);
+    let needed: Vec<f64> = frame_peaks(audio, ceiling as f32)
+        .into_iter()
+        .map(|peak| (ceiling / peak as f64).min(1.0))
+        .collect();
+    if needed.iter().all(|&gain| gain >= 1.0) {
+        return Ok(audio.clone());
+    }
+
+    let gains = gain_envelope(&needed, audio.sample_rate);
+    let channels = audio.channels.max(1) as usize;
+    Ok(AudioData {
+        samples: audio
+            .samples
+            .chunks_exact(channels)
+            .zip(&gains)
+            .flat_map(|(frame, &gain)| {
+                frame.iter().map(move |&s| from_real(to_real(s) * gain as Real))
+            })
+            .collect(),
+        sample_rate: audio.sample_rate,
+        channels: audio.channels,
+    })
+}
+
+/// Smooth per-frame gain that never exceeds `needed` at any frame
+///
+/// Each frame takes the lowest gain needed within the look-ahead window;
+/// averaging that over the same window turns the steps into ramps that
+/// still reach each dip in time. Recovery is then slowed to the release.
+fn gain_envelope(needed: &[f64], sample_rate: u32) -> Vec<f64> {
+    let window = ((LOOKAHEAD_SECONDS * sample_rate as f64).round() as usize).max(1);
+
+    // Lowest gain in needed[n..n + window], via a queue of candidates
+    let mut lowest = vec![1.0; needed.len()];
+    let mut queue: VecDeque<usize> = VecDeque::new();
+    for i in (0..needed.len()).rev() {
+        while queue.back().is_some_and(|&j| needed[j] >= needed[i]) {
+            queue.pop_back();
+        }
+        queue.push_back(i);
+        while queue.front().is_some_and(|&j| j >= i + window) {
+            queue.pop_front();
+        }
+        lowest[i] = needed[queue[0]];
+    }
+
+    // Average over the previous `window` frames; before the start, the
+    // first frame's value stands in
+    let mut sum = lowest[0] * window as f64;
+    let mut smoothed = Vec::with_capacity(needed.len());
+    smoothed.push(lowest[0]);
+    for i in 1..lowest.len() {
+        sum += lowest[i] - lowest[i.saturating_sub(window)];
+        smoothed.push(sum / window as f64);
+    }
+
+    let release = 1.0 - (-1.0 / (RELEASE_SECONDS * sample_rate as f64)).exp();
+    let mut gain = 1.0f64;
+    for value in &mut smoothed {
+        gain = value.min(gain + (1.0 - gain) * release);
+        *value = gain;
+    }
+    smoothed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::dsp::true_peak::true_peak;
+
+    #[test]
+    fn test_envelope_reaches_each_dip_in_time() {
+        let mut needed = vec![1.0; 2000];
+        needed[100] = 0.5;
+        needed[1000] = 0.8;
+        // 40 frames of look-ahead at 8 kHz
+        let gains = gain_envelope(&needed, 8000);
+        for (gain, need) in gains.iter().zip(&needed) {
+            assert!(gain <= need);
+        }
+        // Ramps down ahead of the peak rather than jumping
+        assert_eq!(gains[50], 1.0);
+        assert!(gains[80] < 1.0 && gains[80] > 0.5);
+        // And recovers afterwards
+        assert!(gains[900] > gains[200]);
+    }
+
+    #[test]
+    fn test_limits_only_the_loud_part() {
+        let samples: Vec<f32> = (0..96000)
+            .map(|i| {
+                let level = if (40000..44000).contains(&i) { 2.0 } else { 0.3 };
+                level * (i as f32 * 0.03).sin()
+            })
+            .collect();
+        let audio = AudioData {
+            samples,
+            sample_rate: 48000,
+            channels: 1,
+        };
+        let limited = limit_true_peak(&audio, -1.0).unwrap();
+        let peak_db = 20.0 * (true_peak(&limited) as f64).log10();
+        assert!(peak_db <= -0.95, "true peak {peak_db} dBTP");
+        // Well away from the burst, nothing changes
+        assert_eq!(limited.samples[..30000], audio.samples[..30000]);
+        for (after, before) in limited.samples[80000..].iter().zip(&audio.samples[80000..]) {
+            assert!((after - before).abs() < 1e-3);
+        }
+
+        let quiet = limit_true_peak(&limited, 0.0).unwrap();
+        assert_eq!(quiet.samples, limited.samples);
+    }
+}
@@ -0,0 +1,54 @@
+// src-tauri/src/audio/dsp/mod.rs
+// Processing that changes the samples themselves
+
+pub mod denoise;
+pub mod gain;
+pub mod limiter;
+pub mod normalize;
+pub mod true_peak;
+
+/// Precision of the arithmetic between decoding and encoding
+///
+/// `f32` by default; the `f64-dsp` feature switches gain, mixing and
+/// resampling to `f64` so repeated processing passes don't accumulate
+/// rounding error. Samples are still stored and encoded as `f32`.
+#[cfg(not(feature = "f64-dsp"))]
+pub type Real = f32;
+#[cfg(feature = "f64-dsp")]
+pub type Real = f64;
+
+/// Widen a stored sample to the processing precision
+#[inline]
+#[allow(clippy::useless_conversion)]
+pub fn to_real(sample: f32) -> Real {
+    sample.into()
+}
+
+/// Narrow a processed value back to a stored sample
+#[inline]
+#[allow(clippy::unnecessary_cast)]
+pub fn from_real(value: Real) -> f32 {
+    value as f32
+}
+
+// Re-export commonly used items
+pub use denoise::{denoise, Denoiser};
+pub use gain::{apply_gain, db_to_linear, linear_to_db};
+pub use limiter::limit_true_peak;
+pub use normalize::{
+    normalize_loudness, normalize_loudness_with_progress, LoudnessStandard, LoudnessTarget,
+    NormalizeReport,
+};
+pub use true_peak::true_peak;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stored_samples_survive_the_round_trip() {
+        for sample in [0.0, 1.0, -1.0, 0.1, f32::MIN_POSITIVE, 0.999_999_9] {
+            assert_eq!(from_real(to_real(sample)), sample);
+        }
+    }
+}
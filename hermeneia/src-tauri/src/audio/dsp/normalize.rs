@@ -0,0 +1,249 @@
+// src-tauri/src/audio/dsp/normalize.rs
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::analysis::loudness::{measure_loudness_with_progress, LoudnessMeasurement};
+use crate::audio::dsp::{apply_gain, limit_true_peak};
+use crate::audio::types::AudioData;
+use crate::error::{AnalysisError, AudioError, Result};
+use crate::progress::{NoProgress, ProgressSink};
+
+/// Most rounds of limiting and re-measuring
+const MAX_PASSES: usize = 4;
+
+/// How close to the target the output must land, in LU
+const TOLERANCE_LU: f64 = 0.1;
+
+/// Loudness to normalize to, and the highest peak allowed on the way
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LoudnessTarget {
+    /// Integrated loudness in LUFS
+    pub integrated_lufs: f64,
+    /// Highest true peak allowed, in dBTP
+    pub true_peak_dbtp: f64,
+    /// Limit peaks that would pass the ceiling; without the limiter the
+    /// gain is lowered instead, which can leave the audio under target
+    #[serde(default = "limiter_default")]
+    pub limiter: bool,
+}
+
+fn limiter_default() -> bool {
+    true
+}
+
+impl LoudnessTarget {
+    pub fn new(integrated_lufs: f64, true_peak_dbtp: f64) -> Self {
+        Self {
+            integrated_lufs,
+            true_peak_dbtp,
+            limiter: true,
+        }
+    }
+
+    /// Lower the gain rather than limit peaks
+    pub fn without_limiter(mut self) -> Self {
+        self.limiter = false;
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !(self.integrated_lufs.is_finite() && self.integrated_lufs < 0.0) {
+            return Err(AudioError::InvalidParameter(format!(
+                "Loudness target must be below 0 LUFS (got {})",
+                self.integrated_lufs
+            )));
+        }
+        if !(self.true_peak_dbtp.is_finite() && self.true_peak_dbtp <= 0.0) {
+            return Err(AudioError::InvalidParameter(format!(
+                "True peak ceiling must be at most 0 dBTP (got {})",
+                self.true_peak_dbtp
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Published loudness targets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum LoudnessStandard {
+    /// -16 LUFS, -1 dBTP: podcasts and spoken word (Apple, AES TD1004)
+    Podcast,
+    /// -14 LUFS, -1 dBTP: music streaming services
+    Streaming,
+    /// -23 LUFS, -1 dBTP: European broadcast (EBU R128)
+    EbuR128,
+    /// -24 LUFS, -2 dBTP: US broadcast (ATSC A/85)
+    AtscA85,
+}
+
+impl LoudnessStandard {
+    pub fn target(self) -> LoudnessTarget {
+        match self {
+            Self::Podcast => LoudnessTarget::new(-16.0, -1.0),
+            Self::Streaming => LoudnessTarget::new(-14.0, -1.0),
+            Self::EbuR128 => LoudnessTarget::new(-23.0, -1.0),
+            Self::AtscA85 => LoudnessTarget::new(-24.0, -2.0),
+        }
+    }
+}
+
+/// What [`normalize_loudness`] found and did
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NormalizeReport {
+    /// The input's levels
+    pub measured: LoudnessMeasurement,
+    /// Gain applied to the whole file, in dB
+    pub gain_db: f64,
+    /// Most the limiter took off any peak, in dB (0 when it didn't act)
+    pub limited_db: f64,
+    /// The output's levels
+    pub output: LoudnessMeasurement,
+}
+
+/// Bring audio to a loudness target
+///
+/// Measures integrated loudness (see
+/// [`measure_loudness`](crate::audio::measure_loudness)), applies the gain
+/// that reaches the target, and then keeps true peaks under the ceiling:
+/// with a limiter by default, otherwise by lowering the gain.
+///
+/// Limiting loud moments lowers the loudness too, a lot when they carry
+/// much of the energy, so limited output is measured again and the gain
+/// raised until it lands within 0.1 LU of the target (a few passes at
+/// most).
+///
+/// # Errors
+/// [`AnalysisError::Unmeasurable`] for silence or audio under 400 ms
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::{normalize_loudness, AudioData, LoudnessStandard};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let samples = (0..96000).map(|i| 0.05 * (i as f32 * 0.06).sin()).collect();
+/// let audio = AudioData { samples, sample_rate: 48000, channels: 1 };
+///
+/// let (normalized, report) = normalize_loudness(&audio, &LoudnessStandard::Podcast.target())?;
+/// assert!((report.output.integrated_lufs.unwrap() + 16.0).abs() < 0.1);
+/// # let _ = normalized;
+/// # Ok(())
+/// # }
+/// ```
+pub fn normalize_loudness(
+    audio: &AudioData,
+    target: &LoudnessTarget,
+) -> Result<(AudioData, NormalizeReport)> {
+    normalize_loudness_with_progress(audio, target, &mut NoProgress)
+}
+
+/// Normalize like [`normalize_loudness`], reporting the "analyze" stage
+/// for the measurements before and after
+pub fn normalize_loudness_with_progress(
+    audio: &AudioData,
+    target: &LoudnessTarget,
+    progress: &mut dyn ProgressSink,
+) -> Result<(AudioData, NormalizeReport)> {
+    target.validate()?;
+    let measured = measure_loudness_with_progress(audio, progress)?;
+    let Some(lufs) = measured.integrated_lufs else {
+        return Err(AnalysisError::Unmeasurable.into());
+    };
+
+    let mut gain_db = target.integrated_lufs - lufs;
+    let overshoot = |gain_db: f64| measured.true_peak_dbtp + gain_db - target.true_peak_dbtp;
+    if !target.limiter && overshoot(gain_db) > 0.0 {
+        gain_db -= overshoot(gain_db);
+    }
+
+    let mut pass = 1;
+    let (normalized, output) = loop {
+        let gained = apply_gain(audio, gain_db)?;
+        if overshoot(gain_db) <= 0.0 {
+            let output = measure_loudness_with_progress(&gained, progress)?;
+            break (gained, output);
+        }
+        let limited = limit_true_peak(&gained, target.true_peak_dbtp)?;
+        let output = measure_loudness_with_progress(&limited, progress)?;
+        let shortfall = output
+            .integrated_lufs
+            .map_or(0.0, |reached| target.integrated_lufs - reached);
+        if shortfall.abs() <= TOLERANCE_LU || pass == MAX_PASSES {
+            break (limited, output);
+        }
+        gain_db += shortfall;
+        pass += 1;
+    };
+    let limited_db = overshoot(gain_db).max(0.0);
+
+    Ok((
+        normalized,
+        NormalizeReport {
+            measured,
+            gain_db,
+            limited_db,
+            output,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A steady tone with one loud burst in the middle
+    fn speech_like(level: f32, burst: f32) -> AudioData {
+        let samples = (0..48000 * 6)
+            .map(|i| {
+                let level = if (140_000..141_000).contains(&i) { burst } else { level };
+                level * (i as f32 * 0.07).sin()
+            })
+            .collect();
+        AudioData {
+            samples,
+            sample_rate: 48000,
+            channels: 1,
+        }
+    }
+
+    #[test]
+    fn test_reaches_target_with_peaks_limited() {
+        let audio = speech_like(0.02, 0.5);
+        let target = LoudnessStandard::Podcast.target();
+        let (_, report) = normalize_loudness(&audio, &target).unwrap();
+
+        let reached = report.output.integrated_lufs.unwrap();
+        assert!((reached + 16.0).abs() < 0.2, "reached {reached} LUFS");
+        assert!(report.limited_db > 0.0);
+        assert!(report.output.true_peak_dbtp <= -0.95, "{:?}", report.output);
+    }
+
+    #[test]
+    fn test_without_limiter_gain_gives_way_to_the_ceiling() {
+        let audio = speech_like(0.02, 0.5);
+        let target = LoudnessStandard::Podcast.target().without_limiter();
+        let (_, report) = normalize_loudness(&audio, &target).unwrap();
+
+        assert_eq!(report.limited_db, 0.0);
+        assert!((report.output.true_peak_dbtp + 1.0).abs() < 0.05);
+        assert!(report.output.integrated_lufs.unwrap() < -17.0);
+    }
+
+    #[test]
+    fn test_silence_and_bad_targets_are_rejected() {
+        let silence = AudioData {
+            samples: vec![0.0; 48000],
+            sample_rate: 48000,
+            channels: 1,
+        };
+        let result = normalize_loudness(&silence, &LoudnessStandard::EbuR128.target());
+        assert!(matches!(
+            result,
+            Err(AudioError::Analysis(AnalysisError::Unmeasurable))
+        ));
+
+        let audio = speech_like(0.1, 0.1);
+        assert!(normalize_loudness(&audio, &LoudnessTarget::new(3.0, -1.0)).is_err());
+        assert!(normalize_loudness(&audio, &LoudnessTarget::new(-16.0, 1.0)).is_err());
+    }
+}
@@ -0,0 +1,132 @@
+// src-tauri/src/audio/dsp/true_peak.rs
+// Inter-sample peaks, as a DAC or lossy encoder would reconstruct them
+
+use crate::audio::types::AudioData;
+
+/// Samples on each side of the point being reconstructed
+const HALF_TAPS: usize = 6;
+
+/// Oversampling BS.1770-4 (Annex 2) asks for at this sample rate
+fn oversampling(sample_rate: u32) -> usize {
+    match sample_rate {
+        0..96_000 => 4,
+        96_000..192_000 => 2,
+        _ => 1,
+    }
+}
+
+/// Windowed-sinc weights for the points between two samples
+///
+/// `weights[p][k]` is the weight of sample `n - HALF_TAPS + k` for the point
+/// `(p + 1) / factor` of the way from sample `n - 1` to sample `n`.
+fn interpolation_weights(factor: usize) -> Vec<[f64; 2 * HALF_TAPS]> {
+    (1..factor)
+        .map(|p| {
+            let t = p as f64 / factor as f64 - 1.0;
+            let mut weights = [0.0; 2 * HALF_TAPS];
+            for (k, weight) in weights.iter_mut().enumerate() {
+                let d = t - (k as f64 - HALF_TAPS as f64);
+                let sinc = if d == 0.0 {
+                    1.0
+                } else {
+                    (std::f64::consts::PI * d).sin() / (std::f64::consts::PI * d)
+                };
+                let hann = 0.5 * (1.0 + (std::f64::consts::PI * d / HALF_TAPS as f64).cos());
+                *weight = sinc * hann;
+            }
+            // Unity gain at DC
+            let sum: f64 = weights.iter().sum();
+            weights.iter_mut().for_each(|w| *w /= sum);
+            weights
+        })
+        .collect()
+}
+
+/// Peak of each frame: the largest absolute value across channels of the
+/// frame itself and of the reconstructed signal since the previous frame
+///
+/// Reconstructing is only worth it near loud samples, so frames whose
+/// neighbourhood can't reach `floor` keep their sample peak. Pass `0.0` to
+/// reconstruct everywhere.
+pub fn frame_peaks(audio: &AudioData, floor: f32) -> Vec<f32> {
+    let channels = audio.channels.max(1) as usize;
+    let frames = audio.frame_count();
+    let peaks: Vec<f32> = audio
+        .samples
+        .chunks_exact(channels)
+        .map(|frame| frame.iter().fold(0.0f32, |peak, s| peak.max(s.abs())))
+        .collect();
+
+    let factor = oversampling(audio.sample_rate);
+    if factor == 1 || frames == 0 {
+        return peaks;
+    }
+    let weights = interpolation_weights(factor);
+    // The most a reconstructed point can exceed its loudest neighbour by
+    let overshoot = weights
+        .iter()
+        .map(|w| w.iter().map(|x| x.abs()).sum::<f64>())
+        .fold(1.0, f64::max);
+
+    let sample = |frame: usize, ch: usize| audio.samples[frame * channels + ch] as f64;
+    let reach = |n: usize| (n.saturating_sub(HALF_TAPS), (n + HALF_TAPS).min(frames));
+    let mut interpolated = Vec::with_capacity(frames);
+    for n in 0..frames {
+        let (first, end) = reach(n);
+        let loudest = peaks[first..end].iter().fold(0.0f32, |a, &b| a.max(b));
+        if (loudest as f64 * overshoot) < floor as f64 {
+            interpolated.push(peaks[n]);
+            continue;
+        }
+        let mut peak = peaks[n];
+        for ch in 0..channels {
+            for phase in &weights {
+                let value: f64 = (first..end)
+                    .map(|m| phase[m + HALF_TAPS - n] * sample(m, ch))
+                    .sum();
+                peak = peak.max(value.abs() as f32);
+            }
+        }
+        interpolated.push(peak);
+    }
+    interpolated
+}
+
+/// Largest absolute value of the reconstructed signal (linear, 1.0 = full
+/// scale), never below the sample peak
+pub fn true_peak(audio: &AudioData) -> f32 {
+    let sample_peak = audio.samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    frame_peaks(audio, sample_peak)
+        .into_iter()
+        .fold(sample_peak, f32::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finds_peaks_between_samples() {
+        // A quarter-rate sine sampled 45 degrees off its crests never
+        // lands on one: sample peak is 0.707 of the true peak
+        let samples: Vec<f32> = (0..4800)
+            .map(|i| (std::f32::consts::FRAC_PI_2 * i as f32 + std::f32::consts::FRAC_PI_4).sin())
+            .collect();
+        let sample_peak = samples.iter().fold(0.0f32, |p, s| p.max(s.abs()));
+        let audio = AudioData {
+            samples,
+            sample_rate: 48000,
+            channels: 1,
+        };
+        assert!((sample_peak - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-3);
+        let peak = true_peak(&audio);
+        assert!((peak - 1.0).abs() < 0.02, "got {peak}");
+
+        // High sample rates aren't oversampled
+        let high = AudioData {
+            sample_rate: 192_000,
+            ..audio
+        };
+        assert_eq!(true_peak(&high), sample_peak);
+    }
+}
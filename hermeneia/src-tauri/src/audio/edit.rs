@@ -0,0 +1,518 @@
+// src-tauri/src/audio/edit.rs
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use symphonia::core::formats::{SeekMode, SeekTo};
+use symphonia::core::units::Time;
+
+use crate::audio::decoder::{convert_audio_buffer_to_f32, open_audio_track, AudioTrack};
+use crate::audio::dsp::db_to_linear;
+use crate::audio::encoder::OutputFormat;
+use crate::audio::pipeline::{
+    check_not_input, EncodeSink, Pipeline, PipelineSummary, Source, StreamSpec,
+};
+use crate::audio::time::{AudioDuration, Timestamp};
+use crate::audio::types::{AudioData, FadeCurve};
+use crate::error::{AudioError, DecodeError, Result};
+use crate::progress::ProgressSink;
+
+/// Which way an [`EditOperation::Fade`] goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FadeDirection {
+    /// From silence at the start of the range to full level at its end
+    In,
+    /// From full level to silence at the end of the range
+    Out,
+}
+
+/// One edit, on a range of the source file's own timeline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum EditOperation {
+    /// Play this range next; with no keeps at all the whole file plays
+    Keep { start: Timestamp, end: Timestamp },
+    /// Leave this range out wherever it would play
+    Cut { start: Timestamp, end: Timestamp },
+    /// Raise or lower this range
+    Gain {
+        start: Timestamp,
+        end: Timestamp,
+        gain_db: f64,
+    },
+    Fade {
+        start: Timestamp,
+        end: Timestamp,
+        direction: FadeDirection,
+        #[serde(default)]
+        curve: FadeCurve,
+    },
+}
+
+impl EditOperation {
+    /// The source range the edit applies to
+    pub fn range(&self) -> Range<Timestamp> {
+        match *self {
+            EditOperation::Keep { start, end }
+            | EditOperation::Cut { start, end }
+            | EditOperation::Gain { start, end, .. }
+            | EditOperation::Fade { start, end, .. } => start..end,
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        let range = self.range();
+        if range.end <= range.start {
+            return Err(AudioError::InvalidParameter(format!(
+                "Edit range {} to {} is empty",
+                range.start, range.end
+            )));
+        }
+        if let EditOperation::Gain { gain_db, .. } = *self {
+            if !gain_db.is_finite() {
+                return Err(AudioError::InvalidParameter(format!(
+                    "Gain must be a finite number of dB (got {})",
+                    gain_db
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A non-destructive edit of one source file
+///
+/// The source is never touched; [`render_edit_list`] plays the kept ranges
+/// in the order they were kept, minus every cut, with the gains and fades
+/// of the ranges they cover multiplied together. Since edits combine
+/// rather than override each other, taking back the last one with
+/// [`EditList::undo`] always gives the list as it was before it.
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::edit::{EditList, EditOperation};
+/// use hermeneia_lib::audio::Timestamp;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let secs = Timestamp::from_seconds;
+/// let mut edits = EditList::new();
+/// edits.push(EditOperation::Keep { start: secs(60.0), end: secs(120.0) });
+/// edits.push(EditOperation::Cut { start: secs(90.0), end: secs(95.0) });
+///
+/// let pieces = edits.plan(secs(600.0))?;
+/// assert_eq!(pieces, [secs(60.0)..secs(90.0), secs(95.0)..secs(120.0)]);
+///
+/// let json = serde_json::to_string(&edits)?;
+/// assert_eq!(serde_json::from_str::<EditList>(&json)?, edits);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EditList {
+    /// In the order they were made
+    pub operations: Vec<EditOperation>,
+}
+
+impl EditList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, operation: EditOperation) {
+        self.operations.push(operation);
+    }
+
+    /// Take back the last edit
+    pub fn undo(&mut self) -> Option<EditOperation> {
+        self.operations.pop()
+    }
+
+    /// The source ranges a render plays, in order, for a source `duration`
+    /// long
+    ///
+    /// Keeps past the end of the source are cut short, and ranges the cuts
+    /// leave empty are dropped.
+    ///
+    /// # Errors
+    /// [`AudioError::InvalidParameter`] for an edit whose range is empty or
+    /// a gain that isn't a number
+    pub fn plan(&self, duration: Timestamp) -> Result<Vec<Range<Timestamp>>> {
+        for operation in &self.operations {
+            operation.validate()?;
+        }
+        let mut pieces: Vec<Range<Timestamp>> = self
+            .operations
+            .iter()
+            .filter(|operation| matches!(operation, EditOperation::Keep { .. }))
+            .map(|keep| keep.range())
+            .collect();
+        if pieces.is_empty() {
+            pieces.push(Timestamp::default()..duration);
+        }
+        for piece in &mut pieces {
+            piece.end = piece.end.min(duration);
+        }
+
+        for operation in &self.operations {
+            let EditOperation::Cut { start, end } = *operation else {
+                continue;
+            };
+            pieces = pieces
+                .into_iter()
+                .flat_map(|piece| {
+                    if end <= piece.start || start >= piece.end {
+                        return vec![piece];
+                    }
+                    vec![piece.start..start, end..piece.end]
+                })
+                .collect();
+        }
+        pieces.retain(|piece| piece.end > piece.start);
+        Ok(pieces)
+    }
+
+    /// Length of a render of a source `duration` long
+    pub fn rendered_duration(&self, duration: Timestamp) -> Result<AudioDuration> {
+        let pieces = self.plan(duration)?;
+        let length = |piece: &Range<Timestamp>| piece.end - piece.start;
+        Ok(pieces.iter().fold(AudioDuration::ZERO, |total, piece| total + length(piece)))
+    }
+}
+
+/// Level changes of an edit list, in source frames
+#[derive(Debug, Clone)]
+enum Envelope {
+    Gain(Range<u64>, f32),
+    Fade(Range<u64>, FadeDirection, FadeCurve),
+}
+
+impl Envelope {
+    fn of(operation: &EditOperation, sample_rate: u32) -> Option<Self> {
+        let range = operation.range();
+        let frames = range.start.to_frames(sample_rate)..range.end.to_frames(sample_rate);
+        match *operation {
+            EditOperation::Gain { gain_db, .. } => {
+                Some(Envelope::Gain(frames, db_to_linear(gain_db) as f32))
+            }
+            EditOperation::Fade {
+                direction, curve, ..
+            } => Some(Envelope::Fade(frames, direction, curve)),
+            EditOperation::Keep { .. } | EditOperation::Cut { .. } => None,
+        }
+    }
+
+    /// Gain at source frame `frame`; 1 outside the range
+    fn gain(&self, frame: u64) -> f32 {
+        match self {
+            Envelope::Gain(range, gain) if range.contains(&frame) => *gain,
+            Envelope::Fade(range, direction, curve) if range.contains(&frame) => {
+                let length = (range.end - range.start) as f64;
+                // The first frame of a fade in and the last of a fade out are silent
+                let position = match direction {
+                    FadeDirection::In => frame - range.start,
+                    FadeDirection::Out => range.end - frame - 1,
+                };
+                curve.gain(position as f64 / length)
+            }
+            _ => 1.0,
+        }
+    }
+}
+
+/// Plays a file's pieces in edit-list order, seeking to each one, with the
+/// list's gains and fades applied
+pub struct EditSource {
+    path: PathBuf,
+    track: AudioTrack,
+    /// Source frame ranges still to play, in order
+    pieces: Vec<Range<u64>>,
+    /// Index into `pieces`; the track is positioned for it when `seeked`
+    current: usize,
+    seeked: bool,
+    envelopes: Vec<Envelope>,
+    total_frames: u64,
+}
+
+impl EditSource {
+    /// Open `path` and plan `edits` against its length
+    ///
+    /// Files that don't say how long they are are read through once first.
+    pub fn open<P: AsRef<Path>>(path: P, edits: &EditList) -> Result<Self> {
+        let path = path.as_ref();
+        let mut track = open_audio_track(path)?;
+        let frames = match track.n_frames {
+            Some(frames) => frames,
+            None => {
+                let frames = track.count_frames(|| Ok(()))?;
+                track = open_audio_track(path)?;
+                frames
+            }
+        };
+        let rate = track.sample_rate;
+        let pieces: Vec<Range<u64>> = edits
+            .plan(Timestamp::from_frames(frames, rate))?
+            .into_iter()
+            .map(|piece| piece.start.to_frames(rate)..piece.end.to_frames(rate).min(frames))
+            .filter(|piece| piece.end > piece.start)
+            .collect();
+        Ok(Self {
+            path: path.to_path_buf(),
+            total_frames: pieces.iter().map(|piece| piece.end - piece.start).sum(),
+            envelopes: edits
+                .operations
+                .iter()
+                .filter_map(|operation| Envelope::of(operation, rate))
+                .collect(),
+            track,
+            pieces,
+            current: 0,
+            seeked: false,
+        })
+    }
+
+    /// Put the track at or before the start of the current piece
+    fn seek(&mut self, start: u64) -> Result<()> {
+        let time = Time::from(start as f64 / self.track.sample_rate as f64);
+        let seek = self.track.format.seek(
+            SeekMode::Accurate,
+            SeekTo::Time {
+                time,
+                track_id: Some(self.track.track_id),
+            },
+        );
+        match seek {
+            Ok(_) => self.track.decoder.reset(),
+            Err(e) => {
+                // Reading from the start gets there too, only slower
+                tracing::debug!(error = %e, "Seek failed, decoding from the start");
+                self.track = open_audio_track(&self.path)?;
+            }
+        }
+        self.seeked = true;
+        Ok(())
+    }
+
+    fn apply_envelopes(&self, samples: &mut [f32], first_frame: u64) {
+        if self.envelopes.is_empty() {
+            return;
+        }
+        let channels = self.track.channels as usize;
+        for (i, frame) in samples.chunks_exact_mut(channels).enumerate() {
+            let at = first_frame + i as u64;
+            let gain: f32 = self.envelopes.iter().map(|envelope| envelope.gain(at)).product();
+            if gain != 1.0 {
+                frame.iter_mut().for_each(|sample| *sample *= gain);
+            }
+        }
+    }
+}
+
+impl Source for EditSource {
+    fn spec(&self) -> StreamSpec {
+        StreamSpec {
+            sample_rate: self.track.sample_rate,
+            channels: self.track.channels,
+        }
+    }
+
+    fn total_frames(&self) -> Option<u64> {
+        Some(self.total_frames)
+    }
+
+    fn next_chunk(&mut self) -> Result<Option<AudioData>> {
+        let channels = self.track.channels as usize;
+        let mut samples = Vec::new();
+        while let Some(piece) = self.pieces.get(self.current).cloned() {
+            if !self.seeked {
+                self.seek(piece.start)?;
+            }
+            let Ok(packet) = self.track.format.next_packet() else {
+                // The file ended early; go on with the next piece
+                self.current += 1;
+                self.seeked = false;
+                continue;
+            };
+            if packet.track_id() != self.track.track_id {
+                continue;
+            }
+            let packet_start = self.track.ts_to_frame(packet.ts());
+            if packet_start >= piece.end {
+                self.current += 1;
+                self.seeked = false;
+                continue;
+            }
+
+            let decoded = self
+                .track
+                .decoder
+                .decode(&packet)
+                .map_err(|e| DecodeError::Packet(e.to_string()))?;
+            samples.clear();
+            convert_audio_buffer_to_f32(&decoded, &mut samples);
+
+            let frames = (samples.len() / channels) as u64;
+            let from = piece.start.saturating_sub(packet_start).min(frames);
+            let to = (piece.end - packet_start).min(frames);
+            if from >= to {
+                continue;
+            }
+            let mut chunk = samples[from as usize * channels..to as usize * channels].to_vec();
+            self.apply_envelopes(&mut chunk, packet_start + from);
+            return Ok(Some(AudioData {
+                samples: chunk,
+                sample_rate: self.track.sample_rate,
+                channels: self.track.channels,
+            }));
+        }
+        Ok(None)
+    }
+}
+
+/// Render an edit list of `input_path` to `output_path`
+///
+/// Seeks to each kept piece instead of decoding the whole file, and
+/// streams through the [`crate::audio::pipeline::Pipeline`], so WAV output
+/// works for files of any length. Reports the "pipeline" stage.
+///
+/// # Errors
+/// As for [`EditList::plan`]; [`AudioError::InvalidParameter`] if
+/// `output_path` is the input itself, and [`DecodeError::EmptyRange`] if
+/// the edits leave nothing to play
+pub fn render_edit_list<P: AsRef<Path>, Q: AsRef<Path>>(
+    input_path: P,
+    edits: &EditList,
+    output_path: Q,
+    format: &OutputFormat,
+    progress: &mut dyn ProgressSink,
+) -> Result<PipelineSummary> {
+    let (input_path, output_path) = (input_path.as_ref(), output_path.as_ref());
+    check_not_input(input_path, output_path)?;
+
+    let source = EditSource::open(input_path, edits)?;
+    if source.total_frames == 0 {
+        let span = edits.operations.iter().map(EditOperation::range);
+        let start = span.clone().map(|range| range.start).min().unwrap_or_default();
+        let end = span.map(|range| range.end).max().unwrap_or_default();
+        return Err(DecodeError::EmptyRange {
+            start: start.as_seconds(),
+            end: end.as_seconds(),
+        }
+        .into());
+    }
+
+    progress.stage("pipeline");
+    let mut sink = EncodeSink::new(output_path, *format);
+    Pipeline::new(source).run_with_progress(&mut sink, progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::encoder::{encode_wav_with_format, WavSampleFormat};
+    use crate::audio::{decode_audio_file, release_reader};
+    use crate::progress::NoProgress;
+
+    fn secs(seconds: f64) -> Timestamp {
+        Timestamp::from_seconds(seconds)
+    }
+
+    #[test]
+    fn test_plan_keeps_in_order_minus_cuts() {
+        let mut edits = EditList::new();
+        assert_eq!(edits.plan(secs(10.0)).unwrap(), [secs(0.0)..secs(10.0)]);
+
+        edits.push(EditOperation::Cut { start: secs(2.0), end: secs(3.0) });
+        assert_eq!(edits.plan(secs(10.0)).unwrap(), [secs(0.0)..secs(2.0), secs(3.0)..secs(10.0)]);
+
+        edits.operations.clear();
+        edits.push(EditOperation::Keep { start: secs(8.0), end: secs(12.0) });
+        edits.push(EditOperation::Keep { start: secs(1.0), end: secs(4.0) });
+        edits.push(EditOperation::Cut { start: secs(0.0), end: secs(2.0) });
+        edits.push(EditOperation::Cut { start: secs(9.0), end: secs(9.5) });
+        let pieces = edits.plan(secs(10.0)).unwrap();
+        assert_eq!(pieces, [secs(8.0)..secs(9.0), secs(9.5)..secs(10.0), secs(2.0)..secs(4.0)]);
+        let rendered = edits.rendered_duration(secs(10.0)).unwrap();
+        assert_eq!(rendered, AudioDuration::from_seconds(3.5));
+
+        assert!(edits.undo().is_some());
+        assert_eq!(edits.plan(secs(10.0)).unwrap().len(), 2);
+
+        edits.push(EditOperation::Cut { start: secs(5.0), end: secs(5.0) });
+        assert!(edits.plan(secs(10.0)).is_err());
+        edits.undo();
+        edits.push(EditOperation::Gain { start: secs(0.0), end: secs(1.0), gain_db: f64::NAN });
+        assert!(edits.plan(secs(10.0)).is_err());
+    }
+
+    #[test]
+    fn test_edit_operations_as_json() {
+        let fade = EditOperation::Fade {
+            start: secs(1.0),
+            end: secs(1.5),
+            direction: FadeDirection::Out,
+            curve: FadeCurve::Cosine,
+        };
+        let json = serde_json::to_string(&fade).unwrap();
+        assert_eq!(
+            json,
+            r#"{"op":"fade","start":1.0,"end":1.5,"direction":"out","curve":"cosine"}"#
+        );
+        let linear: EditOperation =
+            serde_json::from_str(r#"{"op":"fade","start":1,"end":2,"direction":"in"}"#).unwrap();
+        assert!(matches!(linear, EditOperation::Fade { curve: FadeCurve::Linear, .. }));
+    }
+
+    #[test]
+    fn test_render_seeks_to_each_piece() {
+        // A ramp, so every output sample says where in the source it came from
+        let audio = AudioData {
+            samples: (0..8000 * 4).map(|i| i as f32 / 32000.0).collect(),
+            sample_rate: 8000,
+            channels: 1,
+        };
+        let dir = std::env::temp_dir();
+        let input = dir.join("hermeneia_test_edit_in.wav");
+        let output = dir.join("hermeneia_test_edit_out.wav");
+        encode_wav_with_format(&audio, &input, WavSampleFormat::Float32).unwrap();
+        let wav = OutputFormat::Wav {
+            sample_format: WavSampleFormat::Float32,
+        };
+
+        let mut edits = EditList::new();
+        edits.push(EditOperation::Keep { start: secs(3.0), end: secs(4.0) });
+        edits.push(EditOperation::Keep { start: secs(0.5), end: secs(1.5) });
+        edits.push(EditOperation::Gain {
+            start: secs(1.0),
+            end: secs(2.0),
+            gain_db: -6.0206,
+        });
+        edits.push(EditOperation::Fade {
+            start: secs(3.0),
+            end: secs(3.5),
+            direction: FadeDirection::In,
+            curve: FadeCurve::Linear,
+        });
+        let summary = render_edit_list(&input, &edits, &output, &wav, &mut NoProgress).unwrap();
+        assert_eq!(summary.frames_out, 16000);
+
+        let rendered = decode_audio_file(&output).unwrap().samples;
+        assert_eq!(rendered[0], 0.0);
+        assert!((rendered[2000] - audio.samples[26000] * 0.5).abs() < 1e-6);
+        assert_eq!(rendered[4000..8000], audio.samples[28000..]);
+        assert_eq!(rendered[8000..12000], audio.samples[4000..8000]);
+        for (out, source) in rendered[12000..].iter().zip(&audio.samples[8000..12000]) {
+            assert!((out - source * 0.5).abs() < 1e-6);
+        }
+        release_reader(&output);
+
+        edits.push(EditOperation::Cut { start: secs(0.0), end: secs(4.0) });
+        assert!(render_edit_list(&input, &edits, &output, &wav, &mut NoProgress).is_err());
+        assert!(render_edit_list(&input, &edits, &input, &wav, &mut NoProgress).is_err());
+        for path in [input, output] {
+            release_reader(&path);
+            std::fs::remove_file(path).ok();
+        }
+    }
+}
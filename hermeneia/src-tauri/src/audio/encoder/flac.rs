@@ -0,0 +1,265 @@
+// src-tauri/src/audio/encoder/flac.rs
+
+use flacenc::bitsink::ByteSink;
+use flacenc::component::BitRepr;
+use flacenc::error::{SourceError, Verify};
+use flacenc::source::{Fill, MemSource, Source};
+use std::path::Path;
+
+use crate::audio::encoder::{progress_fraction, quantize};
+use crate::audio::types::AudioData;
+use crate::error::{AudioError, EncodeError, Result};
+use crate::progress::{check_cancelled, NoProgress, ProgressSink};
+
+/// Compression level used when none is given, matching the `flac` tool
+pub const DEFAULT_FLAC_COMPRESSION: u8 = 5;
+
+/// Highest supported compression level (0 is fastest, 8 is smallest)
+pub const MAX_FLAC_COMPRESSION: u8 = 8;
+
+/// Encode PCM audio data to a lossless FLAC file
+///
+/// # Arguments
+/// * `audio` - The audio data to encode
+/// * `output_path` - Where to save the FLAC file
+/// * `bits_per_sample` - 16 or 24
+/// * `compression_level` - 0 (fastest) to 8 (smallest); every level is lossless
+pub fn encode_flac<P: AsRef<Path>>(
+    audio: &AudioData,
+    output_path: P,
+    bits_per_sample: u16,
+    compression_level: u8,
+) -> Result<()> {
+    write_flac(
+        audio,
+        output_path.as_ref(),
+        bits_per_sample,
+        compression_level,
+        &mut NoProgress,
+    )
+}
+
+pub(crate) fn write_flac(
+    audio: &AudioData,
+    output_path: &Path,
+    bits_per_sample: u16,
+    compression_level: u8,
+    progress: &mut dyn ProgressSink,
+) -> Result<()> {
+    if !matches!(bits_per_sample, 16 | 24) {
+        return Err(AudioError::InvalidParameter(format!(
+            "FLAC bit depth must be 16 or 24 (got {})",
+            bits_per_sample
+        )));
+    }
+
+    if compression_level > MAX_FLAC_COMPRESSION {
+        return Err(AudioError::InvalidParameter(format!(
+            "FLAC compression level must be 0 to {} (got {})",
+            MAX_FLAC_COMPRESSION, compression_level
+        )));
+    }
+
+    if !(1..=8).contains(&audio.channels) {
+        return Err(AudioError::InvalidParameter(format!(
+            "FLAC supports 1 to 8 channels (got {})",
+            audio.channels
+        )));
+    }
+
+    let samples: Vec<i32> = audio
+        .samples
+        .iter()
+        .map(|&s| quantize(s, bits_per_sample))
+        .collect();
+
+    let config = encoder_config(compression_level)
+        .into_verified()
+        .map_err(|(_, e)| EncodeError::config("FLAC", format!("{:?}", e)))?;
+
+    let source = ProgressSource {
+        inner: MemSource::from_samples(
+            &samples,
+            audio.channels as usize,
+            bits_per_sample as usize,
+            audio.sample_rate as usize,
+        ),
+        total_frames: audio.frame_count(),
+        read_frames: 0,
+        progress,
+    };
+
+    let stream = flacenc::encode_with_fixed_block_size(&config, source, config.block_size)
+        .map_err(|e| EncodeError::codec("FLAC", format!("{:?}", e)))?;
+    check_cancelled(progress)?;
+
+    let mut sink = ByteSink::new();
+    stream
+        .write(&mut sink)
+        .map_err(|e| EncodeError::codec("FLAC", format!("failed to write stream: {:?}", e)))?;
+
+    std::fs::write(output_path, sink.as_slice())?;
+
+    Ok(())
+}
+
+/// Encoder settings for a compression level
+///
+/// Loosely follows the `flac` tool's presets: levels 0 to 2 use fixed
+/// predictors only, 3 and up add LPC with a rising order. Level 5 is flacenc's
+/// own default. The block size stays at the default so every level pads the
+/// last block the same way.
+fn encoder_config(compression_level: u8) -> flacenc::config::Encoder {
+    let mut config = flacenc::config::Encoder::default();
+    match compression_level {
+        0..=2 => {
+            config.subframe_coding.use_lpc = false;
+            if compression_level == 0 {
+                config.stereo_coding.use_leftside = false;
+                config.stereo_coding.use_rightside = false;
+                config.stereo_coding.use_midside = false;
+                config.subframe_coding.fixed.max_order = 2;
+            } else if compression_level == 1 {
+                config.stereo_coding.use_leftside = false;
+                config.stereo_coding.use_rightside = false;
+            }
+        }
+        3 => config.subframe_coding.qlpc.lpc_order = 6,
+        4 => config.subframe_coding.qlpc.lpc_order = 8,
+        5 => {}
+        6 => config.subframe_coding.qlpc.lpc_order = 14,
+        7 => config.subframe_coding.qlpc.lpc_order = 18,
+        _ => config.subframe_coding.qlpc.lpc_order = 24,
+    }
+    config
+}
+
+/// Source wrapper that reports how far the encoder has read
+///
+/// flacenc encodes in one call, so the read position is the only progress
+/// signal available. Reporting end of input is also the only way to stop it
+/// early, which is how cancellation is handled.
+struct ProgressSource<'a, S> {
+    inner: S,
+    total_frames: usize,
+    read_frames: usize,
+    progress: &'a mut dyn ProgressSink,
+}
+
+impl<S: Source> Source for ProgressSource<'_, S> {
+    fn channels(&self) -> usize {
+        self.inner.channels()
+    }
+
+    fn bits_per_sample(&self) -> usize {
+        self.inner.bits_per_sample()
+    }
+
+    fn sample_rate(&self) -> usize {
+        self.inner.sample_rate()
+    }
+
+    fn read_samples<F: Fill>(
+        &mut self,
+        block_size: usize,
+        dest: &mut F,
+    ) -> std::result::Result<usize, SourceError> {
+        if self.progress.is_cancelled() {
+            return Ok(0);
+        }
+        let read = self.inner.read_samples(block_size, dest)?;
+        self.read_frames += read;
+        self.progress.progress(progress_fraction(self.read_frames, self.total_frames));
+        Ok(read)
+    }
+
+    fn len_hint(&self) -> Option<usize> {
+        self.inner.len_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::decoder::decode_audio_file;
+
+    #[test]
+    fn test_flac_round_trip_is_lossless_at_bit_depth() {
+        let frames = 8192;
+        let mut samples = Vec::with_capacity(frames * 2);
+        for i in 0..frames {
+            let t = i as f32 / 44100.0;
+            samples.push((t * 440.0 * std::f32::consts::TAU).sin() * 0.5);
+            samples.push((t * 220.0 * std::f32::consts::TAU).sin() * 0.25);
+        }
+        let audio = AudioData {
+            samples,
+            sample_rate: 44100,
+            channels: 2,
+        };
+
+        let path = std::env::temp_dir().join("hermeneia_test_encode.flac");
+        encode_flac(&audio, &path, 16, DEFAULT_FLAC_COMPRESSION).unwrap();
+
+        let decoded = decode_audio_file(&path).unwrap();
+        assert_eq!(decoded.channels, 2);
+        assert_eq!(decoded.sample_rate, 44100);
+        assert_eq!(decoded.samples.len(), audio.samples.len());
+        for (original, decoded) in audio.samples.iter().zip(decoded.samples.iter()) {
+            assert!((original - decoded).abs() < 1.0 / 16384.0);
+        }
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_flac_rejects_invalid_bit_depth() {
+        let audio = AudioData {
+            samples: vec![0.0; 16],
+            sample_rate: 44100,
+            channels: 1,
+        };
+        let path = std::env::temp_dir().join("hermeneia_test_invalid.flac");
+        assert!(encode_flac(&audio, &path, 32, DEFAULT_FLAC_COMPRESSION).is_err());
+        assert!(encode_flac(&audio, &path, 16, MAX_FLAC_COMPRESSION + 1).is_err());
+    }
+
+    #[test]
+    fn test_flac_levels_are_lossless_and_shrink() {
+        // Tones over a little noise, so longer predictors have something to find
+        let frames = 16384;
+        let mut samples = Vec::with_capacity(frames * 2);
+        let mut seed = 1u32;
+        for i in 0..frames {
+            let t = i as f32 / 44100.0;
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            let noise = (seed >> 8) as f32 / 16777216.0 - 0.5;
+            let tone = (t * 440.0 * std::f32::consts::TAU).sin() * 0.3
+                + (t * 1330.0 * std::f32::consts::TAU).sin() * 0.2
+                + noise * 0.02;
+            samples.push(tone);
+            samples.push(tone * 0.8);
+        }
+        let audio = AudioData {
+            samples,
+            sample_rate: 44100,
+            channels: 2,
+        };
+
+        let mut sizes = Vec::new();
+        for level in [0, DEFAULT_FLAC_COMPRESSION, MAX_FLAC_COMPRESSION] {
+            let path = std::env::temp_dir().join(format!("hermeneia_test_level_{}.flac", level));
+            encode_flac(&audio, &path, 16, level).unwrap();
+            sizes.push(std::fs::metadata(&path).unwrap().len());
+
+            let decoded = decode_audio_file(&path).unwrap();
+            assert_eq!(decoded.samples.len(), audio.samples.len());
+            for (original, decoded) in audio.samples.iter().zip(decoded.samples.iter()) {
+                assert!((original - decoded).abs() < 1.0 / 16384.0);
+            }
+            std::fs::remove_file(path).ok();
+        }
+        assert!(sizes[0] > sizes[1], "{:?}", sizes);
+        assert!(sizes[1] >= sizes[2], "{:?}", sizes);
+    }
+}
@@ -0,0 +1,424 @@
+// src-tauri/src/audio/encoder/mod.rs
+
+mod flac;
+mod mp3;
+#[cfg(feature = "opus")]
+mod opus;
+mod wav;
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::audio::types::AudioData;
+use crate::error::Result;
+use crate::profile;
+use crate::progress::{NoProgress, ProgressSink};
+
+pub use flac::{encode_flac, DEFAULT_FLAC_COMPRESSION, MAX_FLAC_COMPRESSION};
+pub use mp3::{encode_mp3, MP3_BITRATES};
+#[cfg(feature = "opus")]
+pub use opus::{encode_opus, encode_opus_speech};
+pub use wav::{encode_wav, encode_wav_with_format, encode_wav_with_options, WavStreamWriter};
+
+/// Sample encoding used when writing WAV files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WavSampleFormat {
+    /// 16-bit signed integer PCM (CD quality, widest compatibility)
+    Pcm16,
+    /// 24-bit signed integer PCM
+    Pcm24,
+    /// 32-bit IEEE float (lossless for our internal f32 samples)
+    #[default]
+    Float32,
+}
+
+impl WavSampleFormat {
+    /// Bits per sample written to the file
+    pub fn bits_per_sample(self) -> u16 {
+        match self {
+            WavSampleFormat::Pcm16 => 16,
+            WavSampleFormat::Pcm24 => 24,
+            WavSampleFormat::Float32 => 32,
+        }
+    }
+}
+
+/// Settings for [`encode_wav_with_options`]
+///
+/// ```
+/// use hermeneia_lib::audio::{EncodeOptions, WavSampleFormat};
+///
+/// let options = EncodeOptions::new().sample_format(WavSampleFormat::Pcm16);
+/// assert!(options.dither);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EncodeOptions {
+    /// Sample encoding (default: 32-bit float)
+    pub sample_format: WavSampleFormat,
+
+    /// Add TPDF dither before reducing to 16 or 24-bit PCM (default: on);
+    /// float output is never dithered
+    pub dither: bool,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        Self {
+            sample_format: WavSampleFormat::default(),
+            dither: true,
+        }
+    }
+}
+
+impl EncodeOptions {
+    /// The defaults: 32-bit float, dithered if the format is changed to PCM
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn sample_format(mut self, sample_format: WavSampleFormat) -> Self {
+        self.sample_format = sample_format;
+        self
+    }
+
+    pub fn dither(mut self, dither: bool) -> Self {
+        self.dither = dither;
+        self
+    }
+}
+
+/// Output container/codec and its settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "format", rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum OutputFormat {
+    /// Uncompressed WAV
+    Wav { sample_format: WavSampleFormat },
+    /// Lossless FLAC (16 or 24 bits per sample, compression level 0 to 8)
+    Flac {
+        bits_per_sample: u16,
+        #[serde(default = "default_flac_compression")]
+        compression_level: u8,
+    },
+    /// Constant-bitrate MP3
+    Mp3 { bitrate_kbps: u32 },
+    /// Ogg Opus (requires the `opus` feature); `speech` tunes it for voice
+    Opus {
+        bitrate_kbps: u32,
+        #[serde(default)]
+        speech: bool,
+    },
+}
+
+impl OutputFormat {
+    /// Conventional file extension for this format (without the dot)
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Wav { .. } => "wav",
+            OutputFormat::Flac { .. } => "flac",
+            OutputFormat::Mp3 { .. } => "mp3",
+            OutputFormat::Opus { .. } => "opus",
+        }
+    }
+
+    /// Pick a format with default settings from a file extension
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "wav" => Some(OutputFormat::Wav {
+                sample_format: WavSampleFormat::default(),
+            }),
+            "flac" => Some(OutputFormat::Flac {
+                bits_per_sample: 24,
+                compression_level: DEFAULT_FLAC_COMPRESSION,
+            }),
+            "mp3" => Some(OutputFormat::Mp3 { bitrate_kbps: 192 }),
+            "opus" | "ogg" => Some(OutputFormat::Opus {
+                bitrate_kbps: 64,
+                speech: false,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Encode PCM audio data to a file in the requested format
+///
+/// # Example
+/// ```no_run
+/// use hermeneia_lib::audio::{decode_audio_file, encode_audio, OutputFormat};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let audio = decode_audio_file("sermon.wav")?;
+/// encode_audio(&audio, "sermon.mp3", &OutputFormat::Mp3 { bitrate_kbps: 128 })?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn encode_audio<P: AsRef<Path>>(
+    audio: &AudioData,
+    output_path: P,
+    format: &OutputFormat,
+) -> Result<()> {
+    encode_audio_with_progress(audio, output_path, format, &mut NoProgress)
+}
+
+/// Encode like [`encode_audio`], reporting progress
+///
+/// `progress` gets the "encode" stage and the fraction encoded so far (0.0
+/// to 1.0). When it asks to cancel, encoding stops with
+/// [`crate::AudioError::Cancelled`] and the output is not kept.
+pub fn encode_audio_with_progress<P: AsRef<Path>>(
+    audio: &AudioData,
+    output_path: P,
+    format: &OutputFormat,
+    progress: &mut dyn ProgressSink,
+) -> Result<()> {
+    let _stage = profile::stage("encode");
+    progress.stage("encode");
+    audio.validate()?;
+    let output_path = output_path.as_ref();
+    match *format {
+        OutputFormat::Wav { sample_format } => {
+            let options = EncodeOptions::new().sample_format(sample_format);
+            wav::write_wav(audio, output_path, &options, progress)
+        }
+        OutputFormat::Flac {
+            bits_per_sample,
+            compression_level,
+        } => flac::write_flac(audio, output_path, bits_per_sample, compression_level, progress),
+        OutputFormat::Mp3 { bitrate_kbps } => {
+            mp3::write_mp3(audio, output_path, bitrate_kbps, progress)
+        }
+        #[cfg(feature = "opus")]
+        OutputFormat::Opus {
+            bitrate_kbps,
+            speech,
+        } => opus::write_opus(audio, output_path, bitrate_kbps, speech, progress),
+        #[cfg(not(feature = "opus"))]
+        OutputFormat::Opus { .. } => Err(crate::error::AudioError::UnsupportedFormat(
+            "Opus output requires building with the `opus` feature".to_string(),
+        )),
+    }
+}
+
+fn default_flac_compression() -> u8 {
+    DEFAULT_FLAC_COMPRESSION
+}
+
+/// Fraction of `total` that `done` represents, for progress reports
+pub(crate) fn progress_fraction(done: usize, total: usize) -> f64 {
+    if total == 0 {
+        1.0
+    } else {
+        (done as f64 / total as f64).min(1.0)
+    }
+}
+
+/// Convert a float sample to a signed integer of the given bit depth
+///
+/// Values are rounded and clipped to the representable range.
+pub(crate) fn quantize(sample: f32, bits: u16) -> i32 {
+    let max = ((1i64 << (bits - 1)) - 1) as f32;
+    let min = -(1i64 << (bits - 1)) as f32;
+    (sample * (max + 1.0)).round().clamp(min, max) as i32
+}
+
+/// Triangular (TPDF) dither for reducing float samples to integers
+///
+/// Adds noise spanning one step either side before rounding, so quiet
+/// passages keep their detail as a steady noise floor instead of turning
+/// into distortion or dropping out to digital silence.
+pub(crate) struct Dither {
+    state: u32,
+}
+
+impl Dither {
+    pub(crate) fn new() -> Self {
+        Self { state: 0x2545_f491 }
+    }
+
+    /// [`quantize`] with dither noise added first
+    pub(crate) fn quantize(&mut self, sample: f32, bits: u16) -> i32 {
+        let step = 1.0 / (1i64 << (bits - 1)) as f32;
+        let noise = (self.uniform() - self.uniform()) * step;
+        quantize(sample + noise, bits)
+    }
+
+    /// Uniform in [0, 1) from a xorshift generator
+    fn uniform(&mut self) -> f32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        (self.state >> 8) as f32 / 16_777_216.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AudioError;
+
+    #[test]
+    fn test_quantize_clips_and_rounds() {
+        assert_eq!(quantize(0.0, 16), 0);
+        assert_eq!(quantize(1.0, 16), 32767);
+        assert_eq!(quantize(-1.0, 16), -32768);
+        assert_eq!(quantize(2.0, 16), 32767);
+        assert_eq!(quantize(0.5, 24), 4194304);
+    }
+
+    #[test]
+    fn test_dither_keeps_sub_step_levels() {
+        // A quarter of a 16-bit step rounds away to nothing undithered
+        let level = 0.25 / 32768.0;
+        assert_eq!(quantize(level, 16), 0);
+
+        let mut dither = Dither::new();
+        let values: Vec<i32> = (0..100_000).map(|_| dither.quantize(level, 16)).collect();
+        assert!(values.iter().all(|v| v.abs() <= 1));
+        let mean = values.iter().sum::<i32>() as f64 / values.len() as f64;
+        assert!((mean - 0.25).abs() < 0.02, "{}", mean);
+    }
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(OutputFormat::from_extension("MP3").unwrap().extension(), "mp3");
+        assert_eq!(OutputFormat::from_extension("ogg").unwrap().extension(), "opus");
+        assert!(OutputFormat::from_extension("txt").is_none());
+    }
+
+    #[test]
+    fn test_flac_compression_defaults_when_missing() {
+        let format: OutputFormat =
+            serde_json::from_str(r#"{"format":"flac","bits_per_sample":16}"#).unwrap();
+        assert_eq!(
+            format,
+            OutputFormat::Flac {
+                bits_per_sample: 16,
+                compression_level: DEFAULT_FLAC_COMPRESSION,
+            }
+        );
+    }
+
+    #[test]
+    fn test_encode_audio_dispatches_by_format() {
+        let audio = AudioData {
+            samples: vec![0.25; 4410],
+            sample_rate: 44100,
+            channels: 1,
+        };
+        let path = std::env::temp_dir().join("hermeneia_test_dispatch.wav");
+        let format = OutputFormat::Wav {
+            sample_format: WavSampleFormat::Pcm16,
+        };
+
+        encode_audio(&audio, &path, &format).unwrap();
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().bits_per_sample, 16);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_encode_audio_dithers_pcm() {
+        // A quarter of a 16-bit step, which plain rounding turns into silence
+        let level = 0.25 / 32768.0;
+        let audio = AudioData {
+            samples: vec![level; 44100],
+            sample_rate: 44100,
+            channels: 1,
+        };
+        let path = std::env::temp_dir().join("hermeneia_test_dither_dispatch.wav");
+        let format = OutputFormat::Wav {
+            sample_format: WavSampleFormat::Pcm16,
+        };
+
+        encode_audio(&audio, &path, &format).unwrap();
+        let mut reader = hound::WavReader::open(&path).unwrap();
+        let values: Vec<i32> = reader.samples::<i32>().map(|s| s.unwrap()).collect();
+        assert!(values.iter().any(|&v| v != 0));
+        let mean = values.iter().sum::<i32>() as f64 / values.len() as f64;
+        assert!((mean - 0.25).abs() < 0.03, "{}", mean);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_encode_reports_progress() {
+        let audio = AudioData {
+            samples: vec![0.1; 44100 * 2 * 3],
+            sample_rate: 44100,
+            channels: 2,
+        };
+
+        let formats = [
+            OutputFormat::Wav {
+                sample_format: WavSampleFormat::Float32,
+            },
+            OutputFormat::Flac {
+                bits_per_sample: 16,
+                compression_level: 0,
+            },
+            OutputFormat::Mp3 { bitrate_kbps: 128 },
+        ];
+        for format in formats {
+            let path = std::env::temp_dir()
+                .join(format!("hermeneia_test_progress.{}", format.extension()));
+            let mut reports = Vec::new();
+            encode_audio_with_progress(&audio, &path, &format, &mut |p: f64| reports.push(p))
+                .unwrap();
+
+            assert!(reports.len() > 1, "{:?} reported {:?}", format, reports);
+            assert!(reports.windows(2).all(|w| w[0] <= w[1]));
+            assert_eq!(reports.last(), Some(&1.0));
+
+            std::fs::remove_file(path).ok();
+        }
+    }
+
+    /// Asks to stop once `remaining` progress reports have come in
+    struct CancelAfter {
+        remaining: usize,
+    }
+
+    impl ProgressSink for CancelAfter {
+        fn progress(&mut self, _fraction: f64) {
+            self.remaining = self.remaining.saturating_sub(1);
+        }
+
+        fn is_cancelled(&self) -> bool {
+            self.remaining == 0
+        }
+    }
+
+    #[test]
+    fn test_cancelled_encode_leaves_no_file() {
+        let audio = AudioData {
+            samples: vec![0.1; 44100 * 2 * 3],
+            sample_rate: 44100,
+            channels: 2,
+        };
+
+        let formats = [
+            OutputFormat::Wav {
+                sample_format: WavSampleFormat::Float32,
+            },
+            OutputFormat::Flac {
+                bits_per_sample: 16,
+                compression_level: 0,
+            },
+            OutputFormat::Mp3 { bitrate_kbps: 128 },
+        ];
+        for format in formats {
+            let path = std::env::temp_dir()
+                .join(format!("hermeneia_test_cancel.{}", format.extension()));
+            std::fs::remove_file(&path).ok();
+            let mut sink = CancelAfter { remaining: 1 };
+            let result = encode_audio_with_progress(&audio, &path, &format, &mut sink);
+
+            assert!(matches!(result, Err(AudioError::Cancelled)), "{:?}", format);
+            assert!(!path.exists(), "{:?} left {}", format, path.display());
+        }
+    }
+}
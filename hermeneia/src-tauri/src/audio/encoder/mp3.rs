@@ -0,0 +1,158 @@
+// src-tauri/src/audio/encoder/mp3.rs
+
+use mp3lame_encoder::{
+    max_required_buffer_size, Bitrate, Builder, FlushNoGap, InterleavedPcm, MonoPcm, Quality,
+};
+use std::path::Path;
+
+use crate::audio::encoder::progress_fraction;
+use crate::audio::types::AudioData;
+use crate::error::{AudioError, EncodeError, Result};
+use crate::progress::{check_cancelled, NoProgress, ProgressSink};
+
+/// Frames handed to LAME per call (keeps the output buffer small)
+const CHUNK_FRAMES: usize = 8192;
+
+/// Constant bitrates supported by the MP3 encoder, in kbps
+pub const MP3_BITRATES: [u32; 16] = [
+    8, 16, 24, 32, 40, 48, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+];
+
+/// Encode PCM audio data to a constant-bitrate MP3 file
+///
+/// MP3 only carries mono or stereo; remix other layouts first.
+///
+/// # Arguments
+/// * `audio` - The audio data to encode
+/// * `output_path` - Where to save the MP3 file
+/// * `bitrate_kbps` - One of [`MP3_BITRATES`] (e.g. 128, 192, 320)
+pub fn encode_mp3<P: AsRef<Path>>(audio: &AudioData, output_path: P, bitrate_kbps: u32) -> Result<()> {
+    write_mp3(audio, output_path.as_ref(), bitrate_kbps, &mut NoProgress)
+}
+
+pub(crate) fn write_mp3(
+    audio: &AudioData,
+    output_path: &Path,
+    bitrate_kbps: u32,
+    progress: &mut dyn ProgressSink,
+) -> Result<()> {
+    let bitrate = to_lame_bitrate(bitrate_kbps)?;
+
+    if !matches!(audio.channels, 1 | 2) {
+        return Err(AudioError::InvalidParameter(format!(
+            "MP3 supports mono or stereo only (got {} channels)",
+            audio.channels
+        )));
+    }
+
+    let mut builder = Builder::new()
+        .ok_or_else(|| EncodeError::init("MP3", "LAME could not be initialized"))?;
+    builder
+        .set_num_channels(audio.channels as u8)
+        .and_then(|_| builder.set_sample_rate(audio.sample_rate))
+        .and_then(|_| builder.set_brate(bitrate))
+        .and_then(|_| builder.set_quality(Quality::Best))
+        .map_err(|e| EncodeError::config("MP3", e))?;
+
+    let mut encoder = builder
+        .build()
+        .map_err(|e| EncodeError::init("MP3", e))?;
+
+    let channels = audio.channels as usize;
+    let mut mp3 = Vec::new();
+    let mut encoded = 0;
+
+    for chunk in audio.samples.chunks(CHUNK_FRAMES * channels) {
+        check_cancelled(progress)?;
+        mp3.reserve(max_required_buffer_size(chunk.len() / channels));
+        let result = if channels == 1 {
+            encoder.encode_to_vec(MonoPcm(chunk), &mut mp3)
+        } else {
+            encoder.encode_to_vec(InterleavedPcm(chunk), &mut mp3)
+        };
+        result.map_err(|e| EncodeError::codec("MP3", e))?;
+
+        encoded += chunk.len();
+        progress.progress(progress_fraction(encoded, audio.samples.len()));
+    }
+
+    mp3.reserve(max_required_buffer_size(0));
+    encoder
+        .flush_to_vec::<FlushNoGap>(&mut mp3)
+        .map_err(|e| EncodeError::codec("MP3", format!("flush failed: {}", e)))?;
+
+    std::fs::write(output_path, mp3)?;
+    progress.progress(1.0);
+
+    Ok(())
+}
+
+fn to_lame_bitrate(kbps: u32) -> Result<Bitrate> {
+    Ok(match kbps {
+        8 => Bitrate::Kbps8,
+        16 => Bitrate::Kbps16,
+        24 => Bitrate::Kbps24,
+        32 => Bitrate::Kbps32,
+        40 => Bitrate::Kbps40,
+        48 => Bitrate::Kbps48,
+        64 => Bitrate::Kbps64,
+        80 => Bitrate::Kbps80,
+        96 => Bitrate::Kbps96,
+        112 => Bitrate::Kbps112,
+        128 => Bitrate::Kbps128,
+        160 => Bitrate::Kbps160,
+        192 => Bitrate::Kbps192,
+        224 => Bitrate::Kbps224,
+        256 => Bitrate::Kbps256,
+        320 => Bitrate::Kbps320,
+        _ => {
+            return Err(AudioError::InvalidParameter(format!(
+                "Unsupported MP3 bitrate {} kbps (expected one of {:?})",
+                kbps, MP3_BITRATES
+            )))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::decoder::get_audio_info;
+
+    fn test_audio(channels: u16) -> AudioData {
+        let frames = 44100;
+        let mut samples = Vec::with_capacity(frames * channels as usize);
+        for i in 0..frames {
+            let value = (i as f32 / 44100.0 * 440.0 * std::f32::consts::TAU).sin() * 0.5;
+            for _ in 0..channels {
+                samples.push(value);
+            }
+        }
+        AudioData {
+            samples,
+            sample_rate: 44100,
+            channels,
+        }
+    }
+
+    #[test]
+    fn test_encode_mp3_mono_and_stereo() {
+        for channels in [1, 2] {
+            let path = std::env::temp_dir().join(format!("hermeneia_test_{}ch.mp3", channels));
+            encode_mp3(&test_audio(channels), &path, 128).unwrap();
+
+            let info = get_audio_info(&path).unwrap();
+            assert_eq!(info.channels, channels);
+            assert_eq!(info.sample_rate, 44100);
+
+            std::fs::remove_file(path).ok();
+        }
+    }
+
+    #[test]
+    fn test_encode_mp3_rejects_invalid_settings() {
+        let path = std::env::temp_dir().join("hermeneia_test_invalid.mp3");
+        assert!(encode_mp3(&test_audio(1), &path, 100).is_err());
+        assert!(encode_mp3(&test_audio(3), &path, 128).is_err());
+    }
+}
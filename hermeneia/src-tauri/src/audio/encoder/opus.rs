@@ -0,0 +1,232 @@
+// src-tauri/src/audio/encoder/opus.rs
+
+use ogg::writing::{PacketWriteEndInfo, PacketWriter};
+use opus::{Application, Bitrate, Channels, Signal};
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use crate::audio::encoder::progress_fraction;
+use crate::audio::resample::resample_audio;
+use crate::audio::types::AudioData;
+use crate::error::{AudioError, EncodeError, Result};
+use crate::progress::{NoProgress, ProgressSink};
+
+/// Opus always runs at 48 kHz internally; other rates are resampled first
+const OPUS_SAMPLE_RATE: u32 = 48000;
+
+/// 20 ms frames, the standard Opus frame size
+const FRAME_SIZE: usize = 960;
+
+/// Largest packet we expect from a single 20 ms frame
+const MAX_PACKET_SIZE: usize = 4000;
+
+/// Ogg logical stream serial number (single-stream files)
+const STREAM_SERIAL: u32 = 0x4845_524d;
+
+/// Encode PCM audio data to an Ogg Opus file
+///
+/// Audio that isn't at 48 kHz is resampled before encoding. Opus carries
+/// mono or stereo here; remix other layouts first.
+///
+/// # Arguments
+/// * `audio` - The audio data to encode
+/// * `output_path` - Where to save the .opus file
+/// * `bitrate_kbps` - Target bitrate (6 to 510 kbps)
+pub fn encode_opus<P: AsRef<Path>>(audio: &AudioData, output_path: P, bitrate_kbps: u32) -> Result<()> {
+    write_opus(audio, output_path.as_ref(), bitrate_kbps, false, &mut NoProgress)
+}
+
+/// Encode like [`encode_opus`], tuned for spoken word
+///
+/// Runs libopus in its VoIP mode with the voice signal hint, which keeps
+/// speech intelligible at low bitrates (24 to 32 kbps mono is plenty for a
+/// sermon) at the cost of music quality.
+pub fn encode_opus_speech<P: AsRef<Path>>(
+    audio: &AudioData,
+    output_path: P,
+    bitrate_kbps: u32,
+) -> Result<()> {
+    write_opus(audio, output_path.as_ref(), bitrate_kbps, true, &mut NoProgress)
+}
+
+pub(crate) fn write_opus(
+    audio: &AudioData,
+    output_path: &Path,
+    bitrate_kbps: u32,
+    speech: bool,
+    progress: &mut dyn ProgressSink,
+) -> Result<()> {
+    if !(6..=510).contains(&bitrate_kbps) {
+        return Err(AudioError::InvalidParameter(format!(
+            "Opus bitrate must be between 6 and 510 kbps (got {})",
+            bitrate_kbps
+        )));
+    }
+
+    let channels = match audio.channels {
+        1 => Channels::Mono,
+        2 => Channels::Stereo,
+        n => {
+            return Err(AudioError::InvalidParameter(format!(
+                "Opus supports mono or stereo only (got {} channels)",
+                n
+            )))
+        }
+    };
+
+    let audio: Cow<AudioData> = if audio.sample_rate == OPUS_SAMPLE_RATE {
+        Cow::Borrowed(audio)
+    } else {
+        Cow::Owned(resample_audio(audio, OPUS_SAMPLE_RATE)?)
+    };
+
+    let application = if speech { Application::Voip } else { Application::Audio };
+    let mut encoder = opus::Encoder::new(OPUS_SAMPLE_RATE, channels, application)
+        .map_err(|e| EncodeError::init("Opus", e))?;
+    encoder
+        .set_bitrate(Bitrate::Bits(bitrate_kbps as i32 * 1000))
+        .map_err(|e| EncodeError::config("Opus", format!("bitrate: {}", e)))?;
+    if speech {
+        encoder
+            .set_signal(Signal::Voice)
+            .map_err(|e| EncodeError::config("Opus", format!("signal: {}", e)))?;
+    }
+
+    let pre_skip = encoder
+        .get_lookahead()
+        .map_err(|e| EncodeError::init("Opus", format!("lookahead query failed: {}", e)))?
+        as u64;
+
+    let file = File::create(output_path)?;
+    let mut writer = PacketWriter::new(BufWriter::new(file));
+
+    // Identification and comment headers each get their own page
+    writer.write_packet(
+        opus_head(audio.channels, pre_skip as u16, audio.sample_rate),
+        STREAM_SERIAL,
+        PacketWriteEndInfo::EndPage,
+        0,
+    )?;
+    writer.write_packet(opus_tags(), STREAM_SERIAL, PacketWriteEndInfo::EndPage, 0)?;
+
+    // Pad with pre_skip frames of silence so the encoder's lookahead is flushed
+    let channel_count = audio.channels as usize;
+    let total_frames = audio.frame_count() as u64;
+    let mut samples = audio.samples.to_vec();
+    samples.resize(samples.len() + pre_skip as usize * channel_count, 0.0);
+
+    let chunks: Vec<&[f32]> = samples.chunks(FRAME_SIZE * channel_count).collect();
+    let mut frame_buf = vec![0.0f32; FRAME_SIZE * channel_count];
+    let mut granule: u64 = 0;
+
+    for (i, chunk) in chunks.iter().enumerate() {
+        if progress.is_cancelled() {
+            // Don't leave a truncated file behind
+            drop(writer);
+            let _ = std::fs::remove_file(output_path);
+            return Err(AudioError::Cancelled);
+        }
+        frame_buf[..chunk.len()].copy_from_slice(chunk);
+        frame_buf[chunk.len()..].fill(0.0);
+
+        let packet = encoder
+            .encode_vec_float(&frame_buf, MAX_PACKET_SIZE)
+            .map_err(|e| EncodeError::codec("Opus", e))?;
+
+        granule += FRAME_SIZE as u64;
+        let is_last = i + 1 == chunks.len();
+        let (end_info, position) = if is_last {
+            // Final granule marks where decoding stops (trims the padding)
+            (PacketWriteEndInfo::EndStream, pre_skip + total_frames)
+        } else {
+            (PacketWriteEndInfo::NormalPacket, granule)
+        };
+
+        writer.write_packet(packet, STREAM_SERIAL, end_info, position)?;
+        progress.progress(progress_fraction(i + 1, chunks.len()));
+    }
+    progress.progress(1.0);
+
+    Ok(())
+}
+
+/// Build the OpusHead identification header (RFC 7845, section 5.1)
+fn opus_head(channels: u16, pre_skip: u16, input_sample_rate: u32) -> Vec<u8> {
+    let mut head = Vec::with_capacity(19);
+    head.extend_from_slice(b"OpusHead");
+    head.push(1); // version
+    head.push(channels as u8);
+    head.extend_from_slice(&pre_skip.to_le_bytes());
+    head.extend_from_slice(&input_sample_rate.to_le_bytes());
+    head.extend_from_slice(&0i16.to_le_bytes()); // output gain
+    head.push(0); // channel mapping family (mono/stereo)
+    head
+}
+
+/// Build the OpusTags comment header (RFC 7845, section 5.2)
+fn opus_tags() -> Vec<u8> {
+    let vendor = concat!("hermeneia ", env!("CARGO_PKG_VERSION"));
+    let mut tags = Vec::with_capacity(16 + vendor.len());
+    tags.extend_from_slice(b"OpusTags");
+    tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    tags.extend_from_slice(vendor.as_bytes());
+    tags.extend_from_slice(&0u32.to_le_bytes()); // no user comments
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opus_head_layout() {
+        let head = opus_head(2, 312, 44100);
+        assert_eq!(&head[..8], b"OpusHead");
+        assert_eq!(head.len(), 19);
+        assert_eq!(head[9], 2);
+        assert_eq!(u16::from_le_bytes([head[10], head[11]]), 312);
+        assert_eq!(u32::from_le_bytes([head[12], head[13], head[14], head[15]]), 44100);
+    }
+
+    #[test]
+    fn test_encode_opus_writes_ogg_stream() {
+        let audio = AudioData {
+            samples: vec![0.1; 44100 * 2],
+            sample_rate: 44100,
+            channels: 2,
+        };
+
+        let path = std::env::temp_dir().join("hermeneia_test_encode.opus");
+        encode_opus(&audio, &path, 64).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(&bytes[..4], b"OggS");
+        assert!(bytes.windows(8).any(|w| w == b"OpusHead"));
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_encode_opus_speech_at_low_bitrate() {
+        let samples = (0..16000 * 2)
+            .map(|i| (i as f32 / 16000.0 * 180.0 * std::f32::consts::TAU).sin() * 0.3)
+            .collect();
+        let audio = AudioData {
+            samples,
+            sample_rate: 16000,
+            channels: 1,
+        };
+
+        let path = std::env::temp_dir().join("hermeneia_test_speech.opus");
+        encode_opus_speech(&audio, &path, 24).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(&bytes[..4], b"OggS");
+        // 2 seconds at 24 kbps, plus Ogg framing
+        assert!(bytes.len() < 8000, "{} bytes", bytes.len());
+
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,447 @@
+// src-tauri/src/audio/encoder/wav.rs
+
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::audio::encoder::{progress_fraction, quantize, Dither, EncodeOptions, WavSampleFormat};
+use crate::audio::types::AudioData;
+use crate::error::{AudioError, Result};
+use crate::progress::{NoProgress, ProgressSink};
+
+/// Encode PCM audio data to a WAV file
+/// 
+/// Outputs 32-bit float WAV files for maximum quality
+/// 
+/// # Arguments
+/// * `audio` - The audio data to encode
+/// * `output_path` - Where to save the WAV file
+/// 
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::{AudioData, encode_wav};
+/// 
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// // Create some test audio data
+/// let audio = AudioData {
+///     samples: vec![0.0, 0.5, -0.5, 1.0, -1.0],
+///     sample_rate: 44100,
+///     channels: 1,
+/// };
+/// 
+/// // Encode to WAV file
+/// # let temp_dir = std::env::temp_dir();
+/// # let output_path = temp_dir.join("test_output.wav");
+/// encode_wav(&audio, &output_path)?;
+/// # std::fs::remove_file(&output_path).ok();
+/// # Ok(())
+/// # }
+/// ```
+pub fn encode_wav<P: AsRef<Path>>(audio: &AudioData, output_path: P) -> Result<()> {
+    encode_wav_with_format(audio, output_path, WavSampleFormat::Float32)
+}
+
+/// Encode PCM audio data to a WAV file with a specific sample format
+///
+/// Integer formats are quantized with rounding and clipped to full scale;
+/// use [`encode_wav_with_options`] to dither them instead.
+///
+/// # Arguments
+/// * `audio` - The audio data to encode
+/// * `output_path` - Where to save the WAV file
+/// * `sample_format` - 16-bit PCM, 24-bit PCM or 32-bit float
+pub fn encode_wav_with_format<P: AsRef<Path>>(
+    audio: &AudioData,
+    output_path: P,
+    sample_format: WavSampleFormat,
+) -> Result<()> {
+    let options = EncodeOptions::new().sample_format(sample_format).dither(false);
+    write_wav(audio, output_path.as_ref(), &options, &mut NoProgress)
+}
+
+/// Encode PCM audio data to a WAV file with [`EncodeOptions`]
+///
+/// Unlike [`encode_wav_with_format`] this dithers 16 and 24-bit output by
+/// default, as [`crate::audio::encode_audio`] and the streaming writers in
+/// the pipeline and trims do.
+///
+/// # Example
+/// ```no_run
+/// use hermeneia_lib::audio::{decode_audio_file, encode_wav_with_options};
+/// use hermeneia_lib::audio::{EncodeOptions, WavSampleFormat};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let audio = decode_audio_file("sermon.flac")?;
+/// let options = EncodeOptions::new().sample_format(WavSampleFormat::Pcm16);
+/// encode_wav_with_options(&audio, "sermon.wav", &options)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn encode_wav_with_options<P: AsRef<Path>>(
+    audio: &AudioData,
+    output_path: P,
+    options: &EncodeOptions,
+) -> Result<()> {
+    write_wav(audio, output_path.as_ref(), options, &mut NoProgress)
+}
+
+/// Frames written between progress reports
+const PROGRESS_CHUNK_FRAMES: usize = 65536;
+
+pub(crate) fn write_wav(
+    audio: &AudioData,
+    output_path: &Path,
+    options: &EncodeOptions,
+    progress: &mut dyn ProgressSink,
+) -> Result<()> {
+    let mut writer = WavStreamWriter::create(
+        output_path,
+        audio.sample_rate,
+        audio.channels,
+        options.sample_format,
+    )?
+    .dither(options.dither);
+
+    let chunk_len = PROGRESS_CHUNK_FRAMES * (audio.channels as usize).max(1);
+    let mut written = 0;
+    for chunk in audio.samples.chunks(chunk_len) {
+        if progress.is_cancelled() {
+            // Don't leave a truncated file behind
+            drop(writer);
+            let _ = std::fs::remove_file(output_path);
+            return Err(AudioError::Cancelled);
+        }
+        writer.write_samples(chunk)?;
+        written += chunk.len();
+        progress.progress(progress_fraction(written, audio.samples.len()));
+    }
+
+    writer.finalize()?;
+    progress.progress(1.0);
+    Ok(())
+}
+
+/// Incremental WAV writer for audio that arrives in chunks
+///
+/// Samples are written as they come in and the header sizes are filled in
+/// by [`WavStreamWriter::finalize`], so the full result never has to sit in
+/// memory.
+///
+/// Space for an RF64 `ds64` chunk is reserved as a `JUNK` chunk up front
+/// (EBU Tech 3306). Files that stay under the 4GB RIFF limit finalize as
+/// plain WAV; bigger ones are switched to RF64 instead of having their
+/// sizes wrap around.
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::{WavSampleFormat, WavStreamWriter};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let output_path = std::env::temp_dir().join("test_stream_writer.wav");
+/// let mut writer = WavStreamWriter::create(&output_path, 16000, 1, WavSampleFormat::Pcm16)?;
+/// writer.write_samples(&[0.0, 0.25, 0.5])?;
+/// writer.write_samples(&[0.25, 0.0])?;
+/// writer.finalize()?;
+/// # std::fs::remove_file(&output_path).ok();
+/// # Ok(())
+/// # }
+/// ```
+pub struct WavStreamWriter {
+    writer: BufWriter<File>,
+    sample_format: WavSampleFormat,
+    block_align: u64,
+    /// Offset of the data chunk's size field
+    data_len_offset: u64,
+    data_bytes: u64,
+    buffer: Vec<u8>,
+    dither: Option<Dither>,
+}
+
+/// Size of the `ds64` payload (RIFF size, data size, sample count, table length)
+const DS64_LEN: u32 = 28;
+/// Offset of the reserved `JUNK`/`ds64` chunk
+const DS64_OFFSET: u64 = 12;
+const WAVE_FORMAT_PCM: u16 = 1;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+/// Tail shared by the KSDATAFORMAT_SUBTYPE GUIDs; the format tag goes first
+const SUBTYPE_GUID_TAIL: [u8; 14] = [
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+];
+
+impl WavStreamWriter {
+    /// Create the output file and write a provisional header
+    pub fn create<P: AsRef<Path>>(
+        output_path: P,
+        sample_rate: u32,
+        channels: u16,
+        sample_format: WavSampleFormat,
+    ) -> Result<Self> {
+        if channels == 0 {
+            return Err(AudioError::InvalidParameter(
+                "WAV output needs at least one channel".to_string(),
+            ));
+        }
+
+        let bits = sample_format.bits_per_sample();
+        let block_align = channels * (bits / 8);
+        let format_tag = match sample_format {
+            WavSampleFormat::Float32 => WAVE_FORMAT_IEEE_FLOAT,
+            WavSampleFormat::Pcm16 | WavSampleFormat::Pcm24 => WAVE_FORMAT_PCM,
+        };
+
+        let mut header = Vec::with_capacity(100);
+        header.extend_from_slice(b"RIFF");
+        header.extend_from_slice(&0u32.to_le_bytes());
+        header.extend_from_slice(b"WAVE");
+
+        // Placeholder that becomes the ds64 chunk if the file outgrows RIFF
+        header.extend_from_slice(b"JUNK");
+        header.extend_from_slice(&DS64_LEN.to_le_bytes());
+        header.extend_from_slice(&[0; DS64_LEN as usize]);
+
+        // The plain format struct is the most widely supported; more than
+        // two channels or more than 16 bits per sample need the extensible one
+        let extensible = channels > 2 || bits > 16;
+        header.extend_from_slice(b"fmt ");
+        header.extend_from_slice(&(if extensible { 40u32 } else { 16 }).to_le_bytes());
+        let tag = if extensible { WAVE_FORMAT_EXTENSIBLE } else { format_tag };
+        header.extend_from_slice(&tag.to_le_bytes());
+        header.extend_from_slice(&channels.to_le_bytes());
+        header.extend_from_slice(&sample_rate.to_le_bytes());
+        header.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+        header.extend_from_slice(&block_align.to_le_bytes());
+        header.extend_from_slice(&bits.to_le_bytes());
+        if extensible {
+            let channel_mask = if channels <= 18 { (1u32 << channels) - 1 } else { 0 };
+            header.extend_from_slice(&22u16.to_le_bytes());
+            header.extend_from_slice(&bits.to_le_bytes());
+            header.extend_from_slice(&channel_mask.to_le_bytes());
+            header.extend_from_slice(&format_tag.to_le_bytes());
+            header.extend_from_slice(&SUBTYPE_GUID_TAIL);
+        }
+
+        header.extend_from_slice(b"data");
+        let data_len_offset = header.len() as u64;
+        header.extend_from_slice(&0u32.to_le_bytes());
+
+        let mut writer = BufWriter::new(File::create(output_path)?);
+        writer.write_all(&header)?;
+
+        Ok(Self {
+            writer,
+            sample_format,
+            block_align: block_align as u64,
+            data_len_offset,
+            data_bytes: 0,
+            buffer: Vec::new(),
+            dither: None,
+        })
+    }
+
+    /// Dither integer samples instead of just rounding them (default: off)
+    pub fn dither(mut self, dither: bool) -> Self {
+        self.dither = dither.then(Dither::new);
+        self
+    }
+
+    /// Append interleaved samples (integer formats are rounded and clipped,
+    /// after dithering if that's on)
+    pub fn write_samples(&mut self, samples: &[f32]) -> Result<()> {
+        self.buffer.clear();
+        let dither = &mut self.dither;
+        let mut to_int = |sample: f32, bits: u16| match dither {
+            Some(dither) => dither.quantize(sample, bits),
+            None => quantize(sample, bits),
+        };
+        match self.sample_format {
+            WavSampleFormat::Float32 => {
+                for &sample in samples {
+                    self.buffer.extend_from_slice(&sample.to_le_bytes());
+                }
+            }
+            WavSampleFormat::Pcm16 => {
+                for &sample in samples {
+                    let value = to_int(sample, 16) as i16;
+                    self.buffer.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+            WavSampleFormat::Pcm24 => {
+                for &sample in samples {
+                    let value = to_int(sample, 24);
+                    self.buffer.extend_from_slice(&value.to_le_bytes()[..3]);
+                }
+            }
+        }
+        self.writer.write_all(&self.buffer)?;
+        self.data_bytes += self.buffer.len() as u64;
+        Ok(())
+    }
+
+    /// Number of frames written so far
+    pub fn frames_written(&self) -> u64 {
+        self.data_bytes / self.block_align
+    }
+
+    /// Finalize the file (writes header sizes)
+    ///
+    /// Switches the header to RF64 when the file is too big for RIFF.
+    pub fn finalize(self) -> Result<()> {
+        let rf64 = self.riff_len() > u32::MAX as u64;
+        self.finalize_as(rf64)
+    }
+
+    /// Size field of the outer chunk: everything after its 8-byte header
+    fn riff_len(&self) -> u64 {
+        self.data_len_offset + 4 + self.data_bytes.next_multiple_of(2) - 8
+    }
+
+    /// Finalize as plain WAV or as RF64
+    pub(crate) fn finalize_as(mut self, rf64: bool) -> Result<()> {
+        // Chunks are padded to an even length
+        if self.data_bytes % 2 == 1 {
+            self.writer.write_all(&[0])?;
+        }
+        let riff_len = self.riff_len();
+
+        if rf64 {
+            let mut ds64 = Vec::with_capacity(8 + DS64_LEN as usize);
+            ds64.extend_from_slice(b"ds64");
+            ds64.extend_from_slice(&DS64_LEN.to_le_bytes());
+            ds64.extend_from_slice(&riff_len.to_le_bytes());
+            ds64.extend_from_slice(&self.data_bytes.to_le_bytes());
+            ds64.extend_from_slice(&self.frames_written().to_le_bytes());
+            ds64.extend_from_slice(&0u32.to_le_bytes());
+
+            self.writer.seek(SeekFrom::Start(0))?;
+            self.writer.write_all(b"RF64")?;
+            self.writer.write_all(&u32::MAX.to_le_bytes())?;
+            self.writer.seek(SeekFrom::Start(DS64_OFFSET))?;
+            self.writer.write_all(&ds64)?;
+            self.writer.seek(SeekFrom::Start(self.data_len_offset))?;
+            self.writer.write_all(&u32::MAX.to_le_bytes())?;
+        } else {
+            self.writer.seek(SeekFrom::Start(4))?;
+            self.writer.write_all(&(riff_len as u32).to_le_bytes())?;
+            self.writer.seek(SeekFrom::Start(self.data_len_offset))?;
+            self.writer.write_all(&(self.data_bytes as u32).to_le_bytes())?;
+        }
+
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hound::{SampleFormat, WavReader};
+
+    #[test]
+    fn test_encode_and_decode_wav() {
+        // Create test audio
+        let test_audio = AudioData {
+            samples: vec![0.0, 0.5, -0.5, 1.0, -1.0],
+            sample_rate: 44100,
+            channels: 1,
+        };
+
+        // Encode to temporary file
+        let temp_path = std::env::temp_dir().join("test_encode.wav");
+        encode_wav(&test_audio, &temp_path).unwrap();
+
+        // Read it back
+        let mut reader = WavReader::open(&temp_path).unwrap();
+        let samples: Vec<f32> = reader.samples::<f32>().map(|s| s.unwrap()).collect();
+
+        // Verify
+        assert_eq!(samples.len(), test_audio.samples.len());
+        for (original, decoded) in test_audio.samples.iter().zip(samples.iter()) {
+            assert!((original - decoded).abs() < 0.0001);
+        }
+
+        // Cleanup
+        std::fs::remove_file(temp_path).ok();
+    }
+
+    #[test]
+    fn test_encode_pcm_bit_depths() {
+        let test_audio = AudioData {
+            samples: vec![0.0, 0.5, -0.5, 1.0, -1.0],
+            sample_rate: 44100,
+            channels: 1,
+        };
+
+        for (format, bits) in [(WavSampleFormat::Pcm16, 16), (WavSampleFormat::Pcm24, 24)] {
+            let temp_path = std::env::temp_dir().join(format!("test_encode_pcm{}.wav", bits));
+            encode_wav_with_format(&test_audio, &temp_path, format).unwrap();
+
+            let mut reader = WavReader::open(&temp_path).unwrap();
+            assert_eq!(reader.spec().bits_per_sample, bits);
+            assert_eq!(reader.spec().sample_format, SampleFormat::Int);
+
+            let scale = (1i64 << (bits - 1)) as f32;
+            let samples: Vec<f32> = reader
+                .samples::<i32>()
+                .map(|s| s.unwrap() as f32 / scale)
+                .collect();
+
+            assert_eq!(samples.len(), test_audio.samples.len());
+            for (original, decoded) in test_audio.samples.iter().zip(samples.iter()) {
+                assert!((original - decoded).abs() < 0.001, "{} vs {}", original, decoded);
+            }
+
+            std::fs::remove_file(temp_path).ok();
+        }
+    }
+
+    #[test]
+    fn test_dithered_pcm_stays_within_a_step() {
+        let samples: Vec<f32> = (0..4410).map(|i| (i as f32 * 0.01).sin() * 0.5).collect();
+        let audio = AudioData {
+            samples,
+            sample_rate: 44100,
+            channels: 1,
+        };
+        let temp_path = std::env::temp_dir().join("test_encode_dithered.wav");
+        let options = EncodeOptions::new().sample_format(WavSampleFormat::Pcm16);
+        encode_wav_with_options(&audio, &temp_path, &options).unwrap();
+
+        let mut reader = WavReader::open(&temp_path).unwrap();
+        assert_eq!(reader.spec().bits_per_sample, 16);
+        let written: Vec<i32> = reader.samples::<i32>().map(|s| s.unwrap()).collect();
+        assert_eq!(written.len(), audio.samples.len());
+        let rounded: Vec<i32> = audio.samples.iter().map(|&s| quantize(s, 16)).collect();
+        assert!(written.iter().zip(&rounded).all(|(w, r)| (w - r).abs() <= 1));
+        assert_ne!(written, rounded);
+
+        // Float output is never dithered
+        encode_wav_with_options(&audio, &temp_path, &EncodeOptions::new()).unwrap();
+        let mut reader = WavReader::open(&temp_path).unwrap();
+        let floats: Vec<f32> = reader.samples::<f32>().map(|s| s.unwrap()).collect();
+        assert_eq!(floats, audio.samples);
+
+        std::fs::remove_file(temp_path).ok();
+    }
+
+    #[test]
+    fn test_stream_writer_matches_one_shot() {
+        let samples: Vec<f32> = (0..1000).map(|i| (i as f32 / 1000.0) - 0.5).collect();
+        let path = std::env::temp_dir().join("test_stream_writer_chunks.wav");
+
+        let mut writer = WavStreamWriter::create(&path, 22050, 2, WavSampleFormat::Float32).unwrap();
+        for chunk in samples.chunks(128) {
+            writer.write_samples(chunk).unwrap();
+        }
+        assert_eq!(writer.frames_written(), 500);
+        writer.finalize().unwrap();
+
+        let mut reader = WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().channels, 2);
+        assert_eq!(reader.spec().sample_rate, 22050);
+        let decoded: Vec<f32> = reader.samples::<f32>().map(|s| s.unwrap()).collect();
+        assert_eq!(decoded, samples);
+
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,217 @@
+// src-tauri/src/audio/export.rs
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::decoder::{decode_audio_file_with_progress, get_audio_info};
+use crate::audio::dsp::{
+    normalize_loudness_with_progress, LoudnessStandard, LoudnessTarget, NormalizeReport,
+};
+use crate::audio::encoder::{
+    encode_audio_with_progress, OutputFormat, WavSampleFormat, MAX_FLAC_COMPRESSION,
+};
+use crate::audio::pipeline::{check_not_input, FileSource, Pipeline, WavSink};
+use crate::audio::types::AudioData;
+use crate::error::Result;
+use crate::memory::{decoded_size_bytes, MemoryBudget, ProcessingMode};
+use crate::progress::ProgressSink;
+
+/// A named way to export: the output format, plus loudness normalization
+/// for presets that want it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportPreset {
+    pub name: String,
+    pub format: OutputFormat,
+    /// Normalize to this loudness before encoding
+    #[serde(default)]
+    pub loudness: Option<LoudnessTarget>,
+}
+
+impl ExportPreset {
+    pub fn new(name: impl Into<String>, format: OutputFormat) -> Self {
+        Self {
+            name: name.into(),
+            format,
+            loudness: None,
+        }
+    }
+
+    pub fn loudness(mut self, target: LoudnessTarget) -> Self {
+        self.loudness = Some(target);
+        self
+    }
+
+    /// The presets every install has
+    pub fn builtin() -> Vec<Self> {
+        let presets = vec![
+            Self::new("Podcast", OutputFormat::Mp3 { bitrate_kbps: 128 })
+                .loudness(LoudnessStandard::Podcast.target()),
+            Self::new(
+                "Broadcast (EBU R128)",
+                OutputFormat::Wav {
+                    sample_format: WavSampleFormat::Pcm24,
+                },
+            )
+            .loudness(LoudnessStandard::EbuR128.target()),
+            Self::new(
+                "Archive",
+                OutputFormat::Flac {
+                    bits_per_sample: 24,
+                    compression_level: MAX_FLAC_COMPRESSION,
+                },
+            ),
+        ];
+        // Small enough to share a full-length service recording
+        #[cfg(feature = "opus")]
+        let presets = {
+            let mut presets = presets;
+            presets.push(
+                Self::new(
+                    "Sermon (Opus)",
+                    OutputFormat::Opus {
+                        bitrate_kbps: 32,
+                        speech: true,
+                    },
+                )
+                .loudness(LoudnessStandard::Podcast.target()),
+            );
+            presets
+        };
+        presets
+    }
+}
+
+/// Encode `audio` with a preset, normalizing its loudness first if the
+/// preset has a target
+///
+/// # Returns
+/// What normalizing did, for presets with a loudness target
+pub fn export_audio<P: AsRef<Path>>(
+    audio: &AudioData,
+    output_path: P,
+    preset: &ExportPreset,
+    progress: &mut dyn ProgressSink,
+) -> Result<Option<NormalizeReport>> {
+    let Some(target) = &preset.loudness else {
+        encode_audio_with_progress(audio, output_path, &preset.format, progress)?;
+        return Ok(None);
+    };
+    let (normalized, report) = normalize_loudness_with_progress(audio, target, progress)?;
+    encode_audio_with_progress(&normalized, output_path, &preset.format, progress)?;
+    Ok(Some(report))
+}
+
+/// Export a file with a preset, decoding it only when `budget` allows
+///
+/// A plain WAV export (no loudness target) streams from the decoder to
+/// disk when the file is over the budget, or always in low-memory mode.
+/// Everything else needs the whole file in memory, and normalizing a
+/// second copy, so those are checked against the budget before decoding.
+///
+/// # Errors
+/// [`AudioError::MemoryBudgetExceeded`] if an export that can't stream
+/// doesn't fit; [`AudioError::InvalidParameter`] if `output_path` is the
+/// input itself
+pub fn export_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    input_path: P,
+    output_path: Q,
+    preset: &ExportPreset,
+    budget: &MemoryBudget,
+    progress: &mut dyn ProgressSink,
+) -> Result<Option<NormalizeReport>> {
+    let (input_path, output_path) = (input_path.as_ref(), output_path.as_ref());
+    check_not_input(input_path, output_path)?;
+
+    let info = get_audio_info(input_path)?;
+    let decoded = decoded_size_bytes(info.duration_seconds, info.sample_rate, info.channels);
+    if let (OutputFormat::Wav { sample_format }, None) = (preset.format, &preset.loudness) {
+        if budget.mode_for(decoded) == ProcessingMode::Streaming {
+            progress.stage("encode");
+            let mut sink = WavSink::new(output_path, sample_format);
+            Pipeline::new(FileSource::open(input_path)?).run_with_progress(&mut sink, progress)?;
+            return Ok(None);
+        }
+    }
+    let copies = if preset.loudness.is_some() { 2 } else { 1 };
+    budget.check(decoded * copies)?;
+    let audio = decode_audio_file_with_progress(input_path, progress)?;
+    export_audio(&audio, output_path, preset, progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{decode_audio_file, measure_loudness};
+    use crate::error::AudioError;
+    use crate::progress::NoProgress;
+
+    #[test]
+    fn test_export_normalizes_when_the_preset_asks() {
+        let samples = (0..48000 * 3).map(|i| 0.01 * (i as f32 * 0.05).sin()).collect();
+        let audio = AudioData {
+            samples,
+            sample_rate: 48000,
+            channels: 1,
+        };
+        let path = std::env::temp_dir().join("hermeneia_test_export_preset.wav");
+        let broadcast = &ExportPreset::builtin()[1];
+        let report = export_audio(&audio, &path, broadcast, &mut NoProgress).unwrap();
+        assert!(report.is_some());
+
+        let written = decode_audio_file(&path).unwrap();
+        let lufs = measure_loudness(&written).unwrap().integrated_lufs.unwrap();
+        assert!((lufs + 23.0).abs() < 0.2, "got {lufs}");
+
+        let plain = ExportPreset::new("Plain", broadcast.format);
+        assert!(export_audio(&audio, &path, &plain, &mut NoProgress).unwrap().is_none());
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_export_file_streams_in_low_memory_mode() {
+        let audio = AudioData {
+            samples: (0..16000).map(|i| 0.25 * (i as f32 * 0.02).sin()).collect(),
+            sample_rate: 16000,
+            channels: 1,
+        };
+        let dir = std::env::temp_dir();
+        let input = dir.join("hermeneia_test_export_file_in.wav");
+        let output = dir.join("hermeneia_test_export_file_out.wav");
+        crate::audio::encode_wav(&audio, &input).unwrap();
+
+        // A budget too small to decode anything still streams plain WAV
+        let tiny = MemoryBudget::from_mb(0).with_low_memory(true);
+        let plain = ExportPreset::new(
+            "Plain",
+            OutputFormat::Wav {
+                sample_format: WavSampleFormat::Float32,
+            },
+        );
+        assert!(export_file(&input, &output, &plain, &tiny, &mut NoProgress).unwrap().is_none());
+        assert_eq!(decode_audio_file(&output).unwrap().frame_count(), 16000);
+
+        // Normalizing has no streaming path, so it has to fit
+        let broadcast = &ExportPreset::builtin()[1];
+        assert!(matches!(
+            export_file(&input, &output, broadcast, &tiny, &mut NoProgress),
+            Err(AudioError::MemoryBudgetExceeded { .. })
+        ));
+        assert!(export_file(&input, &input, &plain, &tiny, &mut NoProgress).is_err());
+        for path in [input, output] {
+            crate::audio::release_reader(&path);
+            std::fs::remove_file(path).ok();
+        }
+    }
+
+    #[test]
+    fn test_presets_round_trip_through_json() {
+        let json = serde_json::to_string(&ExportPreset::builtin()).unwrap();
+        let back: Vec<ExportPreset> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, ExportPreset::builtin());
+        let minimal: ExportPreset =
+            serde_json::from_str(r#"{"name":"x","format":{"format":"flac","bits_per_sample":16}}"#)
+                .unwrap();
+        assert_eq!(minimal.loudness, None);
+    }
+}
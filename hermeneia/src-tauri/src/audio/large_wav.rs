@@ -0,0 +1,421 @@
+// src-tauri/src/audio/large_wav.rs
+
+use std::io::{Seek, SeekFrom};
+
+use symphonia::core::audio::Channels;
+use symphonia::core::codecs::{
+    CodecParameters, CodecType, CODEC_TYPE_PCM_F32LE, CODEC_TYPE_PCM_F64LE,
+    CODEC_TYPE_PCM_S16LE, CODEC_TYPE_PCM_S24LE, CODEC_TYPE_PCM_S32LE, CODEC_TYPE_PCM_U8,
+};
+use symphonia::core::errors::{
+    decode_error, end_of_stream_error, seek_error, unsupported_error, Result as SymphoniaResult,
+    SeekErrorKind,
+};
+use symphonia::core::formats::{
+    Cue, FormatOptions, FormatReader, Packet, SeekMode, SeekTo, SeekedTo, Track,
+};
+use symphonia::core::io::{MediaSource, MediaSourceStream, ReadBytes};
+use symphonia::core::meta::{Metadata, MetadataLog};
+use symphonia::core::probe::{Descriptor, Instantiate, QueryDescriptor};
+use symphonia::core::units::TimeBase;
+
+/// Frames handed out per packet; the container itself isn't packetized
+const PACKET_FRAMES: u64 = 4096;
+
+/// Sony Wave64 GUIDs for the outer chunk, its form type and the chunks we read
+const W64_RIFF: [u8; 16] = [
+    0x72, 0x69, 0x66, 0x66, 0x2E, 0x91, 0xCF, 0x11, 0xA5, 0xD6, 0x28, 0xDB, 0x04, 0xC1, 0x00, 0x00,
+];
+const W64_WAVE: [u8; 16] = w64_guid(*b"wave");
+const W64_FMT: [u8; 16] = w64_guid(*b"fmt ");
+const W64_DATA: [u8; 16] = w64_guid(*b"data");
+
+/// Wave64 chunk GUIDs other than `riff` are the FourCC followed by a fixed tail
+const fn w64_guid(fourcc: [u8; 4]) -> [u8; 16] {
+    let tail = [0xF3, 0xAC, 0xD3, 0x11, 0x8C, 0xD1, 0x00, 0xC0, 0x4F, 0x8E, 0xDB, 0x8A];
+    let mut guid = [0; 16];
+    let mut i = 0;
+    while i < 16 {
+        guid[i] = if i < 4 { fourcc[i] } else { tail[i - 4] };
+        i += 1;
+    }
+    guid
+}
+
+const WAVE_FORMAT_PCM: u16 = 1;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// Reader for WAV files too big for a 32-bit RIFF header
+///
+/// Handles RF64 (EBU Tech 3306, also written by our encoder past 4GB), its
+/// BW64 variant and Sony Wave64, all holding plain PCM or float samples.
+/// Symphonia's own WAV reader only understands `RIFF`, so this is
+/// registered alongside it on the probe used by the reader pool.
+pub struct LargeWavReader {
+    reader: MediaSourceStream,
+    tracks: Vec<Track>,
+    cues: Vec<Cue>,
+    metadata: MetadataLog,
+    block_align: u64,
+    data_start: u64,
+    data_end: u64,
+}
+
+/// The parts of a `fmt ` chunk needed to decode the samples
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct WaveFormat {
+    codec: CodecType,
+    channels: u16,
+    sample_rate: u32,
+    block_align: u16,
+    bits_per_sample: u16,
+}
+
+impl WaveFormat {
+    fn read(source: &mut MediaSourceStream, len: u64) -> SymphoniaResult<Self> {
+        if len < 16 {
+            return decode_error("large wav: fmt chunk too short");
+        }
+        let mut format_tag = source.read_u16()?;
+        let channels = source.read_u16()?;
+        let sample_rate = source.read_u32()?;
+        let _byte_rate = source.read_u32()?;
+        let block_align = source.read_u16()?;
+        let bits_per_sample = source.read_u16()?;
+        let mut consumed = 16;
+
+        // WAVEFORMATEXTENSIBLE keeps the real format in its sub-format GUID
+        if format_tag == WAVE_FORMAT_EXTENSIBLE && len >= 40 {
+            let _cb_size = source.read_u16()?;
+            let _valid_bits = source.read_u16()?;
+            let _channel_mask = source.read_u32()?;
+            format_tag = source.read_u16()?;
+            consumed = 26;
+        }
+        source.ignore_bytes(len - consumed)?;
+
+        let codec = match (format_tag, bits_per_sample) {
+            (WAVE_FORMAT_PCM, 8) => CODEC_TYPE_PCM_U8,
+            (WAVE_FORMAT_PCM, 16) => CODEC_TYPE_PCM_S16LE,
+            (WAVE_FORMAT_PCM, 24) => CODEC_TYPE_PCM_S24LE,
+            (WAVE_FORMAT_PCM, 32) => CODEC_TYPE_PCM_S32LE,
+            (WAVE_FORMAT_IEEE_FLOAT, 32) => CODEC_TYPE_PCM_F32LE,
+            (WAVE_FORMAT_IEEE_FLOAT, 64) => CODEC_TYPE_PCM_F64LE,
+            _ => return unsupported_error("large wav: only PCM and float samples are supported"),
+        };
+        if channels == 0 || channels > 32 || sample_rate == 0 {
+            return decode_error("large wav: invalid channel count or sample rate");
+        }
+        if block_align as u32 != channels as u32 * (bits_per_sample as u32 / 8) {
+            return decode_error("large wav: block align doesn't match the sample format");
+        }
+
+        Ok(Self {
+            codec,
+            channels,
+            sample_rate,
+            block_align,
+            bits_per_sample,
+        })
+    }
+}
+
+/// Layout of the samples found while walking the header
+struct DataChunk {
+    format: WaveFormat,
+    len: u64,
+}
+
+/// Walk RF64/BW64 chunks up to the start of the data chunk
+fn read_rf64_header(source: &mut MediaSourceStream) -> SymphoniaResult<DataChunk> {
+    let _riff_len = source.read_u32()?;
+    if source.read_quad_bytes()? != *b"WAVE" {
+        return unsupported_error("large wav: RF64 form is not WAVE");
+    }
+
+    let mut ds64_data_len = None;
+    let mut format = None;
+    loop {
+        let id = source.read_quad_bytes()?;
+        let len = source.read_u32()? as u64;
+        match &id {
+            b"ds64" => {
+                if len < 24 {
+                    return decode_error("large wav: ds64 chunk too short");
+                }
+                let _riff_len = source.read_u64()?;
+                ds64_data_len = Some(source.read_u64()?);
+                source.ignore_bytes(len - 16)?;
+            }
+            b"fmt " => format = Some(WaveFormat::read(source, len)?),
+            b"data" => {
+                let format = match format {
+                    Some(format) => format,
+                    None => return decode_error("large wav: data chunk before fmt chunk"),
+                };
+                // A size of all ones means "see ds64"
+                let len = match (len, ds64_data_len) {
+                    (0xFFFF_FFFF, Some(real)) => real,
+                    (0xFFFF_FFFF, None) => return decode_error("large wav: missing ds64 chunk"),
+                    _ => len,
+                };
+                return Ok(DataChunk { format, len });
+            }
+            _ => source.ignore_bytes(len)?,
+        }
+        // Chunks are padded to an even length
+        if len % 2 == 1 {
+            source.ignore_bytes(1)?;
+        }
+    }
+}
+
+/// Walk Wave64 chunks up to the start of the data chunk
+fn read_w64_header(source: &mut MediaSourceStream) -> SymphoniaResult<DataChunk> {
+    // The marker was only the riff GUID's first quad
+    let mut rest = [0; 12];
+    source.read_buf_exact(&mut rest)?;
+    if rest != W64_RIFF[4..] {
+        return unsupported_error("large wav: not a Wave64 file");
+    }
+    let _riff_len = source.read_u64()?;
+    let mut form = [0; 16];
+    source.read_buf_exact(&mut form)?;
+    if form != W64_WAVE {
+        return unsupported_error("large wav: Wave64 form is not wave");
+    }
+
+    let mut format = None;
+    loop {
+        let mut guid = [0; 16];
+        source.read_buf_exact(&mut guid)?;
+        // Wave64 sizes include the 24-byte chunk header
+        let len = match source.read_u64()?.checked_sub(24) {
+            Some(len) => len,
+            None => return decode_error("large wav: Wave64 chunk too short"),
+        };
+
+        if guid == W64_DATA {
+            let format = match format {
+                Some(format) => format,
+                None => return decode_error("large wav: data chunk before fmt chunk"),
+            };
+            return Ok(DataChunk { format, len });
+        } else if guid == W64_FMT {
+            format = Some(WaveFormat::read(source, len)?);
+        } else {
+            source.ignore_bytes(len)?;
+        }
+        // Chunks are aligned to 8 bytes
+        source.ignore_bytes((8 - (len + 24) % 8) % 8)?;
+    }
+}
+
+impl QueryDescriptor for LargeWavReader {
+    fn query() -> &'static [Descriptor] {
+        &[Descriptor {
+            short_name: "large_wav",
+            long_name: "RF64 / Wave64 (WAV beyond 4GB)",
+            extensions: &["wav", "rf64", "w64"],
+            mime_types: &["audio/x-rf64", "audio/x-w64"],
+            markers: &[b"RF64", b"BW64", &W64_RIFF],
+            score: Self::score,
+            inst: Instantiate::Format(|source, options| {
+                Ok(Box::new(LargeWavReader::try_new(source, options)?))
+            }),
+        }]
+    }
+
+    fn score(_context: &[u8]) -> u8 {
+        255
+    }
+}
+
+impl FormatReader for LargeWavReader {
+    fn try_new(mut source: MediaSourceStream, _options: &FormatOptions) -> SymphoniaResult<Self> {
+        let marker = source.read_quad_bytes()?;
+        let data = match &marker {
+            b"RF64" | b"BW64" => read_rf64_header(&mut source)?,
+            _ if marker == W64_RIFF[..4] => read_w64_header(&mut source)?,
+            _ => return unsupported_error("large wav: not an RF64 or Wave64 file"),
+        };
+
+        let format = data.format;
+        let block_align = format.block_align as u64;
+        let data_start = source.pos();
+        // Don't trust the header past the end of a truncated file
+        let data_len = match source.byte_len() {
+            Some(file_len) => data.len.min(file_len.saturating_sub(data_start)),
+            None => data.len,
+        };
+        let n_frames = data_len / block_align;
+
+        let mut codec_params = CodecParameters::new();
+        codec_params
+            .for_codec(format.codec)
+            .with_sample_rate(format.sample_rate)
+            .with_time_base(TimeBase::new(1, format.sample_rate))
+            .with_n_frames(n_frames)
+            .with_max_frames_per_packet(PACKET_FRAMES)
+            .with_bits_per_sample(format.bits_per_sample as u32)
+            .with_bits_per_coded_sample(format.bits_per_sample as u32);
+        if let Some(channels) = Channels::from_bits(((1u64 << format.channels) - 1) as u32) {
+            codec_params.with_channels(channels);
+        }
+
+        Ok(Self {
+            reader: source,
+            tracks: vec![Track::new(0, codec_params)],
+            cues: Vec::new(),
+            metadata: MetadataLog::default(),
+            block_align,
+            data_start,
+            data_end: data_start + n_frames * block_align,
+        })
+    }
+
+    fn next_packet(&mut self) -> SymphoniaResult<Packet> {
+        let pos = self.reader.pos();
+        let frames = (self.data_end.saturating_sub(pos) / self.block_align).min(PACKET_FRAMES);
+        if frames == 0 {
+            return end_of_stream_error();
+        }
+
+        let ts = (pos - self.data_start) / self.block_align;
+        let data = self
+            .reader
+            .read_boxed_slice_exact((frames * self.block_align) as usize)?;
+        Ok(Packet::new_from_boxed_slice(0, ts, frames, data))
+    }
+
+    fn metadata(&mut self) -> Metadata<'_> {
+        self.metadata.metadata()
+    }
+
+    fn cues(&self) -> &[Cue] {
+        &self.cues
+    }
+
+    fn tracks(&self) -> &[Track] {
+        &self.tracks
+    }
+
+    fn seek(&mut self, _mode: SeekMode, to: SeekTo) -> SymphoniaResult<SeekedTo> {
+        let params = &self.tracks[0].codec_params;
+        let ts = match to {
+            SeekTo::TimeStamp { ts, .. } => ts,
+            SeekTo::Time { time, .. } => match params.time_base {
+                Some(time_base) => time_base.calc_timestamp(time),
+                None => return seek_error(SeekErrorKind::Unseekable),
+            },
+        };
+        if params.n_frames.is_some_and(|n_frames| ts > n_frames) {
+            return seek_error(SeekErrorKind::OutOfRange);
+        }
+
+        // Every frame starts a packet, so seeking is exact
+        self.reader
+            .seek(SeekFrom::Start(self.data_start + ts * self.block_align))?;
+        Ok(SeekedTo {
+            track_id: 0,
+            actual_ts: ts,
+            required_ts: ts,
+        })
+    }
+
+    fn into_inner(self: Box<Self>) -> MediaSourceStream {
+        self.reader
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::decoder::decode_audio_file;
+    use crate::audio::encoder::{WavSampleFormat, WavStreamWriter};
+
+    fn samples(frames: usize, channels: usize) -> Vec<f32> {
+        (0..frames * channels)
+            .map(|i| ((i % 200) as f32 / 100.0) - 1.0)
+            .collect()
+    }
+
+    /// Build a Wave64 file by hand: riff, wave, fmt and data chunks
+    fn write_w64(path: &std::path::Path, samples: &[f32], channels: u16, rate: u32) {
+        let mut fmt = Vec::new();
+        fmt.extend_from_slice(&WAVE_FORMAT_IEEE_FLOAT.to_le_bytes());
+        fmt.extend_from_slice(&channels.to_le_bytes());
+        fmt.extend_from_slice(&rate.to_le_bytes());
+        fmt.extend_from_slice(&(rate * channels as u32 * 4).to_le_bytes());
+        fmt.extend_from_slice(&(channels * 4).to_le_bytes());
+        fmt.extend_from_slice(&32u16.to_le_bytes());
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+
+        let mut file = Vec::new();
+        file.extend_from_slice(&W64_RIFF);
+        file.extend_from_slice(&0u64.to_le_bytes());
+        file.extend_from_slice(&W64_WAVE);
+        for (guid, body) in [(W64_FMT, &fmt), (W64_DATA, &data)] {
+            file.extend_from_slice(&guid);
+            file.extend_from_slice(&(body.len() as u64 + 24).to_le_bytes());
+            file.extend_from_slice(body);
+            file.resize(file.len().next_multiple_of(8), 0);
+        }
+        let riff_len = file.len() as u64;
+        file[16..24].copy_from_slice(&riff_len.to_le_bytes());
+        std::fs::write(path, file).unwrap();
+    }
+
+    #[test]
+    fn test_w64_guids() {
+        assert_eq!(&W64_WAVE[..4], b"wave");
+        assert_eq!(W64_FMT[4..], W64_DATA[4..]);
+    }
+
+    #[test]
+    fn test_decodes_rf64_from_stream_writer() {
+        let path = std::env::temp_dir().join("hermeneia_test_large.rf64.wav");
+        let original = samples(10_000, 2);
+        for format in [WavSampleFormat::Float32, WavSampleFormat::Pcm16] {
+            let mut writer = WavStreamWriter::create(&path, 48000, 2, format).unwrap();
+            writer.write_samples(&original).unwrap();
+            writer.finalize_as(true).unwrap();
+            assert_eq!(&std::fs::read(&path).unwrap()[..4], b"RF64");
+
+            let decoded = decode_audio_file(&path).unwrap();
+            assert_eq!((decoded.sample_rate, decoded.channels), (48000, 2));
+            assert_eq!(decoded.samples.len(), original.len());
+            for (a, b) in original.iter().zip(&decoded.samples) {
+                assert!((a - b).abs() < 1e-4, "{} vs {}", a, b);
+            }
+            crate::audio::release_reader(&path);
+        }
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_decodes_w64() {
+        let path = std::env::temp_dir().join("hermeneia_test_large.w64");
+        let original = samples(5_000, 1);
+        write_w64(&path, &original, 1, 22050);
+
+        let decoded = decode_audio_file(&path).unwrap();
+        assert_eq!((decoded.sample_rate, decoded.channels), (22050, 1));
+        assert_eq!(decoded.samples, original);
+        crate::audio::release_reader(&path);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_seek_is_frame_exact() {
+        let path = std::env::temp_dir().join("hermeneia_test_large_seek.w64");
+        let original = samples(20_000, 1);
+        write_w64(&path, &original, 1, 10_000);
+
+        let clip = crate::audio::decode_audio_range(&path, 1.0, 1.5).unwrap();
+        assert_eq!(clip.samples, original[10_000..15_000]);
+        crate::audio::release_reader(&path);
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,74 @@
+// src-tauri/src/audio/markers.rs
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::time::Timestamp;
+
+/// A named point or range on a file's timeline
+///
+/// Chapters, cue sheet tracks and user bookmarks are all markers. A marker
+/// without an `end` is a point; chapter formats that need one take it to
+/// last until the next marker (see [`close_markers`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Marker {
+    pub start: Timestamp,
+    /// End of the range, for markers that cover one
+    #[serde(default)]
+    pub end: Option<Timestamp>,
+    pub title: String,
+}
+
+impl Marker {
+    /// A point marker
+    pub fn new(start: Timestamp, title: impl Into<String>) -> Self {
+        Self {
+            start,
+            end: None,
+            title: title.into(),
+        }
+    }
+
+    /// Make the marker cover `start..end`
+    pub fn with_end(mut self, end: Timestamp) -> Self {
+        self.end = Some(end);
+        self
+    }
+}
+
+/// Sort markers by start, keeping the given order for equal starts
+pub fn sort_markers(markers: &mut [Marker]) {
+    markers.sort_by_key(|m| m.start);
+}
+
+/// Give every marker an end: the start of the next one, or `duration` for
+/// the last
+///
+/// Markers must be sorted; existing ends are kept.
+pub fn close_markers(markers: &mut [Marker], duration: Timestamp) {
+    let starts: Vec<Timestamp> = markers.iter().skip(1).map(|m| m.start).collect();
+    let ends = starts.into_iter().chain(std::iter::once(duration));
+    for (marker, end) in markers.iter_mut().zip(ends) {
+        marker.end.get_or_insert(end.max(marker.start));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_close_markers_fills_missing_ends() {
+        let at = Timestamp::from_seconds;
+        let mut markers = vec![
+            Marker::new(at(60.0), "Two"),
+            Marker::new(at(0.0), "One").with_end(at(30.0)),
+            Marker::new(at(90.0), "Three"),
+        ];
+        sort_markers(&mut markers);
+        close_markers(&mut markers, at(120.0));
+
+        let ends: Vec<_> = markers.iter().map(|m| m.end.unwrap().as_seconds()).collect();
+        assert_eq!(ends, vec![30.0, 90.0, 120.0]);
+        assert_eq!(markers[0].title, "One");
+    }
+}
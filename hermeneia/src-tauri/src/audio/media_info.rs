@@ -0,0 +1,162 @@
+// src-tauri/src/audio/media_info.rs
+// File-level details for AudioInfo: container, codec profile and tags
+
+use symphonia::core::codecs::{
+    CodecParameters, CodecType, CODEC_TYPE_AAC, CODEC_TYPE_MP1, CODEC_TYPE_MP2, CODEC_TYPE_MP3,
+};
+use symphonia::core::meta::{MetadataRevision, StandardTagKey};
+
+use super::types::TagSummary;
+
+/// Bytes at the start of a file needed to recognize its container
+pub(crate) const HEADER_LEN: usize = 12;
+
+/// What a probe learns about a file besides its tracks
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ProbeDetails {
+    /// Container recognized from the file header, if any
+    pub(crate) container: Option<&'static str>,
+    pub(crate) tags: TagSummary,
+}
+
+/// Name the container from the first [`HEADER_LEN`] bytes of a file
+///
+/// Bare MPEG and ADTS streams (possibly behind an ID3 tag) have no
+/// container header; see [`stream_container`] for those.
+pub(crate) fn sniff_container(header: &[u8]) -> Option<&'static str> {
+    let magic = |at: usize, bytes: &[u8]| header.get(at..at + bytes.len()) == Some(bytes);
+    if magic(0, b"RIFF") && magic(8, b"WAVE") {
+        Some("WAV")
+    } else if magic(0, b"RF64") {
+        Some("RF64")
+    } else if magic(0, b"riff") {
+        Some("Wave64")
+    } else if magic(0, b"fLaC") {
+        Some("FLAC")
+    } else if magic(0, b"OggS") {
+        Some("Ogg")
+    } else if magic(4, b"ftyp") {
+        Some("MP4")
+    } else if magic(0, &[0x1a, 0x45, 0xdf, 0xa3]) {
+        Some("Matroska")
+    } else if magic(0, b"caff") {
+        Some("CAF")
+    } else if magic(0, b"FORM") {
+        Some("AIFF")
+    } else {
+        None
+    }
+}
+
+/// Container name for a file whose header wasn't recognized, from its codec
+pub(crate) fn stream_container(codec: CodecType) -> Option<&'static str> {
+    match codec {
+        CODEC_TYPE_MP1 | CODEC_TYPE_MP2 | CODEC_TYPE_MP3 => Some("MPEG audio"),
+        CODEC_TYPE_AAC => Some("ADTS"),
+        _ => None,
+    }
+}
+
+/// Codec profile declared in the stream's setup data
+///
+/// Only AAC declares one that symphonia passes through (the audio object
+/// type in its AudioSpecificConfig). Profile levels aren't exposed by any
+/// of the demuxers.
+pub(crate) fn codec_profile(params: &CodecParameters) -> Option<String> {
+    if params.codec != CODEC_TYPE_AAC {
+        return None;
+    }
+    let object_type = params.extra_data.as_deref()?.first()? >> 3;
+    let name = match object_type {
+        1 => "AAC Main",
+        2 => "AAC LC",
+        3 => "AAC SSR",
+        4 => "AAC LTP",
+        5 => "HE-AAC",
+        23 => "AAC LD",
+        29 => "HE-AAC v2",
+        39 => "AAC ELD",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+/// Summarize the tags of each revision, earlier revisions taking precedence
+pub(crate) fn summarize_tags<'a>(
+    revisions: impl IntoIterator<Item = &'a MetadataRevision>,
+) -> TagSummary {
+    let mut summary = TagSummary::default();
+    for revision in revisions {
+        summary.has_picture |= !revision.visuals().is_empty();
+        for tag in revision.tags() {
+            let field = match tag.std_key {
+                Some(StandardTagKey::TrackTitle) => &mut summary.title,
+                Some(StandardTagKey::Artist) => &mut summary.artist,
+                Some(StandardTagKey::Album) => &mut summary.album,
+                Some(StandardTagKey::AlbumArtist) => &mut summary.album_artist,
+                Some(StandardTagKey::Date | StandardTagKey::ReleaseDate) => &mut summary.date,
+                Some(StandardTagKey::Genre) => &mut summary.genre,
+                Some(StandardTagKey::TrackNumber) => &mut summary.track_number,
+                Some(StandardTagKey::Comment) => &mut summary.comment,
+                _ => {
+                    summary.other_tags += 1;
+                    continue;
+                }
+            };
+            let value = tag.value.to_string();
+            if field.is_none() && !value.trim().is_empty() {
+                *field = Some(value.trim().to_string());
+            }
+        }
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use symphonia::core::meta::{MetadataBuilder, Tag, Value};
+
+    fn tag(std_key: StandardTagKey, key: &str, value: &str) -> Tag {
+        Tag::new(Some(std_key), key, Value::from(value))
+    }
+
+    #[test]
+    fn test_sniff_container() {
+        assert_eq!(sniff_container(b"RIFF\0\0\0\0WAVEfmt "), Some("WAV"));
+        assert_eq!(sniff_container(b"\0\0\0\x20ftypM4A "), Some("MP4"));
+        assert_eq!(sniff_container(b"OggS\0\x02"), Some("Ogg"));
+        assert_eq!(sniff_container(b"ID3\x04\0\0\0\0\0\0\0\0"), None);
+        assert_eq!(sniff_container(b"RI"), None);
+        assert_eq!(stream_container(CODEC_TYPE_MP3), Some("MPEG audio"));
+    }
+
+    #[test]
+    fn test_aac_profile_from_audio_specific_config() {
+        let mut params = CodecParameters::new();
+        params.for_codec(CODEC_TYPE_AAC).with_extra_data(vec![0x12, 0x10].into());
+        assert_eq!(codec_profile(&params).as_deref(), Some("AAC LC"));
+        params.for_codec(CODEC_TYPE_MP3);
+        assert_eq!(codec_profile(&params), None);
+    }
+
+    #[test]
+    fn test_first_revision_wins_and_others_are_counted() {
+        let mut id3 = MetadataBuilder::new();
+        id3.add_tag(tag(StandardTagKey::TrackTitle, "TIT2", "Sermon on the Mount"));
+        id3.add_tag(tag(StandardTagKey::Artist, "TPE1", "  "));
+        id3.add_tag(tag(StandardTagKey::Bpm, "TBPM", "90"));
+        let mut vorbis = MetadataBuilder::new();
+        vorbis.add_tag(tag(StandardTagKey::TrackTitle, "TITLE", "Other title"));
+        vorbis.add_tag(tag(StandardTagKey::Artist, "ARTIST", "Reader"));
+        vorbis.add_tag(tag(StandardTagKey::Date, "DATE", "2024"));
+
+        let summary = summarize_tags([&id3.metadata(), &vorbis.metadata()]);
+        assert_eq!(summary.title.as_deref(), Some("Sermon on the Mount"));
+        // A blank value doesn't hide a later one
+        assert_eq!(summary.artist.as_deref(), Some("Reader"));
+        assert_eq!(summary.date.as_deref(), Some("2024"));
+        assert_eq!(summary.other_tags, 1);
+        assert!(!summary.has_picture);
+    }
+}
@@ -1,14 +1,89 @@
 // src-tauri/src/audio/mod.rs
 
+pub mod analysis;
+pub mod channels;
+pub mod chapters;
 pub mod decoder;
+pub mod dsp;
+pub mod edit;
 pub mod encoder;
+pub mod export;
+pub(crate) mod large_wav;
+pub mod markers;
+pub(crate) mod media_info;
+pub mod pipeline;
+pub(crate) mod probe_cache;
+pub mod raw;
+pub(crate) mod reader_pool;
+pub mod redact;
+pub mod render;
+pub mod resample;
+pub mod split;
+pub mod time;
 pub mod trim;
 pub mod types;
+pub(crate) mod watchdog;
 pub mod waveform;
 
 // Re-export commonly used items
-pub use decoder::{decode_audio_file, get_audio_info};
-pub use encoder::encode_wav;
-pub use trim::trim_audio;
-pub use types::{AudioData, AudioInfo, TrimParams, WaveformPeaks};
-pub use waveform::extract_waveform_peaks;
\ No newline at end of file
+pub use analysis::{
+    compare_audio, compare_audio_with_progress, compare_fingerprints, detect_clipping,
+    detect_clipping_with_progress, detect_silence, detect_silence_with_progress,
+    fingerprint_audio, fingerprint_audio_with_progress, measure_loudness,
+    measure_loudness_with_progress, measure_quality, measure_quality_with_progress, ClipRegion,
+    ClippingOptions, ClippingReport, CompareOptions, ComparisonReport, Fingerprint,
+    FingerprintMatch, FingerprintOptions, LoudnessMeasurement, MismatchRegion, QualityOptions,
+    QualityReport, SilenceOptions, SilenceRegion,
+};
+pub use channels::{remix_channels, ChannelMixer};
+pub use chapters::{
+    read_audacity_labels, read_chapters, read_cue_sheet, write_audacity_labels, write_chapters,
+    write_cue_sheet, CueSheet,
+};
+pub use decoder::{
+    decode_audio_chunks, decode_audio_chunks_with, decode_audio_file,
+    decode_audio_file_with_progress, decode_audio_range, decode_audio_range_with, get_audio_info,
+    AudioChunks,
+};
+pub use dsp::{
+    apply_gain, db_to_linear, denoise, limit_true_peak, linear_to_db, normalize_loudness,
+    normalize_loudness_with_progress, true_peak, Denoiser, LoudnessStandard, LoudnessTarget,
+    NormalizeReport,
+};
+pub use edit::{render_edit_list, EditList, EditOperation, FadeDirection};
+pub use encoder::{
+    encode_audio, encode_audio_with_progress, encode_flac, encode_mp3, encode_wav,
+    encode_wav_with_format, encode_wav_with_options, EncodeOptions, OutputFormat,
+    WavSampleFormat, WavStreamWriter, DEFAULT_FLAC_COMPRESSION, MAX_FLAC_COMPRESSION,
+};
+#[cfg(feature = "opus")]
+pub use encoder::{encode_opus, encode_opus_speech};
+pub use export::{export_audio, export_file, ExportPreset};
+pub use markers::Marker;
+pub use render::{
+    render_waveform_png, render_waveform_rgba, render_waveform_svg, write_waveform_image,
+    write_waveform_svg, Color, RenderOptions,
+};
+pub use reader_pool::{clear_reader_pool, release_reader, set_low_memory_caches};
+pub use redact::{
+    redact_audio, redact_file, redaction_log_path, RedactOptions, RedactedRange, RedactionLog,
+    RedactionStyle,
+};
+pub use resample::{resample_audio, StreamResampler};
+pub use split::{
+    export_file_segments, export_segments, extract_segment, segment_paths, split_at_markers,
+    split_by_silence, split_every, Segment,
+};
+pub use time::{AudioDuration, Timestamp};
+pub use trim::{
+    concatenate, extract_segments, join_file_segments, trim_audio, trim_file, trim_file_with,
+    TrimOutput, TrimSummary,
+};
+pub use types::{
+    AudioData, AudioInfo, FadeCurve, PlanarAudio, TagSummary, TrimParams, TrimPoint,
+    WaveformPeaks,
+};
+pub use waveform::{
+    extract_waveform_peaks, extract_waveform_peaks_with_progress, stream_waveform_peaks,
+    PeakChunk, WaveformOptions, STREAM_CHUNKS,
+};
\ No newline at end of file
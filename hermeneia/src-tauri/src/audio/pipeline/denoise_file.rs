@@ -0,0 +1,90 @@
+// src-tauri/src/audio/pipeline/denoise_file.rs
+
+use std::path::Path;
+
+use crate::audio::encoder::OutputFormat;
+use crate::audio::pipeline::{
+    check_not_input, Denoise, EncodeSink, FileSource, Pipeline, PipelineSummary,
+};
+use crate::error::Result;
+use crate::progress::ProgressSink;
+
+/// Write a copy of a file with its background noise turned down
+///
+/// `strength` runs from 0.0 (unchanged) to 1.0 (full reduction); see
+/// [`crate::audio::Denoiser`]. Streams through the [`Pipeline`], so WAV
+/// output works for files of any length. Reports the "pipeline" stage.
+///
+/// # Errors
+/// [`AudioError::InvalidParameter`] if `output_path` is the input itself
+/// or `strength` is out of range
+///
+/// # Example
+/// ```no_run
+/// use hermeneia_lib::audio::pipeline::denoise_file;
+/// use hermeneia_lib::audio::{OutputFormat, WavSampleFormat};
+/// use hermeneia_lib::progress::NoProgress;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let format = OutputFormat::Wav {
+///     sample_format: WavSampleFormat::Pcm24,
+/// };
+/// denoise_file("sermon.wav", "sermon_clean.wav", 0.7, &format, &mut NoProgress)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn denoise_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    input_path: P,
+    output_path: Q,
+    strength: f32,
+    format: &OutputFormat,
+    progress: &mut dyn ProgressSink,
+) -> Result<PipelineSummary> {
+    let (input_path, output_path) = (input_path.as_ref(), output_path.as_ref());
+    check_not_input(input_path, output_path)?;
+
+    let source = FileSource::open(input_path)?;
+    progress.stage("pipeline");
+    let mut sink = EncodeSink::new(output_path, *format);
+    Pipeline::new(source)
+        .then(Denoise::new(strength))
+        .run_with_progress(&mut sink, progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::encoder::WavSampleFormat;
+    use crate::audio::{encode_wav, get_audio_info, AudioData};
+    use crate::error::AudioError;
+    use crate::progress::NoProgress;
+
+    #[test]
+    fn test_denoise_file_keeps_the_format() {
+        let dir = std::env::temp_dir();
+        let input = dir.join("hermeneia_test_denoise_file_in.wav");
+        let output = dir.join("hermeneia_test_denoise_file_out.wav");
+        let audio = AudioData {
+            samples: (0..22050 * 2).map(|i| 0.3 * (i as f32 * 0.05).sin()).collect(),
+            sample_rate: 22050,
+            channels: 2,
+        };
+        encode_wav(&audio, &input).unwrap();
+
+        let format = OutputFormat::Wav {
+            sample_format: WavSampleFormat::Pcm16,
+        };
+        let summary = denoise_file(&input, &output, 0.5, &format, &mut NoProgress).unwrap();
+        assert_eq!(summary.frames_in, summary.frames_out);
+        let info = get_audio_info(&output).unwrap();
+        assert_eq!((info.sample_rate, info.channels), (22050, 2));
+        assert!((info.duration_seconds - 1.0).abs() < 0.001);
+
+        let result = denoise_file(&input, &input, 0.5, &format, &mut NoProgress);
+        assert!(matches!(result, Err(AudioError::InvalidParameter(_))));
+        assert!(denoise_file(&input, &output, 1.5, &format, &mut NoProgress).is_err());
+
+        std::fs::remove_file(input).ok();
+        std::fs::remove_file(output).ok();
+    }
+}
@@ -0,0 +1,314 @@
+// src-tauri/src/audio/pipeline/mod.rs
+// Chained source → transforms → sink processing, one chunk at a time
+
+pub mod denoise_file;
+pub mod registry;
+pub mod resample_file;
+pub mod sink;
+pub mod source;
+pub mod transform;
+#[cfg(feature = "user-effects")]
+pub mod user_effects;
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::types::AudioData;
+use crate::error::{AudioError, Result};
+use crate::profile;
+use crate::progress::{check_cancelled, NoProgress, ProgressSink};
+
+pub use denoise_file::denoise_file;
+pub use registry::{effects, Effect, EffectInfo, EffectParams, EffectRegistry, ParamInfo};
+pub use resample_file::{resample_file, ResampleSpec};
+pub use sink::{EncodeSink, MemorySink, WavSink};
+pub use source::{FileSource, MemorySource};
+pub use transform::{Chain, Denoise, Gain, Remix, Resample};
+
+/// Format of the audio flowing between two nodes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamSpec {
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl StreamSpec {
+    /// Format of an in-memory buffer
+    pub fn of(audio: &AudioData) -> Self {
+        Self {
+            sample_rate: audio.sample_rate,
+            channels: audio.channels,
+        }
+    }
+}
+
+/// Where a pipeline's audio comes from
+pub trait Source {
+    /// Format of every chunk this source produces
+    fn spec(&self) -> StreamSpec;
+
+    /// Total frames, when known up front (used for progress)
+    fn total_frames(&self) -> Option<u64> {
+        None
+    }
+
+    /// Next chunk of audio, or `None` at the end of the stream
+    fn next_chunk(&mut self) -> Result<Option<AudioData>>;
+}
+
+/// A processing step between the source and the sink
+pub trait Transform {
+    /// Short name for logs and errors
+    fn name(&self) -> &'static str;
+
+    /// Prepare for input in `input` format and report the output format
+    ///
+    /// Called once before any audio flows; fails if the node can't handle
+    /// the format.
+    fn configure(&mut self, input: StreamSpec) -> Result<StreamSpec>;
+
+    /// Process one chunk; the result may be shorter or empty for nodes
+    /// that buffer internally
+    fn process(&mut self, chunk: AudioData) -> Result<AudioData>;
+
+    /// Emit anything still buffered once the source is exhausted
+    fn flush(&mut self) -> Result<Option<AudioData>> {
+        Ok(None)
+    }
+}
+
+impl<T: Transform + ?Sized> Transform for Box<T> {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn configure(&mut self, input: StreamSpec) -> Result<StreamSpec> {
+        (**self).configure(input)
+    }
+
+    fn process(&mut self, chunk: AudioData) -> Result<AudioData> {
+        (**self).process(chunk)
+    }
+
+    fn flush(&mut self) -> Result<Option<AudioData>> {
+        (**self).flush()
+    }
+}
+
+/// Where a pipeline's audio ends up
+pub trait Sink {
+    /// Called once with the final format before the first chunk
+    fn open(&mut self, spec: StreamSpec) -> Result<()>;
+
+    fn write(&mut self, chunk: &AudioData) -> Result<()>;
+
+    /// Called once after the last chunk (e.g. to finalize headers)
+    fn finish(&mut self) -> Result<()>;
+}
+
+/// Frame counts from a finished run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PipelineSummary {
+    /// Format the sink received
+    pub output: StreamSpec,
+    pub frames_in: u64,
+    pub frames_out: u64,
+}
+
+/// A source followed by any number of transforms, run into a sink
+///
+/// Audio moves through in the source's chunk size, so memory use depends
+/// on the chunk size and any buffering in the transforms, not on the
+/// length of the input.
+///
+/// # Example
+/// ```no_run
+/// use hermeneia_lib::audio::pipeline::{FileSource, Gain, Pipeline, Remix, Resample, WavSink};
+/// use hermeneia_lib::audio::WavSampleFormat;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut sink = WavSink::new("sermon_16k.wav", WavSampleFormat::Pcm16);
+/// Pipeline::new(FileSource::open("sermon.flac")?)
+///     .then(Remix::new(1))
+///     .then(Resample::new(16000))
+///     .then(Gain::new(-3.0))
+///     .run(&mut sink)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Pipeline {
+    source: Box<dyn Source>,
+    transforms: Vec<Box<dyn Transform>>,
+}
+
+impl Pipeline {
+    pub fn new(source: impl Source + 'static) -> Self {
+        Self {
+            source: Box::new(source),
+            transforms: Vec::new(),
+        }
+    }
+
+    /// Append a transform after the ones already added
+    pub fn then(mut self, transform: impl Transform + 'static) -> Self {
+        self.transforms.push(Box::new(transform));
+        self
+    }
+
+    /// Run the whole stream into `sink`
+    pub fn run(self, sink: &mut dyn Sink) -> Result<PipelineSummary> {
+        self.run_with_progress(sink, &mut NoProgress)
+    }
+
+    /// Run like [`Pipeline::run`], reporting the fraction of the source
+    /// consumed (0.0 to 1.0)
+    ///
+    /// Cancellation is checked before each chunk; the sink is left
+    /// unfinished when `progress` asks to stop.
+    pub fn run_with_progress(
+        mut self,
+        sink: &mut dyn Sink,
+        progress: &mut dyn ProgressSink,
+    ) -> Result<PipelineSummary> {
+        let _stage = profile::stage("pipeline");
+        let mut spec = self.source.spec();
+        for transform in &mut self.transforms {
+            spec = transform.configure(spec).map_err(|e| match e {
+                AudioError::InvalidParameter(msg) => {
+                    AudioError::InvalidParameter(format!("{}: {}", transform.name(), msg))
+                }
+                other => other,
+            })?;
+        }
+        sink.open(spec)?;
+
+        let total = self.source.total_frames().filter(|&n| n > 0);
+        let mut frames_in = 0u64;
+        let mut frames_out = 0u64;
+
+        while let Some(chunk) = self.source.next_chunk()? {
+            check_cancelled(progress)?;
+            frames_in += chunk.frame_count() as u64;
+            if let Some(out) = push(&mut self.transforms, chunk)? {
+                frames_out += out.frame_count() as u64;
+                sink.write(&out)?;
+            }
+            if let Some(total) = total {
+                progress.progress((frames_in as f64 / total as f64).min(1.0));
+            }
+        }
+
+        // Flush front to back, so what one node releases still goes through
+        // the ones after it before they flush
+        for i in 0..self.transforms.len() {
+            let (current, rest) = self.transforms[i..].split_at_mut(1);
+            if let Some(tail) = current[0].flush()? {
+                if let Some(out) = push(rest, tail)? {
+                    frames_out += out.frame_count() as u64;
+                    sink.write(&out)?;
+                }
+            }
+        }
+
+        sink.finish()?;
+        progress.progress(1.0);
+
+        Ok(PipelineSummary {
+            output: spec,
+            frames_in,
+            frames_out,
+        })
+    }
+}
+
+/// Send a chunk through `transforms`; `None` if some node swallowed it
+fn push(transforms: &mut [Box<dyn Transform>], chunk: AudioData) -> Result<Option<AudioData>> {
+    let mut chunk = chunk;
+    for transform in transforms {
+        if chunk.samples.is_empty() {
+            return Ok(None);
+        }
+        chunk = transform.process(chunk)?;
+    }
+    Ok((!chunk.samples.is_empty()).then_some(chunk))
+}
+
+/// Refuse to write over the file being read; creating the output
+/// truncates it, which would destroy the input
+pub(crate) fn check_not_input(input_path: &Path, output_path: &Path) -> Result<()> {
+    if let (Ok(input), Ok(output)) = (input_path.canonicalize(), output_path.canonicalize()) {
+        if input == output {
+            return Err(AudioError::InvalidParameter(format!(
+                "Output would overwrite the input file {}",
+                input_path.display()
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{remix_channels, resample_audio};
+
+    fn ramp(frames: usize, sample_rate: u32, channels: u16) -> AudioData {
+        AudioData {
+            samples: (0..frames * channels as usize)
+                .map(|i| ((i % 1000) as f32 / 1000.0) - 0.5)
+                .collect(),
+            sample_rate,
+            channels,
+        }
+    }
+
+    #[test]
+    fn test_empty_pipeline_copies_source() {
+        let audio = ramp(5000, 16000, 2);
+        let mut sink = MemorySink::default();
+        let summary = Pipeline::new(MemorySource::new(audio.clone(), 1024))
+            .run(&mut sink)
+            .unwrap();
+
+        assert_eq!(summary.frames_in, 5000);
+        assert_eq!(summary.frames_out, 5000);
+        assert_eq!(sink.into_audio().unwrap().samples, audio.samples);
+    }
+
+    #[test]
+    fn test_chunked_chain_matches_whole_buffer() {
+        let audio = ramp(44100, 44100, 2);
+        let mut sink = MemorySink::default();
+        let summary = Pipeline::new(MemorySource::new(audio.clone(), 3000))
+            .then(Remix::new(1))
+            .then(Resample::new(16000))
+            .run(&mut sink)
+            .unwrap();
+
+        let expected = resample_audio(&remix_channels(&audio, 1).unwrap(), 16000).unwrap();
+        let output = sink.into_audio().unwrap();
+        assert_eq!(summary.output, StreamSpec { sample_rate: 16000, channels: 1 });
+        assert_eq!(output.samples.len(), expected.samples.len());
+        for (a, b) in output.samples.iter().zip(&expected.samples) {
+            assert!((a - b).abs() < 1e-5, "{} vs {}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_progress_reaches_one() {
+        let mut reports = Vec::new();
+        Pipeline::new(MemorySource::new(ramp(4000, 8000, 1), 1000))
+            .run_with_progress(&mut MemorySink::default(), &mut |f: f64| reports.push(f))
+            .unwrap();
+        assert_eq!(reports, vec![0.25, 0.5, 0.75, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_configure_errors_name_the_node() {
+        let result = Pipeline::new(MemorySource::new(ramp(10, 8000, 1), 10))
+            .then(Remix::new(0))
+            .run(&mut MemorySink::default());
+        assert!(matches!(result, Err(AudioError::InvalidParameter(msg)) if msg.starts_with("remix")));
+    }
+}
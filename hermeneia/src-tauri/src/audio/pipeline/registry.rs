@@ -0,0 +1,293 @@
+// src-tauri/src/audio/pipeline/registry.rs
+
+use std::collections::BTreeMap;
+use std::sync::{OnceLock, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::pipeline::{Gain, Remix, Resample, Transform};
+use crate::error::{AudioError, Result};
+
+/// Parameter values by name; missing ones take their default
+pub type EffectParams = BTreeMap<String, f64>;
+
+/// One adjustable parameter of an effect
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParamInfo {
+    pub name: String,
+    pub description: String,
+    pub min: f64,
+    pub max: f64,
+    pub default: f64,
+    /// Display unit, e.g. "dB" or "Hz" (empty if unitless)
+    pub unit: String,
+}
+
+impl ParamInfo {
+    pub fn new(name: &str, description: &str, min: f64, max: f64, default: f64, unit: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            min,
+            max,
+            default,
+            unit: unit.to_string(),
+        }
+    }
+}
+
+/// What the frontend needs to list an effect and draw its controls
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EffectInfo {
+    /// Stable key used to build the effect, e.g. "gain"
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub params: Vec<ParamInfo>,
+}
+
+impl EffectInfo {
+    /// Value of parameter `name`, or its default if it wasn't given
+    ///
+    /// Fails if the effect has no such parameter or the value is out of range.
+    pub fn value(&self, params: &EffectParams, name: &str) -> Result<f64> {
+        let spec = self.params.iter().find(|p| p.name == name).ok_or_else(|| {
+            AudioError::InvalidParameter(format!("{} has no parameter '{}'", self.id, name))
+        })?;
+        let value = params.get(name).copied().unwrap_or(spec.default);
+        if !(spec.min..=spec.max).contains(&value) {
+            return Err(AudioError::InvalidParameter(format!(
+                "{}: {} must be between {} and {} (got {})",
+                self.id, name, spec.min, spec.max, value
+            )));
+        }
+        Ok(value)
+    }
+
+    /// Reject parameters the effect doesn't know about (usually typos)
+    fn check_names(&self, params: &EffectParams) -> Result<()> {
+        match params.keys().find(|k| !self.params.iter().any(|p| &p.name == *k)) {
+            Some(unknown) => Err(AudioError::InvalidParameter(format!(
+                "{} has no parameter '{}'",
+                self.id, unknown
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// An offline effect that can be inserted into a [`crate::audio::pipeline::Pipeline`]
+///
+/// Implement this and call [`EffectRegistry::register`] to make a new effect
+/// available everywhere effects are listed, without changes to the pipeline
+/// or the commands.
+pub trait Effect: Send + Sync {
+    fn info(&self) -> EffectInfo;
+
+    /// A fresh transform with the given parameters
+    ///
+    /// Parameter names are already checked; use [`EffectInfo::value`] to
+    /// read them.
+    fn build(&self, params: &EffectParams) -> Result<Box<dyn Transform>>;
+}
+
+/// The set of effects, keyed by id
+pub struct EffectRegistry {
+    effects: BTreeMap<String, Box<dyn Effect>>,
+}
+
+impl EffectRegistry {
+    /// A registry with no effects
+    pub fn new() -> Self {
+        Self {
+            effects: BTreeMap::new(),
+        }
+    }
+
+    /// A registry with gain, remix and resample
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        for effect in [
+            Box::new(GainEffect) as Box<dyn Effect>,
+            Box::new(RemixEffect),
+            Box::new(ResampleEffect),
+        ] {
+            registry
+                .register(effect)
+                .expect("built-in effect ids are unique");
+        }
+        registry
+    }
+
+    /// Add an effect; fails if its id is already taken
+    pub fn register(&mut self, effect: Box<dyn Effect>) -> Result<()> {
+        let id = effect.info().id;
+        if self.effects.contains_key(&id) {
+            return Err(AudioError::InvalidParameter(format!(
+                "An effect with id '{}' is already registered",
+                id
+            )));
+        }
+        self.effects.insert(id, effect);
+        Ok(())
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.effects.contains_key(id)
+    }
+
+    /// Every effect, sorted by id
+    pub fn list(&self) -> Vec<EffectInfo> {
+        self.effects.values().map(|e| e.info()).collect()
+    }
+
+    /// Build effect `id` as a transform, ready for [`crate::audio::pipeline::Pipeline::then`]
+    pub fn build(&self, id: &str, params: &EffectParams) -> Result<Box<dyn Transform>> {
+        let effect = self
+            .effects
+            .get(id)
+            .ok_or_else(|| AudioError::InvalidParameter(format!("Unknown effect '{}'", id)))?;
+        effect.info().check_names(params)?;
+        effect.build(params)
+    }
+}
+
+impl Default for EffectRegistry {
+    fn default() -> Self {
+        Self::with_builtins()
+    }
+}
+
+/// The app-wide registry
+///
+/// Starts with the built-ins, plus the user's saved effect chains when the
+/// `user-effects` feature is on.
+pub fn effects() -> &'static RwLock<EffectRegistry> {
+    static REGISTRY: OnceLock<RwLock<EffectRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        #[allow(unused_mut)]
+        let mut registry = EffectRegistry::with_builtins();
+        #[cfg(feature = "user-effects")]
+        crate::audio::pipeline::user_effects::register_user_effects(&mut registry);
+        RwLock::new(registry)
+    })
+}
+
+struct GainEffect;
+
+impl Effect for GainEffect {
+    fn info(&self) -> EffectInfo {
+        EffectInfo {
+            id: "gain".to_string(),
+            name: "Gain".to_string(),
+            description: "Raise or lower the level by a fixed amount".to_string(),
+            params: vec![ParamInfo::new("gain_db", "Gain", -60.0, 24.0, 0.0, "dB")],
+        }
+    }
+
+    fn build(&self, params: &EffectParams) -> Result<Box<dyn Transform>> {
+        let gain_db = self.info().value(params, "gain_db")?;
+        Ok(Box::new(Gain::new(gain_db)))
+    }
+}
+
+struct RemixEffect;
+
+impl Effect for RemixEffect {
+    fn info(&self) -> EffectInfo {
+        EffectInfo {
+            id: "remix".to_string(),
+            name: "Channels".to_string(),
+            description: "Mix down or up to a different channel count".to_string(),
+            params: vec![ParamInfo::new("channels", "Channels", 1.0, 8.0, 2.0, "")],
+        }
+    }
+
+    fn build(&self, params: &EffectParams) -> Result<Box<dyn Transform>> {
+        let channels = self.info().value(params, "channels")?;
+        Ok(Box::new(Remix::new(channels.round() as u16)))
+    }
+}
+
+struct ResampleEffect;
+
+impl Effect for ResampleEffect {
+    fn info(&self) -> EffectInfo {
+        EffectInfo {
+            id: "resample".to_string(),
+            name: "Sample rate".to_string(),
+            description: "Convert to a different sample rate".to_string(),
+            params: vec![ParamInfo::new(
+                "sample_rate",
+                "Sample rate",
+                8000.0,
+                192_000.0,
+                48000.0,
+                "Hz",
+            )],
+        }
+    }
+
+    fn build(&self, params: &EffectParams) -> Result<Box<dyn Transform>> {
+        let sample_rate = self.info().value(params, "sample_rate")?;
+        Ok(Box::new(Resample::new(sample_rate.round() as u32)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::pipeline::{MemorySink, MemorySource, Pipeline, StreamSpec};
+    use crate::audio::types::AudioData;
+
+    fn params(pairs: &[(&str, f64)]) -> EffectParams {
+        pairs.iter().map(|&(k, v)| (k.to_string(), v)).collect()
+    }
+
+    #[test]
+    fn test_builtins_are_listed_by_id() {
+        let ids: Vec<String> = EffectRegistry::with_builtins()
+            .list()
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(ids, vec!["gain", "remix", "resample"]);
+    }
+
+    #[test]
+    fn test_duplicate_id_rejected() {
+        let mut registry = EffectRegistry::with_builtins();
+        assert!(registry.register(Box::new(GainEffect)).is_err());
+    }
+
+    #[test]
+    fn test_params_are_checked() {
+        let registry = EffectRegistry::with_builtins();
+        assert!(registry.build("gain", &params(&[("gain_db", 100.0)])).is_err());
+        assert!(registry.build("gain", &params(&[("gain", -3.0)])).is_err());
+        assert!(registry.build("reverb", &EffectParams::new()).is_err());
+        // Defaults fill in what's missing
+        assert!(registry.build("gain", &EffectParams::new()).is_ok());
+    }
+
+    #[test]
+    fn test_built_effect_runs_in_pipeline() {
+        let audio = AudioData {
+            samples: vec![0.5; 4000],
+            sample_rate: 8000,
+            channels: 2,
+        };
+        let registry = EffectRegistry::with_builtins();
+        let mut sink = MemorySink::default();
+        let summary = Pipeline::new(MemorySource::new(audio, 500))
+            .then(registry.build("remix", &params(&[("channels", 1.0)])).unwrap())
+            .then(registry.build("gain", &params(&[("gain_db", -6.0206)])).unwrap())
+            .run(&mut sink)
+            .unwrap();
+
+        assert_eq!(summary.output, StreamSpec { sample_rate: 8000, channels: 1 });
+        let output = sink.into_audio().unwrap();
+        assert_eq!(output.samples.len(), 2000);
+        assert!(output.samples.iter().all(|s| (s - 0.25).abs() < 1e-4));
+    }
+}
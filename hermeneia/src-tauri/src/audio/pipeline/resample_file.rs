@@ -0,0 +1,121 @@
+// src-tauri/src/audio/pipeline/resample_file.rs
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::encoder::WavSampleFormat;
+use crate::audio::pipeline::{
+    check_not_input, FileSource, Pipeline, PipelineSummary, Remix, Resample, Source, WavSink,
+};
+use crate::error::Result;
+use crate::progress::ProgressSink;
+
+/// Format to bring a file to with [`resample_file`]
+///
+/// Unset fields keep the input's sample rate or channel count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ResampleSpec {
+    #[serde(default)]
+    pub sample_rate: Option<u32>,
+    #[serde(default)]
+    pub channels: Option<u16>,
+    #[serde(default)]
+    pub sample_format: WavSampleFormat,
+}
+
+impl ResampleSpec {
+    /// 16 kHz mono 16-bit, what speech recognition tools expect
+    pub fn speech() -> Self {
+        Self {
+            sample_rate: Some(16000),
+            channels: Some(1),
+            sample_format: WavSampleFormat::Pcm16,
+        }
+    }
+}
+
+/// Write a WAV copy of a file at another sample rate, channel count or
+/// bit depth
+///
+/// Streams through the [`Pipeline`], so files of any length fit in memory.
+/// Reports the "resample" stage.
+///
+/// # Errors
+/// [`AudioError::InvalidParameter`] if `output_path` is the input itself
+///
+/// # Example
+/// ```no_run
+/// use hermeneia_lib::audio::pipeline::{resample_file, ResampleSpec};
+/// use hermeneia_lib::progress::NoProgress;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// resample_file("sermon.flac", "sermon_16k.wav", &ResampleSpec::speech(), &mut NoProgress)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn resample_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    input_path: P,
+    output_path: Q,
+    spec: &ResampleSpec,
+    progress: &mut dyn ProgressSink,
+) -> Result<PipelineSummary> {
+    let (input_path, output_path) = (input_path.as_ref(), output_path.as_ref());
+    check_not_input(input_path, output_path)?;
+
+    let source = FileSource::open(input_path)?;
+    let source_spec = source.spec();
+    let channels = spec.channels.unwrap_or(source_spec.channels);
+    let sample_rate = spec.sample_rate.unwrap_or(source_spec.sample_rate);
+
+    progress.stage("resample");
+    let mut sink = WavSink::new(output_path, spec.sample_format);
+    Pipeline::new(source)
+        .then(Remix::new(channels))
+        .then(Resample::new(sample_rate))
+        .run_with_progress(&mut sink, progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::pipeline::StreamSpec;
+    use crate::audio::{encode_wav, get_audio_info, AudioData};
+    use crate::error::AudioError;
+    use crate::progress::NoProgress;
+
+    #[test]
+    fn test_resample_file_for_speech_recognition() {
+        let dir = std::env::temp_dir();
+        let input = dir.join("hermeneia_test_resample_file_in.wav");
+        let output = dir.join("hermeneia_test_resample_file_out.wav");
+        let audio = AudioData {
+            samples: (0..44100 * 2).map(|i| 0.3 * (i as f32 * 0.01).sin()).collect(),
+            sample_rate: 44100,
+            channels: 2,
+        };
+        encode_wav(&audio, &input).unwrap();
+
+        let summary =
+            resample_file(&input, &output, &ResampleSpec::speech(), &mut NoProgress).unwrap();
+        assert_eq!(summary.output, StreamSpec { sample_rate: 16000, channels: 1 });
+        let info = get_audio_info(&output).unwrap();
+        assert_eq!((info.sample_rate, info.channels), (16000, 1));
+        assert!((info.duration_seconds - 1.0).abs() < 0.01);
+
+        // Unset fields keep the input's format
+        let spec = ResampleSpec {
+            sample_rate: Some(22050),
+            ..Default::default()
+        };
+        let summary = resample_file(&input, &output, &spec, &mut NoProgress).unwrap();
+        assert_eq!(summary.output, StreamSpec { sample_rate: 22050, channels: 2 });
+
+        let result = resample_file(&input, &input, &spec, &mut NoProgress);
+        assert!(matches!(result, Err(AudioError::InvalidParameter(_))));
+        assert_eq!(get_audio_info(&input).unwrap().sample_rate, 44100);
+
+        std::fs::remove_file(input).ok();
+        std::fs::remove_file(output).ok();
+    }
+}
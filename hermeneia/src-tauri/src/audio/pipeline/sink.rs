@@ -0,0 +1,183 @@
+// src-tauri/src/audio/pipeline/sink.rs
+
+use std::path::{Path, PathBuf};
+
+use crate::audio::encoder::{encode_audio, OutputFormat, WavSampleFormat, WavStreamWriter};
+use crate::audio::pipeline::{Sink, StreamSpec};
+use crate::audio::types::AudioData;
+use crate::error::Result;
+
+/// Collects the output in memory
+#[derive(Debug, Default)]
+pub struct MemorySink {
+    audio: Option<AudioData>,
+}
+
+impl MemorySink {
+    /// Everything written, or `None` if the pipeline never ran
+    pub fn into_audio(self) -> Option<AudioData> {
+        self.audio
+    }
+}
+
+impl Sink for MemorySink {
+    fn open(&mut self, spec: StreamSpec) -> Result<()> {
+        self.audio = Some(AudioData {
+            samples: Vec::new(),
+            sample_rate: spec.sample_rate,
+            channels: spec.channels,
+        });
+        Ok(())
+    }
+
+    fn write(&mut self, chunk: &AudioData) -> Result<()> {
+        if let Some(audio) = &mut self.audio {
+            audio.samples.extend_from_slice(&chunk.samples);
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes WAV as the audio arrives
+pub struct WavSink {
+    path: PathBuf,
+    sample_format: WavSampleFormat,
+    writer: Option<WavStreamWriter>,
+}
+
+impl WavSink {
+    pub fn new<P: AsRef<Path>>(path: P, sample_format: WavSampleFormat) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            sample_format,
+            writer: None,
+        }
+    }
+}
+
+impl Sink for WavSink {
+    fn open(&mut self, spec: StreamSpec) -> Result<()> {
+        self.writer = Some(WavStreamWriter::create(
+            &self.path,
+            spec.sample_rate,
+            spec.channels,
+            self.sample_format,
+        )?
+        .dither(true));
+        Ok(())
+    }
+
+    fn write(&mut self, chunk: &AudioData) -> Result<()> {
+        match &mut self.writer {
+            Some(writer) => writer.write_samples(&chunk.samples),
+            None => Ok(()),
+        }
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        match self.writer.take() {
+            Some(writer) => writer.finalize(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Writes any [`OutputFormat`]
+///
+/// WAV streams straight to disk; the other encoders need the whole signal,
+/// so it is collected in memory and encoded when the pipeline finishes.
+pub enum EncodeSink {
+    Wav(WavSink),
+    Buffered {
+        path: PathBuf,
+        format: OutputFormat,
+        buffer: MemorySink,
+    },
+}
+
+impl EncodeSink {
+    pub fn new<P: AsRef<Path>>(path: P, format: OutputFormat) -> Self {
+        match format {
+            OutputFormat::Wav { sample_format } => EncodeSink::Wav(WavSink::new(path, sample_format)),
+            format => EncodeSink::Buffered {
+                path: path.as_ref().to_path_buf(),
+                format,
+                buffer: MemorySink::default(),
+            },
+        }
+    }
+}
+
+impl Sink for EncodeSink {
+    fn open(&mut self, spec: StreamSpec) -> Result<()> {
+        match self {
+            EncodeSink::Wav(sink) => sink.open(spec),
+            EncodeSink::Buffered { buffer, .. } => buffer.open(spec),
+        }
+    }
+
+    fn write(&mut self, chunk: &AudioData) -> Result<()> {
+        match self {
+            EncodeSink::Wav(sink) => sink.write(chunk),
+            EncodeSink::Buffered { buffer, .. } => buffer.write(chunk),
+        }
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        match self {
+            EncodeSink::Wav(sink) => sink.finish(),
+            EncodeSink::Buffered {
+                path,
+                format,
+                buffer,
+            } => match std::mem::take(buffer).into_audio() {
+                Some(audio) => encode_audio(&audio, path, format),
+                None => Ok(()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::decoder::decode_audio_file;
+    use crate::audio::pipeline::{MemorySource, Pipeline};
+    use crate::audio::{release_reader, DEFAULT_FLAC_COMPRESSION};
+
+    #[test]
+    fn test_encode_sink_writes_each_format() {
+        let audio = AudioData {
+            // A whole number of FLAC blocks
+            samples: (0..16_384).map(|i| ((i % 80) as f32 / 80.0) - 0.5).collect(),
+            sample_rate: 16000,
+            channels: 1,
+        };
+        let formats = [
+            OutputFormat::Wav {
+                sample_format: WavSampleFormat::Float32,
+            },
+            OutputFormat::Flac {
+                bits_per_sample: 24,
+                compression_level: DEFAULT_FLAC_COMPRESSION,
+            },
+        ];
+        for format in formats {
+            let path =
+                std::env::temp_dir().join(format!("hermeneia_test_sink.{}", format.extension()));
+            let mut sink = EncodeSink::new(&path, format);
+            Pipeline::new(MemorySource::new(audio.clone(), 1000))
+                .run(&mut sink)
+                .unwrap();
+
+            let decoded = decode_audio_file(&path).unwrap();
+            assert_eq!(decoded.frame_count(), audio.frame_count());
+            release_reader(&path);
+            std::fs::remove_file(&path).ok();
+        }
+    }
+}
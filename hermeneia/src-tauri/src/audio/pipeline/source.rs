@@ -0,0 +1,171 @@
+// src-tauri/src/audio/pipeline/source.rs
+
+use std::path::Path;
+
+use crate::audio::decoder::{
+    convert_audio_buffer_to_f32, open_audio_track, AudioChunks, AudioTrack,
+};
+use crate::audio::pipeline::{Source, StreamSpec};
+use crate::audio::types::AudioData;
+use crate::error::{DecodeError, Result};
+
+/// Decodes a file one packet at a time
+pub struct FileSource {
+    track: AudioTrack,
+}
+
+impl FileSource {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self {
+            track: open_audio_track(path.as_ref())?,
+        })
+    }
+}
+
+impl Source for FileSource {
+    fn spec(&self) -> StreamSpec {
+        StreamSpec {
+            sample_rate: self.track.sample_rate,
+            channels: self.track.channels,
+        }
+    }
+
+    fn total_frames(&self) -> Option<u64> {
+        self.track.n_frames
+    }
+
+    fn next_chunk(&mut self) -> Result<Option<AudioData>> {
+        let track = &mut self.track;
+        while let Ok(packet) = track.format.next_packet() {
+            // Skip packets from other tracks (e.g., video, album art)
+            if packet.track_id() != track.track_id {
+                continue;
+            }
+
+            let decoded = track
+                .decoder
+                .decode(&packet)
+                .map_err(|e| DecodeError::Packet(e.to_string()))?;
+
+            let mut samples = Vec::new();
+            convert_audio_buffer_to_f32(&decoded, &mut samples);
+            if samples.is_empty() {
+                continue;
+            }
+
+            return Ok(Some(AudioData {
+                samples,
+                sample_rate: track.sample_rate,
+                channels: track.channels,
+            }));
+        }
+        Ok(None)
+    }
+}
+
+/// Fixed-length chunks, for transforms that want even blocks rather than
+/// whatever size the codec's packets are
+impl Source for AudioChunks {
+    fn spec(&self) -> StreamSpec {
+        StreamSpec {
+            sample_rate: self.sample_rate(),
+            channels: self.channels(),
+        }
+    }
+
+    fn total_frames(&self) -> Option<u64> {
+        AudioChunks::total_frames(self)
+    }
+
+    fn next_chunk(&mut self) -> Result<Option<AudioData>> {
+        self.next().transpose()
+    }
+}
+
+/// Hands out an in-memory buffer in fixed-size chunks
+pub struct MemorySource {
+    audio: AudioData,
+    chunk_frames: usize,
+    position: usize,
+}
+
+impl MemorySource {
+    pub fn new(audio: AudioData, chunk_frames: usize) -> Self {
+        Self {
+            audio,
+            chunk_frames: chunk_frames.max(1),
+            position: 0,
+        }
+    }
+}
+
+impl Source for MemorySource {
+    fn spec(&self) -> StreamSpec {
+        StreamSpec::of(&self.audio)
+    }
+
+    fn total_frames(&self) -> Option<u64> {
+        Some(self.audio.frame_count() as u64)
+    }
+
+    fn next_chunk(&mut self) -> Result<Option<AudioData>> {
+        let channels = self.audio.channels as usize;
+        let start = self.position * channels;
+        if channels == 0 || start >= self.audio.samples.len() {
+            return Ok(None);
+        }
+
+        let end = (start + self.chunk_frames * channels).min(self.audio.samples.len());
+        self.position += (end - start) / channels;
+        Ok(Some(AudioData {
+            samples: self.audio.samples[start..end].to_vec(),
+            sample_rate: self.audio.sample_rate,
+            channels: self.audio.channels,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{decode_audio_file, encode_wav, release_reader};
+
+    #[test]
+    fn test_memory_source_chunks_cover_everything() {
+        let audio = AudioData {
+            samples: (0..10).map(|i| i as f32).collect(),
+            sample_rate: 8000,
+            channels: 2,
+        };
+        let mut source = MemorySource::new(audio, 2);
+        let mut lengths = Vec::new();
+        while let Some(chunk) = source.next_chunk().unwrap() {
+            lengths.push(chunk.frame_count());
+        }
+        assert_eq!(lengths, vec![2, 2, 1]);
+    }
+
+    #[test]
+    fn test_file_source_matches_full_decode() {
+        let path = std::env::temp_dir().join("hermeneia_test_pipeline_source.wav");
+        let audio = AudioData {
+            samples: (0..20_000).map(|i| ((i % 50) as f32 / 50.0) - 0.5).collect(),
+            sample_rate: 16000,
+            channels: 2,
+        };
+        encode_wav(&audio, &path).unwrap();
+
+        let mut source = FileSource::open(&path).unwrap();
+        assert_eq!(source.spec(), StreamSpec::of(&audio));
+        assert_eq!(source.total_frames(), Some(10_000));
+        let mut samples = Vec::new();
+        while let Some(chunk) = source.next_chunk().unwrap() {
+            samples.extend(chunk.samples);
+        }
+        drop(source);
+
+        assert_eq!(samples, decode_audio_file(&path).unwrap().samples);
+        release_reader(&path);
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,349 @@
+// src-tauri/src/audio/pipeline/transform.rs
+
+use crate::audio::channels::remix_channels;
+use crate::audio::dsp::{db_to_linear, from_real, to_real, Denoiser, Real};
+use crate::audio::pipeline::{StreamSpec, Transform};
+use crate::audio::resample::StreamResampler;
+use crate::audio::types::AudioData;
+use crate::error::{AudioError, Result};
+
+/// Constant gain in decibels, like [`crate::audio::apply_gain`]
+pub struct Gain {
+    gain_db: f64,
+    factor: Real,
+}
+
+impl Gain {
+    pub fn new(gain_db: f64) -> Self {
+        Self {
+            gain_db,
+            factor: db_to_linear(gain_db) as Real,
+        }
+    }
+}
+
+impl Transform for Gain {
+    fn name(&self) -> &'static str {
+        "gain"
+    }
+
+    fn configure(&mut self, input: StreamSpec) -> Result<StreamSpec> {
+        if !self.gain_db.is_finite() {
+            return Err(AudioError::InvalidParameter(format!(
+                "Gain must be a finite number of dB (got {})",
+                self.gain_db
+            )));
+        }
+        Ok(input)
+    }
+
+    fn process(&mut self, mut chunk: AudioData) -> Result<AudioData> {
+        for sample in &mut chunk.samples {
+            *sample = from_real(to_real(*sample) * self.factor);
+        }
+        Ok(chunk)
+    }
+}
+
+/// Channel count conversion, like [`crate::audio::remix_channels`]
+pub struct Remix {
+    channels: u16,
+}
+
+impl Remix {
+    pub fn new(channels: u16) -> Self {
+        Self { channels }
+    }
+}
+
+impl Transform for Remix {
+    fn name(&self) -> &'static str {
+        "remix"
+    }
+
+    fn configure(&mut self, input: StreamSpec) -> Result<StreamSpec> {
+        if self.channels == 0 || input.channels == 0 {
+            return Err(AudioError::InvalidParameter(format!(
+                "Cannot remix {} channel(s) to {} channel(s)",
+                input.channels, self.channels
+            )));
+        }
+        Ok(StreamSpec {
+            channels: self.channels,
+            ..input
+        })
+    }
+
+    fn process(&mut self, chunk: AudioData) -> Result<AudioData> {
+        if chunk.channels == self.channels {
+            return Ok(chunk);
+        }
+        remix_channels(&chunk, self.channels)
+    }
+}
+
+/// Sample rate conversion, like [`crate::audio::resample_audio`]
+///
+/// Buffers up to one resampler block, so output lags the input slightly
+/// and the tail comes out when the pipeline flushes.
+pub struct Resample {
+    sample_rate: u32,
+    /// `None` when the input is already at the target rate
+    resampler: Option<StreamResampler>,
+    channels: u16,
+}
+
+impl Resample {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            resampler: None,
+            channels: 0,
+        }
+    }
+
+    fn wrap(&self, samples: Vec<f32>) -> AudioData {
+        AudioData {
+            samples,
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+        }
+    }
+}
+
+impl Transform for Resample {
+    fn name(&self) -> &'static str {
+        "resample"
+    }
+
+    fn configure(&mut self, input: StreamSpec) -> Result<StreamSpec> {
+        self.channels = input.channels;
+        self.resampler = if input.sample_rate == self.sample_rate && self.sample_rate != 0 {
+            None
+        } else {
+            Some(StreamResampler::new(
+                input.sample_rate,
+                self.sample_rate,
+                input.channels,
+            )?)
+        };
+        Ok(StreamSpec {
+            sample_rate: self.sample_rate,
+            ..input
+        })
+    }
+
+    fn process(&mut self, chunk: AudioData) -> Result<AudioData> {
+        match &mut self.resampler {
+            Some(resampler) => {
+                let samples = resampler.process(&chunk.samples)?;
+                Ok(self.wrap(samples))
+            }
+            None => Ok(chunk),
+        }
+    }
+
+    fn flush(&mut self) -> Result<Option<AudioData>> {
+        match &mut self.resampler {
+            Some(resampler) => {
+                let samples = resampler.flush()?;
+                Ok(Some(self.wrap(samples)))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Noise reduction, like [`crate::audio::denoise`]
+///
+/// Lags the input by half an analysis window; the tail comes out when the
+/// pipeline flushes.
+pub struct Denoise {
+    strength: f32,
+    denoiser: Option<Denoiser>,
+    spec: StreamSpec,
+}
+
+impl Denoise {
+    pub fn new(strength: f32) -> Self {
+        Self {
+            strength,
+            denoiser: None,
+            spec: StreamSpec {
+                sample_rate: 0,
+                channels: 0,
+            },
+        }
+    }
+
+    fn wrap(&self, samples: Vec<f32>) -> AudioData {
+        AudioData {
+            samples,
+            sample_rate: self.spec.sample_rate,
+            channels: self.spec.channels,
+        }
+    }
+}
+
+impl Transform for Denoise {
+    fn name(&self) -> &'static str {
+        "denoise"
+    }
+
+    fn configure(&mut self, input: StreamSpec) -> Result<StreamSpec> {
+        self.denoiser = Some(Denoiser::new(input.sample_rate, input.channels, self.strength)?);
+        self.spec = input;
+        Ok(input)
+    }
+
+    fn process(&mut self, chunk: AudioData) -> Result<AudioData> {
+        match &mut self.denoiser {
+            Some(denoiser) => {
+                let samples = denoiser.process(&chunk.samples);
+                Ok(self.wrap(samples))
+            }
+            None => Ok(chunk),
+        }
+    }
+
+    fn flush(&mut self) -> Result<Option<AudioData>> {
+        match &mut self.denoiser {
+            Some(denoiser) => {
+                let samples = denoiser.flush();
+                Ok(Some(self.wrap(samples)))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Several transforms run as one, e.g. a saved effect preset
+pub struct Chain {
+    transforms: Vec<Box<dyn Transform>>,
+}
+
+impl Chain {
+    pub fn new(transforms: Vec<Box<dyn Transform>>) -> Self {
+        Self { transforms }
+    }
+}
+
+impl Transform for Chain {
+    fn name(&self) -> &'static str {
+        "chain"
+    }
+
+    fn configure(&mut self, input: StreamSpec) -> Result<StreamSpec> {
+        let mut spec = input;
+        for transform in &mut self.transforms {
+            spec = transform.configure(spec)?;
+        }
+        Ok(spec)
+    }
+
+    fn process(&mut self, chunk: AudioData) -> Result<AudioData> {
+        let mut chunk = chunk;
+        for transform in &mut self.transforms {
+            chunk = transform.process(chunk)?;
+        }
+        Ok(chunk)
+    }
+
+    fn flush(&mut self) -> Result<Option<AudioData>> {
+        // Same order as the pipeline: each tail still passes through the
+        // nodes after it
+        let mut flushed: Option<AudioData> = None;
+        for i in 0..self.transforms.len() {
+            let (current, rest) = self.transforms[i..].split_at_mut(1);
+            if let Some(mut tail) = current[0].flush()? {
+                for transform in rest.iter_mut() {
+                    tail = transform.process(tail)?;
+                }
+                match &mut flushed {
+                    Some(audio) => audio.samples.extend_from_slice(&tail.samples),
+                    None => flushed = Some(tail),
+                }
+            }
+        }
+        Ok(flushed.filter(|audio| !audio.samples.is_empty()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::pipeline::{MemorySink, MemorySource, Pipeline};
+
+    #[test]
+    fn test_gain_matches_apply_gain() {
+        let audio = AudioData {
+            samples: vec![0.5, -0.25, 0.125],
+            sample_rate: 8000,
+            channels: 1,
+        };
+        let mut gain = Gain::new(-6.0);
+        gain.configure(StreamSpec::of(&audio)).unwrap();
+        let expected = crate::audio::apply_gain(&audio, -6.0).unwrap();
+        assert_eq!(gain.process(audio).unwrap().samples, expected.samples);
+    }
+
+    #[test]
+    fn test_non_finite_gain_rejected_at_configure() {
+        let spec = StreamSpec {
+            sample_rate: 8000,
+            channels: 1,
+        };
+        assert!(Gain::new(f64::NAN).configure(spec).is_err());
+    }
+
+    #[test]
+    fn test_resample_at_same_rate_passes_through() {
+        let mut resample = Resample::new(8000);
+        let spec = StreamSpec {
+            sample_rate: 8000,
+            channels: 2,
+        };
+        assert_eq!(resample.configure(spec).unwrap(), spec);
+        assert!(resample.flush().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_denoise_streams_to_the_input_length() {
+        let audio = AudioData {
+            samples: (0..16000).map(|i| ((i * 7919 % 200) as f32 / 1000.0) - 0.1).collect(),
+            sample_rate: 16000,
+            channels: 2,
+        };
+        let expected = crate::audio::denoise(&audio, 0.5).unwrap();
+
+        let mut sink = MemorySink::default();
+        Pipeline::new(MemorySource::new(audio, 700))
+            .then(Denoise::new(0.5))
+            .run(&mut sink)
+            .unwrap();
+        assert_eq!(sink.into_audio().unwrap().samples, expected.samples);
+
+        let spec = StreamSpec {
+            sample_rate: 16000,
+            channels: 1,
+        };
+        assert!(Denoise::new(-0.1).configure(spec).is_err());
+    }
+
+    #[test]
+    fn test_chain_matches_separate_nodes() {
+        let audio = AudioData {
+            samples: (0..8000).map(|i| ((i % 50) as f32 / 50.0) - 0.5).collect(),
+            sample_rate: 8000,
+            channels: 2,
+        };
+        let mut chain = Chain::new(vec![Box::new(Remix::new(1)), Box::new(Resample::new(16000))]);
+        let spec = chain.configure(StreamSpec::of(&audio)).unwrap();
+        assert_eq!(spec, StreamSpec { sample_rate: 16000, channels: 1 });
+
+        let mut samples = chain.process(audio.clone()).unwrap().samples;
+        samples.extend(chain.flush().unwrap().unwrap().samples);
+        let expected = crate::audio::resample_audio(&remix_channels(&audio, 1).unwrap(), 16000).unwrap();
+        assert_eq!(samples.len(), expected.samples.len());
+    }
+}
@@ -0,0 +1,142 @@
+// src-tauri/src/audio/pipeline/user_effects.rs
+
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::audio::pipeline::registry::{Effect, EffectInfo, EffectParams, EffectRegistry};
+use crate::audio::pipeline::{Chain, Transform};
+use crate::error::{AudioError, Result};
+
+/// Folder under the app's config dir that holds user effects
+pub const USER_EFFECTS_DIR: &str = "effects";
+
+/// A user-defined effect: a fixed chain of built-in effects, saved as JSON
+///
+/// ```json
+/// {
+///   "id": "podcast-voice",
+///   "name": "Podcast voice",
+///   "description": "Mono at 16 kHz, 3 dB down",
+///   "steps": [
+///     { "effect": "remix", "params": { "channels": 1 } },
+///     { "effect": "resample", "params": { "sample_rate": 16000 } },
+///     { "effect": "gain", "params": { "gain_db": -3 } }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserEffect {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub steps: Vec<UserEffectStep>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserEffectStep {
+    pub effect: String,
+    #[serde(default)]
+    pub params: EffectParams,
+}
+
+impl UserEffect {
+    /// Read and check one effect file
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        let effect: UserEffect = serde_json::from_str(&json).map_err(|e| {
+            AudioError::InvalidParameter(format!("{}: {}", path.display(), e))
+        })?;
+        // Build once now so a bad step is reported at startup, not mid-export
+        effect.build(&EffectParams::new())?;
+        Ok(effect)
+    }
+}
+
+impl Effect for UserEffect {
+    fn info(&self) -> EffectInfo {
+        EffectInfo {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            params: Vec::new(),
+        }
+    }
+
+    fn build(&self, _params: &EffectParams) -> Result<Box<dyn Transform>> {
+        // Steps may only use built-ins, so one user effect can't loop
+        // through another
+        let builtins = EffectRegistry::with_builtins();
+        let transforms = self
+            .steps
+            .iter()
+            .map(|step| builtins.build(&step.effect, &step.params))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Box::new(Chain::new(transforms)))
+    }
+}
+
+/// Register every `*.json` effect in `dir`
+///
+/// Bad files are logged and skipped so one typo doesn't hide the rest.
+/// Returns how many were registered.
+pub fn register_from_dir(registry: &mut EffectRegistry, dir: &Path) -> usize {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    let mut registered = 0;
+    for path in paths {
+        match UserEffect::load(&path).and_then(|effect| registry.register(Box::new(effect))) {
+            Ok(()) => registered += 1,
+            Err(e) => tracing::warn!("Skipping user effect {}: {}", path.display(), e),
+        }
+    }
+    registered
+}
+
+/// Register the effects saved in the app's config dir
+pub(crate) fn register_user_effects(registry: &mut EffectRegistry) {
+    if let Some(dir) = crate::settings::app_config_dir() {
+        register_from_dir(registry, &dir.join(USER_EFFECTS_DIR));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_from_dir_skips_bad_files() {
+        let dir = std::env::temp_dir().join("hermeneia_test_user_effects");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("voice.json"),
+            r#"{"id": "voice", "name": "Voice", "steps": [
+                {"effect": "remix", "params": {"channels": 1}},
+                {"effect": "gain", "params": {"gain_db": -3}}
+            ]}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("typo.json"),
+            r#"{"id": "typo", "name": "Typo", "steps": [{"effect": "gian"}]}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("notes.txt"), "not an effect").unwrap();
+
+        let mut registry = EffectRegistry::with_builtins();
+        assert_eq!(register_from_dir(&mut registry, &dir), 1);
+        assert!(registry.contains("voice"));
+        assert!(!registry.contains("typo"));
+        assert!(registry.build("voice", &EffectParams::new()).is_ok());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,200 @@
+// src-tauri/src/audio/probe_cache.rs
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use symphonia::core::formats::Track;
+
+use super::media_info::ProbeDetails;
+use super::reader_pool::FileKey;
+
+/// Files whose probe results are remembered
+///
+/// Far more than the reader pool holds, since an entry is only a few
+/// hundred bytes and keeps no file open.
+pub(super) const PROBE_CACHE_CAPACITY: usize = 64;
+
+/// Files remembered in low-memory mode
+pub(super) const LOW_MEMORY_CAPACITY: usize = 16;
+
+/// Cache shared by every probe in the process
+static PROBE_CACHE: ProbeCache = ProbeCache::new(PROBE_CACHE_CAPACITY);
+
+/// A probe's findings: tracks with their codec parameters, plus container
+/// and tags
+#[derive(Debug)]
+pub(crate) struct CachedProbe {
+    pub(crate) tracks: Box<[Track]>,
+    pub(crate) details: ProbeDetails,
+}
+
+/// Results of earlier probes, keyed by file
+///
+/// Lets metadata lookups such as [`crate::audio::get_audio_info`] answer
+/// without opening the file, even while its pooled reader is busy (e.g.
+/// during playback). Entries are keyed by path, size and modification
+/// time, so a rewritten file misses and is probed again.
+pub(crate) struct ProbeCache {
+    capacity: AtomicUsize,
+    /// Least recently used first
+    entries: Mutex<VecDeque<(FileKey, Arc<CachedProbe>)>>,
+}
+
+impl ProbeCache {
+    pub(crate) const fn new(capacity: usize) -> Self {
+        Self {
+            capacity: AtomicUsize::new(capacity),
+            entries: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<(FileKey, Arc<CachedProbe>)>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn get(&self, key: &FileKey) -> Option<Arc<CachedProbe>> {
+        let mut entries = self.lock();
+        let index = entries.iter().position(|(k, _)| k == key)?;
+        // Move to the back so busy files outlive one-off lookups
+        let entry = entries.remove(index)?;
+        let probe = entry.1.clone();
+        entries.push_back(entry);
+        Some(probe)
+    }
+
+    fn insert(&self, key: FileKey, tracks: &[Track], details: &ProbeDetails) {
+        let mut entries = self.lock();
+        // An older version of the same file is never valid again
+        entries.retain(|(k, _)| k.path != key.path);
+        let probe = CachedProbe {
+            tracks: tracks.into(),
+            details: details.clone(),
+        };
+        entries.push_back((key, Arc::new(probe)));
+        let capacity = self.capacity.load(Ordering::Relaxed);
+        while entries.len() > capacity {
+            entries.pop_front();
+        }
+    }
+
+    fn set_capacity(&self, capacity: usize) {
+        self.capacity.store(capacity, Ordering::Relaxed);
+        let mut entries = self.lock();
+        while entries.len() > capacity {
+            entries.pop_front();
+        }
+    }
+
+    fn remove(&self, path: &Path) {
+        if let Ok(path) = path.canonicalize() {
+            self.lock().retain(|(k, _)| k.path != path);
+        }
+    }
+
+    fn clear(&self) {
+        self.lock().clear();
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.lock().len()
+    }
+}
+
+/// An earlier probe of `path`, if the file hasn't changed since
+pub(crate) fn cached(path: &Path) -> Option<Arc<CachedProbe>> {
+    PROBE_CACHE.get(&FileKey::for_path(path)?)
+}
+
+/// Remember what a fresh probe found
+pub(crate) fn remember(key: FileKey, tracks: &[Track], details: &ProbeDetails) {
+    PROBE_CACHE.insert(key, tracks, details);
+}
+
+/// Forget the cached probe of `path`
+pub(crate) fn forget(path: &Path) {
+    PROBE_CACHE.remove(path);
+}
+
+/// Remember at most `capacity` files, forgetting the oldest extras now
+pub(crate) fn set_capacity(capacity: usize) {
+    PROBE_CACHE.set_capacity(capacity);
+}
+
+/// Forget every cached probe
+pub(crate) fn forget_all() {
+    PROBE_CACHE.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use symphonia::core::codecs::CodecParameters;
+
+    fn tracks(sample_rate: u32) -> Vec<Track> {
+        let mut params = CodecParameters::new();
+        params.with_sample_rate(sample_rate);
+        vec![Track::new(0, params)]
+    }
+
+    fn touch(name: &str, len: usize) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("hermeneia_test_probe_cache_{}", name));
+        std::fs::write(&path, vec![0u8; len]).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_hit_until_file_changes() {
+        let cache = ProbeCache::new(4);
+        let path = touch("changes", 10);
+        cache.insert(FileKey::for_path(&path).unwrap(), &tracks(8000), &ProbeDetails::default());
+
+        let hit = cache.get(&FileKey::for_path(&path).unwrap()).unwrap();
+        assert_eq!(hit.tracks[0].codec_params.sample_rate, Some(8000));
+
+        touch("changes", 20);
+        assert!(cache.get(&FileKey::for_path(&path).unwrap()).is_none());
+
+        // Re-probing replaces the stale entry instead of adding a second one
+        cache.insert(FileKey::for_path(&path).unwrap(), &tracks(16000), &ProbeDetails::default());
+        assert_eq!(cache.len(), 1);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_capacity_evicts_least_recently_used() {
+        let cache = ProbeCache::new(2);
+        let paths: Vec<_> = (0..3).map(|i| touch(&format!("lru_{}", i), 10)).collect();
+        let key = |i: usize| FileKey::for_path(&paths[i]).unwrap();
+
+        cache.insert(key(0), &tracks(8000), &ProbeDetails::default());
+        cache.insert(key(1), &tracks(8000), &ProbeDetails::default());
+        // Using 0 makes 1 the oldest
+        cache.get(&key(0)).unwrap();
+        cache.insert(key(2), &tracks(8000), &ProbeDetails::default());
+
+        assert!(cache.get(&key(0)).is_some());
+        assert!(cache.get(&key(1)).is_none());
+        assert!(cache.get(&key(2)).is_some());
+
+        // Shrinking drops the oldest straight away
+        cache.set_capacity(1);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get(&key(2)).is_some());
+        for path in &paths {
+            std::fs::remove_file(path).ok();
+        }
+    }
+
+    #[test]
+    fn test_remove_invalidates() {
+        let cache = ProbeCache::new(4);
+        let path = touch("remove", 10);
+        cache.insert(FileKey::for_path(&path).unwrap(), &tracks(8000), &ProbeDetails::default());
+        cache.remove(&path);
+        assert_eq!(cache.len(), 0);
+        std::fs::remove_file(&path).ok();
+    }
+}
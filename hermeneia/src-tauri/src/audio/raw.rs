@@ -0,0 +1,158 @@
+// src-tauri/src/audio/raw.rs
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use crate::audio::types::AudioData;
+use crate::error::{AudioError, DecodeError, Result};
+
+/// First bytes of every raw checkpoint file
+const RAW_MAGIC: &[u8; 4] = b"HRAW";
+
+/// Bumped whenever the header layout changes
+const RAW_VERSION: u16 = 1;
+
+/// Magic, version, channels, sample rate, frame count
+const RAW_HEADER_LEN: u64 = 4 + 2 + 2 + 4 + 8;
+
+/// Samples converted per write or read
+const RAW_BLOCK: usize = 64 * 1024;
+
+impl AudioData {
+    /// Write the samples exactly as they are, for reloading with [`AudioData::load_raw`]
+    ///
+    /// Unlike the encoders nothing is clipped or quantized, so a checkpoint
+    /// of an intermediate result reloads bit for bit. The layout is a
+    /// 20-byte little-endian header (`HRAW`, version, channels, sample
+    /// rate, frame count) followed by interleaved `f32` samples.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use hermeneia_lib::audio::AudioData;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let audio = AudioData::silence(1.0, 48000, 2)?;
+    /// audio.save_raw("checkpoint.hraw")?;
+    /// let reloaded = AudioData::load_raw("checkpoint.hraw")?;
+    /// assert_eq!(reloaded.samples, audio.samples);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn save_raw<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.validate()?;
+        let mut writer = BufWriter::new(File::create(path.as_ref())?);
+
+        writer.write_all(RAW_MAGIC)?;
+        writer.write_all(&RAW_VERSION.to_le_bytes())?;
+        writer.write_all(&self.channels.to_le_bytes())?;
+        writer.write_all(&self.sample_rate.to_le_bytes())?;
+        writer.write_all(&(self.frame_count() as u64).to_le_bytes())?;
+
+        let mut bytes = Vec::with_capacity(RAW_BLOCK * 4);
+        for block in self.samples.chunks(RAW_BLOCK) {
+            bytes.clear();
+            bytes.extend(block.iter().flat_map(|s| s.to_le_bytes()));
+            writer.write_all(&bytes)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Read a file written by [`AudioData::save_raw`]
+    ///
+    /// # Errors
+    /// `DecodeError::Malformed` if the header is wrong or the file is cut short
+    pub fn load_raw<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| AudioError::FileOpen {
+            path: path.to_string_lossy().to_string(),
+            source: e,
+        })?;
+        let file_len = file.metadata()?.len();
+        let mut reader = BufReader::new(file);
+
+        let mut header = [0u8; RAW_HEADER_LEN as usize];
+        reader
+            .read_exact(&mut header)
+            .map_err(|_| DecodeError::Malformed("Raw audio header is incomplete".to_string()))?;
+        if &header[0..4] != RAW_MAGIC {
+            return Err(DecodeError::Malformed("Not a raw audio checkpoint".to_string()).into());
+        }
+        let version = u16::from_le_bytes([header[4], header[5]]);
+        if version != RAW_VERSION {
+            return Err(DecodeError::Malformed(format!(
+                "Unsupported raw audio version {}",
+                version
+            ))
+            .into());
+        }
+        let channels = u16::from_le_bytes([header[6], header[7]]);
+        let sample_rate = u32::from_le_bytes(header[8..12].try_into().expect("4 bytes"));
+        let frames = u64::from_le_bytes(header[12..20].try_into().expect("8 bytes"));
+
+        let expected = frames
+            .checked_mul(channels as u64 * 4)
+            .and_then(|n| n.checked_add(RAW_HEADER_LEN));
+        if expected != Some(file_len) {
+            return Err(DecodeError::Malformed(format!(
+                "Raw audio is {} bytes but the header describes {} frame(s) of {} channel(s)",
+                file_len, frames, channels
+            ))
+            .into());
+        }
+
+        let total = (frames * channels as u64) as usize;
+        let mut samples = Vec::with_capacity(total);
+        let mut bytes = vec![0u8; RAW_BLOCK * 4];
+        while samples.len() < total {
+            let count = (total - samples.len()).min(RAW_BLOCK);
+            let block = &mut bytes[..count * 4];
+            reader.read_exact(block)?;
+            samples.extend(
+                block
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+            );
+        }
+
+        AudioData::new(samples, sample_rate, channels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raw_round_trip_is_exact() {
+        let path = std::env::temp_dir().join("hermeneia_test_raw_round_trip.hraw");
+        // Values an integer format would clip or round
+        let audio = AudioData::new(
+            (0..RAW_BLOCK * 2 + 6).map(|i| (i as f32 * 0.37).sin() * 1.5).collect(),
+            44100,
+            2,
+        )
+        .unwrap();
+        audio.save_raw(&path).unwrap();
+
+        let reloaded = AudioData::load_raw(&path).unwrap();
+        assert_eq!(reloaded.sample_rate, 44100);
+        assert_eq!(reloaded.channels, 2);
+        assert_eq!(reloaded.samples, audio.samples);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_truncated_raw_is_rejected() {
+        let path = std::env::temp_dir().join("hermeneia_test_raw_truncated.hraw");
+        AudioData::silence(0.1, 8000, 1).unwrap().save_raw(&path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() - 2]).unwrap();
+        assert!(matches!(AudioData::load_raw(&path), Err(AudioError::Decode(DecodeError::Malformed(_)))));
+
+        std::fs::write(&path, b"RIFF0000WAVEfmt ").unwrap();
+        assert!(matches!(AudioData::load_raw(&path), Err(AudioError::Decode(DecodeError::Malformed(_)))));
+        std::fs::remove_file(&path).ok();
+    }
+}
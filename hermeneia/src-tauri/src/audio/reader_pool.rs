@@ -0,0 +1,453 @@
+// src-tauri/src/audio/reader_pool.rs
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+use symphonia::core::codecs::CODEC_TYPE_NULL;
+use symphonia::core::formats::{FormatOptions, FormatReader, SeekMode, SeekTo};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::{Hint, Probe};
+use tracing::debug;
+
+use super::large_wav::LargeWavReader;
+use super::media_info::{self, ProbeDetails};
+use super::probe_cache;
+use super::watchdog::{self, CancellableFile, PROBE_TIMEOUT};
+use crate::error::{AudioError, DecodeError, Result};
+use crate::profile;
+
+/// Readers kept open at once
+///
+/// Enough for the file being edited plus a few recently viewed ones; each
+/// holds a file handle and a read buffer.
+const POOL_CAPACITY: usize = 4;
+
+/// Readers kept open in low-memory mode: just the file being worked on
+const LOW_MEMORY_POOL_CAPACITY: usize = 1;
+
+/// Pool shared by every decode in the process
+static READER_POOL: ReaderPool = ReaderPool::new(POOL_CAPACITY);
+
+/// Identifies one version of a file on disk
+///
+/// Size and modification time are part of the key, so a file that was
+/// rewritten since its reader was pooled gets probed again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FileKey {
+    pub(crate) path: PathBuf,
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl FileKey {
+    pub(crate) fn for_path(path: &Path) -> Option<Self> {
+        let path = path.canonicalize().ok()?;
+        let metadata = std::fs::metadata(&path).ok()?;
+        Some(Self {
+            path,
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+}
+
+/// Probed format readers not currently in use, keyed by file
+pub(crate) struct ReaderPool {
+    capacity: AtomicUsize,
+    /// Least recently returned first
+    idle: Mutex<VecDeque<IdleReader>>,
+}
+
+struct IdleReader {
+    key: FileKey,
+    format: Box<dyn FormatReader>,
+    details: ProbeDetails,
+    /// Packets have been read, so the reader must rewind before reuse
+    used: bool,
+}
+
+impl IdleReader {
+    /// Seek back to the start of the first audio track
+    ///
+    /// Tracks without a frame count (streamed FLAC) don't rewind: seeking
+    /// in them can land past the start and end the stream early.
+    fn rewind(&mut self) -> bool {
+        if !self.used {
+            return true;
+        }
+        let Some(track_id) = self
+            .format
+            .tracks()
+            .iter()
+            .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+            .filter(|t| t.codec_params.n_frames.is_some())
+            .map(|t| t.id)
+        else {
+            return false;
+        };
+
+        let seek = self
+            .format
+            .seek(SeekMode::Accurate, SeekTo::TimeStamp { ts: 0, track_id });
+        self.used = false;
+        seek.is_ok()
+    }
+}
+
+/// A probed format reader that goes back to its pool when dropped
+///
+/// Consecutive operations on the same file (info, then peaks, then a trim
+/// preview) reuse the probe instead of opening and probing it each time.
+/// Dereferences to the underlying [`FormatReader`].
+pub(crate) struct PooledReader {
+    pool: &'static ReaderPool,
+    key: Option<FileKey>,
+    format: Option<Box<dyn FormatReader>>,
+    details: ProbeDetails,
+    used: bool,
+}
+
+impl PooledReader {
+    /// Container and tags found when the file was probed
+    pub(crate) fn details(&self) -> &ProbeDetails {
+        &self.details
+    }
+}
+
+impl Deref for PooledReader {
+    type Target = dyn FormatReader;
+
+    fn deref(&self) -> &Self::Target {
+        self.format.as_deref().expect("reader present until dropped")
+    }
+}
+
+impl DerefMut for PooledReader {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.used = true;
+        self.format.as_deref_mut().expect("reader present until dropped")
+    }
+}
+
+impl Drop for PooledReader {
+    fn drop(&mut self) {
+        if let (Some(key), Some(format)) = (self.key.take(), self.format.take()) {
+            self.pool.give_back(IdleReader {
+                key,
+                format,
+                details: std::mem::take(&mut self.details),
+                used: self.used,
+            });
+        }
+    }
+}
+
+impl ReaderPool {
+    pub(crate) const fn new(capacity: usize) -> Self {
+        Self {
+            capacity: AtomicUsize::new(capacity),
+            idle: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<IdleReader>> {
+        self.idle.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Open `path`, reusing an idle reader when the file is unchanged
+    ///
+    /// A reused reader is rewound to the start of its audio track, so
+    /// callers see the same state as after a fresh probe.
+    pub(crate) fn open(&'static self, path: &Path) -> Result<PooledReader> {
+        let key = FileKey::for_path(path);
+
+        if let Some(key) = &key {
+            let idle = {
+                let mut idle = self.lock();
+                idle.iter()
+                    .position(|r| &r.key == key)
+                    .and_then(|i| idle.remove(i))
+            };
+            // A reader that can't seek back is dropped and the file probed again
+            if let Some(mut reader) = idle {
+                if reader.rewind() {
+                    debug!(file = %path.display(), "Reusing pooled reader");
+                    return Ok(PooledReader {
+                        pool: self,
+                        key: Some(reader.key),
+                        format: Some(reader.format),
+                        details: reader.details,
+                        used: false,
+                    });
+                }
+            }
+        }
+
+        let (format, details) = probe(path)?;
+        if let Some(key) = &key {
+            probe_cache::remember(key.clone(), format.tracks(), &details);
+        }
+        Ok(PooledReader {
+            pool: self,
+            key,
+            format: Some(format),
+            details,
+            used: false,
+        })
+    }
+
+    fn give_back(&self, reader: IdleReader) {
+        let mut idle = self.lock();
+        idle.push_back(reader);
+        let capacity = self.capacity.load(Ordering::Relaxed);
+        while idle.len() > capacity {
+            idle.pop_front();
+        }
+    }
+
+    /// Keep at most `capacity` idle readers, closing the oldest extras now
+    fn set_capacity(&self, capacity: usize) {
+        self.capacity.store(capacity, Ordering::Relaxed);
+        let mut idle = self.lock();
+        while idle.len() > capacity {
+            idle.pop_front();
+        }
+    }
+
+    fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn release(&self, path: &Path) {
+        if let Ok(path) = path.canonicalize() {
+            self.lock().retain(|r| r.key.path != path);
+        }
+    }
+
+    #[cfg(test)]
+    fn contains(&self, path: &Path) -> bool {
+        let key = FileKey::for_path(path);
+        self.lock().iter().any(|r| Some(&r.key) == key.as_ref())
+    }
+}
+
+/// Open `path` for reading through the shared reader pool
+pub(crate) fn open_reader(path: &Path) -> Result<PooledReader> {
+    READER_POOL.open(path)
+}
+
+/// Open a file and detect its container format
+///
+/// The probe runs under a [`PROBE_TIMEOUT`] watchdog so a malformed file
+/// that sends the demuxer spinning fails with `DecodeError::Watchdog` instead of
+/// hanging the caller.
+fn probe(path: &Path) -> Result<(Box<dyn FormatReader>, ProbeDetails)> {
+    let _stage = profile::stage("probe");
+    let path_str = path.to_string_lossy().to_string();
+
+    // Open the file and read its header to name the container
+    let open_error = |e| AudioError::FileOpen {
+        path: path_str.clone(),
+        source: e,
+    };
+    let mut file = File::open(path).map_err(open_error)?;
+    let mut header = Vec::with_capacity(media_info::HEADER_LEN);
+    (&mut file)
+        .take(media_info::HEADER_LEN as u64)
+        .read_to_end(&mut header)
+        .and_then(|_| file.seek(SeekFrom::Start(0)))
+        .map_err(open_error)?;
+    let container = media_info::sniff_container(&header);
+
+    // Create a hint to help symphonia detect the format
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    // Probe the media source to detect format, giving up if it takes too long
+    let what = format!("probing {}", path_str);
+    watchdog::with_timeout(&what, PROBE_TIMEOUT, move |cancelled| {
+        let source = CancellableFile::new(file, cancelled);
+        let mss = MediaSourceStream::new(Box::new(source), Default::default());
+        let mut probed = format_probe()
+            .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
+            .map_err(|e| DecodeError::Probe(e.to_string()))?;
+
+        // Tags ahead of the container (ID3) come first, then the container's own
+        let mut format = probed.format;
+        let leading = probed.metadata.get();
+        let own = format.metadata();
+        let revisions = leading.as_ref().and_then(|m| m.current()).into_iter();
+        let tags = media_info::summarize_tags(revisions.chain(own.current()));
+        Ok((format, ProbeDetails { container, tags }))
+    })
+}
+
+/// Symphonia's default formats plus the RF64/Wave64 reader
+fn format_probe() -> &'static Probe {
+    static PROBE: OnceLock<Probe> = OnceLock::new();
+    PROBE.get_or_init(|| {
+        let mut probe = Probe::default();
+        symphonia::default::register_enabled_formats(&mut probe);
+        probe.register_all::<LargeWavReader>();
+        probe
+    })
+}
+
+/// Close every pooled reader and forget cached probe results
+///
+/// Pooled readers keep their files open, which on Windows stops them from
+/// being deleted or renamed. Call this before touching files the app has
+/// read recently.
+pub fn clear_reader_pool() {
+    READER_POOL.clear();
+    probe_cache::forget_all();
+}
+
+/// Shrink the reader pool and probe cache for low-memory mode, or give
+/// them back their usual sizes
+pub fn set_low_memory_caches(low_memory: bool) {
+    if low_memory {
+        READER_POOL.set_capacity(LOW_MEMORY_POOL_CAPACITY);
+        probe_cache::set_capacity(probe_cache::LOW_MEMORY_CAPACITY);
+    } else {
+        READER_POOL.set_capacity(POOL_CAPACITY);
+        probe_cache::set_capacity(probe_cache::PROBE_CACHE_CAPACITY);
+    }
+}
+
+/// Close the pooled readers for `path`, if any, and forget its cached probe
+pub fn release_reader<P: AsRef<Path>>(path: P) {
+    READER_POOL.release(path.as_ref());
+    probe_cache::forget(path.as_ref());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::encoder::encode_wav;
+    use crate::audio::types::AudioData;
+
+    /// A pool of its own, so other tests' decodes can't evict its readers
+    fn test_pool() -> &'static ReaderPool {
+        Box::leak(Box::new(ReaderPool::new(2)))
+    }
+
+    fn write_test_wav(name: &str, frames: usize) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("hermeneia_test_pool_{}.wav", name));
+        let audio = AudioData {
+            samples: (0..frames).map(|i| (i % 100) as f32 / 100.0).collect(),
+            sample_rate: 8000,
+            channels: 1,
+        };
+        encode_wav(&audio, &path).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_reader_is_reused_and_rewound() {
+        let pool = test_pool();
+        let path = write_test_wav("reuse", 8000);
+
+        let mut reader = pool.open(&path).unwrap();
+        let first_packet = reader.next_packet().unwrap().data.to_vec();
+        while reader.next_packet().is_ok() {}
+        drop(reader);
+        assert!(pool.contains(&path));
+
+        // Taken out of the pool and back at the start of the file
+        let mut reader = pool.open(&path).unwrap();
+        assert!(!pool.contains(&path));
+        assert_eq!(reader.next_packet().unwrap().data.to_vec(), first_packet);
+
+        drop(reader);
+        pool.release(&path);
+        assert!(!pool.contains(&path));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_probe_is_cached_until_released() {
+        let path = write_test_wav("cached", 800);
+        drop(test_pool().open(&path).unwrap());
+        let probe = probe_cache::cached(&path).unwrap();
+        assert_eq!(probe.tracks[0].codec_params.n_frames, Some(800));
+        assert_eq!(probe.details.container, Some("WAV"));
+
+        release_reader(&path);
+        assert!(probe_cache::cached(&path).is_none());
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_garbage_file_fails_to_probe() {
+        let path = std::env::temp_dir().join("hermeneia_test_pool_garbage.wav");
+        std::fs::write(&path, b"RIFF\xff\xff\xff\xffWAVEnot really a wave file").unwrap();
+        let result = test_pool().open(&path);
+        assert!(matches!(result, Err(AudioError::Decode(_))));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_cancelled_probe_returns() {
+        let path = write_test_wav("cancelled", 8000);
+        let cancelled = watchdog::CancelFlag::default();
+        cancelled.store(true, Ordering::Relaxed);
+        let source = CancellableFile::new(File::open(&path).unwrap(), cancelled);
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let mss = MediaSourceStream::new(Box::new(source), Default::default());
+            let probed = format_probe().format(
+                &Hint::new(),
+                mss,
+                &FormatOptions::default(),
+                &MetadataOptions::default(),
+            );
+            tx.send(probed.is_err()).unwrap();
+        });
+        // A retried read would spin here until the timeout
+        let failed = rx.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+        assert!(failed);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_rewritten_file_is_probed_again() {
+        let pool = test_pool();
+        let path = write_test_wav("rewritten", 8000);
+        drop(pool.open(&path).unwrap());
+        assert!(pool.contains(&path));
+
+        // Different length, so a different key
+        write_test_wav("rewritten", 4000);
+        assert!(!pool.contains(&path));
+        let reader = pool.open(&path).unwrap();
+        assert_eq!(reader.tracks()[0].codec_params.n_frames, Some(4000));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_pool_evicts_oldest() {
+        let pool = test_pool();
+        let paths: Vec<_> = (0..3)
+            .map(|i| write_test_wav(&format!("evict_{}", i), 800))
+            .collect();
+        for path in &paths {
+            drop(pool.open(path).unwrap());
+        }
+
+        assert!(!pool.contains(&paths[0]));
+        assert!(pool.contains(&paths[1]) && pool.contains(&paths[2]));
+        for path in &paths {
+            std::fs::remove_file(path).ok();
+        }
+    }
+}
@@ -0,0 +1,431 @@
+// src-tauri/src/audio/redact.rs
+
+use std::f64::consts::TAU;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::dsp::{db_to_linear, from_real, to_real, Real};
+use crate::audio::encoder::OutputFormat;
+use crate::audio::markers::Marker;
+use crate::audio::pipeline::{
+    check_not_input, EncodeSink, FileSource, MemorySink, MemorySource, Pipeline, Source,
+    StreamSpec, Transform,
+};
+use crate::audio::time::{AudioDuration, Timestamp};
+use crate::audio::types::AudioData;
+use crate::error::{AudioError, Result};
+use crate::progress::ProgressSink;
+
+/// What a redacted range is replaced with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "style", rename_all = "lowercase")]
+pub enum RedactionStyle {
+    #[default]
+    Silence,
+    /// A sine tone, so listeners can tell something was removed
+    Bleep { frequency_hz: f64, level_db: f64 },
+}
+
+impl RedactionStyle {
+    /// The usual 1 kHz censor tone
+    pub fn bleep() -> Self {
+        Self::Bleep {
+            frequency_hz: 1000.0,
+            level_db: -12.0,
+        }
+    }
+}
+
+/// Settings for [`redact_file`] and [`redact_audio`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactOptions {
+    pub style: RedactionStyle,
+    /// Length of the ramps into and out of each range, which keep the cuts
+    /// from clicking; they lie outside the range so none of it is heard
+    pub fade_ms: f64,
+}
+
+impl Default for RedactOptions {
+    fn default() -> Self {
+        Self {
+            style: RedactionStyle::Silence,
+            fade_ms: 5.0,
+        }
+    }
+}
+
+/// A stretch of audio that was removed, with why
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RedactedRange {
+    pub start: Timestamp,
+    pub end: Timestamp,
+    /// Titles of the markers that asked for it; overlapping markers are
+    /// redacted as one range
+    pub reasons: Vec<String>,
+}
+
+/// The audit trail of a redaction, saved next to the redacted copy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RedactionLog {
+    pub input: String,
+    pub output: String,
+    /// Local time with offset, RFC 3339
+    pub created_at: String,
+    pub style: RedactionStyle,
+    /// In time order, cut to the length of the input
+    pub ranges: Vec<RedactedRange>,
+    /// Total length removed
+    pub redacted: AudioDuration,
+}
+
+/// Where [`redact_file`] keeps the log for `output_path`, e.g.
+/// `interview.wav.redactions.json`
+pub fn redaction_log_path(output_path: &Path) -> PathBuf {
+    let mut name = output_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".redactions.json");
+    output_path.with_file_name(name)
+}
+
+/// Sort and merge the ranges to redact
+///
+/// # Errors
+/// [`AudioError::InvalidParameter`] for a point marker or one that ends
+/// before it starts
+pub fn plan_redactions(markers: &[Marker]) -> Result<Vec<RedactedRange>> {
+    let mut ranges = Vec::with_capacity(markers.len());
+    for marker in markers {
+        let Some(end) = marker.end.filter(|&end| end > marker.start) else {
+            return Err(AudioError::InvalidParameter(format!(
+                "Redaction '{}' at {} needs an end after its start",
+                marker.title, marker.start
+            )));
+        };
+        ranges.push(RedactedRange {
+            start: marker.start,
+            end,
+            reasons: vec![marker.title.clone()],
+        });
+    }
+    ranges.sort_by_key(|range| range.start);
+
+    let mut merged: Vec<RedactedRange> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => {
+                last.end = last.end.max(range.end);
+                for reason in range.reasons {
+                    if !reason.is_empty() && !last.reasons.contains(&reason) {
+                        last.reasons.push(reason);
+                    }
+                }
+            }
+            _ => merged.push(RedactedRange {
+                reasons: range.reasons.into_iter().filter(|r| !r.is_empty()).collect(),
+                ..range
+            }),
+        }
+    }
+    Ok(merged)
+}
+
+/// Silences or bleeps time ranges of a stream
+pub struct Redact {
+    ranges: Vec<RedactedRange>,
+    options: RedactOptions,
+    /// Ranges in frames, set by `configure`
+    frames: Vec<(u64, u64)>,
+    fade_frames: u64,
+    spec: StreamSpec,
+    position: u64,
+}
+
+impl Redact {
+    /// `ranges` as made by [`plan_redactions`]
+    pub fn new(ranges: Vec<RedactedRange>, options: RedactOptions) -> Self {
+        Self {
+            ranges,
+            options,
+            frames: Vec::new(),
+            fade_frames: 0,
+            spec: StreamSpec {
+                sample_rate: 0,
+                channels: 0,
+            },
+            position: 0,
+        }
+    }
+
+    /// How much of the replacement to mix in at `frame`: 1 inside a range,
+    /// ramping to 0 over the fade either side of it
+    fn weight(&self, frame: u64) -> f64 {
+        let fade = self.fade_frames;
+        let first = self.frames.partition_point(|&(_, end)| end + fade <= frame);
+        let mut weight: f64 = 0.0;
+        for &(start, end) in &self.frames[first..] {
+            if frame + fade < start {
+                break;
+            }
+            let w = if frame < start {
+                1.0 - (start - frame) as f64 / (fade + 1) as f64
+            } else if frame >= end {
+                1.0 - (frame - end + 1) as f64 / (fade + 1) as f64
+            } else {
+                1.0
+            };
+            weight = weight.max(w);
+        }
+        weight
+    }
+}
+
+impl Transform for Redact {
+    fn name(&self) -> &'static str {
+        "redact"
+    }
+
+    fn configure(&mut self, input: StreamSpec) -> Result<StreamSpec> {
+        if !(self.options.fade_ms.is_finite() && self.options.fade_ms >= 0.0) {
+            return Err(AudioError::InvalidParameter(format!(
+                "Fade must not be negative (got {}ms)",
+                self.options.fade_ms
+            )));
+        }
+        if let RedactionStyle::Bleep {
+            frequency_hz,
+            level_db,
+        } = self.options.style
+        {
+            let nyquist = f64::from(input.sample_rate) / 2.0;
+            if !(frequency_hz > 0.0 && frequency_hz < nyquist) {
+                return Err(AudioError::InvalidParameter(format!(
+                    "Bleep frequency must be between 0 and {} Hz (got {})",
+                    nyquist, frequency_hz
+                )));
+            }
+            if !(level_db.is_finite() && level_db <= 0.0) {
+                return Err(AudioError::InvalidParameter(format!(
+                    "Bleep level must be at most 0 dBFS (got {})",
+                    level_db
+                )));
+            }
+        }
+        self.frames = self
+            .ranges
+            .iter()
+            .map(|range| {
+                (range.start.to_frames(input.sample_rate), range.end.to_frames(input.sample_rate))
+            })
+            .collect();
+        self.fade_frames =
+            AudioDuration::from_seconds(self.options.fade_ms / 1000.0).to_frames(input.sample_rate);
+        self.spec = input;
+        Ok(input)
+    }
+
+    fn process(&mut self, mut chunk: AudioData) -> Result<AudioData> {
+        let channels = usize::from(self.spec.channels.max(1));
+        let rate = f64::from(self.spec.sample_rate);
+        for (i, frame) in chunk.samples.chunks_mut(channels).enumerate() {
+            let position = self.position + i as u64;
+            let weight = self.weight(position);
+            if weight == 0.0 {
+                continue;
+            }
+            let replacement = match self.options.style {
+                RedactionStyle::Silence => 0.0,
+                RedactionStyle::Bleep {
+                    frequency_hz,
+                    level_db,
+                } => db_to_linear(level_db) * (TAU * frequency_hz * position as f64 / rate).sin(),
+            };
+            let keep = (1.0 - weight) as Real;
+            let add = (replacement * weight) as Real;
+            for sample in frame {
+                *sample = from_real(to_real(*sample) * keep + add);
+            }
+        }
+        self.position += chunk.frame_count() as u64;
+        Ok(chunk)
+    }
+}
+
+/// Silence or bleep `markers`' ranges of a decoded buffer
+///
+/// # Errors
+/// See [`plan_redactions`]; also [`AudioError::InvalidParameter`] for a
+/// negative fade or a bleep that can't be played at this sample rate
+pub fn redact_audio(
+    audio: &AudioData,
+    markers: &[Marker],
+    options: &RedactOptions,
+) -> Result<AudioData> {
+    let mut sink = MemorySink::default();
+    Pipeline::new(MemorySource::new(audio.clone(), 65_536))
+        .then(Redact::new(plan_redactions(markers)?, *options))
+        .run(&mut sink)?;
+    Ok(sink.into_audio().unwrap_or_else(|| AudioData {
+        samples: Vec::new(),
+        sample_rate: audio.sample_rate,
+        channels: audio.channels,
+    }))
+}
+
+/// Write a copy of a file with `markers`' ranges silenced or bleeped, and
+/// an audit log of what was removed next to it (see [`redaction_log_path`])
+///
+/// Each marker's title is recorded as the reason for its range. Streams
+/// through the [`crate::audio::pipeline::Pipeline`], so WAV output works
+/// for files of any length. Reports the "redact" stage.
+///
+/// # Errors
+/// As for [`redact_audio`], and [`AudioError::InvalidParameter`] if
+/// `output_path` is the input itself
+pub fn redact_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    input_path: P,
+    output_path: Q,
+    format: &OutputFormat,
+    markers: &[Marker],
+    options: &RedactOptions,
+    progress: &mut dyn ProgressSink,
+) -> Result<RedactionLog> {
+    let (input_path, output_path) = (input_path.as_ref(), output_path.as_ref());
+    check_not_input(input_path, output_path)?;
+
+    let source = FileSource::open(input_path)?;
+    let mut ranges = plan_redactions(markers)?;
+    if let Some(frames) = source.total_frames() {
+        let length = Timestamp::from_frames(frames, source.spec().sample_rate);
+        ranges.retain(|range| range.start < length);
+        for range in &mut ranges {
+            range.end = range.end.min(length);
+        }
+    }
+
+    progress.stage("redact");
+    let mut sink = EncodeSink::new(output_path, *format);
+    Pipeline::new(source)
+        .then(Redact::new(ranges.clone(), *options))
+        .run_with_progress(&mut sink, progress)?;
+
+    let log = RedactionLog {
+        input: input_path.display().to_string(),
+        output: output_path.display().to_string(),
+        created_at: chrono::Local::now().to_rfc3339(),
+        style: options.style,
+        redacted: ranges
+            .iter()
+            .fold(AudioDuration::ZERO, |total, range| total + (range.end - range.start)),
+        ranges,
+    };
+    let json = serde_json::to_string_pretty(&log)
+        .map_err(|e| AudioError::InvalidParameter(e.to_string()))?;
+    std::fs::write(redaction_log_path(output_path), json)?;
+    Ok(log)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{decode_audio_file, encode_wav, release_reader, WavSampleFormat};
+    use crate::progress::NoProgress;
+
+    fn tone(seconds: u32) -> AudioData {
+        AudioData {
+            samples: (0..8000 * seconds * 2).map(|i| 0.5 * ((i / 2) as f32 * 0.05).sin()).collect(),
+            sample_rate: 8000,
+            channels: 2,
+        }
+    }
+
+    fn range(start: f64, end: f64, title: &str) -> Marker {
+        Marker::new(Timestamp::from_seconds(start), title).with_end(Timestamp::from_seconds(end))
+    }
+
+    fn peak(audio: &AudioData, start: f64, end: f64) -> f32 {
+        let frames = (start * 8000.0) as usize..(end * 8000.0) as usize;
+        audio.samples[frames.start * 2..frames.end * 2].iter().fold(0.0, |m, s| m.max(s.abs()))
+    }
+
+    #[test]
+    fn test_plan_merges_overlaps_and_rejects_points() {
+        let plan = plan_redactions(&[
+            range(5.0, 6.0, "address"),
+            range(1.0, 2.0, "name"),
+            range(1.5, 3.0, "name"),
+            range(2.5, 2.8, "phone"),
+        ])
+        .unwrap();
+        assert_eq!(plan.len(), 2);
+        assert_eq!(plan[0].start, Timestamp::from_seconds(1.0));
+        assert_eq!(plan[0].end, Timestamp::from_seconds(3.0));
+        assert_eq!(plan[0].reasons, ["name", "phone"]);
+
+        let point = Marker::new(Timestamp::from_seconds(1.0), "name");
+        assert!(matches!(plan_redactions(&[point]), Err(AudioError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_silence_and_bleep() {
+        let audio = tone(3);
+        let ranges = [range(1.0, 1.5, "name")];
+        let silenced = redact_audio(&audio, &ranges, &RedactOptions::default()).unwrap();
+        assert_eq!(silenced.samples.len(), audio.samples.len());
+        assert_eq!(peak(&silenced, 1.0, 1.5), 0.0);
+        assert!(peak(&silenced, 0.0, 0.99) > 0.49);
+        // The fade stays outside the range and is over after 5 ms
+        assert!(peak(&silenced, 1.506, 3.0) > 0.49);
+
+        let options = RedactOptions {
+            style: RedactionStyle::bleep(),
+            ..Default::default()
+        };
+        let bleeped = redact_audio(&audio, &ranges, &options).unwrap();
+        let level = peak(&bleeped, 1.0, 1.5);
+        assert!((level - db_to_linear(-12.0) as f32).abs() < 0.01, "{}", level);
+
+        let too_high = RedactOptions {
+            style: RedactionStyle::Bleep {
+                frequency_hz: 5000.0,
+                level_db: -12.0,
+            },
+            ..Default::default()
+        };
+        assert!(redact_audio(&audio, &ranges, &too_high).is_err());
+    }
+
+    #[test]
+    fn test_redact_file_writes_copy_and_log() {
+        let dir = std::env::temp_dir();
+        let input = dir.join("hermeneia_test_redact_in.wav");
+        let output = dir.join("hermeneia_test_redact_out.wav");
+        encode_wav(&tone(2), &input).unwrap();
+
+        let format = OutputFormat::Wav {
+            sample_format: WavSampleFormat::Float32,
+        };
+        let markers = [range(0.5, 1.0, "name"), range(1.8, 9.0, "phone number")];
+        let options = RedactOptions::default();
+        let log =
+            redact_file(&input, &output, &format, &markers, &options, &mut NoProgress).unwrap();
+        assert_eq!(log.ranges.len(), 2);
+        assert_eq!(log.ranges[1].end, Timestamp::from_seconds(2.0), "cut to the file's length");
+        assert_eq!(log.redacted, AudioDuration::from_seconds(0.7));
+
+        let written = decode_audio_file(&output).unwrap();
+        release_reader(&output);
+        assert_eq!(peak(&written, 0.5, 1.0), 0.0);
+        let saved: RedactionLog =
+            serde_json::from_str(&std::fs::read_to_string(redaction_log_path(&output)).unwrap())
+                .unwrap();
+        assert_eq!(saved, log);
+
+        let same = redact_file(&input, &input, &format, &markers, &options, &mut NoProgress);
+        assert!(matches!(same, Err(AudioError::InvalidParameter(_))));
+
+        for path in [input, redaction_log_path(&output), output] {
+            std::fs::remove_file(path).ok();
+        }
+    }
+}
@@ -0,0 +1,599 @@
+// src-tauri/src/audio/render.rs
+
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::audio::markers::Marker;
+use crate::audio::time::Timestamp;
+use crate::audio::types::WaveformPeaks;
+use crate::error::{AudioError, Result};
+
+/// An RGBA color used when rendering waveform images
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    /// Create an opaque color from RGB components
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: 255 }
+    }
+
+    /// Fully transparent color (useful as a background for web embeds)
+    pub const TRANSPARENT: Color = Color { r: 0, g: 0, b: 0, a: 0 };
+
+    /// Format as an SVG/CSS hex color (`#RRGGBB`)
+    fn to_hex_rgb(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+
+    /// Opacity in the range [0.0, 1.0] for SVG `fill-opacity`
+    fn opacity(self) -> f32 {
+        self.a as f32 / 255.0
+    }
+
+    /// Draw this color over `under`, weighted by its alpha
+    fn over(self, under: [u8; 4]) -> [u8; 4] {
+        let alpha = self.a as u32;
+        let mix = |top: u8, bottom: u8| {
+            ((top as u32 * alpha + bottom as u32 * (255 - alpha) + 127) / 255) as u8
+        };
+        [
+            mix(self.r, under[0]),
+            mix(self.g, under[1]),
+            mix(self.b, under[2]),
+            (alpha + under[3] as u32 * (255 - alpha) / 255) as u8,
+        ]
+    }
+}
+
+/// Serializes as `#RRGGBB`, or `#RRGGBBAA` when not opaque
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut hex = self.to_hex_rgb();
+        if self.a != 255 {
+            let _ = write!(hex, "{:02x}", self.a);
+        }
+        serializer.serialize_str(&hex)
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    /// Parse `#RRGGBB`, `#RRGGBBAA` (leading `#` optional) or `transparent`
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("transparent") {
+            return Ok(Color::TRANSPARENT);
+        }
+
+        let hex = s.trim_start_matches('#');
+        if !(hex.len() == 6 || hex.len() == 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!(
+                "Invalid color '{}': expected #RRGGBB or #RRGGBBAA",
+                s
+            ));
+        }
+
+        let component = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or(0);
+
+        Ok(Color {
+            r: component(0),
+            g: component(2),
+            b: component(4),
+            a: if hex.len() == 8 { component(6) } else { 255 },
+        })
+    }
+}
+
+/// Options controlling how a waveform image is rendered
+///
+/// Marker and selection times count from the start of the peaks, so for
+/// peaks of part of a file they are relative to that part.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RenderOptions {
+    /// Image width in pixels
+    pub width: u32,
+
+    /// Image height in pixels
+    pub height: u32,
+
+    /// Waveform fill color
+    pub foreground: Color,
+
+    /// Background color (may be transparent)
+    pub background: Color,
+
+    /// Markers drawn as vertical lines at their start
+    pub markers: Vec<Marker>,
+
+    /// Marker line color
+    pub marker_color: Color,
+
+    /// Time range highlighted behind the waveform
+    pub selection: Option<(Timestamp, Timestamp)>,
+
+    /// Highlight color; translucent so the background shows through
+    pub selection_color: Color,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            width: 1800,
+            height: 280,
+            foreground: Color::rgb(0x4a, 0x90, 0xe2),
+            background: Color::rgb(0xff, 0xff, 0xff),
+            markers: Vec::new(),
+            marker_color: Color::rgb(0xe2, 0x4a, 0x4a),
+            selection: None,
+            selection_color: Color {
+                a: 0x40,
+                ..Color::rgb(0x4a, 0x90, 0xe2)
+            },
+        }
+    }
+}
+
+impl RenderOptions {
+    fn validate(&self) -> Result<()> {
+        if self.width == 0 || self.height == 0 {
+            return Err(AudioError::RenderFailed(format!(
+                "Image dimensions must be non-zero (got {}x{})",
+                self.width, self.height
+            )));
+        }
+        Ok(())
+    }
+
+    /// Pixel column of each marker that falls inside the image
+    fn marker_columns(&self, peaks: &WaveformPeaks) -> Vec<(u32, &Marker)> {
+        self.markers
+            .iter()
+            .filter_map(|marker| {
+                let x = time_to_column(marker.start, peaks.duration_seconds, self.width)?;
+                (x < self.width).then_some((x, marker))
+            })
+            .collect()
+    }
+
+    /// Columns `start..end` covered by the selection, if any
+    fn selection_columns(&self, peaks: &WaveformPeaks) -> Option<(u32, u32)> {
+        let (start, end) = self.selection?;
+        let column = |time| time_to_column(time, peaks.duration_seconds, self.width);
+        let (start, end) = (column(start)?.min(self.width), column(end)?.min(self.width));
+        // Even a very short selection shows up as one column
+        (start < self.width && end >= start).then(|| (start, end.max(start + 1)))
+    }
+}
+
+/// Column a time falls in, for an image spanning `duration_seconds`
+fn time_to_column(time: Timestamp, duration_seconds: f64, width: u32) -> Option<u32> {
+    if duration_seconds <= 0.0 {
+        return None;
+    }
+    let x = time.as_seconds() / duration_seconds * width as f64;
+    Some(x.floor().min(u32::MAX as f64) as u32)
+}
+
+/// Reduce peaks to one (min, max) pair per pixel column
+///
+/// When there are more peaks than columns, each column takes the extreme
+/// values of all peaks it covers. When there are fewer, peaks are stretched
+/// across several columns.
+fn column_extents(peaks: &WaveformPeaks, width: u32) -> Vec<(f32, f32)> {
+    let num_peaks = peaks.min_peaks.len().min(peaks.max_peaks.len());
+    if num_peaks == 0 {
+        return vec![(0.0, 0.0); width as usize];
+    }
+
+    (0..width as usize)
+        .map(|x| {
+            let start = x * num_peaks / width as usize;
+            let end = ((x + 1) * num_peaks / width as usize).max(start + 1).min(num_peaks);
+
+            let min = peaks.min_peaks[start..end]
+                .iter()
+                .fold(f32::MAX, |acc, &v| acc.min(v));
+            let max = peaks.max_peaks[start..end]
+                .iter()
+                .fold(f32::MIN, |acc, &v| acc.max(v));
+
+            (min.clamp(-1.0, 1.0), max.clamp(-1.0, 1.0))
+        })
+        .collect()
+}
+
+/// Map an amplitude in [-1.0, 1.0] to a pixel row (0 = top)
+fn amplitude_to_row(amplitude: f32, height: u32) -> u32 {
+    let half = (height as f32 - 1.0) / 2.0;
+    let row = half - amplitude * half;
+    row.round().clamp(0.0, height as f32 - 1.0) as u32
+}
+
+/// Render waveform peaks to an RGBA pixel buffer
+///
+/// Returns `width * height * 4` bytes in row-major order.
+pub fn render_waveform_rgba(peaks: &WaveformPeaks, options: &RenderOptions) -> Result<Vec<u8>> {
+    options.validate()?;
+
+    let width = options.width as usize;
+    let height = options.height;
+
+    let bg = options.background;
+    let fg = options.foreground;
+
+    let mut pixels = Vec::with_capacity(width * height as usize * 4);
+    for _ in 0..width * height as usize {
+        pixels.extend_from_slice(&[bg.r, bg.g, bg.b, bg.a]);
+    }
+
+    // Tint whole columns, waveform on top, marker lines over everything
+    let tint_column = |pixels: &mut [u8], x: u32, color: Color| {
+        for y in 0..height as usize {
+            let offset = (y * width + x as usize) * 4;
+            let under: [u8; 4] = pixels[offset..offset + 4].try_into().unwrap();
+            pixels[offset..offset + 4].copy_from_slice(&color.over(under));
+        }
+    };
+
+    if let Some((start, end)) = options.selection_columns(peaks) {
+        for x in start..end {
+            tint_column(&mut pixels, x, options.selection_color);
+        }
+    }
+
+    for (x, (min, max)) in column_extents(peaks, options.width).into_iter().enumerate() {
+        let top = amplitude_to_row(max, height);
+        let bottom = amplitude_to_row(min, height);
+
+        for y in top..=bottom {
+            let offset = (y as usize * width + x) * 4;
+            pixels[offset..offset + 4].copy_from_slice(&[fg.r, fg.g, fg.b, fg.a]);
+        }
+    }
+
+    for (x, _) in options.marker_columns(peaks) {
+        tint_column(&mut pixels, x, options.marker_color);
+    }
+
+    Ok(pixels)
+}
+
+/// Render waveform peaks to a PNG file
+///
+/// # Example
+/// ```no_run
+/// use hermeneia_lib::audio::{
+///     extract_waveform_peaks, render_waveform_png, RenderOptions, WaveformOptions,
+/// };
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let peaks = extract_waveform_peaks("sermon.mp3", &WaveformOptions::new().num_peaks(4000))?;
+/// render_waveform_png(&peaks, &RenderOptions::default(), "sermon.png")?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn render_waveform_png<P: AsRef<Path>>(
+    peaks: &WaveformPeaks,
+    options: &RenderOptions,
+    output_path: P,
+) -> Result<()> {
+    let pixels = render_waveform_rgba(peaks, options)?;
+
+    let file = File::create(output_path)?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), options.width, options.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder
+        .write_header()
+        .map_err(|e| AudioError::RenderFailed(format!("Failed to write PNG header: {}", e)))?;
+    writer
+        .write_image_data(&pixels)
+        .map_err(|e| AudioError::RenderFailed(format!("Failed to write PNG data: {}", e)))?;
+
+    Ok(())
+}
+
+/// Render waveform peaks to an SVG document
+///
+/// The waveform is drawn as a single closed path (upper envelope from
+/// left to right, lower envelope back), which keeps the markup small
+/// regardless of the image width.
+pub fn render_waveform_svg(peaks: &WaveformPeaks, options: &RenderOptions) -> Result<String> {
+    options.validate()?;
+
+    let width = options.width;
+    let height = options.height;
+    let extents = column_extents(peaks, width);
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = width,
+        h = height
+    );
+
+    if options.background.a > 0 {
+        let _ = writeln!(
+            svg,
+            r#"  <rect width="100%" height="100%" fill="{}" fill-opacity="{:.3}"/>"#,
+            options.background.to_hex_rgb(),
+            options.background.opacity()
+        );
+    }
+
+    if let Some((start, end)) = options.selection_columns(peaks) {
+        let _ = writeln!(
+            svg,
+            r#"  <rect x="{}" width="{}" height="100%" fill="{}" fill-opacity="{:.3}"/>"#,
+            start,
+            end - start,
+            options.selection_color.to_hex_rgb(),
+            options.selection_color.opacity()
+        );
+    }
+
+    let mut path = String::new();
+    for (x, (_, max)) in extents.iter().enumerate() {
+        let cmd = if x == 0 { 'M' } else { 'L' };
+        let _ = write!(path, "{}{} {} ", cmd, x, amplitude_to_row(*max, height));
+    }
+    for (x, (min, _)) in extents.iter().enumerate().rev() {
+        let _ = write!(path, "L{} {} ", x, amplitude_to_row(*min, height) + 1);
+    }
+    path.push('Z');
+
+    let _ = writeln!(
+        svg,
+        r#"  <path d="{}" fill="{}" fill-opacity="{:.3}"/>"#,
+        path,
+        options.foreground.to_hex_rgb(),
+        options.foreground.opacity()
+    );
+
+    // Lines sit on pixel centers so they stay one pixel wide; the title
+    // shows as a tooltip in browsers
+    for (x, marker) in options.marker_columns(peaks) {
+        let _ = writeln!(
+            svg,
+            concat!(
+                r#"  <line x1="{x}.5" x2="{x}.5" y2="{}" stroke="{}" stroke-opacity="{:.3}">"#,
+                "<title>{}</title></line>"
+            ),
+            height,
+            options.marker_color.to_hex_rgb(),
+            options.marker_color.opacity(),
+            escape_xml(&marker.title),
+            x = x
+        );
+    }
+    svg.push_str("</svg>\n");
+
+    Ok(svg)
+}
+
+/// Render waveform peaks to an SVG file
+pub fn write_waveform_svg<P: AsRef<Path>>(
+    peaks: &WaveformPeaks,
+    options: &RenderOptions,
+    output_path: P,
+) -> Result<()> {
+    let svg = render_waveform_svg(peaks, options)?;
+    std::fs::write(output_path, svg)?;
+    Ok(())
+}
+
+/// Render waveform peaks to a PNG or SVG file, chosen by its extension
+pub fn write_waveform_image<P: AsRef<Path>>(
+    peaks: &WaveformPeaks,
+    options: &RenderOptions,
+    output_path: P,
+) -> Result<()> {
+    let output_path = output_path.as_ref();
+    let extension = output_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("png") => render_waveform_png(peaks, options, output_path),
+        Some("svg") => write_waveform_svg(peaks, options, output_path),
+        _ => Err(AudioError::UnsupportedFormat(format!(
+            "can't render a waveform to '{}'; use a .png or .svg file",
+            output_path.display()
+        ))),
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_peaks(num_peaks: usize, amplitude: f32) -> WaveformPeaks {
+        WaveformPeaks {
+            min_peaks: vec![-amplitude; num_peaks],
+            max_peaks: vec![amplitude; num_peaks],
+            num_peaks,
+            duration_seconds: 1.0,
+            channels: 1,
+            sample_rate: 44100,
+            ..WaveformPeaks::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!("#ff0000".parse::<Color>().unwrap(), Color::rgb(255, 0, 0));
+        assert_eq!("00ff0080".parse::<Color>().unwrap().a, 0x80);
+        assert_eq!("transparent".parse::<Color>().unwrap(), Color::TRANSPARENT);
+        assert!("#zzz".parse::<Color>().is_err());
+        assert!("#12345".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn test_rgba_buffer_size() {
+        let options = RenderOptions {
+            width: 64,
+            height: 32,
+            ..Default::default()
+        };
+        let pixels = render_waveform_rgba(&test_peaks(1000, 0.5), &options).unwrap();
+        assert_eq!(pixels.len(), 64 * 32 * 4);
+    }
+
+    #[test]
+    fn test_silence_renders_center_line_only() {
+        let options = RenderOptions {
+            width: 10,
+            height: 11,
+            foreground: Color::rgb(255, 0, 0),
+            background: Color::rgb(0, 0, 0),
+            ..Default::default()
+        };
+        let pixels = render_waveform_rgba(&test_peaks(10, 0.0), &options).unwrap();
+
+        for y in 0..11usize {
+            let offset = (y * 10) * 4;
+            let is_foreground = pixels[offset] == 255;
+            assert_eq!(is_foreground, y == 5, "row {} foreground = {}", y, is_foreground);
+        }
+    }
+
+    #[test]
+    fn test_full_scale_fills_column() {
+        let options = RenderOptions {
+            width: 4,
+            height: 8,
+            foreground: Color::rgb(255, 0, 0),
+            background: Color::rgb(0, 0, 0),
+            ..Default::default()
+        };
+        let pixels = render_waveform_rgba(&test_peaks(4, 1.0), &options).unwrap();
+        assert!(pixels.chunks(4).all(|px| px[0] == 255));
+    }
+
+    #[test]
+    fn test_zero_size_rejected() {
+        let options = RenderOptions {
+            width: 0,
+            ..Default::default()
+        };
+        assert!(render_waveform_rgba(&test_peaks(10, 0.5), &options).is_err());
+        assert!(render_waveform_svg(&test_peaks(10, 0.5), &options).is_err());
+    }
+
+    #[test]
+    fn test_svg_output() {
+        let options = RenderOptions {
+            width: 100,
+            height: 50,
+            background: Color::TRANSPARENT,
+            ..Default::default()
+        };
+        let svg = render_waveform_svg(&test_peaks(500, 0.5), &options).unwrap();
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(r#"viewBox="0 0 100 50""#));
+        assert!(!svg.contains("<rect"), "transparent background should be omitted");
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_png_round_trip() {
+        let path = std::env::temp_dir().join("hermeneia_test_render.png");
+        let options = RenderOptions {
+            width: 120,
+            height: 40,
+            ..Default::default()
+        };
+        render_waveform_png(&test_peaks(300, 0.7), &options, &path).unwrap();
+
+        let decoder = png::Decoder::new(File::open(&path).unwrap());
+        let reader = decoder.read_info().unwrap();
+        assert_eq!(reader.info().width, 120);
+        assert_eq!(reader.info().height, 40);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_selection_and_markers_are_drawn() {
+        let options = RenderOptions {
+            width: 10,
+            height: 5,
+            background: Color::rgb(0, 0, 0),
+            foreground: Color::rgb(0, 0, 255),
+            markers: vec![Marker::new(Timestamp::from_seconds(0.75), "Sermon")],
+            marker_color: Color::rgb(255, 0, 0),
+            selection: Some((Timestamp::from_seconds(0.25), Timestamp::from_seconds(0.5))),
+            selection_color: Color::rgb(0, 255, 0),
+        };
+        // Silence, so the top row shows only background, selection and markers
+        let pixels = render_waveform_rgba(&test_peaks(10, 0.0), &options).unwrap();
+        let top_row: Vec<[u8; 3]> = pixels[..10 * 4]
+            .chunks(4)
+            .map(|px| [px[0], px[1], px[2]])
+            .collect();
+        let (bg, sel, marker) = ([0, 0, 0], [0, 255, 0], [255, 0, 0]);
+        assert_eq!(top_row, vec![bg, bg, sel, sel, sel, bg, bg, marker, bg, bg]);
+
+        let svg = render_waveform_svg(&test_peaks(10, 0.0), &options).unwrap();
+        assert!(svg.contains(r#"<rect x="2" width="3""#));
+        assert!(svg.contains(r#"<line x1="7.5""#));
+        assert!(svg.contains("<title>Sermon</title>"));
+    }
+
+    #[test]
+    fn test_translucent_colors_blend() {
+        let half_red = Color { a: 128, ..Color::rgb(255, 0, 0) };
+        assert_eq!(half_red.over([0, 0, 255, 255]), [128, 0, 127, 255]);
+        assert_eq!(Color::TRANSPARENT.over([1, 2, 3, 4]), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_options_from_json() {
+        let options: RenderOptions = serde_json::from_str(
+            r##"{"width": 640, "marker_color": "#00ff0080", "selection": [1.0, 2.5]}"##,
+        )
+        .unwrap();
+        assert_eq!(options.width, 640);
+        assert_eq!(options.height, RenderOptions::default().height);
+        assert_eq!(options.marker_color, Color { a: 0x80, ..Color::rgb(0, 255, 0) });
+        assert_eq!(options.selection.unwrap().1, Timestamp::from_seconds(2.5));
+        assert_eq!(serde_json::to_string(&options.marker_color).unwrap(), r##""#00ff0080""##);
+    }
+}
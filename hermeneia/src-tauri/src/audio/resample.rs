@@ -0,0 +1,316 @@
+// src-tauri/src/audio/resample.rs
+
+use rubato::{
+    Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction,
+};
+
+use crate::audio::dsp::{from_real, to_real, Real};
+use crate::audio::types::AudioData;
+use crate::error::{AudioError, Result};
+use crate::profile;
+
+/// Number of input frames fed to the resampler per call
+const CHUNK_FRAMES: usize = 1024;
+
+/// Resample audio to a new sample rate using band-limited sinc interpolation
+///
+/// Returns a copy of the input unchanged if it is already at `target_rate`.
+/// The output length is `round(frames * target_rate / sample_rate)`, and the
+/// audio stays time-aligned with the input.
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::{AudioData, resample_audio};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// // One second of 44.1 kHz mono audio
+/// let audio = AudioData {
+///     samples: vec![0.0; 44100],
+///     sample_rate: 44100,
+///     channels: 1,
+/// };
+///
+/// let resampled = resample_audio(&audio, 16000)?;
+/// assert_eq!(resampled.sample_rate, 16000);
+/// assert_eq!(resampled.frame_count(), 16000);
+/// # Ok(())
+/// # }
+/// ```
+pub fn resample_audio(audio: &AudioData, target_rate: u32) -> Result<AudioData> {
+    let _stage = profile::stage("resample");
+    if audio.sample_rate == target_rate && target_rate != 0 && audio.channels != 0 {
+        return Ok(audio.clone());
+    }
+
+    let mut resampler = StreamResampler::new(audio.sample_rate, target_rate, audio.channels)?;
+    let mut samples = resampler.process(&audio.samples)?;
+    samples.extend(resampler.flush()?);
+
+    Ok(AudioData {
+        samples,
+        sample_rate: target_rate,
+        channels: audio.channels,
+    })
+}
+
+/// Sinc resampler for audio that arrives in chunks
+///
+/// Produces the same output as [`resample_audio`] on the concatenated
+/// input: [`StreamResampler::flush`] drains or pads the tail so the total
+/// length is `round(frames * target_rate / sample_rate)`.
+///
+/// rubato's sinc resamplers already centre their first output on the first
+/// input frame, so nothing is dropped from the start even though
+/// `output_delay` reports half the filter length.
+pub struct StreamResampler {
+    resampler: SincFixedIn<Real>,
+    ratio: f64,
+    /// Planar input not yet fed to the resampler
+    pending: Vec<Vec<Real>>,
+    frames_in: u64,
+    frames_out: u64,
+}
+
+impl StreamResampler {
+    pub fn new(source_rate: u32, target_rate: u32, channels: u16) -> Result<Self> {
+        if target_rate == 0 || source_rate == 0 {
+            return Err(AudioError::ResampleFailed(format!(
+                "Invalid sample rates: {} Hz -> {} Hz",
+                source_rate, target_rate
+            )));
+        }
+
+        if channels == 0 {
+            return Err(AudioError::ResampleFailed("Audio has no channels".to_string()));
+        }
+
+        let ratio = target_rate as f64 / source_rate as f64;
+        let params = SincInterpolationParameters {
+            sinc_len: 256,
+            f_cutoff: 0.95,
+            interpolation: SincInterpolationType::Cubic,
+            oversampling_factor: 128,
+            window: WindowFunction::BlackmanHarris2,
+        };
+
+        let resampler =
+            SincFixedIn::<Real>::new(ratio, 1.0, params, CHUNK_FRAMES, channels as usize)
+                .map_err(|e| {
+                    AudioError::ResampleFailed(format!("Failed to create resampler: {}", e))
+                })?;
+
+        Ok(Self {
+            resampler,
+            ratio,
+            pending: vec![Vec::new(); channels as usize],
+            frames_in: 0,
+            frames_out: 0,
+        })
+    }
+
+    /// Forget all input and output so far, e.g. after a seek
+    pub fn reset(&mut self) {
+        self.resampler.reset();
+        for plane in &mut self.pending {
+            plane.clear();
+        }
+        self.frames_in = 0;
+        self.frames_out = 0;
+    }
+
+    /// Resample the next interleaved chunk
+    ///
+    /// Input is processed in fixed blocks, so the output may lag behind
+    /// (or be empty for small chunks) until [`StreamResampler::flush`].
+    pub fn process(&mut self, samples: &[f32]) -> Result<Vec<f32>> {
+        let channels = self.pending.len();
+        for (plane, input) in self.pending.iter_mut().zip(deinterleave(samples, channels)) {
+            plane.extend(input);
+        }
+        self.frames_in += (samples.len() / channels) as u64;
+
+        let mut output = vec![Vec::new(); channels];
+        let mut position = 0;
+        while position + CHUNK_FRAMES <= self.pending[0].len() {
+            let chunk: Vec<&[Real]> = self
+                .pending
+                .iter()
+                .map(|plane| &plane[position..position + CHUNK_FRAMES])
+                .collect();
+
+            let processed = self
+                .resampler
+                .process(&chunk, None)
+                .map_err(|e| AudioError::ResampleFailed(e.to_string()))?;
+            append_planes(&mut output, processed);
+
+            position += CHUNK_FRAMES;
+        }
+        for plane in &mut self.pending {
+            plane.drain(..position);
+        }
+
+        Ok(self.emit(output, u64::MAX))
+    }
+
+    /// Resample whatever input is left and the filter tail
+    pub fn flush(&mut self) -> Result<Vec<f32>> {
+        let expected = (self.frames_in as f64 * self.ratio).round() as u64;
+        let mut output = vec![Vec::new(); self.pending.len()];
+
+        // Remaining input frames, if any
+        if !self.pending[0].is_empty() {
+            let chunk: Vec<&[Real]> = self.pending.iter().map(|plane| &plane[..]).collect();
+            let processed = self
+                .resampler
+                .process_partial(Some(&chunk), None)
+                .map_err(|e| AudioError::ResampleFailed(e.to_string()))?;
+            append_planes(&mut output, processed);
+            for plane in &mut self.pending {
+                plane.clear();
+            }
+        }
+
+        // Flush the filter tail until the output is complete
+        while self.frames_out + (output[0].len() as u64) < expected {
+            let processed = self
+                .resampler
+                .process_partial::<&[Real]>(None, None)
+                .map_err(|e| AudioError::ResampleFailed(e.to_string()))?;
+            if processed[0].is_empty() {
+                break;
+            }
+            append_planes(&mut output, processed);
+        }
+
+        // Pad to the exact length if the tail came up short
+        let mut samples = self.emit(output, expected.saturating_sub(self.frames_out));
+        let missing = expected.saturating_sub(self.frames_out);
+        samples.resize(samples.len() + missing as usize * self.pending.len(), 0.0);
+        self.frames_out += missing;
+        Ok(samples)
+    }
+
+    /// Cap at `limit` frames and interleave
+    fn emit(&mut self, mut planes: Vec<Vec<Real>>, limit: u64) -> Vec<f32> {
+        let end = planes[0].len().min(limit.min(usize::MAX as u64) as usize);
+        for plane in &mut planes {
+            plane.truncate(end);
+        }
+        self.frames_out += end as u64;
+        interleave(&planes)
+    }
+}
+
+/// Split interleaved samples into one Vec per channel at processing precision
+pub(crate) fn deinterleave(samples: &[f32], channels: usize) -> Vec<Vec<Real>> {
+    let frames = samples.len() / channels;
+    let mut planes = vec![Vec::with_capacity(frames); channels];
+
+    for frame in samples.chunks_exact(channels) {
+        for (plane, &sample) in planes.iter_mut().zip(frame) {
+            plane.push(to_real(sample));
+        }
+    }
+
+    planes
+}
+
+/// Merge per-channel buffers back into interleaved samples
+pub(crate) fn interleave(planes: &[Vec<Real>]) -> Vec<f32> {
+    let frames = planes.first().map_or(0, |p| p.len());
+    let mut samples = Vec::with_capacity(frames * planes.len());
+
+    for frame_idx in 0..frames {
+        for plane in planes {
+            samples.push(from_real(plane[frame_idx]));
+        }
+    }
+
+    samples
+}
+
+fn append_planes(output: &mut [Vec<Real>], processed: Vec<Vec<Real>>) {
+    for (out, plane) in output.iter_mut().zip(processed) {
+        out.extend(plane);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(freq: f32, sample_rate: u32, channels: u16, seconds: f32) -> AudioData {
+        let frames = (sample_rate as f32 * seconds) as usize;
+        let mut samples = Vec::with_capacity(frames * channels as usize);
+        for i in 0..frames {
+            let value = (2.0 * std::f32::consts::PI * freq * i as f32 / sample_rate as f32).sin();
+            for _ in 0..channels {
+                samples.push(value * 0.5);
+            }
+        }
+        AudioData {
+            samples,
+            sample_rate,
+            channels,
+        }
+    }
+
+    #[test]
+    fn test_same_rate_is_passthrough() {
+        let audio = sine(440.0, 44100, 2, 0.1);
+        let resampled = resample_audio(&audio, 44100).unwrap();
+        assert_eq!(resampled.samples, audio.samples);
+    }
+
+    #[test]
+    fn test_output_length_matches_ratio() {
+        for (from, to) in [(44100, 48000), (48000, 16000), (16000, 44100)] {
+            let audio = sine(440.0, from, 2, 1.3);
+            let resampled = resample_audio(&audio, to).unwrap();
+
+            let expected = (audio.frame_count() as f64 * to as f64 / from as f64).round() as usize;
+            assert_eq!(resampled.frame_count(), expected, "{} -> {}", from, to);
+            assert_eq!(resampled.sample_rate, to);
+            assert_eq!(resampled.channels, 2);
+        }
+    }
+
+    #[test]
+    fn test_preserves_amplitude() {
+        let audio = sine(440.0, 44100, 1, 1.0);
+        let resampled = resample_audio(&audio, 48000).unwrap();
+
+        // Skip the edges where the filter ramps in/out
+        let middle = &resampled.samples[4800..43200];
+        let peak = middle.iter().fold(0.0f32, |acc, &s| acc.max(s.abs()));
+        assert!((peak - 0.5).abs() < 0.02, "peak was {}", peak);
+    }
+
+    #[test]
+    fn test_stays_time_aligned() {
+        // A ramp shows any shift as an offset in value
+        let audio = AudioData {
+            samples: (0..3000).map(|i| i as f32 / 3000.0).collect(),
+            sample_rate: 1000,
+            channels: 1,
+        };
+        let resampled = resample_audio(&audio, 2000).unwrap();
+        assert!((resampled.samples[3000] - 0.5).abs() < 1e-3);
+        assert!((resampled.samples[1000] - 1.0 / 6.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_zero_rate_rejected() {
+        let audio = sine(440.0, 44100, 1, 0.1);
+        assert!(resample_audio(&audio, 0).is_err());
+    }
+
+    #[test]
+    fn test_interleave_round_trip() {
+        let samples = vec![1.0, -1.0, 2.0, -2.0, 3.0, -3.0];
+        let planes = deinterleave(&samples, 2);
+        assert_eq!(planes, vec![vec![1.0, 2.0, 3.0], vec![-1.0, -2.0, -3.0]]);
+        assert_eq!(interleave(&planes), samples);
+    }
+}
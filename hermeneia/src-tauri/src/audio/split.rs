@@ -0,0 +1,436 @@
+// src-tauri/src/audio/split.rs
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::analysis::silence::{detect_silence, SilenceOptions};
+use crate::audio::decoder::decode_audio_range;
+use crate::audio::encoder::{encode_audio_with_progress, OutputFormat};
+use crate::audio::markers::{close_markers, sort_markers, Marker};
+use crate::audio::time::Timestamp;
+use crate::audio::types::AudioData;
+use crate::error::{AudioError, Result};
+use crate::naming::{resolve_collision, CollisionPolicy, PathTemplate, TemplateFields};
+use crate::progress::{check_cancelled, ProgressSink};
+
+/// One piece of a split, as a time range in the source audio
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Segment {
+    /// 1-based position in the split
+    pub index: usize,
+    pub start_seconds: f64,
+    pub end_seconds: f64,
+    /// Name of the piece, for segments cut at markers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+impl Segment {
+    /// Length of the segment in seconds
+    pub fn duration_seconds(&self) -> f64 {
+        self.end_seconds - self.start_seconds
+    }
+}
+
+/// Plan fixed-length segments covering `duration_seconds`
+///
+/// The last segment holds whatever is left over and may be shorter.
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::split_every;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let segments = split_every(25.0, 10.0)?;
+/// assert_eq!(segments.len(), 3);
+/// assert_eq!(segments[2].start_seconds, 20.0);
+/// assert_eq!(segments[2].end_seconds, 25.0);
+/// # Ok(())
+/// # }
+/// ```
+pub fn split_every(duration_seconds: f64, every_seconds: f64) -> Result<Vec<Segment>> {
+    if !(every_seconds.is_finite() && every_seconds > 0.0) {
+        return Err(AudioError::InvalidParameter(format!(
+            "Segment length must be positive (got {}s)",
+            every_seconds
+        )));
+    }
+
+    let count = (duration_seconds / every_seconds).ceil() as usize;
+    Ok((0..count)
+        .map(|i| Segment {
+            index: i + 1,
+            start_seconds: i as f64 * every_seconds,
+            end_seconds: ((i + 1) as f64 * every_seconds).min(duration_seconds),
+            title: None,
+        })
+        .collect())
+}
+
+/// Plan segments separated by the silences in `audio`
+///
+/// Each cut lands in the middle of a silence so no sound is lost and every
+/// segment keeps a little quiet padding on both sides. Silence at the very
+/// start and end of the audio is dropped rather than becoming its own segment.
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::{split_by_silence, AudioData, SilenceOptions};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// // Two one-second sounds separated by a second of silence
+/// let mut samples = vec![0.5f32; 8000];
+/// samples.extend(vec![0.0; 8000]);
+/// samples.extend(vec![0.5; 8000]);
+/// let audio = AudioData { samples, sample_rate: 8000, channels: 1 };
+///
+/// let segments = split_by_silence(&audio, &SilenceOptions::default())?;
+/// assert_eq!(segments.len(), 2);
+/// assert!((segments[0].end_seconds - 1.5).abs() < 0.01);
+/// # Ok(())
+/// # }
+/// ```
+pub fn split_by_silence(audio: &AudioData, options: &SilenceOptions) -> Result<Vec<Segment>> {
+    let duration = audio.duration_seconds();
+    let silences = detect_silence(audio, options)?;
+
+    let mut cuts = Vec::with_capacity(silences.len() + 2);
+    let mut start = 0.0;
+    let mut end = duration;
+
+    for silence in &silences {
+        if silence.start_seconds <= 0.0 {
+            start = silence.end_seconds;
+        } else if silence.end_seconds >= duration {
+            end = silence.start_seconds;
+        } else {
+            cuts.push((silence.start_seconds + silence.end_seconds) / 2.0);
+        }
+    }
+
+    if end <= start {
+        // Nothing but silence
+        return Ok(Vec::new());
+    }
+
+    let mut bounds = vec![start];
+    bounds.extend(cuts);
+    bounds.push(end);
+
+    Ok(bounds
+        .windows(2)
+        .enumerate()
+        .map(|(i, pair)| Segment {
+            index: i + 1,
+            start_seconds: pair[0],
+            end_seconds: pair[1],
+            title: None,
+        })
+        .collect())
+}
+
+/// Plan one segment per marker, named after it
+///
+/// This is how cue sheets, chapters and the editor's markers become
+/// pieces. Markers without an end run to the next marker, the last one to
+/// `duration_seconds`. Ends past the audio are clamped, and markers that
+/// start at or after its end are dropped.
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::{split_at_markers, Marker, Timestamp};
+///
+/// let at = Timestamp::from_seconds;
+/// let markers = vec![Marker::new(at(0.0), "Prelude"), Marker::new(at(95.0), "Homily")];
+/// let segments = split_at_markers(&markers, 600.0);
+/// assert_eq!(segments[1].title.as_deref(), Some("Homily"));
+/// assert_eq!(segments[1].end_seconds, 600.0);
+/// ```
+pub fn split_at_markers(markers: &[Marker], duration_seconds: f64) -> Vec<Segment> {
+    let mut markers = markers.to_vec();
+    sort_markers(&mut markers);
+    close_markers(&mut markers, Timestamp::from_seconds(duration_seconds));
+
+    markers
+        .into_iter()
+        .filter(|m| m.start.as_seconds() < duration_seconds)
+        .enumerate()
+        .map(|(i, marker)| Segment {
+            index: i + 1,
+            start_seconds: marker.start.as_seconds(),
+            end_seconds: marker.end.map_or(duration_seconds, |end| {
+                end.as_seconds().min(duration_seconds)
+            }),
+            title: Some(marker.title).filter(|t| !t.is_empty()),
+        })
+        .collect()
+}
+
+/// Where each segment goes, relative to `dir`
+///
+/// Besides the values in `fields` (usually [`TemplateFields::for_input`]
+/// plus `format`), `template` can use `{segment}` (the index, zero-padded
+/// to the same width for every piece), `{index}` and `{title}` (the
+/// segment's title, else its number). A path that already exists is
+/// handled as `collisions` says.
+///
+/// # Returns
+/// A path per segment, or `None` for one to leave out because its file
+/// exists and `collisions` is [`CollisionPolicy::Skip`]
+///
+/// # Errors
+/// If the template gives two segments the same path
+pub fn segment_paths(
+    segments: &[Segment],
+    dir: &Path,
+    template: &PathTemplate,
+    fields: &TemplateFields,
+    collisions: CollisionPolicy,
+) -> Result<Vec<Option<PathBuf>>> {
+    let width = segments.len().to_string().len().max(3);
+    let mut seen = HashSet::new();
+    let paths = segments
+        .iter()
+        .map(|segment| {
+            let number = format!("{:0width$}", segment.index, width = width);
+            let mut fields = fields.clone();
+            fields
+                .set("index", segment.index.to_string())
+                .set("title", segment.title.clone().unwrap_or_else(|| number.clone()))
+                .set("segment", number);
+            let path = dir.join(template.render(&fields)?);
+            if !seen.insert(path.clone()) {
+                return Err(AudioError::InvalidParameter(format!(
+                    "Template '{}' gives more than one segment the name '{}'",
+                    template.as_str(),
+                    path.display()
+                )));
+            }
+            Ok(path)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // A suffixed name mustn't land on a later piece's own name
+    let mut taken = seen;
+    Ok(paths
+        .into_iter()
+        .map(|path| {
+            taken.remove(&path);
+            let path = resolve_collision(path, collisions, &taken)?;
+            taken.insert(path.clone());
+            Some(path)
+        })
+        .collect())
+}
+
+/// Encode each segment of `audio` to the matching path
+///
+/// Folders in the paths are created. Progress covers all the pieces;
+/// cancelling stops before the next one.
+pub fn export_segments(
+    audio: &AudioData,
+    segments: &[Segment],
+    paths: &[PathBuf],
+    format: &OutputFormat,
+    progress: &mut dyn ProgressSink,
+) -> Result<()> {
+    export_pieces(segments, paths, format, progress, |segment| {
+        Ok(extract_segment(audio, segment))
+    })
+}
+
+/// Like [`export_segments`], decoding each segment straight from the file
+///
+/// Only one segment is in memory at a time, so this is the streaming path
+/// for files too long to decode whole.
+pub fn export_file_segments<P: AsRef<Path>>(
+    path: P,
+    segments: &[Segment],
+    paths: &[PathBuf],
+    format: &OutputFormat,
+    progress: &mut dyn ProgressSink,
+) -> Result<()> {
+    export_pieces(segments, paths, format, progress, |segment| {
+        decode_audio_range(path.as_ref(), segment.start_seconds, segment.end_seconds)
+    })
+}
+
+fn export_pieces(
+    segments: &[Segment],
+    paths: &[PathBuf],
+    format: &OutputFormat,
+    progress: &mut dyn ProgressSink,
+    mut piece: impl FnMut(&Segment) -> Result<AudioData>,
+) -> Result<()> {
+    let count = segments.len();
+    for (i, (segment, path)) in segments.iter().zip(paths).enumerate() {
+        check_cancelled(progress)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let piece = piece(segment)?;
+        let mut scaled = |fraction: f64| progress.progress((i as f64 + fraction) / count as f64);
+        encode_audio_with_progress(&piece, path, format, &mut scaled)?;
+    }
+    progress.progress(1.0);
+    Ok(())
+}
+
+/// Copy one segment out of `audio`
+///
+/// Segment bounds are converted to whole frames and clamped to the audio.
+pub fn extract_segment(audio: &AudioData, segment: &Segment) -> AudioData {
+    let channels = audio.channels as usize;
+    let to_frame = |seconds: f64| {
+        ((seconds * audio.sample_rate as f64).round().max(0.0) as usize).min(audio.frame_count())
+    };
+    let start = to_frame(segment.start_seconds);
+    let end = to_frame(segment.end_seconds).max(start);
+
+    AudioData {
+        samples: audio.samples[start * channels..end * channels].to_vec(),
+        sample_rate: audio.sample_rate,
+        channels: audio.channels,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::NoProgress;
+
+    fn audio(parts: &[(f32, usize)]) -> AudioData {
+        AudioData {
+            samples: parts
+                .iter()
+                .flat_map(|&(level, frames)| std::iter::repeat_n(level, frames))
+                .collect(),
+            sample_rate: 1000,
+            channels: 1,
+        }
+    }
+
+    #[test]
+    fn test_split_every_covers_duration() {
+        let segments = split_every(30.0, 10.0).unwrap();
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[0].index, 1);
+        assert_eq!(segments.last().unwrap().end_seconds, 30.0);
+
+        assert!(split_every(0.0, 10.0).unwrap().is_empty());
+        assert!(split_every(10.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_split_by_silence_cuts_mid_gap() {
+        let audio = audio(&[(0.0, 700), (0.5, 1000), (0.0, 1000), (0.5, 2000), (0.0, 600)]);
+        let segments = split_by_silence(&audio, &SilenceOptions::default()).unwrap();
+
+        assert_eq!(segments.len(), 2);
+        assert!((segments[0].start_seconds - 0.7).abs() < 1e-9);
+        assert!((segments[0].end_seconds - 2.2).abs() < 1e-9);
+        assert!((segments[1].start_seconds - 2.2).abs() < 1e-9);
+        assert!((segments[1].end_seconds - 4.7).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_split_by_silence_all_silent() {
+        let audio = audio(&[(0.0, 3000)]);
+        assert!(split_by_silence(&audio, &SilenceOptions::default())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_extract_segment() {
+        let audio = AudioData {
+            samples: (0..20).map(|i| i as f32).collect(),
+            sample_rate: 10,
+            channels: 2,
+        };
+        let segment = Segment {
+            index: 1,
+            start_seconds: 0.2,
+            end_seconds: 0.5,
+            title: None,
+        };
+        let piece = extract_segment(&audio, &segment);
+        assert_eq!(piece.samples, vec![4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+
+        // Out-of-range bounds are clamped
+        let segment = Segment {
+            index: 2,
+            start_seconds: 0.8,
+            end_seconds: 5.0,
+            title: None,
+        };
+        assert_eq!(extract_segment(&audio, &segment).frame_count(), 2);
+    }
+    #[test]
+    fn test_split_at_markers() {
+        let at = Timestamp::from_seconds;
+        let markers = vec![
+            Marker::new(at(30.0), "Reading"),
+            Marker::new(at(0.0), "Hymn").with_end(at(20.0)),
+            Marker::new(at(50.0), "Sermon").with_end(at(90.0)),
+            Marker::new(at(70.0), "After the end"),
+        ];
+        let segments = split_at_markers(&markers, 60.0);
+        let spans: Vec<_> = segments
+            .iter()
+            .map(|s| (s.index, s.start_seconds, s.end_seconds, s.title.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![(1, 0.0, 20.0, "Hymn"), (2, 30.0, 50.0, "Reading"), (3, 50.0, 60.0, "Sermon")]
+        );
+    }
+
+    #[test]
+    fn test_export_segments_names_pieces_from_the_template() {
+        let audio = audio(&[(0.25, 3000)]);
+        let at = Timestamp::from_seconds;
+        let markers = vec![Marker::new(at(0.0), "Intro"), Marker::new(at(1.0), "")];
+        let segments = split_at_markers(&markers, audio.duration_seconds());
+
+        let dir = std::env::temp_dir().join("hermeneia_test_export_segments");
+        let mut fields = TemplateFields::new();
+        fields.set("album", "Vespers").set("format", "wav");
+        let template = PathTemplate::parse("{album}/{segment} {title}.{format}").unwrap();
+        let overwrite = CollisionPolicy::Overwrite;
+        let paths = segment_paths(&segments, &dir, &template, &fields, overwrite).unwrap();
+        let album = dir.join("Vespers");
+        let paths: Vec<PathBuf> = paths.into_iter().flatten().collect();
+        assert_eq!(paths, vec![album.join("001 Intro.wav"), album.join("002 002.wav")]);
+
+        let format = OutputFormat::Wav {
+            sample_format: crate::audio::WavSampleFormat::Pcm16,
+        };
+        let mut fractions = Vec::new();
+        let mut sink = |f: f64| fractions.push(f);
+        export_segments(&audio, &segments, &paths, &format, &mut sink).unwrap();
+        assert_eq!(fractions.last(), Some(&1.0));
+        let second = crate::audio::decode_audio_file(&paths[1]).unwrap();
+        assert_eq!(second.frame_count(), 2000);
+
+        // Decoding piece by piece from the file gives the same result
+        let source = dir.join("source.wav");
+        crate::audio::encode_wav(&audio, &source).unwrap();
+        export_file_segments(&source, &segments, &paths, &format, &mut NoProgress).unwrap();
+        let streamed = crate::audio::decode_audio_file(&paths[1]).unwrap();
+        assert_eq!(streamed.samples, second.samples);
+
+        // Pieces already written are kept under the other policies
+        let suffixed = segment_paths(&segments, &dir, &template, &fields, CollisionPolicy::Suffix);
+        let suffixed = suffixed.unwrap();
+        assert_eq!(suffixed[0], Some(album.join("001 Intro_2.wav")));
+        let skipped = segment_paths(&segments, &dir, &template, &fields, CollisionPolicy::Skip);
+        assert_eq!(skipped.unwrap(), vec![None, None]);
+
+        let clash = PathTemplate::parse("{album}.{format}").unwrap();
+        assert!(segment_paths(&segments, &dir, &clash, &fields, overwrite).is_err());
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,323 @@
+// src-tauri/src/audio/time.rs
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, Sub};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AudioError, Result};
+
+/// Ticks per second in [`Timestamp`] and [`AudioDuration`]
+///
+/// 705,600,000 is divisible by every common sample rate (8k, 11.025k,
+/// 16k, 22.05k, 32k, 44.1k, 48k, 88.2k, 96k, 176.4k, 192k), so a frame
+/// position at any of them is a whole number of ticks and converts back to
+/// the same frame with no rounding.
+pub const TICKS_PER_SECOND: u64 = 705_600_000;
+
+/// A position in a file, measured from its start
+///
+/// Stored as integer ticks ([`TICKS_PER_SECOND`]) rather than float
+/// seconds, so converting to frames and back is exact and positions can be
+/// compared and hashed. Serializes as a number of seconds, which is what
+/// the frontend and the JSON reports use.
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::{AudioDuration, Timestamp};
+///
+/// let start = Timestamp::from_frames(22050, 44100);
+/// assert_eq!(start.as_seconds(), 0.5);
+/// assert_eq!(start.to_frames(48000), 24000);
+///
+/// let end = start + AudioDuration::from_seconds(1.5);
+/// assert_eq!((end - start).to_frames(16000), 24000);
+/// ```
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(try_from = "f64", into = "f64")]
+pub struct Timestamp(u64);
+
+/// A length of audio
+///
+/// Same representation and serialization as [`Timestamp`]; subtracting two
+/// timestamps gives a duration, and adding a duration to a timestamp gives
+/// a timestamp.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(try_from = "f64", into = "f64")]
+pub struct AudioDuration(u64);
+
+/// Seconds to ticks; negative and NaN become 0, huge values saturate
+fn seconds_to_ticks(seconds: f64) -> u64 {
+    if seconds.is_nan() || seconds <= 0.0 {
+        return 0;
+    }
+    // `as` saturates at u64::MAX
+    (seconds * TICKS_PER_SECOND as f64).round() as u64
+}
+
+/// Ticks to the nearest frame at `sample_rate`
+fn ticks_to_frames(ticks: u64, sample_rate: u32) -> u64 {
+    let scaled = ticks as u128 * sample_rate as u128 + TICKS_PER_SECOND as u128 / 2;
+    (scaled / TICKS_PER_SECOND as u128).min(u64::MAX as u128) as u64
+}
+
+fn frames_to_ticks(frames: u64, sample_rate: u32) -> u64 {
+    debug_assert!(sample_rate > 0, "sample rate must be non-zero");
+    let ticks = frames as u128 * TICKS_PER_SECOND as u128 / sample_rate.max(1) as u128;
+    ticks.min(u64::MAX as u128) as u64
+}
+
+/// Strict seconds check for values coming from users or JSON
+fn checked_seconds(seconds: f64, what: &str) -> Result<u64> {
+    if seconds.is_finite() && seconds >= 0.0 {
+        Ok(seconds_to_ticks(seconds))
+    } else {
+        Err(AudioError::InvalidParameter(format!(
+            "{} must be a non-negative number of seconds (got {})",
+            what, seconds
+        )))
+    }
+}
+
+impl Timestamp {
+    /// The start of the file
+    pub const ZERO: Self = Self(0);
+
+    /// Position `seconds` into the file; negative or NaN clamps to the start
+    ///
+    /// Use `Timestamp::try_from(seconds)` to reject those instead.
+    pub fn from_seconds(seconds: f64) -> Self {
+        Self(seconds_to_ticks(seconds))
+    }
+
+    pub fn from_millis(millis: u64) -> Self {
+        Self(millis.saturating_mul(TICKS_PER_SECOND / 1000))
+    }
+
+    /// Position of frame `frame` at `sample_rate`
+    pub fn from_frames(frame: u64, sample_rate: u32) -> Self {
+        Self(frames_to_ticks(frame, sample_rate))
+    }
+
+    pub fn as_seconds(self) -> f64 {
+        self.0 as f64 / TICKS_PER_SECOND as f64
+    }
+
+    /// Nearest frame index at `sample_rate`
+    pub fn to_frames(self, sample_rate: u32) -> u64 {
+        ticks_to_frames(self.0, sample_rate)
+    }
+
+    /// Halfway between `self` and `other`
+    pub fn midpoint(self, other: Self) -> Self {
+        Self(self.0 / 2 + other.0 / 2 + (self.0 % 2 + other.0 % 2) / 2)
+    }
+
+    /// `self - earlier`, or zero if `earlier` is later
+    pub fn saturating_since(self, earlier: Self) -> AudioDuration {
+        AudioDuration(self.0.saturating_sub(earlier.0))
+    }
+}
+
+impl AudioDuration {
+    pub const ZERO: Self = Self(0);
+
+    /// `seconds` long; negative or NaN clamps to zero
+    pub fn from_seconds(seconds: f64) -> Self {
+        Self(seconds_to_ticks(seconds))
+    }
+
+    pub fn from_millis(millis: u64) -> Self {
+        Self(millis.saturating_mul(TICKS_PER_SECOND / 1000))
+    }
+
+    /// Length of `frames` frames at `sample_rate`
+    pub fn from_frames(frames: u64, sample_rate: u32) -> Self {
+        Self(frames_to_ticks(frames, sample_rate))
+    }
+
+    pub fn as_seconds(self) -> f64 {
+        self.0 as f64 / TICKS_PER_SECOND as f64
+    }
+
+    /// Nearest whole number of frames at `sample_rate`
+    pub fn to_frames(self, sample_rate: u32) -> u64 {
+        ticks_to_frames(self.0, sample_rate)
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl Add<AudioDuration> for Timestamp {
+    type Output = Timestamp;
+
+    fn add(self, rhs: AudioDuration) -> Timestamp {
+        Timestamp(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl AddAssign<AudioDuration> for Timestamp {
+    fn add_assign(&mut self, rhs: AudioDuration) {
+        *self = *self + rhs;
+    }
+}
+
+/// Saturates at the start of the file
+impl Sub<AudioDuration> for Timestamp {
+    type Output = Timestamp;
+
+    fn sub(self, rhs: AudioDuration) -> Timestamp {
+        Timestamp(self.0.saturating_sub(rhs.0))
+    }
+}
+
+/// Distance between two positions; zero if `rhs` is later
+impl Sub<Timestamp> for Timestamp {
+    type Output = AudioDuration;
+
+    fn sub(self, rhs: Timestamp) -> AudioDuration {
+        self.saturating_since(rhs)
+    }
+}
+
+impl Add for AudioDuration {
+    type Output = AudioDuration;
+
+    fn add(self, rhs: AudioDuration) -> AudioDuration {
+        AudioDuration(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl AddAssign for AudioDuration {
+    fn add_assign(&mut self, rhs: AudioDuration) {
+        *self = *self + rhs;
+    }
+}
+
+/// Saturates at zero
+impl Sub for AudioDuration {
+    type Output = AudioDuration;
+
+    fn sub(self, rhs: AudioDuration) -> AudioDuration {
+        AudioDuration(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl Mul<u32> for AudioDuration {
+    type Output = AudioDuration;
+
+    fn mul(self, rhs: u32) -> AudioDuration {
+        AudioDuration(self.0.saturating_mul(rhs as u64))
+    }
+}
+
+impl Div<u32> for AudioDuration {
+    type Output = AudioDuration;
+
+    fn div(self, rhs: u32) -> AudioDuration {
+        AudioDuration(self.0 / rhs as u64)
+    }
+}
+
+impl TryFrom<f64> for Timestamp {
+    type Error = AudioError;
+
+    fn try_from(seconds: f64) -> Result<Self> {
+        checked_seconds(seconds, "Timestamp").map(Self)
+    }
+}
+
+impl TryFrom<f64> for AudioDuration {
+    type Error = AudioError;
+
+    fn try_from(seconds: f64) -> Result<Self> {
+        checked_seconds(seconds, "Duration").map(Self)
+    }
+}
+
+impl From<Timestamp> for f64 {
+    fn from(timestamp: Timestamp) -> f64 {
+        timestamp.as_seconds()
+    }
+}
+
+impl From<AudioDuration> for f64 {
+    fn from(duration: AudioDuration) -> f64 {
+        duration.as_seconds()
+    }
+}
+
+impl From<AudioDuration> for std::time::Duration {
+    fn from(duration: AudioDuration) -> Self {
+        std::time::Duration::from_nanos(
+            (duration.0 as u128 * 1_000_000_000 / TICKS_PER_SECOND as u128) as u64,
+        )
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.3}s", self.as_seconds())
+    }
+}
+
+impl fmt::Display for AudioDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.3}s", self.as_seconds())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frames_round_trip_at_common_rates() {
+        for rate in [8000, 11025, 16000, 22050, 32000, 44100, 48000, 88200, 96000, 192000] {
+            for frame in [0, 1, 441, 12_345_677, 3 * 3600 * rate as u64] {
+                assert_eq!(Timestamp::from_frames(frame, rate).to_frames(rate), frame);
+            }
+        }
+    }
+
+    #[test]
+    fn test_converts_between_rates_exactly() {
+        let t = Timestamp::from_frames(44100 * 90 + 1, 44100);
+        // One 44.1k frame doesn't land on a 48k frame; round to the nearest
+        assert_eq!(t.to_frames(48000), 48000 * 90 + 1);
+        assert_eq!(Timestamp::from_seconds(2.5).to_frames(22050), 55125);
+    }
+
+    #[test]
+    fn test_arithmetic_saturates() {
+        let a = Timestamp::from_seconds(1.0);
+        let b = Timestamp::from_seconds(3.0);
+        assert_eq!((b - a).as_seconds(), 2.0);
+        assert_eq!(a - b, AudioDuration::ZERO);
+        assert_eq!(a - AudioDuration::from_seconds(5.0), Timestamp::ZERO);
+        assert_eq!(a.midpoint(b).as_seconds(), 2.0);
+        assert_eq!((AudioDuration::from_seconds(3.0) / 2).as_seconds(), 1.5);
+    }
+
+    #[test]
+    fn test_bad_seconds() {
+        assert_eq!(Timestamp::from_seconds(-1.0), Timestamp::ZERO);
+        assert_eq!(Timestamp::from_seconds(f64::NAN), Timestamp::ZERO);
+        assert!(Timestamp::try_from(-1.0).is_err());
+        assert!(AudioDuration::try_from(f64::INFINITY).is_err());
+    }
+
+    #[test]
+    fn test_serializes_as_seconds() {
+        let t = Timestamp::from_millis(1500);
+        assert_eq!(serde_json::to_string(&t).unwrap(), "1.5");
+        assert_eq!(serde_json::from_str::<Timestamp>("1.5").unwrap(), t);
+        assert!(serde_json::from_str::<Timestamp>("-2").is_err());
+    }
+}
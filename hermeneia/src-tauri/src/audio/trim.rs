@@ -1,16 +1,44 @@
 // src-tauri/src/audio/trim.rs
 
-use crate::audio::types::{AudioData, TrimParams};
-use crate::error::{AudioError, Result};
+use std::borrow::Cow;
+use std::ops::Range;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::audio::channels::remix_channels;
+use crate::audio::decoder::{
+    decode_audio_file_with_progress, decode_audio_range_with, get_audio_info,
+};
+use crate::audio::encoder::{encode_audio_with_progress, OutputFormat, WavStreamWriter};
+use crate::audio::pipeline::check_not_input;
+use crate::audio::resample::resample_audio;
+use crate::audio::time::{AudioDuration, Timestamp};
+use crate::audio::types::{AudioData, FadeCurve, TrimParams, TrimPoint};
+use crate::error::{AudioError, DecodeError, Result};
+use crate::memory::{decoded_size_bytes, MemoryBudget, ProcessingMode};
+use crate::profile;
+use crate::progress::{check_cancelled, ProgressSink};
+
+/// What [`trim_file`] kept
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TrimSummary {
+    /// The range, with ends relative to the end of the file resolved
+    pub start: Timestamp,
+    pub end: Timestamp,
+    /// Frames written to the output
+    pub frames: u64,
+}
 
 /// Trim audio data to a specific time range
 /// 
 /// # Arguments
 /// * `audio` - The audio data to trim
-/// * `params` - Start and end times in seconds
+/// * `params` - Range to keep; relative ends are resolved against the audio's length
 /// 
 /// # Returns
-/// New AudioData containing only the trimmed portion
+/// New AudioData containing only the trimmed portion, faded in and out as
+/// `params` asks
 /// 
 /// # Example
 /// ```
@@ -35,30 +63,23 @@ use crate::error::{AudioError, Result};
 /// # }
 /// ```
 pub fn trim_audio(audio: &AudioData, params: &TrimParams) -> Result<AudioData> {
-    // Validate trim range against audio duration
-    let duration = audio.duration_seconds();
-    
-    if params.end_seconds > duration {
-        return Err(AudioError::TrimRangeOutOfBounds {
-            start: params.start_seconds,
-            end: params.end_seconds,
-            duration,
-        });
-    }
+    audio.validate()?;
 
-    // Calculate sample indices
-    // Formula: sample_index = time_in_seconds × sample_rate × num_channels
-    let samples_per_second = audio.sample_rate as f64 * audio.channels as f64;
-    
-    let start_sample_index = (params.start_seconds * samples_per_second) as usize;
-    let end_sample_index = (params.end_seconds * samples_per_second) as usize;
+    // Resolve the range against the audio's length
+    let frames = audio.frame_count() as u64;
+    let range = params.resolve(Timestamp::from_frames(frames, audio.sample_rate))?;
 
-    // Clamp to valid range
-    let start_sample_index = start_sample_index.min(audio.samples.len());
-    let end_sample_index = end_sample_index.min(audio.samples.len());
+    // Frame positions are exact; each frame holds one sample per channel
+    let channels = audio.channels as usize;
+    let start_frame = range.start.to_frames(audio.sample_rate).min(frames) as usize;
+    let end_frame = range.end.to_frames(audio.sample_rate).min(frames) as usize;
+    let start_sample_index = start_frame * channels;
+    let end_sample_index = end_frame * channels;
 
     // Extract the slice
-    let trimmed_samples = audio.samples[start_sample_index..end_sample_index].to_vec();
+    let mut trimmed_samples = audio.samples[start_sample_index..end_sample_index].to_vec();
+    let clip_frames = (end_frame - start_frame) as u64;
+    Fader::new(params, clip_frames, audio.sample_rate).apply(&mut trimmed_samples, channels);
 
     Ok(AudioData {
         samples: trimmed_samples,
@@ -67,9 +88,448 @@ pub fn trim_audio(audio: &AudioData, params: &TrimParams) -> Result<AudioData> {
     })
 }
 
+/// Applies a clip's fades as its chunks go by
+struct Fader {
+    fade_in: u64,
+    fade_out: u64,
+    curve: FadeCurve,
+    /// Length of the whole clip, where the fade out ends
+    clip_frames: u64,
+    /// Clip frames already passed
+    frame: u64,
+}
+
+impl Fader {
+    fn new(params: &TrimParams, clip_frames: u64, sample_rate: u32) -> Self {
+        let frames = |ms: f64| AudioDuration::from_seconds(ms / 1000.0).to_frames(sample_rate);
+        Self {
+            fade_in: frames(params.fade_in_ms),
+            fade_out: frames(params.fade_out_ms),
+            curve: params.fade_curve,
+            clip_frames,
+            frame: 0,
+        }
+    }
+
+    /// Fade the next interleaved frames of the clip
+    fn apply(&mut self, samples: &mut [f32], channels: usize) {
+        let fade_out_from = self.clip_frames.saturating_sub(self.fade_out);
+        for frame in samples.chunks_exact_mut(channels) {
+            let at = self.frame;
+            self.frame += 1;
+            if at >= self.fade_in && at < fade_out_from {
+                continue;
+            }
+            let mut gain = 1.0;
+            if at < self.fade_in {
+                gain *= self.curve.gain(at as f64 / self.fade_in as f64);
+            }
+            if at >= fade_out_from {
+                // The last frame is silent, like the first one of a fade in
+                let left = self.clip_frames.saturating_sub(at + 1);
+                gain *= self.curve.gain(left as f64 / self.fade_out as f64);
+            }
+            frame.iter_mut().for_each(|sample| *sample *= gain);
+        }
+    }
+}
+
+/// Trim several ranges out of the same audio, in the order given
+///
+/// Ranges may overlap and needn't be in file order; each is cut on
+/// its own as [`trim_audio`] would.
+pub fn extract_segments(audio: &AudioData, segments: &[TrimParams]) -> Result<Vec<AudioData>> {
+    segments.iter().map(|params| trim_audio(audio, params)).collect()
+}
+
+/// Join clips end to end into one
+///
+/// The result has the first clip's sample rate and channel count; clips
+/// that differ are remixed and resampled to match.
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::{concatenate, AudioData};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let intro = AudioData { samples: vec![0.5; 8000], sample_rate: 8000, channels: 1 };
+/// let stereo = AudioData { samples: vec![0.25; 32000], sample_rate: 16000, channels: 2 };
+///
+/// let joined = concatenate(&[intro, stereo])?;
+/// assert_eq!((joined.sample_rate, joined.channels), (8000, 1));
+/// assert_eq!(joined.frame_count(), 16000);
+/// # Ok(())
+/// # }
+/// ```
+pub fn concatenate(clips: &[AudioData]) -> Result<AudioData> {
+    let Some(first) = clips.first() else {
+        return Err(AudioError::InvalidParameter("No audio to join".to_string()));
+    };
+    let mut joined = AudioData {
+        samples: Vec::new(),
+        sample_rate: first.sample_rate,
+        channels: first.channels,
+    };
+    joined.validate()?;
+    for clip in clips {
+        clip.validate()?;
+        let mut clip = Cow::Borrowed(clip);
+        if clip.channels != joined.channels {
+            clip = Cow::Owned(remix_channels(&clip, joined.channels)?);
+        }
+        if clip.sample_rate != joined.sample_rate {
+            clip = Cow::Owned(resample_audio(&clip, joined.sample_rate)?);
+        }
+        joined.samples.extend_from_slice(&clip.samples);
+    }
+    Ok(joined)
+}
+
+/// Write the part of a file that `params` keeps, decoding only that part
+///
+/// Seeks to the start of the range instead of decoding everything before
+/// it, so a 5-second clip from a 3-hour MP3 costs 5 seconds of decoding.
+/// WAV output is written as it decodes; other formats hold the clip, which
+/// is checked against `budget` first. Files that don't report their length
+/// are decoded whole when the range is relative to their end. Reports the
+/// "decode" and "encode" stages.
+///
+/// # Errors
+/// [`AudioError::InvalidParameter`] if `output_path` is the input itself;
+/// [`DecodeError::EmptyRange`] if the range holds no audio
+pub fn trim_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    input_path: P,
+    output_path: Q,
+    params: &TrimParams,
+    format: &OutputFormat,
+    budget: &MemoryBudget,
+    progress: &mut dyn ProgressSink,
+) -> Result<TrimSummary> {
+    let output = TrimOutput::new(*format);
+    trim_file_with(input_path, output_path, params, &output, budget, progress)
+}
+
+/// How [`trim_file_with`] writes a clip
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrimOutput {
+    pub format: OutputFormat,
+    /// Mix the clip to this many channels
+    pub channels: Option<u16>,
+    /// Resample the clip to this rate in Hz
+    pub sample_rate: Option<u32>,
+    /// Decode the whole file and cut it in memory instead of seeking, when
+    /// the whole file fits the budget
+    pub full_decode: bool,
+}
+
+impl TrimOutput {
+    pub fn new(format: OutputFormat) -> Self {
+        Self {
+            format,
+            channels: None,
+            sample_rate: None,
+            full_decode: false,
+        }
+    }
+
+    pub fn channels(mut self, channels: u16) -> Self {
+        self.channels = Some(channels);
+        self
+    }
+
+    pub fn sample_rate(mut self, sample_rate: u32) -> Self {
+        self.sample_rate = Some(sample_rate);
+        self
+    }
+
+    pub fn full_decode(mut self, full_decode: bool) -> Self {
+        self.full_decode = full_decode;
+        self
+    }
+}
+
+/// Write the part of a file that `params` keeps like [`trim_file`],
+/// converting the clip as `output` asks
+///
+/// Mixing down is done as the clip streams; resampling needs the clip in
+/// memory, so a resampled WAV is checked against `budget` like the other
+/// formats. Cancellation through `progress` is checked once per chunk; a
+/// WAV that can't be written whole, cancelled included, is removed.
+///
+/// # Returns
+/// The resolved range and the frames written, at the output's rate
+pub fn trim_file_with<P: AsRef<Path>, Q: AsRef<Path>>(
+    input_path: P,
+    output_path: Q,
+    params: &TrimParams,
+    output: &TrimOutput,
+    budget: &MemoryBudget,
+    progress: &mut dyn ProgressSink,
+) -> Result<TrimSummary> {
+    let (input_path, output_path) = (input_path.as_ref(), output_path.as_ref());
+    check_not_input(input_path, output_path)?;
+
+    let info = get_audio_info(input_path)?;
+    let range = if info.duration_seconds > 0.0 {
+        Some(params.resolve(Timestamp::from_seconds(info.duration_seconds))?)
+    } else if let (TrimPoint::At(start), TrimPoint::At(end)) = (params.start, params.end) {
+        Some(start..end)
+    } else {
+        // Relative ends need the length, which only a full decode finds
+        None
+    };
+    let whole = decoded_size_bytes(info.duration_seconds, info.sample_rate, info.channels);
+    let seek = !output.full_decode || budget.mode_for(whole) == ProcessingMode::Streaming;
+    let Some(range) = range.filter(|_| seek) else {
+        let audio = decode_audio_file_with_progress(input_path, progress)?;
+        let range = params.resolve(Timestamp::from_frames(
+            audio.frame_count() as u64,
+            audio.sample_rate,
+        ))?;
+        let clip = convert_clip(trim_audio(&audio, params)?, output)?;
+        progress.stage("encode");
+        encode_audio_with_progress(&clip, output_path, &output.format, progress)?;
+        return Ok(TrimSummary {
+            start: range.start,
+            end: range.end,
+            frames: clip.frame_count() as u64,
+        });
+    };
+
+    let (start, end) = (range.start.as_seconds(), range.end.as_seconds());
+    let clip_frames = (range.end - range.start).to_frames(info.sample_rate);
+    let expected = clip_frames.max(1) as f64;
+    let mut fader = Fader::new(params, clip_frames, info.sample_rate);
+    let channels = info.channels as usize;
+    let mix_to = output.channels.filter(|&to| to != info.channels);
+    let resampling = output.sample_rate.is_some_and(|rate| rate != info.sample_rate);
+    let _stage = profile::stage("decode");
+    progress.stage("decode");
+
+    let frames = match output.format {
+        OutputFormat::Wav { sample_format } if !resampling => {
+            let out_channels = mix_to.unwrap_or(info.channels);
+            let mut writer =
+                WavStreamWriter::create(output_path, info.sample_rate, out_channels, sample_format)?
+                    .dither(true);
+            let mut written = 0;
+            let streamed = decode_audio_range_with(input_path, start, end, |mut chunk| {
+                check_cancelled(progress)?;
+                fader.apply(&mut chunk.samples, channels);
+                written += chunk.frame_count();
+                match mix_to {
+                    Some(to) => writer.write_samples(&remix_channels(&chunk, to)?.samples)?,
+                    None => writer.write_samples(&chunk.samples)?,
+                }
+                progress.progress((written as f64 / expected).min(1.0));
+                Ok(())
+            })
+            .and_then(|frames| writer.finalize().map(|()| frames));
+            match streamed {
+                Ok(frames) if frames > 0 => frames,
+                result => {
+                    // Don't leave a cut-short or empty file behind
+                    std::fs::remove_file(output_path).ok();
+                    result?
+                }
+            }
+        }
+        _ => {
+            budget.check(decoded_size_bytes(end - start, info.sample_rate, info.channels))?;
+            let mut clip: Option<AudioData> = None;
+            decode_audio_range_with(input_path, start, end, |mut chunk| {
+                check_cancelled(progress)?;
+                fader.apply(&mut chunk.samples, channels);
+                let clip = match &mut clip {
+                    Some(clip) => {
+                        clip.samples.extend_from_slice(&chunk.samples);
+                        clip
+                    }
+                    None => clip.insert(chunk),
+                };
+                progress.progress((clip.frame_count() as f64 / expected).min(1.0));
+                Ok(())
+            })?;
+            match clip {
+                Some(clip) => {
+                    let clip = convert_clip(clip, output)?;
+                    progress.stage("encode");
+                    encode_audio_with_progress(&clip, output_path, &output.format, progress)?;
+                    clip.frame_count() as u64
+                }
+                None => 0,
+            }
+        }
+    };
+    if frames == 0 {
+        return Err(DecodeError::EmptyRange { start, end }.into());
+    }
+
+    Ok(TrimSummary {
+        start: range.start,
+        end: range.end,
+        frames,
+    })
+}
+
+/// Mix and resample a clip held in memory as `output` asks
+fn convert_clip(mut clip: AudioData, output: &TrimOutput) -> Result<AudioData> {
+    if let Some(channels) = output.channels.filter(|&to| to != clip.channels) {
+        clip = remix_channels(&clip, channels)?;
+    }
+    if let Some(sample_rate) = output.sample_rate.filter(|&to| to != clip.sample_rate) {
+        clip = resample_audio(&clip, sample_rate)?;
+    }
+    Ok(clip)
+}
+
+/// Cut several ranges out of a file and write them, joined in the order
+/// given, to `output_path`
+///
+/// Like [`trim_file`], each range is seeked to rather than decoded from
+/// the start, WAV output is written as it decodes, and other formats hold
+/// the joined clip after checking it against `budget`. Files that don't
+/// report their length are decoded whole only when a range is relative to
+/// their end. A WAV that can't be written whole is removed.
+///
+/// # Returns
+/// What each range kept, in order
+///
+/// # Errors
+/// [`AudioError::InvalidParameter`] if there are no ranges or `output_path`
+/// is the input itself; [`DecodeError::EmptyRange`] if a range holds no
+/// audio
+pub fn join_file_segments<P: AsRef<Path>, Q: AsRef<Path>>(
+    input_path: P,
+    output_path: Q,
+    segments: &[TrimParams],
+    format: &OutputFormat,
+    budget: &MemoryBudget,
+    progress: &mut dyn ProgressSink,
+) -> Result<Vec<TrimSummary>> {
+    let (input_path, output_path) = (input_path.as_ref(), output_path.as_ref());
+    check_not_input(input_path, output_path)?;
+    if segments.is_empty() {
+        return Err(AudioError::InvalidParameter("No ranges to join".to_string()));
+    }
+
+    let info = get_audio_info(input_path)?;
+    let ranges = if info.duration_seconds > 0.0 {
+        let duration = Timestamp::from_seconds(info.duration_seconds);
+        let ranges = segments.iter().map(|params| params.resolve(duration));
+        Some(ranges.collect::<Result<Vec<_>>>()?)
+    } else {
+        // Ranges from the start can be seeked to without the length
+        let absolute = segments.iter().map(|params| match (params.start, params.end) {
+            (TrimPoint::At(start), TrimPoint::At(end)) => Some(start..end),
+            _ => None,
+        });
+        absolute.collect::<Option<Vec<_>>>()
+    };
+    let Some(ranges) = ranges else {
+        // Relative ends need the length, which only a full decode finds
+        let audio = decode_audio_file_with_progress(input_path, progress)?;
+        let duration = Timestamp::from_frames(audio.frame_count() as u64, audio.sample_rate);
+        let clips = extract_segments(&audio, segments)?;
+        let summaries = segments
+            .iter()
+            .zip(&clips)
+            .map(|(params, clip)| {
+                segment_summary(params.resolve(duration)?, clip.frame_count() as u64)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        progress.stage("encode");
+        encode_audio_with_progress(&concatenate(&clips)?, output_path, format, progress)?;
+        return Ok(summaries);
+    };
+
+    let total_seconds: f64 = ranges.iter().map(|r| (r.end - r.start).as_seconds()).sum();
+    let expected = (total_seconds * info.sample_rate as f64).max(1.0);
+    let _stage = profile::stage("decode");
+    progress.stage("decode");
+
+    let faders = segments.iter().zip(&ranges).map(|(params, range)| {
+        Fader::new(params, (range.end - range.start).to_frames(info.sample_rate), info.sample_rate)
+    });
+    let channels = info.channels as usize;
+    let mut summaries = Vec::with_capacity(ranges.len());
+    let mut written = 0u64;
+    let mut report = |frames: usize, progress: &mut dyn ProgressSink| {
+        written += frames as u64;
+        progress.progress((written as f64 / expected).min(1.0));
+    };
+    if let OutputFormat::Wav { sample_format } = *format {
+        let mut writer =
+            WavStreamWriter::create(output_path, info.sample_rate, info.channels, sample_format)?
+                .dither(true);
+        let streamed = ranges
+            .iter()
+            .zip(faders)
+            .try_for_each(|(range, mut fader)| {
+                let (start, end) = (range.start.as_seconds(), range.end.as_seconds());
+                let frames = decode_audio_range_with(input_path, start, end, |mut chunk| {
+                    check_cancelled(progress)?;
+                    fader.apply(&mut chunk.samples, channels);
+                    writer.write_samples(&chunk.samples)?;
+                    report(chunk.frame_count(), progress);
+                    Ok(())
+                })?;
+                summaries.push(segment_summary(range.clone(), frames)?);
+                Ok(())
+            })
+            .and_then(|()| writer.finalize());
+        if let Err(e) = streamed {
+            // Don't leave a cut-short file behind
+            std::fs::remove_file(output_path).ok();
+            return Err(e);
+        }
+    } else {
+        budget.check(decoded_size_bytes(total_seconds, info.sample_rate, info.channels))?;
+        let mut joined = AudioData {
+            samples: Vec::new(),
+            sample_rate: info.sample_rate,
+            channels: info.channels,
+        };
+        for (range, mut fader) in ranges.iter().zip(faders) {
+            let (start, end) = (range.start.as_seconds(), range.end.as_seconds());
+            let frames = decode_audio_range_with(input_path, start, end, |mut chunk| {
+                check_cancelled(progress)?;
+                fader.apply(&mut chunk.samples, channels);
+                joined.samples.extend_from_slice(&chunk.samples);
+                report(chunk.frame_count(), progress);
+                Ok(())
+            })?;
+            summaries.push(segment_summary(range.clone(), frames)?);
+        }
+        progress.stage("encode");
+        encode_audio_with_progress(&joined, output_path, format, progress)?;
+    }
+    Ok(summaries)
+}
+
+/// What a segment of [`join_file_segments`] kept, or an error if it held
+/// no audio, the way [`trim_audio`] rejects a range outside the file
+fn segment_summary(range: Range<Timestamp>, frames: u64) -> Result<TrimSummary> {
+    if frames == 0 {
+        return Err(DecodeError::EmptyRange {
+            start: range.start.as_seconds(),
+            end: range.end.as_seconds(),
+        }
+        .into());
+    }
+    Ok(TrimSummary {
+        start: range.start,
+        end: range.end,
+        frames,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::audio::encoder::{WavSampleFormat, DEFAULT_FLAC_COMPRESSION};
+    use crate::audio::{decode_audio_file, encode_wav_with_format, release_reader};
+    use crate::progress::NoProgress;
 
     /// Helper to create test audio data
     fn create_test_audio(duration_seconds: f64, sample_rate: u32, channels: u16) -> AudioData {
@@ -121,6 +581,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_trim_last_seconds() {
+        let audio = create_test_audio(10.0, 44100, 2);
+        let trimmed = trim_audio(&audio, &TrimParams::last(2.5).unwrap()).unwrap();
+        assert_eq!(trimmed.duration_seconds(), 2.5);
+        assert_eq!(trimmed.samples[..], audio.samples[audio.samples.len() - 220500..]);
+
+        let tail = trim_audio(&audio, &TrimParams::to_end(9.0).unwrap()).unwrap();
+        assert_eq!(tail.duration_seconds(), 1.0);
+    }
+
     #[test]
     fn test_invalid_trim_params() {
         // Start > End
@@ -139,4 +610,293 @@ mod tests {
         assert_eq!(mono.samples.len(), 44100);
         assert_eq!(stereo.samples.len(), 88200); // 2x for stereo
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_trim_fades_in_and_out() {
+        let audio = AudioData {
+            samples: vec![1.0; 2000 * 2],
+            sample_rate: 1000,
+            channels: 2,
+        };
+        // 100 ms in, 200 ms out, over a one-second clip
+        let params = TrimParams::new(0.5, 1.5).unwrap().fades(100.0, 200.0);
+        let clip = trim_audio(&audio, &params).unwrap();
+        let left: Vec<f32> = clip.samples.iter().step_by(2).copied().collect();
+        assert_eq!(clip.samples[..2], [0.0, 0.0]);
+        assert_eq!(left[50], 0.5);
+        assert!(left[100..800].iter().all(|&s| s == 1.0));
+        assert!((left[899] - 0.5).abs() < 0.01);
+        assert_eq!(left[999], 0.0);
+        assert!(left[800..].windows(2).all(|w| w[1] <= w[0]));
+
+        let cosine = trim_audio(&audio, &params.clone().fade_curve(FadeCurve::Cosine)).unwrap();
+        assert!((cosine.samples[2 * 25] - 0.146).abs() < 0.01);
+        assert_eq!(cosine.samples[2 * 50], 0.5);
+
+        let negative = TrimParams::new(0.5, 1.5).unwrap().fades(-1.0, 0.0);
+        assert!(matches!(trim_audio(&audio, &negative), Err(AudioError::InvalidTrimParams(_))));
+
+        // Streamed from a file, the fades come out the same
+        let dir = std::env::temp_dir();
+        let input = dir.join("hermeneia_test_trim_fade_in.wav");
+        let output = dir.join("hermeneia_test_trim_fade_out.wav");
+        encode_wav_with_format(&audio, &input, WavSampleFormat::Float32).unwrap();
+        let wav = OutputFormat::Wav {
+            sample_format: WavSampleFormat::Float32,
+        };
+        let budget = MemoryBudget::from_mb(64);
+        trim_file(&input, &output, &params, &wav, &budget, &mut NoProgress).unwrap();
+        assert_eq!(decode_audio_file(&output).unwrap().samples, clip.samples);
+        for path in [input, output] {
+            release_reader(&path);
+            std::fs::remove_file(path).ok();
+        }
+    }
+
+    #[test]
+    fn test_extract_and_concatenate_segments() {
+        let audio = AudioData {
+            samples: (0..1000).map(|i| i as f32 / 1000.0).collect(),
+            sample_rate: 100,
+            channels: 1,
+        };
+        let segments = [
+            TrimParams::new(8.0, 9.0).unwrap(),
+            TrimParams::new(1.0, 1.5).unwrap(),
+            TrimParams::last(0.25).unwrap(),
+        ];
+        let clips = extract_segments(&audio, &segments).unwrap();
+        let joined = concatenate(&clips).unwrap();
+        assert_eq!(joined.frame_count(), 175);
+        assert_eq!(joined.samples[..2], [0.8, 0.801]);
+        assert_eq!(joined.samples[100..102], [0.1, 0.101]);
+        assert_eq!(joined.samples[150], 0.975);
+
+        // Joined onto stereo, the mono clip is doubled up
+        let stereo = create_test_audio(0.5, 100, 2);
+        let joined = concatenate(&[stereo, clips[1].clone()]).unwrap();
+        assert_eq!(joined.channels, 2);
+        assert_eq!(joined.samples[100..104], [0.1, 0.1, 0.101, 0.101]);
+
+        let faster = create_test_audio(1.0, 200, 1);
+        let joined = concatenate(&[clips[1].clone(), faster]).unwrap();
+        assert_eq!((joined.sample_rate, joined.frame_count()), (100, 150));
+
+        assert!(concatenate(&[]).is_err());
+        assert!(extract_segments(&audio, &[TrimParams::new(5.0, 11.0).unwrap()]).is_err());
+    }
+
+    #[test]
+    fn test_join_file_segments_in_order() {
+        let audio = AudioData {
+            samples: (0..8000 * 3).map(|i| i as f32 / 24000.0).collect(),
+            sample_rate: 8000,
+            channels: 1,
+        };
+        let dir = std::env::temp_dir();
+        let input = dir.join("hermeneia_test_join_in.wav");
+        let output = dir.join("hermeneia_test_join_out.wav");
+        encode_wav_with_format(&audio, &input, WavSampleFormat::Float32).unwrap();
+        let wav = OutputFormat::Wav {
+            sample_format: WavSampleFormat::Float32,
+        };
+        let budget = MemoryBudget::from_mb(64);
+
+        let segments = [TrimParams::last(0.5).unwrap(), TrimParams::new(0.5, 1.0).unwrap()];
+        let summaries =
+            join_file_segments(&input, &output, &segments, &wav, &budget, &mut NoProgress)
+                .unwrap();
+        assert_eq!(summaries[0].start, Timestamp::from_seconds(2.5));
+        assert_eq!(summaries.iter().map(|s| s.frames).collect::<Vec<_>>(), [4000, 4000]);
+        let joined = decode_audio_file(&output).unwrap();
+        assert_eq!(joined.samples[..4000], audio.samples[20000..]);
+        assert_eq!(joined.samples[4000..], audio.samples[4000..8000]);
+        release_reader(&output);
+
+        let flac = output.with_extension("flac");
+        let format = OutputFormat::Flac {
+            bits_per_sample: 24,
+            compression_level: DEFAULT_FLAC_COMPRESSION,
+        };
+        // Two whole FLAC blocks
+        let blocks = [TrimParams::new(2.0, 2.512).unwrap(), TrimParams::new(0.0, 0.512).unwrap()];
+        let summaries =
+            join_file_segments(&input, &flac, &blocks, &format, &budget, &mut NoProgress);
+        assert_eq!(summaries.unwrap().len(), 2);
+        assert_eq!(decode_audio_file(&flac).unwrap().frame_count(), 8192);
+
+        let nothing = [TrimParams::new(5.0, 6.0).unwrap()];
+        assert!(join_file_segments(&input, &output, &nothing, &wav, &budget, &mut NoProgress)
+            .is_err());
+        assert!(join_file_segments(&input, &output, &[], &wav, &budget, &mut NoProgress).is_err());
+        for path in [input, output, flac] {
+            release_reader(&path);
+            std::fs::remove_file(path).ok();
+        }
+    }
+
+    #[test]
+    fn test_trim_file_decodes_only_the_range() {
+        let audio = AudioData {
+            samples: (0..8000 * 3).map(|i| i as f32 / 24000.0).collect(),
+            sample_rate: 8000,
+            channels: 1,
+        };
+        let dir = std::env::temp_dir();
+        let input = dir.join("hermeneia_test_trim_file_in.wav");
+        let output = dir.join("hermeneia_test_trim_file_out.wav");
+        encode_wav_with_format(&audio, &input, WavSampleFormat::Float32).unwrap();
+        let wav = OutputFormat::Wav {
+            sample_format: WavSampleFormat::Float32,
+        };
+        let budget = MemoryBudget::from_mb(64);
+
+        let last = TrimParams::last(1.0).unwrap();
+        let summary = trim_file(&input, &output, &last, &wav, &budget, &mut NoProgress).unwrap();
+        assert_eq!(summary.start, Timestamp::from_seconds(2.0));
+        assert_eq!(summary.frames, 8000);
+        assert_eq!(decode_audio_file(&output).unwrap().samples, audio.samples[16000..]);
+        release_reader(&output);
+
+        let flac = output.with_extension("flac");
+        // One whole FLAC block
+        let middle = TrimParams::new(1.0, 1.512).unwrap();
+        let format = OutputFormat::Flac {
+            bits_per_sample: 24,
+            compression_level: DEFAULT_FLAC_COMPRESSION,
+        };
+        let summary = trim_file(&input, &flac, &middle, &format, &budget, &mut NoProgress);
+        assert_eq!(summary.unwrap().frames, 4096);
+        assert_eq!(decode_audio_file(&flac).unwrap().frame_count(), 4096);
+
+        let nothing = TrimParams::new(5.0, 6.0).unwrap();
+        assert!(trim_file(&input, &output, &nothing, &wav, &budget, &mut NoProgress).is_err());
+        assert!(trim_file(&input, &input, &middle, &wav, &budget, &mut NoProgress).is_err());
+        for path in [input, output, flac] {
+            release_reader(&path);
+            std::fs::remove_file(path).ok();
+        }
+    }
+
+    /// Asks to stop once `remaining` progress reports have come in
+    struct CancelAfter {
+        remaining: usize,
+    }
+
+    impl ProgressSink for CancelAfter {
+        fn progress(&mut self, _fraction: f64) {
+            self.remaining = self.remaining.saturating_sub(1);
+        }
+
+        fn is_cancelled(&self) -> bool {
+            self.remaining == 0
+        }
+    }
+
+    #[test]
+    fn test_trim_file_with_converts_and_cleans_up() {
+        let audio = create_test_audio(3.0, 8000, 2);
+        let dir = std::env::temp_dir();
+        let input = dir.join("hermeneia_test_trim_with_in.wav");
+        let output = dir.join("hermeneia_test_trim_with_out.wav");
+        encode_wav_with_format(&audio, &input, WavSampleFormat::Float32).unwrap();
+        let wav = OutputFormat::Wav {
+            sample_format: WavSampleFormat::Pcm16,
+        };
+        let budget = MemoryBudget::from_mb(64);
+        let middle = TrimParams::new(1.0, 2.0).unwrap();
+
+        // Mixed down as it streams
+        let mono = TrimOutput::new(wav).channels(1);
+        let summary =
+            trim_file_with(&input, &output, &middle, &mono, &budget, &mut NoProgress).unwrap();
+        assert_eq!(summary.frames, 8000);
+        let clip = decode_audio_file(&output).unwrap();
+        assert_eq!((clip.channels, clip.frame_count()), (1, 8000));
+        release_reader(&output);
+
+        // Resampled in memory, from the whole file when asked
+        let resampled = mono.sample_rate(16000).full_decode(true);
+        let summary =
+            trim_file_with(&input, &output, &middle, &resampled, &budget, &mut NoProgress);
+        assert_eq!(summary.unwrap().frames, 16000);
+        let clip = decode_audio_file(&output).unwrap();
+        assert_eq!((clip.channels, clip.sample_rate), (1, 16000));
+        release_reader(&output);
+
+        // Stopped part way, the streamed WAV doesn't stay behind
+        let mut cancel = CancelAfter { remaining: 1 };
+        let result = trim_file_with(&input, &output, &middle, &mono, &budget, &mut cancel);
+        assert!(matches!(result, Err(AudioError::Cancelled)));
+        assert!(!output.exists());
+        for path in [input, output] {
+            release_reader(&path);
+            std::fs::remove_file(path).ok();
+        }
+    }
+
+    /// Records the stages an operation reports
+    #[derive(Default)]
+    struct Stages(Vec<&'static str>);
+
+    impl ProgressSink for Stages {
+        fn stage(&mut self, name: &'static str) {
+            self.0.push(name);
+        }
+
+        fn progress(&mut self, _fraction: f64) {}
+    }
+
+    #[test]
+    fn test_join_seeks_in_a_file_of_unknown_length() {
+        let audio = AudioData {
+            samples: (0..8000 * 3).map(|i| i as f32 / 24000.0).collect(),
+            sample_rate: 8000,
+            channels: 1,
+        };
+        let dir = std::env::temp_dir();
+        let input = dir.join("hermeneia_test_join_streamed_in.flac");
+        let output = dir.join("hermeneia_test_join_streamed_out.wav");
+        crate::audio::encode_flac(&audio, &input, 24, DEFAULT_FLAC_COMPRESSION).unwrap();
+        // A streamed FLAC leaves the STREAMINFO sample count at 0 ("unknown")
+        let mut bytes = std::fs::read(&input).unwrap();
+        bytes[21] &= 0xf0;
+        bytes[22..26].fill(0);
+        std::fs::write(&input, &bytes).unwrap();
+        release_reader(&input);
+        assert_eq!(crate::audio::get_audio_info(&input).unwrap().duration_seconds, 0.0);
+        let wav = OutputFormat::Wav {
+            sample_format: WavSampleFormat::Float32,
+        };
+        let budget = MemoryBudget::from_mb(64);
+
+        // Ranges from the start are seeked to, with no full decode first
+        let segments = [TrimParams::new(2.0, 2.5).unwrap(), TrimParams::new(0.5, 1.0).unwrap()];
+        let mut stages = Stages::default();
+        let summaries =
+            join_file_segments(&input, &output, &segments, &wav, &budget, &mut stages).unwrap();
+        assert_eq!(stages.0, ["decode"]);
+        assert_eq!(summaries.iter().map(|s| s.frames).collect::<Vec<_>>(), [4000, 4000]);
+        release_reader(&output);
+
+        // A range past the end is an error, and the partial WAV goes away
+        let past = [TrimParams::new(0.0, 0.5).unwrap(), TrimParams::new(5.0, 6.0).unwrap()];
+        let result = join_file_segments(&input, &output, &past, &wav, &budget, &mut NoProgress);
+        assert!(matches!(result, Err(AudioError::Decode(DecodeError::EmptyRange { .. }))));
+        assert!(!output.exists());
+
+        // One relative end needs the length, so the file is decoded whole
+        let relative = [TrimParams::new(0.5, 1.0).unwrap(), TrimParams::last(0.5).unwrap()];
+        let mut stages = Stages::default();
+        let summaries =
+            join_file_segments(&input, &output, &relative, &wav, &budget, &mut stages).unwrap();
+        assert_eq!(stages.0[0], "decode");
+        assert!(stages.0.contains(&"encode"));
+        let length = decode_audio_file(&input).unwrap().duration_seconds();
+        assert_eq!(summaries[1].end.as_seconds(), length);
+        for path in [input, output] {
+            release_reader(&path);
+            std::fs::remove_file(path).ok();
+        }
+    }
+}
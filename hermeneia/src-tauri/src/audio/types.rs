@@ -1,11 +1,22 @@
+use std::iter::{Skip, StepBy};
+use std::ops::Range;
+use std::slice::{Chunks, ChunksExact, ChunksExactMut, ChunksMut};
+
 use serde::{Deserialize, Serialize};
 
+use crate::audio::time::{AudioDuration, Timestamp};
+use crate::error::{AudioError, Result};
+
 /// Represents decoded audio data in memory as PCM samples
 /// 
 /// Samples are stored interleaved: [L, R, L, R, ...] for stereo
 /// or [M, M, M, ...] for mono, where each sample is a 32-bit float
 /// in the range [-1.0, 1.0]
+///
+/// With the `serde-audio` feature it also implements `Serialize` and
+/// `Deserialize`; for checkpoints on disk prefer [`AudioData::save_raw`].
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde-audio", derive(Serialize, Deserialize))]
 pub struct AudioData {
     /// PCM audio samples as 32-bit floats, interleaved by channel
     /// Example for stereo: [left_0, right_0, left_1, right_1, ...]
@@ -19,6 +30,109 @@ pub struct AudioData {
 }
 
 impl AudioData {
+    /// Create audio from interleaved samples, checking the layout
+    ///
+    /// # Errors
+    /// `InvalidParameter` if the rate or channel count is zero, or the
+    /// sample count isn't a whole number of frames
+    ///
+    /// # Example
+    /// ```
+    /// use hermeneia_lib::audio::AudioData;
+    ///
+    /// let stereo = AudioData::new(vec![0.1, -0.1, 0.2, -0.2], 48000, 2).unwrap();
+    /// assert_eq!(stereo.frame_count(), 2);
+    /// assert!(AudioData::new(vec![0.1, -0.1, 0.2], 48000, 2).is_err());
+    /// ```
+    pub fn new(samples: Vec<f32>, sample_rate: u32, channels: u16) -> Result<Self> {
+        let audio = Self {
+            samples,
+            sample_rate,
+            channels,
+        };
+        audio.validate()?;
+        Ok(audio)
+    }
+
+    /// `duration_seconds` of digital silence
+    pub fn silence(duration_seconds: f64, sample_rate: u32, channels: u16) -> Result<Self> {
+        if !(duration_seconds >= 0.0 && duration_seconds.is_finite()) {
+            return Err(AudioError::InvalidParameter(format!(
+                "Silence duration must be a non-negative number of seconds (got {})",
+                duration_seconds
+            )));
+        }
+        let frames = (duration_seconds * sample_rate as f64).round() as usize;
+        Self::new(vec![0.0; frames * channels as usize], sample_rate, channels)
+    }
+
+    /// Create audio from frames, each holding one sample per channel
+    ///
+    /// The channel count is taken from the first frame; every frame must
+    /// have the same length.
+    ///
+    /// # Example
+    /// ```
+    /// use hermeneia_lib::audio::AudioData;
+    ///
+    /// let stereo = AudioData::from_frames([[0.5, -0.5], [0.25, -0.25]], 44100).unwrap();
+    /// assert_eq!(stereo.channels, 2);
+    /// assert_eq!(stereo.samples, vec![0.5, -0.5, 0.25, -0.25]);
+    /// ```
+    pub fn from_frames<F, I>(frames: I, sample_rate: u32) -> Result<Self>
+    where
+        F: AsRef<[f32]>,
+        I: IntoIterator<Item = F>,
+    {
+        let mut samples = Vec::new();
+        let mut channels = None;
+        for (index, frame) in frames.into_iter().enumerate() {
+            let frame = frame.as_ref();
+            let expected = *channels.get_or_insert(frame.len());
+            if frame.len() != expected {
+                return Err(AudioError::InvalidParameter(format!(
+                    "Frame {} has {} sample(s), expected {}",
+                    index,
+                    frame.len(),
+                    expected
+                )));
+            }
+            samples.extend_from_slice(frame);
+        }
+        let channels = channels.ok_or_else(|| {
+            AudioError::InvalidParameter("Cannot infer the channel count from no frames".to_string())
+        })?;
+        let channels = u16::try_from(channels).map_err(|_| {
+            AudioError::InvalidParameter(format!("Too many channels: {}", channels))
+        })?;
+        Self::new(samples, sample_rate, channels)
+    }
+
+    /// Check the invariants [`AudioData::new`] enforces
+    ///
+    /// The fields are public, so buffers built by hand can be checked
+    /// before they reach code that divides by the rate or channel count.
+    pub fn validate(&self) -> Result<()> {
+        if self.sample_rate == 0 {
+            return Err(AudioError::InvalidParameter(
+                "Sample rate must be greater than zero".to_string(),
+            ));
+        }
+        if self.channels == 0 {
+            return Err(AudioError::InvalidParameter(
+                "Channel count must be greater than zero".to_string(),
+            ));
+        }
+        if !self.samples.len().is_multiple_of(self.channels as usize) {
+            return Err(AudioError::InvalidParameter(format!(
+                "{} sample(s) is not a whole number of {}-channel frames",
+                self.samples.len(),
+                self.channels
+            )));
+        }
+        Ok(())
+    }
+
     /// Calculate the total duration of the audio in seconds
     /// 
     /// Duration = total_samples / (sample_rate * channels)
@@ -34,6 +148,219 @@ impl AudioData {
     pub fn frame_count(&self) -> usize {
         self.samples.len() / self.channels as usize
     }
+
+    /// Iterate over frames, each a slice with one sample per channel
+    ///
+    /// # Example
+    /// ```
+    /// use hermeneia_lib::audio::AudioData;
+    ///
+    /// let stereo = AudioData::new(vec![0.1, 0.2, 0.3, 0.4], 44100, 2).unwrap();
+    /// let left: Vec<f32> = stereo.frames().map(|frame| frame[0]).collect();
+    /// assert_eq!(left, vec![0.1, 0.3]);
+    /// ```
+    pub fn frames(&self) -> ChunksExact<'_, f32> {
+        self.samples.chunks_exact(self.channels.max(1) as usize)
+    }
+
+    /// Like [`AudioData::frames`], for changing samples in place
+    pub fn frames_mut(&mut self) -> ChunksExactMut<'_, f32> {
+        self.samples.chunks_exact_mut(self.channels.max(1) as usize)
+    }
+
+    /// Iterate over the samples of one channel
+    ///
+    /// # Panics
+    /// If `channel` is not less than `self.channels`
+    ///
+    /// # Example
+    /// ```
+    /// use hermeneia_lib::audio::AudioData;
+    ///
+    /// let mut stereo = AudioData::new(vec![0.1, 0.2, 0.3, 0.4], 44100, 2).unwrap();
+    /// for sample in stereo.channel_mut(1) {
+    ///     *sample = 0.0;
+    /// }
+    /// assert_eq!(stereo.channel(1).sum::<f32>(), 0.0);
+    /// assert_eq!(stereo.channel(0).copied().collect::<Vec<_>>(), vec![0.1, 0.3]);
+    /// ```
+    pub fn channel(&self, channel: usize) -> StepBy<Skip<std::slice::Iter<'_, f32>>> {
+        let channels = self.check_channel(channel);
+        self.samples.iter().skip(channel).step_by(channels)
+    }
+
+    /// Like [`AudioData::channel`], for changing samples in place
+    pub fn channel_mut(&mut self, channel: usize) -> StepBy<Skip<std::slice::IterMut<'_, f32>>> {
+        let channels = self.check_channel(channel);
+        self.samples.iter_mut().skip(channel).step_by(channels)
+    }
+
+    /// Iterate over interleaved blocks of up to `frames` frames each
+    ///
+    /// Every block but the last holds exactly `frames` frames.
+    pub fn frame_chunks(&self, frames: usize) -> Chunks<'_, f32> {
+        self.samples.chunks(self.chunk_len(frames))
+    }
+
+    /// Like [`AudioData::frame_chunks`], for changing samples in place
+    pub fn frame_chunks_mut(&mut self, frames: usize) -> ChunksMut<'_, f32> {
+        let len = self.chunk_len(frames);
+        self.samples.chunks_mut(len)
+    }
+
+    fn check_channel(&self, channel: usize) -> usize {
+        assert!(
+            channel < self.channels as usize,
+            "channel {} out of range for {}-channel audio",
+            channel,
+            self.channels
+        );
+        self.channels as usize
+    }
+
+    fn chunk_len(&self, frames: usize) -> usize {
+        assert!(frames > 0, "chunk size must be at least one frame");
+        frames * self.channels.max(1) as usize
+    }
+}
+
+/// Audio stored one buffer per channel instead of interleaved
+///
+/// Per-channel work (filters, analysis) can walk each plane as a plain
+/// slice; convert back with [`PlanarAudio::into_interleaved`] for playback
+/// and encoding, which expect the [`AudioData`] layout.
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::AudioData;
+///
+/// let stereo = AudioData::new(vec![1.0, -1.0, 0.5, -0.5], 44100, 2).unwrap();
+/// let mut planar = stereo.to_planar();
+/// for s in &mut planar.planes[1] {
+///     *s = 0.0; // mute the right channel
+/// }
+/// assert_eq!(planar.into_interleaved().samples, vec![1.0, 0.0, 0.5, 0.0]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde-audio", derive(Serialize, Deserialize))]
+pub struct PlanarAudio {
+    /// One buffer per channel, all the same length: `planes[channel][frame]`
+    pub planes: Vec<Vec<f32>>,
+
+    /// Sample rate in Hz
+    pub sample_rate: u32,
+}
+
+impl PlanarAudio {
+    /// Create planar audio, checking that every plane has the same length
+    pub fn new(planes: Vec<Vec<f32>>, sample_rate: u32) -> Result<Self> {
+        if sample_rate == 0 {
+            return Err(AudioError::InvalidParameter(
+                "Sample rate must be greater than zero".to_string(),
+            ));
+        }
+        if planes.is_empty() || planes.len() > u16::MAX as usize {
+            return Err(AudioError::InvalidParameter(format!(
+                "Cannot store {} channel(s)",
+                planes.len()
+            )));
+        }
+        let frames = planes[0].len();
+        if let Some(c) = planes.iter().position(|p| p.len() != frames) {
+            return Err(AudioError::InvalidParameter(format!(
+                "Channel {} has {} frame(s), expected {}",
+                c,
+                planes[c].len(),
+                frames
+            )));
+        }
+        Ok(Self {
+            planes,
+            sample_rate,
+        })
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.planes.len() as u16
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.planes.first().map_or(0, Vec::len)
+    }
+
+    pub fn duration_seconds(&self) -> f64 {
+        self.frame_count() as f64 / self.sample_rate as f64
+    }
+
+    /// Interleave into a new [`AudioData`]
+    pub fn to_interleaved(&self) -> AudioData {
+        let channels = self.planes.len();
+        let frames = self.frame_count();
+        let mut samples = vec![0.0; frames * channels];
+        for (c, plane) in self.planes.iter().enumerate() {
+            for (frame, &sample) in plane.iter().enumerate() {
+                samples[frame * channels + c] = sample;
+            }
+        }
+        AudioData {
+            samples,
+            sample_rate: self.sample_rate,
+            channels: channels as u16,
+        }
+    }
+
+    /// Interleave into an [`AudioData`]; mono moves its buffer without copying
+    pub fn into_interleaved(mut self) -> AudioData {
+        if self.planes.len() == 1 {
+            return AudioData {
+                samples: self.planes.pop().unwrap_or_default(),
+                sample_rate: self.sample_rate,
+                channels: 1,
+            };
+        }
+        self.to_interleaved()
+    }
+}
+
+impl AudioData {
+    /// Split into one buffer per channel
+    pub fn to_planar(&self) -> PlanarAudio {
+        let channels = self.channels.max(1) as usize;
+        let frames = self.samples.len() / channels;
+        let mut planes = vec![Vec::with_capacity(frames); channels];
+        for frame in self.frames() {
+            for (plane, &sample) in planes.iter_mut().zip(frame) {
+                plane.push(sample);
+            }
+        }
+        PlanarAudio {
+            planes,
+            sample_rate: self.sample_rate,
+        }
+    }
+
+    /// Split into one buffer per channel; mono moves its buffer without copying
+    pub fn into_planar(self) -> PlanarAudio {
+        if self.channels == 1 {
+            return PlanarAudio {
+                planes: vec![self.samples],
+                sample_rate: self.sample_rate,
+            };
+        }
+        self.to_planar()
+    }
+}
+
+impl From<PlanarAudio> for AudioData {
+    fn from(planar: PlanarAudio) -> Self {
+        planar.into_interleaved()
+    }
+}
+
+impl From<AudioData> for PlanarAudio {
+    fn from(audio: AudioData) -> Self {
+        audio.into_planar()
+    }
 }
 
 /// Metadata about an audio file without loading all samples
@@ -55,23 +382,130 @@ pub struct AudioInfo {
 
     /// Bit depth if available (e.g., 16, 24)
     pub bit_depth: Option<u16>,
+
+    /// Container format (e.g., "WAV", "MP4", "Ogg"), if recognized
+    #[serde(default)]
+    pub container: Option<String>,
+
+    /// Codec profile where the stream declares one (e.g., "AAC LC", "HE-AAC")
+    #[serde(default)]
+    pub codec_profile: Option<String>,
+
+    /// Average bitrate over the whole file in kbit/s, container overhead
+    /// included; `None` when the duration is unknown
+    #[serde(default)]
+    pub bitrate_kbps: Option<u32>,
+
+    /// Size of the file on disk in bytes
+    #[serde(default)]
+    pub file_size_bytes: u64,
+
+    /// Common tags from the file's metadata
+    #[serde(default)]
+    pub tags: TagSummary,
+}
+
+/// The embedded tags a file header shows
+///
+/// Covers ID3, Vorbis comments, MP4 atoms and RIFF INFO alike; when a file
+/// carries several tag blocks, the first value found for each field wins.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TagSummary {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub album_artist: Option<String>,
+    /// Release or recording date, as written in the tag
+    pub date: Option<String>,
+    pub genre: Option<String>,
+    /// Track number, as written in the tag (e.g., "3" or "3/12")
+    pub track_number: Option<String>,
+    pub comment: Option<String>,
+    /// Tags not summarized above
+    pub other_tags: usize,
+    /// Whether the file embeds a picture (e.g., cover art)
+    pub has_picture: bool,
+}
+
+/// One end of a trim range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrimPoint {
+    /// A time from the start of the file
+    At(Timestamp),
+    /// A time before the end of the file; `BeforeEnd(ZERO)` is the very end
+    BeforeEnd(AudioDuration),
+}
+
+impl TrimPoint {
+    /// The end of the file, whatever its length
+    pub const END: Self = TrimPoint::BeforeEnd(AudioDuration::ZERO);
+
+    /// Position in a file `duration` long; points before the start clamp to it
+    pub fn resolve(self, duration: Timestamp) -> Timestamp {
+        match self {
+            TrimPoint::At(time) => time,
+            TrimPoint::BeforeEnd(offset) => duration - offset,
+        }
+    }
+}
+
+/// Shape of a trimmed clip's fades
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FadeCurve {
+    /// Gain rises in a straight line
+    #[default]
+    Linear,
+    /// Half a cosine period: starts and ends gently, which sounds smoother
+    /// on speech
+    Cosine,
+}
+
+impl FadeCurve {
+    /// Gain at `position` through a fade-in, from 0.0 to 1.0
+    pub fn gain(self, position: f64) -> f32 {
+        let position = position.clamp(0.0, 1.0);
+        match self {
+            FadeCurve::Linear => position as f32,
+            FadeCurve::Cosine => (0.5 - 0.5 * (position * std::f64::consts::PI).cos()) as f32,
+        }
+    }
 }
 
 /// Parameters for trimming an audio file
+///
+/// Either end can be relative to the end of the file, so "from 1:00 to the
+/// end" and "the last 30 seconds" work without knowing the length up front;
+/// [`TrimParams::resolve`] turns them into times once the length is known.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct TrimParams {
-    /// Start time in seconds (must be >= 0)
-    pub start_seconds: f64,
+    /// Start of the kept range
+    pub start: TrimPoint,
 
-    /// End time in seconds (must be > start_seconds)
-    pub end_seconds: f64,
+    /// End of the kept range (must come after `start`)
+    pub end: TrimPoint,
+
+    /// Fade the clip in from silence over this long, so it doesn't start
+    /// with a click (0 for none)
+    #[serde(default)]
+    pub fade_in_ms: f64,
+
+    /// Fade the clip out to silence over this long (0 for none)
+    #[serde(default)]
+    pub fade_out_ms: f64,
+
+    #[serde(default)]
+    pub fade_curve: FadeCurve,
 }
 
 /// Waveform peak data for visualization
 ///
 /// Contains min/max peak values for efficient waveform rendering.
 /// Each peak represents a segment of the audio file.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct WaveformPeaks {
     /// Minimum amplitude values for each segment (range: -1.0 to 1.0)
     pub min_peaks: Vec<f32>,
@@ -90,34 +524,225 @@ pub struct WaveformPeaks {
 
     /// Sample rate (for reference)
     pub sample_rate: u32,
+
+    /// RMS level of each segment; empty unless requested with
+    /// `WaveformOptions::rms`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rms_peaks: Vec<f32>,
+
+    /// The same peaks for each channel on its own; empty unless requested
+    /// with `WaveformOptions::per_channel`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channel_peaks: Vec<WaveformPeaks>,
 }
 
 impl TrimParams {
     /// Create new trim parameters with validation
-    pub fn new(start_seconds: f64, end_seconds: f64) -> crate::error::Result<Self> {
-        use crate::error::AudioError;
-
-        if start_seconds < 0.0 {
+    pub fn new(start_seconds: f64, end_seconds: f64) -> Result<Self> {
+        let start = Self::start_time(start_seconds)?;
+        let end = Timestamp::from_seconds(end_seconds);
+        if end <= start {
             return Err(AudioError::InvalidTrimParams(
-                format!("Start time cannot be negative: {}", start_seconds)
+                format!("End time ({}) must be greater than start time ({})", 
+                    end_seconds, start_seconds)
             ));
         }
 
-        if end_seconds <= start_seconds {
+        Ok(Self::between(TrimPoint::At(start), TrimPoint::At(end)))
+    }
+
+    /// Keep everything from `start_seconds` to the end of the file
+    pub fn to_end(start_seconds: f64) -> Result<Self> {
+        Ok(Self::between(TrimPoint::At(Self::start_time(start_seconds)?), TrimPoint::END))
+    }
+
+    /// Keep the last `duration_seconds` of the file (all of it if it's shorter)
+    pub fn last(duration_seconds: f64) -> Result<Self> {
+        let duration = AudioDuration::try_from(duration_seconds)
+            .ok()
+            .filter(|d| !d.is_zero())
+            .ok_or_else(|| {
+                AudioError::InvalidTrimParams(format!(
+                    "Duration must be greater than zero: {}",
+                    duration_seconds
+                ))
+            })?;
+        Ok(Self::between(TrimPoint::BeforeEnd(duration), TrimPoint::END))
+    }
+
+    /// Fade in and out over these many milliseconds (0 for none)
+    pub fn fades(mut self, fade_in_ms: f64, fade_out_ms: f64) -> Self {
+        self.fade_in_ms = fade_in_ms;
+        self.fade_out_ms = fade_out_ms;
+        self
+    }
+
+    pub fn fade_curve(mut self, fade_curve: FadeCurve) -> Self {
+        self.fade_curve = fade_curve;
+        self
+    }
+
+    /// Keep the range from `start` to `end`, without fades; checked by
+    /// [`TrimParams::resolve`]
+    pub fn between(start: TrimPoint, end: TrimPoint) -> Self {
+        Self {
+            start,
+            end,
+            fade_in_ms: 0.0,
+            fade_out_ms: 0.0,
+            fade_curve: FadeCurve::default(),
+        }
+    }
+
+    fn start_time(start_seconds: f64) -> Result<Timestamp> {
+        if start_seconds.is_nan() || start_seconds < 0.0 {
             return Err(AudioError::InvalidTrimParams(
-                format!("End time ({}) must be greater than start time ({})", 
-                    end_seconds, start_seconds)
+                format!("Start time cannot be negative: {}", start_seconds)
             ));
         }
+        Ok(Timestamp::from_seconds(start_seconds))
+    }
 
-        Ok(Self {
-            start_seconds,
-            end_seconds,
-        })
+    /// The kept range in a file `duration` long
+    ///
+    /// # Errors
+    /// `TrimRangeOutOfBounds` if the end is past the end of the file, and
+    /// `InvalidTrimParams` if the range comes out empty or a fade is negative
+    pub fn resolve(&self, duration: Timestamp) -> Result<Range<Timestamp>> {
+        for fade in [self.fade_in_ms, self.fade_out_ms] {
+            if !(fade.is_finite() && fade >= 0.0) {
+                return Err(AudioError::InvalidTrimParams(format!(
+                    "Fade must not be negative (got {}ms)",
+                    fade
+                )));
+            }
+        }
+        let start = self.start.resolve(duration);
+        let end = self.end.resolve(duration);
+
+        if end > duration {
+            return Err(AudioError::TrimRangeOutOfBounds {
+                start: start.as_seconds(),
+                end: end.as_seconds(),
+                duration: duration.as_seconds(),
+            });
+        }
+        if end <= start {
+            return Err(AudioError::InvalidTrimParams(format!(
+                "Range {} to {} is empty in a file {} long",
+                start, end, duration
+            )));
+        }
+        Ok(start..end)
+    }
+
+    /// Get the duration of the trimmed audio, if it doesn't depend on the file length
+    ///
+    /// Anything measured from the end is `None`: the tail of a file shorter
+    /// than it is the whole file.
+    pub fn trim_duration(&self) -> Option<AudioDuration> {
+        match (self.start, self.end) {
+            (TrimPoint::At(start), TrimPoint::At(end)) => Some(end - start),
+            _ => None,
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_rejects_bad_layout() {
+        assert!(AudioData::new(vec![0.0; 4], 0, 2).is_err());
+        assert!(AudioData::new(vec![0.0; 4], 44100, 0).is_err());
+        assert!(AudioData::new(vec![0.0; 5], 44100, 2).is_err());
+        assert!(AudioData::new(Vec::new(), 44100, 2).is_ok());
+    }
+
+    #[test]
+    fn test_silence_length() {
+        let audio = AudioData::silence(0.5, 48000, 2).unwrap();
+        assert_eq!(audio.frame_count(), 24000);
+        assert!(audio.samples.iter().all(|&s| s == 0.0));
+        assert!(AudioData::silence(-1.0, 48000, 2).is_err());
+        assert!(AudioData::silence(f64::NAN, 48000, 2).is_err());
+    }
+
+    #[test]
+    fn test_frame_and_channel_views() {
+        let mut audio = AudioData::new((0..10).map(|i| i as f32).collect(), 8000, 2).unwrap();
+        assert_eq!(audio.frames().count(), 5);
+        assert_eq!(audio.channel(1).copied().collect::<Vec<_>>(), vec![1.0, 3.0, 5.0, 7.0, 9.0]);
+
+        for frame in audio.frames_mut() {
+            frame.swap(0, 1);
+        }
+        assert_eq!(&audio.samples[..4], &[1.0, 0.0, 3.0, 2.0]);
+
+        let sizes: Vec<usize> = audio.frame_chunks(2).map(|c| c.len()).collect();
+        assert_eq!(sizes, vec![4, 4, 2]);
+        for chunk in audio.frame_chunks_mut(2) {
+            chunk.fill(0.0);
+        }
+        assert!(audio.samples.iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_channel_out_of_range_panics() {
+        let audio = AudioData::new(vec![0.0; 4], 8000, 2).unwrap();
+        let _ = audio.channel(2);
+    }
+
+    #[test]
+    fn test_planar_round_trip() {
+        let audio = AudioData::new((0..12).map(|i| i as f32).collect(), 8000, 3).unwrap();
+        let planar = audio.to_planar();
+        assert_eq!(planar.channels(), 3);
+        assert_eq!(planar.frame_count(), 4);
+        assert_eq!(planar.planes[1], vec![1.0, 4.0, 7.0, 10.0]);
+        assert_eq!(AudioData::from(planar).samples, audio.samples);
+
+        let mono = AudioData::new(vec![0.1, 0.2], 8000, 1).unwrap();
+        assert_eq!(mono.clone().into_planar().into_interleaved().samples, mono.samples);
+    }
+
+    #[test]
+    fn test_planar_rejects_ragged_planes() {
+        assert!(PlanarAudio::new(vec![vec![0.0; 4], vec![0.0; 3]], 8000).is_err());
+        assert!(PlanarAudio::new(Vec::new(), 8000).is_err());
+        assert!(PlanarAudio::new(vec![vec![0.0; 4]], 0).is_err());
+    }
+
+    #[cfg(feature = "serde-audio")]
+    #[test]
+    fn test_serde_round_trip() {
+        let audio = AudioData::new(vec![0.25, -0.5], 22050, 2).unwrap();
+        let json = serde_json::to_string(&audio).unwrap();
+        let back: AudioData = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.samples, audio.samples);
+        assert_eq!((back.sample_rate, back.channels), (22050, 2));
     }
 
-    /// Get the duration of the trimmed audio
-    pub fn trim_duration(&self) -> f64 {
-        self.end_seconds - self.start_seconds
+    #[test]
+    fn test_relative_trims_resolve_against_length() {
+        let secs = Timestamp::from_seconds;
+        let to_end = TrimParams::to_end(4.0).unwrap();
+        assert_eq!(to_end.resolve(secs(10.0)).unwrap(), secs(4.0)..secs(10.0));
+        assert!(to_end.resolve(secs(3.0)).is_err());
+
+        let last = TrimParams::last(3.0).unwrap();
+        assert_eq!(last.trim_duration(), None);
+        assert_eq!(last.resolve(secs(10.0)).unwrap(), secs(7.0)..secs(10.0));
+        // Shorter than the requested tail: keep the whole file
+        assert_eq!(last.resolve(secs(2.0)).unwrap(), secs(0.0)..secs(2.0));
+        assert!(TrimParams::last(0.0).is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_from_frames_rejects_ragged_frames() {
+        let frames: Vec<Vec<f32>> = vec![vec![0.1, 0.2], vec![0.3]];
+        assert!(AudioData::from_frames(frames, 8000).is_err());
+        assert!(AudioData::from_frames(Vec::<[f32; 2]>::new(), 8000).is_err());
+    }
+}
@@ -0,0 +1,172 @@
+// src-tauri/src/audio/watchdog.rs
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+use symphonia::core::io::MediaSource;
+use tracing::warn;
+
+use crate::error::{AudioError, DecodeError, Result};
+
+/// Longest a file may take to probe before it's treated as broken
+///
+/// Healthy files probe in milliseconds even over a network share; only
+/// malformed ones that send the demuxer spinning get anywhere near this.
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Set to make a [`CancellableFile`] fail every read from then on
+pub type CancelFlag = Arc<AtomicBool>;
+
+/// File that stops returning data once its cancel flag is set
+///
+/// Symphonia has no way to interrupt a probe, but every loop that reads
+/// endlessly ends as soon as its reads start failing.
+#[derive(Debug)]
+pub struct CancellableFile {
+    file: File,
+    len: Option<u64>,
+    cancelled: CancelFlag,
+}
+
+impl CancellableFile {
+    pub fn new(file: File, cancelled: CancelFlag) -> Self {
+        let len = file.metadata().ok().map(|m| m.len());
+        Self {
+            file,
+            len,
+            cancelled,
+        }
+    }
+
+    /// Fails once cancelled; not with `Interrupted`, which `read_exact` and
+    /// symphonia's stream retry forever
+    fn check(&self) -> io::Result<()> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "cancelled by the watchdog"));
+        }
+        Ok(())
+    }
+}
+
+impl Read for CancellableFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.check()?;
+        self.file.read(buf)
+    }
+}
+
+impl Seek for CancellableFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.check()?;
+        self.file.seek(pos)
+    }
+}
+
+impl MediaSource for CancellableFile {
+    fn is_seekable(&self) -> bool {
+        true
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        self.len
+    }
+}
+
+/// Run `task` on its own thread and give up after `timeout`
+///
+/// On timeout the task's cancel flag is set and a `DecodeError::Watchdog` is
+/// returned right away; the thread is left to wind down on its own (its
+/// reads fail from then on, so it normally does so quickly).
+pub fn with_timeout<T, F>(what: &str, timeout: Duration, task: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(CancelFlag) -> Result<T> + Send + 'static,
+{
+    let cancelled = CancelFlag::default();
+    let (tx, rx) = mpsc::channel();
+    let flag = cancelled.clone();
+    std::thread::Builder::new()
+        .name("probe-watchdog".to_string())
+        .spawn(move || {
+            // The receiver is gone if we already timed out
+            let _ = tx.send(task(flag));
+        })
+        .map_err(AudioError::Io)?;
+
+    match rx.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(mpsc::RecvTimeoutError::Timeout) => {
+            cancelled.store(true, Ordering::Relaxed);
+            warn!(what, timeout_sec = timeout.as_secs_f64(), "Gave up waiting");
+            Err(DecodeError::Watchdog(format!(
+                "Timed out after {:.0}s {}; the file may be corrupt",
+                timeout.as_secs_f64(),
+                what
+            ))
+            .into())
+        }
+        Err(mpsc::RecvTimeoutError::Disconnected) => {
+            Err(DecodeError::Watchdog(format!("Crashed while {}", what)).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn test_result_passes_through() {
+        let value = with_timeout("adding", Duration::from_secs(5), |_| Ok(2 + 2)).unwrap();
+        assert_eq!(value, 4);
+    }
+
+    #[test]
+    fn test_spinning_task_times_out_and_is_cancelled() {
+        let (stopped_tx, stopped_rx) = mpsc::channel();
+        let started = Instant::now();
+        let result = with_timeout("spinning", Duration::from_millis(50), move |cancelled| {
+            while !cancelled.load(Ordering::Relaxed) {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            stopped_tx.send(()).unwrap();
+            Ok(())
+        });
+
+        assert!(matches!(
+            result,
+            Err(AudioError::Decode(DecodeError::Watchdog(msg))) if msg.contains("spinning")
+        ));
+        assert!(started.elapsed() < Duration::from_secs(2));
+        // The task saw the cancel flag and finished
+        stopped_rx.recv_timeout(Duration::from_secs(2)).unwrap();
+    }
+
+    #[test]
+    fn test_panicking_task_is_an_error() {
+        let result: Result<()> =
+            with_timeout("exploding", Duration::from_secs(5), |_| panic!("boom"));
+        assert!(matches!(result, Err(AudioError::Decode(DecodeError::Watchdog(_)))));
+    }
+
+    #[test]
+    fn test_cancelled_file_stops_reading() {
+        let path = std::env::temp_dir().join("hermeneia_test_cancellable.bin");
+        std::fs::write(&path, [7u8; 64]).unwrap();
+
+        let cancelled = CancelFlag::default();
+        let mut file = CancellableFile::new(File::open(&path).unwrap(), cancelled.clone());
+        assert_eq!(file.byte_len(), Some(64));
+        let mut buf = [0u8; 8];
+        assert_eq!(file.read(&mut buf).unwrap(), 8);
+
+        cancelled.store(true, Ordering::Relaxed);
+        assert_eq!(file.read(&mut buf).unwrap_err().kind(), io::ErrorKind::TimedOut);
+        assert!(file.seek(SeekFrom::Start(0)).is_err());
+        std::fs::remove_file(&path).ok();
+    }
+}
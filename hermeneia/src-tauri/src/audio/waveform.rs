@@ -1,16 +1,139 @@
 // src-tauri/src/audio/waveform.rs
 
+use serde::{Deserialize, Serialize};
 use symphonia::core::audio::AudioBufferRef;
-use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
-use symphonia::core::formats::FormatOptions;
-use symphonia::core::io::MediaSourceStream;
-use symphonia::core::meta::MetadataOptions;
-use symphonia::core::probe::Hint;
-use std::fs::File;
+use std::ops::Range;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
+use crate::audio::decoder::{decode_audio_range_with, open_audio_track};
+use crate::audio::time::Timestamp;
 use crate::audio::types::WaveformPeaks;
-use crate::error::{AudioError, Result};
+use crate::error::{AudioError, DecodeError, Result};
+use crate::profile;
+use crate::progress::{check_cancelled, NoProgress, ProgressSink};
+
+/// Settings for [`extract_waveform_peaks`]
+///
+/// Build one with [`WaveformOptions::new`] and the chained setters, or
+/// deserialize it (e.g. from the frontend); missing fields take their
+/// defaults, so new options never change existing callers.
+///
+/// ```
+/// use hermeneia_lib::audio::{Timestamp, WaveformOptions};
+///
+/// let options = WaveformOptions::new()
+///     .num_peaks(4000)
+///     .rms(true)
+///     .range(Timestamp::from_seconds(60.0), Some(Timestamp::from_seconds(120.0)));
+/// assert!(options.per_channel == false);
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WaveformOptions {
+    /// Number of peak pairs to extract (default: 2000)
+    pub num_peaks: usize,
+
+    /// Also extract peaks for every channel on its own
+    /// ([`WaveformPeaks::channel_peaks`])
+    pub per_channel: bool,
+
+    /// Also measure the RMS level of every segment ([`WaveformPeaks::rms_peaks`])
+    pub rms: bool,
+
+    /// Return levels on a dB scale down to this floor (e.g. -60.0) instead of
+    /// linear amplitude; see [`WaveformPeaks::db_scaled`]
+    pub db_floor: Option<f32>,
+
+    /// Where to start in the file (default: the beginning)
+    pub start: Option<Timestamp>,
+
+    /// Where to stop (default: the end of the file)
+    pub end: Option<Timestamp>,
+
+    /// Set to stop extraction early with [`AudioError::Cancelled`]
+    #[serde(skip)]
+    pub cancel: Option<Arc<AtomicBool>>,
+}
+
+impl Default for WaveformOptions {
+    fn default() -> Self {
+        Self {
+            num_peaks: 2000,
+            per_channel: false,
+            rms: false,
+            db_floor: None,
+            start: None,
+            end: None,
+            cancel: None,
+        }
+    }
+}
+
+impl WaveformOptions {
+    /// The defaults: 2000 linear min/max peaks over the whole file
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn num_peaks(mut self, num_peaks: usize) -> Self {
+        self.num_peaks = num_peaks;
+        self
+    }
+
+    pub fn per_channel(mut self, per_channel: bool) -> Self {
+        self.per_channel = per_channel;
+        self
+    }
+
+    pub fn rms(mut self, rms: bool) -> Self {
+        self.rms = rms;
+        self
+    }
+
+    pub fn db_scale(mut self, floor_db: f32) -> Self {
+        self.db_floor = Some(floor_db);
+        self
+    }
+
+    /// Only cover `start` to `end` (`None` for the end of the file)
+    pub fn range(mut self, start: Timestamp, end: Option<Timestamp>) -> Self {
+        self.start = Some(start);
+        self.end = end;
+        self
+    }
+
+    pub fn cancel_flag(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.num_peaks == 0 {
+            return Err(AudioError::InvalidParameter(
+                "num_peaks must be greater than 0".to_string(),
+            ));
+        }
+        if let Some(floor) = self.db_floor {
+            if !(floor.is_finite() && floor < 0.0) {
+                return Err(AudioError::InvalidParameter(format!(
+                    "dB floor must be below 0 dBFS (got {})",
+                    floor
+                )));
+            }
+        }
+        if let (Some(start), Some(end)) = (self.start, self.end) {
+            if end <= start {
+                return Err(AudioError::InvalidParameter(format!(
+                    "Waveform range end ({}) must come after its start ({})",
+                    end, start
+                )));
+            }
+        }
+        Ok(())
+    }
+}
 
 /// Extract waveform peaks from an audio file for visualization
 ///
@@ -20,7 +143,7 @@ use crate::error::{AudioError, Result};
 ///
 /// # Arguments
 /// * `path` - Path to the audio file
-/// * `num_peaks` - Number of peak pairs to extract (default: 2000 if None)
+/// * `options` - Peak count, extra measurements, scale and time range
 ///
 /// # Returns
 /// WaveformPeaks containing min/max amplitude data for visualization
@@ -28,318 +151,742 @@ use crate::error::{AudioError, Result};
 /// # Performance
 /// - Memory efficient: Only stores peak data, not all samples
 /// - 4-hour audio: ~16KB of peak data vs ~1.27GB of raw samples
-/// - Processes files in a single streaming pass
+/// - Processes files in a single streaming pass, or two when the header
+///   has no frame count (many VBR MP3s and streamed Oggs): a quick scan of
+///   the packets to measure the file, then the decode
 ///
 /// # Example
 /// ```no_run
-/// use hermeneia_lib::audio::extract_waveform_peaks;
+/// use hermeneia_lib::audio::{extract_waveform_peaks, WaveformOptions};
 ///
 /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
 /// // Extract 2000 peaks for waveform display
-/// let peaks = extract_waveform_peaks("long_sermon.mp3", Some(2000))?;
+/// let peaks = extract_waveform_peaks("long_sermon.mp3", &WaveformOptions::new())?;
 /// println!("Peaks: {}, Duration: {:.1}s", peaks.num_peaks, peaks.duration_seconds);
 /// # Ok(())
 /// # }
 /// ```
 pub fn extract_waveform_peaks<P: AsRef<Path>>(
     path: P,
-    num_peaks: Option<usize>,
+    options: &WaveformOptions,
+) -> Result<WaveformPeaks> {
+    extract_waveform_peaks_with_progress(path, options, &mut NoProgress)
+}
+
+/// Extract like [`extract_waveform_peaks`], reporting the "decode" stage,
+/// after a "probe" stage for files that have to be scanned first
+///
+/// Cancellation (through `progress` or [`WaveformOptions::cancel`]) is
+/// checked once per packet; a cancelled extraction returns
+/// [`AudioError::Cancelled`].
+pub fn extract_waveform_peaks_with_progress<P: AsRef<Path>>(
+    path: P,
+    options: &WaveformOptions,
+    progress: &mut dyn ProgressSink,
+) -> Result<WaveformPeaks> {
+    extract(path.as_ref(), options, progress, None)
+}
+
+/// Peaks handed out by [`stream_waveform_peaks`] as soon as they're complete
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeakChunk {
+    /// Index of the first peak in the chunk
+    pub start: usize,
+    /// Peaks `start..start + peaks.num_peaks`, scaled like the final result;
+    /// `duration_seconds` covers only this stretch
+    pub peaks: WaveformPeaks,
+    /// Share of all the peaks done once this chunk is in, from 0.0 to 1.0
+    pub fraction: f64,
+}
+
+/// Extract like [`extract_waveform_peaks_with_progress`], handing
+/// `on_chunk` each run of peaks as it's completed
+///
+/// Chunks come in order, about [`STREAM_CHUNKS`] of them, and together
+/// cover every peak of the returned result, so a long file's waveform
+/// can be drawn while it's still being decoded.
+///
+/// ```
+/// use hermeneia_lib::audio::{encode_wav, stream_waveform_peaks, AudioData, WaveformOptions};
+/// use hermeneia_lib::progress::NoProgress;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let path = std::env::temp_dir().join("hermeneia_doc_stream_peaks.wav");
+/// let audio = AudioData { samples: vec![0.5; 48000], sample_rate: 48000, channels: 1 };
+/// encode_wav(&audio, &path)?;
+///
+/// let mut drawn = 0;
+/// let options = WaveformOptions::new().num_peaks(500);
+/// let peaks = stream_waveform_peaks(&path, &options, &mut NoProgress, &mut |chunk| {
+///     assert_eq!(chunk.start, drawn);
+///     drawn += chunk.peaks.num_peaks;
+/// })?;
+/// assert_eq!(drawn, peaks.num_peaks);
+/// # std::fs::remove_file(&path)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn stream_waveform_peaks<P: AsRef<Path>>(
+    path: P,
+    options: &WaveformOptions,
+    progress: &mut dyn ProgressSink,
+    on_chunk: &mut dyn FnMut(PeakChunk),
 ) -> Result<WaveformPeaks> {
-    let path = path.as_ref();
-    let path_str = path.to_string_lossy().to_string();
-    let num_peaks = num_peaks.unwrap_or(2000);
+    extract(path.as_ref(), options, progress, Some(on_chunk))
+}
 
-    if num_peaks == 0 {
-        return Err(AudioError::InvalidTrimParams(
-            "num_peaks must be greater than 0".to_string(),
-        ));
+/// Chunks [`stream_waveform_peaks`] splits the peaks into, fewer when
+/// there are fewer peaks
+pub const STREAM_CHUNKS: usize = 50;
+
+fn extract(
+    path: &Path,
+    options: &WaveformOptions,
+    progress: &mut dyn ProgressSink,
+    on_chunk: Option<&mut dyn FnMut(PeakChunk)>,
+) -> Result<WaveformPeaks> {
+    options.validate()?;
+
+    let cancelled = |progress: &dyn ProgressSink| {
+        let flagged = options.cancel.as_ref().is_some_and(|c| c.load(Ordering::Relaxed));
+        if flagged {
+            return Err(AudioError::Cancelled);
+        }
+        check_cancelled(progress)
+    };
+    let mut track = open_audio_track(path)?;
+    let (sample_rate, channels) = (track.sample_rate, track.channels);
+
+    // Calculate total frames and duration
+    let file_frames = match track.n_frames {
+        Some(frames) => frames,
+        None => {
+            // No frame count in the header: read through once to find it
+            let _stage = profile::stage("probe");
+            progress.stage("probe");
+            let frames = track.count_frames(|| cancelled(&*progress))?;
+            drop(track);
+            track = open_audio_track(path)?;
+            frames
+        }
+    };
+    let file_end = Timestamp::from_frames(file_frames, sample_rate);
+    let start = options.start.unwrap_or_default();
+    let end = options.end.map_or(file_end, |end| end.min(file_end));
+    let ranged = options.start.is_some() || options.end.is_some();
+    if ranged && end <= start {
+        return Err(DecodeError::EmptyRange {
+            start: start.as_seconds(),
+            end: end.as_seconds(),
+        }
+        .into());
     }
 
-    // Open the file
-    let file = File::open(path).map_err(|e| AudioError::FileOpen {
-        path: path_str.clone(),
-        source: e,
-    })?;
+    let (total_frames, duration_seconds) = if ranged {
+        ((end - start).to_frames(sample_rate), (end - start).as_seconds())
+    } else {
+        (file_frames, file_frames as f64 / sample_rate as f64)
+    };
+    let _stage = profile::stage("decode");
+    progress.stage("decode");
 
-    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut peaks = PeakAccumulator::new(channels, total_frames, options);
+    let header = WaveformPeaks {
+        duration_seconds,
+        channels,
+        sample_rate,
+        ..WaveformPeaks::default()
+    };
+    let mut chunks = on_chunk.map(|on_chunk| ChunkSender {
+        on_chunk,
+        chunk_peaks: options.num_peaks.div_ceil(STREAM_CHUNKS),
+        sent: 0,
+    });
+
+    if ranged {
+        // Seeking is the decoder's job; hand the track back to the pool first
+        drop(track);
+        decode_audio_range_with(path, start.as_seconds(), end.as_seconds(), |chunk| {
+            cancelled(progress)?;
+            let channels = chunk.channels as usize;
+            for frame in chunk.samples.chunks_exact(channels) {
+                peaks.add_frame(|ch| frame[ch]);
+            }
+            progress.progress((peaks.current_frame as f64 / total_frames.max(1) as f64).min(1.0));
+            if let Some(chunks) = &mut chunks {
+                chunks.send_completed(&peaks, &header, options.db_floor);
+            }
+            Ok(())
+        })?;
+    } else {
+        // Stream through packets and calculate peaks
+        while let Ok(packet) = track.format.next_packet() {
+            cancelled(progress)?;
+
+            // Skip non-audio tracks
+            if packet.track_id() != track.track_id {
+                continue;
+            }
 
-    // Create format hint
-    let mut hint = Hint::new();
-    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
-        hint.with_extension(extension);
+            // Decode packet
+            let decoded = track
+                .decoder
+                .decode(&packet)
+                .map_err(|e| DecodeError::Packet(e.to_string()))?;
+
+            // Process samples from this packet
+            process_packet_peaks(&decoded, &mut peaks);
+            progress.progress((peaks.current_frame as f64 / total_frames.max(1) as f64).min(1.0));
+            if let Some(chunks) = &mut chunks {
+                chunks.send_completed(&peaks, &header, options.db_floor);
+            }
+        }
+    }
+    progress.progress(1.0);
+    if let Some(chunks) = &mut chunks {
+        // A short read leaves the last segments empty; they go out as silence
+        chunks.send(&peaks, peaks.num_peaks, &header, options.db_floor);
     }
 
-    // Probe the file
-    let probed = symphonia::default::get_probe()
-        .format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())
-        .map_err(|e| AudioError::DecodeFailed(format!("Failed to probe: {}", e)))?;
+    let result = peaks.finish(header);
+    Ok(match options.db_floor {
+        Some(floor) => result.db_scaled(floor),
+        None => result,
+    })
+}
 
-    let mut format = probed.format;
+/// Hands completed peaks to a [`stream_waveform_peaks`] caller
+struct ChunkSender<'a> {
+    on_chunk: &'a mut dyn FnMut(PeakChunk),
+    /// Least peaks worth a chunk before the end
+    chunk_peaks: usize,
+    /// Peaks before this index have been sent
+    sent: usize,
+}
 
-    // Find audio track
-    let track = format
-        .tracks()
-        .iter()
-        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
-        .ok_or_else(|| AudioError::DecodeFailed("No audio track found".to_string()))?;
+impl ChunkSender<'_> {
+    /// Send what's complete, once there's enough of it
+    fn send_completed(&mut self, peaks: &PeakAccumulator, header: &WaveformPeaks, db: Option<f32>) {
+        let completed = peaks.completed();
+        if completed >= self.sent + self.chunk_peaks {
+            self.send(peaks, completed, header, db);
+        }
+    }
 
-    let track_id = track.id;
-    let sample_rate = track
-        .codec_params
-        .sample_rate
-        .ok_or_else(|| AudioError::DecodeFailed("Sample rate not found".to_string()))?;
+    /// Send every peak before `upto` not sent yet
+    fn send(
+        &mut self,
+        peaks: &PeakAccumulator,
+        upto: usize,
+        header: &WaveformPeaks,
+        db: Option<f32>,
+    ) {
+        if upto <= self.sent {
+            return;
+        }
+        let chunk = peaks.chunk(self.sent..upto, header);
+        (self.on_chunk)(PeakChunk {
+            start: self.sent,
+            peaks: match db {
+                Some(floor) => chunk.db_scaled(floor),
+                None => chunk,
+            },
+            fraction: upto as f64 / peaks.num_peaks as f64,
+        });
+        self.sent = upto;
+    }
+}
 
-    let channels = track
-        .codec_params
-        .channels
-        .ok_or_else(|| AudioError::DecodeFailed("Channel info not found".to_string()))?
-        .count() as u16;
+/// Running min/max (and optionally sum of squares) for every segment
+#[derive(Default)]
+struct Envelope {
+    min: Vec<f32>,
+    max: Vec<f32>,
+    /// Empty unless RMS was requested
+    sum_squares: Vec<f64>,
+    counts: Vec<u32>,
+}
 
-    // Calculate total frames and duration
-    let total_frames = track
-        .codec_params
-        .n_frames
-        .ok_or_else(|| AudioError::DecodeFailed("Frame count not available".to_string()))?;
-
-    let duration_seconds = total_frames as f64 / sample_rate as f64;
-
-    // Create decoder
-    let mut decoder = symphonia::default::get_codecs()
-        .make(&track.codec_params, &DecoderOptions::default())
-        .map_err(|e| AudioError::DecodeFailed(format!("Failed to create decoder: {}", e)))?;
-
-    // Initialize peak buffers
-    let mut min_peaks = vec![f32::MAX; num_peaks];
-    let mut max_peaks = vec![f32::MIN; num_peaks];
-
-    // Calculate how many frames belong to each peak segment
-    let frames_per_peak = total_frames as f64 / num_peaks as f64;
-
-    // Track current frame position
-    let mut current_frame: u64 = 0;
-
-    // Stream through packets and calculate peaks
-    loop {
-        // Get next packet
-        let packet = match format.next_packet() {
-            Ok(packet) => packet,
-            Err(_) => break, // End of stream
+impl Envelope {
+    fn new(num_peaks: usize, rms: bool) -> Self {
+        let rms_len = if rms { num_peaks } else { 0 };
+        Self {
+            min: vec![f32::MAX; num_peaks],
+            max: vec![f32::MIN; num_peaks],
+            sum_squares: vec![0.0; rms_len],
+            counts: vec![0; rms_len],
+        }
+    }
+
+    fn add(&mut self, index: usize, sample: f32) {
+        self.min[index] = self.min[index].min(sample);
+        self.max[index] = self.max[index].max(sample);
+        if let Some(sum) = self.sum_squares.get_mut(index) {
+            *sum += sample as f64 * sample as f64;
+            self.counts[index] += 1;
+        }
+    }
+
+    fn into_peaks(self, header: WaveformPeaks) -> WaveformPeaks {
+        self.peaks(0..self.min.len(), header)
+    }
+
+    /// The peaks of segments `range`
+    fn peaks(&self, range: Range<usize>, header: WaveformPeaks) -> WaveformPeaks {
+        // Segments no frame reached (a short read) show as silence
+        let settle = |&p: &f32| if p == f32::MAX || p == f32::MIN { 0.0 } else { p };
+        let rms_range = if self.sum_squares.is_empty() { 0..0 } else { range.clone() };
+        WaveformPeaks {
+            num_peaks: range.len(),
+            min_peaks: self.min[range.clone()].iter().map(settle).collect(),
+            max_peaks: self.max[range].iter().map(settle).collect(),
+            rms_peaks: self.sum_squares[rms_range.clone()]
+                .iter()
+                .zip(&self.counts[rms_range])
+                .map(|(&sum, &n)| if n == 0 { 0.0 } else { (sum / n as f64).sqrt() as f32 })
+                .collect(),
+            ..header
+        }
+    }
+}
+
+/// Sorts frames into segments for the mixed envelope and, if asked, each channel
+struct PeakAccumulator {
+    channels: usize,
+    num_peaks: usize,
+    frames_per_peak: f64,
+    current_frame: u64,
+    mixed: Envelope,
+    /// Empty unless per-channel peaks were requested
+    per_channel: Vec<Envelope>,
+}
+
+impl PeakAccumulator {
+    fn new(channels: u16, total_frames: u64, options: &WaveformOptions) -> Self {
+        let per_channel_count = if options.per_channel { channels as usize } else { 0 };
+        Self {
+            channels: channels as usize,
+            num_peaks: options.num_peaks,
+            // Calculate how many frames belong to each peak segment
+            frames_per_peak: total_frames as f64 / options.num_peaks as f64,
+            current_frame: 0,
+            mixed: Envelope::new(options.num_peaks, options.rms),
+            per_channel: (0..per_channel_count)
+                .map(|_| Envelope::new(options.num_peaks, options.rms))
+                .collect(),
+        }
+    }
+
+    /// Add one frame, reading channel `ch` with `sample(ch)`
+    fn add_frame(&mut self, sample: impl Fn(usize) -> f32) {
+        // Determine which peak segment this frame belongs to
+        let index = (self.current_frame as f64 / self.frames_per_peak) as usize;
+        if index >= self.num_peaks {
+            return; // Safety: don't overflow peak buffer
+        }
+
+        for ch in 0..self.channels {
+            let value = sample(ch);
+            self.mixed.add(index, value);
+            if let Some(channel) = self.per_channel.get_mut(ch) {
+                channel.add(index, value);
+            }
+        }
+        self.current_frame += 1;
+    }
+
+    /// Segments before this one have had all their frames
+    fn completed(&self) -> usize {
+        ((self.current_frame as f64 / self.frames_per_peak) as usize).min(self.num_peaks)
+    }
+
+    /// Peaks of segments `range`, each channel's too if asked for, with
+    /// `header`'s duration cut down to the range
+    fn chunk(&self, range: Range<usize>, header: &WaveformPeaks) -> WaveformPeaks {
+        let header = WaveformPeaks {
+            duration_seconds: header.duration_seconds * range.len() as f64
+                / self.num_peaks as f64,
+            ..header.clone_header()
         };
+        let channel_peaks = self
+            .per_channel
+            .iter()
+            .map(|channel| {
+                channel.peaks(
+                    range.clone(),
+                    WaveformPeaks {
+                        channels: 1,
+                        ..header.clone_header()
+                    },
+                )
+            })
+            .collect();
+        WaveformPeaks {
+            channel_peaks,
+            ..self.mixed.peaks(range, header)
+        }
+    }
 
-        // Skip non-audio tracks
-        if packet.track_id() != track_id {
-            continue;
+    fn finish(self, header: WaveformPeaks) -> WaveformPeaks {
+        let channel_peaks = self
+            .per_channel
+            .into_iter()
+            .map(|channel| {
+                channel.into_peaks(WaveformPeaks {
+                    channels: 1,
+                    ..header.clone_header()
+                })
+            })
+            .collect();
+        WaveformPeaks {
+            channel_peaks,
+            ..self.mixed.into_peaks(header)
         }
+    }
+}
 
-        // Decode packet
-        let decoded = decoder
-            .decode(&packet)
-            .map_err(|e| AudioError::DecodeFailed(format!("Decode error: {}", e)))?;
+impl WaveformPeaks {
+    /// Bytes before the peak arrays in [`WaveformPeaks::to_bytes`]
+    pub const BINARY_HEADER_LEN: usize = 20;
+
+    /// Header flag: `rms_peaks` follow `max_peaks`
+    pub const FLAG_RMS: u16 = 1;
+
+    /// Header flag: every channel's arrays follow the mixed ones
+    pub const FLAG_PER_CHANNEL: u16 = 2;
+
+    /// Pack the peaks into a compact little-endian buffer for the frontend
+    ///
+    /// Sent as a raw IPC response instead of JSON, so thousands of peaks
+    /// cost one copy rather than a number-to-text round trip. Layout:
+    ///
+    /// | offset | type | field |
+    /// |---|---|---|
+    /// | 0 | f64 | `duration_seconds` |
+    /// | 8 | u32 | `sample_rate` |
+    /// | 12 | u32 | `num_peaks` |
+    /// | 16 | u16 | `channels` |
+    /// | 18 | u16 | flags ([`Self::FLAG_RMS`], [`Self::FLAG_PER_CHANNEL`]) |
+    /// | 20 | f32 × `num_peaks` | `min_peaks` |
+    /// | 20 + 4n | f32 × `num_peaks` | `max_peaks` |
+    /// | 20 + 8n | f32 × `num_peaks` | `rms_peaks`, with `FLAG_RMS` |
+    ///
+    /// With `FLAG_PER_CHANNEL`, the same arrays (min, max and, with
+    /// `FLAG_RMS`, rms) follow for each of the `channels` channels in turn.
+    /// Without flags the layout is the original min/max one.
+    ///
+    /// The arrays start 4-byte aligned, so the frontend can view them as
+    /// `Float32Array`s without copying (see `src/utils/waveform.ts`).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut flags = 0;
+        if !self.rms_peaks.is_empty() {
+            flags |= Self::FLAG_RMS;
+        }
+        if !self.channel_peaks.is_empty() {
+            flags |= Self::FLAG_PER_CHANNEL;
+        }
 
-        // Process samples from this packet
-        process_packet_peaks(
-            &decoded,
-            channels,
-            &mut current_frame,
-            frames_per_peak,
-            &mut min_peaks,
-            &mut max_peaks,
-        );
+        let mut bytes = Vec::with_capacity(Self::BINARY_HEADER_LEN + self.encoded_len(flags));
+        bytes.extend_from_slice(&self.duration_seconds.to_le_bytes());
+        bytes.extend_from_slice(&self.sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(self.num_peaks as u32).to_le_bytes());
+        bytes.extend_from_slice(&self.channels.to_le_bytes());
+        bytes.extend_from_slice(&flags.to_le_bytes());
+        for peaks in std::iter::once(self).chain(&self.channel_peaks) {
+            let rms: &[f32] = if flags & Self::FLAG_RMS != 0 { &peaks.rms_peaks } else { &[] };
+            for peak in peaks.min_peaks.iter().chain(&peaks.max_peaks).chain(rms) {
+                bytes.extend_from_slice(&peak.to_le_bytes());
+            }
+        }
+        bytes
     }
 
-    // Handle any peaks that didn't get set (shouldn't happen, but safety)
-    for i in 0..num_peaks {
-        if min_peaks[i] == f32::MAX {
-            min_peaks[i] = 0.0;
+    /// Bytes of peak data after the header for `flags`
+    fn encoded_len(&self, flags: u16) -> usize {
+        let arrays = if flags & Self::FLAG_RMS != 0 { 3 } else { 2 };
+        let sets = if flags & Self::FLAG_PER_CHANNEL != 0 { 1 + self.channels as usize } else { 1 };
+        sets * arrays * self.num_peaks * 4
+    }
+
+    /// Read peaks back from the [`WaveformPeaks::to_bytes`] layout
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let invalid = || AudioError::InvalidParameter("Malformed waveform peak data".to_string());
+        let header = bytes.get(..Self::BINARY_HEADER_LEN).ok_or_else(invalid)?;
+        let flags = u16::from_le_bytes(header[18..20].try_into().unwrap());
+        let mut peaks = Self {
+            num_peaks: u32::from_le_bytes(header[12..16].try_into().unwrap()) as usize,
+            duration_seconds: f64::from_le_bytes(header[0..8].try_into().unwrap()),
+            channels: u16::from_le_bytes(header[16..18].try_into().unwrap()),
+            sample_rate: u32::from_le_bytes(header[8..12].try_into().unwrap()),
+            ..Self::default()
+        };
+        if flags & !(Self::FLAG_RMS | Self::FLAG_PER_CHANNEL) != 0
+            || bytes.len() != Self::BINARY_HEADER_LEN + peaks.encoded_len(flags)
+        {
+            return Err(invalid());
         }
-        if max_peaks[i] == f32::MIN {
-            max_peaks[i] = 0.0;
+
+        let n = peaks.num_peaks;
+        let mut values = bytes[Self::BINARY_HEADER_LEN..]
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()));
+        let mut read_set = |header: &Self| {
+            let mut set = header.clone_header();
+            set.num_peaks = n;
+            set.min_peaks = values.by_ref().take(n).collect();
+            set.max_peaks = values.by_ref().take(n).collect();
+            if flags & Self::FLAG_RMS != 0 {
+                set.rms_peaks = values.by_ref().take(n).collect();
+            }
+            set
+        };
+
+        let mixed = read_set(&peaks);
+        if flags & Self::FLAG_PER_CHANNEL != 0 {
+            let channel = Self {
+                channels: 1,
+                ..peaks.clone_header()
+            };
+            peaks.channel_peaks = (0..peaks.channels).map(|_| read_set(&channel)).collect();
         }
+        Ok(Self {
+            channel_peaks: peaks.channel_peaks,
+            ..mixed
+        })
     }
 
-    Ok(WaveformPeaks {
-        min_peaks,
-        max_peaks,
-        num_peaks,
-        duration_seconds,
-        channels,
-        sample_rate,
-    })
+    /// Reduce to `num_peaks` peaks, each covering several of the current ones
+    ///
+    /// Keeps the extremes of every group, so a short transient still shows
+    /// at lower zoom. Asking for as many peaks as there are or more returns
+    /// a copy.
+    pub fn downsample(&self, num_peaks: usize) -> Result<Self> {
+        if num_peaks == 0 {
+            return Err(AudioError::InvalidParameter(
+                "num_peaks must be greater than 0".to_string(),
+            ));
+        }
+        if num_peaks >= self.num_peaks {
+            return Ok(self.clone());
+        }
+
+        let mut min_peaks = Vec::with_capacity(num_peaks);
+        let mut max_peaks = Vec::with_capacity(num_peaks);
+        let mut rms_peaks = Vec::new();
+        for i in 0..num_peaks {
+            // Spread the remainder evenly instead of piling it on the last peak
+            let from = i * self.num_peaks / num_peaks;
+            let to = (i + 1) * self.num_peaks / num_peaks;
+            min_peaks.push(self.min_peaks[from..to].iter().copied().fold(f32::MAX, f32::min));
+            max_peaks.push(self.max_peaks[from..to].iter().copied().fold(f32::MIN, f32::max));
+            if let Some(group) = self.rms_peaks.get(from..to) {
+                let mean_square = group.iter().map(|r| r * r).sum::<f32>() / group.len() as f32;
+                rms_peaks.push(mean_square.sqrt());
+            }
+        }
+
+        Ok(Self {
+            min_peaks,
+            max_peaks,
+            rms_peaks,
+            num_peaks,
+            channel_peaks: self
+                .channel_peaks
+                .iter()
+                .map(|channel| channel.downsample(num_peaks))
+                .collect::<Result<_>>()?,
+            ..self.clone_header()
+        })
+    }
+
+    /// The peaks covering `start` to `end`
+    ///
+    /// Peaks partly inside the range are kept, so the result can start a
+    /// little before `start` and end a little after `end`;
+    /// `duration_seconds` reports exactly what it covers. A range starting
+    /// past the end gives no peaks.
+    pub fn slice(&self, start: Timestamp, end: Timestamp) -> Result<Self> {
+        if end <= start || self.num_peaks == 0 || self.duration_seconds <= 0.0 {
+            return Err(AudioError::InvalidParameter(format!(
+                "Cannot slice {} to {} from {:.3}s of peaks",
+                start, end, self.duration_seconds
+            )));
+        }
+
+        let seconds_per_peak = self.duration_seconds / self.num_peaks as f64;
+        let index = |time: Timestamp| time.as_seconds() / seconds_per_peak;
+        let from = (index(start).floor() as usize).min(self.num_peaks);
+        let to = (index(end).ceil() as usize).clamp((from + 1).min(self.num_peaks), self.num_peaks);
+
+        Ok(Self {
+            min_peaks: self.min_peaks[from..to].to_vec(),
+            max_peaks: self.max_peaks[from..to].to_vec(),
+            rms_peaks: self.rms_peaks.get(from..to).unwrap_or_default().to_vec(),
+            num_peaks: to - from,
+            duration_seconds: (to - from) as f64 * seconds_per_peak,
+            channel_peaks: self
+                .channel_peaks
+                .iter()
+                .map(|channel| channel.slice(start, end))
+                .collect::<Result<_>>()?,
+            ..self.clone_header()
+        })
+    }
+
+    /// Scale so the largest absolute peak is `target_max` (e.g. 1.0 to fill the view)
+    ///
+    /// Silent peaks are returned unchanged. Channel peaks get the same gain
+    /// as the mix, so they stay comparable.
+    pub fn normalize(&self, target_max: f32) -> Self {
+        let loudest = self
+            .min_peaks
+            .iter()
+            .chain(&self.max_peaks)
+            .fold(0.0f32, |loudest, p| loudest.max(p.abs()));
+        if loudest == 0.0 || !target_max.is_finite() {
+            return self.clone();
+        }
+
+        self.map_levels(&|p| p * target_max / loudest)
+    }
+
+    /// Levels on a dB scale from `floor_db` (0.0) to 0 dBFS (1.0)
+    ///
+    /// Quiet passages get far more of the display height than on a linear
+    /// scale. Signs are kept, so min peaks stay at or below zero.
+    pub fn db_scaled(&self, floor_db: f32) -> Self {
+        self.map_levels(&|p| {
+            let db = 20.0 * p.abs().max(f32::MIN_POSITIVE).log10();
+            let level = ((db - floor_db) / -floor_db).clamp(0.0, 1.0);
+            level.copysign(p)
+        })
+    }
+
+    /// Apply `f` to every level, including RMS and each channel's
+    fn map_levels(&self, f: &dyn Fn(f32) -> f32) -> Self {
+        let map = |levels: &[f32]| levels.iter().map(|&p| f(p)).collect();
+        Self {
+            min_peaks: map(&self.min_peaks),
+            max_peaks: map(&self.max_peaks),
+            rms_peaks: map(&self.rms_peaks),
+            channel_peaks: self.channel_peaks.iter().map(|c| c.map_levels(f)).collect(),
+            ..self.clone_header()
+        }
+    }
+
+    /// Combine peak sets taken from separate channels (or tracks) into one envelope
+    ///
+    /// All sets must have the same number of peaks and sample rate; the
+    /// result keeps the overall extremes and the sum of their channels.
+    /// RMS levels are combined only when every set has them, and the
+    /// result has no per-channel peaks of its own.
+    pub fn merge_channels(peaks: &[WaveformPeaks]) -> Result<Self> {
+        let (first, rest) = peaks.split_first().ok_or_else(|| {
+            AudioError::InvalidParameter("No waveform peaks to merge".to_string())
+        })?;
+        if let Some(other) = rest
+            .iter()
+            .find(|p| p.num_peaks != first.num_peaks || p.sample_rate != first.sample_rate)
+        {
+            return Err(AudioError::InvalidParameter(format!(
+                "Cannot merge {} peaks at {} Hz with {} peaks at {} Hz",
+                first.num_peaks, first.sample_rate, other.num_peaks, other.sample_rate
+            )));
+        }
+
+        let mut merged = first.clone();
+        merged.channel_peaks.clear();
+        for other in rest {
+            for (a, b) in merged.min_peaks.iter_mut().zip(&other.min_peaks) {
+                *a = a.min(*b);
+            }
+            for (a, b) in merged.max_peaks.iter_mut().zip(&other.max_peaks) {
+                *a = a.max(*b);
+            }
+            merged.channels = merged.channels.saturating_add(other.channels);
+            merged.duration_seconds = merged.duration_seconds.max(other.duration_seconds);
+        }
+
+        merged.rms_peaks.clear();
+        if peaks.iter().all(|p| p.rms_peaks.len() == p.num_peaks) {
+            // Power adds up, weighted by how many channels each set covers
+            let total_channels = peaks.iter().map(|p| p.channels.max(1) as f32).sum::<f32>();
+            merged.rms_peaks = (0..first.num_peaks)
+                .map(|i| {
+                    let power = peaks
+                        .iter()
+                        .map(|p| p.channels.max(1) as f32 * p.rms_peaks[i] * p.rms_peaks[i])
+                        .sum::<f32>();
+                    (power / total_channels).sqrt()
+                })
+                .collect();
+        }
+        Ok(merged)
+    }
+
+    /// Same metadata with no peaks, for struct update syntax
+    fn clone_header(&self) -> Self {
+        Self {
+            duration_seconds: self.duration_seconds,
+            channels: self.channels,
+            sample_rate: self.sample_rate,
+            num_peaks: self.num_peaks,
+            ..Self::default()
+        }
+    }
 }
 
 /// Process a decoded packet and update peak values
 ///
-/// Handles all sample formats and updates min/max peaks for the appropriate segments
-fn process_packet_peaks(
-    buffer: &AudioBufferRef,
-    channels: u16,
-    current_frame: &mut u64,
-    frames_per_peak: f64,
-    min_peaks: &mut [f32],
-    max_peaks: &mut [f32],
-) {
+/// Handles all sample formats and updates the segments the packet covers
+fn process_packet_peaks(buffer: &AudioBufferRef, peaks: &mut PeakAccumulator) {
     // Convert buffer to f32 samples and process
     match buffer {
-        AudioBufferRef::F32(buf) => {
-            process_samples_generic(
-                buf.planes().planes(),
-                channels,
-                current_frame,
-                frames_per_peak,
-                min_peaks,
-                max_peaks,
-                |&s| s,
-            );
-        }
+        AudioBufferRef::F32(buf) => process_samples_generic(buf.planes().planes(), peaks, |&s| s),
         AudioBufferRef::F64(buf) => {
-            process_samples_generic(
-                buf.planes().planes(),
-                channels,
-                current_frame,
-                frames_per_peak,
-                min_peaks,
-                max_peaks,
-                |&s| s as f32,
-            );
+            process_samples_generic(buf.planes().planes(), peaks, |&s| s as f32)
         }
         AudioBufferRef::S16(buf) => {
-            process_samples_generic(
-                buf.planes().planes(),
-                channels,
-                current_frame,
-                frames_per_peak,
-                min_peaks,
-                max_peaks,
-                |&s| s as f32 / 32768.0,
-            );
+            process_samples_generic(buf.planes().planes(), peaks, |&s| s as f32 / 32768.0)
         }
         AudioBufferRef::S32(buf) => {
-            process_samples_generic(
-                buf.planes().planes(),
-                channels,
-                current_frame,
-                frames_per_peak,
-                min_peaks,
-                max_peaks,
-                |&s| s as f32 / 2147483648.0,
-            );
+            process_samples_generic(buf.planes().planes(), peaks, |&s| s as f32 / 2147483648.0)
         }
         AudioBufferRef::S8(buf) => {
-            process_samples_generic(
-                buf.planes().planes(),
-                channels,
-                current_frame,
-                frames_per_peak,
-                min_peaks,
-                max_peaks,
-                |&s| s as f32 / 128.0,
-            );
-        }
-        AudioBufferRef::S24(buf) => {
-            process_samples_generic(
-                buf.planes().planes(),
-                channels,
-                current_frame,
-                frames_per_peak,
-                min_peaks,
-                max_peaks,
-                |&s| s.inner() as f32 / 8388608.0,
-            );
-        }
-        AudioBufferRef::U8(buf) => {
-            process_samples_generic(
-                buf.planes().planes(),
-                channels,
-                current_frame,
-                frames_per_peak,
-                min_peaks,
-                max_peaks,
-                |&s| (s as f32 - 128.0) / 128.0,
-            );
-        }
-        AudioBufferRef::U16(buf) => {
-            process_samples_generic(
-                buf.planes().planes(),
-                channels,
-                current_frame,
-                frames_per_peak,
-                min_peaks,
-                max_peaks,
-                |&s| (s as f32 - 32768.0) / 32768.0,
-            );
-        }
-        AudioBufferRef::U24(buf) => {
-            process_samples_generic(
-                buf.planes().planes(),
-                channels,
-                current_frame,
-                frames_per_peak,
-                min_peaks,
-                max_peaks,
-                |&s| (s.inner() as f32 - 8388608.0) / 8388608.0,
-            );
-        }
-        AudioBufferRef::U32(buf) => {
-            process_samples_generic(
-                buf.planes().planes(),
-                channels,
-                current_frame,
-                frames_per_peak,
-                min_peaks,
-                max_peaks,
-                |&s| (s as f32 - 2147483648.0) / 2147483648.0,
-            );
+            process_samples_generic(buf.planes().planes(), peaks, |&s| s as f32 / 128.0)
         }
+        AudioBufferRef::S24(buf) => process_samples_generic(buf.planes().planes(), peaks, |&s| {
+            s.inner() as f32 / 8388608.0
+        }),
+        AudioBufferRef::U8(buf) => process_samples_generic(buf.planes().planes(), peaks, |&s| {
+            (s as f32 - 128.0) / 128.0
+        }),
+        AudioBufferRef::U16(buf) => process_samples_generic(buf.planes().planes(), peaks, |&s| {
+            (s as f32 - 32768.0) / 32768.0
+        }),
+        AudioBufferRef::U24(buf) => process_samples_generic(buf.planes().planes(), peaks, |&s| {
+            (s.inner() as f32 - 8388608.0) / 8388608.0
+        }),
+        AudioBufferRef::U32(buf) => process_samples_generic(buf.planes().planes(), peaks, |&s| {
+            (s as f32 - 2147483648.0) / 2147483648.0
+        }),
     }
 }
 
 /// Generic sample processor for any sample type
 ///
 /// Processes planar audio data (separate channel planes) and updates peaks
-fn process_samples_generic<T, F>(
-    planes: &[&[T]],
-    channels: u16,
-    current_frame: &mut u64,
-    frames_per_peak: f64,
-    min_peaks: &mut [f32],
-    max_peaks: &mut [f32],
-    convert: F,
-) where
+fn process_samples_generic<T, F>(planes: &[&[T]], peaks: &mut PeakAccumulator, convert: F)
+where
     F: Fn(&T) -> f32,
 {
     if planes.is_empty() {
         return;
     }
 
-    let num_peaks = min_peaks.len();
-    let frame_count = planes[0].len();
-
-    // Iterate through frames (one sample per channel)
-    for frame_idx in 0..frame_count {
-        // Determine which peak segment this frame belongs to
-        let peak_idx = (*current_frame as f64 / frames_per_peak) as usize;
-
-        if peak_idx >= num_peaks {
-            break; // Safety: don't overflow peak buffer
-        }
-
-        // Calculate min/max across all channels for this frame
-        let mut frame_min = f32::MAX;
-        let mut frame_max = f32::MIN;
-
-        for channel in 0..channels as usize {
-            if channel < planes.len() {
-                let sample = convert(&planes[channel][frame_idx]);
-                frame_min = frame_min.min(sample);
-                frame_max = frame_max.max(sample);
-            }
-        }
-
-        // Update peak values for this segment
-        min_peaks[peak_idx] = min_peaks[peak_idx].min(frame_min);
-        max_peaks[peak_idx] = max_peaks[peak_idx].max(frame_max);
-
-        *current_frame += 1;
+    // Channels the stream didn't deliver a plane for read as silence
+    for frame_idx in 0..planes[0].len() {
+        peaks.add_frame(|ch| planes.get(ch).map_or(0.0, |plane| convert(&plane[frame_idx])));
     }
 }
 
@@ -385,9 +932,65 @@ mod tests {
         std::fs::remove_file(path).ok();
     }
 
+    fn ramp_peaks(num_peaks: usize) -> WaveformPeaks {
+        WaveformPeaks {
+            min_peaks: (0..num_peaks).map(|i| -(i as f32) / 100.0).collect(),
+            max_peaks: (0..num_peaks).map(|i| i as f32 / 100.0).collect(),
+            num_peaks,
+            duration_seconds: num_peaks as f64 / 10.0,
+            channels: 1,
+            sample_rate: 8000,
+            ..WaveformPeaks::default()
+        }
+    }
+
+    #[test]
+    fn test_downsample_keeps_extremes() {
+        let peaks = ramp_peaks(10).downsample(3).unwrap();
+        assert_eq!(peaks.num_peaks, 3);
+        // Groups of 3, 3 and 4 peaks
+        assert_eq!(peaks.max_peaks, vec![0.02, 0.05, 0.09]);
+        assert_eq!(peaks.min_peaks, vec![-0.02, -0.05, -0.09]);
+        assert_eq!(peaks.duration_seconds, 1.0);
+        assert!(ramp_peaks(10).downsample(0).is_err());
+        assert_eq!(ramp_peaks(10).downsample(20).unwrap().num_peaks, 10);
+    }
+
+    #[test]
+    fn test_slice_by_time() {
+        // 0.1s per peak
+        let peaks = ramp_peaks(10);
+        let secs = Timestamp::from_seconds;
+        let slice = peaks.slice(secs(0.25), secs(0.5)).unwrap();
+        assert_eq!(slice.max_peaks, vec![0.02, 0.03, 0.04]);
+        assert!((slice.duration_seconds - 0.3).abs() < 1e-9);
+        let past_end = peaks.slice(secs(5.0), secs(9.0)).unwrap();
+        assert_eq!(past_end.num_peaks, 0);
+        assert!(past_end.max_peaks.is_empty());
+        assert_eq!(past_end.duration_seconds, 0.0);
+        // A range running off the end stops at the last peak
+        assert_eq!(peaks.slice(secs(0.95), secs(9.0)).unwrap().max_peaks, vec![0.09]);
+        assert!(peaks.slice(secs(0.5), secs(0.5)).is_err());
+    }
+
+    #[test]
+    fn test_normalize_and_merge() {
+        let normalized = ramp_peaks(10).normalize(1.0);
+        assert_eq!(normalized.max_peaks[9], 1.0);
+        assert_eq!(normalized.min_peaks[9], -1.0);
+
+        let mut right = ramp_peaks(10);
+        right.max_peaks[0] = 0.5;
+        let merged = WaveformPeaks::merge_channels(&[ramp_peaks(10), right]).unwrap();
+        assert_eq!(merged.channels, 2);
+        assert_eq!(merged.max_peaks[0], 0.5);
+        assert!(WaveformPeaks::merge_channels(&[ramp_peaks(10), ramp_peaks(5)]).is_err());
+    }
+
     #[test]
     fn test_extract_peaks_validates_num_peaks() {
-        let result = extract_waveform_peaks("nonexistent.mp3", Some(0));
+        let options = WaveformOptions::new().num_peaks(0);
+        let result = extract_waveform_peaks("nonexistent.mp3", &options);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("greater than 0"));
     }
@@ -399,7 +1002,7 @@ mod tests {
         let temp_file = create_test_wav_file(&audio, "basic");
 
         // Extract 100 peaks
-        let peaks = extract_waveform_peaks(&temp_file, Some(100))
+        let peaks = extract_waveform_peaks(&temp_file, &WaveformOptions::new().num_peaks(100))
             .expect("Failed to extract peaks");
 
         // Verify basic properties
@@ -418,16 +1021,16 @@ mod tests {
         let audio = create_test_audio(2.0, 44100, 2);
         let temp_file = create_test_wav_file(&audio, "valid_range");
 
-        let peaks = extract_waveform_peaks(&temp_file, Some(200))
+        let peaks = extract_waveform_peaks(&temp_file, &WaveformOptions::new().num_peaks(200))
             .expect("Failed to extract peaks");
 
         // All peaks should be in valid amplitude range [-1.0, 1.0]
         for &min in &peaks.min_peaks {
-            assert!(min >= -1.0 && min <= 1.0, "Min peak out of range: {}", min);
+            assert!((-1.0..=1.0).contains(&min), "Min peak out of range: {}", min);
         }
 
         for &max in &peaks.max_peaks {
-            assert!(max >= -1.0 && max <= 1.0, "Max peak out of range: {}", max);
+            assert!((-1.0..=1.0).contains(&max), "Max peak out of range: {}", max);
         }
 
         cleanup_test_file(&temp_file);
@@ -438,7 +1041,7 @@ mod tests {
         let audio = create_test_audio(1.5, 44100, 2);
         let temp_file = create_test_wav_file(&audio, "min_max");
 
-        let peaks = extract_waveform_peaks(&temp_file, Some(150))
+        let peaks = extract_waveform_peaks(&temp_file, &WaveformOptions::new().num_peaks(150))
             .expect("Failed to extract peaks");
 
         // For each segment, min should be <= max
@@ -462,8 +1065,9 @@ mod tests {
 
         // Test with different peak counts
         for num_peaks in [10, 100, 500, 1000, 2000] {
-            let peaks = extract_waveform_peaks(&temp_file, Some(num_peaks))
-                .expect(&format!("Failed with {} peaks", num_peaks));
+            let options = WaveformOptions::new().num_peaks(num_peaks);
+            let peaks = extract_waveform_peaks(&temp_file, &options)
+                .unwrap_or_else(|e| panic!("Failed with {} peaks: {}", num_peaks, e));
 
             assert_eq!(peaks.num_peaks, num_peaks);
             assert_eq!(peaks.min_peaks.len(), num_peaks);
@@ -479,7 +1083,7 @@ mod tests {
         let audio = create_test_audio(1.0, 44100, 1);
         let temp_file = create_test_wav_file(&audio, "mono");
 
-        let peaks = extract_waveform_peaks(&temp_file, Some(100))
+        let peaks = extract_waveform_peaks(&temp_file, &WaveformOptions::new().num_peaks(100))
             .expect("Failed to extract peaks from mono audio");
 
         assert_eq!(peaks.channels, 1);
@@ -494,7 +1098,7 @@ mod tests {
         let audio = create_test_audio(30.0, 44100, 2);
         let temp_file = create_test_wav_file(&audio, "long");
 
-        let peaks = extract_waveform_peaks(&temp_file, Some(2000))
+        let peaks = extract_waveform_peaks(&temp_file, &WaveformOptions::new().num_peaks(2000))
             .expect("Failed to extract peaks from long audio");
 
         assert_eq!(peaks.num_peaks, 2000);
@@ -509,7 +1113,7 @@ mod tests {
         let temp_file = create_test_wav_file(&audio, "default");
 
         // Test with None (should default to 2000)
-        let peaks = extract_waveform_peaks(&temp_file, None)
+        let peaks = extract_waveform_peaks(&temp_file, &WaveformOptions::new())
             .expect("Failed with default peaks");
 
         assert_eq!(peaks.num_peaks, 2000);
@@ -519,7 +1123,8 @@ mod tests {
 
     #[test]
     fn test_file_not_found() {
-        let result = extract_waveform_peaks("/nonexistent/path/audio.mp3", Some(100));
+        let options = WaveformOptions::new().num_peaks(100);
+        let result = extract_waveform_peaks("/nonexistent/path/audio.mp3", &options);
         assert!(result.is_err());
     }
 
@@ -535,8 +1140,8 @@ mod tests {
 
         // Middle third is loud (0.8 amplitude)
         let third = total_samples / 3;
-        for i in third..(2 * third) {
-            samples[i] = 0.8;
+        for sample in &mut samples[third..(2 * third)] {
+            *sample = 0.8;
         }
 
         let audio = AudioData {
@@ -548,7 +1153,7 @@ mod tests {
         let temp_file = create_test_wav_file(&audio, "amplitude_variation");
 
         // Extract 30 peaks (10 per second)
-        let peaks = extract_waveform_peaks(&temp_file, Some(30))
+        let peaks = extract_waveform_peaks(&temp_file, &WaveformOptions::new().num_peaks(30))
             .expect("Failed to extract peaks");
 
         // First 10 peaks should be near 0 (silence)
@@ -580,4 +1185,165 @@ mod tests {
 
         cleanup_test_file(&temp_file);
     }
+
+    #[test]
+    fn test_peaks_binary_round_trip() {
+        let peaks = WaveformPeaks {
+            min_peaks: vec![-0.5, -0.25, 0.0],
+            max_peaks: vec![0.5, 0.75, 1.0],
+            num_peaks: 3,
+            duration_seconds: 12.5,
+            channels: 2,
+            sample_rate: 48000,
+            ..WaveformPeaks::default()
+        };
+
+        let bytes = peaks.to_bytes();
+        assert_eq!(bytes.len(), WaveformPeaks::BINARY_HEADER_LEN + 3 * 8);
+        // min_peaks[1] sits right after the header and the first min peak
+        assert_eq!(bytes[24..28], (-0.25f32).to_le_bytes());
+
+        let decoded = WaveformPeaks::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.min_peaks, peaks.min_peaks);
+        assert_eq!(decoded.max_peaks, peaks.max_peaks);
+        assert_eq!(decoded.duration_seconds, 12.5);
+        assert_eq!(decoded.channels, 2);
+        assert_eq!(decoded.sample_rate, 48000);
+
+        assert!(WaveformPeaks::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(WaveformPeaks::from_bytes(&bytes[..8]).is_err());
+    }
+
+    #[test]
+    fn test_options_rms_per_channel_and_range() {
+        // Left at 0.5, right at 0.25, one second
+        let samples = (0..8000).flat_map(|_| [0.5f32, -0.25]).collect();
+        let audio = AudioData {
+            samples,
+            sample_rate: 8000,
+            channels: 2,
+        };
+        let temp_file = create_test_wav_file(&audio, "options");
+
+        let options = WaveformOptions::new().num_peaks(10).per_channel(true).rms(true);
+        let peaks = extract_waveform_peaks(&temp_file, &options).unwrap();
+        assert_eq!(peaks.rms_peaks.len(), 10);
+        assert!((peaks.rms_peaks[0] - (0.15625f32).sqrt()).abs() < 1e-4);
+        assert_eq!(peaks.channel_peaks.len(), 2);
+        assert_eq!(peaks.channel_peaks[0].channels, 1);
+        assert!(peaks.channel_peaks[0].min_peaks.iter().all(|&p| (p - 0.5).abs() < 1e-4));
+        assert!(peaks.channel_peaks[1].max_peaks.iter().all(|&p| (p + 0.25).abs() < 1e-4));
+        assert!((peaks.channel_peaks[1].rms_peaks[3] - 0.25).abs() < 1e-4);
+
+        let bytes = peaks.to_bytes();
+        assert_eq!(bytes[18..20], 3u16.to_le_bytes());
+        let decoded = WaveformPeaks::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.rms_peaks, peaks.rms_peaks);
+        assert_eq!(decoded.channel_peaks[1].max_peaks, peaks.channel_peaks[1].max_peaks);
+
+        let secs = Timestamp::from_seconds;
+        let options = WaveformOptions::new().num_peaks(5).range(secs(0.25), Some(secs(0.75)));
+        let ranged = extract_waveform_peaks(&temp_file, &options).unwrap();
+        assert_eq!(ranged.num_peaks, 5);
+        assert!((ranged.duration_seconds - 0.5).abs() < 1e-9);
+        assert!(ranged.max_peaks.iter().all(|&p| (p - 0.5).abs() < 1e-4));
+
+        let options = WaveformOptions::new().range(secs(2.0), None);
+        assert!(extract_waveform_peaks(&temp_file, &options).is_err());
+
+        cleanup_test_file(&temp_file);
+    }
+
+    #[test]
+    fn test_db_scale_and_cancel_flag() {
+        let scaled = ramp_peaks(10).db_scaled(-60.0);
+        // 0.09 is about -21 dBFS, so roughly 65% of the way up from -60 dB
+        assert!((scaled.max_peaks[9] - 0.651).abs() < 0.01);
+        assert!(scaled.min_peaks[9] < 0.0);
+        assert_eq!(scaled.max_peaks[0], 0.0);
+
+        let audio = create_test_audio(1.0, 8000, 1);
+        let temp_file = create_test_wav_file(&audio, "cancel_flag");
+        let cancel = Arc::new(AtomicBool::new(true));
+        let options = WaveformOptions::new().cancel_flag(cancel);
+        let result = extract_waveform_peaks(&temp_file, &options);
+        assert!(matches!(result, Err(AudioError::Cancelled)));
+        assert!(extract_waveform_peaks(&temp_file, &WaveformOptions::new().db_scale(3.0)).is_err());
+
+        cleanup_test_file(&temp_file);
+    }
+
+    #[test]
+    fn test_stream_chunks_cover_the_result() {
+        let audio = create_test_audio(3.0, 8000, 2);
+        let temp_file = create_test_wav_file(&audio, "stream");
+
+        for options in [
+            WaveformOptions::new().num_peaks(300).per_channel(true).rms(true),
+            WaveformOptions::new()
+                .num_peaks(120)
+                .range(Timestamp::from_seconds(0.5), Some(Timestamp::from_seconds(2.5))),
+        ] {
+            let mut chunks: Vec<PeakChunk> = Vec::new();
+            let peaks = stream_waveform_peaks(&temp_file, &options, &mut NoProgress, &mut |c| {
+                chunks.push(c)
+            })
+            .unwrap();
+            assert!(chunks.len() > 1 && chunks.len() <= STREAM_CHUNKS);
+            assert_eq!(chunks.last().unwrap().fraction, 1.0);
+
+            let mut next = 0;
+            let (mut max, mut rms, mut right) = (Vec::new(), Vec::new(), Vec::new());
+            let mut duration = 0.0;
+            for chunk in &chunks {
+                assert_eq!(chunk.start, next);
+                next += chunk.peaks.num_peaks;
+                max.extend_from_slice(&chunk.peaks.max_peaks);
+                rms.extend_from_slice(&chunk.peaks.rms_peaks);
+                if let Some(channel) = chunk.peaks.channel_peaks.get(1) {
+                    right.extend_from_slice(&channel.min_peaks);
+                }
+                duration += chunk.peaks.duration_seconds;
+            }
+            assert_eq!(next, peaks.num_peaks);
+            assert_eq!(max, peaks.max_peaks);
+            assert_eq!(rms, peaks.rms_peaks);
+            let whole_right = peaks.channel_peaks.get(1).map(|c| c.min_peaks.clone());
+            assert_eq!(right, whole_right.unwrap_or_default());
+            assert!((duration - peaks.duration_seconds).abs() < 1e-9);
+        }
+
+        cleanup_test_file(&temp_file);
+    }
+
+    #[test]
+    fn test_file_without_a_frame_count_is_measured_first() {
+        let audio = create_test_audio(2.0, 8000, 1);
+        let path = std::env::temp_dir().join("hermeneia_test_streamed.flac");
+        crate::audio::encode_flac(&audio, &path, 16, 5).unwrap();
+
+        // A streamed FLAC leaves the STREAMINFO sample count at 0 ("unknown")
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[21] &= 0xf0;
+        bytes[22..26].fill(0);
+        std::fs::write(&path, &bytes).unwrap();
+        crate::audio::release_reader(&path);
+        assert_eq!(open_audio_track(&path).unwrap().n_frames, None);
+
+        // Scanned for its length, it matches a WAV of the same samples
+        let streamed = extract_waveform_peaks(&path, &WaveformOptions::new()).unwrap();
+        // Decoded after the scan, through the same pooled reader
+        let decoded = crate::audio::decode_audio_file(&path).unwrap();
+        assert!(decoded.frame_count() >= 16000);
+        let wav = create_test_wav_file(&decoded, "streamed");
+        let from_wav = extract_waveform_peaks(&wav, &WaveformOptions::new()).unwrap();
+        assert_eq!(streamed.duration_seconds, from_wav.duration_seconds);
+        assert_eq!(streamed.max_peaks, from_wav.max_peaks);
+        assert_eq!(streamed.min_peaks, from_wav.min_peaks);
+
+        for path in [path, wav] {
+            crate::audio::release_reader(&path);
+            cleanup_test_file(&path);
+        }
+    }
 }
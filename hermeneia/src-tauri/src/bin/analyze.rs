@@ -0,0 +1,239 @@
+use clap::Parser;
+use hermeneia_lib::audio::{
+    decode_audio_file_with_progress, detect_clipping, detect_silence, get_audio_info,
+    measure_loudness, ClippingOptions, ClippingReport, LoudnessMeasurement, SilenceOptions, SilenceRegion,
+};
+use hermeneia_lib::cli::{
+    exit_with, expand_inputs, format_time, parse_args, parse_time, BatchItem,
+    ExitError, FileProgress, Output, OutputArgs, EXIT_CODES_HELP,
+};
+use hermeneia_lib::memory::{decoded_size_bytes, MemoryBudget};
+use hermeneia_lib::pool::{worker_limit, WorkerPool};
+use hermeneia_lib::profile::Operation;
+use serde::Serialize;
+use std::process::ExitCode;
+use tracing::{debug, info};
+
+/// Command-line tool for auditing audio files
+#[derive(Parser, Debug)]
+#[command(name = "analyze")]
+#[command(about = "Report loudness, clipping and silence for audio files", long_about = None)]
+#[command(after_help = EXIT_CODES_HELP)]
+struct Args {
+    /// Input audio files or glob patterns (e.g. "archive/**/*.wav")
+    #[arg(short, long, required = true, num_args = 1..)]
+    input: Vec<String>,
+
+    /// Number of files to analyze in parallel [default: one per CPU core]
+    #[arg(short = 'j', long)]
+    jobs: Option<usize>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// Level in dBFS at or above which samples count as clipped
+    #[arg(long, default_value_t = -0.01, allow_negative_numbers = true)]
+    clip_threshold: f64,
+
+    /// Shortest run of clipped samples to report
+    #[arg(long, default_value_t = 3)]
+    min_clip_run: usize,
+
+    /// Level in dBFS below which audio counts as silence
+    #[arg(long, default_value_t = -40.0, allow_negative_numbers = true)]
+    silence_threshold: f64,
+
+    /// Shortest silence to report
+    #[arg(long, value_parser = parse_time, default_value = "2")]
+    min_silence: f64,
+}
+
+/// Per-file details for the `--json` report
+#[derive(Debug, Serialize)]
+struct AnalyzeResult {
+    sample_rate: u32,
+    channels: u16,
+    duration_seconds: f64,
+    loudness: LoudnessMeasurement,
+    clipping: ClippingReport,
+    silence: Vec<SilenceRegion>,
+    silence_seconds: f64,
+    /// Short descriptions of anything that likely needs attention
+    issues: Vec<String>,
+}
+
+fn analyze_file(
+    item: &BatchItem,
+    args: &Args,
+    budget: MemoryBudget,
+    progress: &FileProgress,
+) -> anyhow::Result<AnalyzeResult> {
+    let input = item.input.display().to_string();
+
+    // Step 1: Decode audio (the checks need the whole file in memory)
+    let info = get_audio_info(&item.input)?;
+    budget.check(decoded_size_bytes(info.duration_seconds, info.sample_rate, info.channels))?;
+
+    info!(file = %input, "Decoding audio");
+    let start_time = std::time::Instant::now();
+    let audio = decode_audio_file_with_progress(&item.input, &mut progress.sink())?;
+
+    debug!(
+        file = %input,
+        sample_rate = audio.sample_rate,
+        channels = audio.channels,
+        duration_sec = audio.duration_seconds(),
+        decode_time_sec = start_time.elapsed().as_secs_f64(),
+        "Audio decoded"
+    );
+
+    // Step 2: Run the detectors
+    progress.stage("analyze");
+    let loudness = measure_loudness(&audio)?;
+    progress.set_fraction(0.5);
+    let clipping = detect_clipping(
+        &audio,
+        &ClippingOptions {
+            threshold_db: args.clip_threshold,
+            min_run_samples: args.min_clip_run,
+        },
+    )?;
+    let silence = detect_silence(
+        &audio,
+        &SilenceOptions {
+            threshold_db: args.silence_threshold,
+            min_duration_seconds: args.min_silence,
+        },
+    )?;
+    let silence_seconds: f64 = silence.iter().map(SilenceRegion::duration_seconds).sum();
+
+    // Step 3: Summarize
+    let duration = audio.duration_seconds();
+    let mut issues = Vec::new();
+    if loudness.sample_peak == 0.0 {
+        issues.push("entirely silent".to_string());
+    } else if duration > 0.0 && silence_seconds / duration >= 0.5 {
+        issues.push(format!(
+            "{:.0}% silence",
+            100.0 * silence_seconds / duration
+        ));
+    }
+    if !clipping.regions.is_empty() {
+        issues.push(format!("clipped in {} place(s)", clipping.regions.len()));
+    }
+
+    progress.finish();
+    info!(
+        file = %input,
+        issues = issues.len(),
+        total_time_sec = start_time.elapsed().as_secs_f64(),
+        "Done! Analysis complete"
+    );
+
+    Ok(AnalyzeResult {
+        sample_rate: audio.sample_rate,
+        channels: audio.channels,
+        duration_seconds: duration,
+        loudness,
+        clipping,
+        silence,
+        silence_seconds,
+        issues,
+    })
+}
+
+/// Human-readable report for one file on stdout
+fn print_result(item: &BatchItem, result: &AnalyzeResult) {
+    println!("{}", item.input.display());
+    println!(
+        "  Format:      {} Hz, {} ch, {}",
+        result.sample_rate,
+        result.channels,
+        format_time(result.duration_seconds)
+    );
+    match result.loudness.integrated_lufs {
+        Some(lufs) => println!("  Loudness:    {:.1} LUFS", lufs),
+        None => println!("  Loudness:    n/a (too short or too quiet)"),
+    }
+    println!(
+        "  Sample peak: {:.1} dBFS",
+        result.loudness.sample_peak_dbfs
+    );
+    println!(
+        "  True peak:   {:.1} dBTP",
+        result.loudness.true_peak_dbtp
+    );
+    println!(
+        "  Clipping:    {} sample(s) in {} region(s)",
+        result.clipping.clipped_samples,
+        result.clipping.regions.len()
+    );
+    for region in &result.clipping.regions {
+        println!(
+            "    {} - {}",
+            format_time(region.start_seconds),
+            format_time(region.end_seconds)
+        );
+    }
+    println!(
+        "  Silence:     {} in {} region(s)",
+        format_time(result.silence_seconds),
+        result.silence.len()
+    );
+    for region in &result.silence {
+        println!(
+            "    {} - {}",
+            format_time(region.start_seconds),
+            format_time(region.end_seconds)
+        );
+    }
+    if result.issues.is_empty() {
+        println!("  Issues:      none");
+    } else {
+        println!("  Issues:      {}", result.issues.join(", "));
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Args = parse_args();
+    exit_with(run(args))
+}
+
+fn run(args: Args) -> anyhow::Result<()> {
+    let output = Output::init(&args.output);
+
+    // Nothing is written, so each item's output is just its input
+    let items: Vec<BatchItem> = expand_inputs(&args.input)?
+        .into_iter()
+        .enumerate()
+        .map(|(i, input)| BatchItem {
+            index: i + 1,
+            output: input.clone(),
+            input,
+        })
+        .collect();
+
+    let jobs = worker_limit(args.jobs);
+    let budget = MemoryBudget::current().per_job(jobs);
+    let results = WorkerPool::new(jobs).map(&items, |item| {
+        let progress = output.file_progress(item);
+        let _profile = Operation::start("analyze", &item.input);
+        analyze_file(item, &args, budget, &progress)
+    });
+
+    if !args.output.json {
+        for (item, result) in items.iter().zip(&results) {
+            if let Ok(result) = result {
+                print_result(item, result);
+            }
+        }
+    }
+
+    output.finish_batch(&items, &results);
+
+    if let Some(err) = ExitError::from_batch(&results) {
+        return Err(err.into());
+    }
+
+    Ok(())
+}
@@ -1,50 +1,100 @@
 use clap::Parser;
-use hermeneia_lib::audio::{decode_audio_file, encode_wav, get_audio_info, trim_audio, TrimParams};
-use tracing::{info, debug, error};
+use hermeneia_lib::audio::{
+    get_audio_info, trim_file_with, OutputFormat, TrimOutput, TrimParams, TrimPoint,
+};
+use hermeneia_lib::cli::{
+    exit_with, format_time, parse_args, parse_time, BatchArgs, BatchItem, ExitError,
+    FileProgress, FormatPreset, Output, OutputArgs, EXIT_CODES_HELP,
+};
+use hermeneia_lib::memory::MemoryBudget;
+use hermeneia_lib::pool::WorkerPool;
+use hermeneia_lib::profile::Operation;
+use serde::Serialize;
+use std::process::ExitCode;
+use tracing::{info, debug};
 
 /// Command-line tool for trimming audio files
 #[derive(Parser, Debug)]
 #[command(name = "audio-trim")]
 #[command(about = "Trim audio files to a specific time range", long_about = None)]
+#[command(after_help = EXIT_CODES_HELP)]
+#[command(group = clap::ArgGroup::new("range").required(true).multiple(true))]
 struct Args {
-    /// Input audio file (MP3, FLAC, WAV, OGG, etc.)
+    #[command(flatten)]
+    batch: BatchArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// Start time (seconds, MM:SS, HH:MM:SS.mmm or 1h2m3s); alone, keeps
+    /// everything after it
+    #[arg(short, long, value_parser = parse_time, group = "range")]
+    start: Option<f64>,
+
+    /// End time (same formats as --start)
+    #[arg(short, long, value_parser = parse_time, group = "range")]
+    end: Option<f64>,
+
+    /// Length of the clip instead of an end time (same formats as --start)
+    #[arg(short, long, value_parser = parse_time, group = "range", conflicts_with = "end")]
+    duration: Option<f64>,
+
+    /// Keep only the last part of each file, this long (same formats as --start)
+    #[arg(
+        long,
+        value_parser = parse_time,
+        group = "range",
+        conflicts_with_all = ["start", "end", "duration"]
+    )]
+    last: Option<f64>,
+
+    /// Output format (inferred from --output, otherwise 32-bit float WAV)
+    #[arg(short, long, value_enum)]
+    format: Option<FormatPreset>,
+
+    /// Bitrate in kbps for MP3/Opus
+    #[arg(long)]
+    bitrate: Option<u32>,
+
+    /// Resample the clip to this rate in Hz (e.g. 16000, 44100, 48000)
+    #[arg(short = 'r', long)]
+    sample_rate: Option<u32>,
+
+    /// Mix the clip down to a single channel
     #[arg(short, long)]
-    input: String,
+    mono: bool,
 
-    /// Output WAV file
-    #[arg(short, long)]
-    output: String,
-
-    /// Start time in seconds
-    #[arg(short, long)]
-    start: f64,
-
-    /// End time in seconds
-    #[arg(short, long)]
-    end: f64,
-
-    /// Show detailed information
-    #[arg(short, long)]
-    verbose: bool,
+    /// Decode the whole file and trim in memory instead of seeking to the range
+    #[arg(long)]
+    full_decode: bool,
 }
 
-fn main() -> anyhow::Result<()> {
-    // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"))
-        )
-        .init();
+/// Per-file details for the `--json` report
+#[derive(Debug, Serialize)]
+struct TrimResult {
+    duration_seconds: f64,
+    sample_rate: u32,
+    channels: u16,
+    format: OutputFormat,
+}
 
-    let args = Args::parse();
+fn trim_file(
+    item: &BatchItem,
+    args: &Args,
+    params: &TrimParams,
+    format: &OutputFormat,
+    budget: MemoryBudget,
+    progress: &FileProgress,
+) -> anyhow::Result<TrimResult> {
+    let input = item.input.display().to_string();
+    let output = item.output.display().to_string();
 
     // Step 1: Get audio info
-    debug!("Getting audio info");
-    let info = get_audio_info(&args.input)?;
+    debug!(file = %input, "Getting audio info");
+    let info = get_audio_info(&item.input)?;
 
     info!(
-        file = %args.input,
+        file = %input,
         duration_sec = info.duration_seconds,
         duration_min = info.duration_seconds / 60.0,
         sample_rate = info.sample_rate,
@@ -53,62 +103,93 @@ fn main() -> anyhow::Result<()> {
         "Input audio file info"
     );
 
-    // Step 2: Validate trim parameters
-    let params = TrimParams::new(args.start, args.end)?;
+    // Step 2: Cut the clip; the library seeks to the range, streams plain
+    // WAV to disk and converts the clip on the way
+    let start_time = std::time::Instant::now();
+    let mut clip_output = TrimOutput::new(*format).full_decode(args.full_decode);
+    if args.mono {
+        clip_output = clip_output.channels(1);
+    }
+    if let Some(sample_rate) = args.sample_rate {
+        clip_output = clip_output.sample_rate(sample_rate);
+    }
+    let mut sink = progress.sink();
+    let summary =
+        trim_file_with(&item.input, &item.output, params, &clip_output, &budget, &mut sink)?;
+    let sample_rate = clip_output.sample_rate.unwrap_or(info.sample_rate);
 
+    progress.finish();
     info!(
-        start_sec = params.start_seconds,
-        end_sec = params.end_seconds,
-        trim_duration_sec = params.trim_duration(),
-        "Trim range"
+        output = %output,
+        clip_start = %format_time(summary.start.as_seconds()),
+        clip_end = %format_time(summary.end.as_seconds()),
+        total_time_sec = start_time.elapsed().as_secs_f64(),
+        "Done! Output saved"
     );
 
-    if params.end_seconds > info.duration_seconds {
-        error!(
-            trim_end = params.end_seconds,
-            audio_duration = info.duration_seconds,
-            "Trim end time exceeds audio duration"
-        );
-        std::process::exit(1);
-    }
-
-    // Step 3: Decode audio
-    info!("Decoding audio");
-    let start_time = std::time::Instant::now();
-    let audio = decode_audio_file(&args.input)?;
+    Ok(TrimResult {
+        duration_seconds: summary.frames as f64 / sample_rate as f64,
+        sample_rate,
+        channels: clip_output.channels.unwrap_or(info.channels),
+        format: *format,
+    })
+}
 
-    debug!(
-        samples = audio.samples.len(),
-        size_mb = (audio.samples.len() * 4) as f64 / 1_048_576.0,
-        decode_time_sec = start_time.elapsed().as_secs_f64(),
-        "Audio decoded"
-    );
+/// A trim point for logs, e.g. "1:30.000" or "end - 0:30.000"
+fn describe_point(point: TrimPoint) -> String {
+    match point {
+        TrimPoint::At(time) => format_time(time.as_seconds()),
+        TrimPoint::BeforeEnd(offset) if offset.is_zero() => "end".to_string(),
+        TrimPoint::BeforeEnd(offset) => format!("end - {}", format_time(offset.as_seconds())),
+    }
+}
 
-    // Step 4: Trim audio
-    info!("Trimming audio");
-    let trimmed = trim_audio(&audio, &params)?;
+fn main() -> ExitCode {
+    let args: Args = parse_args();
+    exit_with(run(args))
+}
 
-    debug!(
-        samples = trimmed.samples.len(),
-        duration_sec = trimmed.duration_seconds(),
-        "Audio trimmed"
-    );
+fn run(args: Args) -> anyhow::Result<()> {
+    let output = Output::init(&args.output);
 
-    // Step 5: Encode to WAV
-    info!("Encoding to WAV");
-    let encode_start = std::time::Instant::now();
-    encode_wav(&trimmed, &args.output)?;
-
-    debug!(
-        encode_time_sec = encode_start.elapsed().as_secs_f64(),
-        "WAV encoding complete"
-    );
+    // Validate trim parameters once for the whole batch
+    let start = args.start.unwrap_or(0.0);
+    let params = match (args.last, args.end, args.duration) {
+        (Some(last), _, _) => TrimParams::last(last)?,
+        (None, Some(end), _) => TrimParams::new(start, end)?,
+        (None, None, Some(duration)) => TrimParams::new(start, start + duration)?,
+        (None, None, None) => TrimParams::to_end(start)?,
+    };
 
     info!(
-        output = %args.output,
-        total_time_sec = start_time.elapsed().as_secs_f64(),
-        "Done! Output saved"
+        start = %describe_point(params.start),
+        end = %describe_point(params.end),
+        trim_duration_sec = params.trim_duration().map(|d| d.as_seconds()),
+        "Trim range"
     );
 
+    let preset = args
+        .format
+        .or_else(|| args.batch.output.as_deref().and_then(FormatPreset::from_path))
+        .unwrap_or(FormatPreset::Wav32f);
+    let format = preset.output_format(args.bitrate);
+
+    let items = args
+        .batch
+        .plan("{stem}_trimmed.{format}", &[("format", format.extension())])?;
+    let jobs = args.batch.jobs();
+    let budget = MemoryBudget::current().per_job(jobs);
+    let results = WorkerPool::new(jobs).map(&items, |item| {
+        let progress = output.file_progress(item);
+        let _profile = Operation::start("audio-trim", &item.input);
+        trim_file(item, &args, &params, &format, budget, &progress)
+    });
+
+    output.finish_batch(&items, &results);
+
+    if let Some(err) = ExitError::from_batch(&results) {
+        return Err(err.into());
+    }
+
     Ok(())
-}
\ No newline at end of file
+}
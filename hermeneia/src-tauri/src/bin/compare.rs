@@ -0,0 +1,225 @@
+use clap::Parser;
+use hermeneia_lib::audio::{
+    compare_audio, decode_audio_file, linear_to_db, measure_quality, remix_channels,
+    resample_audio, CompareOptions, ComparisonReport, QualityOptions, QualityReport,
+};
+use hermeneia_lib::cli::{
+    exit_with, format_time, parse_args, parse_time, ExitError, ExitStatus, Output, OutputArgs,
+    EXIT_CODES_HELP,
+};
+use hermeneia_lib::profile::Operation;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use tracing::{debug, info, warn};
+
+/// Command-line tool for diffing two audio files
+#[derive(Parser, Debug)]
+#[command(name = "compare")]
+#[command(about = "Align two audio files and report where and by how much they differ", long_about = None)]
+#[command(after_help = EXIT_CODES_HELP)]
+struct Args {
+    /// The original file
+    reference: PathBuf,
+
+    /// The processed or round-tripped file to check against it
+    candidate: PathBuf,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// Differences above this level in dBFS count as a mismatch
+    #[arg(short, long, default_value_t = -90.0, allow_negative_numbers = true)]
+    tolerance: f64,
+
+    /// Largest offset between the files to search when aligning them (e.g. 0.5, 2s)
+    #[arg(long, value_parser = parse_time, default_value = "1")]
+    max_offset: f64,
+
+    /// Length of the regions mismatches are reported in
+    #[arg(long, value_parser = parse_time, default_value = "0.1")]
+    region: f64,
+
+    /// Most mismatch regions to list (the JSON report always has all of them)
+    #[arg(long, default_value_t = 20)]
+    max_regions: usize,
+
+    /// Also score the candidate with segmental SNR and spectral distance; the files are
+    /// expected to differ, so mismatches don't fail the run
+    #[arg(long)]
+    quality: bool,
+}
+
+/// The `--json` report
+#[derive(Debug, Serialize)]
+struct CompareResult<'a> {
+    reference: &'a Path,
+    candidate: &'a Path,
+    /// True when no region differs by more than the tolerance
+    matches: bool,
+    #[serde(flatten)]
+    report: &'a ComparisonReport,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quality: Option<&'a QualityReport>,
+}
+
+fn main() -> ExitCode {
+    let args: Args = parse_args();
+    exit_with(run(args))
+}
+
+fn run(args: Args) -> anyhow::Result<()> {
+    let _output = Output::init(&args.output);
+    let _profile = Operation::start("compare", &args.candidate);
+
+    // Step 1: Decode both files
+    info!(file = %args.reference.display(), "Decoding reference");
+    let start_time = std::time::Instant::now();
+    let reference = decode_audio_file(&args.reference)?;
+    info!(file = %args.candidate.display(), "Decoding candidate");
+    let mut candidate = decode_audio_file(&args.candidate)?;
+
+    debug!(
+        decode_time_sec = start_time.elapsed().as_secs_f64(),
+        reference_sec = reference.duration_seconds(),
+        candidate_sec = candidate.duration_seconds(),
+        "Audio decoded"
+    );
+
+    // Step 2: Bring the candidate into the reference's format
+    if candidate.channels != reference.channels {
+        warn!(
+            from = candidate.channels,
+            to = reference.channels,
+            "Channel counts differ; remixing candidate"
+        );
+        candidate = remix_channels(&candidate, reference.channels)?;
+    }
+    if candidate.sample_rate != reference.sample_rate {
+        warn!(
+            from = candidate.sample_rate,
+            to = reference.sample_rate,
+            "Sample rates differ; resampling candidate"
+        );
+        candidate = resample_audio(&candidate, reference.sample_rate)?;
+    }
+
+    // Step 3: Align and compare
+    info!("Comparing");
+    let options = CompareOptions {
+        max_offset_seconds: args.max_offset,
+        tolerance_db: args.tolerance,
+        region_seconds: args.region,
+    };
+    let report = compare_audio(&reference, &candidate, &options)?;
+    let matches = report.mismatches.is_empty();
+    let quality = if args.quality {
+        info!("Scoring quality");
+        let options = QualityOptions {
+            max_offset_seconds: args.max_offset,
+            ..QualityOptions::default()
+        };
+        Some(measure_quality(&reference, &candidate, &options)?)
+    } else {
+        None
+    };
+
+    // Step 4: Report
+    if args.output.json {
+        let result = CompareResult {
+            reference: &args.reference,
+            candidate: &args.candidate,
+            matches,
+            report: &report,
+            quality: quality.as_ref(),
+        };
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    } else {
+        print_report(&report, &args);
+        if let Some(quality) = &quality {
+            print_quality(quality);
+        }
+    }
+
+    if !matches && quality.is_none() {
+        anyhow::bail!(ExitError::new(
+            ExitStatus::CheckFailed,
+            format!(
+                "Files differ in {} region(s) (peak difference {:.1} dBFS)",
+                report.mismatches.len(),
+                report.peak_difference_db
+            )
+        ));
+    }
+
+    Ok(())
+}
+
+/// Human-readable summary on stdout
+fn print_report(report: &ComparisonReport, args: &Args) {
+    let verdict = if report.is_identical() {
+        "identical"
+    } else if report.mismatches.is_empty() {
+        "match within tolerance"
+    } else {
+        "DIFFER"
+    };
+    println!("Result:          {}", verdict);
+    println!(
+        "Offset:          {} frames ({:+.4}s)",
+        report.offset_frames, report.offset_seconds
+    );
+    println!("Compared:        {} frames", report.compared_frames);
+    if report.length_difference_frames != 0 {
+        let longer = if report.length_difference_frames > 0 {
+            "reference"
+        } else {
+            "candidate"
+        };
+        println!(
+            "Length:          {} has {} extra frame(s)",
+            longer,
+            report.length_difference_frames.unsigned_abs()
+        );
+    }
+    println!(
+        "Peak difference: {:.6} ({:.1} dBFS)",
+        report.peak_difference, report.peak_difference_db
+    );
+    println!(
+        "RMS difference:  {:.6} ({:.1} dBFS)",
+        report.rms_difference, report.rms_difference_db
+    );
+
+    if report.mismatches.is_empty() {
+        return;
+    }
+
+    println!(
+        "Mismatches above {:.1} dBFS: {}",
+        args.tolerance,
+        report.mismatches.len()
+    );
+    for region in report.mismatches.iter().take(args.max_regions) {
+        println!(
+            "  {} - {}  peak {:.1} dBFS",
+            format_time(region.start_seconds),
+            format_time(region.end_seconds),
+            linear_to_db(region.peak_difference as f64)
+        );
+    }
+    if report.mismatches.len() > args.max_regions {
+        println!(
+            "  ... and {} more",
+            report.mismatches.len() - args.max_regions
+        );
+    }
+}
+
+/// Quality scores on stdout, after the comparison
+fn print_quality(quality: &QualityReport) {
+    println!("Quality ({} frames scored):", quality.scored_frames);
+    println!("  SNR:               {:.1} dB", quality.snr_db);
+    println!("  Segmental SNR:     {:.1} dB", quality.segmental_snr_db);
+    println!("  Spectral distance: {:.2} dB", quality.spectral_distance_db);
+}
@@ -0,0 +1,268 @@
+use clap::{Parser, ValueEnum};
+use hermeneia_lib::audio::pipeline::{FileSource, Pipeline, Remix, Resample, WavSink};
+use hermeneia_lib::audio::{
+    decode_audio_file_with_progress, encode_audio_with_progress, get_audio_info, remix_channels,
+    resample_audio, OutputFormat, WavSampleFormat, DEFAULT_FLAC_COMPRESSION,
+};
+use hermeneia_lib::cli::{
+    exit_with, parse_args, BatchArgs, BatchItem, ExitError, FileProgress, Output,
+    OutputArgs, EXIT_CODES_HELP,
+};
+use hermeneia_lib::memory::{decoded_size_bytes, MemoryBudget, ProcessingMode};
+use hermeneia_lib::pool::WorkerPool;
+use hermeneia_lib::profile::Operation;
+use serde::Serialize;
+use std::process::ExitCode;
+use tracing::{debug, info};
+
+/// Output containers supported by the convert tool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum FormatArg {
+    Wav,
+    Flac,
+    Mp3,
+    Opus,
+}
+
+/// Command-line tool for converting audio between formats
+#[derive(Parser, Debug)]
+#[command(name = "convert")]
+#[command(about = "Convert audio files to WAV, FLAC, MP3 or Opus", long_about = None)]
+#[command(after_help = EXIT_CODES_HELP)]
+struct Args {
+    #[command(flatten)]
+    batch: BatchArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// Output format (inferred from --output if omitted)
+    #[arg(short, long, value_enum)]
+    format: Option<FormatArg>,
+
+    /// Resample to this rate in Hz (e.g. 16000, 44100, 48000)
+    #[arg(short = 'r', long)]
+    sample_rate: Option<u32>,
+
+    /// Mix down/up to this many channels (e.g. 1 for mono)
+    #[arg(short, long)]
+    channels: Option<u16>,
+
+    /// Bit depth for WAV (16, 24 or 32 float) and FLAC (16 or 24)
+    #[arg(short, long)]
+    bit_depth: Option<u16>,
+
+    /// Bitrate in kbps for MP3/Opus
+    #[arg(long)]
+    bitrate: Option<u32>,
+
+    /// FLAC compression level, 0 (fastest) to 8 (smallest)
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..=8))]
+    compression_level: Option<u8>,
+
+    /// Tune Opus for spoken word (defaults the bitrate to 32 kbps)
+    #[arg(long)]
+    speech: bool,
+}
+
+/// Turn the CLI flags into a concrete encoder configuration
+fn output_format(args: &Args) -> anyhow::Result<OutputFormat> {
+    let format = match args.format {
+        Some(format) => format,
+        None => {
+            let ext = args
+                .batch
+                .output
+                .as_ref()
+                .and_then(|p| p.extension())
+                .and_then(|e| e.to_str())
+                .unwrap_or_default();
+            match OutputFormat::from_extension(ext) {
+                Some(OutputFormat::Wav { .. }) => FormatArg::Wav,
+                Some(OutputFormat::Flac { .. }) => FormatArg::Flac,
+                Some(OutputFormat::Mp3 { .. }) => FormatArg::Mp3,
+                Some(OutputFormat::Opus { .. }) => FormatArg::Opus,
+                _ => anyhow::bail!(ExitError::usage(
+                    "Cannot infer output format from --output; pass --format wav|flac|mp3|opus"
+                )),
+            }
+        }
+    };
+
+    Ok(match format {
+        FormatArg::Wav => OutputFormat::Wav {
+            sample_format: match args.bit_depth.unwrap_or(32) {
+                16 => WavSampleFormat::Pcm16,
+                24 => WavSampleFormat::Pcm24,
+                32 => WavSampleFormat::Float32,
+                other => anyhow::bail!(ExitError::usage(format!(
+                    "WAV bit depth must be 16, 24 or 32 (got {})",
+                    other
+                ))),
+            },
+        },
+        FormatArg::Flac => OutputFormat::Flac {
+            bits_per_sample: args.bit_depth.unwrap_or(24),
+            compression_level: args.compression_level.unwrap_or(DEFAULT_FLAC_COMPRESSION),
+        },
+        FormatArg::Mp3 => OutputFormat::Mp3 {
+            bitrate_kbps: args.bitrate.unwrap_or(192),
+        },
+        FormatArg::Opus => OutputFormat::Opus {
+            bitrate_kbps: args.bitrate.unwrap_or(if args.speech { 32 } else { 64 }),
+            speech: args.speech,
+        },
+    })
+}
+
+/// Per-file details for the `--json` report
+#[derive(Debug, Serialize)]
+struct ConvertResult {
+    duration_seconds: f64,
+    sample_rate: u32,
+    channels: u16,
+    format: OutputFormat,
+}
+
+fn convert_file(
+    item: &BatchItem,
+    args: &Args,
+    format: &OutputFormat,
+    budget: MemoryBudget,
+    progress: &FileProgress,
+) -> anyhow::Result<ConvertResult> {
+    let input = item.input.display().to_string();
+
+    // Step 1: Check the decoded size against the memory budget. Remixing and
+    // resampling build a second buffer, so count the output as well.
+    let info = get_audio_info(&item.input)?;
+    let channels = args.channels.unwrap_or(info.channels);
+    let sample_rate = args.sample_rate.unwrap_or(info.sample_rate);
+    let required = decoded_size_bytes(info.duration_seconds, info.sample_rate, info.channels)
+        + decoded_size_bytes(info.duration_seconds, sample_rate, channels);
+
+    // WAV can be written as it decodes; the other encoders need it all
+    if let OutputFormat::Wav { sample_format } = format {
+        if budget.mode_for(required) == ProcessingMode::Streaming {
+            return stream_to_wav(item, channels, sample_rate, *sample_format, progress);
+        }
+    }
+    budget.check(required)?;
+
+    // Step 2: Decode audio
+    info!(file = %input, "Decoding audio");
+    let start_time = std::time::Instant::now();
+    let mut audio = decode_audio_file_with_progress(&item.input, &mut progress.sink())?;
+
+    debug!(
+        file = %input,
+        sample_rate = audio.sample_rate,
+        channels = audio.channels,
+        duration_sec = audio.duration_seconds(),
+        decode_time_sec = start_time.elapsed().as_secs_f64(),
+        "Audio decoded"
+    );
+
+    // Step 3: Channel conversion (before resampling, so mono downmixes resample less data)
+    if let Some(channels) = args.channels {
+        if channels != audio.channels {
+            info!(file = %input, from = audio.channels, to = channels, "Remixing channels");
+            audio = remix_channels(&audio, channels)?;
+        }
+    }
+
+    // Step 4: Sample rate conversion
+    if let Some(sample_rate) = args.sample_rate {
+        if sample_rate != audio.sample_rate {
+            info!(file = %input, from = audio.sample_rate, to = sample_rate, "Resampling");
+            audio = resample_audio(&audio, sample_rate)?;
+        }
+    }
+
+    // Step 5: Encode
+    info!(file = %input, format = ?format, "Encoding");
+    let encode_start = std::time::Instant::now();
+    encode_audio_with_progress(&audio, &item.output, format, &mut progress.sink())?;
+
+    debug!(
+        file = %input,
+        encode_time_sec = encode_start.elapsed().as_secs_f64(),
+        "Encoding complete"
+    );
+
+    progress.finish();
+    info!(
+        output = %item.output.display(),
+        total_time_sec = start_time.elapsed().as_secs_f64(),
+        "Done! Output saved"
+    );
+
+    Ok(ConvertResult {
+        duration_seconds: audio.duration_seconds(),
+        sample_rate: audio.sample_rate,
+        channels: audio.channels,
+        format: *format,
+    })
+}
+
+/// Decode, convert and write WAV chunk by chunk, for files over the memory budget
+fn stream_to_wav(
+    item: &BatchItem,
+    channels: u16,
+    sample_rate: u32,
+    sample_format: WavSampleFormat,
+    progress: &FileProgress,
+) -> anyhow::Result<ConvertResult> {
+    info!(file = %item.input.display(), "Streaming to WAV");
+    progress.stage("convert");
+    let start_time = std::time::Instant::now();
+
+    let mut sink = WavSink::new(&item.output, sample_format);
+    let summary = Pipeline::new(FileSource::open(&item.input)?)
+        .then(Remix::new(channels))
+        .then(Resample::new(sample_rate))
+        .run_with_progress(&mut sink, &mut progress.sink())?;
+
+    progress.finish();
+    info!(
+        output = %item.output.display(),
+        total_time_sec = start_time.elapsed().as_secs_f64(),
+        "Done! Output saved"
+    );
+
+    Ok(ConvertResult {
+        duration_seconds: summary.frames_out as f64 / sample_rate as f64,
+        sample_rate,
+        channels,
+        format: OutputFormat::Wav { sample_format },
+    })
+}
+
+fn main() -> ExitCode {
+    let args: Args = parse_args();
+    exit_with(run(args))
+}
+
+fn run(args: Args) -> anyhow::Result<()> {
+    let output = Output::init(&args.output);
+    let format = output_format(&args)?;
+
+    let items = args
+        .batch
+        .plan("{stem}.{format}", &[("format", format.extension())])?;
+    let jobs = args.batch.jobs();
+    let budget = MemoryBudget::current().per_job(jobs);
+    let results = WorkerPool::new(jobs).map(&items, |item| {
+        let progress = output.file_progress(item);
+        let _profile = Operation::start("convert", &item.input);
+        convert_file(item, &args, &format, budget, &progress)
+    });
+
+    output.finish_batch(&items, &results);
+
+    if let Some(err) = ExitError::from_batch(&results) {
+        return Err(err.into());
+    }
+
+    Ok(())
+}
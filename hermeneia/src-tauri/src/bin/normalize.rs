@@ -0,0 +1,210 @@
+use clap::Parser;
+use hermeneia_lib::audio::{
+    apply_gain, decode_audio_file_with_progress, encode_audio_with_progress,
+    measure_loudness_with_progress, normalize_loudness_with_progress, LoudnessMeasurement,
+    LoudnessStandard, LoudnessTarget, OutputFormat,
+};
+use hermeneia_lib::cli::{
+    exit_with, parse_args, BatchArgs, BatchItem, ExitError, FileProgress, Output,
+    OutputArgs, EXIT_CODES_HELP,
+};
+use hermeneia_lib::pool::WorkerPool;
+use hermeneia_lib::profile::Operation;
+use serde::Serialize;
+use std::process::ExitCode;
+use tracing::{debug, info, warn};
+
+/// Command-line tool for normalizing audio levels
+#[derive(Parser, Debug)]
+#[command(name = "normalize")]
+#[command(about = "Normalize audio files to a peak level or a LUFS loudness target", long_about = None)]
+#[command(after_help = EXIT_CODES_HELP)]
+#[command(group = clap::ArgGroup::new("target").required(true))]
+struct Args {
+    #[command(flatten)]
+    batch: BatchArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// Target sample peak in dBFS (e.g. -1)
+    #[arg(long, group = "target", allow_negative_numbers = true)]
+    peak: Option<f64>,
+
+    /// Target integrated loudness in LUFS (e.g. -16 for podcasts, -23 for EBU R128)
+    #[arg(long, group = "target", allow_negative_numbers = true)]
+    lufs: Option<f64>,
+
+    /// Published loudness target (podcast -16, streaming -14, ebu-r128 -23, atsc-a85 -24 LUFS)
+    #[arg(long, value_enum, group = "target")]
+    standard: Option<LoudnessStandard>,
+
+    /// Highest true peak allowed after a loudness gain change, in dBTP [default: -1, or the
+    /// standard's]
+    #[arg(long, allow_negative_numbers = true)]
+    ceiling: Option<f64>,
+
+    /// Lower the gain to respect the ceiling instead of limiting peaks (may miss the target)
+    #[arg(long)]
+    no_limiter: bool,
+}
+
+impl Args {
+    /// The loudness target, unless normalizing to a sample peak
+    fn loudness_target(&self) -> Option<LoudnessTarget> {
+        let mut target = match (self.lufs, self.standard) {
+            (Some(lufs), _) => LoudnessTarget::new(lufs, -1.0),
+            (None, Some(standard)) => standard.target(),
+            (None, None) => return None,
+        };
+        if let Some(ceiling) = self.ceiling {
+            target.true_peak_dbtp = ceiling;
+        }
+        target.limiter = !self.no_limiter;
+        Some(target)
+    }
+}
+
+/// Per-file details for the `--json` report
+#[derive(Debug, Serialize)]
+struct NormalizeResult {
+    measured: LoudnessMeasurement,
+    gain_db: f64,
+    /// Most the limiter took off a peak, in dB
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limited_db: Option<f64>,
+    /// Levels after normalizing to a loudness target
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<LoudnessMeasurement>,
+}
+
+fn normalize_file(
+    item: &BatchItem,
+    args: &Args,
+    progress: &FileProgress,
+) -> anyhow::Result<NormalizeResult> {
+    let input = item.input.display().to_string();
+
+    let format = item
+        .output
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(OutputFormat::from_extension)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Cannot infer output format from '{}'; use a .wav, .flac, .mp3 or .opus name",
+                item.output.display()
+            )
+        })?;
+
+    // Step 1: Decode audio
+    info!(file = %input, "Decoding audio");
+    let start_time = std::time::Instant::now();
+    let audio = decode_audio_file_with_progress(&item.input, &mut progress.sink())?;
+
+    debug!(
+        file = %input,
+        sample_rate = audio.sample_rate,
+        channels = audio.channels,
+        duration_sec = audio.duration_seconds(),
+        decode_time_sec = start_time.elapsed().as_secs_f64(),
+        "Audio decoded"
+    );
+
+    // Steps 2 and 3: Analyze and apply gain
+    let (normalized, result) = match args.loudness_target() {
+        Some(target) => {
+            let (normalized, report) =
+                normalize_loudness_with_progress(&audio, &target, &mut progress.sink())?;
+            info!(
+                file = %input,
+                integrated_lufs = ?report.measured.integrated_lufs,
+                true_peak_dbtp = report.measured.true_peak_dbtp,
+                gain_db = report.gain_db,
+                limited_db = report.limited_db,
+                "Normalized loudness"
+            );
+            let reached = report.output.integrated_lufs.unwrap_or(f64::NEG_INFINITY);
+            if (reached - target.integrated_lufs).abs() > 0.5 {
+                warn!(
+                    file = %input,
+                    target_lufs = target.integrated_lufs,
+                    reached_lufs = reached,
+                    ceiling_dbtp = target.true_peak_dbtp,
+                    "Target missed; the peak ceiling held the gain back"
+                );
+            }
+            let result = NormalizeResult {
+                measured: report.measured,
+                gain_db: report.gain_db,
+                limited_db: Some(report.limited_db),
+                output: Some(report.output),
+            };
+            (normalized, result)
+        }
+        None => {
+            let measured = measure_loudness_with_progress(&audio, &mut progress.sink())?;
+            info!(
+                file = %input,
+                sample_peak_dbfs = measured.sample_peak_dbfs,
+                "Measured levels"
+            );
+            if measured.sample_peak == 0.0 {
+                anyhow::bail!("'{}' is silent; nothing to normalize", input);
+            }
+            let gain_db = args.peak.unwrap_or_default() - measured.sample_peak_dbfs;
+            info!(file = %input, gain_db, "Applying gain");
+            let result = NormalizeResult {
+                measured,
+                gain_db,
+                limited_db: None,
+                output: None,
+            };
+            (apply_gain(&audio, gain_db)?, result)
+        }
+    };
+
+    // Step 4: Encode
+    info!(file = %input, format = ?format, "Encoding");
+    let encode_start = std::time::Instant::now();
+    encode_audio_with_progress(&normalized, &item.output, &format, &mut progress.sink())?;
+
+    debug!(
+        file = %input,
+        encode_time_sec = encode_start.elapsed().as_secs_f64(),
+        "Encoding complete"
+    );
+
+    progress.finish();
+    info!(
+        output = %item.output.display(),
+        total_time_sec = start_time.elapsed().as_secs_f64(),
+        "Done! Output saved"
+    );
+
+    Ok(result)
+}
+
+fn main() -> ExitCode {
+    let args: Args = parse_args();
+    exit_with(run(args))
+}
+
+fn run(args: Args) -> anyhow::Result<()> {
+    let output = Output::init(&args.output);
+
+    let items = args.batch.plan("{stem}_normalized.{format}", &[("format", "wav")])?;
+    let results = WorkerPool::new(args.batch.jobs()).map(&items, |item| {
+        let progress = output.file_progress(item);
+        let _profile = Operation::start("normalize", &item.input);
+        normalize_file(item, &args, &progress)
+    });
+
+    output.finish_batch(&items, &results);
+
+    if let Some(err) = ExitError::from_batch(&results) {
+        return Err(err.into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use clap::Parser;
+use hermeneia_lib::audio::pipeline::{resample_file, ResampleSpec};
+use hermeneia_lib::audio::WavSampleFormat;
+use hermeneia_lib::cli::{
+    exit_with, parse_args, BatchArgs, BatchItem, ExitError, FileProgress, Output,
+    OutputArgs, EXIT_CODES_HELP,
+};
+use hermeneia_lib::pool::WorkerPool;
+use hermeneia_lib::profile::Operation;
+use serde::Serialize;
+use std::process::ExitCode;
+use tracing::info;
+
+/// Command-line tool for changing a file's sample rate, channels or bit depth
+#[derive(Parser, Debug)]
+#[command(name = "resample")]
+#[command(
+    about = "Write WAV copies of audio files at another sample rate, channel count or bit depth",
+    long_about = None
+)]
+#[command(after_help = EXIT_CODES_HELP)]
+struct Args {
+    #[command(flatten)]
+    batch: BatchArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// 16 kHz mono 16-bit, for speech recognition tools (other flags override parts of it)
+    #[arg(long)]
+    speech: bool,
+
+    /// Sample rate in Hz (e.g. 16000, 44100, 48000) [default: the input's]
+    #[arg(short = 'r', long)]
+    sample_rate: Option<u32>,
+
+    /// Mix down/up to this many channels (e.g. 1 for mono) [default: the input's]
+    #[arg(short, long)]
+    channels: Option<u16>,
+
+    /// Bits per sample: 16, 24 or 32 (float) [default: 32, or 16 with --speech]
+    #[arg(short, long)]
+    bit_depth: Option<u16>,
+}
+
+impl Args {
+    fn spec(&self) -> anyhow::Result<ResampleSpec> {
+        let mut spec = if self.speech {
+            ResampleSpec::speech()
+        } else {
+            ResampleSpec::default()
+        };
+        spec.sample_rate = self.sample_rate.or(spec.sample_rate);
+        spec.channels = self.channels.or(spec.channels);
+        if let Some(bit_depth) = self.bit_depth {
+            spec.sample_format = match bit_depth {
+                16 => WavSampleFormat::Pcm16,
+                24 => WavSampleFormat::Pcm24,
+                32 => WavSampleFormat::Float32,
+                other => anyhow::bail!(ExitError::usage(format!(
+                    "Bit depth must be 16, 24 or 32 (got {})",
+                    other
+                ))),
+            };
+        }
+        Ok(spec)
+    }
+}
+
+/// Per-file details for the `--json` report
+#[derive(Debug, Serialize)]
+struct ResampleResult {
+    duration_seconds: f64,
+    sample_rate: u32,
+    channels: u16,
+    bits_per_sample: u16,
+}
+
+fn resample(
+    item: &BatchItem,
+    spec: &ResampleSpec,
+    progress: &FileProgress,
+) -> anyhow::Result<ResampleResult> {
+    info!(file = %item.input.display(), "Resampling");
+    let start_time = std::time::Instant::now();
+    let summary = resample_file(&item.input, &item.output, spec, &mut progress.sink())?;
+
+    progress.finish();
+    info!(
+        output = %item.output.display(),
+        total_time_sec = start_time.elapsed().as_secs_f64(),
+        "Done! Output saved"
+    );
+
+    Ok(ResampleResult {
+        duration_seconds: summary.frames_out as f64 / summary.output.sample_rate as f64,
+        sample_rate: summary.output.sample_rate,
+        channels: summary.output.channels,
+        bits_per_sample: spec.sample_format.bits_per_sample(),
+    })
+}
+
+fn main() -> ExitCode {
+    let args: Args = parse_args();
+    exit_with(run(args))
+}
+
+fn run(args: Args) -> anyhow::Result<()> {
+    let output = Output::init(&args.output);
+    let spec = args.spec()?;
+
+    let items = args.batch.plan("{stem}_resampled.{format}", &[("format", "wav")])?;
+    let jobs = args.batch.jobs();
+    let results = WorkerPool::new(jobs).map(&items, |item| {
+        let progress = output.file_progress(item);
+        let _profile = Operation::start("resample", &item.input);
+        resample(item, &spec, &progress)
+    });
+
+    output.finish_batch(&items, &results);
+
+    if let Some(err) = ExitError::from_batch(&results) {
+        return Err(err.into());
+    }
+
+    Ok(())
+}
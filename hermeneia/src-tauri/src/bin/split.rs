@@ -0,0 +1,262 @@
+use clap::Parser;
+use hermeneia_lib::audio::{
+    decode_audio_file_with_progress, export_segments, read_chapters, read_cue_sheet,
+    segment_paths, split_at_markers, split_by_silence, split_every, OutputFormat, Segment,
+    SilenceOptions,
+};
+use hermeneia_lib::cli::{
+    exit_with, format_time, parse_args, parse_time, BatchArgs, BatchItem, ExitError,
+    FileProgress, FormatPreset, Output, OutputArgs, EXIT_CODES_HELP,
+};
+use hermeneia_lib::naming::{PathTemplate, TemplateFields};
+use hermeneia_lib::pool::WorkerPool;
+use hermeneia_lib::profile::Operation;
+use hermeneia_lib::settings::Settings;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use tracing::{debug, info};
+
+/// Command-line tool for splitting audio files into pieces
+#[derive(Parser, Debug)]
+#[command(name = "split")]
+#[command(
+    about = "Split audio files at silences, cue sheet tracks, chapters or into fixed-length pieces",
+    long_about = None
+)]
+#[command(after_help = EXIT_CODES_HELP)]
+#[command(group = clap::ArgGroup::new("mode").required(true))]
+struct Args {
+    /// Inputs, plus where the segment index goes (-o is the index JSON for a single input)
+    #[command(flatten)]
+    batch: BatchArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// Cut into pieces of this length (e.g. 600, 10m, 1h)
+    #[arg(long, value_parser = parse_time, group = "mode")]
+    every: Option<f64>,
+
+    /// Cut in the middle of each silence
+    #[arg(long, group = "mode")]
+    by_silence: bool,
+
+    /// Cut at the tracks of a cue sheet, or the chapters of another file
+    #[arg(long, value_name = "FILE", group = "mode")]
+    cue: Option<PathBuf>,
+
+    /// Cut at each input's own chapters
+    #[arg(long, group = "mode")]
+    chapters: bool,
+
+    /// Level in dBFS below which audio counts as silence (with --by-silence)
+    #[arg(long, default_value_t = -40.0, allow_negative_numbers = true)]
+    silence_threshold: f64,
+
+    /// Shortest silence to cut at (with --by-silence)
+    #[arg(long, value_parser = parse_time, default_value = "0.5")]
+    min_silence: f64,
+
+    /// Output format for the pieces
+    #[arg(short, long, value_enum, default_value = "wav32f")]
+    format: FormatPreset,
+
+    /// Bitrate in kbps for MP3/Opus
+    #[arg(long)]
+    bitrate: Option<u32>,
+
+    /// File name template for the pieces: {stem}, {ext}, {segment}, {title}, {album},
+    /// {artist} and {format} [default: {stem}_{segment}.{format}, or
+    /// {segment} {title}.{format} with --cue/--chapters]
+    #[arg(long)]
+    segment_template: Option<String>,
+}
+
+impl Args {
+    fn segment_template(&self) -> &str {
+        match &self.segment_template {
+            Some(template) => template,
+            None if self.cue.is_some() || self.chapters => "{segment} {title}.{format}",
+            None => "{stem}_{segment}.{format}",
+        }
+    }
+}
+
+/// The index JSON written next to the pieces
+#[derive(Debug, Serialize)]
+struct SplitIndex<'a> {
+    source: &'a Path,
+    sample_rate: u32,
+    channels: u16,
+    duration_seconds: f64,
+    /// Album title from the cue sheet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    /// Album performer from the cue sheet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    performer: Option<String>,
+    segments: Vec<IndexEntry>,
+}
+
+/// One piece in the index
+#[derive(Debug, Serialize)]
+struct IndexEntry {
+    #[serde(flatten)]
+    segment: Segment,
+    file: PathBuf,
+}
+
+/// Per-file details for the `--json` report
+#[derive(Debug, Serialize)]
+struct SplitResult {
+    segments: usize,
+    files: Vec<PathBuf>,
+}
+
+fn split_file(
+    item: &BatchItem,
+    args: &Args,
+    format: &OutputFormat,
+    progress: &FileProgress,
+) -> anyhow::Result<SplitResult> {
+    let input = item.input.display().to_string();
+    let dir = item.output.parent().unwrap_or(Path::new(""));
+
+    // Step 1: Decode audio
+    info!(file = %input, "Decoding audio");
+    let start_time = std::time::Instant::now();
+    let audio = decode_audio_file_with_progress(&item.input, &mut progress.sink())?;
+
+    debug!(
+        file = %input,
+        duration_sec = audio.duration_seconds(),
+        decode_time_sec = start_time.elapsed().as_secs_f64(),
+        "Audio decoded"
+    );
+
+    // Step 2: Plan the cuts
+    let duration = audio.duration_seconds();
+    let mut fields = TemplateFields::for_input(&item.input);
+    fields.set("format", format.extension());
+    let (mut title, mut performer) = (None, None);
+    let segments = if let Some(every) = args.every {
+        split_every(duration, every)?
+    } else if let Some(cue) = &args.cue {
+        let is_cue = cue.extension().is_some_and(|e| e.eq_ignore_ascii_case("cue"));
+        let markers = if is_cue {
+            let sheet = read_cue_sheet(cue)?;
+            (title, performer) = (sheet.title, sheet.performer);
+            sheet.tracks
+        } else {
+            read_chapters(cue)?
+        };
+        split_at_markers(&markers, duration)
+    } else if args.chapters {
+        split_at_markers(&read_chapters(&item.input)?, duration)
+    } else {
+        split_by_silence(
+            &audio,
+            &SilenceOptions {
+                threshold_db: args.silence_threshold,
+                min_duration_seconds: args.min_silence,
+            },
+        )?
+    };
+    if let Some(album) = &title {
+        fields.set("album", album.as_str());
+    }
+    if let Some(artist) = &performer {
+        fields.set("artist", artist.as_str());
+    }
+
+    if segments.is_empty() {
+        anyhow::bail!("'{}' has nothing to split (empty, entirely silent or no markers)", input);
+    }
+    info!(file = %input, segments = segments.len(), "Planned segments");
+
+    // Step 3: Write each piece
+    let template = PathTemplate::parse(args.segment_template())?;
+    let collisions = args.batch.on_collision.unwrap_or(Settings::load().output.collisions);
+    let paths = segment_paths(&segments, dir, &template, &fields, collisions)?;
+    // Pieces whose file exists are left out under --on-collision skip
+    let (segments, paths): (Vec<Segment>, Vec<PathBuf>) = segments
+        .into_iter()
+        .zip(paths)
+        .filter_map(|(segment, path)| Some((segment, path?)))
+        .unzip();
+    if paths.contains(&item.input) {
+        anyhow::bail!(ExitError::usage(format!(
+            "Segment would overwrite input '{}'",
+            input
+        )));
+    }
+    for segment in &segments {
+        debug!(
+            file = %input,
+            segment = segment.index,
+            start = %format_time(segment.start_seconds),
+            end = %format_time(segment.end_seconds),
+            "Planned segment"
+        );
+    }
+    export_segments(&audio, &segments, &paths, format, &mut progress.sink())?;
+
+    let entries = segments
+        .into_iter()
+        .zip(paths)
+        .map(|(segment, file)| IndexEntry { segment, file })
+        .collect();
+
+    // Step 4: Write the index
+    let index = SplitIndex {
+        source: &item.input,
+        sample_rate: audio.sample_rate,
+        channels: audio.channels,
+        duration_seconds: duration,
+        title,
+        performer,
+        segments: entries,
+    };
+    let file = std::fs::File::create(&item.output)?;
+    serde_json::to_writer_pretty(std::io::BufWriter::new(file), &index)?;
+
+    progress.finish();
+    info!(
+        output = %item.output.display(),
+        segments = index.segments.len(),
+        total_time_sec = start_time.elapsed().as_secs_f64(),
+        "Done! Index saved"
+    );
+
+    Ok(SplitResult {
+        segments: index.segments.len(),
+        files: index.segments.into_iter().map(|e| e.file).collect(),
+    })
+}
+
+fn main() -> ExitCode {
+    let args: Args = parse_args();
+    exit_with(run(args))
+}
+
+fn run(args: Args) -> anyhow::Result<()> {
+    let output = Output::init(&args.output);
+
+    let format = args.format.output_format(args.bitrate);
+
+    let items = args.batch.plan("{stem}_segments.{format}", &[("format", "json")])?;
+    let results = WorkerPool::new(args.batch.jobs()).map(&items, |item| {
+        let progress = output.file_progress(item);
+        let _profile = Operation::start("split", &item.input);
+        split_file(item, &args, &format, &progress)
+    });
+
+    output.finish_batch(&items, &results);
+
+    if let Some(err) = ExitError::from_batch(&results) {
+        return Err(err.into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,230 @@
+use clap::{Parser, ValueEnum};
+use hermeneia_lib::audio::{
+    extract_waveform_peaks_with_progress, read_chapters, render_waveform_png, write_waveform_svg,
+    Color, RenderOptions, Timestamp, WaveformOptions,
+};
+use hermeneia_lib::cli::{
+    exit_with, parse_args, parse_time, BatchArgs, BatchItem, ExitError, FileProgress, Output,
+    OutputArgs, EXIT_CODES_HELP,
+};
+use hermeneia_lib::pool::WorkerPool;
+use hermeneia_lib::profile::Operation;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use tracing::{debug, info};
+
+/// Output formats supported by the waveform tool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Peak data as JSON (min/max arrays plus metadata)
+    Json,
+    /// Rendered waveform image
+    Png,
+    /// Rendered waveform as scalable vector graphics
+    Svg,
+}
+
+impl OutputFormat {
+    /// Guess the format from the output file extension
+    fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "json" => Some(Self::Json),
+            "png" => Some(Self::Png),
+            "svg" => Some(Self::Svg),
+            _ => None,
+        }
+    }
+
+    /// File extension used for batch outputs
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Png => "png",
+            Self::Svg => "svg",
+        }
+    }
+}
+
+/// Command-line tool for exporting audio waveforms
+#[derive(Parser, Debug)]
+#[command(name = "waveform")]
+#[command(about = "Export waveform peaks as JSON or render them to a PNG/SVG image", long_about = None)]
+#[command(after_help = EXIT_CODES_HELP)]
+struct Args {
+    #[command(flatten)]
+    batch: BatchArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// Output format (inferred from --output, otherwise JSON)
+    #[arg(short, long, value_enum)]
+    format: Option<OutputFormat>,
+
+    /// Number of peaks to extract (defaults to the image width for images, 2000 for JSON)
+    #[arg(short = 'n', long)]
+    peaks: Option<usize>,
+
+    /// Image width in pixels
+    #[arg(long, default_value_t = 1800)]
+    width: u32,
+
+    /// Image height in pixels
+    #[arg(long, default_value_t = 280)]
+    height: u32,
+
+    /// Waveform color (#RRGGBB or #RRGGBBAA)
+    #[arg(long, default_value = "#4a90e2")]
+    color: Color,
+
+    /// Background color (#RRGGBB, #RRGGBBAA or "transparent")
+    #[arg(long, default_value = "#ffffff")]
+    background: Color,
+
+    /// Draw the input's chapters as marker lines on images
+    #[arg(long)]
+    chapters: bool,
+
+    /// Draw the tracks of this cue sheet as marker lines on images
+    #[arg(long, value_name = "CUE")]
+    markers: Option<PathBuf>,
+
+    /// Marker line color
+    #[arg(long, default_value = "#e24a4a")]
+    marker_color: Color,
+
+    /// Highlight a time range on images (e.g. --select 1:30 2:45)
+    #[arg(long, num_args = 2, value_names = ["START", "END"], value_parser = parse_time)]
+    select: Option<Vec<f64>>,
+
+    /// Highlight color for --select
+    #[arg(long, default_value = "#4a90e240")]
+    select_color: Color,
+}
+
+/// Per-file details for the `--json` report
+#[derive(Debug, Serialize)]
+struct WaveformResult {
+    num_peaks: usize,
+    duration_seconds: f64,
+    channels: u16,
+    sample_rate: u32,
+}
+
+fn export_waveform(
+    item: &BatchItem,
+    format: OutputFormat,
+    args: &Args,
+    progress: &FileProgress,
+) -> anyhow::Result<WaveformResult> {
+    let input = item.input.display().to_string();
+
+    // Images get one peak per pixel column unless told otherwise
+    let num_peaks = args.peaks.unwrap_or(match format {
+        OutputFormat::Json => 2000,
+        OutputFormat::Png | OutputFormat::Svg => args.width as usize,
+    });
+
+    // Step 1: Extract peaks
+    info!(file = %input, num_peaks, "Extracting waveform peaks");
+    let start_time = std::time::Instant::now();
+    let options = WaveformOptions::new().num_peaks(num_peaks);
+    let peaks = extract_waveform_peaks_with_progress(&item.input, &options, &mut progress.sink())?;
+
+    debug!(
+        file = %input,
+        duration_sec = peaks.duration_seconds,
+        channels = peaks.channels,
+        sample_rate = peaks.sample_rate,
+        extract_time_sec = start_time.elapsed().as_secs_f64(),
+        "Peaks extracted"
+    );
+
+    // Step 2: Write output
+    let mut markers = Vec::new();
+    if format != OutputFormat::Json {
+        if args.chapters {
+            markers.extend(read_chapters(&item.input)?);
+        }
+        if let Some(cue) = &args.markers {
+            markers.extend(read_chapters(cue)?);
+        }
+    }
+    let options = RenderOptions {
+        width: args.width,
+        height: args.height,
+        foreground: args.color,
+        background: args.background,
+        markers,
+        marker_color: args.marker_color,
+        selection: args.select.as_deref().map(|range| {
+            (Timestamp::from_seconds(range[0]), Timestamp::from_seconds(range[1]))
+        }),
+        selection_color: args.select_color,
+    };
+
+    progress.stage("write");
+    match format {
+        OutputFormat::Json => {
+            let file = std::fs::File::create(&item.output)?;
+            serde_json::to_writer_pretty(std::io::BufWriter::new(file), &peaks)?;
+        }
+        OutputFormat::Png => render_waveform_png(&peaks, &options, &item.output)?,
+        OutputFormat::Svg => write_waveform_svg(&peaks, &options, &item.output)?,
+    }
+
+    progress.finish();
+    info!(
+        output = %item.output.display(),
+        format = ?format,
+        total_time_sec = start_time.elapsed().as_secs_f64(),
+        "Done! Output saved"
+    );
+
+    Ok(WaveformResult {
+        num_peaks: peaks.num_peaks,
+        duration_seconds: peaks.duration_seconds,
+        channels: peaks.channels,
+        sample_rate: peaks.sample_rate,
+    })
+}
+
+fn main() -> ExitCode {
+    let args: Args = parse_args();
+    exit_with(run(args))
+}
+
+fn run(args: Args) -> anyhow::Result<()> {
+    let output = Output::init(&args.output);
+
+    let format = match (args.format, &args.batch.output) {
+        (Some(format), _) => format,
+        (None, Some(output)) => match OutputFormat::from_path(output) {
+            Some(format) => format,
+            None => anyhow::bail!(ExitError::usage(format!(
+                "Cannot infer output format from '{}'; pass --format json|png|svg",
+                output.display()
+            ))),
+        },
+        (None, None) => OutputFormat::Json,
+    };
+
+    let items = args
+        .batch
+        .plan("{stem}_waveform.{format}", &[("format", format.extension())])?;
+    let results = WorkerPool::new(args.batch.jobs()).map(&items, |item| {
+        let progress = output.file_progress(item);
+        let _profile = Operation::start("waveform", &item.input);
+        export_waveform(item, format, &args, &progress)
+    });
+
+    output.finish_batch(&items, &results);
+
+    if let Some(err) = ExitError::from_batch(&results) {
+        return Err(err.into());
+    }
+
+    Ok(())
+}
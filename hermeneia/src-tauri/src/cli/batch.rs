@@ -0,0 +1,375 @@
+// src-tauri/src/cli/batch.rs
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::audio::decoder::get_audio_info;
+use crate::error::{AudioError, Result};
+use crate::naming::{resolve_collision, CollisionPolicy, PathTemplate, TemplateFields};
+use crate::pool::worker_limit;
+use crate::settings::Settings;
+
+/// Input/output flags shared by every batch-capable CLI tool
+///
+/// Flatten into a tool's argument struct with `#[command(flatten)]`.
+/// A single input may be written to an explicit `--output` file; multiple
+/// inputs (or glob patterns) are written into `--output-dir` using
+/// `--name-template`, or the template saved in the app's settings.
+#[derive(clap::Args, Debug, Clone)]
+pub struct BatchArgs {
+    /// Input audio files or glob patterns (e.g. "recordings/*.mp3")
+    #[arg(short, long, required = true, num_args = 1..)]
+    pub input: Vec<String>,
+
+    /// Output file (only valid when a single input is given)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Directory for batch outputs (defaults to each input's directory)
+    #[arg(long)]
+    pub output_dir: Option<PathBuf>,
+
+    /// Output path template, may include folders: {stem}, {ext}, {format}, {index}, {dir},
+    /// {date}, {title}, {artist}, {album}, {speaker}, {lang} and tool-specific fields
+    #[arg(long)]
+    pub name_template: Option<String>,
+
+    /// What to do when an output already exists [default: from settings, else overwrite]
+    #[arg(long, value_enum)]
+    pub on_collision: Option<CollisionPolicy>,
+
+    /// Number of files to process in parallel [default: one per CPU core]
+    #[arg(short = 'j', long)]
+    pub jobs: Option<usize>,
+}
+
+/// One unit of batch work: an input file and where its result goes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchItem {
+    /// 1-based position in the batch (used for `{index}`)
+    pub index: usize,
+    pub input: PathBuf,
+    pub output: PathBuf,
+}
+
+impl BatchArgs {
+    /// Resolve inputs and compute an output path for each one
+    ///
+    /// Inputs whose output exists are left out under
+    /// [`CollisionPolicy::Skip`]; folders in the template are created.
+    ///
+    /// # Arguments
+    /// * `default_template` - Name template used when neither `--name-template`
+    ///   nor the settings give one
+    /// * `fields` - Extra template fields, e.g. `[("format", "mp3")]`
+    pub fn plan(&self, default_template: &str, fields: &[(&str, &str)]) -> Result<Vec<BatchItem>> {
+        let inputs = expand_inputs(&self.input)?;
+
+        if let Some(output) = &self.output {
+            if inputs.len() != 1 {
+                return Err(AudioError::InvalidParameter(format!(
+                    "--output takes a single file but {} inputs were given; use --output-dir instead",
+                    inputs.len()
+                )));
+            }
+            return Ok(vec![BatchItem {
+                index: 1,
+                input: inputs[0].clone(),
+                output: output.clone(),
+            }]);
+        }
+
+        let naming = Settings::load().output;
+        let template = self
+            .name_template
+            .as_deref()
+            .or(naming.template.as_deref())
+            .unwrap_or(default_template);
+        let template = PathTemplate::parse(template)?;
+        let policy = self.on_collision.unwrap_or(naming.collisions);
+        let mut seen = HashSet::new();
+        let mut items = Vec::with_capacity(inputs.len());
+
+        for (i, input) in inputs.into_iter().enumerate() {
+            let mut values = TemplateFields::for_input(&input);
+            if template.uses_tags() {
+                // An unreadable file fails later with a better error; name it
+                // without tags for now
+                if let Ok(info) = get_audio_info(&input) {
+                    values = values.with_tags(&info.tags);
+                }
+            }
+            values.set("index", (i + 1).to_string());
+            for (field, value) in fields {
+                values.set(field, *value);
+            }
+            let dir = match &self.output_dir {
+                Some(dir) => dir.clone(),
+                None => input.parent().map(Path::to_path_buf).unwrap_or_default(),
+            };
+            let output = dir.join(template.render(&values)?);
+
+            if output == input {
+                return Err(AudioError::InvalidParameter(format!(
+                    "Output would overwrite input '{}'; change --name-template or --output-dir",
+                    input.display()
+                )));
+            }
+            if policy == CollisionPolicy::Overwrite && seen.contains(&output) {
+                return Err(AudioError::InvalidParameter(format!(
+                    "Several inputs map to the same output '{}'; add {{index}} to --name-template",
+                    output.display()
+                )));
+            }
+            let Some(output) = resolve_collision(output, policy, &seen) else {
+                tracing::info!(file = %input.display(), "Skipping, output already exists");
+                continue;
+            };
+            seen.insert(output.clone());
+
+            items.push(BatchItem {
+                index: i + 1,
+                input,
+                output,
+            });
+        }
+
+        if let Some(dir) = &self.output_dir {
+            std::fs::create_dir_all(dir)?;
+        }
+        for item in &items {
+            if let Some(parent) = item.output.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// Effective worker count (at least 1); see [`worker_limit`]
+    pub fn jobs(&self) -> usize {
+        worker_limit(self.jobs)
+    }
+}
+
+/// Expand input arguments into a de-duplicated list of files
+///
+/// Arguments containing glob metacharacters (`*`, `?`, `[`) are expanded;
+/// anything else is taken as a literal path. Glob matches come back in
+/// alphabetical order. A pattern that matches no
+/// files is an error so typos don't silently produce an empty batch.
+pub fn expand_inputs<S: AsRef<str>>(inputs: &[S]) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for input in inputs {
+        let input = input.as_ref();
+
+        if !input.contains(['*', '?', '[']) {
+            files.push(PathBuf::from(input));
+            continue;
+        }
+
+        let matches = glob::glob(input).map_err(|e| {
+            AudioError::InvalidParameter(format!("Invalid glob pattern '{}': {}", input, e))
+        })?;
+
+        let before = files.len();
+        for entry in matches {
+            let path = entry.map_err(|e| AudioError::Io(e.into_error()))?;
+            if path.is_file() {
+                files.push(path);
+            }
+        }
+
+        if files.len() == before {
+            return Err(AudioError::InvalidParameter(format!(
+                "No files match '{}'",
+                input
+            )));
+        }
+    }
+
+    // Keep the order stable but drop files matched by several patterns
+    let mut seen = HashSet::new();
+    files.retain(|path| seen.insert(path.clone()));
+
+    Ok(files)
+}
+
+/// Render an output file name from a template
+///
+/// Takes the fields of a [`PathTemplate`], with `{index}` set to `index`
+/// and extra fields supplied by the tool. Unknown fields are rejected, and
+/// so are templates with folders.
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::cli::render_name_template;
+/// use std::path::Path;
+///
+/// let name = render_name_template("{stem}_trimmed.wav", Path::new("talks/intro.mp3"), 1, &[])
+///     .unwrap();
+/// assert_eq!(name, "intro_trimmed.wav");
+/// ```
+pub fn render_name_template(
+    template: &str,
+    input: &Path,
+    index: usize,
+    fields: &[(&str, &str)],
+) -> Result<String> {
+    let parsed = PathTemplate::parse(template)?;
+    let mut values = TemplateFields::for_input(input);
+    values.set("index", index.to_string());
+    for (field, value) in fields {
+        values.set(field, *value);
+    }
+    let rendered = parsed.render(&values)?;
+
+    match rendered.to_str() {
+        Some(name) if rendered.components().count() == 1 => Ok(name.to_string()),
+        _ => Err(AudioError::InvalidParameter(format!(
+            "Name template '{}' must produce a plain file name (got '{}')",
+            template,
+            rendered.display()
+        ))),
+    }
+}
+
+/// Log every failed batch item and return the number of failures
+pub fn report_failures<R, E: std::fmt::Display>(
+    items: &[BatchItem],
+    results: &[std::result::Result<R, E>],
+) -> usize {
+    let mut failed = 0;
+    for (item, result) in items.iter().zip(results) {
+        if let Err(e) = result {
+            failed += 1;
+            tracing::error!(file = %item.input.display(), error = %e, "Failed to process file");
+        }
+    }
+    failed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(inputs: &[&str]) -> BatchArgs {
+        BatchArgs {
+            input: inputs.iter().map(|s| s.to_string()).collect(),
+            output: None,
+            output_dir: None,
+            name_template: None,
+            on_collision: None,
+            jobs: Some(1),
+        }
+    }
+
+    #[test]
+    fn test_render_template_fields() {
+        let input = Path::new("/data/sermon.mp3");
+        let name = render_name_template("{index}-{stem}.{format}", input, 3, &[("format", "flac")])
+            .unwrap();
+        assert_eq!(name, "3-sermon.flac");
+
+        assert_eq!(
+            render_name_template("{stem}_copy.{ext}", input, 1, &[]).unwrap(),
+            "sermon_copy.mp3"
+        );
+    }
+
+    #[test]
+    fn test_render_template_errors() {
+        let input = Path::new("a.wav");
+        assert!(render_name_template("{nope}.wav", input, 1, &[]).is_err());
+        assert!(render_name_template("{stem.wav", input, 1, &[]).is_err());
+        assert!(render_name_template("sub/{stem}.wav", input, 1, &[]).is_err());
+    }
+
+    #[test]
+    fn test_expand_inputs_glob() {
+        let dir = std::env::temp_dir().join("hermeneia_test_batch_glob");
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["b.wav", "a.wav", "notes.txt"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+
+        let pattern = format!("{}/*.wav", dir.display());
+        let files = expand_inputs(&[pattern.as_str(), pattern.as_str()]).unwrap();
+        assert_eq!(files, vec![dir.join("a.wav"), dir.join("b.wav")]);
+
+        let missing = format!("{}/*.flac", dir.display());
+        assert!(expand_inputs(&[missing]).is_err());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_plan_single_output() {
+        let mut batch = args(&["in.mp3"]);
+        batch.output = Some(PathBuf::from("out.wav"));
+
+        let items = batch.plan("{stem}_trimmed.wav", &[]).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].output, PathBuf::from("out.wav"));
+    }
+
+    #[test]
+    fn test_plan_rejects_output_with_many_inputs() {
+        let mut batch = args(&["a.mp3", "b.mp3"]);
+        batch.output = Some(PathBuf::from("out.wav"));
+        assert!(batch.plan("{stem}.wav", &[]).is_err());
+    }
+
+    #[test]
+    fn test_plan_uses_template_and_detects_collisions() {
+        let mut batch = args(&["x/a.mp3", "y/b.mp3"]);
+        batch.output_dir = Some(std::env::temp_dir().join("hermeneia_test_batch_plan"));
+
+        let items = batch.plan("{stem}_trimmed.wav", &[]).unwrap();
+        assert_eq!(items[1].output.file_name().unwrap(), "b_trimmed.wav");
+        assert_eq!(items[1].index, 2);
+
+        // Same stem in different folders collides in a shared output dir
+        let batch = BatchArgs {
+            input: vec!["x/a.mp3".into(), "y/a.mp3".into()],
+            ..batch
+        };
+        assert!(batch.plan("{stem}.wav", &[]).is_err());
+        assert!(batch.plan("{index}_{stem}.wav", &[]).is_ok());
+
+        std::fs::remove_dir_all(std::env::temp_dir().join("hermeneia_test_batch_plan")).ok();
+    }
+
+    #[test]
+    fn test_plan_creates_folders_and_applies_collision_policy() {
+        let dir = std::env::temp_dir().join("hermeneia_test_batch_naming");
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("talk.wav");
+        std::fs::write(&input, b"").unwrap();
+
+        let mut batch = args(&[input.to_str().unwrap()]);
+        batch.output_dir = Some(dir.join("out"));
+        batch.name_template = Some("{dir}/{stem}.{format}".to_string());
+        let planned = |batch: &BatchArgs| batch.plan("{stem}.mp3", &[("format", "mp3")]).unwrap();
+
+        let items = planned(&batch);
+        let expected = dir.join("out").join("hermeneia_test_batch_naming").join("talk.mp3");
+        assert_eq!(items[0].output, expected);
+        assert!(expected.parent().unwrap().is_dir());
+        std::fs::write(&expected, b"").unwrap();
+
+        batch.on_collision = Some(CollisionPolicy::Suffix);
+        assert_eq!(planned(&batch)[0].output.file_name().unwrap(), "talk_2.mp3");
+        batch.on_collision = Some(CollisionPolicy::Skip);
+        assert!(planned(&batch).is_empty());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_plan_refuses_to_overwrite_input() {
+        let batch = args(&["dir/a.wav"]);
+        assert!(batch.plan("{stem}.wav", &[]).is_err());
+    }
+}
@@ -0,0 +1,39 @@
+// src-tauri/src/cli/completions.rs
+
+use clap::Parser;
+use clap_complete::Shell;
+
+/// Parse a tool's arguments, handling `--completions <shell>` first
+///
+/// Every tool has required arguments (`--input`, a mode flag, ...), which
+/// would make `tool --completions bash` fail before the flag is ever seen.
+/// The command line is therefore checked for `--completions` with errors
+/// ignored; if present, the completion script is printed to stdout and the
+/// process exits. Otherwise this is the same as `A::parse()`.
+///
+/// # Example
+/// ```no_run
+/// # use clap::Parser;
+/// # #[derive(Parser)]
+/// # struct Args {
+/// #     #[command(flatten)]
+/// #     output: hermeneia_lib::cli::OutputArgs,
+/// # }
+/// let args: Args = hermeneia_lib::cli::parse_args();
+/// ```
+pub fn parse_args<A: Parser>() -> A {
+    let requested = A::command()
+        .ignore_errors(true)
+        .try_get_matches()
+        .ok()
+        .and_then(|matches| matches.get_one::<Shell>("completions").copied());
+
+    if let Some(shell) = requested {
+        let mut command = A::command();
+        let name = command.get_name().to_string();
+        clap_complete::generate(shell, &mut command, name, &mut std::io::stdout());
+        std::process::exit(0);
+    }
+
+    A::parse()
+}
@@ -0,0 +1,221 @@
+// src-tauri/src/cli/exit.rs
+
+use std::fmt;
+use std::process::ExitCode;
+
+use crate::error::{AnalysisError, AudioError};
+
+/// Exit codes shared by every CLI tool
+///
+/// | Code | Meaning                                                        |
+/// |------|----------------------------------------------------------------|
+/// | 0    | Success                                                        |
+/// | 1    | Any other failure                                              |
+/// | 2    | Bad arguments (unknown flag, out-of-range value, bad trim)     |
+/// | 3    | An input could not be opened, recognised or decoded            |
+/// | 4    | An output could not be encoded or written                      |
+/// | 5    | Batch run where some files succeeded and others failed         |
+/// | 6    | The check ran but failed (e.g. `compare` found differences)    |
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    Success = 0,
+    Failure = 1,
+    Usage = 2,
+    Input = 3,
+    Output = 4,
+    Partial = 5,
+    CheckFailed = 6,
+}
+
+/// The table above as plain text, for each tool's `--help` footer
+pub const EXIT_CODES_HELP: &str = "\
+Exit codes:
+  0  success
+  1  other failure
+  2  bad arguments
+  3  input could not be opened or decoded
+  4  output could not be encoded or written
+  5  some files in the batch failed
+  6  check failed (e.g. compare found differences)";
+
+impl ExitStatus {
+    /// Pick the exit status for an error returned from a tool's `run`
+    ///
+    /// The first [`ExitError`], [`AudioError`] or I/O error found in the
+    /// error chain decides; anything else is a plain [`ExitStatus::Failure`].
+    pub fn of(error: &anyhow::Error) -> Self {
+        for cause in error.chain() {
+            if let Some(exit) = cause.downcast_ref::<ExitError>() {
+                return exit.status;
+            }
+            if let Some(audio) = cause.downcast_ref::<AudioError>() {
+                return Self::from_audio_error(audio);
+            }
+            if cause.is::<std::io::Error>() {
+                // Inputs are opened through the decoder (AudioError::FileOpen),
+                // so bare I/O errors come from writing results
+                return Self::Output;
+            }
+        }
+        Self::Failure
+    }
+
+    fn from_audio_error(error: &AudioError) -> Self {
+        match error {
+            AudioError::FileOpen { .. }
+            | AudioError::UnsupportedFormat(_)
+            | AudioError::Decode(_)
+            | AudioError::Download { .. }
+            | AudioError::InvalidFeed(_)
+            | AudioError::Analysis(AnalysisError::EmptyFormat { .. })
+            | AudioError::Analysis(AnalysisError::Unmeasurable)
+            | AudioError::Analysis(AnalysisError::ModelDamaged { .. }) => Self::Input,
+            AudioError::Encode(_)
+            | AudioError::RenderFailed(_)
+            | AudioError::Io(_)
+            | AudioError::Storage(_) => Self::Output,
+            AudioError::InvalidParameter(_)
+            | AudioError::InvalidTrimParams(_)
+            | AudioError::TrimRangeOutOfBounds { .. }
+            | AudioError::Analysis(AnalysisError::FormatMismatch { .. }) => Self::Usage,
+            AudioError::ResampleFailed(_)
+            | AudioError::MemoryBudgetExceeded { .. }
+            | AudioError::Playback(_)
+            | AudioError::Cancelled
+            | AudioError::Analysis(AnalysisError::Engine(_)) => Self::Failure,
+        }
+    }
+}
+
+impl From<ExitStatus> for ExitCode {
+    fn from(status: ExitStatus) -> Self {
+        ExitCode::from(status as u8)
+    }
+}
+
+/// An error that carries its own exit status
+#[derive(Debug)]
+pub struct ExitError {
+    pub status: ExitStatus,
+    pub message: String,
+}
+
+impl ExitError {
+    pub fn new(status: ExitStatus, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    /// Bad-arguments error for checks a tool does itself
+    pub fn usage(message: impl Into<String>) -> Self {
+        Self::new(ExitStatus::Usage, message)
+    }
+
+    /// Summarize a finished batch, or `None` if every file succeeded
+    ///
+    /// A partly failed batch exits with [`ExitStatus::Partial`]. When every
+    /// file failed for the same kind of reason that reason's status is used,
+    /// so a single missing input still exits with [`ExitStatus::Input`].
+    pub fn from_batch<R>(results: &[anyhow::Result<R>]) -> Option<Self> {
+        let failed = results.iter().filter(|r| r.is_err()).count();
+        if failed == 0 {
+            return None;
+        }
+
+        let status = if failed < results.len() {
+            ExitStatus::Partial
+        } else {
+            let mut statuses = results
+                .iter()
+                .filter_map(|r| r.as_ref().err())
+                .map(ExitStatus::of);
+            let first = statuses.next().unwrap_or(ExitStatus::Failure);
+            if statuses.all(|s| s == first) {
+                first
+            } else {
+                ExitStatus::Failure
+            }
+        };
+
+        Some(Self::new(
+            status,
+            format!("{} of {} file(s) failed", failed, results.len()),
+        ))
+    }
+}
+
+impl fmt::Display for ExitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ExitError {}
+
+/// Report the outcome of a tool's `run` and turn it into the process exit code
+///
+/// Errors are printed to stderr the same way `fn main() -> anyhow::Result<()>`
+/// prints them.
+pub fn exit_with(result: anyhow::Result<()>) -> ExitCode {
+    match result {
+        Ok(()) => ExitStatus::Success.into(),
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            ExitStatus::of(&e).into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{DecodeError, EncodeError};
+
+    #[test]
+    fn test_status_from_error_chain() {
+        let open = anyhow::Error::from(AudioError::FileOpen {
+            path: "a.wav".to_string(),
+            source: std::io::Error::from(std::io::ErrorKind::NotFound),
+        });
+        assert_eq!(ExitStatus::of(&open), ExitStatus::Input);
+        assert_eq!(
+            ExitStatus::of(&open.context("while trimming")),
+            ExitStatus::Input
+        );
+
+        let encode = anyhow::Error::from(AudioError::from(EncodeError::codec("WAV", "disk full")));
+        assert_eq!(ExitStatus::of(&encode), ExitStatus::Output);
+
+        let bad = anyhow::Error::from(AudioError::InvalidParameter("x".to_string()));
+        assert_eq!(ExitStatus::of(&bad), ExitStatus::Usage);
+
+        assert_eq!(
+            ExitStatus::of(&anyhow::anyhow!("something else")),
+            ExitStatus::Failure
+        );
+        assert_eq!(
+            ExitStatus::of(&ExitError::new(ExitStatus::CheckFailed, "differ").into()),
+            ExitStatus::CheckFailed
+        );
+    }
+
+    #[test]
+    fn test_status_from_batch() {
+        let decode = || anyhow::Error::from(AudioError::from(DecodeError::Packet("bad".to_string())));
+        let encode = || anyhow::Error::from(AudioError::from(EncodeError::codec("WAV", "bad")));
+
+        assert!(ExitError::from_batch(&[Ok(()), Ok(())]).is_none());
+
+        let partial = ExitError::from_batch(&[Ok(()), Err(decode())]).unwrap();
+        assert_eq!(partial.status, ExitStatus::Partial);
+        assert_eq!(partial.message, "1 of 2 file(s) failed");
+
+        let same = ExitError::from_batch::<()>(&[Err(decode()), Err(decode())]).unwrap();
+        assert_eq!(same.status, ExitStatus::Input);
+
+        let mixed = ExitError::from_batch::<()>(&[Err(decode()), Err(encode())]).unwrap();
+        assert_eq!(mixed.status, ExitStatus::Failure);
+    }
+}
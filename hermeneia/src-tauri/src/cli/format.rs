@@ -0,0 +1,99 @@
+// src-tauri/src/cli/format.rs
+
+use std::path::Path;
+
+use crate::audio::{OutputFormat, WavSampleFormat, DEFAULT_FLAC_COMPRESSION};
+
+/// Output format names accepted by `--format`
+///
+/// The WAV variants carry their sample format in the name so a single flag
+/// covers everything the encoders can write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum FormatPreset {
+    /// 16-bit PCM WAV
+    Wav16,
+    /// 24-bit PCM WAV
+    Wav24,
+    /// 32-bit float WAV
+    Wav32f,
+    /// 24-bit FLAC
+    Flac,
+    /// MP3 (192 kbps unless --bitrate is given)
+    Mp3,
+    /// Ogg Opus (64 kbps unless --bitrate is given)
+    Opus,
+}
+
+impl FormatPreset {
+    /// Guess a preset from an output file extension (WAV defaults to 32-bit float)
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?;
+        Some(match OutputFormat::from_extension(ext)? {
+            OutputFormat::Wav { .. } => Self::Wav32f,
+            OutputFormat::Flac { .. } => Self::Flac,
+            OutputFormat::Mp3 { .. } => Self::Mp3,
+            OutputFormat::Opus { .. } => Self::Opus,
+        })
+    }
+
+    /// Concrete encoder settings, with an optional bitrate override for lossy formats
+    pub fn output_format(self, bitrate_kbps: Option<u32>) -> OutputFormat {
+        match self {
+            Self::Wav16 => OutputFormat::Wav {
+                sample_format: WavSampleFormat::Pcm16,
+            },
+            Self::Wav24 => OutputFormat::Wav {
+                sample_format: WavSampleFormat::Pcm24,
+            },
+            Self::Wav32f => OutputFormat::Wav {
+                sample_format: WavSampleFormat::Float32,
+            },
+            Self::Flac => OutputFormat::Flac {
+                bits_per_sample: 24,
+                compression_level: DEFAULT_FLAC_COMPRESSION,
+            },
+            Self::Mp3 => OutputFormat::Mp3 {
+                bitrate_kbps: bitrate_kbps.unwrap_or(192),
+            },
+            Self::Opus => OutputFormat::Opus {
+                bitrate_kbps: bitrate_kbps.unwrap_or(64),
+                speech: false,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_path() {
+        assert_eq!(FormatPreset::from_path(Path::new("a/b.WAV")), Some(FormatPreset::Wav32f));
+        assert_eq!(FormatPreset::from_path(Path::new("b.ogg")), Some(FormatPreset::Opus));
+        assert_eq!(FormatPreset::from_path(Path::new("b.m4a")), None);
+        assert_eq!(FormatPreset::from_path(Path::new("noext")), None);
+    }
+
+    #[test]
+    fn test_output_format() {
+        assert_eq!(
+            FormatPreset::Wav16.output_format(None),
+            OutputFormat::Wav {
+                sample_format: WavSampleFormat::Pcm16
+            }
+        );
+        assert_eq!(
+            FormatPreset::Mp3.output_format(Some(320)),
+            OutputFormat::Mp3 { bitrate_kbps: 320 }
+        );
+        // Bitrate is meaningless for lossless formats
+        assert_eq!(
+            FormatPreset::Flac.output_format(Some(320)),
+            OutputFormat::Flac {
+                bits_per_sample: 24,
+                compression_level: DEFAULT_FLAC_COMPRESSION,
+            }
+        );
+    }
+}
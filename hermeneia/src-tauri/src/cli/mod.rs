@@ -0,0 +1,19 @@
+// src-tauri/src/cli/mod.rs
+// Shared helpers for the command-line tools in src/bin
+
+pub mod batch;
+pub mod completions;
+pub mod exit;
+pub mod format;
+pub mod output;
+pub mod time;
+
+// Re-export commonly used items
+pub use batch::{
+    expand_inputs, render_name_template, report_failures, BatchArgs, BatchItem,
+};
+pub use completions::parse_args;
+pub use exit::{exit_with, ExitError, ExitStatus, EXIT_CODES_HELP};
+pub use format::FormatPreset;
+pub use output::{BatchReport, FileProgress, FileReport, Output, OutputArgs};
+pub use time::{format_time, parse_time};
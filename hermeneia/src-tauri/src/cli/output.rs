@@ -0,0 +1,390 @@
+// src-tauri/src/cli/output.rs
+
+use std::fmt::Display;
+use std::io::{IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use serde::Serialize;
+
+use crate::cli::batch::{report_failures, BatchItem};
+use crate::notify::{self, JobEvent, NotificationSettings};
+use crate::progress::ProgressSink;
+
+/// Verbosity and output-mode flags shared by every CLI tool
+///
+/// Flatten into a tool's argument struct with `#[command(flatten)]`.
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct OutputArgs {
+    /// Only print errors (no progress bars or info logs)
+    #[arg(short, long, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Show detailed information
+    #[arg(short, long)]
+    pub verbose: bool,
+
+    /// Print a machine-readable JSON report to stdout when done
+    #[arg(long)]
+    pub json: bool,
+
+    /// Show a desktop notification when the batch finishes
+    #[arg(long)]
+    pub notify: bool,
+
+    /// POST a JSON summary of the batch to URL when it finishes (repeatable)
+    #[arg(long, value_name = "URL")]
+    pub webhook: Vec<String>,
+
+    /// Print a shell completion script to stdout and exit
+    #[arg(long, value_enum, value_name = "SHELL", exclusive = true)]
+    pub completions: Option<clap_complete::Shell>,
+}
+
+/// Terminal output for a CLI run: logging, progress bars and the final report
+///
+/// Logs always go to stderr so `--json` output on stdout stays clean. While
+/// progress bars are visible, log lines are printed above them instead of
+/// tearing through.
+pub struct Output {
+    multi: MultiProgress,
+    show_progress: bool,
+    json: bool,
+    notifications: NotificationSettings,
+    started: Instant,
+}
+
+impl Output {
+    /// Set up logging for the run and decide whether progress bars are shown
+    ///
+    /// Bars are hidden with `--quiet`, with `--json`, or when stderr isn't a
+    /// terminal (e.g. output redirected to a log file). `RUST_LOG` still
+    /// overrides the level picked from the flags.
+    pub fn init(args: &OutputArgs) -> Self {
+        let show_progress = !args.quiet && !args.json && std::io::stderr().is_terminal();
+        let multi = MultiProgress::with_draw_target(if show_progress {
+            ProgressDrawTarget::stderr()
+        } else {
+            ProgressDrawTarget::hidden()
+        });
+
+        // flacenc logs encoder statistics at info level; keep those out of normal runs
+        let filter = if args.quiet {
+            "error"
+        } else if args.verbose {
+            "debug"
+        } else if args.json {
+            "warn"
+        } else {
+            "info,flacenc=warn"
+        };
+
+        let writer_multi = multi.clone();
+        tracing_subscriber::fmt()
+            .with_env_filter(
+                tracing_subscriber::EnvFilter::try_from_default_env()
+                    .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(filter)),
+            )
+            .with_writer(move || LogWriter {
+                multi: writer_multi.clone(),
+                buffer: Vec::new(),
+            })
+            .init();
+
+        Self {
+            multi,
+            show_progress,
+            json: args.json,
+            notifications: NotificationSettings {
+                desktop: args.notify,
+                webhooks: args.webhook.clone(),
+                min_seconds: 0.0,
+                ..Default::default()
+            },
+            started: Instant::now(),
+        }
+    }
+
+    /// Add a progress bar for one file of the batch
+    pub fn file_progress(&self, item: &BatchItem) -> FileProgress {
+        if !self.show_progress {
+            return FileProgress {
+                bar: ProgressBar::hidden(),
+            };
+        }
+
+        let name = item
+            .input
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| item.input.display().to_string());
+
+        let bar = self.multi.add(ProgressBar::new(PROGRESS_STEPS));
+        bar.set_style(
+            ProgressStyle::with_template("{prefix:.bold} {msg:>9} [{bar:30.cyan/blue}] {percent:>3}%")
+                .expect("progress template is valid")
+                .progress_chars("=> "),
+        );
+        bar.set_prefix(name);
+        bar.enable_steady_tick(Duration::from_millis(200));
+
+        FileProgress { bar }
+    }
+
+    /// Log failures, with `--json` print the batch report to stdout, and
+    /// with `--notify` or `--webhook` announce the batch's end
+    ///
+    /// # Returns
+    /// Number of failed items
+    pub fn finish_batch<R, E>(
+        &self,
+        items: &[BatchItem],
+        results: &[std::result::Result<R, E>],
+    ) -> usize
+    where
+        R: Serialize,
+        E: Display,
+    {
+        let failed = report_failures(items, results);
+
+        if self.json {
+            let report = BatchReport::new(items, results);
+            let stdout = std::io::stdout();
+            let mut stdout = stdout.lock();
+            if serde_json::to_writer_pretty(&mut stdout, &report).is_ok() {
+                writeln!(stdout).ok();
+            }
+        }
+
+        let event = batch_event(items, results, failed).took(self.started.elapsed());
+        notify::notify(&self.notifications, &event);
+
+        failed
+    }
+}
+
+/// Summary of a batch for notifications, named after the running tool
+fn batch_event<R, E: Display>(
+    items: &[BatchItem],
+    results: &[std::result::Result<R, E>],
+    failed: usize,
+) -> JobEvent {
+    let job = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "hermeneia".to_string());
+    let outputs = items
+        .iter()
+        .zip(results)
+        .filter(|(_, result)| result.is_ok())
+        .map(|(item, _)| item.output.display().to_string())
+        .collect();
+    let error = results
+        .iter()
+        .find_map(|result| result.as_ref().err())
+        .map(|e| format!("{:#}", e));
+    let mut event = JobEvent::new(
+        &job,
+        items.iter().map(|item| item.input.display().to_string()).collect(),
+        outputs,
+        error,
+    );
+    event.failed = failed;
+    event
+}
+
+/// Resolution of the per-file bars (0.1%)
+const PROGRESS_STEPS: u64 = 1000;
+
+/// Progress bar for one file, moved through named stages (decode, encode, ...)
+pub struct FileProgress {
+    bar: ProgressBar,
+}
+
+impl FileProgress {
+    /// Start a new stage and reset the bar to 0%
+    pub fn stage(&self, name: &'static str) {
+        self.bar.set_message(name);
+        self.bar.set_position(0);
+    }
+
+    /// Set progress within the current stage (0.0 to 1.0)
+    pub fn set_fraction(&self, fraction: f64) {
+        self.bar
+            .set_position((fraction.clamp(0.0, 1.0) * PROGRESS_STEPS as f64) as u64);
+    }
+
+    /// Sink for the `*_with_progress` library functions, which announce
+    /// their own stages
+    pub fn sink(&self) -> impl ProgressSink + '_ {
+        self
+    }
+
+    /// Remove the bar once the file is done
+    pub fn finish(&self) {
+        self.bar.finish_and_clear();
+    }
+}
+
+impl Drop for FileProgress {
+    fn drop(&mut self) {
+        // Failed files return early; don't leave a frozen bar behind
+        if !self.bar.is_finished() {
+            self.bar.finish_and_clear();
+        }
+    }
+}
+
+impl ProgressSink for &FileProgress {
+    fn stage(&mut self, name: &'static str) {
+        FileProgress::stage(self, name);
+    }
+
+    fn progress(&mut self, fraction: f64) {
+        self.set_fraction(fraction);
+    }
+
+    fn message(&mut self, message: &str) {
+        self.bar.set_message(message.to_string());
+    }
+}
+
+/// `--json` report for a batch run
+#[derive(Debug, Serialize)]
+pub struct BatchReport<'a, R: Serialize> {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub files: Vec<FileReport<'a, R>>,
+}
+
+/// One entry of a [`BatchReport`]; `result` holds the tool-specific details
+#[derive(Debug, Serialize)]
+pub struct FileReport<'a, R: Serialize> {
+    pub input: &'a std::path::Path,
+    pub output: &'a std::path::Path,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<&'a R>,
+}
+
+impl<'a, R: Serialize> BatchReport<'a, R> {
+    /// Pair each item with its outcome
+    pub fn new<E: Display>(items: &'a [BatchItem], results: &'a [std::result::Result<R, E>]) -> Self {
+        let files: Vec<FileReport<'a, R>> = items
+            .iter()
+            .zip(results)
+            .map(|(item, result)| FileReport {
+                input: &item.input,
+                output: &item.output,
+                ok: result.is_ok(),
+                error: result.as_ref().err().map(|e| format!("{:#}", e)),
+                result: result.as_ref().ok(),
+            })
+            .collect();
+        let failed = files.iter().filter(|f| !f.ok).count();
+
+        Self {
+            succeeded: files.len() - failed,
+            failed,
+            files,
+        }
+    }
+}
+
+/// Buffers one formatted log event and prints it above any progress bars
+struct LogWriter {
+    multi: MultiProgress,
+    buffer: Vec<u8>,
+}
+
+impl Write for LogWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for LogWriter {
+    fn drop(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        let buffer = std::mem::take(&mut self.buffer);
+        self.multi.suspend(|| {
+            std::io::stderr().write_all(&buffer).ok();
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_batch_report_json() {
+        let items = vec![
+            BatchItem {
+                index: 1,
+                input: PathBuf::from("a.wav"),
+                output: PathBuf::from("a.mp3"),
+            },
+            BatchItem {
+                index: 2,
+                input: PathBuf::from("b.wav"),
+                output: PathBuf::from("b.mp3"),
+            },
+        ];
+        let results: Vec<std::result::Result<u32, String>> = vec![Ok(7), Err("boom".to_string())];
+
+        let report = serde_json::to_value(BatchReport::new(&items, &results)).unwrap();
+        assert_eq!(report["succeeded"], 1);
+        assert_eq!(report["failed"], 1);
+        assert_eq!(report["files"][0]["input"], "a.wav");
+        assert_eq!(report["files"][0]["result"], 7);
+        assert!(report["files"][0].get("error").is_none());
+        assert_eq!(report["files"][1]["ok"], false);
+        assert_eq!(report["files"][1]["error"], "boom");
+        assert!(report["files"][1].get("result").is_none());
+    }
+
+    #[test]
+    fn test_batch_event_lists_what_was_written() {
+        let item = |index, name: &str| BatchItem {
+            index,
+            input: PathBuf::from(format!("{}.wav", name)),
+            output: PathBuf::from(format!("{}.mp3", name)),
+        };
+        let items = vec![item(1, "a"), item(2, "b"), item(3, "c")];
+        let results: Vec<std::result::Result<(), String>> =
+            vec![Ok(()), Err("boom".to_string()), Ok(())];
+
+        let event = batch_event(&items, &results, 1);
+        assert_eq!(event.outcome, notify::JobOutcome::Failed);
+        assert_eq!(event.inputs, ["a.wav", "b.wav", "c.wav"]);
+        assert_eq!(event.outputs, ["a.mp3", "c.mp3"]);
+        assert_eq!((event.failed, event.error.as_deref()), (1, Some("boom")));
+
+        let all_ok: Vec<std::result::Result<(), String>> = vec![Ok(()); 3];
+        assert_eq!(batch_event(&items, &all_ok, 0).outcome, notify::JobOutcome::Completed);
+    }
+
+    #[test]
+    fn test_hidden_progress_is_inert() {
+        let progress = FileProgress {
+            bar: ProgressBar::hidden(),
+        };
+        let mut sink = progress.sink();
+        sink.stage("decode");
+        sink.progress(0.5);
+        sink.progress(2.0);
+        sink.message("window 1 of 2");
+        progress.finish();
+    }
+}
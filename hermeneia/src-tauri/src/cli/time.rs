@@ -0,0 +1,184 @@
+// src-tauri/src/cli/time.rs
+
+/// Parse a time value typed on the command line into seconds
+///
+/// Accepted forms:
+/// - Plain seconds: `90`, `5025.5`
+/// - Clock style: `MM:SS`, `HH:MM:SS`, each optionally with a fraction (`1:23:45.5`)
+/// - Unit suffixes: `500ms`, `45s`, `10m`, `1h30m`, `1h23m45.5s`
+///
+/// Returns a `String` error so it can be used directly as a clap `value_parser`.
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::cli::parse_time;
+///
+/// assert_eq!(parse_time("1:23:45.5"), Ok(5025.5));
+/// assert_eq!(parse_time("02:30"), Ok(150.0));
+/// assert_eq!(parse_time("10m"), Ok(600.0));
+/// ```
+pub fn parse_time(value: &str) -> Result<f64, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err("time value is empty".to_string());
+    }
+
+    let seconds = if value.contains(':') {
+        parse_clock(value)?
+    } else if value.ends_with(|c: char| c.is_ascii_alphabetic()) {
+        parse_units(value)?
+    } else {
+        parse_number(value, value)?
+    };
+
+    if !seconds.is_finite() || seconds < 0.0 {
+        return Err(format!("'{}' is not a valid non-negative time", value));
+    }
+
+    Ok(seconds)
+}
+
+/// Format seconds as `H:MM:SS.mmm` (or `M:SS.mmm` under an hour) for log output
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::cli::format_time;
+///
+/// assert_eq!(format_time(5025.5), "1:23:45.500");
+/// assert_eq!(format_time(65.25), "1:05.250");
+/// ```
+pub fn format_time(seconds: f64) -> String {
+    let total_ms = (seconds.max(0.0) * 1000.0).round() as u64;
+    let ms = total_ms % 1000;
+    let total_secs = total_ms / 1000;
+    let (h, m, s) = (total_secs / 3600, (total_secs / 60) % 60, total_secs % 60);
+
+    if h > 0 {
+        format!("{}:{:02}:{:02}.{:03}", h, m, s, ms)
+    } else {
+        format!("{}:{:02}.{:03}", m, s, ms)
+    }
+}
+
+/// `MM:SS[.fff]` or `HH:MM:SS[.fff]`
+fn parse_clock(value: &str) -> Result<f64, String> {
+    let parts: Vec<&str> = value.split(':').collect();
+    if parts.len() > 3 {
+        return Err(format!("'{}' has too many ':' fields (expected MM:SS or HH:MM:SS)", value));
+    }
+
+    let (last, leading) = parts.split_last().expect("split always yields one part");
+    let seconds = parse_number(last, value)?;
+    if seconds >= 60.0 {
+        return Err(format!("seconds field in '{}' must be below 60", value));
+    }
+
+    let mut total = seconds;
+    for (i, part) in leading.iter().rev().enumerate() {
+        if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("'{}' is not a valid time (bad field '{}')", value, part));
+        }
+        let field: u64 = part
+            .parse()
+            .map_err(|_| format!("'{}' is not a valid time", value))?;
+        // Minutes are bounded when hours are present; the leading field is not
+        if i == 0 && leading.len() == 2 && field >= 60 {
+            return Err(format!("minutes field in '{}' must be below 60", value));
+        }
+        total += field as f64 * 60f64.powi(i as i32 + 1);
+    }
+
+    Ok(total)
+}
+
+/// `1h23m45.5s`, `10m`, `500ms`
+fn parse_units(value: &str) -> Result<f64, String> {
+    let mut total = 0.0;
+    let mut rest = value;
+
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .ok_or_else(|| format!("'{}' is missing a unit after the last number", value))?;
+        if number_len == 0 {
+            return Err(format!("'{}' is not a valid time", value));
+        }
+        let number = parse_number(&rest[..number_len], value)?;
+        rest = &rest[number_len..];
+
+        let unit_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let scale = match &rest[..unit_len] {
+            "h" => 3600.0,
+            "m" | "min" => 60.0,
+            "s" => 1.0,
+            "ms" => 0.001,
+            unit => {
+                return Err(format!(
+                    "unknown unit '{}' in '{}' (use h, m, s or ms)",
+                    unit, value
+                ))
+            }
+        };
+        rest = &rest[unit_len..];
+        total += number * scale;
+    }
+
+    Ok(total)
+}
+
+fn parse_number(text: &str, whole: &str) -> Result<f64, String> {
+    if text.is_empty() || !text.chars().all(|c| c.is_ascii_digit() || c == '.') {
+        return Err(format!("'{}' is not a valid time", whole));
+    }
+    text.parse()
+        .map_err(|_| format!("'{}' is not a valid time", whole))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_seconds() {
+        assert_eq!(parse_time("0"), Ok(0.0));
+        assert_eq!(parse_time("5025.5"), Ok(5025.5));
+        assert_eq!(parse_time(" 12 "), Ok(12.0));
+    }
+
+    #[test]
+    fn test_clock_formats() {
+        assert_eq!(parse_time("02:30"), Ok(150.0));
+        assert_eq!(parse_time("1:23:45.5"), Ok(5025.5));
+        assert_eq!(parse_time("01:23:45.500"), Ok(5025.5));
+        assert_eq!(parse_time("0:00.25"), Ok(0.25));
+        // A leading minutes field may exceed 59
+        assert_eq!(parse_time("90:00"), Ok(5400.0));
+    }
+
+    #[test]
+    fn test_unit_suffixes() {
+        assert_eq!(parse_time("45s"), Ok(45.0));
+        assert_eq!(parse_time("10m"), Ok(600.0));
+        assert_eq!(parse_time("1h30m"), Ok(5400.0));
+        assert_eq!(parse_time("1h23m45.5s"), Ok(5025.5));
+        assert_eq!(parse_time("500ms"), Ok(0.5));
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        for bad in ["", "-5", "abc", "1:2:3:4", "1:60", "1:60:00", "5x", "1h30", "1::2", "1.2.3"] {
+            assert!(parse_time(bad).is_err(), "'{}' should be rejected", bad);
+        }
+    }
+
+    #[test]
+    fn test_format_round_trip() {
+        assert_eq!(format_time(0.0), "0:00.000");
+        assert_eq!(format_time(3600.0), "1:00:00.000");
+        for seconds in [0.5, 59.999, 754.125, 5025.5] {
+            assert_eq!(parse_time(&format_time(seconds)), Ok(seconds));
+        }
+    }
+}
@@ -0,0 +1,1736 @@
+// src-tauri/src/commands.rs
+// Tauri commands and app setup, built with the `tauri` feature
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use crate::error::AudioError;
+use crate::i18n::{self, Message};
+use crate::progress::ProgressSink;
+use crate::{
+    audio, gpu, ingest, jobs, memory, models, naming, notify, pacing, playback, power, profile,
+    runtime, schedule, settings, speakers, storage, subtitles, transcribe, transcript_diff,
+    translation,
+};
+
+/// Event carrying a [`ProgressEvent`] while a long command runs
+pub const PROGRESS_EVENT: &str = "progress";
+
+/// Least time between two progress events for the same operation
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How `export_segments` names pieces when neither the call nor the saved
+/// output template says
+const SEGMENT_TEMPLATE: &str = "{segment} {title}.{format}";
+
+/// Payload of [`PROGRESS_EVENT`]
+#[derive(Debug, Clone, Serialize)]
+struct ProgressEvent<'a> {
+    /// Which command is reporting, e.g. "waveform_peaks"
+    operation: &'static str,
+    stage: &'static str,
+    /// Translatable name of `stage`
+    label: Message,
+    fraction: f64,
+    message: Option<&'a str>,
+}
+
+/// Forwards library progress to the frontend as [`PROGRESS_EVENT`]s
+///
+/// Fractions are throttled to one event per [`PROGRESS_INTERVAL`]; stage
+/// changes, messages and completion are always sent.
+struct EventProgress {
+    app: tauri::AppHandle,
+    operation: &'static str,
+    stage: &'static str,
+    fraction: f64,
+    last_emit: Option<Instant>,
+}
+
+impl EventProgress {
+    fn new(app: tauri::AppHandle, operation: &'static str) -> Self {
+        Self {
+            app,
+            operation,
+            stage: "",
+            fraction: 0.0,
+            last_emit: None,
+        }
+    }
+
+    fn emit(&mut self, message: Option<&str>) {
+        self.last_emit = Some(Instant::now());
+        let event = ProgressEvent {
+            operation: self.operation,
+            stage: self.stage,
+            label: i18n::stage_label(self.stage),
+            fraction: self.fraction,
+            message,
+        };
+        let _ = self.app.emit(PROGRESS_EVENT, event);
+    }
+}
+
+impl ProgressSink for EventProgress {
+    fn stage(&mut self, name: &'static str) {
+        self.stage = name;
+        self.fraction = 0.0;
+        self.emit(None);
+    }
+
+    fn progress(&mut self, fraction: f64) {
+        self.fraction = fraction;
+        let due = self.last_emit.is_none_or(|at| at.elapsed() >= PROGRESS_INTERVAL);
+        if due || fraction >= 1.0 {
+            self.emit(None);
+        }
+    }
+
+    fn message(&mut self, message: &str) {
+        self.emit(Some(message));
+    }
+}
+
+/// Announces a long command's end as [`notify::NotificationSettings`] ask
+struct JobNotice {
+    job: &'static str,
+    input: String,
+    started: Instant,
+}
+
+impl JobNotice {
+    fn start(job: &'static str, input: &str) -> Self {
+        Self {
+            job,
+            input: input.to_string(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Record the outcome in the history, notify in the background and pass
+    /// `result` through; `outputs` names the files a successful run wrote
+    fn finish<T, E: Into<Message>>(
+        self,
+        result: std::result::Result<T, E>,
+        outputs: impl FnOnce(&T) -> Vec<String>,
+    ) -> std::result::Result<T, Message> {
+        let result = result.map_err(Into::into);
+        let (outputs, error) = match &result {
+            Ok(value) => (outputs(value), None),
+            Err(message) => (Vec::new(), Some(message.text.clone())),
+        };
+        let event = notify::JobEvent::new(self.job, vec![self.input], outputs, error)
+            .took(self.started.elapsed());
+        storage::record_job_quietly(&event);
+        notify::notify_in_background(settings::Settings::load().notifications, event);
+        result
+    }
+}
+
+// Commands fail with an `i18n::Message`, so the frontend can show errors in
+// the user's language and fall back to the English text.
+
+// Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+#[tauri::command]
+fn greet(name: &str) -> String {
+    format!("Hello, {}! You've been greeted from Rust!", name)
+}
+
+/// Extract waveform peaks from an audio file for visualization
+///
+/// Tauri command that processes audio files and returns peak data
+/// for displaying waveforms in the frontend.
+///
+/// # Arguments
+/// * `file_path` - Path to the audio file
+/// * `options` - Peak count, RMS, per-channel, dB scale and time range;
+///   every field is optional (defaults in [`audio::WaveformOptions`])
+///
+/// # Returns
+/// WaveformPeaks as a binary buffer (layout in [`WaveformPeaks::to_bytes`]),
+/// which the frontend reads as an `ArrayBuffer` instead of parsing JSON
+///
+/// Runs off the main thread and reports progress as [`PROGRESS_EVENT`]s
+/// with operation "waveform_peaks".
+#[tauri::command(async)]
+fn get_waveform_peaks(
+    app: tauri::AppHandle,
+    file_path: String,
+    options: Option<audio::WaveformOptions>,
+) -> std::result::Result<tauri::ipc::Response, Message> {
+    let _profile = profile::Operation::start("waveform_peaks", &file_path);
+    let mut progress = EventProgress::new(app, "waveform_peaks");
+    let options = options.unwrap_or_default();
+    let peaks = audio::extract_waveform_peaks_with_progress(&file_path, &options, &mut progress)?;
+    let _stage = profile::stage("serialize");
+    Ok(tauri::ipc::Response::new(peaks.to_bytes()))
+}
+
+/// Event carrying a [`WaveformStreamEvent`] while `stream_waveform_peaks`
+/// runs
+pub const WAVEFORM_STREAM_EVENT: &str = "waveform-stream";
+
+/// Payload of [`WAVEFORM_STREAM_EVENT`]; `id` is the stream's, as
+/// `stream_waveform_peaks` returned it
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum WaveformStreamEvent {
+    /// The next run of peaks, with the share of the file done
+    Chunk {
+        id: u64,
+        #[serde(flatten)]
+        chunk: audio::PeakChunk,
+    },
+    /// Every chunk has been sent
+    Done { id: u64 },
+    /// Stopped early, cancelled or on an error; no more chunks follow
+    Failed { id: u64, error: Message },
+}
+
+/// Waveform streams still running, by id
+fn waveform_streams() -> &'static runtime::CancelRegistry {
+    static STREAMS: OnceLock<runtime::CancelRegistry> = OnceLock::new();
+    STREAMS.get_or_init(runtime::CancelRegistry::new)
+}
+
+/// Extract a file's waveform in the background, sending the peaks as
+/// [`WAVEFORM_STREAM_EVENT`]s as they're done
+///
+/// Returns at once with the stream's id, for matching its events and for
+/// `cancel_waveform_stream`. The chunks fill the waveform in from the
+/// start and end with a `done` or `failed` event.
+#[tauri::command]
+fn stream_waveform_peaks(
+    app: tauri::AppHandle,
+    file_path: String,
+    options: Option<audio::WaveformOptions>,
+) -> u64 {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let mut registration = waveform_streams().register(id);
+    let options = options.unwrap_or_default();
+    std::thread::spawn(move || {
+        let _profile = profile::Operation::start("waveform_peaks", &file_path);
+        let result =
+            audio::stream_waveform_peaks(&file_path, &options, &mut registration, &mut |chunk| {
+                let _ = app.emit(WAVEFORM_STREAM_EVENT, WaveformStreamEvent::Chunk { id, chunk });
+            });
+        drop(registration);
+        let event = match result {
+            Ok(_) => WaveformStreamEvent::Done { id },
+            Err(e) => WaveformStreamEvent::Failed { id, error: e.into() },
+        };
+        let _ = app.emit(WAVEFORM_STREAM_EVENT, event);
+    });
+    id
+}
+
+/// Stop a waveform stream; it ends with a `failed` event
+///
+/// # Returns
+/// `false` if the stream had already finished
+#[tauri::command]
+fn cancel_waveform_stream(id: u64) -> bool {
+    waveform_streams().cancel(id)
+}
+
+/// Everything the file header shows: duration, format, container, codec
+/// profile, bitrate, size and a summary of the embedded tags
+///
+/// Reads only the file's headers, and nothing at all for a file probed
+/// earlier in the session.
+#[tauri::command(async)]
+fn get_audio_info(file_path: String) -> std::result::Result<audio::AudioInfo, Message> {
+    Ok(audio::get_audio_info(&file_path)?)
+}
+
+/// Render a file's waveform to a PNG or SVG image, chosen by the output
+/// extension, with optional markers and a highlighted selection
+///
+/// Extracts one peak per pixel column. Reports progress as
+/// [`PROGRESS_EVENT`]s with operation "waveform_image".
+#[tauri::command(async)]
+fn render_waveform_image(
+    app: tauri::AppHandle,
+    file_path: String,
+    output_path: String,
+    options: Option<audio::RenderOptions>,
+) -> std::result::Result<(), Message> {
+    let _profile = profile::Operation::start("waveform_image", &file_path);
+    let mut progress = EventProgress::new(app, "waveform_image");
+    let options = options.unwrap_or_default();
+    let peaks_options = audio::WaveformOptions::new().num_peaks(options.width.max(1) as usize);
+    let peaks =
+        audio::extract_waveform_peaks_with_progress(&file_path, &peaks_options, &mut progress)?;
+    let _stage = profile::stage("render");
+    Ok(audio::write_waveform_image(&peaks, &options, &output_path)?)
+}
+
+/// Chapters of an audio file, the tracks of a cue sheet or the labels of
+/// an Audacity label track (`.txt`), as markers
+#[tauri::command(async)]
+fn read_markers(file_path: String) -> std::result::Result<Vec<audio::Marker>, Message> {
+    Ok(audio::read_chapters(&file_path)?)
+}
+
+/// Save markers as an Audacity label track
+#[tauri::command(async)]
+fn export_audacity_labels(
+    output_path: String,
+    markers: Vec<audio::Marker>,
+) -> std::result::Result<(), Message> {
+    Ok(audio::write_audacity_labels(&output_path, &markers)?)
+}
+
+/// Save a transcript as an Audacity label track, one label per segment
+#[tauri::command(async)]
+fn export_transcript_labels(
+    output_path: String,
+    segments: Vec<transcribe::Segment>,
+) -> std::result::Result<(), Message> {
+    let markers: Vec<audio::Marker> = segments.iter().map(transcribe::Segment::to_marker).collect();
+    Ok(audio::write_audacity_labels(&output_path, &markers)?)
+}
+
+/// Read an Audacity label track back as transcript segments, e.g. after
+/// correcting the text or timing in Audacity
+#[tauri::command(async)]
+fn import_transcript_labels(
+    file_path: String,
+) -> std::result::Result<Vec<transcribe::Segment>, Message> {
+    let markers = audio::read_audacity_labels(&file_path)?;
+    Ok(markers.iter().map(transcribe::Segment::from_marker).collect())
+}
+
+/// Export one file per marker, e.g. the editor's markers or a cue sheet's
+/// tracks
+///
+/// `template` names the pieces inside `output_dir` (see
+/// [`audio::segment_paths`]); without one the saved output template is
+/// used if it tells pieces apart (`{segment}`, `{index}` or `{title}`),
+/// else `{segment} {title}.{format}`. Pieces whose file exists follow the
+/// saved collision policy. Files over the memory budget, and every file in
+/// low-memory mode, are decoded one piece at a time. Reports progress as
+/// [`PROGRESS_EVENT`]s with operation "export_segments".
+///
+/// # Returns
+/// The files written, in timeline order; skipped pieces are left out
+#[tauri::command(async)]
+fn export_segments(
+    app: tauri::AppHandle,
+    file_path: String,
+    markers: Vec<audio::Marker>,
+    output_dir: String,
+    format: audio::OutputFormat,
+    template: Option<String>,
+) -> std::result::Result<Vec<String>, Message> {
+    let _profile = profile::Operation::start("export_segments", &file_path);
+    let notice = JobNotice::start("export_segments", &file_path);
+    let mut progress = EventProgress::new(app, "export_segments");
+    let naming = settings::Settings::load().output;
+    let result = (|| -> std::result::Result<Vec<String>, Message> {
+        let saved = naming.template.as_deref().map(naming::PathTemplate::parse).transpose()?;
+        let saved = saved.filter(|t| ["segment", "index", "title"].iter().any(|f| t.uses(f)));
+        let template = match template {
+            Some(template) => naming::PathTemplate::parse(&template)?,
+            None => saved.map_or_else(|| naming::PathTemplate::parse(SEGMENT_TEMPLATE), Ok)?,
+        };
+        let info = audio::get_audio_info(&file_path)?;
+        let required =
+            memory::decoded_size_bytes(info.duration_seconds, info.sample_rate, info.channels);
+        // Without a length up front the file has to be decoded to split it
+        let streaming = info.duration_seconds > 0.0
+            && memory::MemoryBudget::current().mode_for(required)
+                == memory::ProcessingMode::Streaming;
+        let decoded = if streaming {
+            None
+        } else {
+            Some(audio::decode_audio_file_with_progress(&file_path, &mut progress)?)
+        };
+        let duration = decoded.as_ref().map_or(info.duration_seconds, |a| a.duration_seconds());
+
+        let segments = audio::split_at_markers(&markers, duration);
+        let mut fields = naming::TemplateFields::for_input(std::path::Path::new(&file_path));
+        if template.uses_tags() {
+            fields = fields.with_tags(&info.tags);
+        }
+        fields.set("format", format.extension());
+        let dir = output_dir.as_ref();
+        let paths = audio::segment_paths(&segments, dir, &template, &fields, naming.collisions)?;
+        let (segments, paths): (Vec<_>, Vec<_>) = segments
+            .into_iter()
+            .zip(paths)
+            .filter_map(|(segment, path)| Some((segment, path?)))
+            .unzip();
+        match &decoded {
+            Some(audio) => {
+                audio::export_segments(audio, &segments, &paths, &format, &mut progress)?
+            }
+            None => {
+                audio::export_file_segments(&file_path, &segments, &paths, &format, &mut progress)?
+            }
+        }
+        Ok(paths.iter().map(|p| p.display().to_string()).collect())
+    })();
+    notice.finish(result, Clone::clone)
+}
+
+/// Built-in export presets followed by the user's own
+#[tauri::command]
+fn list_export_presets() -> Vec<audio::ExportPreset> {
+    let mut presets = audio::ExportPreset::builtin();
+    presets.extend(settings::Settings::load().export_presets);
+    presets
+}
+
+/// Save a preset of the user's, replacing any with the same name
+#[tauri::command]
+fn save_export_preset(preset: audio::ExportPreset) -> std::result::Result<(), Message> {
+    settings::update(|settings| {
+        match settings.export_presets.iter_mut().find(|p| p.name == preset.name) {
+            Some(existing) => *existing = preset,
+            None => settings.export_presets.push(preset),
+        }
+    })
+    .map_err(AudioError::from)?;
+    Ok(())
+}
+
+/// Remove one of the user's presets
+#[tauri::command]
+fn delete_export_preset(name: String) -> std::result::Result<(), Message> {
+    settings::update(|settings| settings.export_presets.retain(|p| p.name != name))
+        .map_err(AudioError::from)?;
+    Ok(())
+}
+
+/// Export a file with a preset, normalizing its loudness first if the
+/// preset has a target
+///
+/// Plain WAV exports stream when the file is over the memory budget or
+/// low-memory mode is on; see [`audio::export_file`]. Reports progress as
+/// [`PROGRESS_EVENT`]s with operation "export".
+///
+/// # Returns
+/// The measurements before and after normalizing, for presets that do
+#[tauri::command(async)]
+fn export_audio(
+    app: tauri::AppHandle,
+    file_path: String,
+    output_path: String,
+    preset: audio::ExportPreset,
+) -> std::result::Result<Option<audio::NormalizeReport>, Message> {
+    let _profile = profile::Operation::start("export", &file_path);
+    let notice = JobNotice::start("export", &file_path);
+    let mut progress = EventProgress::new(app, "export");
+    let budget = memory::MemoryBudget::current();
+    let result = audio::export_file(&file_path, &output_path, &preset, &budget, &mut progress);
+    notice.finish(result, |_| vec![output_path.clone()])
+}
+
+/// Cut the range `params` keeps out of a file into `output_path`
+///
+/// Seeks to the range instead of decoding the whole file, so trimming a
+/// short clip from a long recording is quick. Reports progress as
+/// [`PROGRESS_EVENT`]s with operation "trim".
+///
+/// # Returns
+/// The resolved range and the frames written
+#[tauri::command(async)]
+fn trim_audio_file(
+    app: tauri::AppHandle,
+    file_path: String,
+    output_path: String,
+    params: audio::TrimParams,
+    format: audio::OutputFormat,
+) -> std::result::Result<audio::TrimSummary, Message> {
+    let _profile = profile::Operation::start("trim", &file_path);
+    let notice = JobNotice::start("trim", &file_path);
+    let mut progress = EventProgress::new(app, "trim");
+    let budget = memory::MemoryBudget::current();
+    let result =
+        audio::trim_file(&file_path, &output_path, &params, &format, &budget, &mut progress);
+    notice.finish(result, |_| vec![output_path.clone()])
+}
+
+/// Cut several ranges out of a file and join them, in the order given,
+/// into `output_path`
+///
+/// Seeks to each range like `trim_audio_file`. Reports progress as
+/// [`PROGRESS_EVENT`]s with operation "join".
+///
+/// # Returns
+/// The resolved range and frames written for each segment
+#[tauri::command(async)]
+fn join_audio_segments(
+    app: tauri::AppHandle,
+    file_path: String,
+    output_path: String,
+    segments: Vec<audio::TrimParams>,
+    format: audio::OutputFormat,
+) -> std::result::Result<Vec<audio::TrimSummary>, Message> {
+    let _profile = profile::Operation::start("join", &file_path);
+    let notice = JobNotice::start("join", &file_path);
+    let mut progress = EventProgress::new(app, "join");
+    let budget = memory::MemoryBudget::current();
+    let result = audio::join_file_segments(
+        &file_path,
+        &output_path,
+        &segments,
+        &format,
+        &budget,
+        &mut progress,
+    );
+    notice.finish(result, |_| vec![output_path.clone()])
+}
+
+/// Write a WAV copy of a file at another sample rate, channel count or
+/// bit depth, e.g. 16 kHz mono for speech recognition tools
+///
+/// Streams, so long recordings don't need to fit in memory. Reports
+/// progress as [`PROGRESS_EVENT`]s with operation "resample".
+///
+/// # Returns
+/// The output format and frame counts
+#[tauri::command(async)]
+fn resample_file(
+    app: tauri::AppHandle,
+    file_path: String,
+    output_path: String,
+    spec: audio::pipeline::ResampleSpec,
+) -> std::result::Result<audio::pipeline::PipelineSummary, Message> {
+    let _profile = profile::Operation::start("resample", &file_path);
+    let notice = JobNotice::start("resample", &file_path);
+    let mut progress = EventProgress::new(app, "resample");
+    let result = audio::pipeline::resample_file(&file_path, &output_path, &spec, &mut progress);
+    notice.finish(result, |_| vec![output_path.clone()])
+}
+
+/// Write a copy of a file with its background noise turned down
+///
+/// `strength` runs from 0.0 (unchanged) to 1.0 (full reduction). Streams,
+/// so long recordings don't need to fit in memory. Reports progress as
+/// [`PROGRESS_EVENT`]s with operation "denoise".
+///
+/// # Returns
+/// The output format and frame counts
+#[tauri::command(async)]
+fn denoise_file(
+    app: tauri::AppHandle,
+    file_path: String,
+    output_path: String,
+    strength: f32,
+    format: audio::OutputFormat,
+) -> std::result::Result<audio::pipeline::PipelineSummary, Message> {
+    let _profile = profile::Operation::start("denoise", &file_path);
+    let notice = JobNotice::start("denoise", &file_path);
+    let mut progress = EventProgress::new(app, "denoise");
+    let result =
+        audio::pipeline::denoise_file(&file_path, &output_path, strength, &format, &mut progress);
+    notice.finish(result, |_| vec![output_path.clone()])
+}
+
+/// Render an edit list of a file to `output_path`, leaving the file itself
+/// as it was
+///
+/// Seeks to each kept range rather than decoding the whole file. Reports
+/// progress as [`PROGRESS_EVENT`]s with operation "render_edits".
+#[tauri::command(async)]
+fn render_edit_list(
+    app: tauri::AppHandle,
+    file_path: String,
+    edits: audio::EditList,
+    output_path: String,
+    format: audio::OutputFormat,
+) -> std::result::Result<audio::pipeline::PipelineSummary, Message> {
+    let _profile = profile::Operation::start("render_edits", &file_path);
+    let notice = JobNotice::start("render_edits", &file_path);
+    let mut progress = EventProgress::new(app, "render_edits");
+    let result =
+        audio::render_edit_list(&file_path, &edits, &output_path, &format, &mut progress);
+    notice.finish(result, |_| vec![output_path.clone()])
+}
+
+/// Write a copy of a file with time ranges silenced or bleeped, and an
+/// audit log of what was removed beside it
+///
+/// # Arguments
+/// * `ranges` - What to remove; each marker's title is logged as the reason
+///
+/// Reports progress as [`PROGRESS_EVENT`]s with operation "redact".
+#[tauri::command(async)]
+fn redact_file(
+    app: tauri::AppHandle,
+    file_path: String,
+    output_path: String,
+    ranges: Vec<audio::Marker>,
+    format: audio::OutputFormat,
+    options: Option<audio::RedactOptions>,
+) -> std::result::Result<audio::RedactionLog, Message> {
+    let _profile = profile::Operation::start("redact", &file_path);
+    let notice = JobNotice::start("redact", &file_path);
+    let mut progress = EventProgress::new(app, "redact");
+    let options = options.unwrap_or_default();
+    let result =
+        audio::redact_file(&file_path, &output_path, &format, &ranges, &options, &mut progress);
+    notice.finish(result, |log| {
+        let log_path = audio::redaction_log_path(std::path::Path::new(&log.output));
+        vec![log.output.clone(), log_path.display().to_string()]
+    })
+}
+
+/// Score a processed file against its original with segmental SNR and
+/// spectral distance
+///
+/// The candidate is remixed and resampled to the reference's format first
+/// if they differ. Reports progress as [`PROGRESS_EVENT`]s with operation
+/// "measure_quality".
+#[tauri::command(async)]
+fn measure_quality(
+    app: tauri::AppHandle,
+    reference_path: String,
+    candidate_path: String,
+    options: Option<audio::QualityOptions>,
+) -> std::result::Result<audio::QualityReport, Message> {
+    let _profile = profile::Operation::start("measure_quality", &candidate_path);
+    let mut progress = EventProgress::new(app, "measure_quality");
+    let reference = audio::decode_audio_file_with_progress(&reference_path, &mut progress)?;
+    let mut candidate = audio::decode_audio_file_with_progress(&candidate_path, &mut progress)?;
+    if candidate.channels != reference.channels {
+        candidate = audio::remix_channels(&candidate, reference.channels)?;
+    }
+    if candidate.sample_rate != reference.sample_rate {
+        candidate = audio::resample_audio(&candidate, reference.sample_rate)?;
+    }
+    Ok(audio::measure_quality_with_progress(
+        &reference,
+        &candidate,
+        &options.unwrap_or_default(),
+        &mut progress,
+    )?)
+}
+
+/// Stage timings (probe, decode, analyze, encode, ...) of the last operation
+///
+/// For attaching to slow-file reports.
+#[tauri::command]
+fn get_last_operation_profile() -> Option<profile::OperationProfile> {
+    profile::last_profile()
+}
+
+/// Report the machine's GPUs and available compute backends
+///
+/// Tauri command for the settings screen. Runs on the async runtime
+/// because detection spawns helper processes (`nvidia-smi`, `vulkaninfo`, ...).
+///
+/// # Returns
+/// GpuInfo as JSON with the adapter list and detected backends
+#[tauri::command(async)]
+fn get_gpu_info() -> gpu::GpuInfo {
+    gpu::query_gpu_info()
+}
+
+/// Report whether inference can run on a GPU, and on which device
+///
+/// Tauri command for the transcription engine and the settings screen.
+/// Runs on the async runtime because the first call spawns `nvidia-smi`,
+/// `rocminfo` and `vulkaninfo`; later calls use the cached results.
+#[tauri::command(async)]
+fn get_inference_capabilities() -> gpu::InferenceCapabilities {
+    gpu::inference_capabilities(&settings::Settings::load().gpu)
+}
+
+/// Read the saved GPU choices for the settings screen
+#[tauri::command]
+fn get_gpu_preference() -> gpu::GpuPreference {
+    settings::Settings::load().gpu
+}
+
+/// Save the user's GPU choices
+///
+/// The render GPU takes effect at the next start; the inference choice the
+/// next time the engine initializes.
+#[tauri::command]
+fn set_gpu_preference(preference: gpu::GpuPreference) -> std::result::Result<(), Message> {
+    settings::update(|settings| settings.gpu = preference).map_err(AudioError::from)?;
+    Ok(())
+}
+
+/// Report the power state and whether batch work is being throttled
+///
+/// The same payload is pushed as a `power-status` event whenever it changes.
+#[tauri::command(async)]
+fn get_power_status() -> power::PowerStatus {
+    power::PowerStatus::new(power::query_power_state(), settings::Settings::load().power)
+}
+
+/// Save how batch work reacts to battery power
+#[tauri::command]
+fn set_power_mode(mode: power::PowerMode) -> std::result::Result<(), Message> {
+    settings::update(|settings| settings.power = mode).map_err(AudioError::from)?;
+    Ok(())
+}
+
+/// Whether low-memory mode is on, and the machine's memory and budget
+#[tauri::command(async)]
+fn get_memory_status() -> memory::MemoryStatus {
+    memory::MemoryStatus::from_settings(&settings::Settings::load())
+}
+
+/// Turn low-memory mode on or off, or back to following the machine
+/// (`None`), resizing the caches straight away
+#[tauri::command]
+fn set_low_memory(enabled: Option<bool>) -> std::result::Result<memory::MemoryStatus, Message> {
+    let settings =
+        settings::update(|settings| settings.low_memory = enabled).map_err(AudioError::from)?;
+    memory::apply_memory_mode(&settings);
+    Ok(memory::MemoryStatus::from_settings(&settings))
+}
+
+/// Read the saved time windows and idle rule for queued heavy work
+#[tauri::command]
+fn get_schedule() -> schedule::Schedule {
+    settings::Settings::load().schedule
+}
+
+/// Save when queued heavy work may run; takes effect at the next poll
+#[tauri::command]
+fn set_schedule(schedule: schedule::Schedule) -> std::result::Result<(), Message> {
+    settings::update(|settings| settings.schedule = schedule).map_err(AudioError::from)?;
+    Ok(())
+}
+
+/// Whether queued work may run now, and if not when the next window opens
+#[tauri::command]
+fn get_schedule_status() -> schedule::ScheduleStatus {
+    settings::Settings::load().schedule.current_status()
+}
+
+/// Read who gets told when long jobs finish
+#[tauri::command]
+fn get_notification_settings() -> notify::NotificationSettings {
+    settings::Settings::load().notifications
+}
+
+/// Save the desktop notification and webhook settings
+#[tauri::command]
+fn set_notification_settings(
+    notifications: notify::NotificationSettings,
+) -> std::result::Result<(), Message> {
+    settings::update(|settings| settings.notifications = notifications).map_err(AudioError::from)?;
+    Ok(())
+}
+
+/// Send a sample notification through each channel in `notifications`,
+/// so the user can check a webhook URL before relying on it
+///
+/// Unlike real notifications, failures are returned.
+#[tauri::command(async)]
+fn send_test_notification(
+    notifications: notify::NotificationSettings,
+) -> std::result::Result<(), Message> {
+    let event = notify::JobEvent::new("test", vec!["sample.wav".to_string()], Vec::new(), None);
+    if notifications.desktop {
+        let (title, body) = event.describe();
+        notify::show_desktop_notification(&title, &body)?;
+    }
+    for url in &notifications.webhooks {
+        notify::post_webhook(url, &event)?;
+    }
+    Ok(())
+}
+
+/// Read the saved output path template and collision policy
+#[tauri::command]
+fn get_output_naming() -> naming::OutputNaming {
+    settings::Settings::load().output
+}
+
+/// Save how outputs are named; an invalid template is rejected
+#[tauri::command]
+fn set_output_naming(output: naming::OutputNaming) -> std::result::Result<(), Message> {
+    if let Some(template) = &output.template {
+        naming::PathTemplate::parse(template)?;
+    }
+    settings::update(|settings| settings.output = output).map_err(AudioError::from)?;
+    Ok(())
+}
+
+/// Fetch a podcast feed and list its episodes
+#[tauri::command(async)]
+fn fetch_podcast_feed(url: String) -> std::result::Result<ingest::Feed, Message> {
+    Ok(ingest::fetch_feed(&url)?)
+}
+
+/// Download episodes of a feed into the library
+///
+/// With `transcribe`, each file is also added to the transcription queue.
+///
+/// # Returns
+/// Where each episode was saved, in the order of `guids`
+#[tauri::command(async)]
+fn download_podcast_episodes(
+    app: tauri::AppHandle,
+    feed: ingest::Feed,
+    guids: Vec<String>,
+    transcribe: bool,
+) -> std::result::Result<Vec<String>, Message> {
+    let _profile = profile::Operation::start("podcast_download", &feed.title);
+    let notice = JobNotice::start("podcast_download", &feed.title);
+    let mut progress = EventProgress::new(app, "podcast_download");
+    let options = ingest::DownloadOptions::default().transcribe(transcribe);
+    let result = ingest::download_episodes(&feed, &guids, &options, &mut progress)
+        .map(|paths| paths.iter().map(|p| p.display().to_string()).collect());
+    notice.finish(result, Clone::clone)
+}
+
+/// Download the audio of a media URL (a video page, a stream) with yt-dlp
+///
+/// # Returns
+/// Where the file was saved in the library
+#[tauri::command(async)]
+fn download_media_url(
+    app: tauri::AppHandle,
+    url: String,
+    transcribe: bool,
+) -> std::result::Result<String, Message> {
+    let _profile = profile::Operation::start("media_download", &url);
+    let notice = JobNotice::start("media_download", &url);
+    let mut progress = EventProgress::new(app, "media_download");
+    let options = ingest::DownloadOptions::default().transcribe(transcribe);
+    let result =
+        ingest::download_url(&url, &options, &mut progress).map(|p| p.display().to_string());
+    notice.finish(result, |path| vec![path.clone()])
+}
+
+/// The media downloader that would be used, if one is installed
+#[tauri::command(async)]
+fn get_downloader_info() -> Option<ingest::DownloaderInfo> {
+    ingest::ExternalDownloader::find(&settings::Settings::load()).map(|d| d.info())
+}
+
+/// Choose the yt-dlp executable; `None` looks for it on the `PATH`
+#[tauri::command]
+fn set_downloader_path(path: Option<String>) -> std::result::Result<(), Message> {
+    settings::update(|settings| settings.downloader = path.map(Into::into))
+        .map_err(AudioError::from)?;
+    Ok(())
+}
+
+fn models_dir() -> std::result::Result<std::path::PathBuf, Message> {
+    models::models_dir().ok_or_else(|| {
+        AudioError::InvalidParameter("there is no data folder to keep models in".to_string())
+            .into()
+    })
+}
+
+/// Check an installed model's size and checksum
+#[tauri::command(async)]
+fn verify_model(
+    app: tauri::AppHandle,
+    spec: models::ModelSpec,
+) -> std::result::Result<models::ModelStatus, Message> {
+    let mut progress = EventProgress::new(app, "model_verify");
+    models::verify_model(&models_dir()?, &spec, &mut progress).map_err(Into::into)
+}
+
+/// Download a model again if it's missing or damaged
+///
+/// # Returns
+/// The model's path
+#[tauri::command(async)]
+fn repair_model(
+    app: tauri::AppHandle,
+    spec: models::ModelSpec,
+) -> std::result::Result<String, Message> {
+    let mut progress = EventProgress::new(app, "model_repair");
+    let (path, _) = models::repair_model(&models_dir()?, &spec, &mut progress)?;
+    Ok(path.display().to_string())
+}
+
+/// Install a model file the user already has, if it checks out
+#[tauri::command(async)]
+fn import_model(
+    app: tauri::AppHandle,
+    spec: models::ModelSpec,
+    source_path: String,
+) -> std::result::Result<String, Message> {
+    let mut progress = EventProgress::new(app, "model_import");
+    let path = models::import_model(&models_dir()?, &spec, source_path.as_ref(), &mut progress)?;
+    Ok(path.display().to_string())
+}
+
+/// Models in the models folder, from its registry
+#[tauri::command(async)]
+fn list_installed_models() -> Vec<models::InstalledModel> {
+    models::models_dir().map_or_else(Vec::new, |dir| models::installed_models(&dir))
+}
+
+/// Files in a folder, e.g. on a USB stick, that look like models
+#[tauri::command(async)]
+fn find_model_files(folder: String) -> std::result::Result<Vec<String>, Message> {
+    let found = models::find_model_files(folder.as_ref())?;
+    Ok(found.iter().map(|path| path.display().to_string()).collect())
+}
+
+/// Install and register a model file from disk without downloading
+///
+/// # Arguments
+/// * `name` - What to call a model that isn't one of `known`; defaults to
+///   the file name
+/// * `known` - Models the file may be a copy of, checked by checksum
+/// * `overwrite` - Replace an installed model with the same name or file
+///   name instead of refusing
+#[tauri::command(async)]
+fn import_local_model(
+    app: tauri::AppHandle,
+    source_path: String,
+    name: Option<String>,
+    known: Vec<models::ModelSpec>,
+    overwrite: Option<bool>,
+) -> std::result::Result<models::InstalledModel, Message> {
+    let mut progress = EventProgress::new(app, "model_import");
+    models::import_local_model(
+        &models_dir()?,
+        source_path.as_ref(),
+        name.as_deref(),
+        &known,
+        overwrite.unwrap_or(false),
+        &mut progress,
+    )
+    .map_err(Into::into)
+}
+
+/// Speaking rate over time, pauses and filler words of a finished transcript
+///
+/// # Arguments
+/// * `segments` - The transcript, with word timings where the engine gave them
+/// * `options` - Rate window, pause threshold and filler list; defaults in
+///   [`pacing::PacingOptions`]
+#[tauri::command(async)]
+fn analyze_pacing(
+    segments: Vec<transcribe::Segment>,
+    options: Option<pacing::PacingOptions>,
+) -> std::result::Result<pacing::PacingReport, Message> {
+    Ok(pacing::analyze_pacing(&segments, &options.unwrap_or_default())?)
+}
+
+/// Talk time, turns, interruptions and longest turn of each speaker in a
+/// diarized transcript
+#[tauri::command(async)]
+fn analyze_talk_time(
+    segments: Vec<transcribe::Segment>,
+    options: Option<speakers::TalkTimeOptions>,
+) -> speakers::TalkTimeReport {
+    speakers::analyze_talk_time(&segments, &options.unwrap_or_default())
+}
+
+/// Analyze several transcripts' talk time and write it as CSV: a row per
+/// speaker per file, then the batch totals under the name "total"
+///
+/// # Arguments
+/// * `output_path` - CSV file to write
+/// * `transcripts` - Pairs of a name for the `file` column and its segments
+///
+/// Returns the batch totals.
+#[tauri::command(async)]
+fn export_talk_time(
+    output_path: String,
+    transcripts: Vec<(String, Vec<transcribe::Segment>)>,
+    options: Option<speakers::TalkTimeOptions>,
+) -> std::result::Result<speakers::TalkTimeReport, Message> {
+    let options = options.unwrap_or_default();
+    let reports: Vec<(String, speakers::TalkTimeReport)> = transcripts
+        .iter()
+        .map(|(name, segments)| (name.clone(), speakers::analyze_talk_time(segments, &options)))
+        .collect();
+    let total = speakers::TalkTimeReport::combine(reports.iter().map(|(_, report)| report));
+    let rows = reports
+        .iter()
+        .map(|(name, report)| (name.as_str(), report))
+        .chain(std::iter::once(("total", &total)));
+    speakers::write_talk_time_csv(&output_path, rows)?;
+    Ok(total)
+}
+
+/// Align two transcripts of the same audio, e.g. from different models,
+/// and list where they differ
+///
+/// The word error rate in the result takes `reference` as correct.
+#[tauri::command(async)]
+fn diff_transcripts(
+    reference: Vec<transcribe::Segment>,
+    candidate: Vec<transcribe::Segment>,
+) -> transcript_diff::TranscriptDiff {
+    transcript_diff::diff_transcripts(&reference, &candidate)
+}
+
+/// Write a transcript as SRT, WebVTT, ASS or SSA subtitles (chosen by the
+/// extension)
+///
+/// With a `translation`, the subtitles are bilingual: both languages in
+/// each cue, or one after the other with `layout` "alternating". Timing
+/// always comes from `segments`. ASS and SSA give each speaker label its
+/// own color and placement from `style`.
+#[tauri::command(async)]
+fn export_subtitles(
+    output_path: String,
+    segments: Vec<transcribe::Segment>,
+    translation: Option<Vec<transcribe::Segment>>,
+    layout: Option<subtitles::BilingualLayout>,
+    style: Option<subtitles::SubtitleStyle>,
+) -> std::result::Result<(), Message> {
+    let cues = match translation {
+        Some(translation) => {
+            subtitles::merge_bilingual(&segments, &translation, layout.unwrap_or_default())
+        }
+        None => subtitles::cues(&segments),
+    };
+    Ok(subtitles::write_subtitles(&output_path, &cues, &style.unwrap_or_default())?)
+}
+
+/// Write a transcript as SRT or WebVTT, rewrapped to `limits`: by default
+/// 42 characters a line, two lines a cue and speakers named
+///
+/// Segments too long for one cue are split into several, timed from the
+/// word timing where there is some.
+#[tauri::command(async)]
+fn export_transcript(
+    output_path: String,
+    segments: Vec<transcribe::Segment>,
+    format: subtitles::CaptionFormat,
+    limits: Option<subtitles::LineLimits>,
+) -> std::result::Result<(), Message> {
+    let limits = limits.unwrap_or_default();
+    match format {
+        subtitles::CaptionFormat::Srt => subtitles::export_srt(&segments, &output_path, &limits)?,
+        subtitles::CaptionFormat::Vtt => subtitles::export_vtt(&segments, &output_path, &limits)?,
+    }
+    Ok(())
+}
+
+/// The saved translation engine, if one has been set up
+#[tauri::command]
+fn get_translation_engine() -> Option<translation::TranslationEngine> {
+    settings::Settings::load().translation_engine
+}
+
+#[tauri::command]
+fn set_translation_engine(
+    engine: Option<translation::TranslationEngine>,
+) -> std::result::Result<(), Message> {
+    settings::update(|settings| settings.translation_engine = engine).map_err(AudioError::from)?;
+    Ok(())
+}
+
+/// Queue a transcript or subtitle file for translation on the background
+/// queue
+///
+/// Uses `engine` if given, else the saved one. Follow it with
+/// `get_translation_job` or the [`jobs::JOB_QUEUE_EVENT`]s.
+#[tauri::command]
+fn submit_translation(
+    request: translation::TranslationRequest,
+    engine: Option<translation::TranslationEngine>,
+    priority: Option<jobs::Priority>,
+) -> std::result::Result<jobs::TranslationJob, Message> {
+    if engine.is_none() && settings::Settings::load().translation_engine.is_none() {
+        let message = "No translation engine is set up".to_string();
+        return Err(AudioError::InvalidParameter(message).into());
+    }
+    let label = match &request.input {
+        translation::TranslationInput::File { path } => Some(path.display().to_string()),
+        translation::TranslationInput::Transcript { .. } => None,
+    };
+    let payload = serde_json::to_value(jobs::TranslationJobRequest { request, engine })
+        .map_err(std::io::Error::from)
+        .map_err(AudioError::from)?;
+    let mut job = jobs::NewJob::new(jobs::JobKind::Translation, payload)
+        .priority(priority.unwrap_or_default());
+    if let Some(label) = label {
+        job = job.label(label);
+    }
+    let job = jobs::app_queue().submit(job);
+    jobs::TranslationJob::from_job(&job).ok_or_else(|| no_translation(job.id))
+}
+
+/// Translations on the background queue, oldest first
+#[tauri::command]
+fn get_translation_jobs() -> Vec<jobs::TranslationJob> {
+    jobs::app_queue().jobs().iter().filter_map(jobs::TranslationJob::from_job).collect()
+}
+
+#[tauri::command]
+fn get_translation_job(id: u64) -> std::result::Result<jobs::TranslationJob, Message> {
+    let job = jobs::app_queue().job(id);
+    job.as_ref().and_then(jobs::TranslationJob::from_job).ok_or_else(|| no_translation(id))
+}
+
+/// Stop a queued or running translation
+///
+/// # Returns
+/// `false` if the job had already finished
+#[tauri::command]
+fn cancel_translation(id: u64) -> std::result::Result<bool, Message> {
+    get_translation_job(id)?;
+    Ok(jobs::app_queue().cancel(id))
+}
+
+/// Drop finished translations and their results
+#[tauri::command]
+fn clear_finished_translations() -> usize {
+    jobs::app_queue().clear_finished(Some(jobs::JobKind::Translation))
+}
+
+fn no_translation(id: u64) -> Message {
+    AudioError::InvalidParameter(format!("There's no translation job {}", id)).into()
+}
+
+/// The translated transcript of a completed translation job
+#[tauri::command]
+fn get_translation_result(id: u64) -> std::result::Result<Vec<transcribe::Segment>, Message> {
+    translation_result(id)
+}
+
+/// Save a completed job's translation as subtitles (SRT, WebVTT, ASS or
+/// SSA) or an Audacity label track (`.txt`), chosen by the extension
+#[tauri::command(async)]
+fn save_translation(id: u64, output_path: String) -> std::result::Result<(), Message> {
+    let segments = translation_result(id)?;
+    let labels = std::path::Path::new(&output_path)
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("txt"));
+    if labels {
+        let markers: Vec<audio::Marker> =
+            segments.iter().map(transcribe::Segment::to_marker).collect();
+        return Ok(audio::write_audacity_labels(&output_path, &markers)?);
+    }
+    let cues = subtitles::cues(&segments);
+    Ok(subtitles::write_subtitles(&output_path, &cues, &Default::default())?)
+}
+
+/// The segments a completed [`jobs::JobKind::Translation`] job returned
+fn translation_result(id: u64) -> std::result::Result<Vec<transcribe::Segment>, Message> {
+    let job = jobs::app_queue().job(id).ok_or_else(|| no_job(id))?;
+    let result = match job.result {
+        Some(result) if job.kind == jobs::JobKind::Translation => result,
+        _ => {
+            return Err(AudioError::InvalidParameter(format!(
+                "Translation job {} hasn't completed",
+                id
+            ))
+            .into())
+        }
+    };
+    let segments = serde_json::from_value(result).map_err(std::io::Error::from);
+    Ok(segments.map_err(AudioError::from)?)
+}
+
+/// Keep a transcript or translation in the history
+///
+/// # Returns
+/// Its id
+#[tauri::command(async)]
+fn save_transcript(transcript: storage::NewTranscript) -> std::result::Result<i64, Message> {
+    Ok(storage::HistoryStore::open_default()?.save_transcript(&transcript)?)
+}
+
+/// Saved transcripts and translations, newest first, optionally only one
+/// kind or those made from one file
+#[tauri::command(async)]
+fn list_transcripts(
+    kind: Option<storage::TranscriptKind>,
+    file_path: Option<String>,
+) -> std::result::Result<Vec<storage::TranscriptSummary>, Message> {
+    let history = storage::HistoryStore::open_default()?;
+    Ok(history.transcripts(kind, file_path.as_deref())?)
+}
+
+#[tauri::command(async)]
+fn get_transcript(id: i64) -> std::result::Result<storage::TranscriptRecord, Message> {
+    storage::HistoryStore::open_default()?
+        .transcript(id)?
+        .ok_or_else(|| AudioError::InvalidParameter(format!("There's no saved transcript {}", id)))
+        .map_err(Into::into)
+}
+
+/// # Returns
+/// `false` if there was no such transcript
+#[tauri::command(async)]
+fn delete_transcript(id: i64) -> std::result::Result<bool, Message> {
+    Ok(storage::HistoryStore::open_default()?.delete_transcript(id)?)
+}
+
+/// Read a file's header and keep what it shows in the history
+#[tauri::command(async)]
+fn remember_audio_file(file_path: String) -> std::result::Result<storage::FileRecord, Message> {
+    let info = audio::get_audio_info(&file_path)?;
+    Ok(storage::HistoryStore::open_default()?.save_file(&file_path, &info)?)
+}
+
+/// Files in the history, most recently recorded first
+#[tauri::command(async)]
+fn list_audio_files() -> std::result::Result<Vec<storage::FileRecord>, Message> {
+    Ok(storage::HistoryStore::open_default()?.files()?)
+}
+
+/// Drop a file from the history; transcripts made from it are kept
+#[tauri::command(async)]
+fn forget_audio_file(file_path: String) -> std::result::Result<bool, Message> {
+    Ok(storage::HistoryStore::open_default()?.delete_file(&file_path)?)
+}
+
+/// The most recent finished jobs, newest first; 100 unless `limit` says
+#[tauri::command(async)]
+fn get_job_history(limit: Option<usize>) -> std::result::Result<Vec<storage::JobRecord>, Message> {
+    Ok(storage::HistoryStore::open_default()?.jobs(limit.unwrap_or(100))?)
+}
+
+#[tauri::command(async)]
+fn delete_job_record(id: i64) -> std::result::Result<bool, Message> {
+    Ok(storage::HistoryStore::open_default()?.delete_job(id)?)
+}
+
+/// # Returns
+/// How many jobs were forgotten
+#[tauri::command(async)]
+fn clear_job_history() -> std::result::Result<usize, Message> {
+    Ok(storage::HistoryStore::open_default()?.clear_jobs()?)
+}
+
+/// Put work on the background queue; its progress arrives as
+/// [`jobs::JOB_QUEUE_EVENT`]s
+#[tauri::command]
+fn submit_job(job: jobs::NewJob) -> jobs::Job {
+    jobs::app_queue().submit(job)
+}
+
+/// Every job on the background queue, oldest first
+#[tauri::command]
+fn get_jobs() -> Vec<jobs::Job> {
+    jobs::app_queue().jobs()
+}
+
+#[tauri::command]
+fn get_job(id: u64) -> std::result::Result<jobs::Job, Message> {
+    jobs::app_queue().job(id).ok_or_else(|| no_job(id))
+}
+
+/// Stop a background job: a pending one never starts, a running one stops
+/// as soon as it notices
+#[tauri::command]
+fn cancel_job(id: u64) -> bool {
+    jobs::app_queue().cancel(id)
+}
+
+/// Queue a failed or cancelled job again
+#[tauri::command]
+fn retry_job(id: u64) -> bool {
+    jobs::app_queue().retry(id)
+}
+
+#[tauri::command]
+fn set_job_priority(id: u64, priority: jobs::Priority) -> bool {
+    jobs::app_queue().set_priority(id, priority)
+}
+
+/// Drop finished background jobs and their results
+#[tauri::command]
+fn clear_finished_jobs() -> usize {
+    jobs::app_queue().clear_finished(None)
+}
+
+/// How many background jobs run at once
+#[tauri::command]
+fn get_background_jobs() -> usize {
+    jobs::app_queue().max_concurrent()
+}
+
+/// Save how many background jobs run at once, or back to one (`None`);
+/// more start straight away if there's now room
+#[tauri::command]
+fn set_background_jobs(count: Option<usize>) -> std::result::Result<(), Message> {
+    settings::update(|settings| settings.background_jobs = count).map_err(AudioError::from)?;
+    jobs::app_queue().set_max_concurrent(count.unwrap_or(1));
+    Ok(())
+}
+
+fn no_job(id: u64) -> Message {
+    AudioError::InvalidParameter(format!("There's no background job {}", id)).into()
+}
+
+/// Transcriptions waiting on the background queue, in the order they'll
+/// start
+#[tauri::command]
+fn get_transcription_queue() -> Vec<jobs::Job> {
+    jobs::app_queue().run_order(Some(jobs::JobKind::Transcription))
+}
+
+/// Put a file on the background queue to be transcribed; an urgent file can
+/// go ahead of everything already waiting without cancelling any of it
+///
+/// # Returns
+/// The waiting transcriptions after the change; a file already queued
+/// keeps its place
+#[tauri::command]
+fn queue_transcription(path: String, priority: Option<jobs::Priority>) -> Vec<jobs::Job> {
+    jobs::queue_transcription(std::path::Path::new(&path), priority.unwrap_or_default());
+    get_transcription_queue()
+}
+
+/// Move a pending background job to `index` in the run order, for
+/// drag-and-drop reordering; see [`jobs::JobQueue::move_to`] for how
+/// priorities follow the move
+#[tauri::command]
+fn move_job(id: u64, index: usize) -> bool {
+    jobs::app_queue().move_to(id, index)
+}
+
+/// Effects that can be added to a processing chain, with their parameters
+#[tauri::command]
+fn list_effects() -> Vec<audio::pipeline::EffectInfo> {
+    audio::pipeline::effects()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .list()
+}
+
+/// The player for the file open in the editor, if any
+#[derive(Default)]
+struct PlayerSlot(Mutex<Option<playback::AudioPlayer>>);
+
+impl PlayerSlot {
+    fn with<T>(
+        &self,
+        f: impl FnOnce(&playback::AudioPlayer) -> T,
+    ) -> std::result::Result<T, Message> {
+        let player = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        player.as_ref().map(f).ok_or_else(|| {
+            Message::new("error.playback.not_open", "No file is open for playback")
+        })
+    }
+}
+
+/// Event carrying a [`PlaybackPosition`] several times a second while
+/// playing, and after every pause, seek or end
+pub const PLAYBACK_POSITION_EVENT: &str = "playback-position";
+
+/// Send `player`'s position to the frontend as [`PLAYBACK_POSITION_EVENT`]s
+/// at the rate in the settings
+fn watch_player(app: &tauri::AppHandle, player: &playback::AudioPlayer) {
+    let app = app.clone();
+    let interval = playback::update_interval(&settings::Settings::load());
+    player.on_update(interval, move |snapshot| {
+        let review = app.state::<ReviewTranscript>();
+        let position = PlaybackPosition::at(snapshot, &review.lock());
+        let _ = app.emit(PLAYBACK_POSITION_EVENT, position);
+    });
+}
+
+/// Open a file for playback, paused at the start
+///
+/// Replaces whatever was playing before. Its position then arrives as
+/// [`PLAYBACK_POSITION_EVENT`]s.
+#[tauri::command(async)]
+fn open_playback(
+    file_path: String,
+    app: tauri::AppHandle,
+    player: tauri::State<'_, PlayerSlot>,
+) -> std::result::Result<playback::PlaybackSnapshot, Message> {
+    let mut slot = player.0.lock().unwrap_or_else(|e| e.into_inner());
+    // Close the old stream before opening a new one on the same device
+    *slot = None;
+    let opened = playback::AudioPlayer::open(&file_path)?;
+    watch_player(&app, &opened);
+    let snapshot = opened.snapshot();
+    *slot = Some(opened);
+    Ok(snapshot)
+}
+
+/// Save how many [`PLAYBACK_POSITION_EVENT`]s a second to send while
+/// playing, or back to the default (`None`)
+#[tauri::command]
+fn set_playback_update_rate(
+    hz: Option<f64>,
+    app: tauri::AppHandle,
+    player: tauri::State<'_, PlayerSlot>,
+) -> std::result::Result<(), Message> {
+    settings::update(|settings| settings.playback_update_hz = hz).map_err(AudioError::from)?;
+    // Nothing open is fine; the next file picks it up
+    let _ = player.with(|p| watch_player(&app, p));
+    Ok(())
+}
+
+#[tauri::command]
+fn play_audio(player: tauri::State<'_, PlayerSlot>) -> std::result::Result<(), Message> {
+    player.with(|p| p.play())
+}
+
+#[tauri::command]
+fn pause_audio(player: tauri::State<'_, PlayerSlot>) -> std::result::Result<(), Message> {
+    player.with(|p| p.pause())
+}
+
+#[tauri::command]
+fn seek_audio(seconds: f64, player: tauri::State<'_, PlayerSlot>) -> std::result::Result<(), Message> {
+    player.with(|p| p.seek(audio::Timestamp::from_seconds(seconds)))
+}
+
+#[tauri::command]
+fn stop_audio(player: tauri::State<'_, PlayerSlot>) -> std::result::Result<(), Message> {
+    player.with(|p| p.stop())
+}
+
+#[tauri::command]
+fn set_playback_volume(
+    volume: f32,
+    player: tauri::State<'_, PlayerSlot>,
+) -> std::result::Result<(), Message> {
+    player.with(|p| p.set_volume(volume))
+}
+
+/// Play a region over and over, e.g. while correcting a transcript segment
+#[tauri::command]
+fn set_loop_region(
+    start_seconds: f64,
+    end_seconds: f64,
+    player: tauri::State<'_, PlayerSlot>,
+) -> std::result::Result<(), Message> {
+    let start = audio::Timestamp::try_from(start_seconds)?;
+    let end = audio::Timestamp::try_from(end_seconds)?;
+    player.with(|p| p.set_loop_region(start, end))??;
+    Ok(())
+}
+
+#[tauri::command]
+fn clear_loop_region(player: tauri::State<'_, PlayerSlot>) -> std::result::Result<(), Message> {
+    player.with(|p| p.clear_loop_region())
+}
+
+/// Position, state and format of the open file; `None` when nothing is open
+#[tauri::command]
+fn get_playback_state(player: tauri::State<'_, PlayerSlot>) -> Option<playback::PlaybackSnapshot> {
+    player.with(|p| p.snapshot()).ok()
+}
+
+/// Output devices playback can use, the system default first
+#[tauri::command(async)]
+fn list_output_devices() -> std::result::Result<Vec<playback::OutputDevice>, Message> {
+    Ok(playback::list_output_devices()?)
+}
+
+/// Play through another output device, or the system default (`None`)
+///
+/// Moves the open file there without losing its place, then saves the
+/// choice for next time. Nothing is saved if the device can't be opened.
+#[tauri::command(async)]
+fn set_output_device(
+    device_id: Option<String>,
+    player: tauri::State<'_, PlayerSlot>,
+) -> std::result::Result<(), Message> {
+    let slot = player.0.lock().unwrap_or_else(|e| e.into_inner());
+    match slot.as_ref() {
+        Some(player) => player.set_output_device(device_id.as_deref())?,
+        // Nothing to move, but don't save a device that isn't there
+        None => {
+            playback::device::output_device(device_id.as_deref())?;
+        }
+    }
+    settings::update(|settings| settings.output_device = device_id).map_err(AudioError::from)?;
+    Ok(())
+}
+
+/// Where the review transcript is kept between runs, next to the settings
+const REVIEW_FILE: &str = "review_transcript.json";
+
+/// The transcript followed along with playback, sorted by start
+///
+/// Saved whenever it changes and read back at startup, so edits survive a
+/// restart.
+#[derive(Default)]
+struct ReviewTranscript(Mutex<Vec<transcribe::Segment>>);
+
+impl ReviewTranscript {
+    fn path() -> Option<std::path::PathBuf> {
+        Some(settings::app_config_dir()?.join(REVIEW_FILE))
+    }
+
+    /// The transcript saved by the last run; empty if there is none or it
+    /// can't be read
+    fn restore() -> Self {
+        let saved = Self::path().map(|path| {
+            let json = std::fs::read_to_string(&path)?;
+            Ok::<_, std::io::Error>(serde_json::from_str(&json)?)
+        });
+        match saved {
+            Some(Ok(segments)) => Self(Mutex::new(segments)),
+            Some(Err(e)) if e.kind() != std::io::ErrorKind::NotFound => {
+                tracing::warn!(error = %e, "Ignoring unreadable review transcript");
+                Self::default()
+            }
+            _ => Self::default(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<transcribe::Segment>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Follow `segments` from now on and save them
+    fn replace(&self, mut segments: Vec<transcribe::Segment>) -> std::io::Result<()> {
+        segments.sort_by_key(|segment| segment.start);
+        let mut current = self.lock();
+        if let Some(path) = Self::path() {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let tmp = path.with_extension("json.tmp");
+            std::fs::write(&tmp, serde_json::to_string(&segments)?)?;
+            std::fs::rename(&tmp, &path)?;
+        }
+        *current = segments;
+        Ok(())
+    }
+}
+
+/// Playback state with the review transcript segment being spoken
+#[derive(Debug, Clone, Serialize)]
+struct PlaybackPosition {
+    #[serde(flatten)]
+    snapshot: playback::PlaybackSnapshot,
+    /// Index into the review transcript, `None` between segments
+    segment: Option<usize>,
+}
+
+impl PlaybackPosition {
+    fn at(snapshot: playback::PlaybackSnapshot, review: &[transcribe::Segment]) -> Self {
+        let position = audio::Timestamp::from_seconds(snapshot.position_seconds);
+        let segment = transcribe::segment_at(review, position);
+        Self { snapshot, segment }
+    }
+}
+
+/// Read an SRT or WebVTT file as a transcript, one segment per cue, and
+/// follow it during playback
+///
+/// # Returns
+/// The segments, sorted by start; indexes from [`get_playback_position`]
+/// and for [`seek_to_segment`] refer to them
+#[tauri::command(async)]
+fn import_subtitles(
+    file_path: String,
+    review: tauri::State<'_, ReviewTranscript>,
+) -> std::result::Result<Vec<transcribe::Segment>, Message> {
+    let segments = subtitles::segments_from_cues(&subtitles::read_subtitles(&file_path)?);
+    review.replace(segments).map_err(AudioError::from)?;
+    Ok(review.lock().clone())
+}
+
+/// Follow a transcript during playback, e.g. one just transcribed or
+/// edited; it is saved, so it is still there after a restart
+#[tauri::command]
+fn set_review_transcript(
+    segments: Vec<transcribe::Segment>,
+    review: tauri::State<'_, ReviewTranscript>,
+) -> std::result::Result<(), Message> {
+    review.replace(segments).map_err(AudioError::from)?;
+    Ok(())
+}
+
+/// The transcript followed during playback, as last set or imported
+#[tauri::command]
+fn get_review_transcript(review: tauri::State<'_, ReviewTranscript>) -> Vec<transcribe::Segment> {
+    review.lock().clone()
+}
+
+/// Playback state and which review transcript segment to highlight;
+/// `None` when nothing is open
+#[tauri::command]
+fn get_playback_position(
+    player: tauri::State<'_, PlayerSlot>,
+    review: tauri::State<'_, ReviewTranscript>,
+) -> Option<PlaybackPosition> {
+    let snapshot = player.with(|p| p.snapshot()).ok()?;
+    Some(PlaybackPosition::at(snapshot, &review.lock()))
+}
+
+/// Jump to the start of a review transcript segment, e.g. when it's clicked
+#[tauri::command]
+fn seek_to_segment(
+    index: usize,
+    player: tauri::State<'_, PlayerSlot>,
+    review: tauri::State<'_, ReviewTranscript>,
+) -> std::result::Result<(), Message> {
+    let start = review.lock().get(index).map(|segment| segment.start).ok_or_else(|| {
+        AudioError::InvalidParameter(format!("There is no segment {} to seek to", index))
+    })?;
+    player.with(|p| p.seek(start))
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+
+    let settings = settings::Settings::load();
+    gpu::apply_optimizations(&settings.gpu);
+
+    tauri::Builder::default()
+        .plugin(tauri_plugin_opener::init())
+        .manage(PlayerSlot::default())
+        .manage(ReviewTranscript::restore())
+        .setup(|app| {
+            // Shrink the caches up front on machines short of memory
+            memory::apply_memory_mode(&settings::Settings::load());
+
+            // Tell the UI when throughput drops because of battery or heat
+            let handle = app.handle().clone();
+            std::thread::spawn(move || {
+                power::watch_power(
+                    std::time::Duration::from_secs(30),
+                    || settings::Settings::load().power,
+                    |status| {
+                        let _ = handle.emit(power::POWER_STATUS_EVENT, status);
+                    },
+                );
+            });
+
+            // Start waiting transcriptions when the schedule opens, and tell
+            // the UI when it opens or closes
+            let handle = app.handle().clone();
+            std::thread::spawn(move || {
+                schedule::watch_schedule(
+                    std::time::Duration::from_secs(30),
+                    || settings::Settings::load().schedule,
+                    |status| {
+                        if status.is_open() {
+                            jobs::app_queue().wake();
+                        }
+                        let _ = handle.emit(schedule::SCHEDULE_STATUS_EVENT, status);
+                    },
+                );
+            });
+
+            // Resume the background queue and pass its changes on to the UI
+            let handle = app.handle().clone();
+            jobs::app_queue().on_change(move |job| {
+                let _ = handle.emit(jobs::JOB_QUEUE_EVENT, job);
+            });
+            let handle = app.handle().clone();
+            jobs::app_queue().on_gpu_fallback(move |_, fallback| {
+                let _ = handle.emit(gpu::GPU_FALLBACK_EVENT, fallback);
+            });
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            greet,
+            get_waveform_peaks,
+            stream_waveform_peaks,
+            cancel_waveform_stream,
+            get_audio_info,
+            render_waveform_image,
+            read_markers,
+            export_audacity_labels,
+            export_transcript_labels,
+            import_transcript_labels,
+            export_segments,
+            list_export_presets,
+            save_export_preset,
+            delete_export_preset,
+            export_audio,
+            trim_audio_file,
+            join_audio_segments,
+            resample_file,
+            denoise_file,
+            render_edit_list,
+            redact_file,
+            measure_quality,
+            get_last_operation_profile,
+            get_gpu_info,
+            get_inference_capabilities,
+            get_gpu_preference,
+            set_gpu_preference,
+            get_power_status,
+            set_power_mode,
+            get_memory_status,
+            set_low_memory,
+            get_schedule,
+            set_schedule,
+            get_schedule_status,
+            get_notification_settings,
+            set_notification_settings,
+            send_test_notification,
+            get_output_naming,
+            set_output_naming,
+            fetch_podcast_feed,
+            download_podcast_episodes,
+            download_media_url,
+            get_downloader_info,
+            set_downloader_path,
+            verify_model,
+            repair_model,
+            import_model,
+            list_installed_models,
+            find_model_files,
+            import_local_model,
+            get_transcription_queue,
+            get_translation_engine,
+            set_translation_engine,
+            submit_translation,
+            get_translation_jobs,
+            get_translation_job,
+            cancel_translation,
+            get_translation_result,
+            save_translation,
+            clear_finished_translations,
+            submit_job,
+            get_jobs,
+            get_job,
+            cancel_job,
+            retry_job,
+            set_job_priority,
+            clear_finished_jobs,
+            get_background_jobs,
+            set_background_jobs,
+            save_transcript,
+            list_transcripts,
+            get_transcript,
+            delete_transcript,
+            remember_audio_file,
+            list_audio_files,
+            forget_audio_file,
+            get_job_history,
+            delete_job_record,
+            clear_job_history,
+            queue_transcription,
+            move_job,
+            analyze_pacing,
+            export_subtitles,
+            export_transcript,
+            analyze_talk_time,
+            export_talk_time,
+            diff_transcripts,
+            list_effects,
+            open_playback,
+            play_audio,
+            pause_audio,
+            seek_audio,
+            stop_audio,
+            set_playback_volume,
+            get_playback_state,
+            set_loop_region,
+            set_playback_update_rate,
+            clear_loop_region,
+            list_output_devices,
+            set_output_device,
+            import_subtitles,
+            set_review_transcript,
+            get_review_transcript,
+            get_playback_position,
+            seek_to_segment
+        ])
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
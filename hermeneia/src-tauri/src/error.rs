@@ -1,7 +1,15 @@
 use thiserror::Error;
 
 /// All possible errors that can occur during audio processing
+///
+/// Failures inside one subsystem carry that subsystem's error
+/// ([`DecodeError`], [`EncodeError`], [`PlaybackError`], [`AnalysisError`]);
+/// the remaining variants are shared by all of them. Every error has a
+/// stable [`code`](AudioError::code) and [`code_name`](AudioError::code_name)
+/// for callers (the frontend, scripts) that need to tell errors apart
+/// without matching on messages.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum AudioError {
     /// Failed to open or read the audio file from disk
     #[error("Failed to open audio file '{path}': {source}")]
@@ -16,11 +24,27 @@ pub enum AudioError {
 
     /// Error occurred while decoding the audio data
     #[error("Audio decoding failed: {0}")]
-    DecodeFailed(String),
+    Decode(#[from] DecodeError),
 
-    /// Error occurred while encoding to WAV
-    #[error("WAV encoding failed: {0}")]
-    EncodeFailed(String),
+    /// Error occurred while encoding the output file (WAV, FLAC, MP3, Opus)
+    #[error("Audio encoding failed: {0}")]
+    Encode(#[from] EncodeError),
+
+    /// The output device couldn't be opened or stopped working
+    #[error("Playback failed: {0}")]
+    Playback(#[from] PlaybackError),
+
+    /// An analysis (or an engine such as a transcriber) couldn't run
+    #[error("Analysis failed: {0}")]
+    Analysis(#[from] AnalysisError),
+
+    /// Error occurred while converting between sample rates
+    #[error("Resampling failed: {0}")]
+    ResampleFailed(String),
+
+    /// A processing parameter is out of range or inconsistent
+    #[error("Invalid parameter: {0}")]
+    InvalidParameter(String),
 
     /// Invalid trim parameters (e.g., start > end, negative values)
     #[error("Invalid trim parameters: {0}")]
@@ -34,18 +58,339 @@ pub enum AudioError {
         duration: f64,
     },
 
+    /// The operation would need more memory than the configured budget allows
+    #[error("Needs about {required_mb} MB but the memory budget is {budget_mb} MB")]
+    MemoryBudgetExceeded { required_mb: u64, budget_mb: u64 },
+
+    /// Failed to render a waveform image (PNG/SVG)
+    #[error("Waveform rendering failed: {0}")]
+    RenderFailed(String),
+
     /// Generic I/O error
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
 
-    /// Error from symphonia decoder
-    #[error("Symphonia error: {0}")]
-    Symphonia(String),
+    /// The caller cancelled the operation before it finished
+    #[error("Operation was cancelled")]
+    Cancelled,
+
+    /// A feed or file couldn't be downloaded
+    #[error("Failed to download '{url}': {reason}")]
+    Download { url: String, reason: String },
+
+    /// A podcast feed couldn't be read
+    #[error("Invalid podcast feed: {0}")]
+    InvalidFeed(String),
+
+    /// The history database couldn't be opened, read or written
+    #[error("History storage error: {0}")]
+    Storage(String),
+}
+
+/// Why a file couldn't be decoded
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The container couldn't be recognised or read
+    #[error("Failed to probe format: {0}")]
+    Probe(String),
+
+    #[error("No audio track found in file")]
+    NoAudioTrack,
+
+    /// The track doesn't state something decoding needs, e.g. its sample rate
+    #[error("{0} not found")]
+    MissingInfo(&'static str),
+
+    /// No decoder could be created for the track's codec
+    #[error("Failed to create decoder: {0}")]
+    Codec(String),
+
+    /// A packet failed to decode
+    #[error("Decode error: {0}")]
+    Packet(String),
+
+    /// A requested time range held no audio
+    #[error("No audio between {start}s and {end}s")]
+    EmptyRange { start: f64, end: f64 },
+
+    /// A probe or read hung past its watchdog, or its thread died
+    #[error("{0}")]
+    Watchdog(String),
+
+    /// A file written by this app (e.g. a raw checkpoint) is damaged
+    #[error("{0}")]
+    Malformed(String),
+
+    /// Embedded chapters or a cue sheet couldn't be parsed
+    #[error("Invalid chapters: {0}")]
+    Chapters(String),
+
+    /// An SRT or WebVTT subtitle file couldn't be parsed
+    #[error("Invalid subtitles: {0}")]
+    Subtitles(String),
+}
+
+/// Why an output couldn't be encoded
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum EncodeError {
+    /// The encoder rejected the settings (bitrate, compression level, ...)
+    #[error("Invalid {format} settings: {message}")]
+    Config {
+        format: &'static str,
+        message: String,
+    },
+
+    /// The encoder library couldn't be set up
+    #[error("Failed to create {format} encoder: {message}")]
+    Init {
+        format: &'static str,
+        message: String,
+    },
+
+    /// Encoding or writing the stream failed part way through
+    #[error("{format} encoding error: {message}")]
+    Codec {
+        format: &'static str,
+        message: String,
+    },
+}
+
+/// Why playback couldn't start or stopped
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum PlaybackError {
+    #[error("No audio output device")]
+    NoDevice,
+
+    /// The device refused the stream or failed while playing
+    #[error("{0}")]
+    Device(String),
+
+    /// The playback thread couldn't be started or died during setup
+    #[error("{0}")]
+    Thread(String),
+}
+
+/// Why an analysis couldn't run
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum AnalysisError {
+    /// The audio has no channels or no sample rate
+    #[error("Cannot analyze audio with {channels} channel(s) at {sample_rate} Hz")]
+    EmptyFormat { channels: u16, sample_rate: u32 },
+
+    /// Two inputs that must match (e.g. for compare) differ in format
+    #[error("Cannot compare {reference} against {candidate}; convert one input first")]
+    FormatMismatch { reference: String, candidate: String },
+
+    /// An external engine (e.g. a speech-to-text model) failed
+    #[error("{0}")]
+    Engine(String),
 
-    /// Error from hound WAV encoder
-    #[error("Hound WAV error: {0}")]
-    Hound(#[from] hound::Error),
+    /// Silence or audio under 400 ms has no integrated loudness
+    #[error("Audio is silent or too short to measure its loudness")]
+    Unmeasurable,
+
+    /// A model file is missing, cut short or doesn't match its checksum
+    #[error("Model '{model}' is unusable ({reason}); download or import it again")]
+    ModelDamaged { model: String, reason: String },
+}
+
+impl AudioError {
+    /// Stable numeric code, grouped by subsystem
+    ///
+    /// | Range | Subsystem        |
+    /// |-------|------------------|
+    /// | 1xx   | shared           |
+    /// | 2xx   | [`DecodeError`]  |
+    /// | 3xx   | [`EncodeError`]  |
+    /// | 4xx   | [`PlaybackError`]|
+    /// | 5xx   | [`AnalysisError`]|
+    ///
+    /// Codes are never reused or renumbered; new variants take the next free
+    /// code in their range.
+    pub fn code(&self) -> u16 {
+        match self {
+            AudioError::FileOpen { .. } => 100,
+            AudioError::UnsupportedFormat(_) => 101,
+            AudioError::ResampleFailed(_) => 102,
+            AudioError::InvalidParameter(_) => 103,
+            AudioError::InvalidTrimParams(_) => 104,
+            AudioError::TrimRangeOutOfBounds { .. } => 105,
+            AudioError::MemoryBudgetExceeded { .. } => 106,
+            AudioError::RenderFailed(_) => 107,
+            AudioError::Io(_) => 108,
+            // 109 was the hound WAV error, now reported as Io or encode.codec
+            AudioError::Cancelled => 110,
+            AudioError::Download { .. } => 111,
+            AudioError::InvalidFeed(_) => 112,
+            AudioError::Storage(_) => 113,
+            AudioError::Decode(e) => e.code(),
+            AudioError::Encode(e) => e.code(),
+            AudioError::Playback(e) => e.code(),
+            AudioError::Analysis(e) => e.code(),
+        }
+    }
+
+    /// Stable string code, e.g. `"decode.no_audio_track"`
+    pub fn code_name(&self) -> &'static str {
+        match self {
+            AudioError::FileOpen { .. } => "file_open",
+            AudioError::UnsupportedFormat(_) => "unsupported_format",
+            AudioError::ResampleFailed(_) => "resample_failed",
+            AudioError::InvalidParameter(_) => "invalid_parameter",
+            AudioError::InvalidTrimParams(_) => "invalid_trim_params",
+            AudioError::TrimRangeOutOfBounds { .. } => "trim_range_out_of_bounds",
+            AudioError::MemoryBudgetExceeded { .. } => "memory_budget_exceeded",
+            AudioError::RenderFailed(_) => "render_failed",
+            AudioError::Io(_) => "io",
+            AudioError::Cancelled => "cancelled",
+            AudioError::Download { .. } => "download",
+            AudioError::InvalidFeed(_) => "invalid_feed",
+            AudioError::Storage(_) => "storage",
+            AudioError::Decode(e) => e.code_name(),
+            AudioError::Encode(e) => e.code_name(),
+            AudioError::Playback(e) => e.code_name(),
+            AudioError::Analysis(e) => e.code_name(),
+        }
+    }
+}
+
+impl DecodeError {
+    pub fn code(&self) -> u16 {
+        match self {
+            DecodeError::Probe(_) => 200,
+            DecodeError::NoAudioTrack => 201,
+            DecodeError::MissingInfo(_) => 202,
+            DecodeError::Codec(_) => 203,
+            DecodeError::Packet(_) => 204,
+            DecodeError::EmptyRange { .. } => 205,
+            DecodeError::Watchdog(_) => 206,
+            DecodeError::Malformed(_) => 207,
+            DecodeError::Chapters(_) => 208,
+            DecodeError::Subtitles(_) => 209,
+        }
+    }
+
+    pub fn code_name(&self) -> &'static str {
+        match self {
+            DecodeError::Probe(_) => "decode.probe",
+            DecodeError::NoAudioTrack => "decode.no_audio_track",
+            DecodeError::MissingInfo(_) => "decode.missing_info",
+            DecodeError::Codec(_) => "decode.codec",
+            DecodeError::Packet(_) => "decode.packet",
+            DecodeError::EmptyRange { .. } => "decode.empty_range",
+            DecodeError::Watchdog(_) => "decode.watchdog",
+            DecodeError::Malformed(_) => "decode.malformed",
+            DecodeError::Chapters(_) => "decode.chapters",
+            DecodeError::Subtitles(_) => "decode.subtitles",
+        }
+    }
+}
+
+impl EncodeError {
+    pub fn config(format: &'static str, message: impl std::fmt::Display) -> Self {
+        EncodeError::Config {
+            format,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn init(format: &'static str, message: impl std::fmt::Display) -> Self {
+        EncodeError::Init {
+            format,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn codec(format: &'static str, message: impl std::fmt::Display) -> Self {
+        EncodeError::Codec {
+            format,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn code(&self) -> u16 {
+        match self {
+            EncodeError::Config { .. } => 300,
+            EncodeError::Init { .. } => 301,
+            EncodeError::Codec { .. } => 302,
+        }
+    }
+
+    pub fn code_name(&self) -> &'static str {
+        match self {
+            EncodeError::Config { .. } => "encode.config",
+            EncodeError::Init { .. } => "encode.init",
+            EncodeError::Codec { .. } => "encode.codec",
+        }
+    }
+}
+
+impl PlaybackError {
+    pub fn code(&self) -> u16 {
+        match self {
+            PlaybackError::NoDevice => 400,
+            PlaybackError::Device(_) => 401,
+            PlaybackError::Thread(_) => 402,
+        }
+    }
+
+    pub fn code_name(&self) -> &'static str {
+        match self {
+            PlaybackError::NoDevice => "playback.no_device",
+            PlaybackError::Device(_) => "playback.device",
+            PlaybackError::Thread(_) => "playback.thread",
+        }
+    }
+}
+
+impl AnalysisError {
+    pub fn code(&self) -> u16 {
+        match self {
+            AnalysisError::EmptyFormat { .. } => 500,
+            AnalysisError::FormatMismatch { .. } => 501,
+            AnalysisError::Engine(_) => 502,
+            AnalysisError::Unmeasurable => 503,
+            AnalysisError::ModelDamaged { .. } => 504,
+        }
+    }
+
+    pub fn code_name(&self) -> &'static str {
+        match self {
+            AnalysisError::EmptyFormat { .. } => "analysis.empty_format",
+            AnalysisError::FormatMismatch { .. } => "analysis.format_mismatch",
+            AnalysisError::Engine(_) => "analysis.engine",
+            AnalysisError::Unmeasurable => "analysis.unmeasurable",
+            AnalysisError::ModelDamaged { .. } => "analysis.model_damaged",
+        }
+    }
 }
 
 /// Convenient Result type that uses our AudioError
-pub type Result<T> = std::result::Result<T, AudioError>;
\ No newline at end of file
+pub type Result<T> = std::result::Result<T, AudioError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_are_stable() {
+        // These are part of the interface; changing one breaks callers
+        assert_eq!(AudioError::InvalidParameter(String::new()).code(), 103);
+        let no_track = AudioError::from(DecodeError::NoAudioTrack);
+        assert_eq!(no_track.code(), 201);
+        assert_eq!(no_track.code_name(), "decode.no_audio_track");
+        let no_device = AudioError::from(PlaybackError::NoDevice);
+        assert_eq!((no_device.code(), no_device.code_name()), (400, "playback.no_device"));
+    }
+
+    #[test]
+    fn test_messages_keep_subsystem_prefix() {
+        let error = AudioError::from(EncodeError::codec("FLAC", "disk full"));
+        assert_eq!(error.to_string(), "Audio encoding failed: FLAC encoding error: disk full");
+        assert_eq!(error.code_name(), "encode.codec");
+    }
+}
@@ -0,0 +1,240 @@
+// src-tauri/src/gpu/cuda.rs
+
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use super::run_command;
+
+/// Oldest compute capability the inference engines are built for (Maxwell)
+pub const MIN_COMPUTE_CAPABILITY: ComputeCapability = ComputeCapability { major: 5, minor: 0 };
+
+/// CUDA compute capability, e.g. 8.6 for an RTX 30-series card
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ComputeCapability {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl ComputeCapability {
+    /// Parse the "8.6" form printed by `nvidia-smi`
+    pub fn parse(value: &str) -> Option<Self> {
+        let (major, minor) = value.trim().split_once('.')?;
+        Some(Self {
+            major: major.parse().ok()?,
+            minor: minor.parse().ok()?,
+        })
+    }
+}
+
+impl std::fmt::Display for ComputeCapability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// One CUDA device as numbered by the driver
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CudaDevice {
+    /// Device ordinal, as used by `CUDA_VISIBLE_DEVICES`
+    pub index: u32,
+    pub name: String,
+    /// `None` on drivers too old to report it
+    pub compute_capability: Option<ComputeCapability>,
+    pub memory_total_mb: Option<u64>,
+}
+
+impl CudaDevice {
+    /// The device is new enough for GPU inference
+    pub fn is_supported(&self) -> bool {
+        self.compute_capability
+            .is_some_and(|cc| cc >= MIN_COMPUTE_CAPABILITY)
+    }
+}
+
+/// What the installed NVIDIA driver offers for CUDA
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CudaInfo {
+    pub driver_version: String,
+    /// Highest CUDA version the driver supports, e.g. "12.2"
+    pub cuda_version: Option<String>,
+    pub devices: Vec<CudaDevice>,
+}
+
+impl CudaInfo {
+    pub fn device_count(&self) -> usize {
+        self.devices.len()
+    }
+
+    /// At least one device can run GPU inference
+    pub fn is_usable(&self) -> bool {
+        self.devices.iter().any(CudaDevice::is_supported)
+    }
+}
+
+/// Detect CUDA support, once per process
+///
+/// Looks for a working NVIDIA driver through `nvidia-smi` (installed with
+/// the driver on Linux and Windows). This says nothing about display
+/// setup; see [`super::apply_optimizations`] for that.
+///
+/// # Returns
+/// `None` when no NVIDIA driver is loaded
+pub fn detect_cuda() -> Option<&'static CudaInfo> {
+    static CUDA: OnceLock<Option<CudaInfo>> = OnceLock::new();
+    CUDA.get_or_init(|| {
+        let cuda = probe();
+        match &cuda {
+            Some(cuda) => info!(
+                driver = %cuda.driver_version,
+                cuda_version = ?cuda.cuda_version,
+                devices = cuda.device_count(),
+                usable = cuda.is_usable(),
+                "CUDA driver detected"
+            ),
+            None => debug!("No CUDA driver found"),
+        }
+        cuda
+    })
+    .as_ref()
+}
+
+fn probe() -> Option<CudaInfo> {
+    let devices = run_command(
+        "nvidia-smi",
+        &[
+            "--query-gpu=index,name,compute_cap,memory.total,driver_version",
+            "--format=csv,noheader,nounits",
+        ],
+    )
+    // Drivers before 510 don't know compute_cap; ask again without it
+    .or_else(|| {
+        run_command(
+            "nvidia-smi",
+            &[
+                "--query-gpu=index,name,memory.total,driver_version",
+                "--format=csv,noheader,nounits",
+            ],
+        )
+    })?;
+
+    let (driver_version, devices) = parse_device_csv(&devices)?;
+    let cuda_version = run_command("nvidia-smi", &[]).and_then(|s| parse_cuda_version(&s));
+
+    Some(CudaInfo {
+        driver_version,
+        cuda_version,
+        devices,
+    })
+}
+
+/// Parse the device query; rows have 5 fields, or 4 without `compute_cap`
+///
+/// # Returns
+/// The driver version and devices, or `None` if no device row parsed
+pub fn parse_device_csv(output: &str) -> Option<(String, Vec<CudaDevice>)> {
+    let mut driver_version = None;
+    let mut devices = Vec::new();
+
+    for line in output.lines() {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let (index, name, capability, memory, driver) = match fields[..] {
+            [index, name, capability, memory, driver] => {
+                (index, name, Some(capability), memory, driver)
+            }
+            [index, name, memory, driver] => (index, name, None, memory, driver),
+            _ => continue,
+        };
+        let Ok(index) = index.parse() else {
+            continue;
+        };
+
+        driver_version.get_or_insert_with(|| driver.to_string());
+        devices.push(CudaDevice {
+            index,
+            name: name.to_string(),
+            compute_capability: capability.and_then(ComputeCapability::parse),
+            memory_total_mb: memory.parse().ok(),
+        });
+    }
+
+    Some((driver_version?, devices))
+}
+
+/// Pull "12.2" out of the `CUDA Version: 12.2` banner of plain `nvidia-smi`
+pub fn parse_cuda_version(output: &str) -> Option<String> {
+    let rest = &output[output.find("CUDA Version:")? + "CUDA Version:".len()..];
+    let version: String = rest
+        .trim_start()
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    (!version.is_empty()).then_some(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_device_csv() {
+        let output = "\
+0, NVIDIA GeForce RTX 3060, 8.6, 12288, 535.54.03
+1, Tesla K80, 3.7, 11441, 535.54.03
+";
+        let (driver, devices) = parse_device_csv(output).unwrap();
+        assert_eq!(driver, "535.54.03");
+        assert_eq!(devices.len(), 2);
+        assert_eq!(
+            devices[0].compute_capability,
+            Some(ComputeCapability { major: 8, minor: 6 })
+        );
+        assert!(devices[0].is_supported());
+        assert!(!devices[1].is_supported());
+    }
+
+    #[test]
+    fn test_parse_device_csv_old_driver() {
+        let (_, devices) = parse_device_csv("0, Quadro P2000, 5120, 470.182.03\n").unwrap();
+        assert_eq!(devices[0].compute_capability, None);
+        assert_eq!(devices[0].memory_total_mb, Some(5120));
+        assert!(!devices[0].is_supported());
+    }
+
+    #[test]
+    fn test_parse_device_csv_no_devices() {
+        assert!(parse_device_csv("").is_none());
+        assert!(parse_device_csv("No devices were found\n").is_none());
+    }
+
+    #[test]
+    fn test_parse_cuda_version() {
+        let banner = "\
++---------------------------------------------------------------------------------------+
+| NVIDIA-SMI 535.54.03              Driver Version: 535.54.03    CUDA Version: 12.2     |
+|-----------------------------------------+----------------------+----------------------+
+";
+        assert_eq!(parse_cuda_version(banner).as_deref(), Some("12.2"));
+        assert_eq!(parse_cuda_version("nothing here"), None);
+    }
+
+    #[test]
+    fn test_usable_needs_a_supported_device() {
+        let device = |cc| CudaDevice {
+            index: 0,
+            name: "GPU".to_string(),
+            compute_capability: ComputeCapability::parse(cc),
+            memory_total_mb: None,
+        };
+        let mut cuda = CudaInfo {
+            driver_version: "550".to_string(),
+            cuda_version: None,
+            devices: vec![device("3.5")],
+        };
+        assert!(!cuda.is_usable());
+        cuda.devices.push(device("7.5"));
+        assert!(cuda.is_usable());
+        assert_eq!(ComputeCapability::parse("7.5").unwrap().to_string(), "7.5");
+    }
+}
@@ -0,0 +1,334 @@
+// src-tauri/src/gpu/fallback.rs
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::info::ComputeBackend;
+use super::preference::InferenceDevice;
+use crate::error::{AnalysisError, AudioError};
+
+/// Event for a background job that falls back from the GPU to the CPU,
+/// carrying a [`GpuFallback`]
+pub const GPU_FALLBACK_EVENT: &str = "gpu-fallback";
+
+/// Devices whose failures will repeat for the rest of the process
+static DISABLED_DEVICES: Mutex<Vec<InferenceDevice>> = Mutex::new(Vec::new());
+
+/// Broad cause of a GPU initialization failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GpuFailureKind {
+    /// Driver too old for the runtime, or runtime and driver don't match
+    DriverMismatch,
+    /// Not enough video memory for the model
+    OutOfMemory,
+    /// The device can't run the compiled kernels
+    Unsupported,
+    Other,
+}
+
+impl GpuFailureKind {
+    /// Classify an error message from CUDA, HIP, Vulkan or Metal
+    pub fn from_message(message: &str) -> Self {
+        let lower = message.to_lowercase();
+        let any = |needles: &[&str]| needles.iter().any(|n| lower.contains(n));
+
+        if any(&[
+            "out of memory",
+            "out_of_memory",
+            "outofmemory",
+            "out_of_device_memory",
+        ]) {
+            Self::OutOfMemory
+        } else if any(&[
+            "driver version is insufficient",
+            "insufficient_driver",
+            "insufficientdriver",
+            "driver_mismatch",
+            "incompatible_driver",
+        ]) {
+            Self::DriverMismatch
+        } else if any(&[
+            "no kernel image",
+            "invalid device function",
+            "not supported",
+            "unsupported",
+        ]) {
+            Self::Unsupported
+        } else {
+            Self::Other
+        }
+    }
+
+    /// Retrying on the same device won't help (unlike running out of memory,
+    /// which depends on what else is using the GPU)
+    pub fn is_persistent(&self) -> bool {
+        matches!(self, Self::DriverMismatch | Self::Unsupported)
+    }
+}
+
+/// GPU acceleration couldn't be set up for a job
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuInitError {
+    pub kind: GpuFailureKind,
+    pub message: String,
+}
+
+impl GpuInitError {
+    /// Wrap an engine error message, classifying it with [`GpuFailureKind::from_message`]
+    pub fn new(message: impl Into<String>) -> Self {
+        let message = message.into();
+        Self {
+            kind: GpuFailureKind::from_message(&message),
+            message,
+        }
+    }
+}
+
+impl From<GpuInitError> for AudioError {
+    fn from(error: GpuInitError) -> Self {
+        AnalysisError::Engine(error.message).into()
+    }
+}
+
+/// Failure of a job that may run on the GPU
+#[derive(Debug)]
+pub enum AcceleratedError<E> {
+    /// Setting up the GPU failed; the job can be retried on the CPU
+    GpuInit(GpuInitError),
+    /// The job itself failed; retrying elsewhere won't help
+    Job(E),
+}
+
+/// Diagnostic for one fallback; the payload of [`GPU_FALLBACK_EVENT`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GpuFallback {
+    pub backend: ComputeBackend,
+    pub device_index: u32,
+    pub kind: GpuFailureKind,
+    /// Error message from the engine
+    pub message: String,
+    /// The device is skipped for the rest of the session
+    pub disabled: bool,
+}
+
+/// Where a job ended up running, for the caller to keep with the job
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobRun<T> {
+    pub output: T,
+    /// Device the job finished on; `None` means the CPU
+    pub device: Option<InferenceDevice>,
+    /// Set when the GPU was tried first and failed
+    pub fallback: Option<GpuFallback>,
+}
+
+/// Run a job on `device`, retrying once on the CPU if GPU setup fails
+///
+/// `run` is called with the device to use (`None` for CPU) and reports GPU
+/// setup problems as [`AcceleratedError::GpuInit`]. Those are logged, passed
+/// to `on_fallback` (e.g. to emit a [`GPU_FALLBACK_EVENT`]) and recorded in
+/// the returned [`JobRun`]. Persistent failures such as driver
+/// mismatches also disable the device so later jobs go straight to the CPU.
+///
+/// # Returns
+/// The job's output and where it ran, or the job's own error
+pub fn run_with_cpu_fallback<T, E, R, F>(
+    device: Option<InferenceDevice>,
+    mut run: R,
+    on_fallback: F,
+) -> Result<JobRun<T>, E>
+where
+    R: FnMut(Option<InferenceDevice>) -> Result<T, AcceleratedError<E>>,
+    F: FnOnce(&GpuFallback),
+    E: From<GpuInitError>,
+{
+    let device = device.filter(|d| !is_disabled(d));
+    let Some(gpu) = device else {
+        return run_on_cpu(run, None);
+    };
+
+    let error = match run(Some(gpu)) {
+        Ok(output) => {
+            return Ok(JobRun {
+                output,
+                device: Some(gpu),
+                fallback: None,
+            })
+        }
+        Err(AcceleratedError::Job(e)) => return Err(e),
+        Err(AcceleratedError::GpuInit(e)) => e,
+    };
+
+    let disabled = error.kind.is_persistent();
+    if disabled {
+        disable_device(gpu);
+    }
+    warn!(
+        backend = ?gpu.backend,
+        device = gpu.index,
+        kind = ?error.kind,
+        error = %error.message,
+        "GPU initialization failed; retrying on CPU"
+    );
+
+    let fallback = GpuFallback {
+        backend: gpu.backend,
+        device_index: gpu.index,
+        kind: error.kind,
+        message: error.message,
+        disabled,
+    };
+    on_fallback(&fallback);
+    run_on_cpu(run, Some(fallback))
+}
+
+fn run_on_cpu<T, E, R>(mut run: R, fallback: Option<GpuFallback>) -> Result<JobRun<T>, E>
+where
+    R: FnMut(Option<InferenceDevice>) -> Result<T, AcceleratedError<E>>,
+    E: From<GpuInitError>,
+{
+    match run(None) {
+        Ok(output) => Ok(JobRun {
+            output,
+            device: None,
+            fallback,
+        }),
+        // A CPU run has no GPU to set up; treat a stray report as a job error
+        Err(AcceleratedError::GpuInit(e)) => Err(e.into()),
+        Err(AcceleratedError::Job(e)) => Err(e),
+    }
+}
+
+/// Skip `device` for the rest of the process
+pub fn disable_device(device: InferenceDevice) {
+    let mut disabled = DISABLED_DEVICES.lock().unwrap_or_else(|e| e.into_inner());
+    if !disabled.contains(&device) {
+        disabled.push(device);
+    }
+}
+
+/// `device` failed persistently earlier in this process
+pub fn is_disabled(device: &InferenceDevice) -> bool {
+    DISABLED_DEVICES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .contains(device)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct JobError(String);
+
+    impl From<GpuInitError> for JobError {
+        fn from(e: GpuInitError) -> Self {
+            Self(e.message)
+        }
+    }
+
+    fn device(index: u32) -> InferenceDevice {
+        InferenceDevice {
+            backend: ComputeBackend::Vulkan,
+            index,
+        }
+    }
+
+    #[test]
+    fn test_classify_messages() {
+        let kind = GpuFailureKind::from_message;
+        assert_eq!(
+            kind("CUDA error: out of memory"),
+            GpuFailureKind::OutOfMemory
+        );
+        assert_eq!(
+            kind("VK_ERROR_OUT_OF_DEVICE_MEMORY"),
+            GpuFailureKind::OutOfMemory
+        );
+        assert_eq!(
+            kind("CUDA driver version is insufficient for CUDA runtime version"),
+            GpuFailureKind::DriverMismatch
+        );
+        assert_eq!(
+            kind("hipErrorNoBinaryForGpu: no kernel image"),
+            GpuFailureKind::Unsupported
+        );
+        assert_eq!(kind("something odd"), GpuFailureKind::Other);
+    }
+
+    #[test]
+    fn test_gpu_success_has_no_fallback() {
+        let run = run_with_cpu_fallback(
+            Some(device(100)),
+            |d| Ok::<_, AcceleratedError<JobError>>(d.is_some()),
+            |_| panic!("no fallback expected"),
+        )
+        .unwrap();
+        assert!(run.output);
+        assert_eq!(run.device, Some(device(100)));
+        assert_eq!(run.fallback, None);
+    }
+
+    #[test]
+    fn test_out_of_memory_retries_on_cpu() {
+        let mut events = Vec::new();
+        let run = run_with_cpu_fallback(
+            Some(device(101)),
+            |d| match d {
+                Some(_) => Err(AcceleratedError::<JobError>::GpuInit(GpuInitError::new(
+                    "CUDA_ERROR_OUT_OF_MEMORY",
+                ))),
+                None => Ok("cpu"),
+            },
+            |fallback| events.push(fallback.clone()),
+        )
+        .unwrap();
+
+        assert_eq!(run.output, "cpu");
+        assert_eq!(run.device, None);
+        let fallback = run.fallback.unwrap();
+        assert_eq!(fallback.kind, GpuFailureKind::OutOfMemory);
+        assert!(!fallback.disabled);
+        assert_eq!(events, [fallback]);
+        assert!(!is_disabled(&device(101)));
+    }
+
+    #[test]
+    fn test_driver_mismatch_disables_device() {
+        let gpu_failure = |d: Option<InferenceDevice>| match d {
+            Some(_) => Err(AcceleratedError::<JobError>::GpuInit(GpuInitError::new(
+                "VK_ERROR_INCOMPATIBLE_DRIVER",
+            ))),
+            None => Ok(()),
+        };
+        let first = run_with_cpu_fallback(Some(device(102)), gpu_failure, |_| {}).unwrap();
+        assert!(first.fallback.unwrap().disabled);
+        assert!(is_disabled(&device(102)));
+
+        // The next job skips the GPU without another failed attempt
+        let second = run_with_cpu_fallback(Some(device(102)), gpu_failure, |_| {
+            panic!("already disabled")
+        })
+        .unwrap();
+        assert_eq!(second.device, None);
+        assert_eq!(second.fallback, None);
+    }
+
+    #[test]
+    fn test_job_errors_are_not_retried() {
+        let mut attempts = 0;
+        let result = run_with_cpu_fallback(
+            Some(device(103)),
+            |_| {
+                attempts += 1;
+                Err::<(), _>(AcceleratedError::Job(JobError("bad input".to_string())))
+            },
+            |_| panic!("no fallback expected"),
+        );
+        assert_eq!(result.unwrap_err(), JobError("bad input".to_string()));
+        assert_eq!(attempts, 1);
+    }
+}
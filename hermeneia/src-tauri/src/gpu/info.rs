@@ -0,0 +1,182 @@
+// src-tauri/src/gpu/info.rs
+
+use serde::{Deserialize, Serialize};
+
+use super::cuda::CudaInfo;
+use super::macos::AppleSiliconInfo;
+use super::rocm::RocmInfo;
+use super::vulkan::VulkanInfo;
+
+/// GPU manufacturer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GpuVendor {
+    Nvidia,
+    Amd,
+    Intel,
+    Apple,
+    Unknown,
+}
+
+impl GpuVendor {
+    /// Guess the vendor from a device or vendor name as printed by system tools
+    pub fn from_name(name: &str) -> Self {
+        let lower = name.to_lowercase();
+        if lower.contains("nvidia") || lower.contains("geforce") || lower.contains("quadro") {
+            Self::Nvidia
+        } else if lower.contains("amd")
+            || lower.contains("ati ")
+            || lower.contains("radeon")
+            || lower.contains("advanced micro devices")
+        {
+            Self::Amd
+        } else if lower.contains("intel") {
+            Self::Intel
+        } else if lower.contains("apple") {
+            Self::Apple
+        } else {
+            Self::Unknown
+        }
+    }
+
+    /// Map a PCI vendor ID (as in sysfs, Vulkan and DXGI) to a vendor
+    pub fn from_pci_id(id: u16) -> Self {
+        match id {
+            0x10de => Self::Nvidia,
+            0x1002 | 0x1022 => Self::Amd,
+            0x8086 => Self::Intel,
+            0x106b => Self::Apple,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Compute APIs that inference engines can run on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComputeBackend {
+    Cuda,
+    Rocm,
+    Metal,
+    Vulkan,
+}
+
+/// One graphics adapter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GpuAdapter {
+    pub vendor: GpuVendor,
+    /// Model name, e.g. "GeForce RTX 3060 Mobile"
+    pub name: String,
+    /// Integrated into the CPU / SoC rather than a discrete card
+    pub integrated: bool,
+    /// Dedicated video memory in MiB, when the platform reports it
+    pub vram_total_mb: Option<u64>,
+    /// Video memory currently free in MiB, when the platform reports it
+    pub vram_free_mb: Option<u64>,
+    pub driver_version: Option<String>,
+}
+
+impl GpuAdapter {
+    /// Adapter with only a name known; the other details are filled in by later probes
+    pub fn named(vendor: GpuVendor, name: impl Into<String>, integrated: bool) -> Self {
+        Self {
+            vendor,
+            name: name.into(),
+            integrated,
+            vram_total_mb: None,
+            vram_free_mb: None,
+            driver_version: None,
+        }
+    }
+}
+
+/// Guess whether an adapter is integrated from its vendor and model name
+///
+/// Intel GPUs are integrated unless they're Arc cards, AMD ones unless
+/// they're Radeon RX; every NVIDIA GPU is treated as discrete.
+pub(crate) fn guess_integrated(vendor: GpuVendor, name: &str) -> bool {
+    let lower = name.to_lowercase();
+    match vendor {
+        GpuVendor::Intel => !lower.contains("arc"),
+        GpuVendor::Amd => !lower.contains("radeon rx"),
+        _ => false,
+    }
+}
+
+/// Everything the app knows about the machine's GPUs
+///
+/// Returned by the `get_gpu_info` Tauri command for the settings screen.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct GpuInfo {
+    pub adapters: Vec<GpuAdapter>,
+    /// Compute backends that look usable on this machine, most preferred first
+    pub backends: Vec<ComputeBackend>,
+    /// Backend inference uses by default; `None` means CPU
+    pub preferred_backend: Option<ComputeBackend>,
+    /// CUDA driver and devices, when an NVIDIA driver is loaded
+    pub cuda: Option<CudaInfo>,
+    /// ROCm release and GPU agents, when ROCm is installed
+    pub rocm: Option<RocmInfo>,
+    /// Hardware Vulkan devices, when a Vulkan loader is installed
+    pub vulkan: Option<VulkanInfo>,
+    /// Chip and unified memory details on Apple Silicon Macs
+    pub apple_silicon: Option<AppleSiliconInfo>,
+}
+
+impl GpuInfo {
+    /// An integrated and a discrete GPU are both present (e.g. a laptop with PRIME/Optimus)
+    pub fn is_hybrid(&self) -> bool {
+        self.adapters.iter().any(|a| a.integrated) && self.adapters.iter().any(|a| !a.integrated)
+    }
+
+    /// At least one adapter from `vendor` is present
+    pub fn has_vendor(&self, vendor: GpuVendor) -> bool {
+        self.adapters.iter().any(|a| a.vendor == vendor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vendor_from_name() {
+        assert_eq!(
+            GpuVendor::from_name("NVIDIA Corporation GA106M"),
+            GpuVendor::Nvidia
+        );
+        assert_eq!(
+            GpuVendor::from_name("Advanced Micro Devices, Inc. [AMD/ATI] Navi 23"),
+            GpuVendor::Amd
+        );
+        assert_eq!(
+            GpuVendor::from_name("Intel Corporation Alder Lake-P GT2"),
+            GpuVendor::Intel
+        );
+        assert_eq!(GpuVendor::from_name("Apple M2 Pro"), GpuVendor::Apple);
+        assert_eq!(GpuVendor::from_name("Matrox G200eR2"), GpuVendor::Unknown);
+    }
+
+    #[test]
+    fn test_vendor_from_pci_id() {
+        assert_eq!(GpuVendor::from_pci_id(0x10de), GpuVendor::Nvidia);
+        assert_eq!(GpuVendor::from_pci_id(0x1002), GpuVendor::Amd);
+        assert_eq!(GpuVendor::from_pci_id(0x8086), GpuVendor::Intel);
+        assert_eq!(GpuVendor::from_pci_id(0x1af4), GpuVendor::Unknown);
+    }
+
+    #[test]
+    fn test_hybrid_needs_both_kinds() {
+        let mut info = GpuInfo {
+            adapters: vec![GpuAdapter::named(GpuVendor::Nvidia, "RTX 3060", false)],
+            ..Default::default()
+        };
+        assert!(!info.is_hybrid());
+
+        info.adapters
+            .push(GpuAdapter::named(GpuVendor::Intel, "Iris Xe", true));
+        assert!(info.is_hybrid());
+        assert!(info.has_vendor(GpuVendor::Nvidia));
+        assert!(!info.has_vendor(GpuVendor::Amd));
+    }
+}
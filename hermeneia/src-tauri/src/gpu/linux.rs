@@ -0,0 +1,316 @@
+// src-tauri/src/gpu/linux.rs
+
+use std::fs;
+use std::path::Path;
+
+use tracing::{debug, info};
+
+use super::info::{guess_integrated, GpuAdapter, GpuVendor};
+
+/// Where the kernel lists DRM devices, one `cardN` entry per GPU
+const DRM_ROOT: &str = "/sys/class/drm";
+
+/// Where distros install the PCI ID database (hwdata / pciutils)
+const PCI_IDS_PATHS: [&str; 3] = [
+    "/usr/share/hwdata/pci.ids",
+    "/usr/share/misc/pci.ids",
+    "/usr/share/pci.ids",
+];
+
+/// PCI base class for display controllers (VGA, 3D and other)
+const PCI_CLASS_DISPLAY: u32 = 0x03;
+
+/// List graphics adapters from sysfs
+///
+/// Reads `/sys/class/drm` rather than running `lspci`, which minimal
+/// distros and containers often lack. Names come from the PCI ID database
+/// when one is installed.
+pub fn query_adapters() -> Vec<GpuAdapter> {
+    let pci_ids = PCI_IDS_PATHS.iter().find_map(|p| fs::read_to_string(p).ok());
+    let adapters = read_drm_adapters(Path::new(DRM_ROOT), pci_ids.as_deref());
+    debug!(adapters = adapters.len(), "GPU detection complete");
+    adapters
+}
+
+/// Collect the PCI graphics adapters under a `/sys/class/drm`-style directory
+///
+/// Each `cardN/device` holds the PCI `vendor`, `device` and `class` files
+/// and a `uevent` with the slot name; amdgpu also exposes
+/// `mem_info_vram_total`. Connector entries (`card0-HDMI-A-1`) and non-PCI
+/// devices are skipped. Adapters are returned in PCI bus order.
+///
+/// # Arguments
+/// * `drm_root` - `/sys/class/drm`, or a fixture directory in tests
+/// * `pci_ids` - Contents of `pci.ids`, used for marketing names
+pub fn read_drm_adapters(drm_root: &Path, pci_ids: Option<&str>) -> Vec<GpuAdapter> {
+    let Ok(entries) = fs::read_dir(drm_root) else {
+        return Vec::new();
+    };
+
+    let mut cards: Vec<(String, GpuAdapter)> = entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.strip_prefix("card")
+                .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+        })
+        .filter_map(|entry| read_pci_adapter(&entry.path().join("device"), pci_ids))
+        .collect();
+
+    cards.sort_by(|a, b| a.0.cmp(&b.0));
+    // One GPU can back several DRM cards (e.g. with simpledrm handing over)
+    cards.dedup_by(|a, b| a.0 == b.0);
+    cards.into_iter().map(|(_, adapter)| adapter).collect()
+}
+
+/// Read one PCI display device; returns its slot name and adapter
+fn read_pci_adapter(device: &Path, pci_ids: Option<&str>) -> Option<(String, GpuAdapter)> {
+    let read_hex = |file: &str| {
+        let value = fs::read_to_string(device.join(file)).ok()?;
+        u32::from_str_radix(value.trim().trim_start_matches("0x"), 16).ok()
+    };
+
+    let class = read_hex("class")?;
+    if class >> 16 != PCI_CLASS_DISPLAY {
+        return None;
+    }
+    let vendor_id = u16::try_from(read_hex("vendor")?).ok()?;
+    let device_id = u16::try_from(read_hex("device")?).ok()?;
+
+    let slot = fs::read_to_string(device.join("uevent"))
+        .ok()
+        .and_then(|uevent| {
+            uevent
+                .lines()
+                .find_map(|line| line.strip_prefix("PCI_SLOT_NAME="))
+                .map(str::to_string)
+        })
+        .unwrap_or_default();
+
+    let vendor = GpuVendor::from_pci_id(vendor_id);
+    let name = pci_ids.and_then(|ids| lookup_pci_name(ids, vendor_id, device_id));
+    let vram_total_mb = fs::read_to_string(device.join("mem_info_vram_total"))
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(|bytes| bytes / (1024 * 1024));
+
+    let integrated = match &name {
+        Some(name) => guess_integrated(vendor, name),
+        None => guess_integrated_by_location(vendor, &slot, vram_total_mb),
+    };
+    let name = name.unwrap_or_else(|| fallback_name(vendor, vendor_id, device_id));
+
+    let mut adapter = GpuAdapter::named(vendor, name, integrated);
+    adapter.vram_total_mb = vram_total_mb;
+    Some((slot, adapter))
+}
+
+/// Integrated-GPU guess for devices the PCI ID database doesn't name
+///
+/// Intel's integrated GPU always sits on the root bus (`0000:00:02.0`);
+/// AMD APUs only get a small carve-out of system memory as VRAM.
+fn guess_integrated_by_location(vendor: GpuVendor, slot: &str, vram_mb: Option<u64>) -> bool {
+    match vendor {
+        GpuVendor::Intel => slot.starts_with("0000:00:"),
+        GpuVendor::Amd => vram_mb.is_some_and(|mb| mb < 2048),
+        _ => false,
+    }
+}
+
+fn fallback_name(vendor: GpuVendor, vendor_id: u16, device_id: u16) -> String {
+    let label = match vendor {
+        GpuVendor::Nvidia => "NVIDIA GPU",
+        GpuVendor::Amd => "AMD GPU",
+        GpuVendor::Intel => "Intel GPU",
+        GpuVendor::Apple | GpuVendor::Unknown => "GPU",
+    };
+    format!("{label} [{vendor_id:04x}:{device_id:04x}]")
+}
+
+/// Look up a device name in `pci.ids`
+///
+/// Vendor lines look like `10de  NVIDIA Corporation` and are followed by
+/// tab-indented device lines such as `\t25a2  GA107M [GeForce RTX 3050 Mobile]`;
+/// the last bracketed part is the marketing name when there is one.
+pub fn lookup_pci_name(pci_ids: &str, vendor_id: u16, device_id: u16) -> Option<String> {
+    let vendor_prefix = format!("{vendor_id:04x}  ");
+    let device_prefix = format!("\t{device_id:04x}  ");
+
+    let mut lines = pci_ids
+        .lines()
+        .skip_while(|line| !line.starts_with(&vendor_prefix))
+        .skip(1);
+    let description = lines
+        .by_ref()
+        .take_while(|line| line.is_empty() || line.starts_with('\t') || line.starts_with('#'))
+        .find_map(|line| line.strip_prefix(&device_prefix))?;
+
+    let name = match (description.rfind('['), description.rfind(']')) {
+        (Some(open), Some(close)) if open < close => &description[open + 1..close],
+        _ => description,
+    };
+    Some(name.trim().to_string())
+}
+
+/// Set the environment variables WebKitGTK needs on NVIDIA machines
+///
+/// On hybrid laptops the discrete GPU is switched on with PRIME render
+/// offload, unless the user chose to render on the integrated GPU
+/// (`render_adapter`); with any NVIDIA GPU the DMA-BUF renderer is disabled
+/// because it renders a blank window with the proprietary driver.
+pub fn apply_nvidia_workarounds(adapters: &[GpuAdapter], render_adapter: Option<&GpuAdapter>) {
+    // Check if environment variables are already set (manual override)
+    if std::env::var("__NV_PRIME_RENDER_OFFLOAD").is_ok() {
+        info!("Manual NVIDIA settings detected");
+        return;
+    }
+
+    if !adapters.iter().any(|a| a.vendor == GpuVendor::Nvidia) {
+        return;
+    }
+
+    // Detect if this is a hybrid GPU setup
+    let is_hybrid = adapters.iter().any(|a| a.integrated);
+    let prefer_integrated = render_adapter.is_some_and(|a| a.integrated);
+
+    if is_hybrid && prefer_integrated {
+        info!("Detected NVIDIA hybrid setup - rendering on the integrated GPU per settings");
+        std::env::set_var("WEBKIT_DISABLE_DMABUF_RENDERER", "1");
+    } else if is_hybrid {
+        info!("Detected NVIDIA hybrid setup - enabling PRIME offload");
+
+        std::env::set_var("__NV_PRIME_RENDER_OFFLOAD", "1");
+        std::env::set_var("__GLX_VENDOR_LIBRARY_NAME", "nvidia");
+        std::env::set_var("WEBKIT_DISABLE_DMABUF_RENDERER", "1");
+    } else {
+        info!("NVIDIA discrete GPU detected");
+        // Apply WebKit fix for rendering issues
+        std::env::set_var("WEBKIT_DISABLE_DMABUF_RENDERER", "1");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const PCI_IDS: &str = "\
+# Comment line
+8086  Intel Corporation
+\t46a6  Alder Lake-P GT2 [Iris Xe Graphics]
+\t\t1028 0b19  Alder Lake-P GT2 [Iris Xe Graphics]
+10de  NVIDIA Corporation
+\t1f95  TU117M [GeForce GTX 1650 Ti Mobile]
+\t25a2  GA107M [GeForce RTX 3050 Mobile]
+1002  Advanced Micro Devices, Inc. [AMD/ATI]
+\t73ff  Navi 23 [Radeon RX 6600/6600 XT/6600M]
+";
+
+    /// A fake `/sys/class/drm` with one directory per card
+    struct Fixture(PathBuf);
+
+    impl Fixture {
+        fn new(name: &str) -> Self {
+            let root = std::env::temp_dir().join(format!("hermeneia_test_drm_{name}"));
+            let _ = fs::remove_dir_all(&root);
+            fs::create_dir_all(&root).unwrap();
+            Self(root)
+        }
+
+        fn card(self, card: &str, slot: &str, class: &str, vendor: &str, device: &str) -> Self {
+            let dir = self.0.join(card).join("device");
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("class"), format!("{class}\n")).unwrap();
+            fs::write(dir.join("vendor"), format!("{vendor}\n")).unwrap();
+            fs::write(dir.join("device"), format!("{device}\n")).unwrap();
+            fs::write(dir.join("uevent"), format!("DRIVER=x\nPCI_SLOT_NAME={slot}\n")).unwrap();
+            self
+        }
+
+        fn file(self, path: &str, contents: &str) -> Self {
+            fs::write(self.0.join(path), contents).unwrap();
+            self
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_read_hybrid_laptop() {
+        let drm = Fixture::new("hybrid")
+            .card("card1", "0000:01:00.0", "0x030200", "0x10de", "0x25a2")
+            .card("card0", "0000:00:02.0", "0x030000", "0x8086", "0x46a6");
+        fs::create_dir_all(drm.0.join("card0-eDP-1")).unwrap();
+
+        let adapters = read_drm_adapters(&drm.0, Some(PCI_IDS));
+        assert_eq!(adapters.len(), 2);
+
+        assert_eq!(adapters[0].vendor, GpuVendor::Intel);
+        assert_eq!(adapters[0].name, "Iris Xe Graphics");
+        assert!(adapters[0].integrated);
+
+        assert_eq!(adapters[1].vendor, GpuVendor::Nvidia);
+        assert_eq!(adapters[1].name, "GeForce RTX 3050 Mobile");
+        assert!(!adapters[1].integrated);
+    }
+
+    #[test]
+    fn test_read_amd_desktop() {
+        let drm = Fixture::new("amd")
+            .card("card0", "0000:03:00.0", "0x030000", "0x1002", "0x73ff")
+            .file("card0/device/mem_info_vram_total", "8573157376\n");
+
+        let adapters = read_drm_adapters(&drm.0, Some(PCI_IDS));
+        assert_eq!(adapters.len(), 1);
+        assert_eq!(adapters[0].vendor, GpuVendor::Amd);
+        assert_eq!(adapters[0].name, "Radeon RX 6600/6600 XT/6600M");
+        assert_eq!(adapters[0].vram_total_mb, Some(8176));
+        assert!(!adapters[0].integrated);
+    }
+
+    #[test]
+    fn test_read_without_pci_ids() {
+        // Minimal containers: no database, so fall back to IDs and bus location
+        let drm = Fixture::new("no_ids")
+            .card("card0", "0000:00:02.0", "0x030000", "0x8086", "0x46a6")
+            .card("card1", "0000:05:00.0", "0x030000", "0x1002", "0x1681")
+            .file("card1/device/mem_info_vram_total", "536870912\n")
+            .card("card2", "0000:01:00.0", "0x030200", "0x10de", "0x1f95");
+
+        let adapters = read_drm_adapters(&drm.0, None);
+        let names: Vec<&str> = adapters.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["Intel GPU [8086:46a6]", "NVIDIA GPU [10de:1f95]", "AMD GPU [1002:1681]"]
+        );
+        assert!(adapters[0].integrated);
+        assert!(!adapters[1].integrated);
+        assert!(adapters[2].integrated);
+    }
+
+    #[test]
+    fn test_read_skips_non_display_devices() {
+        let drm = Fixture::new("skip")
+            .card("card0", "0000:00:1f.3", "0x040300", "0x8086", "0x51c8");
+        fs::create_dir_all(drm.0.join("renderD128")).unwrap();
+
+        assert!(read_drm_adapters(&drm.0, Some(PCI_IDS)).is_empty());
+        assert!(read_drm_adapters(Path::new("/nonexistent/drm"), None).is_empty());
+    }
+
+    #[test]
+    fn test_lookup_pci_name() {
+        assert_eq!(
+            lookup_pci_name(PCI_IDS, 0x10de, 0x1f95).as_deref(),
+            Some("GeForce GTX 1650 Ti Mobile")
+        );
+        // Device IDs only match under their own vendor
+        assert_eq!(lookup_pci_name(PCI_IDS, 0x8086, 0x25a2), None);
+        assert_eq!(lookup_pci_name(PCI_IDS, 0x1234, 0x0001), None);
+    }
+}
@@ -0,0 +1,212 @@
+// src-tauri/src/gpu/macos.rs
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::info::{GpuAdapter, GpuVendor};
+use super::run_command;
+
+/// Graphics adapters as reported by `system_profiler`, plus Metal support
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MacDisplays {
+    pub adapters: Vec<GpuAdapter>,
+    /// At least one adapter reports a Metal GPU family
+    pub metal: bool,
+    /// Metal family of the first adapter that reports one, e.g. "metal3"
+    pub metal_family: Option<String>,
+    /// GPU core count, reported for Apple Silicon only
+    pub gpu_cores: Option<u32>,
+}
+
+/// Apple Silicon chip, whose GPU shares memory with the CPU
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppleSiliconInfo {
+    /// Chip name, e.g. "Apple M2 Pro"
+    pub chip: String,
+    /// Unified memory shared by CPU and GPU, in MiB
+    pub unified_memory_mb: Option<u64>,
+    pub gpu_cores: Option<u32>,
+    /// Highest Metal family supported, e.g. "metal3"
+    pub metal_family: Option<String>,
+}
+
+/// True on Apple Silicon, including x86_64 builds running under Rosetta
+///
+/// Only uses `sysctl`, so it is cheap enough to call at startup.
+pub fn is_apple_silicon() -> bool {
+    if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
+        return true;
+    }
+    cfg!(target_os = "macos")
+        && run_command("sysctl", &["-n", "hw.optional.arm64"]).is_some_and(|v| v.trim() == "1")
+}
+
+/// Describe the Apple Silicon chip, or `None` on Intel Macs and other platforms
+///
+/// GPU core count and Metal family come from an earlier
+/// [`query_displays`] call; the chip name and memory size from `sysctl`.
+pub fn apple_silicon_info(displays: &MacDisplays) -> Option<AppleSiliconInfo> {
+    if !is_apple_silicon() {
+        return None;
+    }
+
+    let chip = run_command("sysctl", &["-n", "machdep.cpu.brand_string"])
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .or_else(|| {
+            displays
+                .adapters
+                .iter()
+                .find(|a| a.vendor == GpuVendor::Apple)
+                .map(|a| a.name.clone())
+        })
+        .unwrap_or_else(|| "Apple Silicon".to_string());
+
+    Some(AppleSiliconInfo {
+        chip,
+        unified_memory_mb: run_command("sysctl", &["-n", "hw.memsize"])
+            .and_then(|v| parse_memsize_mb(&v)),
+        gpu_cores: displays.gpu_cores,
+        metal_family: displays.metal_family.clone(),
+    })
+}
+
+/// Convert the byte count printed by `sysctl -n hw.memsize` to MiB
+fn parse_memsize_mb(value: &str) -> Option<u64> {
+    value.trim().parse::<u64>().ok().map(|bytes| bytes / (1024 * 1024))
+}
+
+/// Query `system_profiler SPDisplaysDataType`
+pub fn query_displays() -> MacDisplays {
+    run_command("system_profiler", &["SPDisplaysDataType", "-json"])
+        .map(|stdout| parse_system_profiler(&stdout))
+        .unwrap_or_default()
+}
+
+/// Parse the JSON printed by `system_profiler SPDisplaysDataType -json`
+pub fn parse_system_profiler(output: &str) -> MacDisplays {
+    let Ok(root) = serde_json::from_str::<Value>(output) else {
+        return MacDisplays::default();
+    };
+    let Some(entries) = root["SPDisplaysDataType"].as_array() else {
+        return MacDisplays::default();
+    };
+
+    let mut displays = MacDisplays::default();
+    for entry in entries {
+        let field = |key: &str| entry[key].as_str().unwrap_or_default();
+        let name = match field("sppci_model") {
+            "" => field("_name"),
+            model => model,
+        };
+        if name.is_empty() {
+            continue;
+        }
+
+        // "sppci_vendor_Apple", "sppci_vendor_amd", or a plain name on older releases
+        let vendor = match GpuVendor::from_name(field("spdisplays_vendor")) {
+            GpuVendor::Unknown => GpuVendor::from_name(name),
+            vendor => vendor,
+        };
+
+        let mut adapter = GpuAdapter::named(
+            vendor,
+            name,
+            field("sppci_bus") == "spdisplays_builtin",
+        );
+        adapter.vram_total_mb = parse_size_mb(field("spdisplays_vram"));
+
+        let family = field("spdisplays_mtlgpufamilysupport");
+        if !family.is_empty() {
+            displays.metal = true;
+            displays
+                .metal_family
+                .get_or_insert_with(|| family.trim_start_matches("spdisplays_").to_string());
+        }
+        if displays.gpu_cores.is_none() {
+            displays.gpu_cores = field("sppci_cores").parse().ok();
+        }
+        displays.adapters.push(adapter);
+    }
+
+    displays
+}
+
+/// Parse sizes like "4 GB" or "1536 MB" into MiB
+fn parse_size_mb(value: &str) -> Option<u64> {
+    let (number, unit) = value.trim().split_once(' ')?;
+    let number: u64 = number.parse().ok()?;
+    match unit {
+        "MB" => Some(number),
+        "GB" => Some(number * 1024),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_apple_silicon() {
+        let output = r#"{"SPDisplaysDataType":[{
+            "_name":"Apple M1 Pro",
+            "spdisplays_mtlgpufamilysupport":"spdisplays_metal3",
+            "spdisplays_vendor":"sppci_vendor_Apple",
+            "sppci_bus":"spdisplays_builtin",
+            "sppci_cores":"16",
+            "sppci_device_type":"spdisplays_gpu",
+            "sppci_model":"Apple M1 Pro"
+        }]}"#;
+        let displays = parse_system_profiler(output);
+        assert!(displays.metal);
+        assert_eq!(displays.metal_family.as_deref(), Some("metal3"));
+        assert_eq!(displays.gpu_cores, Some(16));
+        assert_eq!(displays.adapters.len(), 1);
+        assert_eq!(displays.adapters[0].vendor, GpuVendor::Apple);
+        assert_eq!(displays.adapters[0].name, "Apple M1 Pro");
+        assert!(displays.adapters[0].integrated);
+        assert_eq!(displays.adapters[0].vram_total_mb, None);
+    }
+
+    #[test]
+    fn test_parse_intel_mac_with_radeon() {
+        let output = r#"{"SPDisplaysDataType":[
+            {"_name":"Intel UHD Graphics 630","spdisplays_vendor":"Intel",
+             "spdisplays_vram_shared":"1536 MB","sppci_bus":"spdisplays_builtin",
+             "sppci_model":"Intel UHD Graphics 630"},
+            {"_name":"AMD Radeon Pro 5500M","spdisplays_vendor":"sppci_vendor_amd",
+             "spdisplays_vram":"4 GB","sppci_bus":"spdisplays_pcie_device",
+             "spdisplays_mtlgpufamilysupport":"spdisplays_metal3",
+             "sppci_model":"AMD Radeon Pro 5500M"}
+        ]}"#;
+        let displays = parse_system_profiler(output);
+        assert!(displays.metal);
+        assert_eq!(displays.adapters.len(), 2);
+        assert!(displays.adapters[0].integrated);
+        assert_eq!(displays.adapters[1].vendor, GpuVendor::Amd);
+        assert_eq!(displays.adapters[1].vram_total_mb, Some(4096));
+        assert!(!displays.adapters[1].integrated);
+        assert_eq!(displays.gpu_cores, None);
+    }
+
+    #[test]
+    fn test_parse_memsize() {
+        assert_eq!(parse_memsize_mb("17179869184\n"), Some(16384));
+        assert_eq!(parse_memsize_mb(""), None);
+    }
+
+    #[test]
+    fn test_apple_silicon_only_on_macos() {
+        if !cfg!(target_os = "macos") {
+            assert!(!is_apple_silicon());
+            assert_eq!(apple_silicon_info(&MacDisplays::default()), None);
+        }
+    }
+
+    #[test]
+    fn test_parse_garbage() {
+        assert_eq!(parse_system_profiler("not json"), MacDisplays::default());
+        assert_eq!(parse_system_profiler("{}"), MacDisplays::default());
+    }
+}
@@ -0,0 +1,244 @@
+// src-tauri/src/gpu/mod.rs
+// GPU detection and platform-specific rendering workarounds
+
+pub mod cuda;
+pub mod fallback;
+pub mod info;
+pub mod linux;
+pub mod macos;
+pub mod nvidia;
+pub mod preference;
+pub mod rocm;
+pub mod vulkan;
+pub mod windows;
+
+// Re-export commonly used items
+pub use cuda::{detect_cuda, ComputeCapability, CudaDevice, CudaInfo};
+pub use fallback::{
+    run_with_cpu_fallback, AcceleratedError, GpuFailureKind, GpuFallback, GpuInitError, JobRun,
+    GPU_FALLBACK_EVENT,
+};
+pub use info::{ComputeBackend, GpuAdapter, GpuInfo, GpuVendor};
+pub use macos::AppleSiliconInfo;
+pub use preference::{
+    inference_capabilities, select_inference_device, GpuPreference, InferenceCapabilities,
+    InferenceDevice,
+};
+pub use rocm::{detect_rocm, RocmDevice, RocmInfo};
+pub use vulkan::{detect_vulkan, VulkanDevice, VulkanInfo};
+
+use tracing::debug;
+
+/// Automatically detect and apply GPU optimizations
+///
+/// Call once at startup, before any webview exists. `preference` carries the
+/// user's saved render GPU on hybrid machines.
+///
+/// On macOS this is a no-op apart from logging: WebKit needs no workarounds
+/// there, and the inference backend is picked by [`preferred_backend`] when
+/// a job runs.
+pub fn apply_optimizations(preference: &GpuPreference) {
+    #[cfg(target_os = "linux")]
+    {
+        let adapters = linux::query_adapters();
+        linux::apply_nvidia_workarounds(&adapters, preference.render_adapter_in(&adapters));
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        // Nothing to apply; say which backend inference will default to
+        if preference.inference_backend.is_none() && macos::is_apple_silicon() {
+            tracing::info!("Apple Silicon detected - using Metal for inference by default");
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let adapters = windows::query_adapters();
+        windows::apply_webview2_workarounds(&adapters, preference.render_adapter_in(&adapters));
+    }
+}
+
+/// Backend the inference engine should use unless the user picks another
+///
+/// CUDA, then ROCm, then Metal on Apple Silicon, then Vulkan; `None` means
+/// run on the CPU. Detection results are cached, so this is cheap after the
+/// first call.
+pub fn preferred_backend() -> Option<ComputeBackend> {
+    if detect_cuda().is_some_and(CudaInfo::is_usable) {
+        return Some(ComputeBackend::Cuda);
+    }
+    if cfg!(target_os = "linux") && detect_rocm().is_some_and(RocmInfo::is_usable) {
+        return Some(ComputeBackend::Rocm);
+    }
+    if cfg!(target_os = "macos") {
+        // Intel Macs have Metal too, but their GPUs rarely beat the CPU path
+        return macos::is_apple_silicon().then_some(ComputeBackend::Metal);
+    }
+    if detect_vulkan().is_some_and(VulkanInfo::is_usable) {
+        return Some(ComputeBackend::Vulkan);
+    }
+    None
+}
+
+/// Detect the machine's GPUs and which compute backends are usable
+///
+/// Adapters come from sysfs on Linux, `system_profiler` on macOS and WMI
+/// on Windows. Where the NVIDIA driver is installed, `nvidia-smi` adds
+/// memory and driver details for NVIDIA cards.
+///
+/// Backends are listed in order of preference: CUDA when a device meets
+/// [`cuda::MIN_COMPUTE_CAPABILITY`], ROCm when `rocminfo` sees a GPU agent,
+/// Metal on macOS, and Vulkan when a hardware Vulkan device exists.
+///
+/// This spawns helper processes, so call it off the UI thread.
+pub fn query_gpu_info() -> GpuInfo {
+    let mut info = GpuInfo::default();
+
+    #[cfg(target_os = "linux")]
+    {
+        info.adapters = linux::query_adapters();
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        info.adapters = windows::query_adapters();
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let displays = macos::query_displays();
+        info.apple_silicon = macos::apple_silicon_info(&displays);
+        // Every Apple Silicon GPU supports Metal; on Intel Macs it depends on the card
+        if displays.metal || info.apple_silicon.is_some() {
+            info.backends.push(ComputeBackend::Metal);
+        }
+        info.adapters = displays.adapters;
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        if let Some(nvidia) = nvidia::query_adapters() {
+            merge_nvidia(&mut info.adapters, nvidia);
+        }
+
+        info.cuda = detect_cuda().cloned();
+        if info.cuda.as_ref().is_some_and(CudaInfo::is_usable) {
+            info.backends.push(ComputeBackend::Cuda);
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        info.rocm = detect_rocm().cloned();
+        if info.rocm.as_ref().is_some_and(RocmInfo::is_usable) {
+            info.backends.push(ComputeBackend::Rocm);
+        }
+    }
+
+    // Vendor-neutral fallback; on macOS Metal covers the same hardware
+    #[cfg(not(target_os = "macos"))]
+    {
+        info.vulkan = detect_vulkan().cloned();
+        if info.vulkan.as_ref().is_some_and(VulkanInfo::is_usable) {
+            info.backends.push(ComputeBackend::Vulkan);
+        }
+    }
+
+    info.preferred_backend = preferred_backend();
+
+    debug!(
+        adapters = info.adapters.len(),
+        backends = ?info.backends,
+        "GPU info collected"
+    );
+    info
+}
+
+/// Fold `nvidia-smi` details into the adapters found by the platform probe
+///
+/// Both list cards in PCI bus order, so the n-th NVIDIA adapter gets the
+/// n-th `nvidia-smi` row. Rows without a match are added as new adapters.
+fn merge_nvidia(adapters: &mut Vec<GpuAdapter>, nvidia: Vec<GpuAdapter>) {
+    let mut rows = nvidia.into_iter();
+    for adapter in adapters
+        .iter_mut()
+        .filter(|a| a.vendor == GpuVendor::Nvidia)
+    {
+        let Some(row) = rows.next() else {
+            return;
+        };
+        *adapter = row;
+    }
+    adapters.extend(rows);
+}
+
+/// Run a helper program and return its stdout, or `None` if it can't be run or fails
+pub(crate) fn run_command(program: &str, args: &[&str]) -> Option<String> {
+    let mut command = std::process::Command::new(program);
+    command.args(args);
+
+    // Don't flash a console window from the GUI process
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    match command.output() {
+        Ok(output) if output.status.success() => String::from_utf8(output.stdout).ok(),
+        Ok(output) => {
+            debug!(program, status = %output.status, "GPU probe exited with an error");
+            None
+        }
+        Err(e) => {
+            debug!(program, error = %e, "GPU probe not available");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_optimizations_doesnt_panic() {
+        // Just verify it doesn't crash
+        apply_optimizations(&GpuPreference::default());
+    }
+
+    #[test]
+    fn test_query_gpu_info_doesnt_panic() {
+        // CI machines usually have no GPU; only check the result is consistent
+        let info = query_gpu_info();
+        if info.backends.contains(&ComputeBackend::Cuda) {
+            assert!(info.has_vendor(GpuVendor::Nvidia));
+            assert!(info.cuda.is_some());
+        }
+        if let Some(backend) = info.preferred_backend {
+            assert!(info.backends.contains(&backend));
+        }
+    }
+
+    #[test]
+    fn test_merge_nvidia_details() {
+        let mut adapters = vec![
+            GpuAdapter::named(GpuVendor::Intel, "Iris Xe Graphics", true),
+            GpuAdapter::named(GpuVendor::Nvidia, "GeForce RTX 3050 Mobile", false),
+        ];
+        let smi = nvidia::parse_smi_csv("NVIDIA GeForce RTX 3050 Laptop GPU, 4096, 3900, 550.1\n");
+        merge_nvidia(&mut adapters, smi);
+
+        assert_eq!(adapters.len(), 2);
+        assert_eq!(adapters[0].name, "Iris Xe Graphics");
+        assert_eq!(adapters[1].name, "NVIDIA GeForce RTX 3050 Laptop GPU");
+        assert_eq!(adapters[1].vram_total_mb, Some(4096));
+
+        // Nothing from the platform probe (e.g. WMI unavailable): rows become adapters
+        let mut empty = Vec::new();
+        merge_nvidia(&mut empty, nvidia::parse_smi_csv("Tesla T4, 15360, 15000, 535.0\n"));
+        assert_eq!(empty.len(), 1);
+    }
+}
@@ -0,0 +1,67 @@
+// src-tauri/src/gpu/nvidia.rs
+
+use super::info::{GpuAdapter, GpuVendor};
+use super::run_command;
+
+/// Fields requested from `nvidia-smi`, in output order
+const QUERY: &str = "--query-gpu=name,memory.total,memory.free,driver_version";
+
+/// Ask the NVIDIA driver about its GPUs
+///
+/// `nvidia-smi` ships with the driver on Linux and Windows, so this works
+/// wherever CUDA could. Returns `None` when the tool is missing or fails
+/// (no NVIDIA driver loaded).
+pub fn query_adapters() -> Option<Vec<GpuAdapter>> {
+    let stdout = run_command("nvidia-smi", &[QUERY, "--format=csv,noheader,nounits"])?;
+    Some(parse_smi_csv(&stdout))
+}
+
+/// Parse `nvidia-smi --format=csv,noheader,nounits` output for [`QUERY`]
+pub fn parse_smi_csv(output: &str) -> Vec<GpuAdapter> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [name, total, free, driver] = fields[..] else {
+                return None;
+            };
+            // Older cards print "[N/A]" for fields they don't support
+            let number = |field: &str| field.parse::<u64>().ok();
+            Some(GpuAdapter {
+                vendor: GpuVendor::Nvidia,
+                name: name.to_string(),
+                integrated: false,
+                vram_total_mb: number(total),
+                vram_free_mb: number(free),
+                driver_version: Some(driver.to_string()).filter(|d| !d.starts_with('[')),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_smi_csv() {
+        let output = "\
+NVIDIA GeForce RTX 3060 Laptop GPU, 6144, 5731, 535.54.03
+Tesla K80, [N/A], [N/A], [N/A]
+";
+        let adapters = parse_smi_csv(output);
+        assert_eq!(adapters.len(), 2);
+        assert_eq!(adapters[0].name, "NVIDIA GeForce RTX 3060 Laptop GPU");
+        assert_eq!(adapters[0].vram_total_mb, Some(6144));
+        assert_eq!(adapters[0].vram_free_mb, Some(5731));
+        assert_eq!(adapters[0].driver_version.as_deref(), Some("535.54.03"));
+        assert_eq!(adapters[1].vram_total_mb, None);
+        assert_eq!(adapters[1].driver_version, None);
+    }
+
+    #[test]
+    fn test_parse_smi_ignores_noise() {
+        assert!(parse_smi_csv("").is_empty());
+        assert!(parse_smi_csv("No devices were found\n").is_empty());
+    }
+}
@@ -0,0 +1,189 @@
+// src-tauri/src/gpu/preference.rs
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::cuda::{detect_cuda, CudaInfo};
+use super::info::{ComputeBackend, GpuAdapter};
+use super::rocm::{detect_rocm, RocmInfo};
+use super::vulkan::{detect_vulkan, VulkanInfo};
+
+/// The user's GPU choices, stored in the app settings
+///
+/// Everything defaults to `None`, meaning "let the app decide".
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GpuPreference {
+    /// Adapter the UI renders on, by name as in [`GpuAdapter::name`]
+    ///
+    /// Only matters on hybrid machines and takes effect at the next start.
+    pub render_adapter: Option<String>,
+    /// Backend to run inference on; `None` uses [`super::preferred_backend`]
+    pub inference_backend: Option<ComputeBackend>,
+    /// Device index within the backend (CUDA ordinal, ROCm agent, ...)
+    pub inference_device: Option<u32>,
+}
+
+impl GpuPreference {
+    /// Find the chosen render adapter among the detected ones
+    ///
+    /// # Returns
+    /// `None` when no adapter was chosen or it is no longer present
+    pub fn render_adapter_in<'a>(&self, adapters: &'a [GpuAdapter]) -> Option<&'a GpuAdapter> {
+        let name = self.render_adapter.as_deref()?;
+        let adapter = adapters.iter().find(|a| a.name == name);
+        if adapter.is_none() {
+            warn!(
+                adapter = name,
+                "Preferred render GPU not found; using the default"
+            );
+        }
+        adapter
+    }
+}
+
+/// Where the inference engine should run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InferenceDevice {
+    pub backend: ComputeBackend,
+    /// Device index within the backend
+    pub index: u32,
+}
+
+/// Pick the inference device, honouring the user's preference when it still applies
+///
+/// Called by the inference engine at init. A preferred backend or device
+/// that is no longer available (driver removed, card swapped) falls back to
+/// the automatic choice with a warning rather than failing, as does one
+/// disabled after a persistent GPU failure earlier in the session.
+///
+/// # Returns
+/// `None` to run on the CPU
+pub fn select_inference_device(preference: &GpuPreference) -> Option<InferenceDevice> {
+    if let Some(backend) = preference.inference_backend {
+        let indices = device_indices(backend);
+        let index = preference
+            .inference_device
+            .or(indices.first().copied())
+            .unwrap_or(0);
+        let device = InferenceDevice { backend, index };
+        if indices.contains(&index) && !super::fallback::is_disabled(&device) {
+            return Some(device);
+        }
+        warn!(?backend, index, "Preferred inference device missing; using default");
+    }
+
+    let backend = super::preferred_backend()?;
+    let index = device_indices(backend).first().copied()?;
+    Some(InferenceDevice { backend, index }).filter(|device| !super::fallback::is_disabled(device))
+}
+
+/// What inference can run on here, for the `get_inference_capabilities` command
+///
+/// Unlike [`super::GpuInfo`] this skips the adapter probes and only reads
+/// the cached backend detections, so it is cheap enough for the engine to
+/// ask at init.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InferenceCapabilities {
+    /// CUDA driver and devices, when an NVIDIA driver is loaded
+    pub cuda: Option<CudaInfo>,
+    /// ROCm release and GPU agents, when ROCm is installed
+    pub rocm: Option<RocmInfo>,
+    /// Hardware Vulkan devices, when a Vulkan loader is installed
+    pub vulkan: Option<VulkanInfo>,
+    /// Backend inference uses by default; `None` means CPU
+    pub preferred_backend: Option<ComputeBackend>,
+    /// Device the next job would run on with the saved preference; `None` means CPU
+    pub device: Option<InferenceDevice>,
+}
+
+impl InferenceCapabilities {
+    /// GPU inference is an option on this machine
+    pub fn gpu_available(&self) -> bool {
+        self.device.is_some()
+    }
+}
+
+/// Report the inference backends and the device `preference` resolves to
+pub fn inference_capabilities(preference: &GpuPreference) -> InferenceCapabilities {
+    InferenceCapabilities {
+        cuda: detect_cuda().cloned(),
+        rocm: detect_rocm().cloned(),
+        vulkan: detect_vulkan().cloned(),
+        preferred_backend: super::preferred_backend(),
+        device: select_inference_device(preference),
+    }
+}
+
+/// Indices of the usable devices for `backend` on this machine
+///
+/// Vulkan numbers devices with the software ones included, so its indices
+/// can have gaps.
+fn device_indices(backend: ComputeBackend) -> Vec<u32> {
+    let count = match backend {
+        ComputeBackend::Cuda => detect_cuda()
+            .filter(|c| c.is_usable())
+            .map_or(0, CudaInfo::device_count),
+        ComputeBackend::Rocm => detect_rocm().map_or(0, RocmInfo::device_count),
+        ComputeBackend::Metal => usize::from(super::preferred_backend() == Some(backend)),
+        ComputeBackend::Vulkan => {
+            return detect_vulkan().map_or_else(Vec::new, |vulkan| {
+                vulkan.devices.iter().map(|d| d.index).collect()
+            })
+        }
+    };
+    (0..count as u32).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::GpuVendor;
+
+    #[test]
+    fn test_render_adapter_lookup() {
+        let adapters = vec![
+            GpuAdapter::named(GpuVendor::Intel, "Iris Xe Graphics", true),
+            GpuAdapter::named(GpuVendor::Nvidia, "GeForce RTX 3050 Mobile", false),
+        ];
+        let mut preference = GpuPreference::default();
+        assert_eq!(preference.render_adapter_in(&adapters), None);
+
+        preference.render_adapter = Some("Iris Xe Graphics".to_string());
+        assert_eq!(preference.render_adapter_in(&adapters), Some(&adapters[0]));
+
+        preference.render_adapter = Some("Radeon RX 580".to_string());
+        assert_eq!(preference.render_adapter_in(&adapters), None);
+    }
+
+    #[test]
+    fn test_missing_fields_default() {
+        let preference: GpuPreference =
+            serde_json::from_str(r#"{"inference_backend":"vulkan"}"#).unwrap();
+        assert_eq!(preference.inference_backend, Some(ComputeBackend::Vulkan));
+        assert_eq!(preference.render_adapter, None);
+    }
+
+    #[test]
+    fn test_unavailable_preference_falls_back() {
+        // An index no machine has must never be returned as-is
+        let preference = GpuPreference {
+            inference_backend: Some(ComputeBackend::Cuda),
+            inference_device: Some(u32::MAX),
+            ..Default::default()
+        };
+        let selected = select_inference_device(&preference);
+        assert_ne!(selected.map(|d| d.index), Some(u32::MAX));
+    }
+
+    #[test]
+    fn test_capabilities_match_the_selection() {
+        // CI machines usually have no GPU; only check the parts agree
+        let capabilities = inference_capabilities(&GpuPreference::default());
+        assert_eq!(capabilities.device, select_inference_device(&GpuPreference::default()));
+        if let Some(device) = capabilities.device {
+            assert_eq!(capabilities.preferred_backend, Some(device.backend));
+        }
+        assert_eq!(capabilities.gpu_available(), capabilities.device.is_some());
+    }
+}
@@ -0,0 +1,181 @@
+// src-tauri/src/gpu/rocm.rs
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use super::run_command;
+
+/// One GPU agent as enumerated by the ROCm runtime
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RocmDevice {
+    /// Position among GPU agents, as used by `HIP_VISIBLE_DEVICES`
+    pub index: u32,
+    /// Marketing name, e.g. "AMD Radeon RX 6800"
+    pub name: String,
+    /// LLVM target the kernels are compiled for, e.g. "gfx1030"
+    pub gfx_target: String,
+}
+
+/// What the installed ROCm stack offers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RocmInfo {
+    /// ROCm release, e.g. "6.0.2", when the install records it
+    pub version: Option<String>,
+    pub devices: Vec<RocmDevice>,
+}
+
+impl RocmInfo {
+    pub fn device_count(&self) -> usize {
+        self.devices.len()
+    }
+
+    /// At least one GPU agent is available
+    pub fn is_usable(&self) -> bool {
+        !self.devices.is_empty()
+    }
+}
+
+/// Detect ROCm support, once per process
+///
+/// Runs `rocminfo` (shipped with the ROCm runtime) and reads the release
+/// from `$ROCM_PATH/.info/version`. Linux only in practice; elsewhere the
+/// tool is simply missing.
+///
+/// # Returns
+/// `None` when ROCm isn't installed or sees no GPU
+pub fn detect_rocm() -> Option<&'static RocmInfo> {
+    static ROCM: OnceLock<Option<RocmInfo>> = OnceLock::new();
+    ROCM.get_or_init(|| {
+        let rocm = probe();
+        match &rocm {
+            Some(rocm) => info!(
+                version = ?rocm.version,
+                devices = rocm.device_count(),
+                "ROCm runtime detected"
+            ),
+            None => debug!("No ROCm runtime found"),
+        }
+        rocm
+    })
+    .as_ref()
+}
+
+fn probe() -> Option<RocmInfo> {
+    let devices = parse_rocminfo(&run_command("rocminfo", &[])?);
+    if devices.is_empty() {
+        return None;
+    }
+
+    let root = std::env::var_os("ROCM_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("/opt/rocm"));
+    let version = std::fs::read_to_string(root.join(".info").join("version"))
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+
+    Some(RocmInfo { version, devices })
+}
+
+/// Pick the GPU agents out of `rocminfo` output
+///
+/// Each agent is a block of `Key: value` lines; CPU agents are skipped.
+pub fn parse_rocminfo(output: &str) -> Vec<RocmDevice> {
+    let mut devices = Vec::new();
+    let mut name = None;
+    let mut marketing_name = None;
+    let mut is_gpu = false;
+
+    let mut flush = |name: &mut Option<String>, marketing: &mut Option<String>, is_gpu: bool| {
+        if let (true, Some(gfx)) = (is_gpu, name.take()) {
+            devices.push(RocmDevice {
+                index: devices.len() as u32,
+                name: marketing.take().unwrap_or_else(|| gfx.clone()),
+                gfx_target: gfx,
+            });
+        }
+        *name = None;
+        *marketing = None;
+    };
+
+    for line in output.lines() {
+        let line = line.trim();
+        if line.starts_with("Agent ") {
+            flush(&mut name, &mut marketing_name, is_gpu);
+            is_gpu = false;
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            // Only the first Name/Marketing Name of an agent; pools and ISAs repeat "Name"
+            "Name" if name.is_none() => name = Some(value.to_string()),
+            "Marketing Name" if marketing_name.is_none() => {
+                marketing_name = Some(value.to_string())
+            }
+            "Device Type" => is_gpu = value == "GPU",
+            _ => {}
+        }
+    }
+    flush(&mut name, &mut marketing_name, is_gpu);
+
+    devices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROCMINFO: &str = "\
+ROCk module is loaded
+=====================
+HSA System Attributes
+=====================
+Runtime Version:         1.1
+*******
+Agent 1
+*******
+  Name:                    AMD Ryzen 9 5900X 12-Core Processor
+  Marketing Name:          AMD Ryzen 9 5900X 12-Core Processor
+  Vendor Name:             CPU
+  Device Type:             CPU
+*******
+Agent 2
+*******
+  Name:                    gfx1030
+  Uuid:                    GPU-6f8e2cf2a1b3c4d5
+  Marketing Name:          AMD Radeon RX 6800
+  Vendor Name:             AMD
+  Device Type:             GPU
+  ISA Info:
+    ISA 1
+      Name:                    amdgcn-amd-amdhsa--gfx1030
+*** Done ***
+";
+
+    #[test]
+    fn test_parse_rocminfo() {
+        let devices = parse_rocminfo(ROCMINFO);
+        assert_eq!(
+            devices,
+            vec![RocmDevice {
+                index: 0,
+                name: "AMD Radeon RX 6800".to_string(),
+                gfx_target: "gfx1030".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_rocminfo_cpu_only() {
+        let cpu_only = ROCMINFO.split("Agent 2").next().unwrap();
+        assert!(parse_rocminfo(cpu_only).is_empty());
+        assert!(parse_rocminfo("").is_empty());
+    }
+}
@@ -0,0 +1,217 @@
+// src-tauri/src/gpu/vulkan.rs
+
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use super::info::GpuVendor;
+use super::run_command;
+
+/// One Vulkan physical device
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VulkanDevice {
+    /// Physical device index as Vulkan enumerates it, software devices included
+    pub index: u32,
+    pub name: String,
+    pub vendor: GpuVendor,
+    /// Highest Vulkan API version the driver exposes, e.g. "1.3.246"
+    pub api_version: String,
+    /// Driver name and version, e.g. "Mesa 23.1.3"
+    pub driver_info: Option<String>,
+    pub integrated: bool,
+}
+
+/// Vulkan devices that can run compute work
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VulkanInfo {
+    pub devices: Vec<VulkanDevice>,
+}
+
+impl VulkanInfo {
+    pub fn device_count(&self) -> usize {
+        self.devices.len()
+    }
+
+    /// At least one hardware device is available
+    pub fn is_usable(&self) -> bool {
+        !self.devices.is_empty()
+    }
+
+    /// The hardware device with this physical device index
+    pub fn device(&self, index: u32) -> Option<&VulkanDevice> {
+        self.devices.iter().find(|d| d.index == index)
+    }
+}
+
+/// Detect Vulkan compute support, once per process
+///
+/// Runs `vulkaninfo --summary` (from the Vulkan tools / SDK). Software
+/// rasterizers such as llvmpipe are left out since they would be slower
+/// than the CPU inference path.
+///
+/// # Returns
+/// `None` when no Vulkan loader or hardware device is found
+pub fn detect_vulkan() -> Option<&'static VulkanInfo> {
+    static VULKAN: OnceLock<Option<VulkanInfo>> = OnceLock::new();
+    VULKAN
+        .get_or_init(|| {
+            let devices = run_command("vulkaninfo", &["--summary"])
+                .map(|s| parse_vulkaninfo_summary(&s))
+                .unwrap_or_default();
+            if devices.is_empty() {
+                debug!("No Vulkan hardware device found");
+                return None;
+            }
+            info!(devices = devices.len(), "Vulkan devices detected");
+            Some(VulkanInfo { devices })
+        })
+        .as_ref()
+}
+
+/// Parse the `Devices:` section of `vulkaninfo --summary`
+///
+/// Each device is a `GPUn:` header followed by `key = value` lines. A
+/// device keeps the `n` from its header, so indices stay the ones Vulkan
+/// uses even with software devices left out.
+pub fn parse_vulkaninfo_summary(output: &str) -> Vec<VulkanDevice> {
+    #[derive(Default)]
+    struct Fields {
+        index: u32,
+        name: Option<String>,
+        api_version: Option<String>,
+        vendor_id: Option<u16>,
+        device_type: Option<String>,
+        driver_info: Option<String>,
+    }
+
+    let mut blocks: Vec<Fields> = Vec::new();
+    for line in output.lines() {
+        let line = line.trim();
+        let header = line.strip_prefix("GPU").and_then(|rest| rest.strip_suffix(':'));
+        if let Some(index) = header.and_then(|n| n.parse().ok()) {
+            blocks.push(Fields {
+                index,
+                ..Default::default()
+            });
+            continue;
+        }
+        let (Some(block), Some((key, value))) = (blocks.last_mut(), line.split_once('=')) else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match key.trim() {
+            "deviceName" => block.name = Some(value),
+            "apiVersion" => block.api_version = Some(value),
+            "vendorID" => {
+                block.vendor_id = u16::from_str_radix(value.trim_start_matches("0x"), 16).ok()
+            }
+            "deviceType" => block.device_type = Some(value),
+            "driverInfo" => block.driver_info = Some(value).filter(|v| !v.is_empty()),
+            _ => {}
+        }
+    }
+
+    blocks
+        .into_iter()
+        .filter(|b| {
+            matches!(
+                b.device_type.as_deref(),
+                Some("PHYSICAL_DEVICE_TYPE_DISCRETE_GPU" | "PHYSICAL_DEVICE_TYPE_INTEGRATED_GPU")
+            )
+        })
+        .filter_map(|b| {
+            let name = b.name?;
+            let vendor = match b.vendor_id.map(GpuVendor::from_pci_id) {
+                Some(GpuVendor::Unknown) | None => GpuVendor::from_name(&name),
+                Some(vendor) => vendor,
+            };
+            Some(VulkanDevice {
+                index: b.index,
+                vendor,
+                api_version: b.api_version.unwrap_or_default(),
+                driver_info: b.driver_info,
+                integrated: b.device_type.as_deref()
+                    == Some("PHYSICAL_DEVICE_TYPE_INTEGRATED_GPU"),
+                name,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUMMARY: &str = "\
+==========
+VULKANINFO
+==========
+
+Vulkan Instance Version: 1.3.246
+
+Devices:
+========
+GPU0:
+\tapiVersion         = 1.3.246
+\tdriverVersion      = 23.1.3
+\tvendorID           = 0x1002
+\tdeviceID           = 0x73bf
+\tdeviceType         = PHYSICAL_DEVICE_TYPE_DISCRETE_GPU
+\tdeviceName         = AMD Radeon RX 6800 (RADV NAVI21)
+\tdriverID           = DRIVER_ID_MESA_RADV
+\tdriverName         = radv
+\tdriverInfo         = Mesa 23.1.3
+GPU1:
+\tapiVersion         = 1.3.246
+\tvendorID           = 0x10005
+\tdeviceType         = PHYSICAL_DEVICE_TYPE_CPU
+\tdeviceName         = llvmpipe (LLVM 15.0.7, 256 bits)
+\tdriverInfo         = Mesa 23.1.3 (LLVM 15.0.7)
+";
+
+    #[test]
+    fn test_parse_summary_skips_software_devices() {
+        let devices = parse_vulkaninfo_summary(SUMMARY);
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].index, 0);
+        assert_eq!(devices[0].name, "AMD Radeon RX 6800 (RADV NAVI21)");
+        assert_eq!(devices[0].vendor, GpuVendor::Amd);
+        assert_eq!(devices[0].api_version, "1.3.246");
+        assert_eq!(devices[0].driver_info.as_deref(), Some("Mesa 23.1.3"));
+        assert!(!devices[0].integrated);
+    }
+
+    #[test]
+    fn test_parse_summary_keeps_vulkan_indices() {
+        // Some drivers enumerate llvmpipe ahead of the real GPU
+        let summary = "\
+Devices:
+========
+GPU0:
+\tapiVersion         = 1.3.246
+\tvendorID           = 0x10005
+\tdeviceType         = PHYSICAL_DEVICE_TYPE_CPU
+\tdeviceName         = llvmpipe (LLVM 15.0.7, 256 bits)
+GPU1:
+\tapiVersion         = 1.3.250
+\tvendorID           = 0x8086
+\tdeviceType         = PHYSICAL_DEVICE_TYPE_INTEGRATED_GPU
+\tdeviceName         = Intel(R) UHD Graphics 620 (KBL GT2)
+";
+        let devices = parse_vulkaninfo_summary(summary);
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].index, 1);
+        assert!(devices[0].integrated);
+
+        let info = VulkanInfo { devices };
+        assert!(info.device(0).is_none());
+        assert_eq!(info.device(1).map(|d| d.vendor), Some(GpuVendor::Intel));
+    }
+
+    #[test]
+    fn test_parse_summary_without_devices() {
+        assert!(parse_vulkaninfo_summary("").is_empty());
+        assert!(parse_vulkaninfo_summary("ERROR: [Loader Message] no ICD\n").is_empty());
+    }
+}
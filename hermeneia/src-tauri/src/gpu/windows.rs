@@ -0,0 +1,183 @@
+// src-tauri/src/gpu/windows.rs
+
+use serde::Deserialize;
+use tracing::{debug, info};
+
+use super::info::{guess_integrated, GpuAdapter, GpuVendor};
+use super::run_command;
+
+/// PowerShell script listing video controllers as JSON
+///
+/// `Win32_VideoController.AdapterRAM` is a 32-bit field that tops out at
+/// 4 GiB, so the full dedicated memory size is read from the display
+/// driver's registry key (the value DXGI reports), matched by name.
+const QUERY_SCRIPT: &str = r#"
+$mem = @{}
+Get-ItemProperty 'HKLM:\SYSTEM\CurrentControlSet\Control\Class\{4d36e968-e325-11ce-bfc1-08002be10318}\0*' -ErrorAction SilentlyContinue |
+    ForEach-Object { if ($_.'HardwareInformation.qwMemorySize') { $mem[$_.DriverDesc] = [uint64]$_.'HardwareInformation.qwMemorySize' } }
+ConvertTo-Json -Compress -InputObject @(Get-CimInstance Win32_VideoController | ForEach-Object {
+    [pscustomobject]@{
+        Name = $_.Name
+        AdapterRAM = $_.AdapterRAM
+        DriverVersion = $_.DriverVersion
+        PNPDeviceID = $_.PNPDeviceID
+        MemorySize = $mem[$_.Name]
+    }
+})
+"#;
+
+/// Browser arguments WebView2 reads at startup
+const WEBVIEW2_ARGS_VAR: &str = "WEBVIEW2_ADDITIONAL_BROWSER_ARGUMENTS";
+
+/// One `Win32_VideoController` row from [`QUERY_SCRIPT`]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct VideoController {
+    name: Option<String>,
+    #[serde(rename = "AdapterRAM")]
+    adapter_ram: Option<u64>,
+    driver_version: Option<String>,
+    #[serde(rename = "PNPDeviceID")]
+    pnp_device_id: Option<String>,
+    memory_size: Option<u64>,
+}
+
+/// List graphics adapters through WMI
+pub fn query_adapters() -> Vec<GpuAdapter> {
+    match run_command(
+        "powershell",
+        &["-NoProfile", "-NonInteractive", "-Command", QUERY_SCRIPT],
+    ) {
+        Some(stdout) => {
+            let adapters = parse_video_controllers(&stdout);
+            debug!(adapters = adapters.len(), "GPU detection complete");
+            adapters
+        }
+        None => Vec::new(),
+    }
+}
+
+/// Parse the JSON printed by [`QUERY_SCRIPT`]
+///
+/// Software adapters (Microsoft Basic Display / Remote Display) are left
+/// out. The vendor comes from the PCI ID in `PNPDeviceID`, e.g.
+/// `PCI\VEN_10DE&DEV_25A2&...`, falling back to the name.
+pub fn parse_video_controllers(output: &str) -> Vec<GpuAdapter> {
+    // A single object comes out when an older PowerShell unwraps the array
+    let controllers = match serde_json::from_str::<Vec<VideoController>>(output) {
+        Ok(controllers) => controllers,
+        Err(_) => match serde_json::from_str::<VideoController>(output) {
+            Ok(controller) => vec![controller],
+            Err(_) => return Vec::new(),
+        },
+    };
+
+    controllers
+        .into_iter()
+        .filter_map(|c| {
+            let name = c.name.filter(|n| !n.is_empty())?;
+            let pci_vendor = c.pnp_device_id.as_deref().and_then(parse_pci_vendor);
+            // 0x1414 is Microsoft's software renderers
+            if pci_vendor == Some(0x1414) || name.starts_with("Microsoft ") {
+                return None;
+            }
+
+            let vendor = match pci_vendor.map(GpuVendor::from_pci_id) {
+                Some(GpuVendor::Unknown) | None => GpuVendor::from_name(&name),
+                Some(vendor) => vendor,
+            };
+            let mut adapter = GpuAdapter::named(vendor, &name, guess_integrated(vendor, &name));
+            adapter.vram_total_mb = c
+                .memory_size
+                .or(c.adapter_ram)
+                .filter(|bytes| *bytes > 0)
+                .map(|bytes| bytes / (1024 * 1024));
+            adapter.driver_version = c.driver_version.filter(|v| !v.is_empty());
+            Some(adapter)
+        })
+        .collect()
+}
+
+/// Pull the vendor ID out of a `PCI\VEN_xxxx&DEV_xxxx...` device ID
+fn parse_pci_vendor(pnp_device_id: &str) -> Option<u16> {
+    let start = pnp_device_id.find("VEN_")? + "VEN_".len();
+    u16::from_str_radix(pnp_device_id.get(start..start + 4)?, 16).ok()
+}
+
+/// Configure WebView2 for hybrid-graphics laptops
+///
+/// With switchable graphics ANGLE can end up on its D3D11on12 or OpenGL
+/// backends, which flicker or render a blank window when Windows moves the
+/// app between GPUs. Pinning ANGLE to D3D11 avoids that. If the user chose
+/// the discrete GPU (`render_adapter`), Chromium is asked for the
+/// high-performance adapter too. Must run before the first webview is
+/// created.
+pub fn apply_webview2_workarounds(adapters: &[GpuAdapter], render_adapter: Option<&GpuAdapter>) {
+    // Check if browser arguments are already set (manual override)
+    if std::env::var_os(WEBVIEW2_ARGS_VAR).is_some() {
+        info!("Manual WebView2 browser arguments detected");
+        return;
+    }
+
+    let is_hybrid = adapters.iter().any(|a| a.integrated) && adapters.iter().any(|a| !a.integrated);
+    if !is_hybrid {
+        return;
+    }
+
+    if render_adapter.is_some_and(|a| !a.integrated) {
+        info!("Detected hybrid GPU setup - rendering on the discrete GPU per settings");
+        std::env::set_var(WEBVIEW2_ARGS_VAR, "--use-angle=d3d11 --force_high_performance_gpu");
+    } else {
+        info!("Detected hybrid GPU setup - pinning WebView2 to ANGLE D3D11");
+        std::env::set_var(WEBVIEW2_ARGS_VAR, "--use-angle=d3d11");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HYBRID_LAPTOP: &str = r#"[
+        {"Name":"Intel(R) Iris(R) Xe Graphics","AdapterRAM":1073741824,"DriverVersion":"31.0.101.4502",
+         "PNPDeviceID":"PCI\\VEN_8086&DEV_9A49&SUBSYS_0A1C1028&REV_01\\3&11583659&0&10","MemorySize":134217728},
+        {"Name":"NVIDIA GeForce RTX 3060 Laptop GPU","AdapterRAM":4293918720,"DriverVersion":"31.0.15.3623",
+         "PNPDeviceID":"PCI\\VEN_10DE&DEV_2520&SUBSYS_0A1C1028&REV_A1\\4&2283F625&0&0008","MemorySize":6442450944},
+        {"Name":"Microsoft Remote Display Adapter","AdapterRAM":null,"DriverVersion":"10.0.22621.1",
+         "PNPDeviceID":"SWD\\REMOTEDISPLAYENUM\\RDPIDD","MemorySize":null}
+    ]"#;
+
+    #[test]
+    fn test_parse_hybrid_laptop() {
+        let adapters = parse_video_controllers(HYBRID_LAPTOP);
+        assert_eq!(adapters.len(), 2);
+
+        assert_eq!(adapters[0].vendor, GpuVendor::Intel);
+        assert!(adapters[0].integrated);
+        assert_eq!(adapters[0].vram_total_mb, Some(128));
+
+        assert_eq!(adapters[1].vendor, GpuVendor::Nvidia);
+        assert!(!adapters[1].integrated);
+        // The registry size beats the 4 GiB-capped AdapterRAM
+        assert_eq!(adapters[1].vram_total_mb, Some(6144));
+        assert_eq!(adapters[1].driver_version.as_deref(), Some("31.0.15.3623"));
+    }
+
+    #[test]
+    fn test_parse_single_object() {
+        let output = r#"{"Name":"AMD Radeon RX 6800","AdapterRAM":4293918720,"DriverVersion":null,
+            "PNPDeviceID":"PCI\\VEN_1002&DEV_73BF","MemorySize":null}"#;
+        let adapters = parse_video_controllers(output);
+        assert_eq!(adapters.len(), 1);
+        assert_eq!(adapters[0].vendor, GpuVendor::Amd);
+        assert!(!adapters[0].integrated);
+        assert_eq!(adapters[0].vram_total_mb, Some(4095));
+        assert_eq!(adapters[0].driver_version, None);
+    }
+
+    #[test]
+    fn test_parse_garbage() {
+        assert!(parse_video_controllers("").is_empty());
+        assert!(parse_video_controllers("Get-CimInstance : Access denied").is_empty());
+        assert_eq!(parse_pci_vendor("ROOT\\BasicDisplay\\0000"), None);
+    }
+}
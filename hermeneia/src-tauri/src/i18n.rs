@@ -0,0 +1,234 @@
+// src-tauri/src/i18n.rs
+// Localizable messages for everything shown in the frontend
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::error::{AnalysisError, AudioError, DecodeError, EncodeError, PlaybackError};
+
+/// A user-facing message the frontend can translate
+///
+/// `key` selects the translation and `params` fill its `{placeholders}`;
+/// `text` is the English message, shown when the current locale has no
+/// translation for `key`. Keys are stable: errors use `error.` plus
+/// [`AudioError::code_name`], progress stages use `stage.` plus the stage
+/// name.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Message {
+    /// Translation key, e.g. `"error.decode.no_audio_track"`
+    pub key: String,
+    /// Values for the translation's placeholders, by name
+    pub params: BTreeMap<&'static str, String>,
+    /// English default
+    pub text: String,
+}
+
+impl Message {
+    /// A message without parameters
+    pub fn new(key: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            params: BTreeMap::new(),
+            text: text.into(),
+        }
+    }
+
+    /// Add a placeholder value
+    pub fn with_param(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.params.insert(name, value.to_string());
+        self
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl From<&AudioError> for Message {
+    fn from(error: &AudioError) -> Self {
+        let message = Message::new(format!("error.{}", error.code_name()), error.to_string());
+        error_params(error)
+            .into_iter()
+            .fold(message, |message, (name, value)| message.with_param(name, value))
+    }
+}
+
+impl From<AudioError> for Message {
+    fn from(error: AudioError) -> Self {
+        Message::from(&error)
+    }
+}
+
+/// Label for a progress stage (see [`crate::progress::ProgressSink::stage`])
+///
+/// Unknown stages get a generic "Working" text but keep their own key, so a
+/// translation can still be added for them.
+pub fn stage_label(stage: &str) -> Message {
+    let text = match stage {
+        "probe" => "Reading file",
+        "decode" => "Decoding",
+        "resample" => "Resampling",
+        "analyze" => "Analyzing",
+        "encode" => "Encoding",
+        "transcribe" => "Transcribing",
+        "translate" => "Translating",
+        "pipeline" => "Processing",
+        "download" => "Downloading",
+        "trim" => "Trimming",
+        "convert" => "Converting",
+        "write" => "Writing",
+        "render" => "Rendering",
+        "redact" => "Redacting",
+        "verify" => "Verifying",
+        "serialize" => "Preparing results",
+        _ => "Working",
+    };
+    Message::new(format!("stage.{}", stage), text)
+}
+
+/// Named values inside an error, for translations to place
+///
+/// Text from other libraries (I/O errors, codec messages) can't be
+/// translated and is passed through as `detail`.
+fn error_params(error: &AudioError) -> Vec<(&'static str, String)> {
+    let detail = |d: &dyn fmt::Display| vec![("detail", d.to_string())];
+    match error {
+        AudioError::FileOpen { path, source } => {
+            vec![("path", path.clone()), ("detail", source.to_string())]
+        }
+        AudioError::UnsupportedFormat(d)
+        | AudioError::ResampleFailed(d)
+        | AudioError::InvalidParameter(d)
+        | AudioError::InvalidTrimParams(d)
+        | AudioError::RenderFailed(d)
+        | AudioError::InvalidFeed(d)
+        | AudioError::Storage(d) => detail(d),
+        AudioError::TrimRangeOutOfBounds {
+            start,
+            end,
+            duration,
+        } => vec![
+            ("start", start.to_string()),
+            ("end", end.to_string()),
+            ("duration", duration.to_string()),
+        ],
+        AudioError::MemoryBudgetExceeded {
+            required_mb,
+            budget_mb,
+        } => vec![
+            ("required_mb", required_mb.to_string()),
+            ("budget_mb", budget_mb.to_string()),
+        ],
+        AudioError::Io(e) => detail(e),
+        AudioError::Cancelled => Vec::new(),
+        AudioError::Download { url, reason } => {
+            vec![("url", url.clone()), ("reason", reason.clone())]
+        }
+        AudioError::Decode(e) => match e {
+            DecodeError::Probe(d)
+            | DecodeError::Codec(d)
+            | DecodeError::Packet(d)
+            | DecodeError::Watchdog(d)
+            | DecodeError::Malformed(d)
+            | DecodeError::Chapters(d)
+            | DecodeError::Subtitles(d) => detail(d),
+            DecodeError::NoAudioTrack => Vec::new(),
+            DecodeError::MissingInfo(what) => vec![("what", what.to_string())],
+            DecodeError::EmptyRange { start, end } => {
+                vec![("start", start.to_string()), ("end", end.to_string())]
+            }
+        },
+        AudioError::Encode(e) => match e {
+            EncodeError::Config { format, message }
+            | EncodeError::Init { format, message }
+            | EncodeError::Codec { format, message } => {
+                vec![("format", format.to_string()), ("detail", message.clone())]
+            }
+        },
+        AudioError::Playback(e) => match e {
+            PlaybackError::NoDevice => Vec::new(),
+            PlaybackError::Device(d) | PlaybackError::Thread(d) => detail(d),
+        },
+        AudioError::Analysis(e) => match e {
+            AnalysisError::EmptyFormat {
+                channels,
+                sample_rate,
+            } => vec![
+                ("channels", channels.to_string()),
+                ("sample_rate", sample_rate.to_string()),
+            ],
+            AnalysisError::FormatMismatch {
+                reference,
+                candidate,
+            } => vec![("reference", reference.clone()), ("candidate", candidate.clone())],
+            AnalysisError::Engine(d) => detail(d),
+            AnalysisError::Unmeasurable => Vec::new(),
+            AnalysisError::ModelDamaged { model, reason } => {
+                vec![("model", model.clone()), ("reason", reason.clone())]
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_message_has_key_params_and_english() {
+        let error = AudioError::from(DecodeError::EmptyRange {
+            start: 1.5,
+            end: 2.0,
+        });
+        let message = Message::from(&error);
+        assert_eq!(message.key, "error.decode.empty_range");
+        assert_eq!(message.params["start"], "1.5");
+        assert_eq!(message.params["end"], "2");
+        assert_eq!(message.text, error.to_string());
+
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["key"], "error.decode.empty_range");
+        assert_eq!(json["params"]["start"], "1.5");
+    }
+
+    #[test]
+    fn test_stage_labels() {
+        assert_eq!(stage_label("decode").key, "stage.decode");
+        assert_eq!(stage_label("decode").text, "Decoding");
+        let unknown = stage_label("denoise");
+        assert_eq!((unknown.key.as_str(), unknown.text.as_str()), ("stage.denoise", "Working"));
+    }
+
+    /// Every `stage("...")` in the crate's source has its own label
+    #[test]
+    fn test_every_emitted_stage_has_a_label() {
+        fn emitted(dir: &std::path::Path, stages: &mut Vec<String>) {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    emitted(&path, stages);
+                } else if path.extension().is_some_and(|e| e == "rs") {
+                    let source = std::fs::read_to_string(&path).unwrap();
+                    for rest in source.split("stage(\"").skip(1) {
+                        let name = rest.split('"').next().unwrap_or_default();
+                        if !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase()) {
+                            stages.push(name.to_string());
+                        }
+                    }
+                }
+            }
+        }
+
+        let src = std::path::Path::new(file!()).parent().unwrap();
+        let mut stages = Vec::new();
+        emitted(src, &mut stages);
+        assert!(stages.iter().any(|s| s == "decode"), "no stages found in {}", src.display());
+        for stage in stages.iter().filter(|s| s.as_str() != "denoise") {
+            assert_ne!(stage_label(stage).text, "Working", "no label for {:?}", stage);
+        }
+    }
+}
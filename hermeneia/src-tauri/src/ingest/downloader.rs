@@ -0,0 +1,296 @@
+// src-tauri/src/ingest/downloader.rs
+// Fetch audio from media URLs with a user-installed yt-dlp
+
+//! Media URLs through an external downloader
+//!
+//! Video and streaming sites change too often for the app to keep up, so
+//! their URLs are handed to [yt-dlp](https://github.com/yt-dlp/yt-dlp),
+//! which the user installs and updates. It runs as a helper process; its
+//! progress is read from stdout and reported like any other stage.
+
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::mpsc::{self, RecvTimeoutError};
+
+use serde::Serialize;
+use tracing::debug;
+
+use crate::error::{AudioError, Result};
+use crate::jobs::{queue_transcription, Priority};
+use crate::progress::{check_cancelled, ProgressSink};
+use crate::settings::Settings;
+
+use super::{helper_command, DownloadOptions, POLL_INTERVAL};
+
+/// Executable looked for on the `PATH`
+#[cfg(target_os = "windows")]
+const PROGRAM: &str = "yt-dlp.exe";
+#[cfg(not(target_os = "windows"))]
+const PROGRAM: &str = "yt-dlp";
+
+/// Folder inside the library that downloads go to
+const DOWNLOADS_DIR: &str = "Downloads";
+
+/// yt-dlp output template for the downloaded file's name
+const OUTPUT_TEMPLATE: &str = "%(title)s [%(id)s].%(ext)s";
+
+/// Prefer audio in a container the decoder reads (MP4), then any audio
+const FORMAT: &str = "bestaudio[ext=m4a]/bestaudio/best";
+
+/// Marks our progress lines in the downloader's output
+const PROGRESS_PREFIX: &str = "hermeneia-progress ";
+
+/// Marks the line naming the finished file
+const FILE_PREFIX: &str = "hermeneia-file ";
+
+/// The downloader for the settings screen
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DownloaderInfo {
+    pub program: PathBuf,
+    /// As reported by `--version`; `None` if it wouldn't run
+    pub version: Option<String>,
+}
+
+/// A yt-dlp executable
+#[derive(Debug, Clone)]
+pub struct ExternalDownloader {
+    program: PathBuf,
+}
+
+impl ExternalDownloader {
+    pub fn new(program: impl Into<PathBuf>) -> Self {
+        Self {
+            program: program.into(),
+        }
+    }
+
+    /// The downloader chosen in the settings, else yt-dlp on the `PATH`
+    pub fn find(settings: &Settings) -> Option<Self> {
+        if let Some(program) = &settings.downloader {
+            return Some(Self::new(program));
+        }
+        let path = std::env::var_os("PATH")?;
+        std::env::split_paths(&path)
+            .map(|dir| dir.join(PROGRAM))
+            .find(|candidate| candidate.is_file())
+            .map(Self::new)
+    }
+
+    pub fn program(&self) -> &Path {
+        &self.program
+    }
+
+    /// Run `--version` to check the downloader works
+    pub fn info(&self) -> DownloaderInfo {
+        let version = helper_command(&self.program)
+            .arg("--version")
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+        DownloaderInfo {
+            program: self.program.clone(),
+            version,
+        }
+    }
+
+    /// Download a URL's audio into the library's `Downloads` folder
+    ///
+    /// Playlists are not expanded; only the linked item is fetched. With
+    /// [`DownloadOptions::transcribe`], the file is added to the
+    /// transcription queue.
+    ///
+    /// # Returns
+    /// Where the file was saved
+    pub fn download(
+        &self,
+        url: &str,
+        options: &DownloadOptions,
+        progress: &mut dyn ProgressSink,
+    ) -> Result<PathBuf> {
+        let folder = options.library_dir.join(DOWNLOADS_DIR);
+        std::fs::create_dir_all(&folder)?;
+        progress.stage("download");
+        progress.message(url);
+
+        let mut command = helper_command(&self.program);
+        command
+            .args(["--newline", "--no-playlist", "--progress", "--windows-filenames"])
+            .args(["--format", FORMAT])
+            .arg("--progress-template")
+            .arg(format!(
+                "download:{}%(progress.downloaded_bytes)s \
+                 %(progress.total_bytes,progress.total_bytes_estimate)s",
+                PROGRESS_PREFIX
+            ))
+            .arg("--print")
+            .arg(format!("after_move:{}%(filepath)s", FILE_PREFIX))
+            .arg("--output")
+            .arg(folder.join(OUTPUT_TEMPLATE))
+            .args(["--", url])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let mut child = command.spawn().map_err(|e| AudioError::Download {
+            url: url.to_string(),
+            reason: format!("couldn't run {}: {}", self.program.display(), e),
+        })?;
+
+        // Both pipes are drained on their own threads so neither can fill up
+        // and stall the downloader
+        let (lines, received) = mpsc::channel();
+        let stdout = child.stdout.take().expect("stdout is piped");
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(|l| l.ok()) {
+                if lines.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+        let errors = std::thread::spawn(move || {
+            let mut text = String::new();
+            stderr.read_to_string(&mut text).ok();
+            text
+        });
+
+        let mut file = None;
+        loop {
+            match received.recv_timeout(POLL_INTERVAL) {
+                Ok(line) => {
+                    if let Some(path) = line.strip_prefix(FILE_PREFIX) {
+                        file = Some(PathBuf::from(path));
+                    } else if let Some(fraction) = parse_progress(&line) {
+                        progress.progress(fraction);
+                    } else {
+                        debug!(line, "Downloader output");
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+            if let Err(e) = check_cancelled(progress) {
+                child.kill().ok();
+                child.wait().ok();
+                return Err(e);
+            }
+        }
+
+        let status = child.wait()?;
+        let errors = errors.join().unwrap_or_default();
+        let fail = |reason: String| AudioError::Download {
+            url: url.to_string(),
+            reason,
+        };
+        if !status.success() {
+            return Err(fail(error_reason(&errors).unwrap_or_else(|| status.to_string())));
+        }
+        let file = file
+            .filter(|file| file.is_file())
+            .ok_or_else(|| fail("the downloader didn't report a file".to_string()))?;
+
+        progress.progress(1.0);
+        if options.transcribe {
+            queue_transcription(&file, Priority::Normal);
+        }
+        Ok(file)
+    }
+}
+
+/// Download a URL's audio with the downloader from the settings
+///
+/// See [`ExternalDownloader::download`].
+pub fn download_url(
+    url: &str,
+    options: &DownloadOptions,
+    progress: &mut dyn ProgressSink,
+) -> Result<PathBuf> {
+    let downloader =
+        ExternalDownloader::find(&Settings::load()).ok_or_else(|| AudioError::Download {
+            url: url.to_string(),
+            reason: "yt-dlp is not installed".to_string(),
+        })?;
+    downloader.download(url, options, progress)
+}
+
+/// Fraction done from one of our progress lines; `None` for other lines
+/// and while the size is unknown (`NA`)
+fn parse_progress(line: &str) -> Option<f64> {
+    let (done, total) = line.strip_prefix(PROGRESS_PREFIX)?.split_once(' ')?;
+    let done: f64 = done.trim().parse().ok()?;
+    let total: f64 = total.trim().parse().ok()?;
+    (total > 0.0).then(|| (done / total).clamp(0.0, 1.0))
+}
+
+/// The downloader's own explanation of a failure
+fn error_reason(stderr: &str) -> Option<String> {
+    let lines: Vec<&str> = stderr.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    lines
+        .iter()
+        .rev()
+        .find_map(|l| l.strip_prefix("ERROR:"))
+        .or(lines.last().copied())
+        .map(|reason| reason.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_progress() {
+        assert_eq!(parse_progress("hermeneia-progress 250 1000"), Some(0.25));
+        assert_eq!(parse_progress("hermeneia-progress 250 NA"), None);
+        assert_eq!(parse_progress("[download] Destination: x.m4a"), None);
+        let reason = error_reason("WARNING: slow\nERROR: [generic] Unsupported URL: x\n");
+        assert_eq!(reason.as_deref(), Some("[generic] Unsupported URL: x"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_download_with_a_stand_in_downloader() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join("hermeneia_test_downloader");
+        let library = dir.join("library");
+        std::fs::create_dir_all(library.join(DOWNLOADS_DIR)).unwrap();
+        let output = library.join(DOWNLOADS_DIR).join("Sermon [abc].m4a");
+
+        // Behaves like yt-dlp given our arguments: the URL comes last
+        let script = dir.join("yt-dlp");
+        std::fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\n\
+                 for url; do :; done\n\
+                 if [ \"$url\" = bad ]; then echo 'ERROR: Unsupported URL: bad' >&2; exit 1; fi\n\
+                 echo 'hermeneia-progress 50 NA'\n\
+                 echo 'hermeneia-progress 50 100'\n\
+                 printf audio > '{0}'\n\
+                 echo 'hermeneia-file {0}'\n",
+                output.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let downloader = ExternalDownloader::new(&script);
+        let options = DownloadOptions::default().library_dir(&library);
+        let mut fractions = Vec::new();
+        let mut sink = |f: f64| fractions.push(f);
+        let path = downloader.download("good", &options, &mut sink).unwrap();
+        assert_eq!(path, output);
+        assert_eq!(fractions, vec![0.5, 1.0]);
+
+        let error = downloader
+            .download("bad", &options, &mut crate::progress::NoProgress)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Failed to download 'bad': Unsupported URL: bad"
+        );
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
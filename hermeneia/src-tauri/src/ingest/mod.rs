@@ -0,0 +1,95 @@
+// src-tauri/src/ingest/mod.rs
+// Bring audio from the web into the library
+
+//! Bring audio from the web into the library
+//!
+//! [`podcast`] reads RSS feeds and downloads their episodes; [`downloader`]
+//! hands any other media URL to a downloader the user has installed
+//! (yt-dlp). Both save into the [library folder](library_dir) and can
+//! queue what they fetch for transcription.
+//!
+//! ```no_run
+//! use hermeneia_lib::ingest::{download_episodes, fetch_feed, DownloadOptions};
+//! use hermeneia_lib::progress::NoProgress;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let feed = fetch_feed("https://example.org/podcast.xml")?;
+//! let latest = vec![feed.episodes[0].guid.clone()];
+//! let options = DownloadOptions::default().transcribe(true);
+//! for path in download_episodes(&feed, &latest, &options, &mut NoProgress)? {
+//!     println!("{}", path.display());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod downloader;
+pub mod podcast;
+
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Duration;
+
+use crate::settings::Settings;
+
+pub use downloader::{download_url, DownloaderInfo, ExternalDownloader};
+pub use podcast::{download_episode, download_episodes, fetch_feed, parse_feed, Episode, Feed};
+
+/// How often a running download is checked for progress and cancellation
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Where downloads go and what happens to them next
+#[derive(Debug, Clone)]
+pub struct DownloadOptions {
+    /// Library folder; podcast episodes go in a folder per show
+    pub library_dir: PathBuf,
+    /// Add each downloaded file to the transcription queue
+    pub transcribe: bool,
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            library_dir: library_dir(&Settings::load()),
+            transcribe: false,
+        }
+    }
+}
+
+impl DownloadOptions {
+    pub fn library_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.library_dir = dir.into();
+        self
+    }
+
+    pub fn transcribe(mut self, transcribe: bool) -> Self {
+        self.transcribe = transcribe;
+        self
+    }
+}
+
+/// The library folder: the one in the settings, else `Hermeneia` in the
+/// user's music folder (or home folder)
+pub fn library_dir(settings: &Settings) -> PathBuf {
+    settings.library_dir.clone().unwrap_or_else(|| {
+        dirs::audio_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_default()
+            .join("Hermeneia")
+    })
+}
+
+/// A helper program's command, without a console window on Windows
+pub(crate) fn helper_command(program: impl AsRef<std::ffi::OsStr>) -> Command {
+    #[cfg_attr(not(target_os = "windows"), allow(unused_mut))]
+    let mut command = Command::new(program);
+
+    // Don't flash a console window from the GUI process
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    command
+}
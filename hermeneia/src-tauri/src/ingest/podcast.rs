@@ -0,0 +1,625 @@
+// src-tauri/src/ingest/podcast.rs
+// Podcast RSS feeds: list episodes and download them into the library
+
+//! Podcast feeds
+//!
+//! Feeds and audio are fetched with the system's `curl`, which ships with
+//! Windows 10+, macOS and nearly every Linux install, so the app needs no
+//! TLS stack of its own. Only `http` and `https` URLs are fetched, redirects
+//! included, so a feed can't point curl at local files or other protocols.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AudioError, Result};
+use crate::jobs::{queue_transcription, Priority};
+use crate::naming::{PathTemplate, TemplateFields};
+use crate::progress::{check_cancelled, ProgressSink};
+
+use super::{helper_command, DownloadOptions, POLL_INTERVAL};
+
+/// Where downloaded episodes go inside the library
+const EPISODE_TEMPLATE: &str = "{album}/{date} {title}.{format}";
+
+/// Longest a feed request may take
+const FEED_TIMEOUT_SECS: &str = "60";
+
+/// A podcast and its episodes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Feed {
+    pub title: String,
+    /// `itunes:author`, else the channel's `author`
+    pub author: Option<String>,
+    /// May contain HTML
+    pub description: Option<String>,
+    /// The show's website
+    pub link: Option<String>,
+    /// Cover art URL
+    pub image: Option<String>,
+    pub language: Option<String>,
+    /// Newest first, as feeds list them
+    pub episodes: Vec<Episode>,
+}
+
+/// One episode of a [`Feed`]; items without audio are left out
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Episode {
+    /// The item's `guid`, or its audio URL when it has none
+    pub guid: String,
+    pub title: String,
+    /// `pubDate` as written in the feed (RFC 2822)
+    pub published: Option<String>,
+    /// Day of `published`, `YYYY-MM-DD`, in the feed's own time zone
+    pub date: Option<String>,
+    /// May contain HTML
+    pub description: Option<String>,
+    pub duration_seconds: Option<f64>,
+    pub audio_url: String,
+    /// Enclosure MIME type, e.g. `audio/mpeg`
+    pub mime_type: Option<String>,
+    /// Enclosure length, when the feed gives a real one
+    pub size_bytes: Option<u64>,
+    pub season: Option<u32>,
+    pub episode: Option<u32>,
+    /// Episode art URL
+    pub image: Option<String>,
+}
+
+/// Download and parse a podcast feed
+pub fn fetch_feed(url: &str) -> Result<Feed> {
+    check_web_url(url)?;
+    let mut command = curl();
+    command.args(["--max-time", FEED_TIMEOUT_SECS, "--", url]);
+    let output = command.output().map_err(|e| curl_error(url, e))?;
+    if !output.status.success() {
+        return Err(download_error(url, &output.stderr));
+    }
+    parse_feed(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Read an RSS 2.0 feed, including the `itunes:` podcast extensions
+pub fn parse_feed(xml: &str) -> Result<Feed> {
+    // Text is trimmed once whole: trimming each piece would eat the spaces
+    // around entities
+    let mut reader = Reader::from_str(xml);
+    let invalid = |e: &dyn std::fmt::Display| AudioError::InvalidFeed(e.to_string());
+
+    let mut feed = Feed::default();
+    let mut seen_channel = false;
+    // Open elements, outermost first, and the text of the innermost
+    let mut path: Vec<String> = Vec::new();
+    let mut text = String::new();
+    let mut item: Option<Item> = None;
+
+    loop {
+        let event = reader.read_event().map_err(|e| invalid(&e))?;
+        match &event {
+            Event::Start(e) | Event::Empty(e) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                let attribute = |key: &str| {
+                    e.try_get_attribute(key)
+                        .ok()
+                        .flatten()
+                        .and_then(|a| a.unescape_value().ok())
+                        .map(|v| v.trim().to_string())
+                        .filter(|v| !v.is_empty())
+                };
+                match (name.as_str(), item.as_mut()) {
+                    ("channel", _) => seen_channel = true,
+                    ("item", _) => item = Some(Item::default()),
+                    ("enclosure", Some(item)) => {
+                        item.episode.audio_url = attribute("url").unwrap_or_default();
+                        item.episode.mime_type = attribute("type");
+                        item.episode.size_bytes = attribute("length")
+                            .and_then(|l| l.parse().ok())
+                            .filter(|&l| l > 0);
+                    }
+                    ("itunes:image", Some(item)) => item.episode.image = attribute("href"),
+                    ("itunes:image", None) => feed.image = attribute("href").or(feed.image.take()),
+                    _ => {}
+                }
+                if matches!(event, Event::Start(_)) {
+                    path.push(name);
+                    text.clear();
+                }
+            }
+            Event::Text(e) => {
+                text.push_str(&e.xml_content().map_err(|e| invalid(&e))?);
+            }
+            Event::CData(e) => text.push_str(&e.decode().map_err(|e| invalid(&e))?),
+            Event::GeneralRef(e) => {
+                let name = e.decode().map_err(|e| invalid(&e))?;
+                match e.resolve_char_ref().map_err(|e| invalid(&e))? {
+                    Some(c) => text.push(c),
+                    None => match quick_xml::escape::resolve_predefined_entity(&name) {
+                        Some(value) => text.push_str(value),
+                        // Leave entities XML doesn't define as they were
+                        None => {
+                            text.push('&');
+                            text.push_str(&name);
+                            text.push(';');
+                        }
+                    },
+                }
+            }
+            Event::End(_) => {
+                let name = path.pop().unwrap_or_default();
+                let value = std::mem::take(&mut text).trim().to_string();
+                let parent = path.last().map(String::as_str);
+                match item.as_mut() {
+                    Some(current) if name == "item" => {
+                        let current = std::mem::take(current);
+                        item = None;
+                        if let Some(episode) = current.finish() {
+                            feed.episodes.push(episode);
+                        }
+                    }
+                    Some(item) => item.set(&name, value),
+                    None => match (parent, name.as_str()) {
+                        (Some("channel"), "title") => feed.title = value,
+                        (Some("channel"), "link") if !value.is_empty() => feed.link = Some(value),
+                        (Some("channel"), "description") => feed.description = non_empty(value),
+                        (Some("channel"), "language") => feed.language = non_empty(value),
+                        (Some("channel"), "itunes:author") => feed.author = non_empty(value),
+                        (Some("channel"), "author") if feed.author.is_none() => {
+                            feed.author = non_empty(value)
+                        }
+                        (Some("image"), "url") if feed.image.is_none() => {
+                            feed.image = non_empty(value)
+                        }
+                        _ => {}
+                    },
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !seen_channel {
+        return Err(AudioError::InvalidFeed(
+            "not an RSS feed (no <channel>)".to_string(),
+        ));
+    }
+    Ok(feed)
+}
+
+/// An `<item>` being read
+#[derive(Debug, Default)]
+struct Item {
+    episode: Episode,
+    guid: Option<String>,
+    /// `content:encoded`, `description` and `itunes:summary`, best first
+    descriptions: [Option<String>; 3],
+}
+
+impl Item {
+    fn set(&mut self, name: &str, value: String) {
+        let episode = &mut self.episode;
+        match name {
+            "title" => episode.title = value,
+            "guid" => self.guid = non_empty(value),
+            "pubDate" => {
+                episode.date = parse_rfc2822_date(&value);
+                episode.published = non_empty(value);
+            }
+            "content:encoded" => self.descriptions[0] = non_empty(value),
+            "description" => self.descriptions[1] = non_empty(value),
+            "itunes:summary" => self.descriptions[2] = non_empty(value),
+            "itunes:duration" => episode.duration_seconds = parse_duration(&value),
+            "itunes:season" => episode.season = value.parse().ok(),
+            "itunes:episode" => episode.episode = value.parse().ok(),
+            "itunes:title" if episode.title.is_empty() => episode.title = value,
+            _ => {}
+        }
+    }
+
+    fn finish(self) -> Option<Episode> {
+        let mut episode = self.episode;
+        if episode.audio_url.is_empty() {
+            return None;
+        }
+        episode.guid = self.guid.unwrap_or_else(|| episode.audio_url.clone());
+        episode.description = self.descriptions.into_iter().flatten().next();
+        Some(episode)
+    }
+}
+
+fn non_empty(value: String) -> Option<String> {
+    (!value.is_empty()).then_some(value)
+}
+
+/// `itunes:duration`: `HH:MM:SS`, `MM:SS` or seconds
+fn parse_duration(value: &str) -> Option<f64> {
+    value
+        .split(':')
+        .try_fold(0.0, |total: f64, part| Some(total * 60.0 + part.trim().parse::<f64>().ok()?))
+        .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+}
+
+/// The date of an RFC 2822 time such as `Tue, 03 Jun 2025 10:00:00 +0000`
+fn parse_rfc2822_date(value: &str) -> Option<String> {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+    let value = value.split_once(',').map_or(value, |(_, rest)| rest);
+    let mut parts = value.split_whitespace();
+    let day: u32 = parts.next()?.parse().ok()?;
+    let month = parts.next()?.get(..3)?.to_ascii_lowercase();
+    let month = MONTHS.iter().position(|m| *m == month)? + 1;
+    let year: u32 = parts.next()?.parse().ok()?;
+    // Two-digit years from old feeds
+    let year = if year < 100 { year + 2000 } else { year };
+    (1..=31)
+        .contains(&day)
+        .then(|| format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+/// Download the episodes with the given GUIDs into the library
+///
+/// Episodes already in the library aren't downloaded again. With
+/// [`DownloadOptions::transcribe`], every file (new or not) is added to the
+/// transcription queue. Progress covers the whole set, one stage per
+/// download.
+///
+/// # Returns
+/// Where each episode is, in the order of `guids`
+pub fn download_episodes(
+    feed: &Feed,
+    guids: &[String],
+    options: &DownloadOptions,
+    progress: &mut dyn ProgressSink,
+) -> Result<Vec<PathBuf>> {
+    let episodes = guids
+        .iter()
+        .map(|guid| {
+            feed.episodes.iter().find(|e| &e.guid == guid).ok_or_else(|| {
+                AudioError::InvalidParameter(format!("No episode '{}' in '{}'", guid, feed.title))
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let count = episodes.len();
+    let mut paths = Vec::with_capacity(count);
+    for (i, episode) in episodes.into_iter().enumerate() {
+        progress.message(&episode.title);
+        let mut scaled = |fraction: f64| progress.progress((i as f64 + fraction) / count as f64);
+        let path = download_episode(feed, episode, &options.library_dir, &mut scaled)?;
+        if options.transcribe {
+            queue_transcription(&path, Priority::Normal);
+        }
+        paths.push(path);
+    }
+    progress.progress(1.0);
+    Ok(paths)
+}
+
+/// Download one episode into the library, unless it is already there
+pub fn download_episode(
+    feed: &Feed,
+    episode: &Episode,
+    library_dir: &Path,
+    progress: &mut dyn ProgressSink,
+) -> Result<PathBuf> {
+    let path = library_dir.join(episode_path(feed, episode)?);
+    if path.exists() {
+        return Ok(path);
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    progress.stage("download");
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    let partial = path.with_file_name(name);
+    let result = fetch_to_file(&episode.audio_url, &partial, episode.size_bytes, progress)
+        .and_then(|()| std::fs::rename(&partial, &path).map_err(AudioError::from));
+    if result.is_err() {
+        std::fs::remove_file(&partial).ok();
+    }
+    result.map(|()| path)
+}
+
+/// Where an episode goes inside the library
+fn episode_path(feed: &Feed, episode: &Episode) -> Result<PathBuf> {
+    let mut fields = TemplateFields::new();
+    fields.set("album", feed.title.as_str());
+    fields.set("title", episode.title.as_str());
+    fields.set("format", audio_extension(episode));
+    if let Some(author) = &feed.author {
+        fields.set("artist", author.as_str()).set("speaker", author.as_str());
+    }
+    if let Some(date) = &episode.date {
+        fields.set("date", date.as_str());
+    }
+    if let Some(language) = &feed.language {
+        fields.set("lang", language.as_str());
+    }
+    PathTemplate::parse(EPISODE_TEMPLATE)?.render(&fields)
+}
+
+/// File extension for an episode's audio, from its URL or MIME type
+fn audio_extension(episode: &Episode) -> &str {
+    let url_path = episode.audio_url.split(['?', '#']).next().unwrap_or_default();
+    let from_url = url_path
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, ext)| ext)
+        .filter(|ext| {
+            (1..=4).contains(&ext.len()) && ext.chars().all(|c| c.is_ascii_alphanumeric())
+        });
+    from_url.unwrap_or(match episode.mime_type.as_deref() {
+        Some("audio/mp4" | "audio/x-m4a" | "audio/m4a") => "m4a",
+        Some("audio/ogg" | "audio/opus") => "ogg",
+        Some("audio/wav" | "audio/x-wav") => "wav",
+        Some("audio/flac" | "audio/x-flac") => "flac",
+        Some("audio/aac") => "aac",
+        _ => "mp3",
+    })
+}
+
+/// Download `url` to `path`, reporting progress against `expected_len`
+pub(crate) fn fetch_to_file(
+    url: &str,
+    path: &Path,
+    expected_len: Option<u64>,
+    progress: &mut dyn ProgressSink,
+) -> Result<()> {
+    check_web_url(url)?;
+    let mut command = curl();
+    command
+        .arg("--output")
+        .arg(path)
+        .args(["--", url])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    let mut child = command.spawn().map_err(|e| curl_error(url, e))?;
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if let Err(e) = check_cancelled(progress) {
+            child.kill().ok();
+            child.wait().ok();
+            return Err(e);
+        }
+        if let Some(expected) = expected_len {
+            let len = std::fs::metadata(path).map_or(0, |m| m.len());
+            progress.progress((len as f64 / expected as f64).min(1.0));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    };
+
+    if !status.success() {
+        let mut stderr = Vec::new();
+        if let Some(mut pipe) = child.stderr.take() {
+            pipe.read_to_end(&mut stderr).ok();
+        }
+        return Err(download_error(url, &stderr));
+    }
+    progress.progress(1.0);
+    Ok(())
+}
+
+/// `curl` set to fail on HTTP errors, follow redirects and stay quiet,
+/// speaking only HTTP(S) even when redirected
+fn curl() -> Command {
+    let mut command = helper_command("curl");
+    command.args(["--fail", "--silent", "--show-error", "--location"]);
+    command.args(["--proto", "=http,https", "--proto-redir", "=http,https"]);
+    command
+}
+
+/// Refuse anything but an `http` or `https` URL before curl sees it
+fn check_web_url(url: &str) -> Result<()> {
+    let scheme = url.split_once("://").map(|(scheme, _)| scheme.to_ascii_lowercase());
+    if matches!(scheme.as_deref(), Some("http" | "https")) {
+        return Ok(());
+    }
+    Err(AudioError::Download {
+        url: url.to_string(),
+        reason: "only http and https URLs can be downloaded".to_string(),
+    })
+}
+
+fn curl_error(url: &str, error: std::io::Error) -> AudioError {
+    let reason = match error.kind() {
+        std::io::ErrorKind::NotFound => "curl is not installed".to_string(),
+        _ => format!("couldn't run curl: {}", error),
+    };
+    AudioError::Download {
+        url: url.to_string(),
+        reason,
+    }
+}
+
+fn download_error(url: &str, stderr: &[u8]) -> AudioError {
+    let reason = String::from_utf8_lossy(stderr)
+        .trim()
+        .trim_start_matches("curl: ")
+        .to_string();
+    AudioError::Download {
+        url: url.to_string(),
+        reason: if reason.is_empty() {
+            "the request failed".to_string()
+        } else {
+            reason
+        },
+    }
+}
+
+/// Serve the files in `dir` over plain HTTP on a local port, answering
+/// `requests` requests, for tests that download; returns the base URL
+#[cfg(test)]
+pub(crate) fn serve_files(dir: PathBuf, requests: usize) -> String {
+    use std::io::{BufRead, BufReader, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().take(requests).flatten() {
+            let mut lines = BufReader::new(&stream).lines();
+            let request = lines.next().and_then(|line| line.ok()).unwrap_or_default();
+            while lines.next().is_some_and(|line| line.is_ok_and(|l| !l.is_empty())) {}
+            let name = request.split(' ').nth(1).unwrap_or_default().trim_start_matches('/');
+            let (status, body) = match std::fs::read(dir.join(name)) {
+                Ok(body) if !name.is_empty() => ("200 OK", body),
+                _ => ("404 Not Found", Vec::new()),
+            };
+            let head = format!(
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(head.as_bytes()).and_then(|()| stream.write_all(&body)).ok();
+        }
+    });
+    format!("http://{address}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::NoProgress;
+
+    const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd"
+     xmlns:content="http://purl.org/rss/1.0/modules/content/">
+  <channel>
+    <title>Word &amp; Sacrament</title>
+    <link>https://example.org</link>
+    <language>en</language>
+    <itunes:author>St. Mark's</itunes:author>
+    <itunes:image href="https://example.org/cover.jpg"/>
+    <description><![CDATA[Weekly <b>sermons</b>]]></description>
+    <item>
+      <title>Advent 1: Watch</title>
+      <guid isPermaLink="false">sermon-2025-11-30</guid>
+      <pubDate>Sun, 30 Nov 2025 10:30:00 -0500</pubDate>
+      <description>Short notes</description>
+      <content:encoded><![CDATA[<p>Full notes</p>]]></content:encoded>
+      <enclosure url="https://cdn.example.org/a1.mp3?src=rss" length="0" type="audio/mpeg"/>
+      <itunes:duration>41:05</itunes:duration>
+      <itunes:episode>12</itunes:episode>
+    </item>
+    <item>
+      <title>Parish news</title>
+      <description>No audio here</description>
+    </item>
+    <item>
+      <title>Christ the King</title>
+      <pubDate>Sun, 23 Nov 2025 10:30:00 -0500</pubDate>
+      <enclosure url="https://cdn.example.org/stream" length="52000000" type="audio/x-m4a"/>
+      <itunes:duration>3120</itunes:duration>
+    </item>
+  </channel>
+</rss>"#;
+
+    #[test]
+    fn test_parse_feed() {
+        let feed = parse_feed(FEED).unwrap();
+        assert_eq!(feed.title, "Word & Sacrament");
+        assert_eq!(feed.author.as_deref(), Some("St. Mark's"));
+        assert_eq!(feed.image.as_deref(), Some("https://example.org/cover.jpg"));
+        assert_eq!(feed.description.as_deref(), Some("Weekly <b>sermons</b>"));
+        assert_eq!(feed.episodes.len(), 2, "items without audio are left out");
+
+        let first = &feed.episodes[0];
+        assert_eq!(first.guid, "sermon-2025-11-30");
+        assert_eq!(first.date.as_deref(), Some("2025-11-30"));
+        assert_eq!(first.description.as_deref(), Some("<p>Full notes</p>"));
+        assert_eq!(first.duration_seconds, Some(2465.0));
+        assert_eq!(first.episode, Some(12));
+        assert_eq!(first.size_bytes, None, "a zero length means unknown");
+
+        let second = &feed.episodes[1];
+        assert_eq!(second.guid, "https://cdn.example.org/stream");
+        assert_eq!(second.size_bytes, Some(52_000_000));
+        assert_eq!(second.duration_seconds, Some(3120.0));
+    }
+
+    #[test]
+    fn test_not_a_feed() {
+        assert!(parse_feed("<html><body>Moved</body></html>").is_err());
+        assert!(parse_feed("<rss><channel><title>x</title></rss>").is_err());
+    }
+
+    #[test]
+    fn test_episode_paths() {
+        let feed = parse_feed(FEED).unwrap();
+        let path = |i: usize| episode_path(&feed, &feed.episodes[i]).unwrap();
+        let folder = Path::new("Word & Sacrament");
+        assert_eq!(path(0), folder.join("2025-11-30 Advent 1_ Watch.mp3"));
+        assert_eq!(path(1), folder.join("2025-11-23 Christ the King.m4a"));
+    }
+
+    #[test]
+    fn test_rfc2822_dates() {
+        assert_eq!(parse_rfc2822_date("Tue, 3 Jun 2025 10:00:00 GMT").unwrap(), "2025-06-03");
+        assert_eq!(parse_rfc2822_date("03 June 25 10:00 +0000").unwrap(), "2025-06-03");
+        assert_eq!(parse_rfc2822_date("yesterday"), None);
+    }
+
+    #[test]
+    fn test_download_over_http() {
+        if Command::new("curl").arg("--version").output().is_err() {
+            return;
+        }
+        let dir = std::env::temp_dir().join("hermeneia_test_ingest");
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("episode.mp3"), b"ID3 fake audio").unwrap();
+        let server = serve_files(dir.clone(), 2);
+        let xml = format!(
+            "<rss><channel><title>Test Show</title><item><title>One</title>\
+             <enclosure url=\"{server}/episode.mp3\" type=\"audio/mpeg\"/></item></channel></rss>"
+        );
+        let feed = parse_feed(&xml).unwrap();
+        let library = dir.join("library");
+        let options = DownloadOptions::default().library_dir(&library);
+        let guids = vec![feed.episodes[0].guid.clone()];
+
+        let paths = download_episodes(&feed, &guids, &options, &mut NoProgress).unwrap();
+        assert_eq!(paths, vec![library.join("Test Show").join("unknown One.mp3")]);
+        assert_eq!(std::fs::read(&paths[0]).unwrap(), b"ID3 fake audio");
+
+        let missing = Episode {
+            audio_url: format!("{server}/gone.mp3"),
+            title: "Gone".to_string(),
+            ..Episode::default()
+        };
+        let error = download_episode(&feed, &missing, &library, &mut NoProgress).unwrap_err();
+        assert!(matches!(error, AudioError::Download { .. }));
+        assert!(!library.join("Test Show").join("unknown Gone.mp3.part").exists());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_only_web_urls_are_fetched() {
+        let source = std::env::temp_dir().join("hermeneia_test_ingest_local.mp3");
+        std::fs::write(&source, b"ID3 private").unwrap();
+        let output = std::env::temp_dir().join("hermeneia_test_ingest_local_copy.mp3");
+        for url in [
+            format!("file://{}", source.display()),
+            "ftp://example.org/a.mp3".to_string(),
+            "example.org/a.mp3".to_string(),
+        ] {
+            let error = fetch_to_file(&url, &output, None, &mut NoProgress).unwrap_err();
+            assert!(matches!(error, AudioError::Download { .. }), "{url}");
+            assert!(matches!(fetch_feed(&url), Err(AudioError::Download { .. })), "{url}");
+        }
+        assert!(!output.exists());
+        assert!(check_web_url("HTTPS://example.org/feed.xml").is_ok());
+        std::fs::remove_file(source).ok();
+    }
+}
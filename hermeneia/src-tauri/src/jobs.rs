@@ -0,0 +1,1357 @@
+// src-tauri/src/jobs.rs
+// Background job queue for transcription, translation and waveform
+// pre-computation, with priorities, retries and a limit on running jobs
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{info, warn};
+
+use crate::audio::{extract_waveform_peaks_with_progress, WaveformOptions};
+use crate::error::{AudioError, Result};
+use crate::gpu::{
+    run_with_cpu_fallback, select_inference_device, AcceleratedError, GpuFallback,
+    InferenceDevice,
+};
+use crate::notify::{notify_in_background, JobEvent};
+use crate::progress::ProgressSink;
+use crate::runtime::{CancelRegistration, CancelRegistry};
+use crate::schedule::Gate;
+use crate::settings::{app_config_dir, Settings};
+use crate::storage::record_job_quietly;
+use crate::transcribe::{transcribe_file, ChunkPlan, Transcriber};
+use crate::translation::{
+    lease_translator, translate_segments, TranslationEngine, TranslationInput, TranslationRequest,
+};
+
+/// Event carrying a [`Job`] whenever one is added or changes
+pub const JOB_QUEUE_EVENT: &str = "job-queue";
+
+const QUEUE_FILE: &str = "job_queue.json";
+
+/// Least change in a running job's progress that listeners hear about
+const PROGRESS_STEP: f64 = 0.01;
+
+/// What a job does; each kind is run by the [`JobRunner`] registered for it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobKind {
+    /// Speech to text; waits until an engine is registered with
+    /// [`transcription_runner`]
+    Transcription,
+    Translation,
+    /// Waveform peaks worked out ahead of time, e.g. for a whole library
+    Waveform,
+}
+
+/// How soon a queued job runs
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobState {
+    pub fn is_finished(self) -> bool {
+        matches!(self, JobState::Completed | JobState::Failed | JobState::Cancelled)
+    }
+}
+
+/// Work to put on a [`JobQueue`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewJob {
+    pub kind: JobKind,
+    /// Whatever the kind's runner needs, e.g. a file and options
+    #[serde(default)]
+    pub payload: Value,
+    #[serde(default)]
+    pub priority: Priority,
+    /// Times to run the job again after it fails before giving up
+    #[serde(default)]
+    pub max_retries: u32,
+    /// Shown in the UI, e.g. the file name
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl NewJob {
+    pub fn new(kind: JobKind, payload: Value) -> Self {
+        Self {
+            kind,
+            payload,
+            priority: Priority::Normal,
+            max_retries: 0,
+            label: None,
+        }
+    }
+
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+}
+
+/// A job on the queue and how it's going; the payload of
+/// [`JOB_QUEUE_EVENT`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Job {
+    pub id: u64,
+    pub kind: JobKind,
+    pub label: Option<String>,
+    pub payload: Value,
+    pub priority: Priority,
+    pub state: JobState,
+    /// Runs started so far, retries included
+    pub attempts: u32,
+    pub max_retries: u32,
+    /// Fraction of the current run done, from 0.0 to 1.0
+    pub progress: f64,
+    /// Why the last run failed; kept while a retry waits
+    pub error: Option<String>,
+    /// What the runner returned
+    pub result: Option<Value>,
+    /// Set when a run couldn't start on the GPU and went on on the CPU
+    #[serde(default)]
+    pub fallback: Option<GpuFallback>,
+    /// Local times with offset, RFC 3339
+    pub submitted_at: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+}
+
+impl Job {
+    /// How a job that completed or failed for good is recorded and
+    /// announced, named after its kind; `None` while it may still run, and
+    /// for cancelled jobs
+    ///
+    /// The input is the payload's `path` when it has one.
+    pub fn event(&self) -> Option<JobEvent> {
+        let error = match self.state {
+            JobState::Completed => None,
+            JobState::Failed => self.error.clone(),
+            _ => return None,
+        };
+        let input = self.payload.get("path").and_then(Value::as_str);
+        let inputs = input.map(str::to_string).into_iter().collect();
+        let mut event = JobEvent::new(&to_text(&self.kind), inputs, Vec::new(), error);
+        let time = |at: &Option<String>| chrono::DateTime::parse_from_rfc3339(at.as_deref()?).ok();
+        if let (Some(started), Some(finished)) = (time(&self.started_at), time(&self.finished_at)) {
+            event = event.took((finished - started).to_std().unwrap_or_default());
+        }
+        if let Some(finished) = &self.finished_at {
+            event.finished_at = finished.clone();
+        }
+        Some(event)
+    }
+}
+
+/// Does the work for one [`JobKind`]
+///
+/// Runs on a thread of its own. `progress` reports to the queue, and says
+/// when the job has been cancelled; return [`AudioError::Cancelled`] then.
+/// Closures taking the payload and progress are runners too.
+pub trait JobRunner: Send + Sync {
+    fn run(&self, payload: &Value, progress: &mut dyn ProgressSink) -> Result<Value>;
+}
+
+impl<F> JobRunner for F
+where
+    F: Fn(&Value, &mut dyn ProgressSink) -> Result<Value> + Send + Sync,
+{
+    fn run(&self, payload: &Value, progress: &mut dyn ProgressSink) -> Result<Value> {
+        self(payload, progress)
+    }
+}
+
+type Listener = Box<dyn Fn(&Job) + Send + Sync>;
+
+type FallbackListener = Box<dyn Fn(&Job, &GpuFallback) + Send + Sync>;
+
+/// Says whether jobs of a kind may start now
+type StartGate = Arc<dyn Fn() -> bool + Send + Sync>;
+
+struct QueueState {
+    /// Oldest first, unless moved with [`JobQueue::move_to`]
+    jobs: Vec<Job>,
+    next_id: u64,
+    max_concurrent: usize,
+    running: usize,
+}
+
+struct Shared {
+    state: Mutex<QueueState>,
+    runners: Mutex<HashMap<JobKind, Arc<dyn JobRunner>>>,
+    gates: Mutex<HashMap<JobKind, StartGate>>,
+    listeners: Mutex<Vec<Listener>>,
+    fallback_listeners: Mutex<Vec<FallbackListener>>,
+    /// Tokens of the running jobs
+    cancels: CancelRegistry,
+    /// Where the queue is saved; `None` keeps it in memory
+    path: Option<PathBuf>,
+}
+
+/// Jobs waiting, running and finished, run by priority on background
+/// threads
+///
+/// The highest-priority pending job starts whenever fewer than
+/// `max_concurrent` are running, oldest first within a priority unless
+/// moved. Jobs of a kind no runner is registered for wait until one is,
+/// and so do jobs of a kind whose gate is closed. A job that fails is
+/// queued again until it has used its retries. Clones share the queue.
+#[derive(Clone)]
+pub struct JobQueue {
+    shared: Arc<Shared>,
+}
+
+impl JobQueue {
+    /// A queue kept in memory only
+    pub fn new(max_concurrent: usize) -> Self {
+        Self::with_jobs(Vec::new(), max_concurrent, None)
+    }
+
+    /// A queue saved to `path` on every change, starting with the jobs
+    /// saved there
+    ///
+    /// Jobs that were running when the queue was last saved were cut off
+    /// and are pending again. A missing file starts an empty queue; an
+    /// unreadable one is logged and ignored, like the settings file.
+    pub fn open<P: AsRef<Path>>(path: P, max_concurrent: usize) -> Self {
+        let path = path.as_ref();
+        let jobs = match load_jobs(path) {
+            Ok(jobs) => jobs,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Ignoring unreadable job queue");
+                Vec::new()
+            }
+        };
+        Self::with_jobs(jobs, max_concurrent, Some(path.to_path_buf()))
+    }
+
+    fn with_jobs(mut jobs: Vec<Job>, max_concurrent: usize, path: Option<PathBuf>) -> Self {
+        for job in jobs.iter_mut().filter(|job| job.state == JobState::Running) {
+            job.state = JobState::Pending;
+            job.progress = 0.0;
+        }
+        let next_id = jobs.iter().map(|job| job.id + 1).max().unwrap_or(1);
+        Self {
+            shared: Arc::new(Shared {
+                state: Mutex::new(QueueState {
+                    jobs,
+                    next_id,
+                    max_concurrent: max_concurrent.max(1),
+                    running: 0,
+                }),
+                runners: Mutex::new(HashMap::new()),
+                gates: Mutex::new(HashMap::new()),
+                listeners: Mutex::new(Vec::new()),
+                fallback_listeners: Mutex::new(Vec::new()),
+                cancels: CancelRegistry::new(),
+                path,
+            }),
+        }
+    }
+
+    /// Run jobs of `kind` with `runner`, replacing any runner registered
+    /// before; pending jobs of that kind start straight away
+    pub fn register<R: JobRunner + 'static>(&self, kind: JobKind, runner: R) {
+        lock(&self.shared.runners).insert(kind, Arc::new(runner));
+        self.dispatch();
+    }
+
+    /// Call `listener` whenever a running job couldn't start on the GPU and
+    /// carries on on the CPU, e.g. to warn the user; the job already has
+    /// the fallback recorded
+    pub fn on_gpu_fallback(&self, listener: impl Fn(&Job, &GpuFallback) + Send + Sync + 'static) {
+        lock(&self.shared.fallback_listeners).push(Box::new(listener));
+    }
+
+    /// Start jobs of `kind` only while `gate` says they may, e.g. inside
+    /// the processing windows of a [`Schedule`](crate::schedule::Schedule)
+    ///
+    /// The gate is asked whenever the queue looks for a job to start and
+    /// one of `kind` is waiting. Nothing notices it opening by itself; call
+    /// [`wake`](Self::wake) then.
+    pub fn set_gate(&self, kind: JobKind, gate: impl Fn() -> bool + Send + Sync + 'static) {
+        lock(&self.shared.gates).insert(kind, Arc::new(gate));
+        self.dispatch();
+    }
+
+    /// Start pending jobs if there's room, e.g. once a gate has opened
+    pub fn wake(&self) {
+        self.dispatch();
+    }
+
+    /// Call `listener` with every job added or changed
+    ///
+    /// Listeners run on whichever thread made the change and must not
+    /// register further listeners.
+    pub fn on_change(&self, listener: impl Fn(&Job) + Send + Sync + 'static) {
+        lock(&self.shared.listeners).push(Box::new(listener));
+    }
+
+    pub fn submit(&self, new: NewJob) -> Job {
+        let job = {
+            let mut state = self.state();
+            let job = Job {
+                id: state.next_id,
+                kind: new.kind,
+                label: new.label,
+                payload: new.payload,
+                priority: new.priority,
+                state: JobState::Pending,
+                attempts: 0,
+                max_retries: new.max_retries,
+                progress: 0.0,
+                error: None,
+                result: None,
+                fallback: None,
+                submitted_at: chrono::Local::now().to_rfc3339(),
+                started_at: None,
+                finished_at: None,
+            };
+            state.next_id += 1;
+            state.jobs.push(job.clone());
+            self.save(&state);
+            job
+        };
+        self.notify(&job);
+        self.dispatch();
+        job
+    }
+
+    /// Submit a job unless one of the same kind with the same payload is
+    /// already waiting or running, e.g. a file queued twice for
+    /// transcription
+    ///
+    /// # Returns
+    /// `None` if it was already queued; that job is left alone
+    pub fn submit_once(&self, new: NewJob) -> Option<Job> {
+        let queued = self.state().jobs.iter().any(|job| {
+            !job.state.is_finished() && job.kind == new.kind && job.payload == new.payload
+        });
+        (!queued).then(|| self.submit(new))
+    }
+
+    /// Every job, oldest first unless moved
+    pub fn jobs(&self) -> Vec<Job> {
+        self.state().jobs.clone()
+    }
+
+    /// Pending jobs, optionally only one kind, in the order they'll start
+    pub fn run_order(&self, kind: Option<JobKind>) -> Vec<Job> {
+        let state = self.state();
+        run_order(&state.jobs)
+            .into_iter()
+            .map(|index| state.jobs[index].clone())
+            .filter(|job| kind.is_none_or(|kind| job.kind == kind))
+            .collect()
+    }
+
+    pub fn job(&self, id: u64) -> Option<Job> {
+        self.state().jobs.iter().find(|job| job.id == id).cloned()
+    }
+
+    /// Stop a job: a pending one never starts, a running one is asked to
+    /// stop and is cancelled once its runner returns
+    ///
+    /// # Returns
+    /// `false` if there's no such job or it has already finished
+    pub fn cancel(&self, id: u64) -> bool {
+        let mut running = false;
+        let cancelled = self.update(id, |job| match job.state {
+            JobState::Pending => {
+                job.state = JobState::Cancelled;
+                job.finished_at = Some(chrono::Local::now().to_rfc3339());
+                true
+            }
+            JobState::Running => {
+                running = self.shared.cancels.cancel(job.id);
+                false
+            }
+            _ => false,
+        });
+        cancelled.is_some() || running
+    }
+
+    /// Queue a failed or cancelled job again, with its retries renewed
+    ///
+    /// # Returns
+    /// `false` if there's no such job or it didn't fail or get cancelled
+    pub fn retry(&self, id: u64) -> bool {
+        let retried = self
+            .update(id, |job| {
+                if !matches!(job.state, JobState::Failed | JobState::Cancelled) {
+                    return false;
+                }
+                job.state = JobState::Pending;
+                job.attempts = 0;
+                job.progress = 0.0;
+                job.error = None;
+                job.finished_at = None;
+                true
+            })
+            .is_some();
+        if retried {
+            self.dispatch();
+        }
+        retried
+    }
+
+    /// Change a pending job's priority
+    ///
+    /// # Returns
+    /// `false` if there's no such job or it isn't pending
+    pub fn set_priority(&self, id: u64, priority: Priority) -> bool {
+        self.update(id, |job| {
+            let pending = job.state == JobState::Pending;
+            if pending {
+                job.priority = priority;
+            }
+            pending
+        })
+        .is_some()
+    }
+
+    /// Move a pending job to `index` in the run order, as when dragged in
+    /// a list
+    ///
+    /// The run order stays sorted by priority, so the job takes the
+    /// priority of its new neighbours if it lands among jobs of another
+    /// priority: dragged above a high-priority job it becomes high priority
+    /// itself. An `index` past the end moves it to the back.
+    ///
+    /// # Returns
+    /// `false` if there's no such job or it isn't pending
+    pub fn move_to(&self, id: u64, index: usize) -> bool {
+        let job = {
+            let mut state = self.state();
+            let mut order: Vec<u64> =
+                run_order(&state.jobs).into_iter().map(|i| state.jobs[i].id).collect();
+            let Some(from) = order.iter().position(|&queued| queued == id) else {
+                return false;
+            };
+            order.remove(from);
+            let index = index.min(order.len());
+            let Some(stored) = index_of(&state.jobs, id) else {
+                return false;
+            };
+            let mut job = state.jobs.remove(stored);
+            let above = index.checked_sub(1).and_then(|i| index_of(&state.jobs, order[i]));
+            let below = order.get(index).and_then(|&next| index_of(&state.jobs, next));
+            if let Some(above) = above {
+                job.priority = job.priority.min(state.jobs[above].priority);
+            }
+            if let Some(below) = below {
+                job.priority = job.priority.max(state.jobs[below].priority);
+            }
+            // Within a priority jobs start in the order they're stored, so
+            // store it just ahead of the job it now runs before, or just
+            // behind the one it runs after
+            let at = match (above, below) {
+                (_, Some(below)) if state.jobs[below].priority == job.priority => below,
+                (Some(above), _) => above + 1,
+                _ => state.jobs.len(),
+            };
+            state.jobs.insert(at, job.clone());
+            self.save(&state);
+            job
+        };
+        self.notify(&job);
+        true
+    }
+
+    pub fn max_concurrent(&self) -> usize {
+        self.state().max_concurrent
+    }
+
+    /// Change how many jobs may run at once; running jobs finish even if
+    /// there are now too many
+    pub fn set_max_concurrent(&self, max_concurrent: usize) {
+        self.state().max_concurrent = max_concurrent.max(1);
+        self.dispatch();
+    }
+
+    /// Forget finished jobs and their results, optionally only one kind
+    ///
+    /// # Returns
+    /// How many were removed
+    pub fn clear_finished(&self, kind: Option<JobKind>) -> usize {
+        let mut state = self.state();
+        let before = state.jobs.len();
+        state
+            .jobs
+            .retain(|job| !job.state.is_finished() || kind.is_some_and(|kind| job.kind != kind));
+        let removed = before - state.jobs.len();
+        if removed > 0 {
+            self.save(&state);
+        }
+        removed
+    }
+
+    fn state(&self) -> MutexGuard<'_, QueueState> {
+        lock(&self.shared.state)
+    }
+
+    /// Apply `change` to a job, then save and notify if it says the job
+    /// changed
+    ///
+    /// # Returns
+    /// The changed job
+    fn update(&self, id: u64, change: impl FnOnce(&mut Job) -> bool) -> Option<Job> {
+        let job = {
+            let mut state = self.state();
+            let job = state.jobs.iter_mut().find(|job| job.id == id)?;
+            if !change(job) {
+                return None;
+            }
+            let job = job.clone();
+            self.save(&state);
+            job
+        };
+        self.notify(&job);
+        Some(job)
+    }
+
+    fn notify(&self, job: &Job) {
+        for listener in lock(&self.shared.listeners).iter() {
+            listener(job);
+        }
+    }
+
+    /// Write the queue to its file, if it has one; called with the state
+    /// locked so saves can't interleave
+    fn save(&self, state: &QueueState) {
+        let Some(path) = &self.shared.path else {
+            return;
+        };
+        if let Err(e) = save_jobs(path, &state.jobs) {
+            warn!(path = %path.display(), error = %e, "Couldn't save the job queue");
+        }
+    }
+
+    /// Start pending jobs while there's room
+    fn dispatch(&self) {
+        let runners = lock(&self.shared.runners).clone();
+        let gates = lock(&self.shared.gates).clone();
+        // Asked without the state locked, since a gate may look at the queue
+        let waiting: HashSet<JobKind> = {
+            let state = self.state();
+            let pending = state.jobs.iter().filter(|job| job.state == JobState::Pending);
+            pending.map(|job| job.kind).collect()
+        };
+        let closed: HashSet<JobKind> = gates
+            .iter()
+            .filter(|(kind, open)| waiting.contains(kind) && !open())
+            .map(|(kind, _)| *kind)
+            .collect();
+        let mut started = Vec::new();
+        {
+            let mut state = self.state();
+            while state.running < state.max_concurrent {
+                let next = state
+                    .jobs
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, job)| {
+                        job.state == JobState::Pending
+                            && runners.contains_key(&job.kind)
+                            && !closed.contains(&job.kind)
+                    })
+                    .min_by_key(|(index, job)| (Reverse(job.priority), *index))
+                    .map(|(index, _)| index);
+                let Some(index) = next else {
+                    break;
+                };
+                let job = &mut state.jobs[index];
+                job.state = JobState::Running;
+                job.attempts += 1;
+                job.progress = 0.0;
+                job.started_at = Some(chrono::Local::now().to_rfc3339());
+                started.push((job.clone(), self.shared.cancels.register(job.id)));
+                state.running += 1;
+            }
+            if !started.is_empty() {
+                self.save(&state);
+            }
+        }
+        for (job, registration) in started {
+            self.notify(&job);
+            let runner = runners[&job.kind].clone();
+            let queue = self.clone();
+            std::thread::spawn(move || queue.run(job, runner, registration));
+        }
+    }
+
+    fn run(&self, job: Job, runner: Arc<dyn JobRunner>, registration: CancelRegistration) {
+        info!(id = job.id, kind = ?job.kind, attempt = job.attempts, "Starting job");
+        let mut progress = QueueProgress {
+            queue: self,
+            id: job.id,
+            registration,
+            reported: 0.0,
+        };
+        let result = runner.run(&job.payload, &mut progress);
+        if let Err(e) = &result {
+            warn!(id = job.id, kind = ?job.kind, error = %e, "Job stopped");
+        }
+        let finished = {
+            let mut state = self.state();
+            state.running -= 1;
+            let finished = state
+                .jobs
+                .iter_mut()
+                .find(|stored| stored.id == job.id)
+                .map(|stored| {
+                    finish(stored, result);
+                    stored.clone()
+                });
+            self.save(&state);
+            finished
+        };
+        if let Some(job) = finished {
+            self.notify(&job);
+        }
+        self.dispatch();
+    }
+}
+
+/// Record how a run ended, queueing the job again if it failed with
+/// retries left
+fn finish(job: &mut Job, result: Result<Value>) {
+    let now = || Some(chrono::Local::now().to_rfc3339());
+    match result {
+        Ok(value) => {
+            job.state = JobState::Completed;
+            job.progress = 1.0;
+            job.error = None;
+            job.result = Some(value);
+            job.finished_at = now();
+        }
+        Err(AudioError::Cancelled) => {
+            job.state = JobState::Cancelled;
+            job.finished_at = now();
+        }
+        Err(e) if job.attempts <= job.max_retries => {
+            job.state = JobState::Pending;
+            job.progress = 0.0;
+            job.error = Some(e.to_string());
+        }
+        Err(e) => {
+            job.state = JobState::Failed;
+            job.error = Some(e.to_string());
+            job.finished_at = now();
+        }
+    }
+}
+
+/// Reports a running job's progress into the queue
+struct QueueProgress<'a> {
+    queue: &'a JobQueue,
+    id: u64,
+    registration: CancelRegistration,
+    /// Progress listeners last heard
+    reported: f64,
+}
+
+impl ProgressSink for QueueProgress<'_> {
+    fn progress(&mut self, fraction: f64) {
+        let mut state = self.queue.state();
+        let Some(job) = state.jobs.iter_mut().find(|job| job.id == self.id) else {
+            return;
+        };
+        job.progress = fraction;
+        if fraction - self.reported >= PROGRESS_STEP || fraction >= 1.0 {
+            self.reported = fraction;
+            let job = job.clone();
+            drop(state);
+            self.queue.notify(&job);
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.registration.is_cancelled()
+    }
+
+    fn gpu_fallback(&mut self, fallback: &GpuFallback) {
+        let recorded = self.queue.update(self.id, |job| {
+            job.fallback = Some(fallback.clone());
+            true
+        });
+        if let Some(job) = recorded {
+            for listener in lock(&self.queue.shared.fallback_listeners).iter() {
+                listener(&job, fallback);
+            }
+        }
+    }
+}
+
+/// Indices of the pending jobs in the order they'll start: highest
+/// priority first, then in the order they're stored
+fn run_order(jobs: &[Job]) -> Vec<usize> {
+    let mut order: Vec<usize> =
+        (0..jobs.len()).filter(|&i| jobs[i].state == JobState::Pending).collect();
+    order.sort_by_key(|&i| (Reverse(jobs[i].priority), i));
+    order
+}
+
+fn index_of(jobs: &[Job], id: u64) -> Option<usize> {
+    jobs.iter().position(|job| job.id == id)
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Where the app's queue is kept between runs, next to the settings
+pub fn queue_path() -> Option<PathBuf> {
+    Some(app_config_dir()?.join(QUEUE_FILE))
+}
+
+/// Write jobs to `path` through a temporary file, so a crash can't leave
+/// half a queue
+///
+/// Each save has a temporary file of its own, so two queues on one file
+/// can't write into each other's.
+fn save_jobs(path: &Path, jobs: &[Job]) -> io::Result<()> {
+    static SAVES: AtomicU64 = AtomicU64::new(0);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let save = SAVES.fetch_add(1, Ordering::Relaxed);
+    let tmp = path.with_extension(format!("json.{}-{}.tmp", std::process::id(), save));
+    fs::write(&tmp, serde_json::to_string_pretty(jobs)?)?;
+    fs::rename(&tmp, path)
+}
+
+fn load_jobs(path: &Path) -> io::Result<Vec<Job>> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+static APP_QUEUE: OnceLock<JobQueue> = OnceLock::new();
+
+/// The app's queue, saved at [`queue_path`], with the built-in runners
+/// registered
+///
+/// Runs as many jobs at once as the `background_jobs` setting says, one
+/// by default. Transcription jobs wait until a speech-to-text engine
+/// registers a runner, and start only when the saved
+/// [`Schedule`](crate::schedule::Schedule) allows.
+pub fn app_queue() -> &'static JobQueue {
+    APP_QUEUE.get_or_init(|| {
+        let max_concurrent = Settings::load().background_jobs.unwrap_or(1);
+        let queue = match queue_path() {
+            Some(path) => JobQueue::open(path, max_concurrent),
+            None => JobQueue::new(max_concurrent),
+        };
+        queue.register(JobKind::Waveform, run_waveform);
+        queue.register(JobKind::Translation, run_translation);
+        queue.set_gate(JobKind::Transcription, || {
+            Settings::load().schedule.current_gate() != Gate::Waiting
+        });
+        queue.on_change(announce_finished);
+        queue
+    })
+}
+
+/// Put a file on the app's queue to be transcribed in the default
+/// windows, labelled with its name
+///
+/// # Returns
+/// `None` if the file is already waiting or being transcribed
+pub fn queue_transcription(path: &Path, priority: Priority) -> Option<Job> {
+    let payload = TranscriptionJob {
+        path: path.to_path_buf(),
+        plan: ChunkPlan::default(),
+    };
+    let mut new = NewJob::new(JobKind::Transcription, to_result(&payload).ok()?).priority(priority);
+    if let Some(name) = path.file_name() {
+        new = new.label(name.to_string_lossy());
+    }
+    app_queue().submit_once(new)
+}
+
+/// Record a job that has finished for good in the history and tell whoever
+/// the notification settings name, like the commands do
+fn announce_finished(job: &Job) {
+    if let Some(event) = job.event() {
+        record_job_quietly(&event);
+        notify_in_background(Settings::load().notifications, event);
+    }
+}
+
+/// Payload of a [`JobKind::Transcription`] job
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptionJob {
+    pub path: PathBuf,
+    #[serde(default)]
+    pub plan: ChunkPlan,
+}
+
+/// Payload of a [`JobKind::Waveform`] job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaveformJob {
+    pub path: PathBuf,
+    #[serde(default)]
+    pub options: WaveformOptions,
+}
+
+/// Payload of a [`JobKind::Translation`] job
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranslationJobRequest {
+    #[serde(flatten)]
+    pub request: TranslationRequest,
+    /// `None` uses the saved engine
+    #[serde(default)]
+    pub engine: Option<TranslationEngine>,
+}
+
+/// How a translation is going, as [`TranslationJob`] reports it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranslationStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// A [`JobKind::Translation`] job as the translation panel shows it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranslationJob {
+    pub id: u64,
+    pub source_language: String,
+    pub target_language: String,
+    /// The subtitle or label file being translated, for file inputs
+    pub input_path: Option<PathBuf>,
+    pub status: TranslationStatus,
+    /// Fraction of segments translated, from 0.0 to 1.0
+    pub progress: f64,
+    pub error: Option<String>,
+    /// Local times with offset, RFC 3339
+    pub submitted_at: String,
+    pub finished_at: Option<String>,
+}
+
+impl TranslationJob {
+    /// `None` if `job` isn't a translation or its payload can't be read
+    pub fn from_job(job: &Job) -> Option<Self> {
+        if job.kind != JobKind::Translation {
+            return None;
+        }
+        let payload: TranslationJobRequest = serde_json::from_value(job.payload.clone()).ok()?;
+        let request = payload.request;
+        Some(Self {
+            id: job.id,
+            source_language: request.source_language,
+            target_language: request.target_language,
+            input_path: match request.input {
+                TranslationInput::File { path } => Some(path),
+                TranslationInput::Transcript { .. } => None,
+            },
+            status: match job.state {
+                JobState::Pending => TranslationStatus::Queued,
+                JobState::Running => TranslationStatus::Running,
+                JobState::Completed => TranslationStatus::Completed,
+                JobState::Failed => TranslationStatus::Failed,
+                JobState::Cancelled => TranslationStatus::Cancelled,
+            },
+            progress: job.progress,
+            error: job.error.clone(),
+            submitted_at: job.submitted_at.clone(),
+            finished_at: job.finished_at.clone(),
+        })
+    }
+}
+
+/// Runner for [`JobKind::Waveform`]; the result is the
+/// [`WaveformPeaks`](crate::audio::WaveformPeaks)
+pub fn run_waveform(payload: &Value, progress: &mut dyn ProgressSink) -> Result<Value> {
+    let job: WaveformJob = parse_payload(JobKind::Waveform, payload)?;
+    let peaks = extract_waveform_peaks_with_progress(&job.path, &job.options, progress)?;
+    to_result(&peaks)
+}
+
+/// A runner for [`JobKind::Transcription`] that transcribes with an
+/// engine made by `engine` for each job; the result is the
+/// [`Transcript`](crate::transcribe::Transcript)
+///
+/// `engine` is given the inference device the GPU settings pick, or `None`
+/// for the CPU. If it can't set up the GPU it reports
+/// [`AcceleratedError::GpuInit`] and is asked again for the CPU, and the
+/// fallback is recorded on the job (see [`JobQueue::on_gpu_fallback`]).
+///
+/// No engine ships with the app; whatever provides one registers it on
+/// [`app_queue`] for [`JobKind::Transcription`], and queued files wait
+/// until then.
+pub fn transcription_runner<F>(engine: F) -> impl JobRunner
+where
+    F: Fn(Option<InferenceDevice>) -> std::result::Result<Box<dyn Transcriber>, EngineError>
+        + Send
+        + Sync,
+{
+    move |payload: &Value, progress: &mut dyn ProgressSink| {
+        let device = select_inference_device(&Settings::load().gpu);
+        run_transcription(payload, device, &engine, progress)
+    }
+}
+
+/// Transcribe the file a [`JobKind::Transcription`] job names, starting on
+/// `device`
+fn run_transcription<F>(
+    payload: &Value,
+    device: Option<InferenceDevice>,
+    engine: &F,
+    progress: &mut dyn ProgressSink,
+) -> Result<Value>
+where
+    F: Fn(Option<InferenceDevice>) -> std::result::Result<Box<dyn Transcriber>, EngineError>,
+{
+    let job: TranscriptionJob = parse_payload(JobKind::Transcription, payload)?;
+    // Reported once the CPU run starts, as `progress` is busy until then
+    let fell_back = std::cell::RefCell::new(None);
+    let run = run_with_cpu_fallback(
+        device,
+        |device| {
+            if let Some(fallback) = fell_back.borrow_mut().take() {
+                progress.gpu_fallback(&fallback);
+            }
+            let mut transcriber = engine(device)?;
+            transcribe_file(&job.path, transcriber.as_mut(), job.plan, &mut *progress)
+                .map_err(AcceleratedError::Job)
+        },
+        |fallback| *fell_back.borrow_mut() = Some(fallback.clone()),
+    )?;
+    to_result(&run.output)
+}
+
+/// How making a transcription engine can fail: setting up the GPU, or
+/// anything else
+pub type EngineError = AcceleratedError<AudioError>;
+
+/// Runner for [`JobKind::Translation`]; the result is the translated
+/// segments
+pub fn run_translation(payload: &Value, progress: &mut dyn ProgressSink) -> Result<Value> {
+    let job: TranslationJobRequest = parse_payload(JobKind::Translation, payload)?;
+    let engine = job
+        .engine
+        .or_else(|| Settings::load().translation_engine)
+        .ok_or_else(|| {
+            AudioError::InvalidParameter("No translation engine is set up".to_string())
+        })?;
+    let request = job.request;
+    let segments = request.input.segments()?;
+    let mut translator = lease_translator(&engine, progress)?;
+    let translated = translate_segments(
+        &segments,
+        &request.source_language,
+        &request.target_language,
+        translator.as_mut(),
+        progress,
+    )?;
+    to_result(&translated)
+}
+
+fn parse_payload<T: DeserializeOwned>(kind: JobKind, payload: &Value) -> Result<T> {
+    serde_json::from_value(payload.clone())
+        .map_err(|e| AudioError::InvalidParameter(format!("Bad {:?} job: {}", kind, e)))
+}
+
+/// A unit enum as its serde name, e.g. "transcription"
+fn to_text<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn to_result<T: Serialize>(value: &T) -> Result<Value> {
+    Ok(serde_json::to_value(value).map_err(io::Error::from)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::JobOutcome;
+    use crate::progress::NoProgress;
+    use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize};
+    use std::time::{Duration, Instant};
+
+    /// Poll until `id` has finished
+    fn wait(queue: &JobQueue, id: u64) -> Job {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            let job = queue.job(id).unwrap();
+            if job.state.is_finished() || Instant::now() > deadline {
+                return job;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    fn job(name: &str) -> NewJob {
+        NewJob::new(JobKind::Waveform, Value::from(name))
+    }
+
+    #[test]
+    fn test_priority_order_and_events() {
+        let queue = JobQueue::new(1);
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let seen = events.clone();
+        queue.on_change(move |job| lock(&seen).push((job.id, job.state)));
+
+        // Nothing runs until there's a runner
+        let low = queue.submit(job("low").priority(Priority::Low));
+        let first = queue.submit(job("first"));
+        let high = queue.submit(job("high").priority(Priority::High));
+        let second = queue.submit(job("second"));
+        assert!(queue.set_priority(second.id, Priority::High));
+        assert!(queue.jobs().iter().all(|job| job.state == JobState::Pending));
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let ran = order.clone();
+        queue.register(JobKind::Waveform, move |payload: &Value, _: &mut dyn ProgressSink| {
+            lock(&ran).push(payload.as_str().unwrap().to_string());
+            Ok(Value::from(payload.as_str().unwrap().len()))
+        });
+        assert_eq!(wait(&queue, low.id).state, JobState::Completed);
+        assert_eq!(*lock(&order), ["high", "second", "first", "low"]);
+        assert_eq!(queue.job(first.id).unwrap().result, Some(Value::from(5)));
+
+        let states: Vec<JobState> =
+            lock(&events).iter().filter(|(id, _)| *id == high.id).map(|(_, s)| *s).collect();
+        assert_eq!(states, [JobState::Pending, JobState::Running, JobState::Completed]);
+        assert_eq!(queue.clear_finished(Some(JobKind::Translation)), 0);
+        assert_eq!(queue.clear_finished(None), 4);
+        assert!(queue.jobs().is_empty());
+    }
+
+    #[test]
+    fn test_moves_keep_the_run_order_by_priority() {
+        let queue = JobQueue::new(1);
+        let submit = |name: &str, priority| queue.submit(job(name).priority(priority)).id;
+        let batch = submit("batch", Priority::Low);
+        let next = submit("next", Priority::Normal);
+        let urgent = submit("urgent", Priority::High);
+        let later = submit("later", Priority::Normal);
+        assert!(queue.submit_once(job("batch").priority(Priority::High)).is_none());
+        assert_eq!(queue.submit_once(job("fifth").priority(Priority::Low)).unwrap().id, 5);
+        let order = |queue: &JobQueue| -> Vec<u64> {
+            queue.run_order(Some(JobKind::Waveform)).iter().map(|job| job.id).collect()
+        };
+        assert_eq!(order(&queue), [urgent, next, later, batch, 5]);
+        assert!(queue.run_order(Some(JobKind::Transcription)).is_empty());
+
+        // Dragged to the front, ahead of the high-priority job
+        assert!(queue.move_to(later, 0));
+        assert_eq!(order(&queue), [later, urgent, next, batch, 5]);
+        assert_eq!(queue.job(later).unwrap().priority, Priority::High);
+        // Within its own priority, then to the very back
+        assert!(queue.move_to(urgent, 0));
+        assert_eq!(order(&queue), [urgent, later, next, batch, 5]);
+        assert!(queue.move_to(next, usize::MAX));
+        assert_eq!(order(&queue), [urgent, later, batch, 5, next]);
+        assert_eq!(queue.job(next).unwrap().priority, Priority::Low);
+
+        assert!(!queue.move_to(99, 0));
+        queue.cancel(batch);
+        assert!(!queue.move_to(batch, 0), "only pending jobs move");
+        let runs = queue.run_order(None);
+        assert!(runs.windows(2).all(|pair| pair[0].priority >= pair[1].priority));
+
+        // They start in that order once there's a runner
+        let ran = Arc::new(Mutex::new(Vec::new()));
+        let seen = ran.clone();
+        queue.register(JobKind::Waveform, move |payload: &Value, _: &mut dyn ProgressSink| {
+            lock(&seen).push(payload.as_str().unwrap().to_string());
+            Ok(Value::Null)
+        });
+        assert_eq!(wait(&queue, next).state, JobState::Completed);
+        assert_eq!(*lock(&ran), ["urgent", "later", "fifth", "next"]);
+    }
+
+    #[test]
+    fn test_transcription_runs_on_the_queue() {
+        struct Engine;
+        impl Transcriber for Engine {
+            fn transcribe(
+                &mut self,
+                audio: &crate::audio::AudioData,
+            ) -> Result<Vec<crate::transcribe::Segment>> {
+                Ok(vec![crate::transcribe::Segment {
+                    start: crate::audio::Timestamp::ZERO,
+                    end: crate::audio::Timestamp::from_seconds(audio.duration_seconds()),
+                    text: "Amen".to_string(),
+                    words: Vec::new(),
+                    speaker: None,
+                }])
+            }
+        }
+        let path = std::env::temp_dir().join("hermeneia_test_job_transcription.wav");
+        let audio = crate::audio::AudioData {
+            samples: vec![0.0; 16000],
+            sample_rate: 16000,
+            channels: 1,
+        };
+        crate::audio::encode_wav(&audio, &path).unwrap();
+
+        let queue = JobQueue::new(1);
+        let payload = serde_json::json!({ "path": path });
+        let queued = queue.submit(NewJob::new(JobKind::Transcription, payload.clone()));
+        assert_eq!(queue.job(queued.id).unwrap().state, JobState::Pending, "no engine yet");
+        queue.register(JobKind::Transcription, transcription_runner(|_| Ok(Box::new(Engine))));
+        let done = wait(&queue, queued.id);
+        assert_eq!(done.state, JobState::Completed);
+        let transcript: crate::transcribe::Transcript =
+            serde_json::from_value(done.result.unwrap()).unwrap();
+        assert_eq!((transcript.chunks, transcript.segments[0].text.as_str()), (1, "Amen"));
+
+        // An engine that can't set up the GPU is asked again for the CPU
+        let gpu = InferenceDevice {
+            backend: crate::gpu::ComputeBackend::Cuda,
+            index: 200,
+        };
+        let fallbacks = Arc::new(Mutex::new(Vec::new()));
+        let heard = fallbacks.clone();
+        queue.on_gpu_fallback(move |job, fallback| lock(&heard).push((job.id, fallback.clone())));
+        let on_gpu = move |payload: &Value, progress: &mut dyn ProgressSink| {
+            let engine = |device: Option<InferenceDevice>| match device {
+                Some(_) => Err(AcceleratedError::GpuInit(crate::gpu::GpuInitError::new(
+                    "CUDA_ERROR_OUT_OF_MEMORY",
+                ))),
+                None => Ok(Box::new(Engine) as Box<dyn Transcriber>),
+            };
+            run_transcription(payload, Some(gpu), &engine, progress)
+        };
+        queue.register(JobKind::Transcription, on_gpu);
+        let moved = wait(&queue, queue.submit(NewJob::new(JobKind::Transcription, payload)).id);
+        assert_eq!(moved.state, JobState::Completed);
+        let fallback = moved.fallback.unwrap();
+        assert_eq!((fallback.device_index, fallback.disabled), (200, false));
+        assert_eq!(*lock(&fallbacks), [(moved.id, fallback)]);
+        assert_eq!(done.fallback, None);
+
+        let missing = serde_json::json!({ "path": path.with_extension("flac") });
+        let failed = wait(&queue, queue.submit(NewJob::new(JobKind::Transcription, missing)).id);
+        assert_eq!(failed.state, JobState::Failed);
+        crate::audio::release_reader(&path);
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_closed_gate_holds_jobs_back() {
+        let queue = JobQueue::new(2);
+        let open = Arc::new(AtomicBool::new(false));
+        let window = open.clone();
+        queue.set_gate(JobKind::Transcription, move || window.load(Ordering::SeqCst));
+        let runner = |_: &Value, _: &mut dyn ProgressSink| Ok(Value::Null);
+        queue.register(JobKind::Transcription, runner);
+        queue.register(JobKind::Waveform, runner);
+
+        // Outside the window: other kinds still run
+        let held = queue.submit(NewJob::new(JobKind::Transcription, Value::from("sermon")));
+        let other = queue.submit(job("peaks"));
+        assert_eq!(wait(&queue, other.id).state, JobState::Completed);
+        std::thread::sleep(Duration::from_millis(20));
+        let still = queue.job(held.id).unwrap();
+        assert_eq!((still.state, still.attempts), (JobState::Pending, 0));
+
+        // Opening the gate alone starts nothing until the queue is woken
+        open.store(true, Ordering::SeqCst);
+        assert_eq!(queue.job(held.id).unwrap().state, JobState::Pending);
+        queue.wake();
+        assert_eq!(wait(&queue, held.id).state, JobState::Completed);
+    }
+
+    #[test]
+    fn test_finished_jobs_become_events() {
+        let queue = JobQueue::new(1);
+        queue.register(JobKind::Waveform, |payload: &Value, _: &mut dyn ProgressSink| {
+            match payload["path"].as_str() {
+                Some("/sermons/ok.mp3") => Ok(Value::Null),
+                _ => Err(AudioError::InvalidParameter("unreadable".to_string())),
+            }
+        });
+        let submit = |path: &str| {
+            let payload = serde_json::json!({ "path": path });
+            let id = queue.submit(NewJob::new(JobKind::Waveform, payload)).id;
+            wait(&queue, id)
+        };
+        let done = submit("/sermons/ok.mp3").event().unwrap();
+        assert_eq!((done.job.as_str(), done.outcome), ("waveform", JobOutcome::Completed));
+        assert_eq!(done.inputs, ["/sermons/ok.mp3"]);
+        assert!(done.duration_seconds >= 0.0);
+
+        let failed = submit("/sermons/bad.mp3").event().unwrap();
+        assert_eq!(failed.outcome, JobOutcome::Failed);
+        assert!(failed.error.unwrap().contains("unreadable"));
+
+        let pending = queue.submit(NewJob::new(JobKind::Translation, Value::Null));
+        assert!(pending.event().is_none(), "not finished");
+        queue.cancel(pending.id);
+        assert!(queue.job(pending.id).unwrap().event().is_none(), "cancelled by the user");
+    }
+
+    #[test]
+    fn test_retries_then_failure() {
+        let queue = JobQueue::new(2);
+        let calls = Arc::new(AtomicU32::new(0));
+        let counted = calls.clone();
+        queue.register(JobKind::Translation, move |_: &Value, _: &mut dyn ProgressSink| {
+            // Fails twice, then works
+            match counted.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Err(AudioError::InvalidParameter("engine busy".to_string())),
+                _ => Ok(Value::Null),
+            }
+        });
+        let flaky = queue.submit(NewJob::new(JobKind::Translation, Value::Null).retries(2));
+        let done = wait(&queue, flaky.id);
+        assert_eq!((done.state, done.attempts, done.error), (JobState::Completed, 3, None));
+
+        calls.store(0, Ordering::SeqCst);
+        let once = queue.submit(NewJob::new(JobKind::Translation, Value::Null).retries(1));
+        let failed = wait(&queue, once.id);
+        assert_eq!((failed.state, failed.attempts), (JobState::Failed, 2));
+        assert!(failed.error.unwrap().contains("engine busy"));
+
+        assert!(queue.retry(once.id));
+        assert_eq!(wait(&queue, once.id).state, JobState::Completed);
+        assert!(!queue.retry(once.id), "only failed or cancelled jobs are retried");
+    }
+
+    #[test]
+    fn test_concurrency_limit_and_cancelling() {
+        let queue = JobQueue::new(2);
+        let running = Arc::new(AtomicUsize::new(0));
+        let most = Arc::new(AtomicUsize::new(0));
+        let (now, peak) = (running.clone(), most.clone());
+        queue.register(JobKind::Waveform, move |_: &Value, progress: &mut dyn ProgressSink| {
+            let count = now.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(count, Ordering::SeqCst);
+            let result = (0..200).try_for_each(|step| {
+                progress.progress(step as f64 / 200.0);
+                crate::progress::check_cancelled(progress)?;
+                std::thread::sleep(Duration::from_millis(1));
+                Ok(())
+            });
+            now.fetch_sub(1, Ordering::SeqCst);
+            result.map(|_| Value::Null)
+        });
+        let ids: Vec<u64> = (0..4).map(|i| queue.submit(job(&i.to_string())).id).collect();
+        let waiting = queue.submit(job("never").priority(Priority::Low));
+        assert!(queue.cancel(waiting.id));
+        assert!(queue.cancel(ids[0]), "running jobs are asked to stop");
+
+        assert_eq!(wait(&queue, ids[0]).state, JobState::Cancelled);
+        for &id in &ids[1..] {
+            assert_eq!(wait(&queue, id).state, JobState::Completed);
+        }
+        let never = queue.job(waiting.id).unwrap();
+        assert_eq!((never.state, never.attempts), (JobState::Cancelled, 0));
+        assert_eq!(most.load(Ordering::SeqCst), 2);
+        assert!(!queue.cancel(ids[1]), "already finished");
+        assert!(!queue.cancel(99));
+    }
+
+    #[test]
+    fn test_saved_queue_resumes() {
+        // Left behind: the cut-off job's thread saves again once released
+        let path = std::env::temp_dir().join("hermeneia_test_job_queue.json");
+        let _ = fs::remove_file(&path);
+        let release = Arc::new(AtomicBool::new(false));
+        {
+            let queue = JobQueue::open(&path, 1);
+            let wait_for = release.clone();
+            queue.register(JobKind::Waveform, move |_: &Value, _: &mut dyn ProgressSink| {
+                while !wait_for.load(Ordering::SeqCst) {
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                Ok(Value::Null)
+            });
+            let cut_off = queue.submit(job("cut off").label("sermon.mp3"));
+            queue.submit(job("waiting"));
+            while queue.job(cut_off.id).unwrap().state != JobState::Running {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+
+            // The app stops here, with one job running and one waiting
+            let reopened = JobQueue::open(&path, 1);
+            let jobs = reopened.jobs();
+            assert_eq!(jobs.len(), 2);
+            assert_eq!((jobs[0].state, jobs[0].attempts), (JobState::Pending, 1));
+            assert_eq!(jobs[0].label.as_deref(), Some("sermon.mp3"));
+            assert_eq!((jobs[1].state, jobs[1].attempts), (JobState::Pending, 0));
+            assert_eq!(reopened.submit(job("next")).id, 3);
+        }
+        release.store(true, Ordering::SeqCst);
+
+        let broken = std::env::temp_dir().join("hermeneia_test_job_queue_broken.json");
+        fs::write(&broken, "not json").unwrap();
+        assert!(JobQueue::open(&broken, 1).jobs().is_empty());
+        fs::remove_file(broken).ok();
+    }
+
+    #[test]
+    fn test_translation_payload() {
+        let payload = serde_json::json!({
+            "source_language": "en",
+            "target_language": "es",
+            "input": {"kind": "file", "path": "/no/such/hermeneia_sermon.srt"},
+            "engine": {"engine": "command", "program": "true"},
+        });
+        let job: TranslationJobRequest = serde_json::from_value(payload.clone()).unwrap();
+        assert_eq!(job.request.target_language, "es");
+
+        let queue = JobQueue::new(1);
+        let queued = queue.submit(NewJob::new(JobKind::Translation, payload.clone()));
+        let view = TranslationJob::from_job(&queued).unwrap();
+        assert_eq!((view.id, view.status), (queued.id, TranslationStatus::Queued));
+        assert_eq!(view.input_path.unwrap(), Path::new("/no/such/hermeneia_sermon.srt"));
+        assert!(queue.cancel(queued.id));
+        let cancelled = TranslationJob::from_job(&queue.job(queued.id).unwrap()).unwrap();
+        assert_eq!(cancelled.status, TranslationStatus::Cancelled);
+        let peaks = queue.submit(NewJob::new(JobKind::Waveform, Value::Null));
+        assert!(TranslationJob::from_job(&peaks).is_none());
+        assert_eq!(queue.clear_finished(Some(JobKind::Translation)), 1);
+
+        assert!(run_translation(&payload, &mut NoProgress).is_err());
+        assert!(matches!(
+            run_translation(&Value::from("sermon.srt"), &mut NoProgress),
+            Err(AudioError::InvalidParameter(_))
+        ));
+    }
+}
@@ -0,0 +1,360 @@
+// src-tauri/src/karaoke.rs
+// Word-synced lyric files (enhanced LRC, ASS and SSA) from transcripts
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::audio::render::Color;
+use crate::audio::time::Timestamp;
+use crate::error::{AudioError, Result};
+use crate::transcribe::{Segment, Word};
+
+/// Look of the ASS/SSA subtitle style; LRC files carry no styling
+///
+/// During a line, each word sweeps from `unsung` to `sung` as it is spoken.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct KaraokeStyle {
+    pub font: String,
+    pub font_size: u32,
+    /// Words already spoken
+    pub sung: Color,
+    /// Words still to come
+    pub unsung: Color,
+    pub outline: Color,
+    /// Video size the positions and font size refer to
+    pub width: u32,
+    pub height: u32,
+    /// Distance of the text from the bottom edge
+    pub margin: u32,
+}
+
+impl Default for KaraokeStyle {
+    fn default() -> Self {
+        Self {
+            font: "Arial".to_string(),
+            font_size: 64,
+            sung: Color::rgb(0xff, 0xd2, 0x3f),
+            unsung: Color::rgb(0xff, 0xff, 0xff),
+            outline: Color::rgb(0, 0, 0),
+            width: 1920,
+            height: 1080,
+            margin: 80,
+        }
+    }
+}
+
+/// Enhanced LRC: one line per segment, each word tagged with its start
+///
+/// ```text
+/// [00:12.00]<00:12.00>Amazing <00:12.50>grace<00:13.20>
+/// [00:13.20]
+/// ```
+///
+/// A line ends with the time its last word ends. When a pause follows, an
+/// empty line at that time clears the display.
+pub fn to_enhanced_lrc(segments: &[Segment]) -> String {
+    let mut lrc = String::new();
+    for (i, segment) in segments.iter().enumerate() {
+        let words = timed_words(segment);
+        let (start, end) = line_span(segment, &words);
+        let _ = write!(lrc, "[{}]", lrc_time(start));
+        for (j, word) in words.iter().enumerate() {
+            let separator = if j + 1 < words.len() { " " } else { "" };
+            let _ = write!(lrc, "<{}>{}{}", lrc_time(word.start), word.text, separator);
+        }
+        let _ = writeln!(lrc, "<{}>", lrc_time(end));
+
+        let next = segments.get(i + 1).map(|next| next.start);
+        if next.is_none_or(|next| next > end) {
+            let _ = writeln!(lrc, "[{}]", lrc_time(end));
+        }
+    }
+    lrc
+}
+
+/// Advanced SubStation Alpha (ASS) with `\kf` sweeps, one event per segment
+pub fn to_ass(segments: &[Segment], style: &KaraokeStyle) -> String {
+    let mut ass = script_info("v4.00+", style.width, style.height);
+    let _ = writeln!(
+        ass,
+        "[V4+ Styles]\n\
+         Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, \
+         BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, \
+         BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n\
+         Style: Karaoke,{},{},{},{},{},&H80000000,0,0,0,0,100,100,0,0,1,3,0,2,{m},{m},{m},1\n",
+        style.font,
+        style.font_size,
+        ass_color(style.sung),
+        ass_color(style.unsung),
+        ass_color(style.outline),
+        m = style.margin
+    );
+    ass.push_str(
+        "[Events]\n\
+         Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n",
+    );
+    for segment in segments {
+        let (start, end, text) = karaoke_line(segment, "kf");
+        let _ = writeln!(
+            ass,
+            "Dialogue: 0,{},{},Karaoke,,0,0,0,,{}",
+            ass_time(start),
+            ass_time(end),
+            text
+        );
+    }
+    ass
+}
+
+/// SubStation Alpha v4 (SSA), for players that predate ASS; uses `\K` sweeps
+pub fn to_ssa(segments: &[Segment], style: &KaraokeStyle) -> String {
+    let mut ssa = script_info("v4.00", style.width, style.height);
+    let _ = writeln!(
+        ssa,
+        "[V4 Styles]\n\
+         Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, TertiaryColour, \
+         BackColour, Bold, Italic, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, \
+         MarginV, AlphaLevel, Encoding\n\
+         Style: Karaoke,{},{},{},{},{},0,0,0,1,3,0,2,{m},{m},{m},0,1\n",
+        style.font,
+        style.font_size,
+        ssa_color(style.sung),
+        ssa_color(style.unsung),
+        ssa_color(style.outline),
+        m = style.margin
+    );
+    ssa.push_str(
+        "[Events]\n\
+         Format: Marked, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n",
+    );
+    for segment in segments {
+        let (start, end, text) = karaoke_line(segment, "K");
+        let _ = writeln!(
+            ssa,
+            "Dialogue: Marked=0,{},{},Karaoke,,0000,0000,0000,,{}",
+            ass_time(start),
+            ass_time(end),
+            text
+        );
+    }
+    ssa
+}
+
+/// Write a karaoke file, choosing LRC, ASS or SSA by the path's extension
+///
+/// # Example
+/// ```no_run
+/// use hermeneia_lib::karaoke::{write_karaoke, KaraokeStyle};
+/// use hermeneia_lib::transcribe::Transcript;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let transcript: Transcript = unimplemented!();
+/// write_karaoke("psalm23.ass", &transcript.segments, &KaraokeStyle::default())?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn write_karaoke<P: AsRef<Path>>(
+    path: P,
+    segments: &[Segment],
+    style: &KaraokeStyle,
+) -> Result<()> {
+    let path = path.as_ref();
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    let contents = match extension.as_deref() {
+        Some("lrc") => to_enhanced_lrc(segments),
+        Some("ass") => to_ass(segments, style),
+        Some("ssa") => to_ssa(segments, style),
+        _ => {
+            return Err(AudioError::UnsupportedFormat(format!(
+                "can't write karaoke to '{}'; use a .lrc, .ass or .ssa file",
+                path.display()
+            )))
+        }
+    };
+    std::fs::write(path, contents)?;
+    Ok(())
+}
+
+/// The segment's words, or the whole segment as one word when the engine
+/// gave no word timing
+fn timed_words(segment: &Segment) -> Vec<Word> {
+    if segment.words.is_empty() {
+        vec![Word {
+            start: segment.start,
+            end: segment.end,
+            text: segment.text.clone(),
+        }]
+    } else {
+        segment.words.clone()
+    }
+}
+
+/// When a line shows: the segment, stretched to cover all its words
+fn line_span(segment: &Segment, words: &[Word]) -> (Timestamp, Timestamp) {
+    let start = words.iter().map(|w| w.start).fold(segment.start, Timestamp::min);
+    let end = words.iter().map(|w| w.end).fold(segment.end, Timestamp::max);
+    (start, end)
+}
+
+/// Start, end and text of one ASS/SSA event, with a `\<tag>` sweep per word
+///
+/// Pauses before and between words become empty `\k` blocks, so every
+/// word starts sweeping exactly when it is spoken.
+fn karaoke_line(segment: &Segment, tag: &str) -> (Timestamp, Timestamp, String) {
+    let words = timed_words(segment);
+    let (start, end) = line_span(segment, &words);
+    let mut text = String::new();
+    let mut cursor = centis(start);
+    for (i, word) in words.iter().enumerate() {
+        let (word_start, word_end) = (centis(word.start).max(cursor), centis(word.end));
+        if word_start > cursor {
+            let _ = write!(text, "{{\\k{}}}", word_start - cursor);
+        }
+        let separator = if i + 1 < words.len() { " " } else { "" };
+        let duration = word_end.saturating_sub(word_start);
+        let _ = write!(text, "{{\\{}{}}}{}{}", tag, duration, ass_text(&word.text), separator);
+        cursor = word_start + duration;
+    }
+    (start, end, text)
+}
+
+pub(crate) fn script_info(version: &str, width: u32, height: u32) -> String {
+    format!(
+        "[Script Info]\n\
+         ScriptType: {}\n\
+         PlayResX: {}\n\
+         PlayResY: {}\n\
+         WrapStyle: 0\n\n",
+        version, width, height
+    )
+}
+
+fn centis(time: Timestamp) -> u64 {
+    time.to_frames(100)
+}
+
+/// `mm:ss.xx`; minutes go past 99 for long files
+fn lrc_time(time: Timestamp) -> String {
+    let cs = centis(time);
+    format!("{:02}:{:02}.{:02}", cs / 6000, cs / 100 % 60, cs % 100)
+}
+
+/// `h:mm:ss.xx`
+pub(crate) fn ass_time(time: Timestamp) -> String {
+    let cs = centis(time);
+    format!("{}:{:02}:{:02}.{:02}", cs / 360_000, cs / 6000 % 60, cs / 100 % 60, cs % 100)
+}
+
+/// `&HAABBGGRR`, where alpha 00 is opaque
+pub(crate) fn ass_color(color: Color) -> String {
+    format!("&H{:02X}{:02X}{:02X}{:02X}", 255 - color.a, color.b, color.g, color.r)
+}
+
+/// SSA colors are BGR as a decimal number, without alpha
+pub(crate) fn ssa_color(color: Color) -> u32 {
+    u32::from_le_bytes([color.r, color.g, color.b, 0])
+}
+
+/// Braces and backslashes would start override tags, so swap them for
+/// look-alikes
+pub(crate) fn ass_text(text: &str) -> String {
+    text.replace('{', "(").replace('}', ")").replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(seconds: f64) -> Timestamp {
+        Timestamp::from_seconds(seconds)
+    }
+
+    fn word(start: f64, end: f64, text: &str) -> Word {
+        Word {
+            start: secs(start),
+            end: secs(end),
+            text: text.to_string(),
+        }
+    }
+
+    fn segments() -> Vec<Segment> {
+        vec![
+            Segment {
+                start: secs(12.0),
+                end: secs(13.2),
+                text: "Amazing grace".to_string(),
+                words: vec![word(12.0, 12.4, "Amazing"), word(12.5, 13.2, "grace")],
+                speaker: None,
+            },
+            Segment {
+                start: secs(13.2),
+                end: secs(14.0),
+                text: "how sweet".to_string(),
+                words: Vec::new(),
+                speaker: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_enhanced_lrc() {
+        assert_eq!(
+            to_enhanced_lrc(&segments()),
+            "[00:12.00]<00:12.00>Amazing <00:12.50>grace<00:13.20>\n\
+             [00:13.20]<00:13.20>how sweet<00:14.00>\n\
+             [00:14.00]\n"
+        );
+    }
+
+    #[test]
+    fn test_ass_events_sweep_each_word() {
+        let ass = to_ass(&segments(), &KaraokeStyle::default());
+        assert!(ass.contains("Style: Karaoke,Arial,64,&H003FD2FF,&H00FFFFFF,&H00000000,"));
+        let events: Vec<_> = ass.lines().filter(|l| l.starts_with("Dialogue:")).collect();
+        assert_eq!(
+            events,
+            vec![
+                concat!(
+                    r"Dialogue: 0,0:00:12.00,0:00:13.20,Karaoke,,0,0,0,,",
+                    r"{\kf40}Amazing {\k10}{\kf70}grace"
+                ),
+                r"Dialogue: 0,0:00:13.20,0:00:14.00,Karaoke,,0,0,0,,{\kf80}how sweet",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ssa_uses_its_own_sections() {
+        let ssa = to_ssa(&segments(), &KaraokeStyle::default());
+        assert!(ssa.contains("ScriptType: v4.00\n"));
+        assert!(ssa.contains("[V4 Styles]"));
+        assert!(ssa.contains(&format!("Style: Karaoke,Arial,64,{},", 0x3fd2ff)));
+        assert!(ssa.contains(r"Marked=0,0:00:12.00,0:00:13.20,Karaoke,,0000,0000,0000,,{\K40}"));
+    }
+
+    #[test]
+    fn test_override_characters_are_escaped() {
+        let segment = Segment {
+            start: secs(0.0),
+            end: secs(1.0),
+            text: r"{\b1}bold".to_string(),
+            words: Vec::new(),
+            speaker: None,
+        };
+        let (_, _, text) = karaoke_line(&segment, "kf");
+        assert_eq!(text, r"{\kf100}(/b1)bold");
+    }
+
+    #[test]
+    fn test_unknown_extension_is_rejected() {
+        let path = std::env::temp_dir().join("hermeneia_test_karaoke.txt");
+        assert!(write_karaoke(&path, &segments(), &KaraokeStyle::default()).is_err());
+        assert!(!path.exists());
+    }
+}
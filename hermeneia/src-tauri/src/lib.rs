@@ -1,45 +1,71 @@
+//! Hermeneia's audio engine, usable from other Rust projects
+//!
+//! Start with [`prelude`]. The stable API, which only changes in a
+//! semver-major release, is:
+//!
+//! - everything in [`prelude`]
+//! - [`audio`] and the modules under it, except items marked hidden
+//! - [`error`], [`i18n`], [`playback`], [`progress`] and [`transcribe`]
+//! - `nonblocking`, the async wrappers built with the `async` feature
+//!
+//! No stable item exposes a type from symphonia, cpal or the encoder
+//! crates, so those can be upgraded or replaced in a minor release. The
+//! error enums, [`audio::OutputFormat`], [`audio::TrimParams`] and
+//! [`transcribe::ChunkPlan`] are `#[non_exhaustive]`, so variants and
+//! settings can be added in a minor release too; build the structs with
+//! their constructors. The
+//! remaining public modules (`cli`, `gpu`, `memory`, `pool`, `power`,
+//! `profile`, `settings`) exist for the bundled command-line tools and the
+//! desktop app; they are hidden from the docs and may change at any time.
+//!
+//! The desktop app itself (its Tauri commands and `run`) is behind the
+//! default `tauri` feature. Build with `--no-default-features` (adding back
+//! `opus` if wanted) to use the library and CLI tools without a webview.
+
 pub mod audio;
+#[doc(hidden)]
+pub mod cli;
+#[cfg(feature = "tauri")]
+mod commands;
 pub mod error;
+#[doc(hidden)]
 pub mod gpu;
+pub mod i18n;
+pub mod ingest;
+pub mod jobs;
+pub mod karaoke;
+#[doc(hidden)]
+pub mod memory;
+pub mod models;
+pub mod naming;
+pub mod pacing;
+#[cfg(feature = "async")]
+pub mod nonblocking;
+pub mod notify;
+pub mod playback;
+#[doc(hidden)]
+pub mod pool;
+#[doc(hidden)]
+pub mod power;
+pub mod prelude;
+#[doc(hidden)]
+pub mod profile;
+pub mod progress;
+pub mod runtime;
+#[doc(hidden)]
+pub mod schedule;
+#[doc(hidden)]
+pub mod settings;
+pub mod speakers;
+pub mod storage;
+pub mod subtitles;
+pub mod transcribe;
+pub mod transcript_diff;
+pub mod translation;
 
 // Re-export for convenience
 pub use audio::*;
-pub use error::{AudioError, Result};
-
-// Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
-#[tauri::command]
-fn greet(name: &str) -> String {
-    format!("Hello, {}! You've been greeted from Rust!", name)
-}
-
-/// Extract waveform peaks from an audio file for visualization
-///
-/// Tauri command that processes audio files and returns peak data
-/// for displaying waveforms in the frontend.
-///
-/// # Arguments
-/// * `file_path` - Path to the audio file
-/// * `num_peaks` - Optional number of peaks (default: 2000)
-///
-/// # Returns
-/// WaveformPeaks as JSON with min/max peak arrays
-#[tauri::command]
-fn get_waveform_peaks(
-    file_path: String,
-    num_peaks: Option<usize>,
-) -> std::result::Result<WaveformPeaks, String> {
-    audio::extract_waveform_peaks(&file_path, num_peaks)
-        .map_err(|e| e.to_string())
-}
-
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-
-    gpu::apply_optimizations();
+pub use error::{AnalysisError, AudioError, DecodeError, EncodeError, PlaybackError, Result};
 
-    tauri::Builder::default()
-        .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![greet, get_waveform_peaks])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
-}
+#[cfg(feature = "tauri")]
+pub use commands::run;
@@ -0,0 +1,293 @@
+// src-tauri/src/memory.rs
+// Memory budget that decides between in-memory and streaming processing
+
+use serde::Serialize;
+use tracing::info;
+
+use crate::error::{AudioError, Result};
+use crate::settings::Settings;
+
+const MIB: u64 = 1024 * 1024;
+
+/// Budget used when total memory can't be determined
+const FALLBACK_BUDGET_BYTES: u64 = 2048 * MIB;
+
+/// Machines with this much memory or less get low-memory mode unless the
+/// user says otherwise
+pub const LOW_MEMORY_THRESHOLD_BYTES: u64 = 8192 * MIB;
+
+/// How an operation should handle its audio
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessingMode {
+    /// Decode everything into one buffer
+    InMemory,
+    /// Work chunk by chunk so memory use stays flat
+    Streaming,
+}
+
+/// Most memory one operation may use for sample buffers
+///
+/// Decoded audio is 4 bytes per sample, so a 4-hour stereo 48 kHz file
+/// needs about 5.5 GB before any processing copies. Operations estimate
+/// their needs up front and switch to streaming (or refuse, when they have
+/// no streaming path) instead of getting killed by the OS.
+///
+/// In low-memory mode every operation with a streaming path takes it,
+/// whatever the estimate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryBudget {
+    bytes: u64,
+    low_memory: bool,
+}
+
+impl MemoryBudget {
+    pub fn from_mb(mb: u64) -> Self {
+        Self {
+            bytes: mb * MIB,
+            low_memory: false,
+        }
+    }
+
+    /// Budget from the `memory_budget_mb` setting, else half the machine's
+    /// memory (a quarter in low-memory mode)
+    pub fn from_settings(settings: &Settings) -> Self {
+        let low_memory = low_memory_enabled(settings);
+        let share = if low_memory { 4 } else { 2 };
+        let bytes = match settings.memory_budget_mb {
+            Some(mb) => mb * MIB,
+            None => total_memory_bytes().map_or(FALLBACK_BUDGET_BYTES, |total| total / share),
+        };
+        Self { bytes, low_memory }
+    }
+
+    /// Budget for the current settings
+    pub fn current() -> Self {
+        Self::from_settings(&Settings::load())
+    }
+
+    /// Share of the budget for one of `jobs` operations running side by side
+    pub fn per_job(self, jobs: usize) -> Self {
+        Self {
+            bytes: self.bytes / jobs.max(1) as u64,
+            ..self
+        }
+    }
+
+    /// Always stream where possible, as in low-memory mode
+    pub fn with_low_memory(mut self, low_memory: bool) -> Self {
+        self.low_memory = low_memory;
+        self
+    }
+
+    pub fn is_low_memory(&self) -> bool {
+        self.low_memory
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    pub fn megabytes(&self) -> u64 {
+        self.bytes / MIB
+    }
+
+    /// `required` bytes fit in the budget
+    pub fn allows(&self, required: u64) -> bool {
+        required <= self.bytes
+    }
+
+    /// In memory when `required` fits, streaming otherwise or in low-memory mode
+    pub fn mode_for(&self, required: u64) -> ProcessingMode {
+        if self.low_memory {
+            ProcessingMode::Streaming
+        } else if self.allows(required) {
+            ProcessingMode::InMemory
+        } else {
+            info!(
+                required_mb = required.div_ceil(MIB),
+                budget_mb = self.megabytes(),
+                "Over the memory budget; streaming instead"
+            );
+            ProcessingMode::Streaming
+        }
+    }
+
+    /// Fail with [`AudioError::MemoryBudgetExceeded`] when `required` doesn't fit
+    ///
+    /// For operations that have no streaming path.
+    pub fn check(&self, required: u64) -> Result<()> {
+        if self.allows(required) {
+            return Ok(());
+        }
+        Err(AudioError::MemoryBudgetExceeded {
+            required_mb: required.div_ceil(MIB),
+            budget_mb: self.megabytes(),
+        })
+    }
+}
+
+/// Bytes needed to hold decoded audio as 32-bit float samples
+///
+/// Unknown durations (0) estimate as 0, so streams without a frame count
+/// are never refused up front.
+pub fn decoded_size_bytes(duration_seconds: f64, sample_rate: u32, channels: u16) -> u64 {
+    let samples = duration_seconds.max(0.0) * sample_rate as f64 * channels as f64;
+    samples.ceil() as u64 * std::mem::size_of::<f32>() as u64
+}
+
+/// Whether low-memory mode is on: the `low_memory` setting, else whether
+/// the machine has [`LOW_MEMORY_THRESHOLD_BYTES`] or less
+pub fn low_memory_enabled(settings: &Settings) -> bool {
+    settings
+        .low_memory
+        .unwrap_or_else(|| low_memory_machine(total_memory_bytes()))
+}
+
+/// A machine with `total` bytes of memory counts as low on memory; one
+/// whose memory can't be read doesn't
+pub fn low_memory_machine(total: Option<u64>) -> bool {
+    total.is_some_and(|total| total <= LOW_MEMORY_THRESHOLD_BYTES)
+}
+
+/// Size the shared caches for the settings' memory mode
+///
+/// Call at startup and whenever the `low_memory` setting changes.
+///
+/// # Returns
+/// Whether low-memory mode is on
+pub fn apply_memory_mode(settings: &Settings) -> bool {
+    let low_memory = low_memory_enabled(settings);
+    crate::audio::set_low_memory_caches(low_memory);
+    info!(low_memory, "Memory mode applied");
+    low_memory
+}
+
+/// Low-memory mode and the budget it works within, for the settings page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MemoryStatus {
+    /// Whether low-memory mode is on
+    pub low_memory: bool,
+    /// The saved setting; `None` means it follows the machine
+    pub setting: Option<bool>,
+    /// Physical memory, if it could be read
+    pub total_mb: Option<u64>,
+    pub budget_mb: u64,
+}
+
+impl MemoryStatus {
+    pub fn from_settings(settings: &Settings) -> Self {
+        let budget = MemoryBudget::from_settings(settings);
+        Self {
+            low_memory: budget.is_low_memory(),
+            setting: settings.low_memory,
+            total_mb: total_memory_bytes().map(|total| total / MIB),
+            budget_mb: budget.megabytes(),
+        }
+    }
+}
+
+/// Physical memory installed in the machine
+pub fn total_memory_bytes() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        parse_meminfo(&std::fs::read_to_string("/proc/meminfo").ok()?)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        crate::gpu::run_command("sysctl", &["-n", "hw.memsize"])?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// Pull `MemTotal` out of `/proc/meminfo` (reported in KiB)
+pub fn parse_meminfo(meminfo: &str) -> Option<u64> {
+    let line = meminfo.lines().find(|l| l.starts_with("MemTotal:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decoded_size() {
+        // Four hours of 48 kHz stereo
+        let bytes = decoded_size_bytes(4.0 * 3600.0, 48000, 2);
+        assert_eq!(bytes, 5_529_600_000);
+        assert_eq!(decoded_size_bytes(0.0, 48000, 2), 0);
+    }
+
+    #[test]
+    fn test_mode_and_check() {
+        let budget = MemoryBudget::from_mb(100);
+        assert_eq!(budget.mode_for(50 * MIB), ProcessingMode::InMemory);
+        assert_eq!(budget.mode_for(101 * MIB), ProcessingMode::Streaming);
+        assert!(budget.check(100 * MIB).is_ok());
+        assert!(matches!(
+            budget.check(150 * MIB + 1),
+            Err(AudioError::MemoryBudgetExceeded {
+                required_mb: 151,
+                budget_mb: 100
+            })
+        ));
+        assert_eq!(budget.per_job(4).megabytes(), 25);
+        assert_eq!(budget.per_job(0), budget);
+
+        // Low-memory mode streams even what would fit, but checks the same
+        let low = budget.with_low_memory(true);
+        assert_eq!(low.mode_for(MIB), ProcessingMode::Streaming);
+        assert!(low.check(100 * MIB).is_ok());
+        assert!(low.per_job(2).is_low_memory());
+    }
+
+    #[test]
+    fn test_budget_from_settings() {
+        let settings = Settings {
+            memory_budget_mb: Some(512),
+            ..Default::default()
+        };
+        assert_eq!(MemoryBudget::from_settings(&settings).megabytes(), 512);
+
+        // Without a setting the budget follows the machine, never zero
+        assert!(MemoryBudget::from_settings(&Settings::default()).bytes() > 0);
+
+        let forced = Settings {
+            low_memory: Some(true),
+            memory_budget_mb: Some(512),
+            ..Default::default()
+        };
+        let budget = MemoryBudget::from_settings(&forced);
+        assert!(budget.is_low_memory());
+        assert_eq!(budget.megabytes(), 512);
+    }
+
+    #[test]
+    fn test_low_memory_machine() {
+        assert!(low_memory_machine(Some(8_000_000_000)));
+        assert!(low_memory_machine(Some(LOW_MEMORY_THRESHOLD_BYTES)));
+        assert!(!low_memory_machine(Some(16 * 1024 * MIB)));
+        assert!(!low_memory_machine(None));
+
+        let off = Settings {
+            low_memory: Some(false),
+            ..Default::default()
+        };
+        assert!(!low_memory_enabled(&off));
+    }
+
+    #[test]
+    fn test_parse_meminfo() {
+        let meminfo = "MemTotal:       16303428 kB\nMemFree:         1187644 kB\n";
+        assert_eq!(parse_meminfo(meminfo), Some(16303428 * 1024));
+        assert_eq!(parse_meminfo(""), None);
+    }
+}
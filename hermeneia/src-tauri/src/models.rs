@@ -0,0 +1,814 @@
+// src-tauri/src/models.rs
+// Model files for engines such as speech-to-text: checking them against
+// known checksums, fetching or importing a good copy and keeping a registry
+// of what's installed
+
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, info, warn};
+
+use crate::error::{AnalysisError, AudioError, Result};
+use crate::ingest::podcast::fetch_to_file;
+use crate::progress::{check_cancelled, ProgressSink};
+use crate::settings::APP_IDENTIFIER;
+
+/// Bytes hashed between progress reports
+const READ_CHUNK: usize = 1 << 20;
+
+/// The registry of installed models, inside the models folder
+const REGISTRY_FILE: &str = "models.json";
+
+/// A model file and what it should contain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelSpec {
+    /// Shown in messages, e.g. "whisper-base.en"
+    pub name: String,
+    /// File name inside the models folder
+    pub file_name: String,
+    /// Size in bytes
+    pub size: u64,
+    /// SHA-256 of the whole file, in hex
+    pub sha256: String,
+    /// Where to download it again; `None` for models that can only be imported
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+/// What [`verify_model`] found
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum ModelStatus {
+    Ok,
+    Missing,
+    /// Shorter than it should be, usually an interrupted download
+    Truncated { expected: u64, actual: u64 },
+    /// Longer than it should be, e.g. an HTML error page saved in its place
+    /// or a different version of the model
+    Oversized { expected: u64, actual: u64 },
+    /// The right size but the wrong contents
+    ChecksumMismatch { expected: String, actual: String },
+}
+
+impl ModelStatus {
+    pub fn is_ok(&self) -> bool {
+        *self == Self::Ok
+    }
+
+    /// Short explanation for errors and the UI
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Ok => "intact".to_string(),
+            Self::Missing => "not found".to_string(),
+            Self::Truncated { expected, actual } => {
+                format!("only {} of {} bytes; the download was cut short", actual, expected)
+            }
+            Self::Oversized { expected, actual } => {
+                format!("{} bytes where {} were expected", actual, expected)
+            }
+            Self::ChecksumMismatch { .. } => "its contents don't match the checksum".to_string(),
+        }
+    }
+}
+
+/// File formats a model can come in, told apart by their first bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelFormat {
+    /// whisper.cpp's own format
+    Ggml,
+    Gguf,
+    Safetensors,
+}
+
+impl ModelFormat {
+    /// The format of the file at `path`, `None` if it isn't a model
+    pub fn detect(path: &Path) -> Result<Option<Self>> {
+        let mut header = Vec::with_capacity(9);
+        File::open(path)?.take(9).read_to_end(&mut header)?;
+        Ok(Self::from_header(&header))
+    }
+
+    fn from_header(header: &[u8]) -> Option<Self> {
+        match header {
+            // The magic 0x67676d6c, little-endian
+            [b'l', b'm', b'g', b'g', ..] => Some(Self::Ggml),
+            [b'G', b'G', b'U', b'F', ..] => Some(Self::Gguf),
+            // The JSON header's length, then the header itself
+            [_, _, _, _, _, _, _, _, b'{', ..] => Some(Self::Safetensors),
+            _ => None,
+        }
+    }
+}
+
+/// A model in the models folder, as recorded in the registry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstalledModel {
+    pub name: String,
+    pub file_name: String,
+    pub size: u64,
+    pub sha256: String,
+    /// Where it was downloaded from
+    pub url: Option<String>,
+    /// The local file it was copied from
+    pub imported_from: Option<String>,
+    /// Whether the checksum came from a known model rather than from the
+    /// imported file itself, in which case it only catches later damage
+    pub checksum_known: bool,
+    pub format: Option<ModelFormat>,
+    /// Local time with offset, RFC 3339
+    pub installed_at: String,
+}
+
+impl InstalledModel {
+    /// What to check the installed file against, e.g. for [`open_model`]
+    pub fn spec(&self) -> ModelSpec {
+        ModelSpec {
+            name: self.name.clone(),
+            file_name: self.file_name.clone(),
+            size: self.size,
+            sha256: self.sha256.clone(),
+            url: self.url.clone(),
+        }
+    }
+}
+
+/// Remembers that a file hashed correctly, so multi-gigabyte models aren't
+/// hashed again on every load; kept next to the model
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct VerifiedStamp {
+    sha256: String,
+    size: u64,
+    modified_ns: u64,
+}
+
+impl VerifiedStamp {
+    fn new(spec: &ModelSpec, metadata: &fs::Metadata) -> Self {
+        Self {
+            sha256: spec.sha256.to_ascii_lowercase(),
+            size: metadata.len(),
+            modified_ns: metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |since| since.as_nanos() as u64),
+        }
+    }
+}
+
+/// Where models are kept, e.g. `~/.local/share/com.hinson.hermeneia/models`
+pub fn models_dir() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join(APP_IDENTIFIER).join("models"))
+}
+
+/// Check the model in `dir` against its size and checksum
+///
+/// The size is checked first, so a cut-short download is reported without
+/// reading it. A file that hashed correctly before and hasn't been touched
+/// since (same size and modification time) isn't hashed again. Reports the
+/// "verify" stage.
+pub fn verify_model(
+    dir: &Path,
+    spec: &ModelSpec,
+    progress: &mut dyn ProgressSink,
+) -> Result<ModelStatus> {
+    let path = dir.join(checked_file_name(spec)?);
+    let status = check_file(&path, spec, progress)?;
+    if status.is_ok() {
+        remember_verified(&path, spec);
+    } else {
+        fs::remove_file(stamp_path(&path)).ok();
+    }
+    Ok(status)
+}
+
+/// Size and checksum of any file against `spec`, using but never writing
+/// the verified stamp
+fn check_file(
+    path: &Path,
+    spec: &ModelSpec,
+    progress: &mut dyn ProgressSink,
+) -> Result<ModelStatus> {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(ModelStatus::Missing),
+        Err(e) => return Err(e.into()),
+    };
+    let (expected, actual) = (spec.size, metadata.len());
+    if actual < expected {
+        return Ok(ModelStatus::Truncated { expected, actual });
+    }
+    if actual > expected {
+        return Ok(ModelStatus::Oversized { expected, actual });
+    }
+
+    let stamped = fs::read_to_string(stamp_path(path))
+        .ok()
+        .and_then(|json| serde_json::from_str::<VerifiedStamp>(&json).ok());
+    if stamped.is_some_and(|stamped| stamped == VerifiedStamp::new(spec, &metadata)) {
+        debug!(model = %spec.name, "Model verified earlier and unchanged");
+        return Ok(ModelStatus::Ok);
+    }
+
+    let expected_hash = spec.sha256.to_ascii_lowercase();
+    let actual_hash = sha256_file(path, actual, progress)?;
+    if actual_hash != expected_hash {
+        return Ok(ModelStatus::ChecksumMismatch {
+            expected: expected_hash,
+            actual: actual_hash,
+        });
+    }
+    Ok(ModelStatus::Ok)
+}
+
+/// Write the stamp for a file that just checked out; only a cache, so if
+/// it can't be written the file is hashed again next time
+fn remember_verified(path: &Path, spec: &ModelSpec) {
+    let Ok(metadata) = fs::metadata(path) else {
+        return;
+    };
+    if let Ok(json) = serde_json::to_string(&VerifiedStamp::new(spec, &metadata)) {
+        fs::write(stamp_path(path), json).ok();
+    }
+}
+
+/// Path of a model that's safe to load
+///
+/// Engines should open models through this, so a damaged file fails with a
+/// clear message instead of part way through inference.
+///
+/// # Errors
+/// [`AnalysisError::ModelDamaged`] if the file is missing or doesn't match
+/// `spec`; [`repair_model`] or [`import_model`] can fix it
+pub fn open_model(
+    dir: &Path,
+    spec: &ModelSpec,
+    progress: &mut dyn ProgressSink,
+) -> Result<PathBuf> {
+    let status = verify_model(dir, spec, progress)?;
+    if !status.is_ok() {
+        return Err(damaged(spec, &status));
+    }
+    Ok(dir.join(checked_file_name(spec)?))
+}
+
+/// Download the model from [`ModelSpec::url`] and check it
+///
+/// The download goes to a `.part` file that only replaces the model once
+/// it matches, so a failed download never leaves a damaged model behind.
+/// Reports the "download" and "verify" stages.
+pub fn download_model(
+    dir: &Path,
+    spec: &ModelSpec,
+    progress: &mut dyn ProgressSink,
+) -> Result<PathBuf> {
+    let Some(url) = &spec.url else {
+        return Err(AudioError::InvalidParameter(format!(
+            "Model '{}' has no download URL; import a copy instead",
+            spec.name
+        )));
+    };
+    fs::create_dir_all(dir)?;
+    let part = dir.join(format!("{}.part", checked_file_name(spec)?));
+    progress.stage("download");
+    let fetched = fetch_to_file(url, &part, Some(spec.size), progress);
+    if let Err(e) = fetched {
+        fs::remove_file(&part).ok();
+        return Err(e);
+    }
+    let path = install(dir, spec, &part, progress)?;
+    register(dir, spec, None, true)?;
+    Ok(path)
+}
+
+/// Copy a model the user already has into the models folder, if it
+/// matches `spec`
+///
+/// For machines without internet access, or models without a download URL.
+/// Reports the "verify" stage.
+pub fn import_model(
+    dir: &Path,
+    spec: &ModelSpec,
+    source: &Path,
+    progress: &mut dyn ProgressSink,
+) -> Result<PathBuf> {
+    import_known(dir, spec, source, progress).map(|model| dir.join(model.file_name))
+}
+
+/// Import a model file from disk, for machines that can't reach the
+/// downloader
+///
+/// The file must be a GGML, GGUF or safetensors model. If it matches one
+/// of the `known` models, by file name or by checksum when it has been
+/// renamed, it's installed and registered as that model and must pass its
+/// checksum. Anything else is installed under its own file name, named
+/// `name` or after the file, and registered with the checksum it has now;
+/// it replaces a model installed under the same name or file name only
+/// with `overwrite`. The copy is checked again before it replaces an
+/// installed model. Reports the "verify" stage.
+///
+/// # Errors
+/// [`AudioError::UnsupportedFormat`] if the file isn't a model;
+/// [`AnalysisError::ModelDamaged`] if it's a damaged copy of a known one;
+/// [`AudioError::InvalidParameter`] if a custom model's file name is one
+/// the models folder uses itself (e.g. `models.json`), or it's already
+/// installed and `overwrite` isn't set
+pub fn import_local_model(
+    dir: &Path,
+    source: &Path,
+    name: Option<&str>,
+    known: &[ModelSpec],
+    overwrite: bool,
+    progress: &mut dyn ProgressSink,
+) -> Result<InstalledModel> {
+    if ModelFormat::detect(source)?.is_none() {
+        return Err(AudioError::UnsupportedFormat(format!(
+            "{} isn't a GGML, GGUF or safetensors model",
+            source.display()
+        )));
+    }
+    let file_name = source
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| AudioError::InvalidParameter("the model path has no file name".into()))?;
+    if let Some(spec) = known.iter().find(|spec| spec.file_name == file_name) {
+        return import_known(dir, spec, source, progress);
+    }
+
+    let size = fs::metadata(source)?.len();
+    let sha256 = sha256_file(source, size, progress)?;
+    let matching = known
+        .iter()
+        .find(|spec| spec.size == size && spec.sha256.eq_ignore_ascii_case(&sha256));
+    let (spec, checksum_known) = match matching {
+        Some(spec) => (spec.clone(), true),
+        None => {
+            let stem = Path::new(&file_name).file_stem().unwrap_or_default();
+            let spec = ModelSpec {
+                name: name.map_or_else(|| stem.to_string_lossy().into_owned(), String::from),
+                file_name,
+                size,
+                sha256,
+                url: None,
+            };
+            check_custom_name(dir, &spec, overwrite)?;
+            (spec, false)
+        }
+    };
+    copy_in(dir, &spec, source, progress)?;
+    register(dir, &spec, Some(source), checksum_known)
+}
+
+/// Keep a custom model off the models folder's own files, and off an
+/// installed model unless `overwrite` says to replace it
+fn check_custom_name(dir: &Path, spec: &ModelSpec, overwrite: bool) -> Result<()> {
+    let file_name = checked_file_name(spec)?;
+    let reserved = file_name.eq_ignore_ascii_case(REGISTRY_FILE)
+        || [".part", ".verified"].iter().any(|suffix| file_name.ends_with(suffix));
+    if reserved {
+        return Err(AudioError::InvalidParameter(format!(
+            "'{}' is a name the models folder uses for its own files; rename the model",
+            file_name
+        )));
+    }
+    let installed = dir.join(file_name).exists()
+        || read_registry(dir)
+            .iter()
+            .any(|model| model.file_name == spec.file_name || model.name == spec.name);
+    if installed && !overwrite {
+        return Err(AudioError::InvalidParameter(format!(
+            "A model named '{}' ({}) is already installed; import with overwrite to replace it",
+            spec.name, file_name
+        )));
+    }
+    Ok(())
+}
+
+/// Files in `folder` (not its subfolders) that look like models, for
+/// picking what to import from removable media
+pub fn find_model_files(folder: &Path) -> Result<Vec<PathBuf>> {
+    let mut found = Vec::new();
+    for entry in fs::read_dir(folder)? {
+        let path = entry?.path();
+        if path.is_file() && matches!(ModelFormat::detect(&path), Ok(Some(_))) {
+            found.push(path);
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+/// The models registered in `dir`, leaving out any whose file has been
+/// deleted since
+pub fn installed_models(dir: &Path) -> Vec<InstalledModel> {
+    let mut models = read_registry(dir);
+    models.retain(|model| dir.join(&model.file_name).is_file());
+    models
+}
+
+fn read_registry(dir: &Path) -> Vec<InstalledModel> {
+    let Ok(json) = fs::read_to_string(dir.join(REGISTRY_FILE)) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&json).unwrap_or_else(|e| {
+        warn!(error = %e, "Ignoring an unreadable model registry");
+        Vec::new()
+    })
+}
+
+/// Record a freshly installed model, replacing whatever was registered
+/// under its file name
+fn register(
+    dir: &Path,
+    spec: &ModelSpec,
+    imported_from: Option<&Path>,
+    checksum_known: bool,
+) -> Result<InstalledModel> {
+    let model = InstalledModel {
+        name: spec.name.clone(),
+        file_name: spec.file_name.clone(),
+        size: spec.size,
+        sha256: spec.sha256.to_ascii_lowercase(),
+        url: spec.url.clone(),
+        imported_from: imported_from.map(|path| path.display().to_string()),
+        checksum_known,
+        format: ModelFormat::detect(&dir.join(checked_file_name(spec)?))?,
+        installed_at: chrono::Local::now().to_rfc3339(),
+    };
+    let mut models = read_registry(dir);
+    models.retain(|other| other.file_name != model.file_name);
+    models.push(model.clone());
+    models.sort_by(|a, b| a.name.cmp(&b.name));
+    let json = serde_json::to_string_pretty(&models)
+        .map_err(|e| AudioError::InvalidParameter(e.to_string()))?;
+    fs::write(dir.join(REGISTRY_FILE), json)?;
+    Ok(model)
+}
+
+/// Check `source` against a known model before copying it in
+fn import_known(
+    dir: &Path,
+    spec: &ModelSpec,
+    source: &Path,
+    progress: &mut dyn ProgressSink,
+) -> Result<InstalledModel> {
+    let status = check_file(source, spec, progress)?;
+    if !status.is_ok() {
+        return Err(damaged(spec, &status));
+    }
+    copy_in(dir, spec, source, progress)?;
+    register(dir, spec, Some(source), true)
+}
+
+/// Copy `source` next to the models and [`install`] it
+fn copy_in(
+    dir: &Path,
+    spec: &ModelSpec,
+    source: &Path,
+    progress: &mut dyn ProgressSink,
+) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let part = dir.join(format!("{}.part", checked_file_name(spec)?));
+    if let Err(e) = fs::copy(source, &part) {
+        fs::remove_file(&part).ok();
+        return Err(e.into());
+    }
+    install(dir, spec, &part, progress)
+}
+
+/// Replace a damaged model with a fresh download, if it needs it
+///
+/// # Returns
+/// The model's path, and whether it had to be downloaded
+pub fn repair_model(
+    dir: &Path,
+    spec: &ModelSpec,
+    progress: &mut dyn ProgressSink,
+) -> Result<(PathBuf, bool)> {
+    let status = verify_model(dir, spec, progress)?;
+    if status.is_ok() {
+        return Ok((dir.join(checked_file_name(spec)?), false));
+    }
+    info!(model = %spec.name, problem = %status.describe(), "Downloading the model again");
+    Ok((download_model(dir, spec, progress)?, true))
+}
+
+/// Check a `.part` file and move it into place
+fn install(
+    dir: &Path,
+    spec: &ModelSpec,
+    part: &Path,
+    progress: &mut dyn ProgressSink,
+) -> Result<PathBuf> {
+    let status = check_file(part, spec, progress).and_then(|status| {
+        if status.is_ok() {
+            Ok(status)
+        } else {
+            Err(damaged(spec, &status))
+        }
+    });
+    if let Err(e) = status {
+        fs::remove_file(part).ok();
+        return Err(e);
+    }
+    let path = dir.join(checked_file_name(spec)?);
+    fs::rename(part, &path)?;
+    remember_verified(&path, spec);
+    Ok(path)
+}
+
+/// The spec's file name, if it names a file directly inside the models
+/// folder; specs arrive from the frontend, so this keeps `..` or an
+/// absolute path from reaching outside it
+fn checked_file_name(spec: &ModelSpec) -> Result<&str> {
+    let mut components = Path::new(&spec.file_name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(name)), None) if name == spec.file_name.as_str() => {
+            Ok(&spec.file_name)
+        }
+        _ => Err(AudioError::InvalidParameter(format!(
+            "Model file name '{}' must be a plain file name",
+            spec.file_name
+        ))),
+    }
+}
+
+fn damaged(spec: &ModelSpec, status: &ModelStatus) -> AudioError {
+    AnalysisError::ModelDamaged {
+        model: spec.name.clone(),
+        reason: status.describe(),
+    }
+    .into()
+}
+
+fn stamp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".verified");
+    path.with_file_name(name)
+}
+
+/// Hex SHA-256 of a file, reporting progress against `len`
+fn sha256_file(path: &Path, len: u64, progress: &mut dyn ProgressSink) -> Result<String> {
+    progress.stage("verify");
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; READ_CHUNK];
+    let mut read = 0u64;
+    loop {
+        check_cancelled(progress)?;
+        let n = file.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+        read += n as u64;
+        progress.progress(read as f64 / len.max(1) as f64);
+    }
+    progress.progress(1.0);
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::NoProgress;
+
+    const CONTENTS: &[u8] = b"weights and biases";
+    /// SHA-256 of CONTENTS
+    const CONTENTS_SHA256: &str =
+        "366b6339d615b10bd6451ce276d48914b8dee320a1b7ff3fbb61dec7882d608b";
+
+    fn spec(url: Option<String>) -> ModelSpec {
+        ModelSpec {
+            name: "tiny".to_string(),
+            file_name: "tiny.bin".to_string(),
+            size: CONTENTS.len() as u64,
+            sha256: CONTENTS_SHA256.to_string(),
+            url,
+        }
+    }
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("hermeneia_test_models_{}", name));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_hash_matches_the_known_checksum() {
+        let dir = scratch("hash");
+        let path = dir.join("tiny.bin");
+        fs::write(&path, CONTENTS).unwrap();
+        assert_eq!(sha256_file(&path, 18, &mut NoProgress).unwrap(), CONTENTS_SHA256);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_verify_finds_each_kind_of_damage() {
+        let dir = scratch("verify");
+        let spec = spec(None);
+        let path = dir.join(&spec.file_name);
+        let verify = || verify_model(&dir, &spec, &mut NoProgress).unwrap();
+
+        assert_eq!(verify(), ModelStatus::Missing);
+        fs::write(&path, &CONTENTS[..7]).unwrap();
+        assert_eq!(verify(), ModelStatus::Truncated { expected: 18, actual: 7 });
+        fs::write(&path, b"<html>Not found</html>").unwrap();
+        assert!(matches!(verify(), ModelStatus::Oversized { .. }));
+        fs::write(&path, b"weights and BIASES").unwrap();
+        assert!(matches!(verify(), ModelStatus::ChecksumMismatch { .. }));
+
+        fs::write(&path, CONTENTS).unwrap();
+        assert_eq!(verify(), ModelStatus::Ok);
+        assert!(stamp_path(&path).exists());
+
+        let error = open_model(&dir, &ModelSpec { size: 99, ..spec.clone() }, &mut NoProgress)
+            .unwrap_err();
+        assert_eq!(error.code_name(), "analysis.model_damaged");
+        assert!(error.to_string().contains("cut short"), "{}", error);
+        assert_eq!(open_model(&dir, &spec, &mut NoProgress).unwrap(), path);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_file_name_stays_in_the_models_folder() {
+        let dir = scratch("escape");
+        let source = dir.join("source.bin");
+        fs::write(&source, CONTENTS).unwrap();
+        for file_name in ["../tiny.bin", "/tmp/tiny.bin", "sub/tiny.bin", "tiny.bin/.", "..", ""] {
+            let spec = ModelSpec {
+                file_name: file_name.to_string(),
+                ..spec(None)
+            };
+            let verified = verify_model(&dir, &spec, &mut NoProgress);
+            assert!(matches!(verified, Err(AudioError::InvalidParameter(_))), "{}", file_name);
+            assert!(import_model(&dir, &spec, &source, &mut NoProgress).is_err());
+            assert!(repair_model(&dir, &spec, &mut NoProgress).is_err());
+        }
+        assert!(!dir.join("tiny.bin.part").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_stamp_is_ignored_once_the_file_changes() {
+        let dir = scratch("stamp");
+        let spec = spec(None);
+        let path = dir.join(&spec.file_name);
+        fs::write(&path, CONTENTS).unwrap();
+        assert!(verify_model(&dir, &spec, &mut NoProgress).unwrap().is_ok());
+
+        // Same size, different contents, newer modification time
+        fs::write(&path, b"weights and BIASES").unwrap();
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(5);
+        File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+        assert!(matches!(
+            verify_model(&dir, &spec, &mut NoProgress).unwrap(),
+            ModelStatus::ChecksumMismatch { .. }
+        ));
+        assert!(!stamp_path(&path).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_import_only_accepts_a_good_copy() {
+        let dir = scratch("import");
+        let models = dir.join("models");
+        let spec = spec(None);
+        let bad = dir.join("bad.bin");
+        fs::write(&bad, &CONTENTS[..4]).unwrap();
+        assert!(import_model(&models, &spec, &bad, &mut NoProgress).is_err());
+        assert_eq!(verify_model(&models, &spec, &mut NoProgress).unwrap(), ModelStatus::Missing);
+        assert!(!stamp_path(&bad).exists(), "nothing is written next to the user's files");
+
+        let good = dir.join("good.bin");
+        fs::write(&good, CONTENTS).unwrap();
+        let path = import_model(&models, &spec, &good, &mut NoProgress).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), CONTENTS);
+        assert!(stamp_path(&path).exists() && !stamp_path(&good).exists());
+        assert!(!models.join("tiny.bin.part").exists());
+        assert_eq!(installed_models(&models)[0].imported_from.as_deref(), good.to_str());
+        assert!(repair_model(&models, &spec, &mut NoProgress).is_ok_and(|(_, fetched)| !fetched));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_repair_downloads_a_damaged_model_again() {
+        let dir = scratch("repair");
+        let models = dir.join("models");
+        let source = dir.join("mirror.bin");
+        fs::write(&source, CONTENTS).unwrap();
+        let server = crate::ingest::podcast::serve_files(dir.clone(), 2);
+        let spec = spec(Some(format!("{server}/mirror.bin")));
+
+        fs::create_dir_all(&models).unwrap();
+        fs::write(models.join("tiny.bin"), &CONTENTS[..9]).unwrap();
+        let (path, fetched) = repair_model(&models, &spec, &mut NoProgress).unwrap();
+        assert!(fetched);
+        assert_eq!(fs::read(&path).unwrap(), CONTENTS);
+
+        // A mirror serving the wrong file leaves no model behind
+        fs::write(&source, b"weights and BIASES").unwrap();
+        fs::remove_file(&path).unwrap();
+        let error = download_model(&models, &spec, &mut NoProgress).unwrap_err();
+        assert_eq!(error.code_name(), "analysis.model_damaged");
+        assert!(!path.exists() && !models.join("tiny.bin.part").exists());
+
+        let no_url = ModelSpec { url: None, ..spec };
+        assert!(download_model(&models, &no_url, &mut NoProgress).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_format_from_header() {
+        assert_eq!(ModelFormat::from_header(b"lmgg\x01\0\0\0"), Some(ModelFormat::Ggml));
+        assert_eq!(ModelFormat::from_header(b"GGUF\x03\0"), Some(ModelFormat::Gguf));
+        assert_eq!(
+            ModelFormat::from_header(b"\x20\0\0\0\0\0\0\0{\"__metadata__\""),
+            Some(ModelFormat::Safetensors)
+        );
+        assert_eq!(ModelFormat::from_header(b"<html>"), None);
+        assert_eq!(ModelFormat::from_header(b""), None);
+    }
+
+    #[test]
+    fn test_local_import_registers_known_and_custom_models() {
+        let dir = scratch("local");
+        let models = dir.join("models");
+        let usb = dir.join("usb");
+        fs::create_dir_all(&usb).unwrap();
+        let ggml = [b"lmgg".as_slice(), CONTENTS].concat();
+        let known = ModelSpec {
+            name: "base.en".to_string(),
+            file_name: "ggml-base.en.bin".to_string(),
+            size: ggml.len() as u64,
+            sha256: sha256_file_of(&ggml),
+            url: Some("https://example.org/ggml-base.en.bin".to_string()),
+        };
+        // A known model under another name, a custom one and something else
+        fs::write(usb.join("base (copy).bin"), &ggml).unwrap();
+        fs::write(usb.join("sermons-large.gguf"), b"GGUF\x03\0\0\0 fine-tuned").unwrap();
+        fs::write(usb.join("notes.txt"), b"read me").unwrap();
+
+        let found = find_model_files(&usb).unwrap();
+        assert_eq!(found, [usb.join("base (copy).bin"), usb.join("sermons-large.gguf")]);
+        let notes = usb.join("notes.txt");
+        let error =
+            import_local_model(&models, &notes, None, &[], false, &mut NoProgress).unwrap_err();
+        assert_eq!(error.code_name(), "unsupported_format");
+
+        let known = [known];
+        let base =
+            import_local_model(&models, &found[0], None, &known, false, &mut NoProgress).unwrap();
+        assert_eq!((base.name.as_str(), base.file_name.as_str()), ("base.en", "ggml-base.en.bin"));
+        assert!(base.checksum_known);
+        assert_eq!(base.format, Some(ModelFormat::Ggml));
+        assert!(open_model(&models, &base.spec(), &mut NoProgress).is_ok());
+
+        let custom = import_local_model(
+            &models,
+            &found[1],
+            Some("Sermons (large)"),
+            &known,
+            false,
+            &mut NoProgress,
+        )
+        .unwrap();
+        assert!(!custom.checksum_known);
+        assert_eq!(custom.file_name, "sermons-large.gguf");
+
+        // Replacing an installed model takes overwrite; the registry's name is never free
+        let import = |source: &Path, name: Option<&str>, overwrite| {
+            import_local_model(&models, source, name, &known, overwrite, &mut NoProgress)
+        };
+        assert!(import(&found[1], None, false).is_err());
+        let renamed = usb.join("renamed.gguf");
+        fs::copy(&found[1], &renamed).unwrap();
+        assert!(import(&renamed, Some("Sermons (large)"), false).is_err());
+        assert!(!models.join("renamed.gguf").exists());
+        let custom = import(&found[1], Some("Sermons (large)"), true).unwrap();
+        let registry = usb.join(REGISTRY_FILE);
+        fs::copy(&found[1], &registry).unwrap();
+        assert!(import(&registry, None, true).is_err());
+        assert_eq!(read_registry(&models).len(), 2);
+        fs::remove_file(&renamed).unwrap();
+
+        let installed = installed_models(&models);
+        assert_eq!(installed, [custom.clone(), base]);
+        fs::remove_file(models.join(&custom.file_name)).unwrap();
+        assert_eq!(installed_models(&models).len(), 1, "deleted files drop out");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn sha256_file_of(contents: &[u8]) -> String {
+        Sha256::digest(contents).iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+}
@@ -0,0 +1,379 @@
+// src-tauri/src/naming.rs
+// Output path templates and what to do when an output already exists
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::types::TagSummary;
+use crate::error::{AudioError, Result};
+
+/// Fields every template may use; any without a value render as
+/// [`MISSING_VALUE`]
+///
+/// | Field | Value |
+/// |-------|-------|
+/// | `{stem}` | Input file name without extension |
+/// | `{ext}` | Input extension |
+/// | `{format}` | Output extension |
+/// | `{index}` | 1-based position in a batch |
+/// | `{dir}` | Name of the input's folder |
+/// | `{date}` | Day the input was last modified, `YYYY-MM-DD` (UTC) |
+/// | `{title}`, `{artist}`, `{album}` | Tags of the input |
+/// | `{speaker}` | The artist tag, the usual place for a speaker's name |
+/// | `{lang}` | Language, from tools that know it (e.g. transcription) |
+pub const STANDARD_FIELDS: &[&str] = &[
+    "stem", "ext", "format", "index", "dir", "date", "title", "artist", "album", "speaker",
+    "lang",
+];
+
+/// Stands in for a standard field with no value, e.g. an untagged speaker
+pub const MISSING_VALUE: &str = "unknown";
+
+/// Fields that come from the input's tags, so reading them needs a probe
+const TAG_FIELDS: &[&str] = &["title", "artist", "album", "speaker"];
+
+/// How outputs are named, kept in [`crate::settings::Settings`]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputNaming {
+    /// Path template relative to the output folder, e.g.
+    /// `{date}/{speaker}/{stem}_{lang}.{format}`; `None` keeps each tool's
+    /// own default name
+    pub template: Option<String>,
+    pub collisions: CollisionPolicy,
+}
+
+/// What to do when an output path is already taken
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, clap::ValueEnum,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CollisionPolicy {
+    /// Replace the existing file
+    #[default]
+    Overwrite,
+    /// Write `name_2.ext`, `name_3.ext`, ... instead
+    Suffix,
+    /// Leave the existing file and don't produce this output
+    Skip,
+}
+
+/// Values for a template's `{fields}`
+#[derive(Debug, Clone, Default)]
+pub struct TemplateFields {
+    values: HashMap<String, String>,
+}
+
+impl TemplateFields {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fields that describe an input file: `stem`, `ext`, `dir` and `date`
+    pub fn for_input(input: &Path) -> Self {
+        let mut fields = Self::new();
+        let name = |s: Option<&std::ffi::OsStr>| s.and_then(|s| s.to_str()).map(str::to_string);
+        if let Some(stem) = name(input.file_stem()) {
+            fields.set("stem", stem);
+        }
+        if let Some(ext) = name(input.extension()) {
+            fields.set("ext", ext);
+        }
+        if let Some(dir) = name(input.parent().and_then(Path::file_name)) {
+            fields.set("dir", dir);
+        }
+        let modified = std::fs::metadata(input).and_then(|m| m.modified());
+        if let Ok(modified) = modified {
+            fields.set("date", format_date(modified));
+        }
+        fields
+    }
+
+    /// Add `title`, `artist`, `album` and `speaker` from the input's tags
+    pub fn with_tags(mut self, tags: &TagSummary) -> Self {
+        for (field, value) in [
+            ("title", &tags.title),
+            ("artist", &tags.artist),
+            ("album", &tags.album),
+            ("speaker", &tags.artist),
+        ] {
+            if let Some(value) = value {
+                self.set(field, value.clone());
+            }
+        }
+        self
+    }
+
+    /// Set a field, standard or tool-specific
+    pub fn set(&mut self, field: &str, value: impl Into<String>) -> &mut Self {
+        self.values.insert(field.to_string(), value.into());
+        self
+    }
+
+    fn get(&self, field: &str) -> Option<&str> {
+        self.values.get(field).map(String::as_str)
+    }
+}
+
+/// An output path template such as `{date}/{speaker}/{stem}_{lang}.{format}`
+///
+/// `/` separates folders (on Windows `\` does too). Field values are made
+/// safe for file names, so a tag like `AC/DC` can't add a folder. A
+/// template must stay relative: it can't start at the root or use `..`.
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::naming::{PathTemplate, TemplateFields};
+/// use std::path::PathBuf;
+///
+/// let template = PathTemplate::parse("{date}/{speaker}/{stem}_{lang}.{format}").unwrap();
+/// let mut fields = TemplateFields::new();
+/// fields.set("date", "2026-03-01").set("stem", "sermon").set("format", "srt");
+/// fields.set("speaker", "J. Smith").set("lang", "en");
+/// assert_eq!(
+///     template.render(&fields).unwrap(),
+///     ["2026-03-01", "J. Smith", "sermon_en.srt"].iter().collect::<PathBuf>()
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathTemplate {
+    source: String,
+    /// One list of pieces per path component
+    components: Vec<Vec<Piece>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Piece {
+    Text(String),
+    Field(String),
+}
+
+impl PathTemplate {
+    pub fn parse(template: &str) -> Result<Self> {
+        let invalid = |why: &str| {
+            AudioError::InvalidParameter(format!("Invalid path template '{}': {}", template, why))
+        };
+        let mut components = Vec::new();
+        for component in template.split(['/', '\\']) {
+            if component.is_empty() {
+                return Err(invalid("it must be relative and can't have empty folder names"));
+            }
+            if component == "." || component == ".." {
+                return Err(invalid("'.' and '..' aren't allowed"));
+            }
+            let mut pieces = Vec::new();
+            let mut rest = component;
+            while let Some(open) = rest.find('{') {
+                if open > 0 {
+                    pieces.push(Piece::Text(rest[..open].to_string()));
+                }
+                let close = rest[open..]
+                    .find('}')
+                    .ok_or_else(|| invalid("unclosed '{'"))?
+                    + open;
+                pieces.push(Piece::Field(rest[open + 1..close].to_string()));
+                rest = &rest[close + 1..];
+            }
+            if !rest.is_empty() {
+                pieces.push(Piece::Text(rest.to_string()));
+            }
+            components.push(pieces);
+        }
+        Ok(Self {
+            source: template.to_string(),
+            components,
+        })
+    }
+
+    /// The template as written
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether any field needs the input's tags, which cost a probe to read
+    pub fn uses_tags(&self) -> bool {
+        self.fields().any(|field| TAG_FIELDS.contains(&field))
+    }
+
+    /// Whether `field` appears in the template
+    pub fn uses(&self, field: &str) -> bool {
+        self.fields().any(|name| name == field)
+    }
+
+    fn fields(&self) -> impl Iterator<Item = &str> {
+        self.components.iter().flatten().filter_map(|piece| match piece {
+            Piece::Field(name) => Some(name.as_str()),
+            Piece::Text(_) => None,
+        })
+    }
+
+    /// Fill in the fields, giving a path relative to the output folder
+    ///
+    /// Fails on a field that is neither set nor one of [`STANDARD_FIELDS`].
+    pub fn render(&self, fields: &TemplateFields) -> Result<PathBuf> {
+        let mut path = PathBuf::new();
+        for pieces in &self.components {
+            let mut component = String::new();
+            for piece in pieces {
+                match piece {
+                    Piece::Text(text) => component.push_str(text),
+                    Piece::Field(name) => {
+                        let value = match fields.get(name) {
+                            Some(value) => value,
+                            None if STANDARD_FIELDS.contains(&name.as_str()) => MISSING_VALUE,
+                            None => {
+                                return Err(AudioError::InvalidParameter(format!(
+                                    "Unknown field '{{{}}}' in path template '{}'",
+                                    name, self.source
+                                )))
+                            }
+                        };
+                        component.push_str(&sanitize(value));
+                    }
+                }
+            }
+            path.push(component);
+        }
+        Ok(path)
+    }
+}
+
+/// Make a field value safe inside one path component
+///
+/// Separators and characters Windows forbids become `_`; leading and
+/// trailing spaces and dots go, since Windows drops the trailing ones.
+fn sanitize(value: &str) -> String {
+    let cleaned: String = value
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let trimmed = cleaned.trim_matches(|c: char| c == ' ' || c == '.');
+    if trimmed.is_empty() {
+        MISSING_VALUE.to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// `YYYY-MM-DD` in UTC
+fn format_date(time: SystemTime) -> String {
+    let seconds = match time.duration_since(UNIX_EPOCH) {
+        Ok(since) => since.as_secs() as i64,
+        Err(before) => -(before.duration().as_secs() as i64),
+    };
+    // Days to civil date, after Howard Hinnant's `civil_from_days`
+    let days = seconds.div_euclid(86_400) + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Where to write an output, given what already exists
+///
+/// `taken` holds paths claimed by other outputs of the same run, which
+/// count as existing. Returns `None` when the output should be skipped.
+pub fn resolve_collision(
+    path: PathBuf,
+    policy: CollisionPolicy,
+    taken: &HashSet<PathBuf>,
+) -> Option<PathBuf> {
+    let exists = |path: &Path| taken.contains(path) || path.exists();
+    if !exists(&path) {
+        return Some(path);
+    }
+    match policy {
+        CollisionPolicy::Overwrite => Some(path),
+        CollisionPolicy::Skip => None,
+        CollisionPolicy::Suffix => {
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+            let extension = path.extension().map(|e| e.to_string_lossy().into_owned());
+            (2..)
+                .map(|n| {
+                    let name = match &extension {
+                        Some(extension) => format!("{}_{}.{}", stem, n, extension),
+                        None => format!("{}_{}", stem, n),
+                    };
+                    path.with_file_name(name)
+                })
+                .find(|candidate| !exists(candidate))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_sanitizes_values_and_fills_missing_fields() {
+        let template = PathTemplate::parse("{artist}/{album}/{index}-{title}.{format}").unwrap();
+        let mut fields = TemplateFields::new();
+        fields.set("artist", "AC/DC").set("title", " What? ").set("index", "3");
+        fields.set("format", "flac");
+        let path = template.render(&fields).unwrap();
+        assert_eq!(path, ["AC_DC", "unknown", "3-What_.flac"].iter().collect::<PathBuf>());
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        for template in ["/abs/{stem}", "{date}//{stem}", "../{stem}", "{stem", "a/./b"] {
+            assert!(PathTemplate::parse(template).is_err(), "{}", template);
+        }
+        let template = PathTemplate::parse("{stem}_{mood}").unwrap();
+        assert!(template.render(&TemplateFields::new()).is_err());
+        let mut fields = TemplateFields::new();
+        fields.set("mood", "..");
+        assert_eq!(template.render(&fields).unwrap(), PathBuf::from("unknown_unknown"));
+    }
+
+    #[test]
+    fn test_uses_tags() {
+        assert!(PathTemplate::parse("{speaker}/{stem}").unwrap().uses_tags());
+        assert!(!PathTemplate::parse("{date}/{stem}").unwrap().uses_tags());
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(UNIX_EPOCH), "1970-01-01");
+        let leap_day = UNIX_EPOCH + std::time::Duration::from_secs(951_782_400 + 3600);
+        assert_eq!(format_date(leap_day), "2000-02-29");
+        let before = UNIX_EPOCH - std::time::Duration::from_secs(1);
+        assert_eq!(format_date(before), "1969-12-31");
+    }
+
+    #[test]
+    fn test_collision_policies() {
+        let dir = std::env::temp_dir().join("hermeneia_test_naming");
+        std::fs::create_dir_all(&dir).unwrap();
+        let existing = dir.join("talk.mp3");
+        std::fs::write(&existing, b"").unwrap();
+        std::fs::write(dir.join("talk_2.mp3"), b"").unwrap();
+        let taken = HashSet::from([dir.join("talk_3.mp3")]);
+
+        let resolve = |policy| resolve_collision(existing.clone(), policy, &taken);
+        assert_eq!(resolve(CollisionPolicy::Overwrite), Some(existing.clone()));
+        assert_eq!(resolve(CollisionPolicy::Skip), None);
+        assert_eq!(resolve(CollisionPolicy::Suffix), Some(dir.join("talk_4.mp3")));
+
+        let fresh = dir.join("new.mp3");
+        assert_eq!(
+            resolve_collision(fresh.clone(), CollisionPolicy::Skip, &taken),
+            Some(fresh)
+        );
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
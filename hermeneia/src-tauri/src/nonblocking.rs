@@ -0,0 +1,540 @@
+// src-tauri/src/nonblocking.rs
+// Async wrappers for tokio users, built with the `async` feature
+
+//! Async versions of the long-running library functions
+//!
+//! Each one runs the blocking function on tokio's blocking pool and takes a
+//! [`CancellationToken`]. Once the token is cancelled the future resolves to
+//! [`AudioError::Cancelled`] right away, and the token is handed to the
+//! blocking function as its [`ProgressSink`], so decoding, encoding,
+//! analysis and transcription also stop at their next chunk. Resampling is
+//! one-shot: it runs to the end in the background and its result is
+//! dropped. A cancelled [`encode_audio`] never leaves a file behind; the
+//! file-to-file operations (trim, join, export, redact and the rest) clean
+//! up after themselves as they do on any other error.
+//!
+//! Buffers are taken as `impl Into<Arc<AudioData>>`, so pass an
+//! [`AudioData`] to hand it over or an `Arc` to keep using it afterwards.
+//!
+//! ```no_run
+//! use hermeneia_lib::nonblocking::{self, CancellationToken};
+//!
+//! # async fn example() -> hermeneia_lib::Result<()> {
+//! let cancel = CancellationToken::new();
+//! let audio = nonblocking::decode_audio_file("sermon.mp3", &cancel).await?;
+//! let loudness = nonblocking::measure_loudness(audio, &cancel).await?;
+//! println!("{:?} LUFS", loudness.integrated_lufs);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+pub use tokio_util::sync::CancellationToken;
+
+use crate::audio::analysis::{
+    self, ClippingOptions, ClippingReport, CompareOptions, ComparisonReport, LoudnessMeasurement,
+    SilenceOptions, SilenceRegion,
+};
+use crate::audio::decoder::{self, decode_audio_range_with};
+use crate::audio::dsp::NormalizeReport;
+use crate::audio::edit::{self, EditList};
+use crate::audio::encoder::{self, OutputFormat};
+use crate::audio::export::{self, ExportPreset};
+use crate::audio::markers::Marker;
+use crate::audio::pipeline::{self, PipelineSummary, ResampleSpec};
+use crate::audio::redact::{self, RedactOptions, RedactionLog};
+use crate::audio::resample;
+use crate::audio::split::{self, Segment};
+use crate::audio::trim::{self, TrimSummary};
+use crate::audio::types::{AudioData, AudioInfo, TrimParams, WaveformPeaks};
+use crate::audio::waveform::{self, WaveformOptions};
+use crate::error::{AudioError, DecodeError, Result};
+use crate::memory::MemoryBudget;
+use crate::progress::ProgressSink;
+use crate::transcribe::{self, ChunkPlan, Transcriber, Transcript};
+
+/// Run `task` on the blocking pool, resolving early if `cancel` fires
+///
+/// The task gets its own clone of the token to check between chunks. A
+/// panic in the task is passed on to the caller, as the blocking function
+/// would have.
+async fn run_blocking<T, F>(cancel: &CancellationToken, task: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(CancellationToken) -> Result<T> + Send + 'static,
+{
+    check(cancel)?;
+    let token = cancel.clone();
+    let handle = tokio::task::spawn_blocking(move || task(token));
+    tokio::select! {
+        joined = handle => match joined {
+            Ok(result) => result,
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            // The runtime is shutting down
+            Err(_) => Err(AudioError::Cancelled),
+        },
+        _ = cancel.cancelled() => Err(AudioError::Cancelled),
+    }
+}
+
+fn check(cancel: &CancellationToken) -> Result<()> {
+    if cancel.is_cancelled() {
+        return Err(AudioError::Cancelled);
+    }
+    Ok(())
+}
+
+/// Sink that only carries cancellation from a token into the blocking
+/// `*_with_progress` functions
+struct TokenProgress(CancellationToken);
+
+impl ProgressSink for TokenProgress {
+    fn progress(&mut self, _fraction: f64) {}
+
+    fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+}
+
+/// Async [`crate::audio::decode_audio_file`]; stops at the next packet when cancelled
+pub async fn decode_audio_file<P: AsRef<Path>>(
+    path: P,
+    cancel: &CancellationToken,
+) -> Result<AudioData> {
+    let path = path.as_ref().to_path_buf();
+    run_blocking(cancel, move |cancel| {
+        decoder::decode_audio_file_with_progress(&path, &mut TokenProgress(cancel))
+    })
+    .await
+}
+
+/// Async [`crate::audio::decode_audio_range`]; stops at the next packet when cancelled
+pub async fn decode_audio_range<P: AsRef<Path>>(
+    path: P,
+    start_seconds: f64,
+    end_seconds: f64,
+    cancel: &CancellationToken,
+) -> Result<AudioData> {
+    let path = path.as_ref().to_path_buf();
+    run_blocking(cancel, move |cancel| {
+        let mut clip: Option<AudioData> = None;
+        decode_audio_range_with(&path, start_seconds, end_seconds, |chunk| {
+            check(&cancel)?;
+            match &mut clip {
+                Some(clip) => clip.samples.extend_from_slice(&chunk.samples),
+                None => clip = Some(chunk),
+            }
+            Ok(())
+        })?;
+        clip.ok_or_else(|| {
+            DecodeError::EmptyRange {
+                start: start_seconds,
+                end: end_seconds,
+            }
+            .into()
+        })
+    })
+    .await
+}
+
+/// Async [`crate::audio::get_audio_info`]
+pub async fn get_audio_info<P: AsRef<Path>>(
+    path: P,
+    cancel: &CancellationToken,
+) -> Result<AudioInfo> {
+    let path = path.as_ref().to_path_buf();
+    run_blocking(cancel, move |_| decoder::get_audio_info(&path)).await
+}
+
+/// Async [`crate::audio::extract_waveform_peaks`]; stops at the next packet when cancelled
+pub async fn extract_waveform_peaks<P: AsRef<Path>>(
+    path: P,
+    options: WaveformOptions,
+    cancel: &CancellationToken,
+) -> Result<WaveformPeaks> {
+    let path = path.as_ref().to_path_buf();
+    run_blocking(cancel, move |cancel| {
+        waveform::extract_waveform_peaks_with_progress(&path, &options, &mut TokenProgress(cancel))
+    })
+    .await
+}
+
+/// Async [`crate::audio::encode_audio`]
+///
+/// Stops at the next chunk when cancelled. Encodes to `<output_path>.part`
+/// and renames it into place only if the token wasn't cancelled in the
+/// meantime, so `output_path` never holds a file from a cancelled call.
+pub async fn encode_audio<P: AsRef<Path>>(
+    audio: impl Into<Arc<AudioData>>,
+    output_path: P,
+    format: OutputFormat,
+    cancel: &CancellationToken,
+) -> Result<()> {
+    let audio = audio.into();
+    let output_path = output_path.as_ref().to_path_buf();
+    run_blocking(cancel, move |cancel| {
+        let partial = partial_path(&output_path);
+        let mut progress = TokenProgress(cancel);
+        let result = encoder::encode_audio_with_progress(&audio, &partial, &format, &mut progress)
+            .and_then(|()| check(&progress.0))
+            .and_then(|()| Ok(std::fs::rename(&partial, &output_path)?));
+        if result.is_err() {
+            let _ = std::fs::remove_file(&partial);
+        }
+        result
+    })
+    .await
+}
+
+/// Async [`crate::audio::trim_file`]; stops at the next chunk when cancelled
+pub async fn trim_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    input_path: P,
+    output_path: Q,
+    params: TrimParams,
+    format: OutputFormat,
+    budget: MemoryBudget,
+    cancel: &CancellationToken,
+) -> Result<TrimSummary> {
+    let (input_path, output_path) = owned_paths(input_path, output_path);
+    run_blocking(cancel, move |cancel| {
+        let mut progress = TokenProgress(cancel);
+        trim::trim_file(&input_path, &output_path, &params, &format, &budget, &mut progress)
+    })
+    .await
+}
+
+/// Async [`crate::audio::join_file_segments`]; stops at the next chunk when cancelled
+pub async fn join_file_segments<P: AsRef<Path>, Q: AsRef<Path>>(
+    input_path: P,
+    output_path: Q,
+    segments: Vec<TrimParams>,
+    format: OutputFormat,
+    budget: MemoryBudget,
+    cancel: &CancellationToken,
+) -> Result<Vec<TrimSummary>> {
+    let (input_path, output_path) = owned_paths(input_path, output_path);
+    run_blocking(cancel, move |cancel| {
+        let mut progress = TokenProgress(cancel);
+        trim::join_file_segments(
+            &input_path,
+            &output_path,
+            &segments,
+            &format,
+            &budget,
+            &mut progress,
+        )
+    })
+    .await
+}
+
+/// Async [`crate::audio::export_file`]; stops at the next chunk when cancelled
+pub async fn export_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    input_path: P,
+    output_path: Q,
+    preset: ExportPreset,
+    budget: MemoryBudget,
+    cancel: &CancellationToken,
+) -> Result<Option<NormalizeReport>> {
+    let (input_path, output_path) = owned_paths(input_path, output_path);
+    run_blocking(cancel, move |cancel| {
+        let mut progress = TokenProgress(cancel);
+        export::export_file(&input_path, &output_path, &preset, &budget, &mut progress)
+    })
+    .await
+}
+
+/// Async [`crate::audio::export_file_segments`]; stops before the next
+/// segment when cancelled
+pub async fn export_file_segments<P: AsRef<Path>>(
+    path: P,
+    segments: Vec<Segment>,
+    paths: Vec<PathBuf>,
+    format: OutputFormat,
+    cancel: &CancellationToken,
+) -> Result<()> {
+    let path = path.as_ref().to_path_buf();
+    run_blocking(cancel, move |cancel| {
+        let mut progress = TokenProgress(cancel);
+        split::export_file_segments(&path, &segments, &paths, &format, &mut progress)
+    })
+    .await
+}
+
+/// Async [`crate::audio::redact_file`]; stops at the next chunk when cancelled
+pub async fn redact_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    input_path: P,
+    output_path: Q,
+    format: OutputFormat,
+    markers: Vec<Marker>,
+    options: RedactOptions,
+    cancel: &CancellationToken,
+) -> Result<RedactionLog> {
+    let (input_path, output_path) = owned_paths(input_path, output_path);
+    run_blocking(cancel, move |cancel| {
+        let mut progress = TokenProgress(cancel);
+        redact::redact_file(&input_path, &output_path, &format, &markers, &options, &mut progress)
+    })
+    .await
+}
+
+/// Async [`crate::audio::pipeline::resample_file`]; stops at the next chunk
+/// when cancelled
+pub async fn resample_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    input_path: P,
+    output_path: Q,
+    spec: ResampleSpec,
+    cancel: &CancellationToken,
+) -> Result<PipelineSummary> {
+    let (input_path, output_path) = owned_paths(input_path, output_path);
+    run_blocking(cancel, move |cancel| {
+        let mut progress = TokenProgress(cancel);
+        pipeline::resample_file(&input_path, &output_path, &spec, &mut progress)
+    })
+    .await
+}
+
+/// Async [`crate::audio::pipeline::denoise_file`]; stops at the next chunk
+/// when cancelled
+pub async fn denoise_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    input_path: P,
+    output_path: Q,
+    strength: f32,
+    format: OutputFormat,
+    cancel: &CancellationToken,
+) -> Result<PipelineSummary> {
+    let (input_path, output_path) = owned_paths(input_path, output_path);
+    run_blocking(cancel, move |cancel| {
+        let mut progress = TokenProgress(cancel);
+        pipeline::denoise_file(&input_path, &output_path, strength, &format, &mut progress)
+    })
+    .await
+}
+
+/// Async [`crate::audio::render_edit_list`]; stops at the next chunk when cancelled
+pub async fn render_edit_list<P: AsRef<Path>, Q: AsRef<Path>>(
+    input_path: P,
+    edits: EditList,
+    output_path: Q,
+    format: OutputFormat,
+    cancel: &CancellationToken,
+) -> Result<PipelineSummary> {
+    let (input_path, output_path) = owned_paths(input_path, output_path);
+    run_blocking(cancel, move |cancel| {
+        let mut progress = TokenProgress(cancel);
+        edit::render_edit_list(&input_path, &edits, &output_path, &format, &mut progress)
+    })
+    .await
+}
+
+fn owned_paths<P: AsRef<Path>, Q: AsRef<Path>>(input: P, output: Q) -> (PathBuf, PathBuf) {
+    (input.as_ref().to_path_buf(), output.as_ref().to_path_buf())
+}
+
+fn partial_path(path: &Path) -> PathBuf {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".part");
+    PathBuf::from(partial)
+}
+
+/// Async [`crate::audio::resample_audio`]
+pub async fn resample_audio(
+    audio: impl Into<Arc<AudioData>>,
+    target_rate: u32,
+    cancel: &CancellationToken,
+) -> Result<AudioData> {
+    let audio = audio.into();
+    run_blocking(cancel, move |_| resample::resample_audio(&audio, target_rate)).await
+}
+
+/// Async [`crate::audio::measure_loudness`]
+pub async fn measure_loudness(
+    audio: impl Into<Arc<AudioData>>,
+    cancel: &CancellationToken,
+) -> Result<LoudnessMeasurement> {
+    let audio = audio.into();
+    run_blocking(cancel, move |cancel| {
+        analysis::measure_loudness_with_progress(&audio, &mut TokenProgress(cancel))
+    })
+    .await
+}
+
+/// Async [`crate::audio::detect_clipping`]
+pub async fn detect_clipping(
+    audio: impl Into<Arc<AudioData>>,
+    options: ClippingOptions,
+    cancel: &CancellationToken,
+) -> Result<ClippingReport> {
+    let audio = audio.into();
+    run_blocking(cancel, move |cancel| {
+        analysis::detect_clipping_with_progress(&audio, &options, &mut TokenProgress(cancel))
+    })
+    .await
+}
+
+/// Async [`crate::audio::detect_silence`]
+pub async fn detect_silence(
+    audio: impl Into<Arc<AudioData>>,
+    options: SilenceOptions,
+    cancel: &CancellationToken,
+) -> Result<Vec<SilenceRegion>> {
+    let audio = audio.into();
+    run_blocking(cancel, move |cancel| {
+        analysis::detect_silence_with_progress(&audio, &options, &mut TokenProgress(cancel))
+    })
+    .await
+}
+
+/// Async [`crate::audio::compare_audio`]
+pub async fn compare_audio(
+    reference: impl Into<Arc<AudioData>>,
+    candidate: impl Into<Arc<AudioData>>,
+    options: CompareOptions,
+    cancel: &CancellationToken,
+) -> Result<ComparisonReport> {
+    let reference = reference.into();
+    let candidate = candidate.into();
+    run_blocking(cancel, move |cancel| {
+        let mut progress = TokenProgress(cancel);
+        analysis::compare_audio_with_progress(&reference, &candidate, &options, &mut progress)
+    })
+    .await
+}
+
+/// Async [`crate::transcribe::transcribe_file`]; stops at the next packet when cancelled
+///
+/// The transcriber is shared rather than moved so a loaded model can be
+/// reused for the next file.
+pub async fn transcribe_file<P, T>(
+    path: P,
+    transcriber: Arc<Mutex<T>>,
+    plan: ChunkPlan,
+    cancel: &CancellationToken,
+) -> Result<Transcript>
+where
+    P: AsRef<Path>,
+    T: Transcriber + Send + 'static,
+{
+    let path = path.as_ref().to_path_buf();
+    run_blocking(cancel, move |cancel| {
+        let mut transcriber = transcriber.lock().unwrap_or_else(|e| e.into_inner());
+        transcribe::transcribe_file(&path, &mut *transcriber, plan, &mut TokenProgress(cancel))
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::pipeline::{MemorySink, MemorySource, Pipeline};
+    use std::time::Duration;
+
+    const WAV: OutputFormat = OutputFormat::Wav {
+        sample_format: crate::audio::WavSampleFormat::Float32,
+    };
+
+    fn tone(seconds: f64) -> AudioData {
+        let frames = (seconds * 8000.0) as usize;
+        AudioData::new(
+            (0..frames).map(|i| (i as f32 * 0.05).sin() * 0.5).collect(),
+            8000,
+            1,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_decode_matches_blocking() {
+        let path = std::env::temp_dir().join("hermeneia_test_nonblocking_decode.wav");
+        crate::audio::encode_audio(&tone(1.0), &path, &WAV).unwrap();
+
+        let cancel = CancellationToken::new();
+        let decoded = decode_audio_file(&path, &cancel).await.unwrap();
+        assert_eq!(decoded.samples, crate::audio::decode_audio_file(&path).unwrap().samples);
+
+        cancel.cancel();
+        assert!(matches!(
+            decode_audio_file(&path, &cancel).await,
+            Err(AudioError::Cancelled)
+        ));
+        crate::audio::release_reader(&path);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_cancel_resolves_without_waiting() {
+        let cancel = CancellationToken::new();
+        let trigger = cancel.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            trigger.cancel();
+        });
+        let result: Result<()> = run_blocking(&cancel, |cancel| {
+            while !cancel.is_cancelled() {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            std::thread::sleep(Duration::from_millis(200));
+            Ok(())
+        })
+        .await;
+        assert!(matches!(result, Err(AudioError::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_encode_leaves_no_file() {
+        let path = std::env::temp_dir().join("hermeneia_test_nonblocking_encode.wav");
+        std::fs::remove_file(&path).ok();
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let result = encode_audio(tone(0.5), &path, WAV, &cancel).await;
+        assert!(matches!(result, Err(AudioError::Cancelled)));
+        assert!(!path.exists());
+        assert!(!partial_path(&path).exists());
+
+        let audio = Arc::new(tone(0.5));
+        encode_audio(audio.clone(), &path, WAV, &CancellationToken::new())
+            .await
+            .unwrap();
+        assert!(path.exists() && !partial_path(&path).exists());
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_trim_file_matches_blocking() {
+        let dir = std::env::temp_dir();
+        let input = dir.join("hermeneia_test_nonblocking_trim_in.wav");
+        let output = dir.join("hermeneia_test_nonblocking_trim_out.wav");
+        crate::audio::encode_audio(&tone(1.0), &input, &WAV).unwrap();
+        let params = TrimParams::new(0.25, 0.75).unwrap();
+        let budget = MemoryBudget::from_mb(64);
+
+        let cancel = CancellationToken::new();
+        let summary = trim_file(&input, &output, params.clone(), WAV, budget, &cancel)
+            .await
+            .unwrap();
+        assert_eq!(summary.frames, 4000);
+        assert!(output.exists());
+        std::fs::remove_file(&output).ok();
+
+        cancel.cancel();
+        let result = trim_file(&input, &output, params, WAV, budget, &cancel).await;
+        assert!(matches!(result, Err(AudioError::Cancelled)));
+        assert!(!output.exists());
+        crate::audio::release_reader(&input);
+        std::fs::remove_file(&input).ok();
+    }
+
+    #[test]
+    fn test_token_progress_stops_the_stream() {
+        let cancel = CancellationToken::new();
+        let mut progress = TokenProgress(cancel.clone());
+        let pipeline = Pipeline::new(MemorySource::new(tone(1.0), 100));
+        assert!(pipeline.run_with_progress(&mut MemorySink::default(), &mut progress).is_ok());
+
+        cancel.cancel();
+        let pipeline = Pipeline::new(MemorySource::new(tone(1.0), 100));
+        let result = pipeline.run_with_progress(&mut MemorySink::default(), &mut progress);
+        assert!(matches!(result, Err(AudioError::Cancelled)));
+    }
+}
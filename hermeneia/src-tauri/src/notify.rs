@@ -0,0 +1,350 @@
+// src-tauri/src/notify.rs
+// Desktop notifications and webhooks when long jobs finish
+
+use std::io::{self, Write};
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::error::{AudioError, Result};
+use crate::ingest::helper_command;
+
+/// Longest a webhook request may take
+const WEBHOOK_TIMEOUT_SECS: &str = "15";
+
+/// Who gets told when a job ends; stored in the app settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    /// Show a desktop notification
+    pub desktop: bool,
+    /// URLs that get a JSON [`JobEvent`] POSTed to them
+    pub webhooks: Vec<String>,
+    pub on_success: bool,
+    pub on_failure: bool,
+    /// Jobs quicker than this aren't worth a notification
+    pub min_seconds: f64,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            desktop: false,
+            webhooks: Vec::new(),
+            on_success: true,
+            on_failure: true,
+            min_seconds: 30.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobOutcome {
+    Completed,
+    /// At least one file failed
+    Failed,
+}
+
+/// What a finished job did; the webhook payload
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobEvent {
+    /// The operation or tool, e.g. "export_audio" or "resample"
+    pub job: String,
+    pub outcome: JobOutcome,
+    pub inputs: Vec<String>,
+    /// Files written by the parts that succeeded
+    pub outputs: Vec<String>,
+    pub failed: usize,
+    /// The first failure's message
+    pub error: Option<String>,
+    pub duration_seconds: f64,
+    /// Local time with offset, RFC 3339
+    pub finished_at: String,
+}
+
+impl JobEvent {
+    /// An event finished now, failed if `error` is set
+    pub fn new(
+        job: &str,
+        inputs: Vec<String>,
+        outputs: Vec<String>,
+        error: Option<String>,
+    ) -> Self {
+        Self {
+            job: job.to_string(),
+            outcome: if error.is_some() {
+                JobOutcome::Failed
+            } else {
+                JobOutcome::Completed
+            },
+            failed: usize::from(error.is_some()),
+            inputs,
+            outputs,
+            error,
+            duration_seconds: 0.0,
+            finished_at: chrono::Local::now().to_rfc3339(),
+        }
+    }
+
+    pub fn took(mut self, duration: Duration) -> Self {
+        self.duration_seconds = duration.as_secs_f64();
+        self
+    }
+
+    /// Notification title and body, e.g. "resample finished" and
+    /// "12 files in 3m 05s"
+    pub fn describe(&self) -> (String, String) {
+        let title = match self.outcome {
+            JobOutcome::Completed => format!("{} finished", self.job),
+            JobOutcome::Failed => format!("{} failed", self.job),
+        };
+        let what = match self.inputs.as_slice() {
+            [one] => std::path::Path::new(one)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| one.clone()),
+            many => format!("{} files", many.len()),
+        };
+        let mut body = format!("{} in {}", what, format_elapsed(self.duration_seconds));
+        if self.inputs.len() > 1 && self.failed > 0 {
+            body.push_str(&format!(", {} failed", self.failed));
+        }
+        if let Some(error) = &self.error {
+            body.push_str(&format!(": {}", error));
+        }
+        (title, body)
+    }
+}
+
+impl NotificationSettings {
+    /// Whether `event` should be announced at all
+    pub fn wants(&self, event: &JobEvent) -> bool {
+        let outcome = match event.outcome {
+            JobOutcome::Completed => self.on_success,
+            JobOutcome::Failed => self.on_failure,
+        };
+        outcome
+            && (self.desktop || !self.webhooks.is_empty())
+            && event.duration_seconds >= self.min_seconds
+    }
+}
+
+/// Announce a finished job as the settings ask
+///
+/// Blocks until every webhook has answered or timed out. A notification
+/// that can't be delivered is logged, never returned: it mustn't turn a
+/// finished job into a failed one.
+pub fn notify(settings: &NotificationSettings, event: &JobEvent) {
+    if !settings.wants(event) {
+        return;
+    }
+    if settings.desktop {
+        let (title, body) = event.describe();
+        if let Err(e) = show_desktop_notification(&title, &body) {
+            warn!(error = %e, "Couldn't show a desktop notification");
+        }
+    }
+    for url in &settings.webhooks {
+        if let Err(e) = post_webhook(url, event) {
+            warn!(error = %e, "Webhook failed");
+        }
+    }
+}
+
+/// Like [`notify`], on a thread of its own so the caller isn't held up
+pub fn notify_in_background(settings: NotificationSettings, event: JobEvent) {
+    if settings.wants(&event) {
+        std::thread::spawn(move || notify(&settings, &event));
+    }
+}
+
+/// POST `event` as JSON to `url` with the system's `curl`
+///
+/// # Errors
+/// [`AudioError::Download`] when curl is missing, the server can't be
+/// reached or it answers with an HTTP error
+pub fn post_webhook(url: &str, event: &JobEvent) -> Result<()> {
+    let mut child = helper_command("curl")
+        .args(["--fail", "--silent", "--show-error", "--location"])
+        .args(["--proto", "=http,https", "--proto-redir", "=http,https"])
+        .args(["--max-time", WEBHOOK_TIMEOUT_SECS])
+        .args(["--header", "Content-Type: application/json"])
+        .args(["--data-binary", "@-", "--", url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| webhook_error(url, e.to_string()))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        serde_json::to_writer(&mut stdin, event).map_err(|e| webhook_error(url, e.to_string()))?;
+        stdin.flush()?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        let reason = String::from_utf8_lossy(&output.stderr);
+        return Err(webhook_error(url, reason.trim().trim_start_matches("curl: ").to_string()));
+    }
+    debug!(url, "Webhook delivered");
+    Ok(())
+}
+
+fn webhook_error(url: &str, reason: String) -> AudioError {
+    AudioError::Download {
+        url: url.to_string(),
+        reason: if reason.is_empty() {
+            "the request failed".to_string()
+        } else {
+            reason
+        },
+    }
+}
+
+/// Show a notification with the desktop's own tool: `notify-send` on
+/// Linux, AppleScript on macOS and a tray balloon through PowerShell on
+/// Windows
+pub fn show_desktop_notification(title: &str, body: &str) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut command = helper_command("osascript");
+        command.arg("-e").arg(format!(
+            "display notification {} with title {}",
+            applescript_string(body),
+            applescript_string(title)
+        ));
+        command
+    };
+
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = helper_command("powershell");
+        command.args(["-NoProfile", "-NonInteractive", "-Command"]).arg(format!(
+            "Add-Type -AssemblyName System.Windows.Forms; \
+             $n = New-Object System.Windows.Forms.NotifyIcon; \
+             $n.Icon = [System.Drawing.SystemIcons]::Information; $n.Visible = $true; \
+             $n.ShowBalloonTip(10000, {}, {}, 'Info'); Start-Sleep -Seconds 10; $n.Dispose()",
+            powershell_string(title),
+            powershell_string(body)
+        ));
+        command
+    };
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = {
+        let mut command = helper_command("notify-send");
+        command.args(["--app-name", "Hermeneia", "--", title, body]);
+        command
+    };
+
+    let output = command.stdin(Stdio::null()).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "the notification tool failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// `text` as a double-quoted AppleScript string literal
+#[cfg_attr(not(any(target_os = "macos", test)), allow(dead_code))]
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// `text` as a single-quoted PowerShell string literal
+///
+/// PowerShell also takes the typographic quotes U+2018 to U+201B as single
+/// quotes, so those are doubled as well.
+#[cfg_attr(not(any(target_os = "windows", test)), allow(dead_code))]
+fn powershell_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('\'');
+    for c in text.chars() {
+        if matches!(c, '\'' | '\u{2018}'..='\u{201B}') {
+            quoted.push(c);
+        }
+        quoted.push(c);
+    }
+    quoted.push('\'');
+    quoted
+}
+
+/// "45s", "3m 05s" or "2h 14m"
+fn format_elapsed(seconds: f64) -> String {
+    let seconds = seconds.max(0.0).round() as u64;
+    match seconds {
+        0..60 => format!("{}s", seconds),
+        60..3600 => format!("{}m {:02}s", seconds / 60, seconds % 60),
+        _ => format!("{}h {:02}m", seconds / 3600, seconds % 3600 / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn batch(files: usize, error: Option<&str>) -> JobEvent {
+        let inputs = (0..files).map(|i| format!("/sermons/{}.wav", i)).collect();
+        JobEvent::new("resample", inputs, Vec::new(), error.map(String::from))
+            .took(Duration::from_secs(185))
+    }
+
+    #[test]
+    fn test_settings_choose_which_jobs_to_announce() {
+        let mut settings = NotificationSettings {
+            desktop: true,
+            ..Default::default()
+        };
+        assert!(settings.wants(&batch(3, None)));
+        assert!(settings.wants(&batch(3, Some("disk full"))));
+        assert!(!settings.wants(&batch(3, None).took(Duration::from_secs(5))));
+
+        settings.on_success = false;
+        assert!(!settings.wants(&batch(3, None)));
+        assert!(settings.wants(&batch(3, Some("disk full"))));
+
+        settings.desktop = false;
+        assert!(!settings.wants(&batch(3, Some("disk full"))), "nowhere to send it");
+        settings.webhooks.push("https://example.org/hook".to_string());
+        assert!(settings.wants(&batch(3, Some("disk full"))));
+    }
+
+    #[test]
+    fn test_describe() {
+        let (title, body) = batch(1, None).describe();
+        assert_eq!(title, "resample finished");
+        assert_eq!(body, "0.wav in 3m 05s");
+
+        let mut failed = batch(12, Some("decode error"));
+        failed.failed = 2;
+        let (title, body) = failed.describe();
+        assert_eq!(title, "resample failed");
+        assert_eq!(body, "12 files in 3m 05s, 2 failed: decode error");
+
+        assert_eq!(format_elapsed(42.4), "42s");
+        assert_eq!(format_elapsed(8040.0), "2h 14m");
+    }
+
+    #[test]
+    fn test_payload_shape() {
+        let json = serde_json::to_value(batch(2, Some("decode error"))).unwrap();
+        assert_eq!(json["job"], "resample");
+        assert_eq!(json["outcome"], "failed");
+        assert_eq!(json["inputs"][1], "/sermons/1.wav");
+        assert_eq!(json["failed"], 1);
+        assert_eq!(json["duration_seconds"], 185.0);
+        let finished = json["finished_at"].as_str().unwrap();
+        assert!(chrono::DateTime::parse_from_rfc3339(finished).is_ok(), "{}", finished);
+    }
+
+    #[test]
+    fn test_quoting_for_notification_scripts() {
+        assert_eq!(applescript_string(r#"say "hi" \ bye"#), r#""say \"hi\" \\ bye""#);
+        assert_eq!(powershell_string("it's done"), "'it''s done'");
+        assert_eq!(powershell_string("it\u{2019}s; rm"), "'it\u{2019}\u{2019}s; rm'");
+    }
+}
@@ -0,0 +1,420 @@
+// src-tauri/src/pacing.rs
+// Speaking rate, pauses and filler words from transcripts
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::time::{AudioDuration, Timestamp};
+use crate::error::{AudioError, Result};
+use crate::transcribe::Segment;
+
+/// Settings for [`analyze_pacing`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PacingOptions {
+    /// Length of the window each point of the rate curve counts words over
+    pub window_seconds: f64,
+    /// Time between points of the rate curve
+    pub step_seconds: f64,
+    /// Shortest silence between two words that counts as a pause
+    pub min_pause_seconds: f64,
+    /// Words and phrases counted as fillers, matched without regard to case
+    /// or punctuation
+    pub fillers: Vec<String>,
+}
+
+impl Default for PacingOptions {
+    fn default() -> Self {
+        Self {
+            window_seconds: 30.0,
+            step_seconds: 10.0,
+            min_pause_seconds: 0.5,
+            // Words like "like" or "so" are fillers only some of the time,
+            // so they're left for users to add
+            fillers: ["um", "umm", "uh", "uhm", "er", "erm", "ah", "hmm", "you know", "i mean"]
+                .map(String::from)
+                .to_vec(),
+        }
+    }
+}
+
+impl PacingOptions {
+    fn validate(&self) -> Result<()> {
+        for (name, value) in [("Window", self.window_seconds), ("Step", self.step_seconds)] {
+            if !(value.is_finite() && value > 0.0) {
+                return Err(AudioError::InvalidParameter(format!(
+                    "{} length must be positive (got {}s)",
+                    name, value
+                )));
+            }
+        }
+        if !(self.min_pause_seconds.is_finite() && self.min_pause_seconds >= 0.0) {
+            return Err(AudioError::InvalidParameter(format!(
+                "Pause length must not be negative (got {}s)",
+                self.min_pause_seconds
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// One point of the rate curve
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RatePoint {
+    /// Middle of the window
+    pub time: Timestamp,
+    pub words_per_minute: f64,
+    /// Filler words in the window
+    pub fillers: usize,
+}
+
+/// A silence between two words
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Pause {
+    pub start: Timestamp,
+    pub duration: AudioDuration,
+}
+
+/// How often one filler was said
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FillerCount {
+    pub filler: String,
+    pub count: usize,
+}
+
+/// Delivery statistics for a transcript
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PacingReport {
+    pub words: usize,
+    /// From the first word's start to the last word's end
+    pub speaking_span: AudioDuration,
+    /// Words over the whole span, pauses included
+    pub words_per_minute: f64,
+    /// Words over the time spent talking, pauses left out
+    pub articulation_rate: f64,
+    /// Words per minute through the talk, one point per step
+    pub rate: Vec<RatePoint>,
+    pub pauses: Vec<Pause>,
+    pub pauses_per_minute: f64,
+    /// Average pause length, in seconds (0 with no pauses)
+    pub mean_pause_seconds: f64,
+    pub longest_pause: AudioDuration,
+    /// Each filler that was said, most frequent first
+    pub fillers: Vec<FillerCount>,
+    pub fillers_per_minute: f64,
+    /// Some segments had no word timing, so their words were spread evenly
+    /// over the segment; pauses inside those segments can't be seen
+    pub estimated_timing: bool,
+}
+
+/// A word, normalized for matching
+struct TimedWord {
+    start: Timestamp,
+    end: Timestamp,
+    text: String,
+}
+
+/// Measure speaking rate, pauses and filler words in a transcript
+///
+/// Uses word timings where the engine gave them. Every word counts towards
+/// the rate, fillers included.
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::Timestamp;
+/// use hermeneia_lib::pacing::{analyze_pacing, PacingOptions};
+/// use hermeneia_lib::transcribe::Segment;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let segment = Segment {
+///     start: Timestamp::from_seconds(0.0),
+///     end: Timestamp::from_seconds(3.0),
+///     text: "Um, grace and peace to you".to_string(),
+///     words: Vec::new(),
+///     speaker: None,
+/// };
+/// let report = analyze_pacing(&[segment], &PacingOptions::default())?;
+/// assert_eq!(report.words, 6);
+/// assert_eq!(report.words_per_minute, 120.0);
+/// assert_eq!(report.fillers[0].filler, "um");
+/// # Ok(())
+/// # }
+/// ```
+pub fn analyze_pacing(segments: &[Segment], options: &PacingOptions) -> Result<PacingReport> {
+    options.validate()?;
+    let (words, estimated_timing) = timed_words(segments);
+    let filler_starts = find_fillers(&words, &options.fillers);
+
+    let (Some(first), Some(last)) = (words.first(), words.last()) else {
+        return Ok(PacingReport {
+            words: 0,
+            speaking_span: AudioDuration::ZERO,
+            words_per_minute: 0.0,
+            articulation_rate: 0.0,
+            rate: Vec::new(),
+            pauses: Vec::new(),
+            pauses_per_minute: 0.0,
+            mean_pause_seconds: 0.0,
+            longest_pause: AudioDuration::ZERO,
+            fillers: Vec::new(),
+            fillers_per_minute: 0.0,
+            estimated_timing,
+        });
+    };
+    let span_start = first.start;
+    let span_end = words.iter().map(|w| w.end).max().unwrap_or(last.end);
+    let span = span_end.saturating_since(span_start);
+
+    let min_pause = AudioDuration::from_seconds(options.min_pause_seconds);
+    let mut pauses = Vec::new();
+    let mut spoken_until = first.end;
+    for word in &words[1..] {
+        let gap = word.start.saturating_since(spoken_until);
+        if !gap.is_zero() && gap >= min_pause {
+            pauses.push(Pause {
+                start: spoken_until,
+                duration: gap,
+            });
+        }
+        spoken_until = spoken_until.max(word.end);
+    }
+    let paused = pauses.iter().fold(AudioDuration::ZERO, |sum, p| sum + p.duration);
+
+    let per_minute = |count: usize, length: AudioDuration| {
+        let minutes = length.as_seconds() / 60.0;
+        if minutes > 0.0 {
+            count as f64 / minutes
+        } else {
+            0.0
+        }
+    };
+
+    // Each point counts the words whose middle falls in a window around
+    // it; windows are cut short at the ends of the talk
+    let half_window = AudioDuration::from_seconds(options.window_seconds / 2.0);
+    let step = AudioDuration::from_seconds(options.step_seconds);
+    let mut rate = Vec::new();
+    let mut time = span_start;
+    while time <= span_end {
+        let from = (time - half_window).max(span_start);
+        let to = (time + half_window).min(span_end);
+        let inside = |t: Timestamp| t >= from && t < to;
+        rate.push(RatePoint {
+            time,
+            words_per_minute: per_minute(
+                words.iter().filter(|w| inside(w.start.midpoint(w.end))).count(),
+                to.saturating_since(from),
+            ),
+            fillers: filler_starts.iter().filter(|(t, _)| inside(*t)).count(),
+        });
+        if step.is_zero() {
+            break;
+        }
+        time += step;
+    }
+
+    let mut fillers: Vec<FillerCount> = Vec::new();
+    for (_, filler) in &filler_starts {
+        match fillers.iter_mut().find(|f| &f.filler == filler) {
+            Some(found) => found.count += 1,
+            None => fillers.push(FillerCount {
+                filler: filler.clone(),
+                count: 1,
+            }),
+        }
+    }
+    // Stable, so ties keep the order they were first said in
+    fillers.sort_by_key(|f| std::cmp::Reverse(f.count));
+
+    Ok(PacingReport {
+        words: words.len(),
+        speaking_span: span,
+        words_per_minute: per_minute(words.len(), span),
+        articulation_rate: per_minute(words.len(), span - paused),
+        rate,
+        pauses_per_minute: per_minute(pauses.len(), span),
+        mean_pause_seconds: if pauses.is_empty() {
+            0.0
+        } else {
+            paused.as_seconds() / pauses.len() as f64
+        },
+        longest_pause: pauses.iter().map(|p| p.duration).max().unwrap_or_default(),
+        pauses,
+        fillers,
+        fillers_per_minute: per_minute(filler_starts.len(), span),
+        estimated_timing,
+    })
+}
+
+/// Every word in time order, and whether any timing had to be estimated
+fn timed_words(segments: &[Segment]) -> (Vec<TimedWord>, bool) {
+    let mut words = Vec::new();
+    let mut estimated = false;
+    for segment in segments {
+        if !segment.words.is_empty() {
+            words.extend(segment.words.iter().filter_map(|word| {
+                let text = normalize(&word.text);
+                (!text.is_empty()).then_some(TimedWord {
+                    start: word.start,
+                    end: word.end,
+                    text,
+                })
+            }));
+            continue;
+        }
+        let texts: Vec<String> = segment
+            .text
+            .split_whitespace()
+            .map(normalize)
+            .filter(|text| !text.is_empty())
+            .collect();
+        if texts.is_empty() {
+            continue;
+        }
+        estimated = true;
+        let each = segment.end.saturating_since(segment.start) / texts.len() as u32;
+        let mut start = segment.start;
+        for text in texts {
+            words.push(TimedWord {
+                start,
+                end: start + each,
+                text,
+            });
+            start += each;
+        }
+    }
+    words.sort_by_key(|word| word.start);
+    (words, estimated)
+}
+
+/// Lowercase, without the punctuation around or inside a word (apostrophes
+/// stay, so "don't" is still one word)
+pub(crate) fn normalize(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric() || *c == '\'' || *c == '\u{2019}')
+        .flat_map(char::to_lowercase)
+        .collect::<String>()
+        .trim_matches(['\'', '\u{2019}'])
+        .to_string()
+}
+
+/// Start of each filler said, and which one it was
+///
+/// Longer phrases are tried first, and the words of a match aren't
+/// reused, so "you know" isn't also counted as "you".
+fn find_fillers(words: &[TimedWord], fillers: &[String]) -> Vec<(Timestamp, String)> {
+    let mut phrases: Vec<(Vec<String>, &String)> = fillers
+        .iter()
+        .map(|filler| (filler.split_whitespace().map(normalize).collect::<Vec<_>>(), filler))
+        .filter(|(tokens, _)| !tokens.is_empty() && tokens.iter().all(|t| !t.is_empty()))
+        .collect();
+    phrases.sort_by_key(|(tokens, _)| std::cmp::Reverse(tokens.len()));
+
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        let matched = phrases.iter().find(|(tokens, _)| {
+            words[i..].len() >= tokens.len()
+                && tokens.iter().zip(&words[i..]).all(|(token, word)| *token == word.text)
+        });
+        match matched {
+            Some((tokens, filler)) => {
+                found.push((words[i].start, filler.to_lowercase()));
+                i += tokens.len();
+            }
+            None => i += 1,
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcribe::Word;
+
+    fn word(start: f64, end: f64, text: &str) -> Word {
+        Word {
+            start: Timestamp::from_seconds(start),
+            end: Timestamp::from_seconds(end),
+            text: text.to_string(),
+        }
+    }
+
+    fn segment(words: Vec<Word>) -> Segment {
+        Segment {
+            start: words[0].start,
+            end: words[words.len() - 1].end,
+            text: words.iter().map(|w| w.text.as_str()).collect::<Vec<_>>().join(" "),
+            words,
+            speaker: None,
+        }
+    }
+
+    #[test]
+    fn test_rate_and_pauses_from_word_timing() {
+        // Two words a second for 30 s, a 3 s pause, then four a second for 15 s
+        let mut words: Vec<Word> =
+            (0..60).map(|i| word(i as f64 * 0.5, i as f64 * 0.5 + 0.4, "grace")).collect();
+        words.extend((0..60).map(|i| {
+            let start = 33.0 + i as f64 * 0.25;
+            word(start, start + 0.2, "peace")
+        }));
+        let options = PacingOptions {
+            window_seconds: 10.0,
+            ..PacingOptions::default()
+        };
+        let report = analyze_pacing(&[segment(words)], &options).unwrap();
+
+        assert_eq!(report.words, 120);
+        assert!((report.speaking_span.as_seconds() - 47.95).abs() < 1e-6);
+        assert_eq!(report.pauses.len(), 1);
+        assert!((report.pauses[0].start.as_seconds() - 29.9).abs() < 1e-6);
+        assert!((report.longest_pause.as_seconds() - 3.1).abs() < 1e-6);
+        assert!(report.articulation_rate > report.words_per_minute);
+
+        let at = |seconds: f64| {
+            report
+                .rate
+                .iter()
+                .find(|p| (p.time.as_seconds() - seconds).abs() < 1e-6)
+                .unwrap()
+                .words_per_minute
+        };
+        assert!((at(10.0) - 120.0).abs() < 1e-6);
+        assert!((at(40.0) - 240.0).abs() < 1e-6);
+        assert_eq!(report.rate.len(), 5);
+    }
+
+    #[test]
+    fn test_counts_fillers_and_phrases() {
+        let segments = [Segment {
+            start: Timestamp::ZERO,
+            end: Timestamp::from_seconds(10.0),
+            text: "Um, you know, the, uh... you know what I mean? UM".to_string(),
+            words: Vec::new(),
+            speaker: None,
+        }];
+        let report = analyze_pacing(&segments, &PacingOptions::default()).unwrap();
+
+        assert!(report.estimated_timing);
+        assert!(report.pauses.is_empty());
+        let counts: Vec<(&str, usize)> =
+            report.fillers.iter().map(|f| (f.filler.as_str(), f.count)).collect();
+        assert_eq!(counts, vec![("um", 2), ("you know", 2), ("uh", 1), ("i mean", 1)]);
+        assert_eq!(report.rate[0].fillers, 6);
+        assert!((report.fillers_per_minute - 36.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_empty_transcripts_and_bad_options() {
+        let report = analyze_pacing(&[], &PacingOptions::default()).unwrap();
+        assert_eq!(report.words, 0);
+        assert!(report.rate.is_empty());
+
+        let options = PacingOptions {
+            step_seconds: 0.0,
+            ..PacingOptions::default()
+        };
+        assert!(analyze_pacing(&[], &options).is_err());
+    }
+}
@@ -0,0 +1,143 @@
+// src-tauri/src/playback/command.rs
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Instruction for the audio output callback
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlaybackCommand {
+    Play,
+    Pause,
+    /// Drop everything buffered and continue from this frame
+    Seek { frame: u64 },
+    /// Linear gain applied to every sample
+    SetVolume(f32),
+}
+
+const TAG_SHIFT: u32 = 56;
+const PAYLOAD_MASK: u64 = (1 << TAG_SHIFT) - 1;
+
+impl PlaybackCommand {
+    /// Pack into one word: tag in the top byte, payload below
+    fn encode(self) -> u64 {
+        let (tag, payload) = match self {
+            Self::Play => (0, 0),
+            Self::Pause => (1, 0),
+            Self::Seek { frame } => (2, frame & PAYLOAD_MASK),
+            Self::SetVolume(volume) => (3, volume.to_bits() as u64),
+        };
+        (tag << TAG_SHIFT) | payload
+    }
+
+    fn decode(word: u64) -> Self {
+        let payload = word & PAYLOAD_MASK;
+        match word >> TAG_SHIFT {
+            0 => Self::Play,
+            1 => Self::Pause,
+            2 => Self::Seek { frame: payload },
+            _ => Self::SetVolume(f32::from_bits(payload as u32)),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Queue {
+    slots: Box<[AtomicU64]>,
+    /// Commands ever received and sent; the difference is the queue length
+    read: AtomicUsize,
+    write: AtomicUsize,
+}
+
+/// Sending side of a [`command_queue`]
+#[derive(Debug)]
+pub struct CommandSender {
+    queue: Arc<Queue>,
+}
+
+/// Receiving side of a [`command_queue`], owned by the audio callback
+#[derive(Debug)]
+pub struct CommandReceiver {
+    queue: Arc<Queue>,
+}
+
+/// Single-producer, single-consumer queue of [`PlaybackCommand`]s
+///
+/// Commands are packed into atomic words, so receiving never locks or
+/// allocates and is safe on the real-time audio thread.
+pub fn command_queue(capacity: usize) -> (CommandSender, CommandReceiver) {
+    let queue = Arc::new(Queue {
+        slots: (0..capacity.max(1)).map(|_| AtomicU64::new(0)).collect(),
+        read: AtomicUsize::new(0),
+        write: AtomicUsize::new(0),
+    });
+    (
+        CommandSender {
+            queue: queue.clone(),
+        },
+        CommandReceiver { queue },
+    )
+}
+
+impl CommandSender {
+    /// Queue a command; gives it back if the queue is full
+    pub fn send(&self, command: PlaybackCommand) -> Result<(), PlaybackCommand> {
+        let queue = &self.queue;
+        let write = queue.write.load(Ordering::Relaxed);
+        if write.wrapping_sub(queue.read.load(Ordering::Acquire)) >= queue.slots.len() {
+            return Err(command);
+        }
+        queue.slots[write % queue.slots.len()].store(command.encode(), Ordering::Relaxed);
+        queue.write.store(write.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+}
+
+impl CommandReceiver {
+    /// Next queued command, if any
+    pub fn try_recv(&self) -> Option<PlaybackCommand> {
+        let queue = &self.queue;
+        let read = queue.read.load(Ordering::Relaxed);
+        if read == queue.write.load(Ordering::Acquire) {
+            return None;
+        }
+        let word = queue.slots[read % queue.slots.len()].load(Ordering::Relaxed);
+        queue.read.store(read.wrapping_add(1), Ordering::Release);
+        Some(PlaybackCommand::decode(word))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commands_round_trip_in_order() {
+        let (sender, receiver) = command_queue(8);
+        let commands = [
+            PlaybackCommand::Play,
+            PlaybackCommand::Seek { frame: 1 << 40 },
+            PlaybackCommand::SetVolume(0.25),
+            PlaybackCommand::Pause,
+        ];
+        for command in commands {
+            sender.send(command).unwrap();
+        }
+
+        let received: Vec<_> = std::iter::from_fn(|| receiver.try_recv()).collect();
+        assert_eq!(received, commands);
+    }
+
+    #[test]
+    fn test_full_queue_rejects() {
+        let (sender, receiver) = command_queue(2);
+        sender.send(PlaybackCommand::Play).unwrap();
+        sender.send(PlaybackCommand::Pause).unwrap();
+        assert_eq!(
+            sender.send(PlaybackCommand::Play),
+            Err(PlaybackCommand::Play)
+        );
+
+        assert_eq!(receiver.try_recv(), Some(PlaybackCommand::Play));
+        assert!(sender.send(PlaybackCommand::Play).is_ok());
+    }
+}
@@ -0,0 +1,152 @@
+// src-tauri/src/playback/device.rs
+
+use cpal::traits::{DeviceTrait, HostTrait};
+use serde::Serialize;
+use tracing::warn;
+
+use crate::error::{PlaybackError, Result};
+use crate::settings::Settings;
+
+/// An output device playback can use
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OutputDevice {
+    /// What [`output_device`] and the `output_device` setting take; cpal
+    /// only tells devices apart by name, so this is the name for now
+    pub id: String,
+    pub name: String,
+    /// The system's default output
+    pub is_default: bool,
+}
+
+/// Output devices on the default host, the system default first
+///
+/// Devices that share a name (some ALSA setups list one per card mode)
+/// are listed once, since they can't be told apart when opening.
+pub fn list_output_devices() -> Result<Vec<OutputDevice>> {
+    let host = cpal::default_host();
+    let default = host.default_output_device().and_then(|device| device.name().ok());
+    let devices = host
+        .output_devices()
+        .map_err(|e| PlaybackError::Device(format!("Failed to list output devices: {}", e)))?;
+
+    let mut listed: Vec<OutputDevice> = Vec::new();
+    for name in devices.filter_map(|device| device.name().ok()) {
+        if listed.iter().any(|device| device.name == name) {
+            continue;
+        }
+        listed.push(OutputDevice {
+            id: name.clone(),
+            is_default: default.as_ref() == Some(&name),
+            name,
+        });
+    }
+    listed.sort_by_key(|device| !device.is_default);
+    Ok(listed)
+}
+
+/// The output device with this id, or the system default for `None`
+pub(crate) fn output_device(id: Option<&str>) -> Result<cpal::Device> {
+    let host = cpal::default_host();
+    let Some(id) = id else {
+        return host.default_output_device().ok_or_else(|| PlaybackError::NoDevice.into());
+    };
+    host.output_devices()
+        .map_err(|e| PlaybackError::Device(format!("Failed to list output devices: {}", e)))?
+        .find(|device| device.name().is_ok_and(|name| name == id))
+        .ok_or_else(|| PlaybackError::Device(format!("No output device named '{}'", id)).into())
+}
+
+/// Rate and channel count an output stream runs at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct OutputFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+/// Format to open `device` at for a stream in the `wanted` format
+///
+/// The stream's own format when the device takes it. Otherwise the channel
+/// count and rate it doesn't take come from the device's default, and
+/// playback resamples and remixes to them.
+pub(crate) fn output_format(device: &cpal::Device, wanted: OutputFormat) -> Result<OutputFormat> {
+    let default = device
+        .default_output_config()
+        .map_err(|e| PlaybackError::Device(format!("Failed to query output device: {}", e)))?;
+    let default = OutputFormat {
+        sample_rate: default.sample_rate().0,
+        channels: default.channels(),
+    };
+    // Not every backend can list its ranges; the default always works
+    let supported: Vec<(u16, u32, u32)> = device
+        .supported_output_configs()
+        .map(|configs| {
+            configs
+                .map(|c| (c.channels(), c.min_sample_rate().0, c.max_sample_rate().0))
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(pick_format(wanted, &supported, default))
+}
+
+/// `wanted`, with its channels or rate replaced by `default`'s where none
+/// of the `(channels, min rate, max rate)` ranges cover them
+fn pick_format(
+    wanted: OutputFormat,
+    supported: &[(u16, u32, u32)],
+    default: OutputFormat,
+) -> OutputFormat {
+    let channels = if supported.iter().any(|&(c, _, _)| c == wanted.channels) {
+        wanted.channels
+    } else {
+        default.channels
+    };
+    let covered = supported
+        .iter()
+        .any(|&(c, min, max)| c == channels && (min..=max).contains(&wanted.sample_rate));
+    OutputFormat {
+        sample_rate: if covered { wanted.sample_rate } else { default.sample_rate },
+        channels,
+    }
+}
+
+/// The device from the `output_device` setting
+///
+/// Falls back to the system default when the saved device has been
+/// unplugged, so playback still starts.
+pub(crate) fn configured_output_device(settings: &Settings) -> Result<cpal::Device> {
+    let Some(id) = settings.output_device.as_deref() else {
+        return output_device(None);
+    };
+    output_device(Some(id)).or_else(|e| {
+        warn!(device = id, error = %e, "Saved output device unavailable; using the default");
+        output_device(None)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(sample_rate: u32, channels: u16) -> OutputFormat {
+        OutputFormat {
+            sample_rate,
+            channels,
+        }
+    }
+
+    #[test]
+    fn test_pick_format_prefers_the_stream_format() {
+        let supported = [(2, 44100, 48000), (1, 8000, 16000)];
+        let default = format(48000, 2);
+        let pick = |wanted| pick_format(wanted, &supported, default);
+        assert_eq!(pick(format(44100, 2)), format(44100, 2));
+        assert_eq!(pick(format(16000, 1)), format(16000, 1));
+        // A 48 kHz-only device: resample to its default
+        assert_eq!(pick(format(24000, 2)), format(48000, 2));
+        // The rate fits, but not with this many channels
+        assert_eq!(pick(format(16000, 2)), format(48000, 2));
+        // No 6-channel output: fold down to the default's two
+        assert_eq!(pick(format(44100, 6)), format(44100, 2));
+        assert_eq!(pick_format(format(44100, 1), &[], default), default);
+    }
+}
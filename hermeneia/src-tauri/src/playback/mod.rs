@@ -0,0 +1,115 @@
+// src-tauri/src/playback/mod.rs
+// Audio playback: a decoder thread feeding the output device callback
+
+pub mod command;
+pub mod device;
+pub mod player;
+pub mod ring;
+pub mod state;
+
+pub use command::{command_queue, CommandReceiver, CommandSender, PlaybackCommand};
+pub use device::{list_output_devices, OutputDevice};
+pub use player::AudioPlayer;
+pub use ring::{sample_ring, RingConsumer, RingProducer};
+pub use state::{PlayState, PlaybackSnapshot, SharedPlaybackState};
+
+use std::time::Duration;
+
+use crate::settings::Settings;
+
+/// Audio buffered ahead of the output when the setting isn't set
+///
+/// Long enough to ride out a slow disk or a busy decoder thread, short
+/// enough that a seek (which throws the buffer away) feels immediate.
+pub const DEFAULT_BUFFER_SECONDS: f64 = 0.5;
+
+/// Range accepted for the `playback_buffer_seconds` setting
+pub const MIN_BUFFER_SECONDS: f64 = 0.05;
+pub const MAX_BUFFER_SECONDS: f64 = 10.0;
+
+/// Playback updates per second while playing when the setting isn't set;
+/// enough for a smooth cursor without flooding the frontend
+pub const DEFAULT_UPDATE_HZ: f64 = 20.0;
+
+/// Range accepted for the `playback_update_hz` setting
+pub const MIN_UPDATE_HZ: f64 = 1.0;
+pub const MAX_UPDATE_HZ: f64 = 60.0;
+
+/// Target buffer duration from the `playback_buffer_seconds` setting
+pub fn buffer_seconds(settings: &Settings) -> f64 {
+    settings
+        .playback_buffer_seconds
+        .filter(|s| s.is_finite())
+        .map_or(DEFAULT_BUFFER_SECONDS, |s| {
+            s.clamp(MIN_BUFFER_SECONDS, MAX_BUFFER_SECONDS)
+        })
+}
+
+/// Time between playback updates from the `playback_update_hz` setting
+pub fn update_interval(settings: &Settings) -> Duration {
+    let hz = settings
+        .playback_update_hz
+        .filter(|hz| hz.is_finite())
+        .map_or(DEFAULT_UPDATE_HZ, |hz| hz.clamp(MIN_UPDATE_HZ, MAX_UPDATE_HZ));
+    Duration::from_secs_f64(1.0 / hz)
+}
+
+/// Ring capacity in samples holding `seconds` of audio for this stream
+///
+/// Sized from the stream itself, so the same target means the same
+/// latency for 16 kHz mono speech and 48 kHz stereo music. Always a whole
+/// number of frames, and at least one.
+pub fn buffer_capacity(sample_rate: u32, channels: u16, seconds: f64) -> usize {
+    let channels = channels.max(1) as usize;
+    let frames = (seconds.max(0.0) * sample_rate as f64).ceil() as usize;
+    frames.max(1) * channels
+}
+
+/// A sample ring for a stream, sized by the current settings
+pub fn stream_ring(sample_rate: u32, channels: u16) -> (RingProducer, RingConsumer) {
+    let seconds = buffer_seconds(&Settings::load());
+    sample_ring(buffer_capacity(sample_rate, channels, seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capacity_follows_stream_format() {
+        // Same duration, very different sizes
+        assert_eq!(buffer_capacity(48000, 2, 0.5), 48000);
+        assert_eq!(buffer_capacity(16000, 1, 0.5), 8000);
+        // Partial frames round up, and never less than one frame
+        assert_eq!(buffer_capacity(44100, 2, 0.00001), 2);
+        assert_eq!(buffer_capacity(44100, 6, 0.0), 6);
+    }
+
+    #[test]
+    fn test_buffer_seconds_setting() {
+        let with = |seconds| Settings {
+            playback_buffer_seconds: seconds,
+            ..Default::default()
+        };
+        assert_eq!(buffer_seconds(&with(None)), DEFAULT_BUFFER_SECONDS);
+        assert_eq!(buffer_seconds(&with(Some(2.0))), 2.0);
+        assert_eq!(buffer_seconds(&with(Some(0.0))), MIN_BUFFER_SECONDS);
+        assert_eq!(buffer_seconds(&with(Some(60.0))), MAX_BUFFER_SECONDS);
+        assert_eq!(
+            buffer_seconds(&with(Some(f64::NAN))),
+            DEFAULT_BUFFER_SECONDS
+        );
+    }
+
+    #[test]
+    fn test_update_interval_setting() {
+        let with = |hz| Settings {
+            playback_update_hz: hz,
+            ..Default::default()
+        };
+        assert_eq!(update_interval(&with(None)), Duration::from_millis(50));
+        assert_eq!(update_interval(&with(Some(10.0))), Duration::from_millis(100));
+        assert_eq!(update_interval(&with(Some(0.0))), Duration::from_secs(1));
+        assert_eq!(update_interval(&with(Some(1000.0))), Duration::from_secs_f64(1.0 / 60.0));
+    }
+}
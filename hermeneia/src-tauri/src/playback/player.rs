@@ -0,0 +1,1199 @@
+// src-tauri/src/playback/player.rs
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use cpal::traits::{DeviceTrait, StreamTrait};
+use cpal::{FromSample, SampleFormat, SizedSample};
+use symphonia::core::formats::{SeekMode, SeekTo};
+use symphonia::core::units::Time;
+use tracing::{debug, warn};
+
+use super::command::{command_queue, CommandReceiver, CommandSender, PlaybackCommand};
+use super::device::{configured_output_device, output_device, output_format, OutputFormat};
+use super::ring::{RingConsumer, RingProducer};
+use super::state::{PlayState, PlaybackSnapshot, SharedPlaybackState};
+use super::stream_ring;
+use crate::audio::decoder::{convert_audio_buffer_to_f32, open_audio_track, AudioTrack};
+use crate::audio::channels::ChannelMixer;
+use crate::audio::resample::StreamResampler;
+use crate::audio::time::Timestamp;
+use crate::error::{AudioError, PlaybackError, Result};
+use crate::settings::Settings;
+
+/// Commands the callback can have waiting at once
+const COMMAND_CAPACITY: usize = 64;
+
+/// Longest the playback thread waits for the callback to flush after a seek
+const SEEK_FLUSH_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Requests from the app to the playback thread
+#[derive(Debug)]
+enum Control {
+    Play,
+    Pause,
+    Seek(Timestamp),
+    SetVolume(f32),
+    /// Loop between two frames, or stop looping
+    SetLoop(Option<(u64, u64)>),
+    /// Move to another output device (`None` for the default) and report back
+    SetDevice(Option<String>, Sender<Result<()>>),
+    /// Send snapshots to a new listener
+    Watch(Updates),
+    Shutdown,
+}
+
+/// Snapshots handed from the playback thread to a listener
+struct Updates {
+    interval: Duration,
+    listener: Box<dyn FnMut(PlaybackSnapshot) + Send>,
+    /// When the last one went out, and what it was
+    last: Option<(Instant, PlaybackSnapshot)>,
+}
+
+impl fmt::Debug for Updates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Updates")
+            .field("interval", &self.interval)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Updates {
+    /// Time until the next snapshot is due while playing
+    fn due_in(&self) -> Duration {
+        self.last.map_or(Duration::ZERO, |(at, _)| {
+            self.interval.saturating_sub(at.elapsed())
+        })
+    }
+
+    /// How long the playback thread may wait before offering another; a
+    /// whole interval when one is overdue but wasn't sent (paused)
+    fn wait(&self) -> Duration {
+        let due = self.due_in();
+        let wait = if due.is_zero() { self.interval } else { due };
+        wait.max(Duration::from_millis(1))
+    }
+
+    /// Send `snapshot` if it's due: every interval while playing, and
+    /// otherwise only when something changed, so a paused player is quiet
+    fn offer(&mut self, snapshot: PlaybackSnapshot) {
+        let due = match &self.last {
+            None => true,
+            Some(_) if snapshot.state == PlayState::Playing => self.due_in().is_zero(),
+            Some((_, last)) => *last != snapshot,
+        };
+        if due {
+            (self.listener)(snapshot);
+            self.last = Some((Instant::now(), snapshot));
+        }
+    }
+}
+
+/// Plays one audio file on the output device from the settings
+///
+/// A playback thread decodes into a sample ring and owns the output stream.
+/// The device callback only pops from the ring and reads a lock-free command
+/// queue, so the real-time thread never waits on a lock or allocates. When
+/// the device can't run at the file's rate or channel count, the playback
+/// thread resamples and remixes before filling the ring, for the same reason.
+pub struct AudioPlayer {
+    path: PathBuf,
+    control: Sender<Control>,
+    state: Arc<SharedPlaybackState>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl AudioPlayer {
+    /// Open `path` for playback, paused at the start
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let track = open_audio_track(&path)?;
+        let state = Arc::new(SharedPlaybackState::new(
+            track.sample_rate,
+            track.channels,
+            track.n_frames,
+        ));
+
+        let (control, control_rx) = mpsc::channel();
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let thread_state = state.clone();
+        let thread = std::thread::Builder::new()
+            .name("playback".to_string())
+            .spawn(move || run_playback_stream(track, control_rx, thread_state, ready_tx))
+            .map_err(|e| {
+                PlaybackError::Thread(format!("Failed to start playback thread: {}", e))
+            })?;
+
+        // Stream setup happens on the playback thread; wait for its verdict
+        match ready_rx.recv() {
+            Ok(Ok(())) => Ok(Self {
+                path,
+                control,
+                state,
+                thread: Some(thread),
+            }),
+            Ok(Err(e)) => {
+                let _ = thread.join();
+                Err(e)
+            }
+            Err(_) => {
+                let _ = thread.join();
+                Err(PlaybackError::Thread("Playback thread exited during setup".to_string()).into())
+            }
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Start or resume; restarts from the beginning after the end
+    pub fn play(&self) {
+        self.send(Control::Play);
+    }
+
+    pub fn pause(&self) {
+        self.send(Control::Pause);
+    }
+
+    pub fn seek(&self, position: Timestamp) {
+        self.send(Control::Seek(position));
+    }
+
+    /// Linear output gain (1.0 = unchanged)
+    pub fn set_volume(&self, volume: f32) {
+        self.send(Control::SetVolume(volume.max(0.0)));
+    }
+
+    /// Play from `start` to `end` over and over, e.g. while correcting a
+    /// transcript segment
+    ///
+    /// Playback jumps to `start` unless it's already inside the region. An
+    /// `end` past the end of the file loops at the end of the file.
+    pub fn set_loop_region(&self, start: Timestamp, end: Timestamp) -> Result<()> {
+        let rate = self.state.sample_rate();
+        let first = start.to_frames(rate);
+        let mut last = end.to_frames(rate);
+        if let Some(duration) = self.state.duration_frames() {
+            last = last.min(duration);
+        }
+        if first >= last {
+            return Err(AudioError::InvalidParameter(format!(
+                "Loop region from {:.3}s to {:.3}s is empty",
+                start.as_seconds(),
+                end.as_seconds()
+            )));
+        }
+        self.send(Control::SetLoop(Some((first, last))));
+        Ok(())
+    }
+
+    /// Stop looping and play on from where it is
+    pub fn clear_loop_region(&self) {
+        self.send(Control::SetLoop(None));
+    }
+
+    /// Pause and go back to the start
+    pub fn stop(&self) {
+        self.pause();
+        self.seek(Timestamp::ZERO);
+    }
+
+    pub fn snapshot(&self) -> PlaybackSnapshot {
+        self.state.snapshot()
+    }
+
+    /// Have the playback thread call `listener` with a snapshot every
+    /// `interval` while playing, and once after each pause, seek or end
+    ///
+    /// Saves the frontend polling [`AudioPlayer::snapshot`] for a smooth
+    /// cursor. Replaces any earlier listener.
+    pub fn on_update(
+        &self,
+        interval: Duration,
+        listener: impl FnMut(PlaybackSnapshot) + Send + 'static,
+    ) {
+        self.send(Control::Watch(Updates {
+            interval,
+            listener: Box::new(listener),
+            last: None,
+        }));
+    }
+
+    /// Carry on playing through another output device, `None` for the
+    /// system default
+    ///
+    /// Position, volume and whether it's playing are kept. If the device
+    /// can't be opened, playback continues where it was and the error is
+    /// returned.
+    pub fn set_output_device(&self, id: Option<&str>) -> Result<()> {
+        let (reply, reply_rx) = mpsc::channel();
+        self.send(Control::SetDevice(id.map(str::to_string), reply));
+        reply_rx.recv().unwrap_or_else(|_| {
+            Err(PlaybackError::Thread("Playback thread has stopped".to_string()).into())
+        })
+    }
+
+    fn send(&self, control: Control) {
+        // Only fails if the playback thread is gone, in which case there's nothing to control
+        let _ = self.control.send(control);
+    }
+}
+
+impl Drop for AudioPlayer {
+    fn drop(&mut self) {
+        self.send(Control::Shutdown);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Playback thread: owns the output stream and keeps its ring filled
+///
+/// Sends the outcome of opening the stream through `ready`, then runs until
+/// the player shuts it down.
+fn run_playback_stream(
+    track: AudioTrack,
+    control: Receiver<Control>,
+    state: Arc<SharedPlaybackState>,
+    ready: Sender<Result<()>>,
+) {
+    let output = configured_output_device(&Settings::load())
+        .and_then(|device| Output::open(&device, &state));
+    let output = match output {
+        Ok(output) => output,
+        Err(e) => {
+            let _ = ready.send(Err(e));
+            return;
+        }
+    };
+    let _ = ready.send(Ok(()));
+
+    // Refill about four times per buffer length
+    let format = output.format;
+    let buffer_seconds =
+        output.producer.capacity() as f64 / (format.sample_rate as f64 * format.channels as f64);
+    let refill_interval = Duration::from_secs_f64((buffer_seconds / 4.0).max(0.005));
+
+    let mut playback = PlaybackThread {
+        decoder: PlaybackDecoder::new(track, format),
+        output,
+        state,
+        updates: None,
+    };
+
+    let mut next = None;
+    loop {
+        // Handle everything the app sent since the last pass
+        loop {
+            let message = match next.take() {
+                Some(message) => message,
+                None => match control.try_recv() {
+                    Ok(message) => message,
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => return,
+                },
+            };
+            if !playback.handle(message) {
+                return;
+            }
+        }
+
+        playback.fill();
+        let wait = match &mut playback.updates {
+            Some(updates) => {
+                updates.offer(playback.state.snapshot());
+                refill_interval.min(updates.wait())
+            }
+            None => refill_interval,
+        };
+
+        next = match control.recv_timeout(wait) {
+            Ok(message) => Some(message),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+    }
+}
+
+/// A running output stream and the ends of its ring and command queue
+struct Output {
+    /// What the device runs at; the ring holds audio in this format
+    format: OutputFormat,
+    producer: RingProducer,
+    commands: CommandSender,
+    _stream: cpal::Stream,
+}
+
+impl Output {
+    /// Start a stream on `device` that picks up from the shared state
+    fn open(device: &cpal::Device, state: &Arc<SharedPlaybackState>) -> Result<Self> {
+        let wanted = OutputFormat {
+            sample_rate: state.sample_rate(),
+            channels: state.channels().max(1),
+        };
+        let format = output_format(device, wanted)?;
+        let (producer, consumer) = stream_ring(format.sample_rate, format.channels);
+        let (commands, command_rx) = command_queue(COMMAND_CAPACITY);
+        let renderer = OutputRenderer::new(consumer, command_rx, state.clone(), format);
+        let stream = open_output_stream(device, format, renderer)?;
+        Ok(Self {
+            format,
+            producer,
+            commands,
+            _stream: stream,
+        })
+    }
+}
+
+/// State owned by the playback thread
+struct PlaybackThread {
+    decoder: PlaybackDecoder,
+    output: Output,
+    state: Arc<SharedPlaybackState>,
+    updates: Option<Updates>,
+}
+
+impl PlaybackThread {
+    /// Act on one request; returns `false` to shut down
+    fn handle(&mut self, message: Control) -> bool {
+        match message {
+            Control::Play => {
+                if self.state.state() == PlayState::Ended {
+                    self.seek(Timestamp::ZERO);
+                }
+                self.send(PlaybackCommand::Play);
+            }
+            Control::Pause => self.send(PlaybackCommand::Pause),
+            Control::Seek(position) => self.seek(position),
+            Control::SetVolume(volume) => self.send(PlaybackCommand::SetVolume(volume)),
+            Control::SetLoop(region) => self.set_loop(region),
+            Control::Watch(updates) => self.updates = Some(updates),
+            Control::SetDevice(id, reply) => {
+                let _ = reply.send(self.switch_device(id.as_deref()));
+            }
+            Control::Shutdown => return false,
+        }
+        true
+    }
+
+    fn send(&self, command: PlaybackCommand) {
+        if self.output.commands.send(command).is_err() {
+            warn!(?command, "Playback command queue full; dropping command");
+        }
+    }
+
+    /// Reposition the decoder and have the callback drop what it buffered
+    fn seek(&mut self, position: Timestamp) {
+        let frame = self.decoder.seek(position);
+        self.state.decoder_finished.store(false, Ordering::Release);
+
+        // Old samples must be flushed before new ones go in
+        self.state.seek_pending.store(true, Ordering::Release);
+        self.send(PlaybackCommand::Seek { frame });
+        let started = Instant::now();
+        while self.state.seek_pending.load(Ordering::Acquire) {
+            if started.elapsed() > SEEK_FLUSH_TIMEOUT {
+                debug!("Output callback didn't flush after seek; continuing");
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    /// Loop between two frames, or stop looping
+    ///
+    /// Seeks to where playback is, or to the loop start from outside the
+    /// loop, so audio already decoded for the old region isn't heard.
+    fn set_loop(&mut self, region: Option<(u64, u64)>) {
+        self.decoder.loop_region = region;
+        self.state.set_loop_region(region);
+        let position = self.state.position_frames();
+        let frame = match region {
+            Some((start, end)) if !(start..end).contains(&position) => start,
+            _ => position,
+        };
+        self.seek(Timestamp::from_frames(frame, self.state.sample_rate()));
+    }
+
+    /// Move the stream to another device, carrying on from the same frame
+    ///
+    /// The new stream starts silent with its own ring. Once the old one is
+    /// closed, the decoder seeks back to the last frame heard, so nothing
+    /// buffered for the old device is skipped or played twice.
+    fn switch_device(&mut self, id: Option<&str>) -> Result<()> {
+        let device = output_device(id)?;
+        let was = self.state.state();
+        self.output = Output::open(&device, &self.state)?;
+        self.decoder.set_output_format(self.output.format);
+
+        let position =
+            Timestamp::from_frames(self.state.position_frames(), self.state.sample_rate());
+        debug!(device = ?id, seconds = position.as_seconds(), "Switched output device");
+        self.seek(position);
+        match was {
+            PlayState::Playing => self.send(PlaybackCommand::Play),
+            // The seek reopened it; keep reporting the end until played again
+            PlayState::Ended => self.state.set_state(PlayState::Ended),
+            PlayState::Paused => {}
+        }
+        Ok(())
+    }
+
+    /// Decode until the ring is full or the file ends
+    fn fill(&mut self) {
+        if self.decoder.fill(&mut self.output.producer) {
+            self.state.decoder_finished.store(true, Ordering::Release);
+        }
+        // The decoder found the real end of a loop that ran past the file
+        if self.decoder.loop_region != self.state.loop_region() {
+            self.state.set_loop_region(self.decoder.loop_region);
+        }
+    }
+}
+
+/// Decodes the file packet by packet for the ring
+struct PlaybackDecoder {
+    track: AudioTrack,
+    /// Converts to the device's rate when it doesn't run at the file's
+    resampler: Option<StreamResampler>,
+    /// Then to the device's channel count, likewise
+    mixer: Option<ChannelMixer>,
+    /// Decoded samples not yet pushed into the ring
+    pending: Vec<f32>,
+    offset: usize,
+    /// Samples before this frame are dropped (after an accurate seek)
+    skip_until_frame: u64,
+    /// A-B loop in frames, and whether the last seek landed before its end
+    /// (playing past the end of a loop carries on to the end of the file)
+    loop_region: Option<(u64, u64)>,
+    looping: bool,
+    /// Something was decoded since the last seek, so wrapping at the end of
+    /// the file can't spin on a loop that starts past it
+    progressed: bool,
+    /// End of the last packet decoded, in the file's frames
+    decoded_to: u64,
+    /// The resampler's tail has been taken at the end of the file
+    flushed: bool,
+    finished: bool,
+}
+
+impl PlaybackDecoder {
+    /// Decode `track` for a device running in `format`
+    fn new(track: AudioTrack, format: OutputFormat) -> Self {
+        let mut decoder = Self {
+            track,
+            resampler: None,
+            mixer: None,
+            pending: Vec::new(),
+            offset: 0,
+            skip_until_frame: 0,
+            loop_region: None,
+            looping: false,
+            progressed: false,
+            decoded_to: 0,
+            flushed: false,
+            finished: false,
+        };
+        decoder.set_output_format(format);
+        decoder
+    }
+
+    /// Resample and remix to `format` from now on, or not at all where it
+    /// matches the file
+    ///
+    /// Takes effect cleanly only from a seek, which throws away the
+    /// resampler's history.
+    fn set_output_format(&mut self, format: OutputFormat) {
+        let (rate, channels) = (self.track.sample_rate, self.track.channels);
+        self.resampler = None;
+        if format.sample_rate != rate {
+            debug!(from = rate, to = format.sample_rate, "Resampling for the output device");
+            self.resampler = StreamResampler::new(rate, format.sample_rate, channels)
+                .map_err(|e| warn!(error = %e, "Can't resample; playing at the file's rate"))
+                .ok();
+        }
+        self.mixer = None;
+        if format.channels != channels {
+            debug!(from = channels, to = format.channels, "Remixing for the output device");
+            self.mixer = ChannelMixer::new(channels, format.channels)
+                .map_err(|e| warn!(error = %e, "Can't remix; playing the file's channels"))
+                .ok();
+        }
+    }
+
+    /// Push pending samples and decode more; returns `true` at the end of the file
+    fn fill(&mut self, producer: &mut RingProducer) -> bool {
+        loop {
+            if self.offset < self.pending.len() {
+                self.offset += producer.push(&self.pending[self.offset..]);
+                if self.offset < self.pending.len() {
+                    return false;
+                }
+            }
+            if self.finished || !self.decode_next() {
+                self.finished = true;
+                return true;
+            }
+        }
+    }
+
+    /// Decode the next packet into `pending`; `false` at the end of the file
+    fn decode_next(&mut self) -> bool {
+        loop {
+            let track = &mut self.track;
+            let Ok(packet) = track.format.next_packet() else {
+                if self.looping && self.progressed {
+                    // The loop runs to the end of the file, which is only
+                    // known now when the length wasn't
+                    if let Some((start, end)) = self.loop_region {
+                        self.loop_region = Some((start, end.min(self.decoded_to)));
+                    }
+                    self.pending.clear();
+                    self.offset = 0;
+                    if self.wrap() {
+                        return true;
+                    }
+                    continue;
+                }
+                return self.flush_resampler();
+            };
+            if packet.track_id() != track.track_id {
+                continue;
+            }
+
+            let packet_start = track.ts_to_frame(packet.ts());
+            let decoded = match track.decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                Err(e) => {
+                    // One bad packet shouldn't end playback
+                    debug!(error = %e, "Skipping undecodable packet");
+                    continue;
+                }
+            };
+
+            self.pending.clear();
+            self.offset = 0;
+            convert_audio_buffer_to_f32(&decoded, &mut self.pending);
+
+            let channels = track.channels.max(1) as usize;
+            let skip = self.skip_until_frame.saturating_sub(packet_start) as usize * channels;
+            if skip >= self.pending.len() {
+                continue;
+            }
+            self.offset = skip;
+            self.progressed = true;
+            self.decoded_to = packet_start + (self.pending.len() / channels) as u64;
+
+            // Cut the packet at the loop end and go back to the loop start
+            let loop_end = self.loop_region.filter(|_| self.looping).map(|(_, end)| end);
+            if let Some(end) = loop_end {
+                let keep = end.saturating_sub(packet_start) as usize * channels;
+                if keep <= self.pending.len() {
+                    self.pending.truncate(keep.max(self.offset));
+                    self.convert();
+                    if self.wrap() {
+                        return true;
+                    }
+                    continue;
+                }
+            }
+            if self.convert() {
+                return true;
+            }
+        }
+    }
+
+    /// End a pass through the loop and seek back to its start, keeping
+    /// what's in `pending`; `false` if that's nothing
+    fn wrap(&mut self) -> bool {
+        let Some((start, _)) = self.loop_region else {
+            return false;
+        };
+        // The pass must come out at its full length for the position to match
+        self.take_tail();
+        let pending = std::mem::take(&mut self.pending);
+        let offset = self.offset;
+        self.seek(Timestamp::from_frames(start, self.track.sample_rate));
+        self.pending = pending;
+        self.offset = offset;
+        self.offset < self.pending.len()
+    }
+
+    /// Bring what's left of `pending` to the device's rate and channels
+    ///
+    /// Returns `false` when the resampler is still filling its first block.
+    fn convert(&mut self) -> bool {
+        if let Some(resampler) = &mut self.resampler {
+            self.pending = resampler
+                .process(&self.pending[self.offset..])
+                .unwrap_or_else(|e| {
+                    warn!(error = %e, "Resampling failed; skipping packet");
+                    Vec::new()
+                });
+            self.offset = 0;
+        }
+        self.remix();
+        self.offset < self.pending.len()
+    }
+
+    fn remix(&mut self) {
+        if let Some(mixer) = &self.mixer {
+            self.pending = mixer.mix(&self.pending[self.offset..]);
+            self.offset = 0;
+        }
+    }
+
+    /// Put the resampler's buffered tail in `pending` at the end of the
+    /// file; `false` when there's nothing more to play
+    fn flush_resampler(&mut self) -> bool {
+        if self.flushed {
+            return false;
+        }
+        self.flushed = true;
+        self.pending.clear();
+        self.offset = 0;
+        self.take_tail();
+        !self.pending.is_empty()
+    }
+
+    /// Add the resampler's buffered tail, remixed, to `pending`
+    fn take_tail(&mut self) {
+        let Some(resampler) = &mut self.resampler else {
+            return;
+        };
+        let tail = resampler.flush().unwrap_or_else(|e| {
+            warn!(error = %e, "Resampling failed at the end of the audio");
+            Vec::new()
+        });
+        match &self.mixer {
+            Some(mixer) => self.pending.extend(mixer.mix(&tail)),
+            None => self.pending.extend(tail),
+        }
+    }
+
+    /// Seek to `position`; returns the frame playback resumes from
+    fn seek(&mut self, position: Timestamp) -> u64 {
+        let track = &mut self.track;
+        let seconds = position.as_seconds();
+        let frame = position.to_frames(track.sample_rate);
+
+        self.pending.clear();
+        self.offset = 0;
+        self.finished = false;
+        self.flushed = false;
+        self.progressed = false;
+        self.looping = self.loop_region.is_some_and(|(_, end)| frame < end);
+        self.skip_until_frame = frame;
+        if let Some(resampler) = &mut self.resampler {
+            resampler.reset();
+        }
+
+        let seek = track.format.seek(
+            SeekMode::Accurate,
+            SeekTo::Time {
+                time: Time::from(seconds),
+                track_id: Some(track.track_id),
+            },
+        );
+        match seek {
+            Ok(_) => track.decoder.reset(),
+            Err(e) => warn!(error = %e, seconds, "Seek failed"),
+        }
+        frame
+    }
+}
+
+/// Fills device buffers from the ring; lives inside the output callback
+pub(crate) struct OutputRenderer {
+    ring: RingConsumer,
+    commands: CommandReceiver,
+    state: Arc<SharedPlaybackState>,
+    /// Channels on the device, which the ring is interleaved with
+    channels: u64,
+    /// The file's rate, and the device's rate the ring is at
+    source_rate: u64,
+    output_rate: u64,
+    playing: bool,
+    volume: f32,
+    /// Frame of the last seek (in the file), and samples played since
+    seek_frame: u64,
+    samples_played: u64,
+}
+
+impl OutputRenderer {
+    /// A paused renderer at the shared state's position and volume, so a
+    /// stream opened mid-file (on another device) doesn't jump back to 0
+    ///
+    /// `format` is the device's, which the ring holds audio in.
+    pub(crate) fn new(
+        ring: RingConsumer,
+        commands: CommandReceiver,
+        state: Arc<SharedPlaybackState>,
+        format: OutputFormat,
+    ) -> Self {
+        Self {
+            ring,
+            commands,
+            channels: format.channels.max(1) as u64,
+            source_rate: state.sample_rate().max(1) as u64,
+            output_rate: format.sample_rate.max(1) as u64,
+            playing: false,
+            volume: state.snapshot().volume,
+            seek_frame: state.position_frames(),
+            samples_played: 0,
+            state,
+        }
+    }
+
+    /// Fill one device buffer; runs on the real-time audio thread
+    pub(crate) fn render<T>(&mut self, out: &mut [T])
+    where
+        T: SizedSample + FromSample<f32>,
+    {
+        self.apply_commands();
+
+        let mut scratch = [0.0f32; 512];
+        let mut written = 0;
+        while written < out.len() && self.playing {
+            let want = (out.len() - written).min(scratch.len());
+            let got = self.ring.pop(&mut scratch[..want]);
+            for (o, &sample) in out[written..written + got].iter_mut().zip(&scratch[..got]) {
+                *o = T::from_sample(sample * self.volume);
+            }
+            written += got;
+            self.samples_played += got as u64;
+            if got < want {
+                break;
+            }
+        }
+        // Paused or underrun
+        for o in &mut out[written..] {
+            *o = T::EQUILIBRIUM;
+        }
+
+        // Played frames are at the device's rate; the position is in the file's
+        let played = self.samples_played / self.channels * self.source_rate / self.output_rate;
+        let mut position = self.seek_frame + played;
+        // The decoder went back to the loop start at the loop end
+        if let Some((start, end)) = self.state.loop_region() {
+            if self.seek_frame < end && position >= end {
+                position = start + (position - end) % (end - start);
+            }
+        }
+        self.state.set_position_frames(position);
+        if self.playing
+            && self.ring.is_empty()
+            && self.state.decoder_finished.load(Ordering::Acquire)
+        {
+            self.playing = false;
+            self.state.set_state(PlayState::Ended);
+        }
+    }
+
+    fn apply_commands(&mut self) {
+        while let Some(command) = self.commands.try_recv() {
+            match command {
+                PlaybackCommand::Play => {
+                    self.playing = true;
+                    self.state.set_state(PlayState::Playing);
+                }
+                PlaybackCommand::Pause => {
+                    self.playing = false;
+                    self.state.set_state(PlayState::Paused);
+                }
+                PlaybackCommand::Seek { frame } => {
+                    self.ring.clear();
+                    self.seek_frame = frame;
+                    self.samples_played = 0;
+                    if self.state.state() == PlayState::Ended {
+                        self.state.set_state(PlayState::Paused);
+                    }
+                    self.state.seek_pending.store(false, Ordering::Release);
+                }
+                PlaybackCommand::SetVolume(volume) => {
+                    self.volume = volume;
+                    self.state.set_volume(volume);
+                }
+            }
+        }
+    }
+}
+
+/// Open a stream on `device` in `format` and start it
+fn open_output_stream(
+    device: &cpal::Device,
+    format: OutputFormat,
+    renderer: OutputRenderer,
+) -> Result<cpal::Stream> {
+    let failed =
+        |what: &str, e: &dyn std::fmt::Display| PlaybackError::Device(format!("{}: {}", what, e));
+
+    let sample_format = device
+        .default_output_config()
+        .map_err(|e| failed("Failed to query output device", &e))?
+        .sample_format();
+    let config = cpal::StreamConfig {
+        channels: format.channels,
+        sample_rate: cpal::SampleRate(format.sample_rate),
+        buffer_size: cpal::BufferSize::Default,
+    };
+    debug!(?format, ?sample_format, "Opening output stream");
+
+    let stream = match sample_format {
+        SampleFormat::I16 => build_output_stream::<i16>(device, &config, renderer),
+        SampleFormat::U16 => build_output_stream::<u16>(device, &config, renderer),
+        _ => build_output_stream::<f32>(device, &config, renderer),
+    }
+    .map_err(|e| failed("Failed to open output stream", &e))?;
+
+    stream
+        .play()
+        .map_err(|e| failed("Failed to start output stream", &e))?;
+    Ok(stream)
+}
+
+fn build_output_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    mut renderer: OutputRenderer,
+) -> std::result::Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: SizedSample + FromSample<f32>,
+{
+    device.build_output_stream(
+        config,
+        move |data: &mut [T], _| renderer.render(data),
+        |e| warn!(error = %e, "Audio output error"),
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::playback::sample_ring;
+    use cpal::Sample;
+
+    fn format(sample_rate: u32, channels: u16) -> OutputFormat {
+        OutputFormat {
+            sample_rate,
+            channels,
+        }
+    }
+
+    fn renderer(
+        channels: u16,
+    ) -> (RingProducer, CommandSender, Arc<SharedPlaybackState>, OutputRenderer) {
+        let (producer, consumer) = sample_ring(64);
+        let (sender, receiver) = command_queue(8);
+        let state = Arc::new(SharedPlaybackState::new(100, channels, None));
+        let renderer =
+            OutputRenderer::new(consumer, receiver, state.clone(), format(100, channels));
+        (producer, sender, state, renderer)
+    }
+
+    #[test]
+    fn test_paused_renders_silence() {
+        let (mut producer, _sender, state, mut renderer) = renderer(1);
+        producer.push(&[0.5; 8]);
+
+        let mut out = [1.0f32; 4];
+        renderer.render(&mut out);
+        assert_eq!(out, [0.0; 4]);
+        assert_eq!(state.state(), PlayState::Paused);
+        assert_eq!(state.position_frames(), 0);
+    }
+
+    #[test]
+    fn test_plays_with_volume_and_tracks_position() {
+        let (mut producer, sender, state, mut renderer) = renderer(2);
+        producer.push(&[0.5; 8]);
+        sender.send(PlaybackCommand::SetVolume(0.5)).unwrap();
+        sender.send(PlaybackCommand::Play).unwrap();
+
+        let mut out = [0i16; 6];
+        renderer.render(&mut out);
+        assert!(out.iter().all(|&s| s == i16::from_sample(0.25f32)));
+        assert_eq!(state.position_frames(), 3);
+        assert_eq!(state.snapshot().volume, 0.5);
+
+        // Underrun: two samples left, the rest is silence
+        let mut out = [1.0f32; 6];
+        renderer.render(&mut out);
+        assert_eq!(out, [0.25, 0.25, 0.0, 0.0, 0.0, 0.0]);
+        assert_eq!(state.state(), PlayState::Playing);
+    }
+
+    #[test]
+    fn test_seek_flushes_and_end_is_reported() {
+        let (mut producer, sender, state, mut renderer) = renderer(1);
+        producer.push(&[0.9; 10]);
+        sender.send(PlaybackCommand::Play).unwrap();
+        state.seek_pending.store(true, Ordering::Release);
+        sender.send(PlaybackCommand::Seek { frame: 500 }).unwrap();
+
+        let mut out = [0.0f32; 4];
+        renderer.render(&mut out);
+        assert_eq!(out, [0.0; 4]);
+        assert!(!state.seek_pending.load(Ordering::Acquire));
+        assert_eq!(state.position_frames(), 500);
+
+        producer.push(&[0.1; 2]);
+        state.decoder_finished.store(true, Ordering::Release);
+        renderer.render(&mut out);
+        assert_eq!(state.position_frames(), 502);
+        assert_eq!(state.state(), PlayState::Ended);
+    }
+
+    #[test]
+    fn test_new_renderer_resumes_shared_state() {
+        // As when the stream moves to another device mid-file
+        let (mut producer, consumer) = sample_ring(64);
+        let (sender, receiver) = command_queue(8);
+        let state = Arc::new(SharedPlaybackState::new(100, 1, None));
+        state.set_position_frames(250);
+        state.set_volume(0.5);
+        let mut renderer = OutputRenderer::new(consumer, receiver, state.clone(), format(100, 1));
+
+        let mut out = [1.0f32; 4];
+        renderer.render(&mut out);
+        assert_eq!(out, [0.0; 4]);
+        assert_eq!(state.position_frames(), 250);
+
+        producer.push(&[0.8; 4]);
+        sender.send(PlaybackCommand::Play).unwrap();
+        renderer.render(&mut out);
+        assert_eq!(out, [0.4; 4]);
+        assert_eq!(state.position_frames(), 254);
+    }
+
+    #[test]
+    fn test_decoder_fills_ring_and_seeks() {
+        use crate::audio::{encode_wav, AudioData};
+
+        // One second ramp, so every frame's value gives away its position
+        let path = std::env::temp_dir().join("hermeneia_test_playback_seek.wav");
+        let audio = AudioData {
+            samples: (0..1000).map(|i| i as f32 / 1000.0).collect(),
+            sample_rate: 1000,
+            channels: 1,
+        };
+        encode_wav(&audio, &path).unwrap();
+
+        let mut decoder = PlaybackDecoder::new(open_audio_track(&path).unwrap(), format(1000, 1));
+        let (mut producer, mut consumer) = sample_ring(100);
+        assert!(!decoder.fill(&mut producer));
+        let mut out = [0.0f32; 100];
+        assert_eq!(consumer.pop(&mut out), 100);
+        assert_eq!(out[10], 0.01);
+
+        assert_eq!(decoder.seek(Timestamp::from_seconds(0.5)), 500);
+        consumer.clear();
+        decoder.fill(&mut producer);
+        consumer.pop(&mut out[..1]);
+        assert!((out[0] - 0.5).abs() < 1e-4, "resumed at {}", out[0]);
+
+        // Drain to the end
+        while !decoder.fill(&mut producer) {
+            consumer.clear();
+        }
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_decoder_resamples_for_the_device() {
+        use crate::audio::{encode_wav, AudioData};
+
+        // The same ramp, on a device running at twice the file's rate
+        let path = std::env::temp_dir().join("hermeneia_test_playback_resample.wav");
+        let audio = AudioData {
+            samples: (0..1000).map(|i| i as f32 / 1000.0).collect(),
+            sample_rate: 1000,
+            channels: 1,
+        };
+        encode_wav(&audio, &path).unwrap();
+
+        let mut decoder = PlaybackDecoder::new(open_audio_track(&path).unwrap(), format(2000, 1));
+        let (mut producer, mut consumer) = sample_ring(4000);
+        while !decoder.fill(&mut producer) {}
+        assert_eq!(consumer.len(), 2000);
+        let mut out = vec![0.0f32; 2000];
+        consumer.pop(&mut out);
+        assert!((out[1000] - 0.5).abs() < 1e-2, "middle is {}", out[1000]);
+
+        // After a seek the resampler starts over from the new position
+        decoder.seek(Timestamp::from_seconds(0.5));
+        while !decoder.fill(&mut producer) {}
+        assert_eq!(consumer.len(), 1000);
+        consumer.pop(&mut out[..1000]);
+        assert!((out[300] - 0.65).abs() < 1e-2, "resumed at {}", out[300]);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_position_is_in_file_frames_when_resampling() {
+        let (mut producer, consumer) = sample_ring(64);
+        let (sender, receiver) = command_queue(8);
+        let state = Arc::new(SharedPlaybackState::new(24000, 2, None));
+        let mut renderer =
+            OutputRenderer::new(consumer, receiver, state.clone(), format(48000, 2));
+
+        producer.push(&[0.1; 16]);
+        sender.send(PlaybackCommand::Play).unwrap();
+        let mut out = [0.0f32; 16];
+        renderer.render(&mut out);
+        // Eight device frames are four of the file's
+        assert_eq!(state.position_frames(), 4);
+    }
+
+    #[test]
+    fn test_mono_file_fills_both_device_channels() {
+        use crate::audio::{encode_wav, AudioData};
+
+        let path = std::env::temp_dir().join("hermeneia_test_playback_remix.wav");
+        let audio = AudioData {
+            samples: (0..100).map(|i| i as f32 / 100.0).collect(),
+            sample_rate: 1000,
+            channels: 1,
+        };
+        encode_wav(&audio, &path).unwrap();
+
+        let mut decoder = PlaybackDecoder::new(open_audio_track(&path).unwrap(), format(1000, 2));
+        let (mut producer, mut consumer) = sample_ring(400);
+        while !decoder.fill(&mut producer) {}
+        let mut out = vec![0.0f32; 200];
+        assert_eq!(consumer.pop(&mut out), 200);
+        assert_eq!(&out[20..24], &[0.1, 0.1, 0.11, 0.11]);
+        std::fs::remove_file(&path).ok();
+
+        // Position still counts the file's frames, not the device's samples
+        let (mut producer, consumer) = sample_ring(64);
+        let (sender, receiver) = command_queue(8);
+        let state = Arc::new(SharedPlaybackState::new(1000, 1, None));
+        let mut renderer =
+            OutputRenderer::new(consumer, receiver, state.clone(), format(1000, 2));
+        producer.push(&out[..8]);
+        sender.send(PlaybackCommand::Play).unwrap();
+        renderer.render(&mut [0.0f32; 8]);
+        assert_eq!(state.position_frames(), 4);
+    }
+
+    #[test]
+    fn test_updates_while_playing_and_on_change() {
+        let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = sent.clone();
+        let mut updates = Updates {
+            interval: Duration::from_secs(60),
+            listener: Box::new(move |snapshot: PlaybackSnapshot| {
+                log.lock().unwrap().push(snapshot.position_seconds)
+            }),
+            last: None,
+        };
+        let state = SharedPlaybackState::new(10, 1, None);
+
+        // The first goes straight out; a paused player then stays quiet
+        updates.offer(state.snapshot());
+        updates.offer(state.snapshot());
+        state.set_position_frames(5);
+        updates.offer(state.snapshot());
+        assert_eq!(*sent.lock().unwrap(), [0.0, 0.5]);
+
+        // Playing, it waits for the interval even though the position moves
+        state.set_state(PlayState::Playing);
+        state.set_position_frames(6);
+        updates.offer(state.snapshot());
+        assert_eq!(sent.lock().unwrap().len(), 2);
+        updates.interval = Duration::ZERO;
+        updates.offer(state.snapshot());
+        assert_eq!(*sent.lock().unwrap(), [0.0, 0.5, 0.6]);
+    }
+
+    #[test]
+    fn test_position_wraps_in_a_loop() {
+        let (mut producer, sender, state, mut renderer) = renderer(1);
+        state.set_loop_region(Some((100, 104)));
+        state.seek_pending.store(true, Ordering::Release);
+        sender.send(PlaybackCommand::Seek { frame: 102 }).unwrap();
+        sender.send(PlaybackCommand::Play).unwrap();
+        renderer.render(&mut [0.0f32; 0]);
+
+        // Two frames to the loop end, then back round from the start
+        producer.push(&[0.5; 7]);
+        renderer.render(&mut [0.0f32; 7]);
+        assert_eq!(state.position_frames(), 101);
+    }
+
+    #[test]
+    fn test_decoder_loops_over_region() {
+        use crate::audio::{encode_wav, AudioData};
+
+        let path = std::env::temp_dir().join("hermeneia_test_playback_loop.wav");
+        let audio = AudioData {
+            samples: (0..1000).map(|i| i as f32 / 1000.0).collect(),
+            sample_rate: 1000,
+            channels: 1,
+        };
+        encode_wav(&audio, &path).unwrap();
+
+        let mut decoder = PlaybackDecoder::new(open_audio_track(&path).unwrap(), format(1000, 1));
+        decoder.loop_region = Some((200, 300));
+        decoder.seek(Timestamp::from_seconds(0.25));
+        let (mut producer, mut consumer) = sample_ring(250);
+        assert!(!decoder.fill(&mut producer));
+        let mut out = [0.0f32; 250];
+        assert_eq!(consumer.pop(&mut out), 250);
+        assert_eq!((out[0], out[49]), (0.25, 0.299));
+        // Straight back to the loop start, twice over
+        assert_eq!((out[50], out[149], out[150]), (0.2, 0.299, 0.2));
+
+        // Past the loop end it plays on to the end of the file
+        decoder.seek(Timestamp::from_seconds(0.9));
+        assert!(decoder.fill(&mut producer));
+        assert_eq!(consumer.len(), 100);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_loop_past_the_end_wraps_at_the_real_end() {
+        use crate::audio::{encode_wav, AudioData};
+
+        let path = std::env::temp_dir().join("hermeneia_test_playback_loop_end.wav");
+        let audio = AudioData {
+            samples: (0..1000).map(|i| i as f32 / 1000.0).collect(),
+            sample_rate: 1000,
+            channels: 1,
+        };
+        encode_wav(&audio, &path).unwrap();
+
+        // As set when the length isn't known: the end is a guess past the file
+        let mut decoder = PlaybackDecoder::new(open_audio_track(&path).unwrap(), format(1000, 1));
+        decoder.loop_region = Some((900, 5000));
+        decoder.seek(Timestamp::from_seconds(0.95));
+        let (mut producer, mut consumer) = sample_ring(100);
+        assert!(!decoder.fill(&mut producer));
+        let mut out = [0.0f32; 100];
+        assert_eq!(consumer.pop(&mut out), 100);
+        assert_eq!((out[49], out[50]), (0.999, 0.9));
+        assert_eq!(decoder.loop_region, Some((900, 1000)));
+        std::fs::remove_file(&path).ok();
+
+        // With the end clamped, the reported position wraps there too
+        let (mut producer, sender, state, mut renderer) = renderer(1);
+        state.set_loop_region(decoder.loop_region);
+        state.seek_pending.store(true, Ordering::Release);
+        sender.send(PlaybackCommand::Seek { frame: 950 }).unwrap();
+        sender.send(PlaybackCommand::Play).unwrap();
+        renderer.render(&mut [0.0f32; 0]);
+        producer.push(&[0.5; 60]);
+        renderer.render(&mut [0.0f32; 60]);
+        assert_eq!(state.position_frames(), 910);
+    }
+}
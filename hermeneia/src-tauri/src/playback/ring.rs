@@ -0,0 +1,173 @@
+// src-tauri/src/playback/ring.rs
+
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Shared storage of a [`sample_ring`]
+///
+/// Samples are stored as `f32` bits in atomics so the producer and consumer
+/// can share the buffer without locks or `unsafe`. `read` and `write` count
+/// samples ever consumed and produced; their difference is the fill level.
+#[derive(Debug)]
+struct Ring {
+    samples: Box<[AtomicU32]>,
+    read: AtomicUsize,
+    write: AtomicUsize,
+}
+
+impl Ring {
+    fn capacity(&self) -> usize {
+        self.samples.len()
+    }
+
+    fn len(&self) -> usize {
+        self.write
+            .load(Ordering::Acquire)
+            .wrapping_sub(self.read.load(Ordering::Acquire))
+    }
+}
+
+/// Decoder side of a sample ring
+///
+/// Not `Clone`, and pushing takes `&mut self`, so there is only ever one
+/// writer.
+#[derive(Debug)]
+pub struct RingProducer {
+    ring: Arc<Ring>,
+}
+
+/// Audio callback side of a sample ring
+///
+/// Never locks or allocates, so it is safe to use on the real-time thread.
+/// Like [`RingProducer`] it has a single owner that reads through `&mut self`.
+#[derive(Debug)]
+pub struct RingConsumer {
+    ring: Arc<Ring>,
+}
+
+/// Single-producer, single-consumer ring of interleaved samples
+///
+/// `capacity` is in samples; see [`super::buffer_capacity`] for sizing it
+/// from the stream.
+pub fn sample_ring(capacity: usize) -> (RingProducer, RingConsumer) {
+    let ring = Arc::new(Ring {
+        samples: (0..capacity.max(1)).map(|_| AtomicU32::new(0)).collect(),
+        read: AtomicUsize::new(0),
+        write: AtomicUsize::new(0),
+    });
+    (RingProducer { ring: ring.clone() }, RingConsumer { ring })
+}
+
+impl RingProducer {
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity()
+    }
+
+    /// Samples that can be pushed without overwriting unread ones
+    pub fn free(&self) -> usize {
+        self.capacity() - self.ring.len()
+    }
+
+    /// Push as many of `samples` as fit; returns how many were pushed
+    pub fn push(&mut self, samples: &[f32]) -> usize {
+        let ring = &self.ring;
+        let write = ring.write.load(Ordering::Relaxed);
+        let count = samples.len().min(self.free());
+        for (i, sample) in samples[..count].iter().enumerate() {
+            let slot = &ring.samples[(write + i) % ring.capacity()];
+            slot.store(sample.to_bits(), Ordering::Relaxed);
+        }
+        ring.write.store(write.wrapping_add(count), Ordering::Release);
+        count
+    }
+}
+
+impl RingConsumer {
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity()
+    }
+
+    /// Samples ready to be read
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Fill `out` with as many buffered samples as are ready; returns how many
+    pub fn pop(&mut self, out: &mut [f32]) -> usize {
+        let ring = &self.ring;
+        let read = ring.read.load(Ordering::Relaxed);
+        let count = out.len().min(self.len());
+        for (i, sample) in out[..count].iter_mut().enumerate() {
+            let slot = &ring.samples[(read + i) % ring.capacity()];
+            *sample = f32::from_bits(slot.load(Ordering::Relaxed));
+        }
+        ring.read.store(read.wrapping_add(count), Ordering::Release);
+        count
+    }
+
+    /// Drop everything buffered, e.g. after a seek
+    pub fn clear(&mut self) {
+        let write = self.ring.write.load(Ordering::Acquire);
+        self.ring.read.store(write, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_pop_wraps_around() {
+        let (mut producer, mut consumer) = sample_ring(4);
+        assert_eq!(producer.push(&[1.0, 2.0, 3.0]), 3);
+
+        let mut out = [0.0; 2];
+        assert_eq!(consumer.pop(&mut out), 2);
+        assert_eq!(out, [1.0, 2.0]);
+
+        // Only three slots are free; the last sample doesn't fit
+        assert_eq!(producer.push(&[4.0, 5.0, 6.0, 7.0]), 3);
+        let mut out = [0.0; 8];
+        assert_eq!(consumer.pop(&mut out), 4);
+        assert_eq!(out[..4], [3.0, 4.0, 5.0, 6.0]);
+        assert!(consumer.is_empty());
+    }
+
+    #[test]
+    fn test_clear_drops_buffered_samples() {
+        let (mut producer, mut consumer) = sample_ring(8);
+        producer.push(&[0.5; 6]);
+        consumer.clear();
+        assert!(consumer.is_empty());
+        assert_eq!(producer.free(), 8);
+    }
+
+    #[test]
+    fn test_threads_see_samples_in_order() {
+        let (mut producer, mut consumer) = sample_ring(64);
+        let total = 10_000;
+
+        let writer = std::thread::spawn(move || {
+            let mut next = 0;
+            while next < total {
+                let chunk: Vec<f32> = (next..(next + 37).min(total)).map(|n| n as f32).collect();
+                next += producer.push(&chunk);
+            }
+        });
+
+        let mut expected = 0;
+        let mut out = [0.0; 16];
+        while expected < total {
+            let n = consumer.pop(&mut out);
+            for &sample in &out[..n] {
+                assert_eq!(sample, expected as f32);
+                expected += 1;
+            }
+        }
+        writer.join().unwrap();
+    }
+}
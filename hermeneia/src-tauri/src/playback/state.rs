@@ -0,0 +1,181 @@
+// src-tauri/src/playback/state.rs
+
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicU8, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// What the player is doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayState {
+    Playing,
+    Paused,
+    /// Played through to the end of the file
+    Ended,
+}
+
+impl PlayState {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Playing,
+            2 => Self::Ended,
+            _ => Self::Paused,
+        }
+    }
+
+    fn as_u8(self) -> u8 {
+        match self {
+            Self::Playing => 0,
+            Self::Paused => 1,
+            Self::Ended => 2,
+        }
+    }
+}
+
+/// Playback state written by the audio callback and read by everyone else
+///
+/// Plain atomics with acquire/release ordering: the callback only ever
+/// stores, readers take a [`PlaybackSnapshot`]. The stream format is fixed
+/// when the player opens the file.
+#[derive(Debug)]
+pub struct SharedPlaybackState {
+    sample_rate: u32,
+    channels: u16,
+    duration_frames: Option<u64>,
+    state: AtomicU8,
+    position_frames: AtomicU64,
+    volume: AtomicU32,
+    /// A-B loop in frames, `loop_end` 0 when not looping
+    loop_start: AtomicU64,
+    loop_end: AtomicU64,
+    /// The decoder has pushed the last packet of the file
+    pub(crate) decoder_finished: AtomicBool,
+    /// A seek was sent to the callback and it hasn't flushed the ring yet
+    pub(crate) seek_pending: AtomicBool,
+}
+
+/// Copy of the playback state at one moment, for the frontend
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlaybackSnapshot {
+    pub state: PlayState,
+    pub position_seconds: f64,
+    /// `None` when the container doesn't report a length
+    pub duration_seconds: Option<f64>,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub volume: f32,
+    /// Start and end of the A-B loop; `None` when not looping
+    pub loop_seconds: Option<(f64, f64)>,
+}
+
+impl SharedPlaybackState {
+    pub fn new(sample_rate: u32, channels: u16, duration_frames: Option<u64>) -> Self {
+        Self {
+            sample_rate,
+            channels,
+            duration_frames,
+            state: AtomicU8::new(PlayState::Paused.as_u8()),
+            position_frames: AtomicU64::new(0),
+            volume: AtomicU32::new(1.0f32.to_bits()),
+            loop_start: AtomicU64::new(0),
+            loop_end: AtomicU64::new(0),
+            decoder_finished: AtomicBool::new(false),
+            seek_pending: AtomicBool::new(false),
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    /// Length of the file; `None` when the container doesn't report it
+    pub fn duration_frames(&self) -> Option<u64> {
+        self.duration_frames
+    }
+
+    pub fn state(&self) -> PlayState {
+        PlayState::from_u8(self.state.load(Ordering::Acquire))
+    }
+
+    pub(crate) fn set_state(&self, state: PlayState) {
+        self.state.store(state.as_u8(), Ordering::Release);
+    }
+
+    pub fn position_frames(&self) -> u64 {
+        self.position_frames.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn set_position_frames(&self, frames: u64) {
+        self.position_frames.store(frames, Ordering::Release);
+    }
+
+    pub(crate) fn set_volume(&self, volume: f32) {
+        self.volume.store(volume.to_bits(), Ordering::Release);
+    }
+
+    /// Start and end frames of the A-B loop, if there is one
+    pub fn loop_region(&self) -> Option<(u64, u64)> {
+        let end = self.loop_end.load(Ordering::Acquire);
+        let start = self.loop_start.load(Ordering::Acquire);
+        (start < end).then_some((start, end))
+    }
+
+    /// Set by the playback thread; the callback reads it to wrap the
+    /// position it reports
+    pub(crate) fn set_loop_region(&self, region: Option<(u64, u64)>) {
+        self.loop_end.store(0, Ordering::Release);
+        if let Some((start, end)) = region {
+            self.loop_start.store(start, Ordering::Release);
+            self.loop_end.store(end, Ordering::Release);
+        }
+    }
+
+    pub fn snapshot(&self) -> PlaybackSnapshot {
+        let rate = self.sample_rate.max(1) as f64;
+        PlaybackSnapshot {
+            state: self.state(),
+            position_seconds: self.position_frames() as f64 / rate,
+            duration_seconds: self.duration_frames.map(|frames| frames as f64 / rate),
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            volume: f32::from_bits(self.volume.load(Ordering::Acquire)),
+            loop_seconds: self
+                .loop_region()
+                .map(|(start, end)| (start as f64 / rate, end as f64 / rate)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_reports_seconds() {
+        let state = SharedPlaybackState::new(16000, 1, Some(160000));
+        state.set_position_frames(24000);
+        state.set_state(PlayState::Playing);
+        state.set_volume(0.5);
+
+        let snapshot = state.snapshot();
+        assert_eq!(snapshot.state, PlayState::Playing);
+        assert_eq!(snapshot.position_seconds, 1.5);
+        assert_eq!(snapshot.duration_seconds, Some(10.0));
+        assert_eq!(snapshot.volume, 0.5);
+        assert_eq!(snapshot.loop_seconds, None);
+    }
+
+    #[test]
+    fn test_loop_region() {
+        let state = SharedPlaybackState::new(1000, 1, None);
+        state.set_loop_region(Some((500, 2000)));
+        assert_eq!(state.loop_region(), Some((500, 2000)));
+        assert_eq!(state.snapshot().loop_seconds, Some((0.5, 2.0)));
+        state.set_loop_region(None);
+        assert_eq!(state.loop_region(), None);
+    }
+}
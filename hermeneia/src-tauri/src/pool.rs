@@ -0,0 +1,244 @@
+// src-tauri/src/pool.rs
+// Bounded worker pool shared by batch waveform, analysis and conversion work
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+
+use tracing::warn;
+
+use crate::power;
+use crate::settings::Settings;
+
+/// Runs tasks on at most `limit` threads at a time
+///
+/// Clones share the same limit, so several batches started from the app
+/// together never exceed it. Free slots are handed out first come, first
+/// served: a large batch can't starve a small one started after it, since
+/// each task queues for its slot behind whatever was already waiting.
+#[derive(Debug, Clone)]
+pub struct WorkerPool {
+    slots: Arc<Slots>,
+}
+
+#[derive(Debug)]
+struct Slots {
+    limit: usize,
+    state: Mutex<SlotState>,
+    freed: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct SlotState {
+    busy: usize,
+    next_ticket: u64,
+    /// Tickets of tasks waiting for a slot, oldest first
+    waiting: VecDeque<u64>,
+}
+
+/// Holds one slot until dropped
+struct SlotGuard<'a>(&'a Slots);
+
+impl Drop for SlotGuard<'_> {
+    fn drop(&mut self) {
+        let mut state = self.0.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.busy -= 1;
+        self.0.freed.notify_all();
+    }
+}
+
+impl Slots {
+    fn acquire(&self) -> SlotGuard<'_> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let ticket = state.next_ticket;
+        state.next_ticket += 1;
+        state.waiting.push_back(ticket);
+
+        while state.busy >= self.limit || state.waiting.front() != Some(&ticket) {
+            state = self.freed.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+        state.waiting.pop_front();
+        state.busy += 1;
+        // The next ticket in line may fit into a slot that is still free
+        self.freed.notify_all();
+
+        SlotGuard(self)
+    }
+}
+
+impl WorkerPool {
+    /// Pool running at most `limit` tasks at once (at least 1)
+    pub fn new(limit: usize) -> Self {
+        Self {
+            slots: Arc::new(Slots {
+                limit: limit.max(1),
+                state: Mutex::new(SlotState::default()),
+                freed: Condvar::new(),
+            }),
+        }
+    }
+
+    /// The app-wide pool, sized by [`worker_limit`] on first use
+    pub fn global() -> &'static WorkerPool {
+        static POOL: OnceLock<WorkerPool> = OnceLock::new();
+        POOL.get_or_init(|| WorkerPool::new(worker_limit(None)))
+    }
+
+    pub fn limit(&self) -> usize {
+        self.slots.limit
+    }
+
+    /// Run `task` over every item and return the results in item order
+    ///
+    /// Items are started in order as slots free up. Runs inline on the
+    /// calling thread when only one task could run at a time anyway. Tasks
+    /// must not call `map` on the same pool; they would wait for slots their
+    /// own batch holds.
+    pub fn map<T, R, F>(&self, items: &[T], task: F) -> Vec<R>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(&T) -> R + Sync,
+    {
+        let workers = self.limit().min(items.len());
+        if workers <= 1 {
+            return items
+                .iter()
+                .map(|item| {
+                    let _slot = self.slots.acquire();
+                    task(item)
+                })
+                .collect();
+        }
+
+        let next = AtomicUsize::new(0);
+        let results: Mutex<Vec<Option<R>>> = Mutex::new((0..items.len()).map(|_| None).collect());
+
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    let _slot = self.slots.acquire();
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    if i >= items.len() {
+                        break;
+                    }
+                    let result = task(&items[i]);
+                    results.lock().unwrap_or_else(|e| e.into_inner())[i] = Some(result);
+                });
+            }
+        });
+
+        results
+            .into_inner()
+            .unwrap_or_else(|e| e.into_inner())
+            .into_iter()
+            .map(|r| r.expect("every item is processed"))
+            .collect()
+    }
+}
+
+/// Number of CPU cores, or 1 if the platform won't say
+pub fn cpu_count() -> usize {
+    std::thread::available_parallelism().map_or(1, usize::from)
+}
+
+/// Decide how many files to process at once
+///
+/// Starts from `requested` (e.g. `--jobs`), else the `max_jobs` setting,
+/// else one per CPU core, then scales down on battery power or under
+/// thermal pressure per the power mode setting.
+pub fn worker_limit(requested: Option<usize>) -> usize {
+    let settings = Settings::load();
+    let requested = requested
+        .or(settings.max_jobs)
+        .unwrap_or_else(cpu_count)
+        .max(1);
+    if requested == 1 {
+        return 1;
+    }
+
+    let throttle = power::throttle_jobs(requested, &power::query_power_state(), settings.power);
+    if let Some(reason) = throttle.reason {
+        warn!(
+            "Using {} of {} parallel jobs: {}",
+            throttle.jobs,
+            requested,
+            reason.describe()
+        );
+    }
+    throttle.jobs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_map_preserves_order() {
+        let items: Vec<u64> = (0..50).collect();
+        let results = WorkerPool::new(4).map(&items, |&n| {
+            std::thread::sleep(Duration::from_millis(50 - n));
+            n * 2
+        });
+        assert_eq!(results, items.iter().map(|n| n * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_limit_is_shared_between_batches() {
+        let pool = WorkerPool::new(3);
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let task = |_: &u32| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(5));
+            running.fetch_sub(1, Ordering::SeqCst);
+        };
+
+        let items = [0u32; 12];
+        std::thread::scope(|scope| {
+            scope.spawn(|| pool.map(&items, task));
+            scope.spawn(|| pool.clone().map(&items, task));
+        });
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+    }
+
+    #[test]
+    fn test_small_batch_is_not_starved() {
+        let pool = WorkerPool::new(2);
+        let big = [Duration::from_millis(20); 20];
+        let small_done_at = Mutex::new(None);
+        let big_done = AtomicUsize::new(0);
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                pool.map(&big, |d| {
+                    std::thread::sleep(*d);
+                    big_done.fetch_add(1, Ordering::SeqCst);
+                })
+            });
+            std::thread::sleep(Duration::from_millis(30));
+            scope.spawn(|| {
+                pool.map(&[()], |_| {
+                    *small_done_at.lock().unwrap() = Some(big_done.load(Ordering::SeqCst));
+                })
+            });
+        });
+
+        // The small batch queued behind a couple of big items, not all of them
+        let done_before = small_done_at.into_inner().unwrap().unwrap();
+        assert!(
+            done_before < big.len() / 2,
+            "waited for {done_before} items"
+        );
+    }
+
+    #[test]
+    fn test_empty_and_single() {
+        let pool = WorkerPool::new(8);
+        assert!(pool.map(&[] as &[u8], |_| ()).is_empty());
+        assert_eq!(pool.map(&[7], |n| n + 1), [8]);
+        assert_eq!(WorkerPool::new(0).limit(), 1);
+    }
+}
@@ -0,0 +1,468 @@
+// src-tauri/src/power.rs
+// Power source detection and battery-aware throttling of batch work
+
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use crate::gpu::run_command;
+
+/// Battery charge at or below which heavy work is deferred
+pub const LOW_BATTERY_PERCENT: u8 = 20;
+
+/// Where Linux lists AC adapters and batteries
+#[cfg(target_os = "linux")]
+const POWER_SUPPLY_ROOT: &str = "/sys/class/power_supply";
+
+/// What the machine is running on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerSource {
+    Ac,
+    Battery,
+    /// No battery found or the platform can't tell (e.g. most desktops)
+    #[default]
+    Unknown,
+}
+
+/// Snapshot of the machine's power situation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct PowerState {
+    pub source: PowerSource,
+    /// Charge of the main battery, when there is one
+    pub battery_percent: Option<u8>,
+    /// The OS is limiting CPU speed to keep temperatures down (macOS only)
+    pub thermal_pressure: bool,
+}
+
+/// How batch work reacts to the power state; stored in the app settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerMode {
+    /// Throttle on battery or under thermal pressure
+    #[default]
+    Auto,
+    /// Never throttle
+    Performance,
+    /// Always throttle, even on AC power
+    PowerSaver,
+}
+
+/// Why batch work is running slower than requested
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThrottleReason {
+    OnBattery,
+    LowBattery,
+    ThermalPressure,
+    PowerSaver,
+}
+
+impl ThrottleReason {
+    /// Short explanation for logs and the UI
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::OnBattery => "running on battery power",
+            Self::LowBattery => "battery is low",
+            Self::ThermalPressure => "the system is limiting CPU speed to cool down",
+            Self::PowerSaver => "power saver mode is on",
+        }
+    }
+}
+
+/// Outcome of [`throttle_jobs`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Throttle {
+    /// Worker count to actually use (at least 1)
+    pub jobs: usize,
+    /// Heavy work (long batches, inference) should wait for better conditions
+    pub defer_heavy: bool,
+    /// `None` when running at full speed
+    pub reason: Option<ThrottleReason>,
+}
+
+/// Power state plus its effect on scheduling; the payload of [`POWER_STATUS_EVENT`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PowerStatus {
+    pub state: PowerState,
+    pub reason: Option<ThrottleReason>,
+    pub defer_heavy: bool,
+}
+
+/// Event the app emits whenever [`PowerStatus`] changes
+pub const POWER_STATUS_EVENT: &str = "power-status";
+
+/// Decide whether work should slow down, and why
+pub fn throttle_reason(state: &PowerState, mode: PowerMode) -> Option<ThrottleReason> {
+    match mode {
+        PowerMode::Performance => return None,
+        PowerMode::Auto | PowerMode::PowerSaver => {}
+    }
+
+    let on_battery = state.source == PowerSource::Battery;
+    if on_battery
+        && state
+            .battery_percent
+            .is_some_and(|p| p <= LOW_BATTERY_PERCENT)
+    {
+        Some(ThrottleReason::LowBattery)
+    } else if state.thermal_pressure {
+        Some(ThrottleReason::ThermalPressure)
+    } else if on_battery {
+        Some(ThrottleReason::OnBattery)
+    } else if mode == PowerMode::PowerSaver {
+        Some(ThrottleReason::PowerSaver)
+    } else {
+        None
+    }
+}
+
+/// Scale a requested worker count to the power state
+///
+/// Throttling halves the workers; a low battery drops to one worker and
+/// asks for heavy work to be deferred.
+pub fn throttle_jobs(requested: usize, state: &PowerState, mode: PowerMode) -> Throttle {
+    let requested = requested.max(1);
+    let reason = throttle_reason(state, mode);
+    let jobs = match reason {
+        None => requested,
+        Some(ThrottleReason::LowBattery) => 1,
+        Some(_) => (requested / 2).max(1),
+    };
+    Throttle {
+        jobs,
+        defer_heavy: reason == Some(ThrottleReason::LowBattery),
+        reason,
+    }
+}
+
+impl PowerStatus {
+    pub fn new(state: PowerState, mode: PowerMode) -> Self {
+        let throttle = throttle_jobs(1, &state, mode);
+        Self {
+            state,
+            reason: throttle.reason,
+            defer_heavy: throttle.defer_heavy,
+        }
+    }
+}
+
+/// Query the current power state
+///
+/// Reads sysfs on Linux, `pmset` on macOS and WMI on Windows. Anything that
+/// can't be determined is reported as [`PowerSource::Unknown`], which never
+/// throttles.
+pub fn query_power_state() -> PowerState {
+    #[cfg(target_os = "linux")]
+    let state = read_power_supply(Path::new(POWER_SUPPLY_ROOT));
+
+    #[cfg(target_os = "macos")]
+    let state = {
+        let mut state = run_command("pmset", &["-g", "batt"])
+            .map(|s| parse_pmset_batt(&s))
+            .unwrap_or_default();
+        state.thermal_pressure =
+            run_command("pmset", &["-g", "therm"]).is_some_and(|s| parse_pmset_therm(&s));
+        state
+    };
+
+    #[cfg(target_os = "windows")]
+    let state = run_command(
+        "powershell",
+        &[
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "Get-CimInstance Win32_Battery | Select-Object BatteryStatus,EstimatedChargeRemaining | ConvertTo-Json -Compress",
+        ],
+    )
+    .map(|s| parse_win32_battery(&s))
+    .unwrap_or_default();
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    let state = PowerState::default();
+
+    debug!(?state, "Power state queried");
+    state
+}
+
+/// Poll the power state and call `on_change` whenever the resulting status changes
+///
+/// Runs forever; start it on its own thread. `mode` is asked again on every
+/// poll so a changed setting takes effect without a restart. The first
+/// status is always reported.
+pub fn watch_power<M, F>(interval: Duration, mode: M, mut on_change: F)
+where
+    M: Fn() -> PowerMode,
+    F: FnMut(&PowerStatus),
+{
+    let mut last = None;
+    loop {
+        let status = PowerStatus::new(query_power_state(), mode());
+        if last != Some(status) {
+            on_change(&status);
+            last = Some(status);
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+/// Read a `/sys/class/power_supply`-style directory
+///
+/// Each supply has a `type` (`Mains`, `USB`, `Battery`); batteries report
+/// `status` and `capacity`, adapters `online`. Peripheral batteries (mice,
+/// headsets) carry `scope=Device` and are ignored.
+pub fn read_power_supply(root: &Path) -> PowerState {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return PowerState::default();
+    };
+
+    let mut external_online = false;
+    let mut found_supply = false;
+    let mut discharging = false;
+    let mut battery_percent = None;
+
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        let read = |file: &str| {
+            std::fs::read_to_string(dir.join(file))
+                .map(|v| v.trim().to_string())
+                .unwrap_or_default()
+        };
+        if read("scope") == "Device" {
+            continue;
+        }
+
+        match read("type").as_str() {
+            "Mains" | "USB" => {
+                found_supply = true;
+                external_online |= read("online") == "1";
+            }
+            "Battery" => {
+                found_supply = true;
+                discharging |= read("status") == "Discharging";
+                if battery_percent.is_none() {
+                    battery_percent = read("capacity").parse::<u8>().ok();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let source = if discharging && !external_online {
+        PowerSource::Battery
+    } else if found_supply {
+        PowerSource::Ac
+    } else {
+        PowerSource::Unknown
+    };
+    PowerState {
+        source,
+        battery_percent,
+        thermal_pressure: false,
+    }
+}
+
+/// Parse `pmset -g batt`
+///
+/// ```text
+/// Now drawing from 'Battery Power'
+///  -InternalBattery-0 (id=4653155)  72%; discharging; 5:12 remaining present: true
+/// ```
+pub fn parse_pmset_batt(output: &str) -> PowerState {
+    let source = if output.contains("'Battery Power'") {
+        PowerSource::Battery
+    } else if output.contains("'AC Power'") {
+        PowerSource::Ac
+    } else {
+        PowerSource::Unknown
+    };
+    let battery_percent = output
+        .split_whitespace()
+        .find_map(|word| word.strip_suffix("%;"))
+        .and_then(|p| p.parse().ok());
+
+    PowerState {
+        source,
+        battery_percent,
+        thermal_pressure: false,
+    }
+}
+
+/// Parse `pmset -g therm`; a `CPU_Speed_Limit` below 100 means thermal pressure
+pub fn parse_pmset_therm(output: &str) -> bool {
+    output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("CPU_Speed_Limit"))
+        .filter_map(|rest| {
+            rest.trim()
+                .trim_start_matches('=')
+                .trim()
+                .parse::<u32>()
+                .ok()
+        })
+        .any(|limit| limit < 100)
+}
+
+/// Parse `Win32_Battery` rows as JSON; `BatteryStatus` 1 means discharging
+pub fn parse_win32_battery(output: &str) -> PowerState {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Battery {
+        battery_status: Option<u16>,
+        estimated_charge_remaining: Option<u8>,
+    }
+
+    // One battery comes out as a bare object, several as an array
+    let batteries = match serde_json::from_str::<Vec<Battery>>(output) {
+        Ok(batteries) => batteries,
+        Err(_) => match serde_json::from_str::<Battery>(output) {
+            Ok(battery) => vec![battery],
+            Err(_) => return PowerState::default(),
+        },
+    };
+    let Some(battery) = batteries.first() else {
+        return PowerState::default();
+    };
+
+    PowerState {
+        source: match battery.battery_status {
+            Some(1) => PowerSource::Battery,
+            Some(_) => PowerSource::Ac,
+            None => PowerSource::Unknown,
+        },
+        battery_percent: battery.estimated_charge_remaining,
+        thermal_pressure: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn on_battery(percent: u8) -> PowerState {
+        PowerState {
+            source: PowerSource::Battery,
+            battery_percent: Some(percent),
+            thermal_pressure: false,
+        }
+    }
+
+    #[test]
+    fn test_throttle_jobs() {
+        let ac = PowerState {
+            source: PowerSource::Ac,
+            ..Default::default()
+        };
+        assert_eq!(throttle_jobs(8, &ac, PowerMode::Auto).jobs, 8);
+        assert_eq!(throttle_jobs(8, &ac, PowerMode::PowerSaver).jobs, 4);
+
+        let throttle = throttle_jobs(8, &on_battery(80), PowerMode::Auto);
+        assert_eq!(throttle.jobs, 4);
+        assert_eq!(throttle.reason, Some(ThrottleReason::OnBattery));
+        assert!(!throttle.defer_heavy);
+
+        let throttle = throttle_jobs(8, &on_battery(15), PowerMode::Auto);
+        assert_eq!(throttle.jobs, 1);
+        assert_eq!(throttle.reason, Some(ThrottleReason::LowBattery));
+        assert!(throttle.defer_heavy);
+
+        // The setting wins over the battery
+        assert_eq!(
+            throttle_jobs(8, &on_battery(15), PowerMode::Performance).jobs,
+            8
+        );
+        assert_eq!(throttle_jobs(1, &on_battery(80), PowerMode::Auto).jobs, 1);
+    }
+
+    #[test]
+    fn test_thermal_pressure_throttles_on_ac() {
+        let hot = PowerState {
+            source: PowerSource::Ac,
+            battery_percent: None,
+            thermal_pressure: true,
+        };
+        let throttle = throttle_jobs(4, &hot, PowerMode::Auto);
+        assert_eq!(throttle.jobs, 2);
+        assert_eq!(throttle.reason, Some(ThrottleReason::ThermalPressure));
+    }
+
+    #[test]
+    fn test_read_power_supply() {
+        let root = std::env::temp_dir().join("hermeneia_test_power_supply");
+        let _ = fs::remove_dir_all(&root);
+        let supply = |name: &str, files: &[(&str, &str)]| {
+            let dir = root.join(name);
+            fs::create_dir_all(&dir).unwrap();
+            for (file, value) in files {
+                fs::write(dir.join(file), format!("{value}\n")).unwrap();
+            }
+        };
+        supply("AC", &[("type", "Mains"), ("online", "0")]);
+        supply(
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("status", "Discharging"),
+                ("capacity", "64"),
+            ],
+        );
+        supply(
+            "hidpp_battery_0",
+            &[
+                ("type", "Battery"),
+                ("scope", "Device"),
+                ("status", "Discharging"),
+                ("capacity", "5"),
+            ],
+        );
+        assert_eq!(read_power_supply(&root), on_battery(64));
+
+        // Plugging in flips the source but keeps the charge level
+        supply("AC", &[("type", "Mains"), ("online", "1")]);
+        supply(
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("status", "Charging"),
+                ("capacity", "64"),
+            ],
+        );
+        let state = read_power_supply(&root);
+        assert_eq!(state.source, PowerSource::Ac);
+        assert_eq!(state.battery_percent, Some(64));
+
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(read_power_supply(&root), PowerState::default());
+    }
+
+    #[test]
+    fn test_parse_pmset() {
+        let batt = "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=4653155)\t72%; discharging; 5:12 remaining present: true\n";
+        assert_eq!(parse_pmset_batt(batt), on_battery(72));
+
+        let ac = "Now drawing from 'AC Power'\n -InternalBattery-0 (id=4653155)\t100%; charged; 0:00 remaining present: true\n";
+        assert_eq!(parse_pmset_batt(ac).source, PowerSource::Ac);
+
+        assert!(parse_pmset_therm("CPU Power notify\n\tCPU_Scheduler_Limit \t= 100\n\tCPU_Available_CPUs \t= 8\n\tCPU_Speed_Limit \t= 70\n"));
+        assert!(!parse_pmset_therm(
+            "Note: No thermal warning level has been recorded\n"
+        ));
+    }
+
+    #[test]
+    fn test_parse_win32_battery() {
+        let state = parse_win32_battery(r#"{"BatteryStatus":1,"EstimatedChargeRemaining":42}"#);
+        assert_eq!(state, on_battery(42));
+
+        let state = parse_win32_battery(r#"[{"BatteryStatus":2,"EstimatedChargeRemaining":99}]"#);
+        assert_eq!(state.source, PowerSource::Ac);
+
+        // Desktops print nothing at all
+        assert_eq!(parse_win32_battery(""), PowerState::default());
+    }
+}
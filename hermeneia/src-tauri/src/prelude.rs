@@ -0,0 +1,38 @@
+// src-tauri/src/prelude.rs
+// The stable API in one import: `use hermeneia_lib::prelude::*;`
+
+//! Everything most users of the audio engine need
+//!
+//! ```
+//! use hermeneia_lib::prelude::*;
+//!
+//! # fn main() -> hermeneia_lib::prelude::Result<()> {
+//! let audio = AudioData::silence(2.0, 44100, 2)?;
+//! let clip = trim_audio(&audio, &TrimParams::last(0.5)?)?;
+//! assert_eq!(clip.frame_count(), 22050);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The two `Segment` types ([`crate::audio::split::Segment`] and
+//! [`crate::transcribe::Segment`]) are left out so a glob import never makes
+//! the name ambiguous; import whichever one you need by path.
+
+pub use crate::audio::analysis::{
+    compare_audio, detect_clipping, detect_silence, measure_loudness, ClippingOptions,
+    ClippingReport, CompareOptions, ComparisonReport, LoudnessMeasurement, SilenceOptions,
+    SilenceRegion,
+};
+pub use crate::audio::pipeline::{
+    FileSource, MemorySink, MemorySource, Pipeline, Sink, Source, StreamSpec, Transform,
+};
+pub use crate::audio::{
+    apply_gain, decode_audio_file, decode_audio_range, encode_audio, extract_waveform_peaks,
+    get_audio_info, remix_channels, resample_audio, trim_audio, AudioData, AudioDuration,
+    AudioInfo, OutputFormat, PlanarAudio, Timestamp, TrimParams, TrimPoint, WavSampleFormat,
+    WaveformOptions, WaveformPeaks,
+};
+pub use crate::error::{AnalysisError, AudioError, DecodeError, EncodeError, PlaybackError, Result};
+pub use crate::playback::{AudioPlayer, PlayState, PlaybackSnapshot};
+pub use crate::progress::{NoProgress, ProgressSink};
+pub use crate::transcribe::{transcribe_file, ChunkPlan, Transcriber, Transcript};
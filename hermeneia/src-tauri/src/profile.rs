@@ -0,0 +1,217 @@
+// src-tauri/src/profile.rs
+// Stage timings per operation, for diagnosing slow files
+
+use std::cell::RefCell;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use tracing::span::EnteredSpan;
+
+thread_local! {
+    /// Operation being recorded on this thread
+    static CURRENT: RefCell<Option<OperationProfile>> = const { RefCell::new(None) };
+}
+
+/// Most recently finished operation in this process
+static LAST_PROFILE: Mutex<Option<OperationProfile>> = Mutex::new(None);
+
+/// Time spent in one stage of an operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageTiming {
+    /// Stage name, e.g. "probe", "decode", "analyze", "encode"
+    pub name: String,
+    pub seconds: f64,
+}
+
+/// Stage-by-stage timings of one operation on one file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperationProfile {
+    /// What ran, e.g. "waveform_peaks" or "convert"
+    pub operation: String,
+    pub file: String,
+    /// Stages in the order they started (nested stages included)
+    pub stages: Vec<StageTiming>,
+    pub total_seconds: f64,
+}
+
+impl OperationProfile {
+    /// Total time spent in stages called `name`
+    pub fn stage_seconds(&self, name: &str) -> f64 {
+        self.stages
+            .iter()
+            .filter(|s| s.name == name)
+            .map(|s| s.seconds)
+            .sum()
+    }
+}
+
+/// Records the stages of one operation until dropped
+///
+/// Stages started on this thread while the operation is alive are added to
+/// its profile, which becomes [`last_profile`] when the operation ends (even
+/// if it failed). Starting an operation inside another one records into the
+/// inner operation and restores the outer one afterwards.
+pub struct Operation {
+    started: Instant,
+    outer: Option<OperationProfile>,
+    ended: bool,
+    _span: EnteredSpan,
+}
+
+impl Operation {
+    pub fn start(operation: &str, file: impl AsRef<Path>) -> Self {
+        let file = file.as_ref().display().to_string();
+        let span = tracing::debug_span!("operation", name = operation, file = %file).entered();
+        let profile = OperationProfile {
+            operation: operation.to_string(),
+            file,
+            stages: Vec::new(),
+            total_seconds: 0.0,
+        };
+
+        Self {
+            started: Instant::now(),
+            outer: CURRENT.with(|c| c.borrow_mut().replace(profile)),
+            ended: false,
+            _span: span,
+        }
+    }
+
+    /// End the operation and return its profile
+    pub fn finish(mut self) -> Option<OperationProfile> {
+        self.end()
+    }
+
+    fn end(&mut self) -> Option<OperationProfile> {
+        if std::mem::replace(&mut self.ended, true) {
+            return None;
+        }
+        let mut profile = CURRENT.with(|c| c.replace(self.outer.take()))?;
+        profile.total_seconds = self.started.elapsed().as_secs_f64();
+
+        debug!(
+            operation = %profile.operation,
+            file = %profile.file,
+            total_sec = profile.total_seconds,
+            stages = %profile
+                .stages
+                .iter()
+                .map(|s| format!("{}={:.3}s", s.name, s.seconds))
+                .collect::<Vec<_>>()
+                .join(" "),
+            "Operation profile"
+        );
+
+        *LAST_PROFILE.lock().unwrap_or_else(|e| e.into_inner()) = Some(profile.clone());
+        Some(profile)
+    }
+}
+
+impl Drop for Operation {
+    fn drop(&mut self) {
+        self.end();
+    }
+}
+
+/// Times one stage until dropped
+pub struct Stage {
+    started: Instant,
+    /// Slot in the current operation's stage list, if one is being recorded
+    index: Option<usize>,
+    _span: EnteredSpan,
+}
+
+/// Start timing a stage of the current operation
+///
+/// Always opens a tracing span; the timing is only kept when an
+/// [`Operation`] is being recorded on this thread.
+pub fn stage(name: &'static str) -> Stage {
+    let span = tracing::debug_span!("stage", name).entered();
+    let index = CURRENT.with(|c| {
+        c.borrow_mut().as_mut().map(|profile| {
+            profile.stages.push(StageTiming {
+                name: name.to_string(),
+                seconds: 0.0,
+            });
+            profile.stages.len() - 1
+        })
+    });
+
+    Stage {
+        started: Instant::now(),
+        index,
+        _span: span,
+    }
+}
+
+impl Drop for Stage {
+    fn drop(&mut self) {
+        let Some(index) = self.index else {
+            return;
+        };
+        let seconds = self.started.elapsed().as_secs_f64();
+        CURRENT.with(|c| {
+            if let Some(stage) = c.borrow_mut().as_mut().and_then(|p| p.stages.get_mut(index)) {
+                stage.seconds = seconds;
+            }
+        });
+    }
+}
+
+/// Profile of the most recently finished operation
+pub fn last_profile() -> Option<OperationProfile> {
+    LAST_PROFILE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_records_stages_in_order() {
+        let operation = Operation::start("test_op", "song.mp3");
+        {
+            let _probe = stage("probe");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        for _ in 0..2 {
+            let _decode = stage("decode");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        let profile = operation.finish().unwrap();
+
+        assert_eq!(profile.operation, "test_op");
+        assert_eq!(profile.file, "song.mp3");
+        let names: Vec<_> = profile.stages.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["probe", "decode", "decode"]);
+        assert!(profile.stage_seconds("decode") >= 0.01);
+        assert!(profile.total_seconds >= profile.stage_seconds("probe") + 0.01);
+    }
+
+    #[test]
+    fn test_nested_operation_restores_outer() {
+        let outer = Operation::start("outer", "a.wav");
+        let first = stage("decode");
+        Operation::start("inner", "b.wav").finish();
+        drop(first);
+        drop(stage("encode"));
+
+        let profile = outer.finish().unwrap();
+        let names: Vec<_> = profile.stages.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(profile.operation, "outer");
+        assert_eq!(names, ["decode", "encode"]);
+    }
+
+    #[test]
+    fn test_stage_without_operation_is_harmless() {
+        let stage = stage("decode");
+        assert_eq!(stage.index, None);
+    }
+}
@@ -0,0 +1,76 @@
+// src-tauri/src/progress.rs
+// Progress reporting and cancellation for long-running operations
+
+use crate::error::{AudioError, Result};
+use crate::gpu::GpuFallback;
+
+/// Receives progress from a long-running operation and can ask it to stop
+///
+/// Every `*_with_progress` function takes one. Operations announce each
+/// [`stage`](ProgressSink::stage) they enter (using the same names as
+/// [`crate::profile::stage`]), report the fraction of that stage done, and
+/// poll [`is_cancelled`](ProgressSink::is_cancelled) between chunks of work,
+/// failing with [`AudioError::Cancelled`] once it returns `true`.
+///
+/// Any `FnMut(f64)` closure is a sink that only takes fractions, so
+/// `&mut |fraction: f64| ...` can be passed directly.
+pub trait ProgressSink {
+    /// A new stage started (e.g. "decode", "encode"); its progress starts at 0
+    fn stage(&mut self, _name: &'static str) {}
+
+    /// Fraction of the current stage done, from 0.0 to 1.0
+    fn progress(&mut self, fraction: f64);
+
+    /// Free-form status worth showing the user (e.g. "window 3 of 12")
+    fn message(&mut self, _message: &str) {}
+
+    /// Whether the caller wants the operation to stop
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+
+    /// The GPU couldn't be set up, so the work carries on on the CPU; the
+    /// job queue records it on the job
+    fn gpu_fallback(&mut self, _fallback: &GpuFallback) {}
+}
+
+/// Fail with [`AudioError::Cancelled`] if `sink` asked to stop
+pub fn check_cancelled(sink: &dyn ProgressSink) -> Result<()> {
+    if sink.is_cancelled() {
+        return Err(AudioError::Cancelled);
+    }
+    Ok(())
+}
+
+/// Sink for callers that don't track progress
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+impl ProgressSink for NoProgress {
+    fn progress(&mut self, _fraction: f64) {}
+}
+
+impl<F: FnMut(f64)> ProgressSink for F {
+    fn progress(&mut self, fraction: f64) {
+        self(fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_closure_is_a_sink() {
+        let mut seen = Vec::new();
+        {
+            let mut record = |fraction: f64| seen.push(fraction);
+            let sink: &mut dyn ProgressSink = &mut record;
+            sink.stage("decode");
+            sink.progress(0.5);
+            sink.message("ignored");
+            assert!(check_cancelled(sink).is_ok());
+        }
+        assert_eq!(seen, vec![0.5]);
+    }
+}
@@ -0,0 +1,469 @@
+// src-tauri/src/runtime.rs
+// Loaded inference runtimes (a model on a device) shared between jobs
+// through leases, and cancelling running jobs by id
+
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::Duration;
+
+use crate::error::Result;
+use crate::progress::{check_cancelled, ProgressSink};
+
+/// How often a lease waiting for a free runtime checks for cancellation
+const WAIT_POLL: Duration = Duration::from_millis(50);
+
+/// How many runtimes a [`RuntimePool`] hands out at once
+///
+/// Built with [`PoolLimits::new`], which keeps both limits at least 1 so a
+/// lease can never wait forever.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolLimits {
+    per_key: usize,
+    total: usize,
+}
+
+impl PoolLimits {
+    /// At most `per_key` of each key and `total` altogether; both at least 1
+    pub fn new(per_key: usize, total: usize) -> Self {
+        Self {
+            per_key: per_key.max(1),
+            total: total.max(1),
+        }
+    }
+
+    /// Leases of one key, e.g. copies of one model on one GPU
+    pub fn per_key(&self) -> usize {
+        self.per_key
+    }
+
+    /// Leases of every key together
+    pub fn total(&self) -> usize {
+        self.total
+    }
+}
+
+struct PoolState<T> {
+    /// Runtimes back from their leases, kept for the next job with that key
+    idle: HashMap<String, Vec<T>>,
+    /// Leases out per key, runtimes still being created included
+    leased: HashMap<String, usize>,
+    total_leased: usize,
+    limits: PoolLimits,
+}
+
+struct PoolShared<T> {
+    state: Mutex<PoolState<T>>,
+    returned: Condvar,
+}
+
+/// Runtimes kept loaded between jobs, leased out one job at a time
+///
+/// Runtimes are keyed, e.g. by model and device, so a job only ever gets
+/// one made for what it asked for. A lease reuses an idle runtime of its
+/// key or makes a new one, and waits while the [`PoolLimits`] are
+/// reached. Dropping the [`RuntimeLease`] gives the runtime back, even when
+/// a panic unwinds through the job. Clones share the pool.
+pub struct RuntimePool<T> {
+    shared: Arc<PoolShared<T>>,
+}
+
+impl<T> Clone for RuntimePool<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> RuntimePool<T> {
+    pub fn new(limits: PoolLimits) -> Self {
+        Self {
+            shared: Arc::new(PoolShared {
+                state: Mutex::new(PoolState {
+                    idle: HashMap::new(),
+                    leased: HashMap::new(),
+                    total_leased: 0,
+                    limits,
+                }),
+                returned: Condvar::new(),
+            }),
+        }
+    }
+
+    pub fn limits(&self) -> PoolLimits {
+        self.state().limits
+    }
+
+    /// Change the limits; leases already out stay out even if there are
+    /// now too many
+    pub fn set_limits(&self, limits: PoolLimits) {
+        self.state().limits = limits;
+        self.shared.returned.notify_all();
+    }
+
+    /// Lease a runtime for `key`, reusing an idle one or making one with
+    /// `create`
+    ///
+    /// Waits while `key` or the pool as a whole is at its limit. Returns
+    /// [`AudioError::Cancelled`](crate::error::AudioError::Cancelled) if
+    /// `progress` is cancelled while waiting, and `create`'s error if it
+    /// fails; either way no slot is kept.
+    pub fn lease<F>(
+        &self,
+        key: &str,
+        progress: &dyn ProgressSink,
+        create: F,
+    ) -> Result<RuntimeLease<T>>
+    where
+        F: FnOnce() -> Result<T>,
+    {
+        let mut state = self.state();
+        loop {
+            check_cancelled(progress)?;
+            let out = state.leased.get(key).copied().unwrap_or(0);
+            if out < state.limits.per_key && state.total_leased < state.limits.total {
+                break;
+            }
+            state = match self.shared.returned.wait_timeout(state, WAIT_POLL) {
+                Ok((state, _)) => state,
+                Err(e) => e.into_inner().0,
+            };
+        }
+        *state.leased.entry(key.to_string()).or_default() += 1;
+        state.total_leased += 1;
+        let idle = state.idle.get_mut(key).and_then(Vec::pop);
+        drop(state);
+
+        // From here the lease holds the slot, so a failed or panicking
+        // `create` frees it as the lease drops
+        let mut lease = RuntimeLease {
+            pool: self.clone(),
+            key: key.to_string(),
+            runtime: idle,
+        };
+        if lease.runtime.is_none() {
+            lease.runtime = Some(create()?);
+        }
+        Ok(lease)
+    }
+
+    /// Runtimes of every key waiting for a job
+    pub fn idle(&self) -> usize {
+        self.state().idle.values().map(Vec::len).sum()
+    }
+
+    /// Leases out now
+    pub fn leased(&self) -> usize {
+        self.state().total_leased
+    }
+
+    /// Drop the idle runtimes of `key`, e.g. after its model file changed;
+    /// leased ones are kept when they come back unless discarded
+    ///
+    /// # Returns
+    /// How many were dropped
+    pub fn evict(&self, key: &str) -> usize {
+        let removed = self.state().idle.remove(key);
+        removed.map_or(0, |runtimes| runtimes.len())
+    }
+
+    /// Drop every idle runtime, e.g. to free memory
+    pub fn clear_idle(&self) -> usize {
+        let idle = std::mem::take(&mut self.state().idle);
+        idle.values().map(Vec::len).sum()
+    }
+
+    fn state(&self) -> MutexGuard<'_, PoolState<T>> {
+        self.shared.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Free a lease's slot, keeping `runtime` for the next job
+    fn release(&self, key: &str, runtime: Option<T>) {
+        let mut state = self.state();
+        if let Some(runtime) = runtime {
+            state.idle.entry(key.to_string()).or_default().push(runtime);
+        }
+        if let Some(out) = state.leased.get_mut(key) {
+            *out -= 1;
+            if *out == 0 {
+                state.leased.remove(key);
+            }
+        }
+        state.total_leased -= 1;
+        drop(state);
+        self.shared.returned.notify_all();
+    }
+}
+
+/// A runtime on loan from a [`RuntimePool`]; dereferences to the runtime
+///
+/// Goes back to the pool when dropped. A runtime dropped during a panic is
+/// thrown away rather than reused, since it may have been left half way
+/// through something, but its slot is always freed.
+pub struct RuntimeLease<T> {
+    pool: RuntimePool<T>,
+    key: String,
+    /// Only `None` while the pool is creating it, or once dropped
+    runtime: Option<T>,
+}
+
+impl<T> RuntimeLease<T> {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Free the slot without keeping the runtime, e.g. after the device
+    /// it runs on failed
+    pub fn discard(mut self) {
+        self.runtime = None;
+    }
+}
+
+impl<T> Deref for RuntimeLease<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.runtime.as_ref().expect("a lease holds its runtime until dropped")
+    }
+}
+
+impl<T> DerefMut for RuntimeLease<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.runtime.as_mut().expect("a lease holds its runtime until dropped")
+    }
+}
+
+impl<T> Drop for RuntimeLease<T> {
+    fn drop(&mut self) {
+        let runtime = self.runtime.take().filter(|_| !std::thread::panicking());
+        self.pool.release(&self.key, runtime);
+    }
+}
+
+/// Set to ask a running job to stop; clones share the flag
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Cancel tokens of the jobs running now, by job id
+///
+/// A job registers when it starts and holds the [`CancelRegistration`]
+/// while it runs; anything that knows its id can then stop it. Clones
+/// share the registry.
+#[derive(Debug, Clone, Default)]
+pub struct CancelRegistry {
+    tokens: Arc<Mutex<HashMap<u64, CancelToken>>>,
+}
+
+impl CancelRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A fresh token for job `id`, replacing any it had; removed again when
+    /// the registration drops
+    pub fn register(&self, id: u64) -> CancelRegistration {
+        let token = CancelToken::new();
+        self.tokens().insert(id, token.clone());
+        CancelRegistration {
+            registry: self.clone(),
+            id,
+            token,
+        }
+    }
+
+    /// Ask job `id` to stop
+    ///
+    /// # Returns
+    /// `false` if no job with that id is registered
+    pub fn cancel(&self, id: u64) -> bool {
+        self.tokens().get(&id).map(CancelToken::cancel).is_some()
+    }
+
+    pub fn is_registered(&self, id: u64) -> bool {
+        self.tokens().contains_key(&id)
+    }
+
+    /// Ids of the registered jobs, in order
+    pub fn registered(&self) -> Vec<u64> {
+        let mut ids: Vec<u64> = self.tokens().keys().copied().collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    fn tokens(&self) -> MutexGuard<'_, HashMap<u64, CancelToken>> {
+        self.tokens.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A job's place in a [`CancelRegistry`], held while it runs
+#[derive(Debug)]
+pub struct CancelRegistration {
+    registry: CancelRegistry,
+    id: u64,
+    token: CancelToken,
+}
+
+impl CancelRegistration {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn token(&self) -> &CancelToken {
+        &self.token
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+}
+
+/// Lets a library call that takes a [`ProgressSink`] stop when the job is
+/// cancelled; progress itself goes nowhere
+impl ProgressSink for CancelRegistration {
+    fn progress(&mut self, _fraction: f64) {}
+
+    fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+}
+
+impl Drop for CancelRegistration {
+    fn drop(&mut self) {
+        let mut tokens = self.registry.tokens();
+        // Leave a newer registration of the same id alone
+        if tokens.get(&self.id).is_some_and(|token| Arc::ptr_eq(&token.0, &self.token.0)) {
+            tokens.remove(&self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AudioError;
+    use crate::progress::NoProgress;
+    use std::sync::atomic::AtomicUsize;
+    use std::time::Instant;
+
+    /// A runtime that knows which one it is
+    struct Model(usize);
+
+    #[test]
+    fn test_leases_reuse_runtimes_per_key() {
+        let pool = RuntimePool::new(PoolLimits::new(2, 3));
+        let made = AtomicUsize::new(0);
+        let make = || Ok(Model(made.fetch_add(1, Ordering::SeqCst)));
+
+        let a = pool.lease("base@cpu", &NoProgress, make).unwrap();
+        let b = pool.lease("base@cpu", &NoProgress, make).unwrap();
+        let c = pool.lease("large@cuda:0", &NoProgress, make).unwrap();
+        assert_eq!((a.0, b.0, c.0), (0, 1, 2));
+        assert_eq!((pool.leased(), pool.idle()), (3, 0));
+        drop(a);
+        drop(c);
+        assert_eq!((pool.leased(), pool.idle()), (1, 2));
+
+        // An idle runtime of the same key is reused; another key's isn't
+        let again = pool.lease("base@cpu", &NoProgress, make).unwrap();
+        assert_eq!((again.key(), again.0), ("base@cpu", 0));
+        let small = pool.lease("small@cpu", &NoProgress, make).unwrap();
+        assert_eq!(small.0, 3);
+        small.discard();
+        assert_eq!(pool.idle(), 1, "discarded runtimes aren't kept");
+
+        let failed = pool.lease("tiny@cpu", &NoProgress, || {
+            Err::<Model, _>(AudioError::InvalidParameter("no such model".to_string()))
+        });
+        assert!(failed.is_err());
+        assert_eq!(pool.leased(), 2, "a failed create frees its slot");
+        assert_eq!(pool.evict("large@cuda:0"), 1);
+        drop((again, b));
+        assert_eq!(pool.clear_idle(), 2);
+    }
+
+    #[test]
+    fn test_limits_make_leases_wait() {
+        let pool = RuntimePool::new(PoolLimits::new(1, 1));
+        let held = pool.lease("base", &NoProgress, || Ok(Model(0))).unwrap();
+        let waiter = {
+            let pool = pool.clone();
+            std::thread::spawn(move || {
+                let started = Instant::now();
+                let lease = pool.lease("other", &NoProgress, || Ok(Model(1))).unwrap();
+                (started.elapsed(), lease.0)
+            })
+        };
+        std::thread::sleep(Duration::from_millis(100));
+        drop(held);
+        let (waited, model) = waiter.join().unwrap();
+        assert!(waited >= Duration::from_millis(90), "waited {:?}", waited);
+        assert_eq!(model, 1);
+
+        struct Cancelled;
+        impl ProgressSink for Cancelled {
+            fn progress(&mut self, _fraction: f64) {}
+            fn is_cancelled(&self) -> bool {
+                true
+            }
+        }
+        let _held = pool.lease("base", &NoProgress, || Ok(Model(2))).unwrap();
+        let result = pool.lease("base", &Cancelled, || Ok(Model(3)));
+        assert!(matches!(result, Err(AudioError::Cancelled)));
+        assert_eq!(pool.leased(), 1);
+
+        // A zero limit would block every lease forever
+        pool.set_limits(PoolLimits::new(0, 0));
+        assert_eq!((pool.limits().per_key(), pool.limits().total()), (1, 1));
+    }
+
+    #[test]
+    fn test_lease_returned_on_panic() {
+        let pool = RuntimePool::new(PoolLimits::new(1, 1));
+        let panicking = pool.clone();
+        let result = std::thread::spawn(move || {
+            let _lease = panicking.lease("base", &NoProgress, || Ok(Model(0))).unwrap();
+            panic!("inference crashed");
+        })
+        .join();
+        assert!(result.is_err());
+        assert_eq!((pool.leased(), pool.idle()), (0, 0), "slot freed, runtime dropped");
+        assert!(pool.lease("base", &NoProgress, || Ok(Model(1))).is_ok());
+    }
+
+    #[test]
+    fn test_cancel_registry() {
+        let registry = CancelRegistry::new();
+        assert!(!registry.cancel(7));
+        let job = registry.register(7);
+        let other = registry.register(9);
+        assert_eq!(registry.registered(), [7, 9]);
+        assert!(registry.cancel(7));
+        assert!(job.is_cancelled());
+        assert!(!other.is_cancelled());
+
+        // A job registered again keeps its new token when the old one drops
+        let rerun = registry.register(7);
+        drop(job);
+        assert!(registry.is_registered(7));
+        assert!(!rerun.is_cancelled());
+        drop(rerun);
+        drop(other);
+        assert!(registry.registered().is_empty());
+    }
+}
@@ -0,0 +1,355 @@
+// src-tauri/src/schedule.rs
+// Time windows and idle detection for queued heavy work
+
+use std::time::Duration;
+
+use chrono::{NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use crate::gpu::run_command;
+use crate::jobs::{app_queue, JobKind};
+
+/// Event the app emits whenever [`ScheduleStatus`] changes
+pub const SCHEDULE_STATUS_EVENT: &str = "schedule-status";
+
+/// A daily stretch of local time, e.g. 22:00 to 06:00
+///
+/// A window whose end is before its start runs past midnight; one whose
+/// start and end are equal covers the whole day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeWindow {
+    #[serde(with = "clock_time")]
+    pub start: NaiveTime,
+    #[serde(with = "clock_time")]
+    pub end: NaiveTime,
+}
+
+impl TimeWindow {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// When queued heavy work may run; stored in the app settings
+///
+/// With no windows and `when_idle` off, work runs whenever it's queued.
+/// Otherwise it runs inside any of the windows, or (with `when_idle`) once
+/// nobody has touched the keyboard or mouse for `idle_minutes`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Schedule {
+    pub windows: Vec<TimeWindow>,
+    pub when_idle: bool,
+    pub idle_minutes: u32,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            windows: Vec::new(),
+            when_idle: false,
+            idle_minutes: 15,
+        }
+    }
+}
+
+/// Why queued work may or may not run right now
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Gate {
+    /// No schedule is set
+    Unrestricted,
+    InWindow,
+    Idle,
+    /// Outside every window and not idle (or idle time is unknown)
+    Waiting,
+}
+
+/// Whether queued work may run; the payload of [`SCHEDULE_STATUS_EVENT`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduleStatus {
+    pub gate: Gate,
+    /// Start of the next window, while waiting for one
+    #[serde(with = "optional_clock_time")]
+    pub next_window: Option<NaiveTime>,
+    /// Transcriptions waiting on the job queue
+    pub pending: usize,
+}
+
+impl ScheduleStatus {
+    pub fn is_open(&self) -> bool {
+        self.gate != Gate::Waiting
+    }
+}
+
+impl Schedule {
+    /// Whether anything limits when work runs
+    pub fn is_restricted(&self) -> bool {
+        !self.windows.is_empty() || self.when_idle
+    }
+
+    /// Decide whether work may run at local time `now`, with the user idle
+    /// for `idle` (`None` when that can't be told)
+    pub fn gate(&self, now: NaiveTime, idle: Option<Duration>) -> Gate {
+        if !self.is_restricted() {
+            Gate::Unrestricted
+        } else if self.windows.iter().any(|window| window.contains(now)) {
+            Gate::InWindow
+        } else if self.when_idle
+            && idle.is_some_and(|idle| idle.as_secs() >= u64::from(self.idle_minutes) * 60)
+        {
+            Gate::Idle
+        } else {
+            Gate::Waiting
+        }
+    }
+
+    /// The window start that comes soonest after `now`, wrapping past midnight
+    pub fn next_window(&self, now: NaiveTime) -> Option<NaiveTime> {
+        let seconds_until = |start: NaiveTime| {
+            (start.num_seconds_from_midnight() + 86_400 - now.num_seconds_from_midnight())
+                % 86_400
+        };
+        self.windows
+            .iter()
+            .map(|window| window.start)
+            .min_by_key(|&start| seconds_until(start))
+    }
+
+    /// Status at local time `now`
+    pub fn status(&self, now: NaiveTime, idle: Option<Duration>, pending: usize) -> ScheduleStatus {
+        let gate = self.gate(now, idle);
+        ScheduleStatus {
+            gate,
+            next_window: if gate == Gate::Waiting {
+                self.next_window(now)
+            } else {
+                None
+            },
+            pending,
+        }
+    }
+
+    /// Status right now, asking the OS for idle time only when it matters
+    pub fn current_status(&self) -> ScheduleStatus {
+        let (now, idle) = self.now();
+        let pending = app_queue().run_order(Some(JobKind::Transcription)).len();
+        self.status(now, idle, pending)
+    }
+
+    /// Whether work may run right now; what the job queue asks before
+    /// starting a transcription
+    pub fn current_gate(&self) -> Gate {
+        let (now, idle) = self.now();
+        self.gate(now, idle)
+    }
+
+    /// Local time, and idle time if the schedule cares about it
+    fn now(&self) -> (NaiveTime, Option<Duration>) {
+        let idle = if self.when_idle {
+            query_idle_time()
+        } else {
+            None
+        };
+        (chrono::Local::now().time(), idle)
+    }
+}
+
+/// How long since the last keyboard or mouse input
+///
+/// Asks `xprintidle` on Linux (X11 sessions only) and `ioreg` on macOS.
+/// `None` when it can't be told, including on Windows, which keeps
+/// idle-only schedules waiting rather than running under the user.
+pub fn query_idle_time() -> Option<Duration> {
+    #[cfg(target_os = "linux")]
+    let idle = run_command("xprintidle", &[]).and_then(|s| parse_xprintidle(&s));
+
+    #[cfg(target_os = "macos")]
+    let idle = run_command("ioreg", &["-c", "IOHIDSystem", "-d", "4"])
+        .and_then(|s| parse_ioreg_idle(&s));
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    let idle = None;
+
+    debug!(?idle, "Idle time queried");
+    idle
+}
+
+/// Parse `xprintidle` output: milliseconds since the last input
+pub fn parse_xprintidle(output: &str) -> Option<Duration> {
+    output.trim().parse().ok().map(Duration::from_millis)
+}
+
+/// Parse `ioreg -c IOHIDSystem` output for `"HIDIdleTime" = <nanoseconds>`
+pub fn parse_ioreg_idle(output: &str) -> Option<Duration> {
+    output.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        if !key.trim_end().ends_with("\"HIDIdleTime\"") {
+            return None;
+        }
+        value.trim().parse().ok().map(Duration::from_nanos)
+    })
+}
+
+/// Poll the schedule and call `on_change` whenever the status changes
+///
+/// Runs forever; start it on its own thread. `schedule` is asked again on
+/// every poll so a changed setting takes effect without a restart. The
+/// first status is always reported.
+pub fn watch_schedule<S, F>(interval: Duration, schedule: S, mut on_change: F)
+where
+    S: Fn() -> Schedule,
+    F: FnMut(&ScheduleStatus),
+{
+    let mut last = None;
+    loop {
+        let status = schedule().current_status();
+        if last != Some(status) {
+            on_change(&status);
+            last = Some(status);
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+/// Times as "HH:MM", the way `<input type="time">` gives them; seconds are
+/// accepted when reading
+mod clock_time {
+    use chrono::NaiveTime;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(time: &NaiveTime, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&time.format("%H:%M"))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveTime, D::Error> {
+        parse(&String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+
+    pub(super) fn parse(value: &str) -> Result<NaiveTime, String> {
+        NaiveTime::parse_from_str(value, "%H:%M")
+            .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M:%S"))
+            .map_err(|_| format!("expected a time like \"22:30\", got \"{}\"", value))
+    }
+}
+
+mod optional_clock_time {
+    use chrono::NaiveTime;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        time: &Option<NaiveTime>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match time {
+            Some(time) => super::clock_time::serialize(time, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<NaiveTime>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|value| super::clock_time::parse(&value).map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn overnight() -> Schedule {
+        serde_json::from_str(r#"{ "windows": [{ "start": "22:00", "end": "06:30" }] }"#).unwrap()
+    }
+
+    #[test]
+    fn test_windows_wrap_past_midnight() {
+        let window = overnight().windows[0];
+        assert!(window.contains(at(23, 0)));
+        assert!(window.contains(at(0, 0)));
+        assert!(window.contains(at(6, 29)));
+        assert!(!window.contains(at(6, 30)));
+        assert!(!window.contains(at(12, 0)));
+
+        let lunch = TimeWindow {
+            start: at(12, 0),
+            end: at(13, 0),
+        };
+        assert!(lunch.contains(at(12, 0)) && !lunch.contains(at(13, 0)));
+        let all_day = TimeWindow {
+            start: at(9, 0),
+            end: at(9, 0),
+        };
+        assert!(all_day.contains(at(3, 0)));
+    }
+
+    #[test]
+    fn test_gate_and_next_window() {
+        assert_eq!(Schedule::default().gate(at(12, 0), None), Gate::Unrestricted);
+
+        let mut schedule = overnight();
+        assert_eq!(schedule.gate(at(23, 15), None), Gate::InWindow);
+        let status = schedule.status(at(12, 0), None, 3);
+        assert_eq!(status.gate, Gate::Waiting);
+        assert!(!status.is_open());
+        assert_eq!(status.next_window, Some(at(22, 0)));
+        assert_eq!(status.pending, 3);
+
+        schedule.windows.push(TimeWindow {
+            start: at(12, 30),
+            end: at(13, 30),
+        });
+        assert_eq!(schedule.next_window(at(12, 0)), Some(at(12, 30)));
+        assert_eq!(schedule.next_window(at(23, 0)), Some(at(12, 30)));
+
+        schedule.when_idle = true;
+        let idle = |minutes: u64| Some(Duration::from_secs(minutes * 60));
+        assert_eq!(schedule.gate(at(10, 0), idle(20)), Gate::Idle);
+        assert_eq!(schedule.gate(at(10, 0), idle(5)), Gate::Waiting);
+        assert_eq!(schedule.gate(at(10, 0), None), Gate::Waiting);
+    }
+
+    #[test]
+    fn test_times_read_and_write_as_hours_and_minutes() {
+        let json = serde_json::to_value(overnight()).unwrap();
+        assert_eq!(json["windows"][0]["start"], "22:00");
+        assert_eq!(json["windows"][0]["end"], "06:30");
+
+        let window: TimeWindow =
+            serde_json::from_str(r#"{ "start": "01:02:03", "end": "4:05" }"#).unwrap();
+        assert_eq!(window.start, NaiveTime::from_hms_opt(1, 2, 3).unwrap());
+        assert_eq!(window.end, at(4, 5));
+        assert!(serde_json::from_str::<TimeWindow>(r#"{ "start": "late", "end": "4:05" }"#)
+            .is_err());
+
+        let status = overnight().status(at(12, 0), None, 0);
+        let json = serde_json::to_value(status).unwrap();
+        assert_eq!(json["next_window"], "22:00");
+        assert_eq!(serde_json::from_value::<ScheduleStatus>(json).unwrap(), status);
+    }
+
+    #[test]
+    fn test_parse_idle_time() {
+        assert_eq!(parse_xprintidle("1234\n"), Some(Duration::from_millis(1234)));
+        assert_eq!(parse_xprintidle("couldn't open display"), None);
+
+        let ioreg = r#"
+    | |   "HIDIdleTimeDelta" = 5
+    | |   "HIDIdleTime" = 90000000000
+    | |   "HIDPointerAcceleration" = 45056"#;
+        assert_eq!(parse_ioreg_idle(ioreg), Some(Duration::from_secs(90)));
+        assert_eq!(parse_ioreg_idle("nothing here"), None);
+    }
+}
@@ -0,0 +1,214 @@
+// src-tauri/src/settings.rs
+// Persistent user settings, stored as JSON in the platform config directory
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::audio::ExportPreset;
+use crate::gpu::GpuPreference;
+use crate::naming::OutputNaming;
+use crate::notify::NotificationSettings;
+use crate::power::PowerMode;
+use crate::schedule::Schedule;
+use crate::translation::TranslationEngine;
+
+/// Matches the bundle identifier in tauri.conf.json, so this is the same
+/// directory Tauri's `app_config_dir` resolves to
+pub(crate) const APP_IDENTIFIER: &str = "com.hinson.hermeneia";
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Everything the app remembers between runs
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub gpu: GpuPreference,
+    /// Whether batch work slows down on battery power
+    pub power: PowerMode,
+    /// When queued heavy work (transcription) may run
+    pub schedule: Schedule,
+    /// Desktop notifications and webhooks when long jobs finish
+    pub notifications: NotificationSettings,
+    /// Most files processed at once; `None` uses one per CPU core
+    pub max_jobs: Option<usize>,
+    /// Jobs on the background queue (see [`crate::jobs`]) run at once;
+    /// `None` runs one at a time
+    pub background_jobs: Option<usize>,
+    /// Memory one operation may use before switching to streaming; `None`
+    /// uses half the machine's memory
+    pub memory_budget_mb: Option<u64>,
+    /// Stream every operation that can and keep caches small; `None` turns
+    /// it on for machines with little memory (see
+    /// [`crate::memory::low_memory_enabled`])
+    pub low_memory: Option<bool>,
+    /// Audio buffered ahead of the output during playback; `None` uses
+    /// [`crate::playback::DEFAULT_BUFFER_SECONDS`]
+    pub playback_buffer_seconds: Option<f64>,
+    /// Playback position updates sent to the frontend per second while
+    /// playing; `None` uses [`crate::playback::DEFAULT_UPDATE_HZ`]
+    pub playback_update_hz: Option<f64>,
+    /// Output device id (see [`crate::playback::list_output_devices`]);
+    /// `None` follows the system default
+    pub output_device: Option<String>,
+    /// Output path template and collision policy for exports and batches
+    pub output: OutputNaming,
+    /// Where downloaded podcast episodes go; `None` uses
+    /// [`crate::ingest::library_dir`]'s default
+    pub library_dir: Option<PathBuf>,
+    /// yt-dlp executable for media URLs; `None` looks for it on the `PATH`
+    pub downloader: Option<PathBuf>,
+    /// Export presets the user saved, offered after
+    /// [`ExportPreset::builtin`]
+    pub export_presets: Vec<ExportPreset>,
+    /// Engine translation jobs use unless they name their own
+    pub translation_engine: Option<TranslationEngine>,
+}
+
+/// The app's config directory, e.g. `~/.config/com.hinson.hermeneia`
+pub fn app_config_dir() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join(APP_IDENTIFIER))
+}
+
+impl Settings {
+    /// Location of the settings file, e.g. `~/.config/com.hinson.hermeneia/settings.json`
+    pub fn path() -> Option<PathBuf> {
+        Some(app_config_dir()?.join(SETTINGS_FILE))
+    }
+
+    /// Load the settings, falling back to defaults
+    ///
+    /// A missing file is normal on first run; an unreadable or corrupt one
+    /// is logged and ignored so a bad file can never stop the app starting.
+    pub fn load() -> Self {
+        match Self::path() {
+            Some(path) => Self::load_or_default(&path),
+            None => Self::default(),
+        }
+    }
+
+    fn load_or_default(path: &Path) -> Self {
+        match Self::load_from(path) {
+            Ok(settings) => settings,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "Ignoring unreadable settings file");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn load_from(path: &Path) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Write the settings to [`Settings::path`]
+    pub fn save(&self) -> io::Result<()> {
+        self.save_to(&Self::saved_path()?)
+    }
+
+    fn saved_path() -> io::Result<PathBuf> {
+        Self::path().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "no config directory on this platform",
+            )
+        })
+    }
+
+    /// Write the settings to `path`, creating parent directories as needed
+    ///
+    /// Writes to a temporary file first so a crash can't leave half a file.
+    pub fn save_to(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp, path)
+    }
+}
+
+/// Load the settings, apply `change` and save them, as one step
+///
+/// Commands that change a setting go through this rather than
+/// [`Settings::load`] and [`Settings::save`]: a lock is held from the load
+/// to the save, so two commands saving different fields at once can't
+/// undo each other.
+///
+/// # Returns
+/// The settings as saved
+pub fn update(change: impl FnOnce(&mut Settings)) -> io::Result<Settings> {
+    update_at(&Settings::saved_path()?, change)
+}
+
+fn update_at(path: &Path, change: impl FnOnce(&mut Settings)) -> io::Result<Settings> {
+    static UPDATE: Mutex<()> = Mutex::new(());
+    let _guard = UPDATE.lock().unwrap_or_else(|e| e.into_inner());
+    let mut settings = Settings::load_or_default(path);
+    change(&mut settings);
+    settings.save_to(path)?;
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::ComputeBackend;
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = std::env::temp_dir().join("hermeneia_test_settings");
+        let path = dir.join("nested").join(SETTINGS_FILE);
+
+        let mut settings = Settings::default();
+        settings.gpu.render_adapter = Some("Iris Xe Graphics".to_string());
+        settings.gpu.inference_backend = Some(ComputeBackend::Cuda);
+        settings.gpu.inference_device = Some(1);
+        settings.save_to(&path).unwrap();
+
+        assert_eq!(Settings::load_from(&path).unwrap(), settings);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_updates_keep_every_change() {
+        let dir = std::env::temp_dir().join("hermeneia_test_settings_update");
+        let path = dir.join(SETTINGS_FILE);
+        fs::remove_dir_all(&dir).ok();
+
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    update_at(&path, |settings| {
+                        settings.export_presets.push(crate::audio::ExportPreset::new(
+                            format!("preset {}", i),
+                            crate::audio::OutputFormat::Mp3 { bitrate_kbps: 128 },
+                        ))
+                    })
+                    .unwrap();
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let saved = update_at(&path, |settings| settings.max_jobs = Some(2)).unwrap();
+        assert_eq!(saved.export_presets.len(), 8);
+        assert_eq!(Settings::load_from(&path).unwrap(), saved);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_empty_object_is_default() {
+        let settings: Settings = serde_json::from_str("{}").unwrap();
+        assert_eq!(settings, Settings::default());
+    }
+}
@@ -0,0 +1,327 @@
+// src-tauri/src/speakers.rs
+// Talk time, turns and interruptions per speaker from diarized transcripts
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::time::{AudioDuration, Timestamp};
+use crate::error::Result;
+use crate::transcribe::Segment;
+
+/// Settings for [`analyze_talk_time`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TalkTimeOptions {
+    /// Least overlap with the other speaker's turn that counts as an
+    /// interruption; diarization boundaries are rarely exact, so a little
+    /// overlap is just two turns meeting
+    pub min_overlap_seconds: f64,
+}
+
+impl Default for TalkTimeOptions {
+    fn default() -> Self {
+        Self {
+            min_overlap_seconds: 0.3,
+        }
+    }
+}
+
+/// One speaker's share of a conversation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeakerStats {
+    pub speaker: String,
+    pub talk_time: AudioDuration,
+    /// Fraction of all labelled talk time (0.0 to 1.0)
+    pub share: f64,
+    /// Stretches of talk not broken by another speaker
+    pub turns: usize,
+    pub longest_turn: AudioDuration,
+    /// Where the longest turn starts (in the first file, for batch totals)
+    pub longest_turn_start: Timestamp,
+    /// Turns started while someone else was still talking
+    pub interruptions: usize,
+    /// Times someone else started talking over this speaker
+    pub interrupted: usize,
+    pub words: usize,
+}
+
+impl SpeakerStats {
+    /// Average turn length in seconds
+    pub fn mean_turn_seconds(&self) -> f64 {
+        if self.turns == 0 {
+            0.0
+        } else {
+            self.talk_time.as_seconds() / self.turns as f64
+        }
+    }
+}
+
+/// Who talked how much in a transcript, or across a batch of them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TalkTimeReport {
+    /// Most talk time first
+    pub speakers: Vec<SpeakerStats>,
+    /// Talk in segments with no speaker label; not in any share
+    pub unlabelled: AudioDuration,
+}
+
+impl TalkTimeReport {
+    /// Totals for a batch, adding up speakers with the same label
+    pub fn combine<'a>(reports: impl IntoIterator<Item = &'a TalkTimeReport>) -> Self {
+        let mut speakers: Vec<SpeakerStats> = Vec::new();
+        let mut unlabelled = AudioDuration::ZERO;
+        for report in reports {
+            unlabelled += report.unlabelled;
+            for stats in &report.speakers {
+                let Some(total) = speakers.iter_mut().find(|s| s.speaker == stats.speaker) else {
+                    speakers.push(stats.clone());
+                    continue;
+                };
+                total.talk_time += stats.talk_time;
+                total.turns += stats.turns;
+                if stats.longest_turn > total.longest_turn {
+                    total.longest_turn = stats.longest_turn;
+                    total.longest_turn_start = stats.longest_turn_start;
+                }
+                total.interruptions += stats.interruptions;
+                total.interrupted += stats.interrupted;
+                total.words += stats.words;
+            }
+        }
+        finish(speakers, unlabelled)
+    }
+}
+
+/// Each speaker's talk time, turns, interruptions and longest turn
+///
+/// Segments are read in time order; a turn lasts until a segment from
+/// someone else. A turn that starts at least
+/// [`TalkTimeOptions::min_overlap_seconds`] before the current one ends
+/// interrupts it. Segments without a [`Segment::speaker`] count towards
+/// [`TalkTimeReport::unlabelled`] only, and don't end a turn.
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::Timestamp;
+/// use hermeneia_lib::speakers::{analyze_talk_time, TalkTimeOptions};
+/// use hermeneia_lib::transcribe::Segment;
+///
+/// let said = |start: f64, end: f64, speaker: &str| Segment {
+///     start: Timestamp::from_seconds(start),
+///     end: Timestamp::from_seconds(end),
+///     text: "...".to_string(),
+///     words: Vec::new(),
+///     speaker: Some(speaker.to_string()),
+/// };
+/// let segments = [said(0.0, 30.0, "Host"), said(29.0, 40.0, "Guest"), said(40.0, 50.0, "Host")];
+/// let report = analyze_talk_time(&segments, &TalkTimeOptions::default());
+///
+/// assert_eq!(report.speakers[0].speaker, "Host");
+/// assert_eq!(report.speakers[0].turns, 2);
+/// assert_eq!(report.speakers[1].interruptions, 1);
+/// ```
+pub fn analyze_talk_time(segments: &[Segment], options: &TalkTimeOptions) -> TalkTimeReport {
+    let mut ordered: Vec<&Segment> = segments.iter().collect();
+    ordered.sort_by_key(|segment| segment.start);
+    let min_overlap = AudioDuration::from_seconds(options.min_overlap_seconds.max(0.0));
+
+    let mut speakers: Vec<SpeakerStats> = Vec::new();
+    let mut unlabelled = AudioDuration::ZERO;
+    // Index into `speakers`, start and end of the turn in progress
+    let mut turn: Option<(usize, Timestamp, Timestamp)> = None;
+
+    for segment in ordered {
+        let length = segment.end.saturating_since(segment.start);
+        let Some(speaker) = segment.speaker.as_deref() else {
+            unlabelled += length;
+            continue;
+        };
+        let index = match speakers.iter().position(|s| s.speaker == speaker) {
+            Some(index) => index,
+            None => {
+                speakers.push(SpeakerStats {
+                    speaker: speaker.to_string(),
+                    talk_time: AudioDuration::ZERO,
+                    share: 0.0,
+                    turns: 0,
+                    longest_turn: AudioDuration::ZERO,
+                    longest_turn_start: segment.start,
+                    interruptions: 0,
+                    interrupted: 0,
+                    words: 0,
+                });
+                speakers.len() - 1
+            }
+        };
+        speakers[index].talk_time += length;
+        speakers[index].words += segment.text.split_whitespace().count();
+
+        turn = match turn {
+            Some((current, start, end)) if current == index => {
+                Some((current, start, end.max(segment.end)))
+            }
+            previous => {
+                if let Some((current, _, end)) = previous {
+                    if end.saturating_since(segment.start) >= min_overlap
+                        && end > segment.start
+                    {
+                        speakers[index].interruptions += 1;
+                        speakers[current].interrupted += 1;
+                    }
+                }
+                speakers[index].turns += 1;
+                Some((index, segment.start, segment.end))
+            }
+        };
+        if let Some((current, start, end)) = turn {
+            let length = end.saturating_since(start);
+            if length > speakers[current].longest_turn {
+                speakers[current].longest_turn = length;
+                speakers[current].longest_turn_start = start;
+            }
+        }
+    }
+    finish(speakers, unlabelled)
+}
+
+/// Fill in shares and sort by talk time
+fn finish(mut speakers: Vec<SpeakerStats>, unlabelled: AudioDuration) -> TalkTimeReport {
+    let total: f64 = speakers.iter().map(|s| s.talk_time.as_seconds()).sum();
+    for stats in &mut speakers {
+        stats.share = if total > 0.0 {
+            stats.talk_time.as_seconds() / total
+        } else {
+            0.0
+        };
+    }
+    speakers.sort_by_key(|s| std::cmp::Reverse(s.talk_time));
+    TalkTimeReport {
+        speakers,
+        unlabelled,
+    }
+}
+
+/// One CSV row per speaker per file, with a header
+///
+/// `reports` pairs each report with the name to put in the `file` column,
+/// e.g. the transcript's path, or "total" for
+/// [`TalkTimeReport::combine`]. Times are in seconds.
+pub fn talk_time_csv<'a>(
+    reports: impl IntoIterator<Item = (&'a str, &'a TalkTimeReport)>,
+) -> String {
+    let mut csv = String::from(
+        "file,speaker,talk_seconds,share,turns,mean_turn_seconds,longest_turn_seconds,\
+         longest_turn_start,interruptions,interrupted,words\n",
+    );
+    for (file, report) in reports {
+        for stats in &report.speakers {
+            let _ = writeln!(
+                csv,
+                "{},{},{:.3},{:.4},{},{:.3},{:.3},{:.3},{},{},{}",
+                csv_field(file),
+                csv_field(&stats.speaker),
+                stats.talk_time.as_seconds(),
+                stats.share,
+                stats.turns,
+                stats.mean_turn_seconds(),
+                stats.longest_turn.as_seconds(),
+                stats.longest_turn_start.as_seconds(),
+                stats.interruptions,
+                stats.interrupted,
+                stats.words
+            );
+        }
+    }
+    csv
+}
+
+/// Write [`talk_time_csv`] to a file
+pub fn write_talk_time_csv<'a, P: AsRef<Path>>(
+    path: P,
+    reports: impl IntoIterator<Item = (&'a str, &'a TalkTimeReport)>,
+) -> Result<()> {
+    std::fs::write(path, talk_time_csv(reports))?;
+    Ok(())
+}
+
+/// Quote a field holding a comma, quote or line break (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn said(start: f64, end: f64, speaker: Option<&str>, text: &str) -> Segment {
+        Segment {
+            start: Timestamp::from_seconds(start),
+            end: Timestamp::from_seconds(end),
+            text: text.to_string(),
+            words: Vec::new(),
+            speaker: speaker.map(String::from),
+        }
+    }
+
+    fn interview() -> Vec<Segment> {
+        vec![
+            said(0.0, 10.0, Some("Host"), "Welcome to the show"),
+            said(10.0, 40.0, Some("Guest"), "Thanks for having me"),
+            said(40.0, 70.0, Some("Guest"), "It started in 1998"),
+            // Cuts in two seconds before the guest is done
+            said(68.0, 75.0, Some("Host"), "And then?"),
+            said(75.0, 80.0, None, "[music]"),
+            // Starts a tenth of a second early: only a boundary
+            said(74.9, 90.0, Some("Guest"), "Then we moved"),
+        ]
+    }
+
+    #[test]
+    fn test_turns_interruptions_and_monologues() {
+        let report = analyze_talk_time(&interview(), &TalkTimeOptions::default());
+        assert_eq!(report.unlabelled, AudioDuration::from_seconds(5.0));
+
+        let guest = &report.speakers[0];
+        assert_eq!(guest.speaker, "Guest");
+        assert!((guest.talk_time.as_seconds() - 75.1).abs() < 1e-6);
+        assert_eq!(guest.turns, 2);
+        assert_eq!(guest.longest_turn, AudioDuration::from_seconds(60.0));
+        assert_eq!(guest.longest_turn_start, Timestamp::from_seconds(10.0));
+        assert_eq!((guest.interruptions, guest.interrupted), (0, 1));
+        assert_eq!(guest.words, 11);
+
+        let host = &report.speakers[1];
+        assert_eq!(host.turns, 2);
+        assert_eq!((host.interruptions, host.interrupted), (1, 0));
+        assert!((guest.share + host.share - 1.0).abs() < 1e-9);
+        assert!((host.mean_turn_seconds() - 8.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_batch_totals_and_csv() {
+        let one = analyze_talk_time(&interview(), &TalkTimeOptions::default());
+        let two = analyze_talk_time(
+            &[said(0.0, 100.0, Some("Host"), "A long \"intro\", really")],
+            &TalkTimeOptions::default(),
+        );
+        let total = TalkTimeReport::combine([&one, &two]);
+        assert_eq!(total.speakers[0].speaker, "Host");
+        assert_eq!(total.speakers[0].turns, 3);
+        assert_eq!(total.speakers[0].longest_turn, AudioDuration::from_seconds(100.0));
+
+        let csv = talk_time_csv([("a.json", &one), ("b, final.json", &two), ("total", &total)]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 1 + 2 + 1 + 2);
+        assert!(lines[0].starts_with("file,speaker,talk_seconds,"));
+        assert_eq!(
+            lines[3],
+            "\"b, final.json\",Host,100.000,1.0000,1,100.000,100.000,0.000,0,0,4"
+        );
+    }
+}
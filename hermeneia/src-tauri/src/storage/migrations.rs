@@ -0,0 +1,61 @@
+// src-tauri/src/storage/migrations.rs
+
+use rusqlite::Connection;
+use tracing::info;
+
+/// Schema changes in order; the database's `user_version` is how many have
+/// been applied
+///
+/// Never edit one that has shipped. Add a new step to the end instead.
+pub(super) const MIGRATIONS: &[&str] = &[
+    // 1: files, transcripts and translations, job outcomes
+    "CREATE TABLE files (
+        path        TEXT PRIMARY KEY,
+        info        TEXT NOT NULL,
+        recorded_at TEXT NOT NULL
+    );
+    CREATE TABLE transcripts (
+        id               INTEGER PRIMARY KEY AUTOINCREMENT,
+        kind             TEXT NOT NULL,
+        file_path        TEXT,
+        language         TEXT,
+        source_id        INTEGER REFERENCES transcripts(id) ON DELETE SET NULL,
+        title            TEXT,
+        segments         TEXT NOT NULL,
+        segment_count    INTEGER NOT NULL,
+        duration_seconds REAL NOT NULL,
+        created_at       TEXT NOT NULL
+    );
+    CREATE INDEX transcripts_by_file ON transcripts(file_path);
+    CREATE TABLE jobs (
+        id               INTEGER PRIMARY KEY AUTOINCREMENT,
+        job              TEXT NOT NULL,
+        outcome          TEXT NOT NULL,
+        inputs           TEXT NOT NULL,
+        outputs          TEXT NOT NULL,
+        failed           INTEGER NOT NULL,
+        error            TEXT,
+        duration_seconds REAL NOT NULL,
+        finished_at      TEXT NOT NULL
+    );",
+    // 2: files were stamped in local time, which doesn't sort across an
+    // offset change; store UTC like everything written since
+    "UPDATE files SET recorded_at = strftime('%Y-%m-%dT%H:%M:%fZ', recorded_at)
+     WHERE strftime('%Y-%m-%dT%H:%M:%fZ', recorded_at) IS NOT NULL",
+];
+
+/// Bring the schema up to date, each step in its own transaction
+///
+/// A database written by a newer build (a higher version than we know) is
+/// left alone; its extra tables and columns don't get in our way.
+pub(super) fn migrate(conn: &mut Connection) -> rusqlite::Result<()> {
+    let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for (index, step) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(step)?;
+        tx.pragma_update(None, "user_version", index + 1)?;
+        tx.commit()?;
+        info!(version = index + 1, "Migrated history database");
+    }
+    Ok(())
+}
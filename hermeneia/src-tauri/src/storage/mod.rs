@@ -0,0 +1,576 @@
+// src-tauri/src/storage/mod.rs
+// History kept across restarts: transcripts, translations, files and jobs
+
+//! History kept across restarts
+//!
+//! A SQLite database in the app data folder holds transcripts and
+//! translations the user saved, what was learned about audio files, and
+//! the outcome of every long-running job. The schema is brought up to date
+//! whenever the database is opened.
+//!
+//! ```no_run
+//! use hermeneia_lib::storage::{HistoryStore, TranscriptKind};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let history = HistoryStore::open_default()?;
+//! for saved in history.transcripts(Some(TranscriptKind::Translation), None)? {
+//!     println!("{} {:?} {}", saved.id, saved.language, saved.created_at);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod migrations;
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::audio::AudioInfo;
+use crate::error::{AudioError, Result};
+use crate::notify::{JobEvent, JobOutcome};
+use crate::settings::APP_IDENTIFIER;
+use crate::transcribe::Segment;
+
+/// The database file, inside the app data folder
+const HISTORY_FILE: &str = "history.sqlite3";
+
+/// How long to wait for another connection's write to finish
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How many finished jobs [`record_job_quietly`] keeps
+pub const JOB_HISTORY_LIMIT: usize = 500;
+
+impl From<rusqlite::Error> for AudioError {
+    fn from(error: rusqlite::Error) -> Self {
+        AudioError::Storage(error.to_string())
+    }
+}
+
+/// Where the history database lives; `None` if the platform has no data
+/// folder
+pub fn history_path() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join(APP_IDENTIFIER).join(HISTORY_FILE))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptKind {
+    Transcription,
+    Translation,
+}
+
+/// A transcript or translation to save
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewTranscript {
+    pub kind: TranscriptKind,
+    /// The audio it was made from
+    #[serde(default)]
+    pub file_path: Option<String>,
+    /// Language code of the text, e.g. "en"
+    #[serde(default)]
+    pub language: Option<String>,
+    /// For a translation, the saved transcript it was translated from
+    #[serde(default)]
+    pub source_id: Option<i64>,
+    #[serde(default)]
+    pub title: Option<String>,
+    pub segments: Vec<Segment>,
+}
+
+/// A saved transcript or translation, without its text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptSummary {
+    pub id: i64,
+    pub kind: TranscriptKind,
+    pub file_path: Option<String>,
+    pub language: Option<String>,
+    /// `None` once the source has been deleted
+    pub source_id: Option<i64>,
+    pub title: Option<String>,
+    pub segment_count: usize,
+    /// End of the last segment
+    pub duration_seconds: f64,
+    /// UTC, RFC 3339
+    pub created_at: String,
+}
+
+/// A saved transcript or translation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptRecord {
+    #[serde(flatten)]
+    pub summary: TranscriptSummary,
+    pub segments: Vec<Segment>,
+}
+
+/// What was last learned about an audio file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileRecord {
+    pub path: String,
+    pub info: AudioInfo,
+    /// UTC, RFC 3339 with milliseconds, so it sorts as text
+    pub recorded_at: String,
+}
+
+/// A finished job, as recorded
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobRecord {
+    pub id: i64,
+    #[serde(flatten)]
+    pub event: JobEvent,
+}
+
+/// A connection to the history database
+///
+/// Connections are cheap; open one where it's needed rather than sharing
+/// it between threads. Writers wait for each other for a few seconds
+/// before giving up.
+pub struct HistoryStore {
+    conn: Connection,
+}
+
+impl HistoryStore {
+    /// Open or create the database at `path`, creating its folder and
+    /// bringing the schema up to date
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut conn = Connection::open(path)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+        migrations::migrate(&mut conn)?;
+        Ok(Self { conn })
+    }
+
+    /// Open the database in the app data folder
+    pub fn open_default() -> Result<Self> {
+        let path = history_path()
+            .ok_or_else(|| AudioError::Storage("no data folder on this system".to_string()))?;
+        Self::open(path)
+    }
+
+    /// Save a transcript or translation
+    ///
+    /// # Returns
+    /// Its id
+    pub fn save_transcript(&self, transcript: &NewTranscript) -> Result<i64> {
+        let duration = transcript
+            .segments
+            .iter()
+            .map(|segment| segment.end.as_seconds())
+            .fold(0.0, f64::max);
+        self.conn.execute(
+            "INSERT INTO transcripts (kind, file_path, language, source_id, title, segments,
+                                      segment_count, duration_seconds, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                to_text(&transcript.kind),
+                transcript.file_path,
+                transcript.language,
+                transcript.source_id,
+                transcript.title,
+                to_json(&transcript.segments)?,
+                transcript.segments.len(),
+                duration,
+                now(),
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Saved transcripts and translations, newest first, optionally only
+    /// one kind or those made from one file
+    pub fn transcripts(
+        &self,
+        kind: Option<TranscriptKind>,
+        file_path: Option<&str>,
+    ) -> Result<Vec<TranscriptSummary>> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, kind, file_path, language, source_id, title, segment_count,
+                    duration_seconds, created_at
+             FROM transcripts
+             WHERE (?1 IS NULL OR kind = ?1) AND (?2 IS NULL OR file_path = ?2)
+             ORDER BY id DESC",
+        )?;
+        let kind = kind.map(|kind| to_text(&kind));
+        let rows = statement.query_map(params![kind, file_path], summary)?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// A saved transcript or translation with its segments
+    pub fn transcript(&self, id: i64) -> Result<Option<TranscriptRecord>> {
+        let record = self
+            .conn
+            .query_row(
+                "SELECT id, kind, file_path, language, source_id, title, segment_count,
+                        duration_seconds, created_at, segments
+                 FROM transcripts WHERE id = ?1",
+                [id],
+                |row| {
+                    Ok(TranscriptRecord {
+                        summary: summary(row)?,
+                        segments: json_column(row, 9)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(record)
+    }
+
+    /// Delete a saved transcript or translation; translations made from it
+    /// are kept
+    ///
+    /// # Returns
+    /// `false` if there was no such transcript
+    pub fn delete_transcript(&self, id: i64) -> Result<bool> {
+        Ok(self.conn.execute("DELETE FROM transcripts WHERE id = ?1", [id])? > 0)
+    }
+
+    /// Remember what's known about an audio file, replacing what was
+    /// recorded before
+    pub fn save_file(&self, path: &str, info: &AudioInfo) -> Result<FileRecord> {
+        let record = FileRecord {
+            path: path.to_string(),
+            info: info.clone(),
+            recorded_at: now(),
+        };
+        self.conn.execute(
+            "INSERT OR REPLACE INTO files (path, info, recorded_at) VALUES (?1, ?2, ?3)",
+            params![record.path, to_json(&record.info)?, record.recorded_at],
+        )?;
+        Ok(record)
+    }
+
+    /// Every file recorded, most recently recorded first
+    pub fn files(&self) -> Result<Vec<FileRecord>> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT path, info, recorded_at FROM files ORDER BY recorded_at DESC")?;
+        let rows = statement.query_map([], file_record)?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn file(&self, path: &str) -> Result<Option<FileRecord>> {
+        let record = self
+            .conn
+            .query_row(
+                "SELECT path, info, recorded_at FROM files WHERE path = ?1",
+                [path],
+                file_record,
+            )
+            .optional()?;
+        Ok(record)
+    }
+
+    /// Forget a file; transcripts made from it are kept
+    ///
+    /// # Returns
+    /// `false` if the file wasn't recorded
+    pub fn delete_file(&self, path: &str) -> Result<bool> {
+        Ok(self.conn.execute("DELETE FROM files WHERE path = ?1", [path])? > 0)
+    }
+
+    /// Record how a job finished
+    ///
+    /// # Returns
+    /// The record's id
+    pub fn record_job(&self, event: &JobEvent) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO jobs (job, outcome, inputs, outputs, failed, error, duration_seconds,
+                               finished_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                event.job,
+                to_text(&event.outcome),
+                to_json(&event.inputs)?,
+                to_json(&event.outputs)?,
+                event.failed,
+                event.error,
+                event.duration_seconds,
+                event.finished_at,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// The most recent `limit` jobs, newest first
+    pub fn jobs(&self, limit: usize) -> Result<Vec<JobRecord>> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, job, outcome, inputs, outputs, failed, error, duration_seconds,
+                    finished_at
+             FROM jobs ORDER BY id DESC LIMIT ?1",
+        )?;
+        let rows = statement.query_map([limit], |row| {
+            Ok(JobRecord {
+                id: row.get(0)?,
+                event: JobEvent {
+                    job: row.get(1)?,
+                    outcome: text_column::<JobOutcome>(row, 2)?,
+                    inputs: json_column(row, 3)?,
+                    outputs: json_column(row, 4)?,
+                    failed: row.get(5)?,
+                    error: row.get(6)?,
+                    duration_seconds: row.get(7)?,
+                    finished_at: row.get(8)?,
+                },
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// # Returns
+    /// `false` if there was no such record
+    pub fn delete_job(&self, id: i64) -> Result<bool> {
+        Ok(self.conn.execute("DELETE FROM jobs WHERE id = ?1", [id])? > 0)
+    }
+
+    /// Forget all but the newest `keep` jobs
+    ///
+    /// # Returns
+    /// How many were removed
+    pub fn prune_jobs(&self, keep: usize) -> Result<usize> {
+        Ok(self.conn.execute(
+            "DELETE FROM jobs WHERE id NOT IN (SELECT id FROM jobs ORDER BY id DESC LIMIT ?1)",
+            [keep],
+        )?)
+    }
+
+    /// Forget every job
+    ///
+    /// # Returns
+    /// How many were removed
+    pub fn clear_jobs(&self) -> Result<usize> {
+        Ok(self.conn.execute("DELETE FROM jobs", [])?)
+    }
+}
+
+/// Record a finished job in the default database, keeping only the newest
+/// [`JOB_HISTORY_LIMIT`], and logging rather than failing if it can't be
+/// written
+pub fn record_job_quietly(event: &JobEvent) {
+    let recorded = HistoryStore::open_default().and_then(|store| {
+        store.record_job(event)?;
+        store.prune_jobs(JOB_HISTORY_LIMIT)
+    });
+    if let Err(e) = recorded {
+        tracing::warn!(job = %event.job, "Couldn't record the job in history: {}", e);
+    }
+}
+
+/// The current time as stored: UTC with milliseconds, which sorts as text
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+fn summary(row: &Row) -> rusqlite::Result<TranscriptSummary> {
+    Ok(TranscriptSummary {
+        id: row.get(0)?,
+        kind: text_column(row, 1)?,
+        file_path: row.get(2)?,
+        language: row.get(3)?,
+        source_id: row.get(4)?,
+        title: row.get(5)?,
+        segment_count: row.get(6)?,
+        duration_seconds: row.get(7)?,
+        created_at: row.get(8)?,
+    })
+}
+
+fn file_record(row: &Row) -> rusqlite::Result<FileRecord> {
+    Ok(FileRecord {
+        path: row.get(0)?,
+        info: json_column(row, 1)?,
+        recorded_at: row.get(2)?,
+    })
+}
+
+fn to_json<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+    serde_json::to_string(value).map_err(|e| AudioError::Storage(e.to_string()))
+}
+
+/// A unit enum as its serde name, e.g. "translation"
+fn to_text<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn json_column<T: DeserializeOwned>(row: &Row, index: usize) -> rusqlite::Result<T> {
+    let text: String = row.get(index)?;
+    serde_json::from_str(&text)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(index, Type::Text, Box::new(e)))
+}
+
+fn text_column<T: DeserializeOwned>(row: &Row, index: usize) -> rusqlite::Result<T> {
+    let text: String = row.get(index)?;
+    serde_json::from_value(serde_json::Value::String(text))
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(index, Type::Text, Box::new(e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::Timestamp;
+
+    fn temp_store(name: &str) -> (HistoryStore, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("hermeneia_test_storage_{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        let store = HistoryStore::open(dir.join(HISTORY_FILE)).unwrap();
+        (store, dir)
+    }
+
+    fn said(start: f64, end: f64, text: &str) -> Segment {
+        Segment {
+            start: Timestamp::from_seconds(start),
+            end: Timestamp::from_seconds(end),
+            text: text.to_string(),
+            words: Vec::new(),
+            speaker: None,
+        }
+    }
+
+    #[test]
+    fn test_transcripts_round_trip_and_survive_reopening() {
+        let (store, dir) = temp_store("transcripts");
+        let spoken = NewTranscript {
+            kind: TranscriptKind::Transcription,
+            file_path: Some("/sermons/john1.mp3".to_string()),
+            language: Some("en".to_string()),
+            source_id: None,
+            title: Some("John 1".to_string()),
+            segments: vec![said(0.0, 4.0, "In the beginning"), said(4.0, 9.5, "was the Word")],
+        };
+        let id = store.save_transcript(&spoken).unwrap();
+        let translated = NewTranscript {
+            kind: TranscriptKind::Translation,
+            language: Some("es".to_string()),
+            source_id: Some(id),
+            segments: vec![said(0.0, 9.5, "En el principio era el Verbo")],
+            ..spoken.clone()
+        };
+        let translation = store.save_transcript(&translated).unwrap();
+        drop(store);
+
+        let store = HistoryStore::open(dir.join(HISTORY_FILE)).unwrap();
+        let all = store.transcripts(None, None).unwrap();
+        assert_eq!(all.iter().map(|t| t.id).collect::<Vec<_>>(), [translation, id]);
+        let only = store.transcripts(Some(TranscriptKind::Transcription), None).unwrap();
+        assert_eq!(only.len(), 1);
+        assert_eq!((only[0].segment_count, only[0].duration_seconds), (2, 9.5));
+        assert!(store.transcripts(None, Some("/other.mp3")).unwrap().is_empty());
+
+        let record = store.transcript(id).unwrap().unwrap();
+        assert_eq!(record.segments, spoken.segments);
+        assert_eq!(record.summary.title.as_deref(), Some("John 1"));
+
+        // Deleting the source keeps the translation but unlinks it
+        assert!(store.delete_transcript(id).unwrap());
+        assert!(!store.delete_transcript(id).unwrap());
+        assert!(store.transcript(id).unwrap().is_none());
+        let kept = store.transcript(translation).unwrap().unwrap();
+        assert_eq!(kept.summary.source_id, None);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_files_and_jobs() {
+        let (store, dir) = temp_store("files_jobs");
+        let info = AudioInfo {
+            duration_seconds: 61.0,
+            sample_rate: 44100,
+            channels: 2,
+            format: "MP3".to_string(),
+            bit_depth: None,
+            container: None,
+            codec_profile: None,
+            bitrate_kbps: Some(128),
+            file_size_bytes: 976_000,
+            tags: Default::default(),
+        };
+        store.save_file("/a.mp3", &info).unwrap();
+        store.save_file("/a.mp3", &AudioInfo { channels: 1, ..info }).unwrap();
+        let files = store.files().unwrap();
+        assert_eq!(files.len(), 1, "saving again replaces the record");
+        assert_eq!(store.file("/a.mp3").unwrap().unwrap().info.channels, 1);
+        assert!(store.delete_file("/a.mp3").unwrap());
+        assert!(store.file("/a.mp3").unwrap().is_none());
+
+        let inputs = |path: &str| vec![path.to_string()];
+        let done = JobEvent::new("export_audio", inputs("/a.mp3"), inputs("/a.wav"), None)
+            .took(Duration::from_millis(1500));
+        let error = Some("Operation was cancelled".to_string());
+        let failed = JobEvent::new("trim_audio_file", inputs("/b.mp3"), Vec::new(), error);
+        store.record_job(&done).unwrap();
+        let second = store.record_job(&failed).unwrap();
+        let jobs = store.jobs(10).unwrap();
+        assert_eq!(jobs.len(), 2);
+        assert_eq!((jobs[0].id, &jobs[0].event), (second, &failed));
+        assert_eq!(jobs[1].event, done);
+        assert_eq!(store.jobs(1).unwrap().len(), 1);
+        assert!(store.delete_job(second).unwrap());
+        let third = store.record_job(&failed).unwrap();
+        assert_eq!(store.prune_jobs(1).unwrap(), 1);
+        assert_eq!(store.jobs(10).unwrap()[0].id, third, "the newest is kept");
+        assert_eq!(store.prune_jobs(1).unwrap(), 0);
+        assert_eq!(store.clear_jobs().unwrap(), 1);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_local_times_are_migrated_to_utc() {
+        let dir = std::env::temp_dir().join("hermeneia_test_storage_utc");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut conn = Connection::open(dir.join(HISTORY_FILE)).unwrap();
+        conn.execute_batch(migrations::MIGRATIONS[0]).unwrap();
+        conn.pragma_update(None, "user_version", 1).unwrap();
+        // Across the end of summer time the later recording sorts first as
+        // local text
+        for (path, at) in [
+            ("/early.mp3", "2026-10-25T02:30:00.5+02:00"),
+            ("/late.mp3", "2026-10-25T02:10:00+01:00"),
+        ] {
+            conn.execute(
+                "INSERT INTO files (path, info, recorded_at) VALUES (?1, '{}', ?2)",
+                [path, at],
+            )
+            .unwrap();
+        }
+        migrations::migrate(&mut conn).unwrap();
+        let times: Vec<(String, String)> = conn
+            .prepare("SELECT path, recorded_at FROM files ORDER BY recorded_at DESC")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(
+            times,
+            [
+                ("/late.mp3".to_string(), "2026-10-25T01:10:00.000Z".to_string()),
+                ("/early.mp3".to_string(), "2026-10-25T00:30:00.500Z".to_string()),
+            ]
+        );
+        assert_eq!(now().len(), times[0].1.len(), "new rows use the same format");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_migrations_run_once() {
+        let (store, dir) = temp_store("migrations");
+        drop(store);
+        let mut conn = Connection::open(dir.join(HISTORY_FILE)).unwrap();
+        let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, migrations::MIGRATIONS.len());
+        migrations::migrate(&mut conn).unwrap();
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,1151 @@
+// src-tauri/src/subtitles.rs
+// SRT, WebVTT and styled ASS/SSA subtitles from transcripts, optionally
+// bilingual, and SRT/WebVTT files read back as transcripts
+
+use std::fmt::Write as _;
+use std::ops::Range;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::render::Color;
+use crate::audio::time::Timestamp;
+use crate::error::{AudioError, DecodeError, Result};
+use crate::karaoke::{ass_color, ass_text, ass_time, script_info, ssa_color};
+use crate::transcribe::Segment;
+
+/// Color of lines with no speaker
+const DEFAULT_COLOR: Color = Color::rgb(0xff, 0xff, 0xff);
+
+/// Colors given in turn to speakers without a style of their own; none is
+/// white, so a speaker never looks like a line with no speaker
+const SPEAKER_COLORS: [Color; 5] = [
+    Color::rgb(0xff, 0xd2, 0x3f),
+    Color::rgb(0x6f, 0xe3, 0xff),
+    Color::rgb(0x9c, 0xf2, 0x8c),
+    Color::rgb(0xff, 0x9e, 0xc8),
+    Color::rgb(0xff, 0xa8, 0x4a),
+];
+
+/// Style for lines with no speaker
+const DEFAULT_STYLE: &str = "Default";
+
+/// One subtitle: lines shown together from `start` to `end`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cue {
+    pub start: Timestamp,
+    pub end: Timestamp,
+    pub lines: Vec<String>,
+    /// Who is talking; ASS/SSA give each speaker a style
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+}
+
+/// How a transcript and its translation share the screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BilingualLayout {
+    /// Each cue shows the original with the translation under it
+    #[default]
+    Stacked,
+    /// The original for the first half of each cue's time, then the
+    /// translation for the second, for screens with room for one language
+    Alternating,
+}
+
+/// Where on the screen a speaker's lines go
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Placement {
+    BottomLeft,
+    #[default]
+    Bottom,
+    BottomRight,
+    TopLeft,
+    Top,
+    TopRight,
+}
+
+impl Placement {
+    /// ASS alignment, laid out like a numeric keypad
+    fn ass_alignment(self) -> u8 {
+        match self {
+            Self::BottomLeft => 1,
+            Self::Bottom => 2,
+            Self::BottomRight => 3,
+            Self::TopLeft => 7,
+            Self::Top => 8,
+            Self::TopRight => 9,
+        }
+    }
+
+    /// SSA alignment: left, center or right, plus 4 for the top
+    fn ssa_alignment(self) -> u8 {
+        match self {
+            Self::BottomLeft => 1,
+            Self::Bottom => 2,
+            Self::BottomRight => 3,
+            Self::TopLeft => 5,
+            Self::Top => 6,
+            Self::TopRight => 7,
+        }
+    }
+}
+
+/// Look of one speaker's lines in ASS/SSA
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeakerStyle {
+    /// The speaker label, as in the transcript
+    pub speaker: String,
+    pub color: Color,
+    #[serde(default)]
+    pub placement: Placement,
+}
+
+/// Look of ASS/SSA subtitles; SRT and WebVTT carry no styling
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubtitleStyle {
+    pub font: String,
+    pub font_size: u32,
+    pub outline: Color,
+    /// Video size the positions and font size refer to
+    pub width: u32,
+    pub height: u32,
+    /// Distance of the text from the edges
+    pub margin: u32,
+    /// Styles for particular speakers; the others get a color each, in
+    /// order of appearance, at the bottom of the screen
+    pub speakers: Vec<SpeakerStyle>,
+}
+
+impl Default for SubtitleStyle {
+    fn default() -> Self {
+        Self {
+            font: "Arial".to_string(),
+            font_size: 64,
+            outline: Color::rgb(0, 0, 0),
+            width: 1920,
+            height: 1080,
+            margin: 80,
+            speakers: Vec::new(),
+        }
+    }
+}
+
+impl SubtitleStyle {
+    /// A style per speaker in `cues` (plus the default style), with the
+    /// name each is written under
+    fn resolve(&self, cues: &[Cue]) -> Vec<(String, SpeakerStyle)> {
+        let mut styles = vec![(
+            DEFAULT_STYLE.to_string(),
+            SpeakerStyle {
+                speaker: String::new(),
+                color: DEFAULT_COLOR,
+                placement: Placement::Bottom,
+            },
+        )];
+        let mut unstyled = 0;
+        for speaker in cues.iter().filter_map(|cue| cue.speaker.as_deref()) {
+            let name = style_name(speaker);
+            // Players match style names ignoring case
+            if name.is_empty()
+                || styles.iter().any(|(known, _)| known.eq_ignore_ascii_case(&name))
+            {
+                continue;
+            }
+            let style = match self.speakers.iter().find(|s| s.speaker == speaker) {
+                Some(style) => style.clone(),
+                None => {
+                    unstyled += 1;
+                    SpeakerStyle {
+                        speaker: speaker.to_string(),
+                        color: SPEAKER_COLORS[(unstyled - 1) % SPEAKER_COLORS.len()],
+                        placement: Placement::Bottom,
+                    }
+                }
+            };
+            styles.push((name, style));
+        }
+        styles
+    }
+}
+
+/// Subtitle formats with no styling, for [`export_srt`] and [`export_vtt`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptionFormat {
+    Srt,
+    Vtt,
+}
+
+/// How much text a SRT or WebVTT cue may hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LineLimits {
+    /// Characters per line; 42 is the usual broadcast limit. A single word
+    /// longer than this gets a line of its own rather than being broken.
+    pub max_chars: usize,
+    /// Lines per cue; longer segments are split into several cues
+    pub max_lines: usize,
+    /// Say who is talking: "Name: " in SRT where the speaker changes, a
+    /// voice tag on every cue in WebVTT
+    pub speaker_labels: bool,
+}
+
+impl Default for LineLimits {
+    fn default() -> Self {
+        Self {
+            max_chars: 42,
+            max_lines: 2,
+            speaker_labels: true,
+        }
+    }
+}
+
+/// One cue per segment, with the segment's timing
+pub fn cues(segments: &[Segment]) -> Vec<Cue> {
+    segments
+        .iter()
+        .map(|segment| Cue {
+            start: segment.start,
+            end: segment.end,
+            lines: text_lines(&segment.text),
+            speaker: segment.speaker.clone(),
+        })
+        .filter(|cue| !cue.lines.is_empty())
+        .collect()
+}
+
+/// Cues whose lines fit `limits`, splitting long segments
+///
+/// Text is rewrapped at spaces. A segment too long for one cue becomes
+/// several, split at the first word of each; with word timing (one timed
+/// word per word of text) they change when that word is spoken, otherwise
+/// the segment's time is shared out by characters. With speaker labels,
+/// the first cue after a change of speaker starts with "Name: ".
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::Timestamp;
+/// use hermeneia_lib::subtitles::{to_srt, wrapped_cues, LineLimits};
+/// use hermeneia_lib::transcribe::Segment;
+///
+/// let segment = Segment {
+///     start: Timestamp::from_seconds(0.0),
+///     end: Timestamp::from_seconds(3.1),
+///     text: "Blessed are the poor in spirit".to_string(),
+///     words: Vec::new(),
+///     speaker: None,
+/// };
+/// let limits = LineLimits { max_chars: 16, max_lines: 1, ..LineLimits::default() };
+/// assert_eq!(
+///     to_srt(&wrapped_cues(&[segment], &limits)),
+///     "1\n00:00:00,000 --> 00:00:01,600\nBlessed are the\n\n\
+///      2\n00:00:01,600 --> 00:00:03,100\npoor in spirit\n\n"
+/// );
+/// ```
+pub fn wrapped_cues(segments: &[Segment], limits: &LineLimits) -> Vec<Cue> {
+    let max_lines = limits.max_lines.max(1);
+    let mut cues = Vec::new();
+    let mut last_speaker = None;
+    for segment in segments {
+        let words: Vec<&str> = segment.text.split_whitespace().collect();
+        if words.is_empty() {
+            continue;
+        }
+        let label = match &segment.speaker {
+            Some(speaker) if limits.speaker_labels && last_speaker != Some(speaker) => {
+                format!("{}: ", speaker)
+            }
+            _ => String::new(),
+        };
+        last_speaker = segment.speaker.as_ref();
+
+        let lines = wrap(&words, label.chars().count(), limits.max_chars);
+        // Characters before each word, to share out time without word timing
+        let mut offsets = Vec::with_capacity(words.len() + 1);
+        let mut total = 0;
+        for word in &words {
+            offsets.push(total);
+            total += word.chars().count() + 1;
+        }
+        let timed = segment.words.len() == words.len();
+        let span = segment.end.saturating_since(segment.start).as_seconds();
+        let time_at = |word: usize| match word {
+            0 => segment.start,
+            _ if word >= words.len() => segment.end,
+            _ if timed => segment.words[word].start.clamp(segment.start, segment.end),
+            _ => {
+                let share = offsets[word] as f64 / total as f64;
+                Timestamp::from_seconds(segment.start.as_seconds() + span * share)
+            }
+        };
+
+        for chunk in lines.chunks(max_lines) {
+            let (first, last) = (chunk[0].start, chunk[chunk.len() - 1].end);
+            let mut text: Vec<String> =
+                chunk.iter().map(|line| words[line.clone()].join(" ")).collect();
+            if first == 0 {
+                text[0].insert_str(0, &label);
+            }
+            cues.push(Cue {
+                start: time_at(first),
+                end: time_at(last),
+                lines: text,
+                speaker: segment.speaker.clone(),
+            });
+        }
+    }
+    cues
+}
+
+/// Merge a transcript and its translation into bilingual cues
+///
+/// All timing comes from `source`. Translated segments are matched to
+/// source segments one to one when there are as many of each; otherwise
+/// each goes to the source segment it overlaps most, so a translation
+/// that split or joined sentences still lines up. Source segments nothing
+/// was matched to show the original alone.
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::Timestamp;
+/// use hermeneia_lib::subtitles::{merge_bilingual, to_srt, BilingualLayout};
+/// use hermeneia_lib::transcribe::Segment;
+///
+/// let segment = |text: &str| Segment {
+///     start: Timestamp::from_seconds(1.0),
+///     end: Timestamp::from_seconds(3.0),
+///     text: text.to_string(),
+///     words: Vec::new(),
+///     speaker: None,
+/// };
+/// let cues = merge_bilingual(
+///     &[segment("In the beginning")],
+///     &[segment("Au commencement")],
+///     BilingualLayout::Stacked,
+/// );
+/// assert_eq!(
+///     to_srt(&cues),
+///     "1\n00:00:01,000 --> 00:00:03,000\nIn the beginning\nAu commencement\n\n"
+/// );
+/// ```
+pub fn merge_bilingual(
+    source: &[Segment],
+    translation: &[Segment],
+    layout: BilingualLayout,
+) -> Vec<Cue> {
+    let mut translated: Vec<Vec<&str>> = vec![Vec::new(); source.len()];
+    if source.len() == translation.len() {
+        for (lines, segment) in translated.iter_mut().zip(translation) {
+            lines.push(&segment.text);
+        }
+    } else if !source.is_empty() {
+        for segment in translation {
+            translated[best_match(source, segment)].push(&segment.text);
+        }
+    }
+
+    let mut cues = Vec::with_capacity(source.len() * 2);
+    for (segment, translation) in source.iter().zip(translated) {
+        let original = text_lines(&segment.text);
+        let translation = text_lines(&translation.join(" "));
+        let speaker = segment.speaker.clone();
+        match layout {
+            BilingualLayout::Stacked => cues.push(Cue {
+                start: segment.start,
+                end: segment.end,
+                lines: [original, translation].concat(),
+                speaker,
+            }),
+            BilingualLayout::Alternating if translation.is_empty() => cues.push(Cue {
+                start: segment.start,
+                end: segment.end,
+                lines: original,
+                speaker,
+            }),
+            BilingualLayout::Alternating => {
+                let middle = segment.start.midpoint(segment.end);
+                cues.push(Cue {
+                    start: segment.start,
+                    end: middle,
+                    lines: original,
+                    speaker: speaker.clone(),
+                });
+                cues.push(Cue {
+                    start: middle,
+                    end: segment.end,
+                    lines: translation,
+                    speaker,
+                });
+            }
+        }
+    }
+    cues.retain(|cue| !cue.lines.is_empty());
+    cues
+}
+
+/// SubRip (SRT)
+pub fn to_srt(cues: &[Cue]) -> String {
+    let mut srt = String::new();
+    for (i, cue) in cues.iter().enumerate() {
+        let _ = writeln!(
+            srt,
+            "{}\n{} --> {}\n{}\n",
+            i + 1,
+            timecode(cue.start, ','),
+            timecode(cue.end, ','),
+            cue.lines.join("\n")
+        );
+    }
+    srt
+}
+
+/// WebVTT
+pub fn to_vtt(cues: &[Cue]) -> String {
+    format_vtt(cues, false)
+}
+
+/// WebVTT, with `voices` starting each cue with a voice tag naming its
+/// speaker
+fn format_vtt(cues: &[Cue], voices: bool) -> String {
+    let mut vtt = String::from("WEBVTT\n\n");
+    for cue in cues {
+        let mut lines: Vec<String> = cue.lines.iter().map(|line| vtt_text(line)).collect();
+        if let (true, Some(speaker), Some(first)) = (voices, &cue.speaker, lines.first_mut()) {
+            first.insert_str(0, &format!("<v {}>", vtt_text(speaker)));
+        }
+        let _ = writeln!(
+            vtt,
+            "{} --> {}\n{}\n",
+            timecode(cue.start, '.'),
+            timecode(cue.end, '.'),
+            lines.join("\n")
+        );
+    }
+    vtt
+}
+
+/// Advanced SubStation Alpha (ASS), one style per speaker
+///
+/// Ready for burning in with tools such as ffmpeg's `subtitles` filter.
+pub fn to_ass(cues: &[Cue], style: &SubtitleStyle) -> String {
+    let mut ass = script_info("v4.00+", style.width, style.height);
+    ass.push_str(
+        "[V4+ Styles]\n\
+         Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, \
+         BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, \
+         BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n",
+    );
+    for (name, speaker) in style.resolve(cues) {
+        let _ = writeln!(
+            ass,
+            "Style: {},{},{},{c},{c},{},&H80000000,0,0,0,0,100,100,0,0,1,3,0,{},{m},{m},{m},1",
+            name,
+            style.font,
+            style.font_size,
+            ass_color(style.outline),
+            speaker.placement.ass_alignment(),
+            c = ass_color(speaker.color),
+            m = style.margin
+        );
+    }
+    ass.push_str(
+        "\n[Events]\n\
+         Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n",
+    );
+    for cue in cues {
+        let (style, name) = dialogue_names(cue);
+        let _ = writeln!(
+            ass,
+            "Dialogue: 0,{},{},{},{},0,0,0,,{}",
+            ass_time(cue.start),
+            ass_time(cue.end),
+            style,
+            name,
+            dialogue_text(cue)
+        );
+    }
+    ass
+}
+
+/// SubStation Alpha v4 (SSA), for players that predate ASS
+pub fn to_ssa(cues: &[Cue], style: &SubtitleStyle) -> String {
+    let mut ssa = script_info("v4.00", style.width, style.height);
+    ssa.push_str(
+        "[V4 Styles]\n\
+         Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, TertiaryColour, \
+         BackColour, Bold, Italic, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, \
+         MarginV, AlphaLevel, Encoding\n",
+    );
+    for (name, speaker) in style.resolve(cues) {
+        let _ = writeln!(
+            ssa,
+            "Style: {},{},{},{c},{c},{},0,0,0,1,3,0,{},{m},{m},{m},0,1",
+            name,
+            style.font,
+            style.font_size,
+            ssa_color(style.outline),
+            speaker.placement.ssa_alignment(),
+            c = ssa_color(speaker.color),
+            m = style.margin
+        );
+    }
+    ssa.push_str(
+        "\n[Events]\n\
+         Format: Marked, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n",
+    );
+    for cue in cues {
+        let (style, name) = dialogue_names(cue);
+        let _ = writeln!(
+            ssa,
+            "Dialogue: Marked=0,{},{},{},{},0000,0000,0000,,{}",
+            ass_time(cue.start),
+            ass_time(cue.end),
+            style,
+            name,
+            dialogue_text(cue)
+        );
+    }
+    ssa
+}
+
+/// Write subtitles, choosing SRT, WebVTT, ASS or SSA by the path's
+/// extension; `style` only applies to ASS and SSA
+///
+/// # Example
+/// ```no_run
+/// use hermeneia_lib::subtitles::{
+///     merge_bilingual, write_subtitles, BilingualLayout, SubtitleStyle,
+/// };
+/// use hermeneia_lib::transcribe::Transcript;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let (english, spanish): (Transcript, Transcript) = unimplemented!();
+/// let cues = merge_bilingual(&english.segments, &spanish.segments, BilingualLayout::Stacked);
+/// write_subtitles("sermon.en-es.vtt", &cues, &SubtitleStyle::default())?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn write_subtitles<P: AsRef<Path>>(path: P, cues: &[Cue], style: &SubtitleStyle) -> Result<()> {
+    let path = path.as_ref();
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    let contents = match extension.as_deref() {
+        Some("srt") => to_srt(cues),
+        Some("vtt") => to_vtt(cues),
+        Some("ass") => to_ass(cues, style),
+        Some("ssa") => to_ssa(cues, style),
+        _ => {
+            return Err(AudioError::UnsupportedFormat(format!(
+                "can't write subtitles to '{}'; use a .srt, .vtt, .ass or .ssa file",
+                path.display()
+            )))
+        }
+    };
+    std::fs::write(path, contents)?;
+    Ok(())
+}
+
+/// Write a transcript as SubRip (SRT), wrapped to `limits`
+pub fn export_srt<P: AsRef<Path>>(
+    segments: &[Segment],
+    path: P,
+    limits: &LineLimits,
+) -> Result<()> {
+    std::fs::write(path, to_srt(&wrapped_cues(segments, limits)))?;
+    Ok(())
+}
+
+/// Write a transcript as WebVTT, wrapped to `limits`
+///
+/// Speakers are named in voice tags, which players can style and which
+/// [`parse_vtt`] reads back, rather than in the text.
+pub fn export_vtt<P: AsRef<Path>>(
+    segments: &[Segment],
+    path: P,
+    limits: &LineLimits,
+) -> Result<()> {
+    let unlabelled = LineLimits {
+        speaker_labels: false,
+        ..*limits
+    };
+    let cues = wrapped_cues(segments, &unlabelled);
+    std::fs::write(path, format_vtt(&cues, limits.speaker_labels))?;
+    Ok(())
+}
+
+/// Parse SubRip (SRT) subtitles
+///
+/// Cue numbers are optional, and markup (`<i>`, `<font>`, `{\an8}`) is
+/// dropped from the text.
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::subtitles::parse_srt;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let cues = parse_srt("1\n00:00:01,000 --> 00:00:03,500\n<i>In the beginning</i>\n")?;
+/// assert_eq!(cues[0].lines, ["In the beginning"]);
+/// assert_eq!(cues[0].end.as_seconds(), 3.5);
+/// # Ok(())
+/// # }
+/// ```
+pub fn parse_srt(text: &str) -> Result<Vec<Cue>> {
+    let mut cues = Vec::new();
+    for (line, block) in blocks(text) {
+        let timing = block.iter().position(|l| l.contains("-->"));
+        let Some(timing) = timing.filter(|&i| i <= 1) else {
+            return Err(invalid(line, "expected a cue number or timing line").into());
+        };
+        let (start, end) = parse_timing(block[timing])
+            .ok_or_else(|| invalid(line + timing, "bad timing (expected hh:mm:ss,mmm)"))?;
+        let lines = block[timing + 1..]
+            .iter()
+            .map(|l| strip_markup(l))
+            .filter(|l| !l.is_empty())
+            .collect();
+        cues.push(Cue {
+            start,
+            end,
+            lines,
+            speaker: None,
+        });
+    }
+    Ok(cues)
+}
+
+/// Parse WebVTT subtitles
+///
+/// `NOTE`, `STYLE` and `REGION` blocks and cue settings are skipped. A
+/// cue whose lines all start with the same voice tag (`<v Pastor>`) gets
+/// that speaker; other tags are dropped and entities decoded.
+pub fn parse_vtt(text: &str) -> Result<Vec<Cue>> {
+    let text = text.trim_start_matches('\u{feff}');
+    if !text.starts_with("WEBVTT") {
+        return Err(invalid(0, "a WebVTT file starts with WEBVTT").into());
+    }
+    let mut cues = Vec::new();
+    for (line, block) in blocks(text).skip(1) {
+        let first = block[0];
+        if ["NOTE", "STYLE", "REGION"].iter().any(|kind| first.starts_with(kind)) {
+            continue;
+        }
+        let Some(timing) = block.iter().position(|l| l.contains("-->")).filter(|&i| i <= 1)
+        else {
+            return Err(invalid(line, "expected a cue identifier or timing line").into());
+        };
+        let (start, end) = parse_timing(block[timing])
+            .ok_or_else(|| invalid(line + timing, "bad timing (expected hh:mm:ss.mmm)"))?;
+        let voices: Vec<Option<&str>> = block[timing + 1..].iter().map(|l| voice(l)).collect();
+        let speaker = match voices.as_slice() {
+            [Some(first), ..] if voices.iter().all(|v| *v == Some(*first)) => {
+                Some(first.to_string())
+            }
+            _ => None,
+        };
+        let lines = block[timing + 1..]
+            .iter()
+            .map(|l| decode_entities(&strip_markup(l)))
+            .filter(|l| !l.is_empty())
+            .collect();
+        cues.push(Cue {
+            start,
+            end,
+            lines,
+            speaker,
+        });
+    }
+    Ok(cues)
+}
+
+/// Read an `.srt` or `.vtt` file
+///
+/// Files that say `WEBVTT` at the top are read as WebVTT whatever their
+/// extension; bytes that aren't valid UTF-8 are read as Latin-1.
+///
+/// # Errors
+/// [`DecodeError::Subtitles`] for a damaged file, with the line it broke on
+pub fn read_subtitles<P: AsRef<Path>>(path: P) -> Result<Vec<Cue>> {
+    let path = path.as_ref();
+    let bytes = std::fs::read(path)?;
+    let text = match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) => e.into_bytes().iter().map(|&b| b as char).collect(),
+    };
+    let text = text.trim_start_matches('\u{feff}');
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        _ if text.starts_with("WEBVTT") => parse_vtt(text),
+        Some("srt") => parse_srt(text),
+        Some("vtt") => parse_vtt(text),
+        _ => Err(AudioError::UnsupportedFormat(format!(
+            "can't read subtitles from '{}'; use a .srt or .vtt file",
+            path.display()
+        ))),
+    }
+}
+
+/// Cues as transcript segments, one per cue, its lines joined by spaces
+pub fn segments_from_cues(cues: &[Cue]) -> Vec<Segment> {
+    let mut segments: Vec<Segment> = cues
+        .iter()
+        .filter(|cue| !cue.lines.is_empty())
+        .map(|cue| Segment {
+            start: cue.start,
+            end: cue.end,
+            text: cue.lines.join(" "),
+            words: Vec::new(),
+            speaker: cue.speaker.clone(),
+        })
+        .collect();
+    segments.sort_by_key(|segment| segment.start);
+    segments
+}
+
+fn invalid(line: usize, message: &str) -> DecodeError {
+    DecodeError::Subtitles(format!("line {}: {}", line + 1, message))
+}
+
+/// Blank-line separated blocks and the 0-based line each starts on
+fn blocks(text: &str) -> impl Iterator<Item = (usize, Vec<&str>)> {
+    let mut lines = text.lines().map(|l| l.trim_end_matches('\r')).enumerate().peekable();
+    std::iter::from_fn(move || {
+        while lines.next_if(|(_, l)| l.trim().is_empty()).is_some() {}
+        let (start, first) = lines.next()?;
+        let mut block = vec![first];
+        while let Some((_, line)) = lines.next_if(|(_, l)| !l.trim().is_empty()) {
+            block.push(line);
+        }
+        Some((start, block))
+    })
+}
+
+/// `start --> end`, ignoring any cue settings after the end
+fn parse_timing(line: &str) -> Option<(Timestamp, Timestamp)> {
+    let (start, rest) = line.split_once("-->")?;
+    let end = rest.split_whitespace().next()?;
+    let (start, end) = (parse_timecode(start.trim())?, parse_timecode(end)?);
+    Some((start, end.max(start)))
+}
+
+/// `hh:mm:ss,mmm`, `hh:mm:ss.mmm` or `mm:ss.mmm`
+fn parse_timecode(text: &str) -> Option<Timestamp> {
+    let (clock, fraction) = text.split_once([',', '.']).unwrap_or((text, "0"));
+    let fraction: String = fraction.chars().chain("000".chars()).take(3).collect();
+    let millis: u64 = fraction.parse().ok()?;
+    let mut parts = clock.split(':').rev().map(|p| p.parse::<u64>().ok());
+    let seconds = parts.next()??;
+    let minutes = parts.next()??;
+    let hours = parts.next().unwrap_or(Some(0))?;
+    if parts.next().is_some() || seconds >= 60 || minutes >= 60 {
+        return None;
+    }
+    Some(Timestamp::from_millis(
+        ((hours * 60 + minutes) * 60 + seconds) * 1000 + millis,
+    ))
+}
+
+/// Text without HTML-style tags or ASS override blocks, trimmed
+///
+/// Only well-formed markup goes: a `<` followed by a tag name, `/` or a
+/// timestamp and closed by `>`, or a `{\` closed by `}`. Anything else,
+/// like the `<` in "x < y", is text.
+fn strip_markup(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(open) = rest.find(['<', '{']) {
+        let (before, tag) = rest.split_at(open);
+        text.push_str(before);
+        let (close, opens) = match tag.as_bytes() {
+            [b'<', next, ..] => ('>', next.is_ascii_alphanumeric() || *next == b'/'),
+            [b'{', b'\\', ..] => ('}', true),
+            _ => ('>', false),
+        };
+        match tag.find(close).filter(|_| opens) {
+            Some(end) => rest = &tag[end + 1..],
+            None => {
+                text.push_str(&tag[..1]);
+                rest = &tag[1..];
+            }
+        }
+    }
+    text.push_str(rest);
+    text.trim().to_string()
+}
+
+/// The speaker of a line starting with a WebVTT voice tag
+fn voice(line: &str) -> Option<&str> {
+    let tag = line.trim_start().strip_prefix("<v")?;
+    let (name, _) = tag.split_once('>')?;
+    let name = name.trim_start_matches(|c: char| c == '.' || c.is_alphanumeric());
+    Some(name.trim()).filter(|name| !name.is_empty())
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", "\u{a0}")
+        .replace("&lrm;", "\u{200e}")
+        .replace("&rlm;", "\u{200f}")
+        .replace("&amp;", "&")
+}
+
+/// Index of the source segment `segment` overlaps most; the nearest one
+/// when it overlaps none
+fn best_match(source: &[Segment], segment: &Segment) -> usize {
+    let overlap = |s: &Segment| segment.end.min(s.end).saturating_since(segment.start.max(s.start));
+    let distance = |s: &Segment| {
+        let (a, b) = (s.start.midpoint(s.end), segment.start.midpoint(segment.end));
+        a.max(b).saturating_since(a.min(b))
+    };
+    (0..source.len())
+        .max_by(|&a, &b| {
+            overlap(&source[a])
+                .cmp(&overlap(&source[b]))
+                .then(distance(&source[b]).cmp(&distance(&source[a])))
+        })
+        .unwrap_or(0)
+}
+
+/// ASS/SSA fields are comma-separated, so names can't hold commas
+fn field(speaker: &str) -> String {
+    speaker.replace(',', " ").trim().to_string()
+}
+
+/// The style a speaker's lines use
+///
+/// A speaker called "Default" (in any case) gets a style of their own
+/// rather than the one for lines with no speaker.
+fn style_name(speaker: &str) -> String {
+    let name = field(speaker);
+    if name.eq_ignore_ascii_case(DEFAULT_STYLE) {
+        return format!("{} (speaker)", name);
+    }
+    name
+}
+
+/// Style and Name fields of a cue's event
+fn dialogue_names(cue: &Cue) -> (String, String) {
+    match cue.speaker.as_deref().filter(|speaker| !field(speaker).is_empty()) {
+        Some(speaker) => (style_name(speaker), field(speaker)),
+        None => (DEFAULT_STYLE.to_string(), String::new()),
+    }
+}
+
+/// A cue's lines as one ASS/SSA event, broken with `\N`
+fn dialogue_text(cue: &Cue) -> String {
+    cue.lines.iter().map(|line| ass_text(line)).collect::<Vec<_>>().join("\\N")
+}
+
+/// The text's non-blank lines, trimmed; a blank line would end the cue
+fn text_lines(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Lines of at most `max_chars`, as ranges of `words`; the first line has
+/// `indent` characters fewer
+fn wrap(words: &[&str], indent: usize, max_chars: usize) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let (mut start, mut width) = (0, indent);
+    for (i, word) in words.iter().enumerate() {
+        let len = word.chars().count();
+        if i > start && width + 1 + len > max_chars {
+            lines.push(start..i);
+            (start, width) = (i, len);
+        } else {
+            width += len + usize::from(i > start);
+        }
+    }
+    lines.push(start..words.len());
+    lines
+}
+
+/// `hh:mm:ss,mmm` (SRT) or `hh:mm:ss.mmm` (WebVTT)
+fn timecode(time: Timestamp, separator: char) -> String {
+    let ms = time.to_frames(1000);
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+/// WebVTT reads `<` as a tag, `&` as an entity and `-->` as timing
+fn vtt_text(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcribe::Word;
+
+    fn segment(start: f64, end: f64, text: &str) -> Segment {
+        Segment {
+            start: Timestamp::from_seconds(start),
+            end: Timestamp::from_seconds(end),
+            text: text.to_string(),
+            words: Vec::new(),
+            speaker: None,
+        }
+    }
+
+    fn source() -> Vec<Segment> {
+        vec![
+            segment(1.0, 3.0, "The Lord is my shepherd;"),
+            segment(3.0, 5.5, "I shall not want."),
+            segment(3725.0, 3726.0, "Amen."),
+        ]
+    }
+
+    #[test]
+    fn test_stacked_srt() {
+        let translation = vec![
+            segment(0.0, 0.0, "El Señor es mi pastor;"),
+            segment(0.0, 0.0, "nada me faltará."),
+            segment(0.0, 0.0, "Amén."),
+        ];
+        let cues = merge_bilingual(&source(), &translation, BilingualLayout::Stacked);
+        assert_eq!(
+            to_srt(&cues),
+            "1\n00:00:01,000 --> 00:00:03,000\nThe Lord is my shepherd;\nEl Señor es mi pastor;\n\n\
+             2\n00:00:03,000 --> 00:00:05,500\nI shall not want.\nnada me faltará.\n\n\
+             3\n01:02:05,000 --> 01:02:06,000\nAmen.\nAmén.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_alternating_vtt_matched_by_overlap() {
+        // The translation joined the first two sentences and missed the
+        // last; it overlaps the first most
+        let translation = vec![segment(1.0, 4.0, "Jehová es mi pastor; nada me faltará. <i>")];
+        let cues = merge_bilingual(&source(), &translation, BilingualLayout::Alternating);
+        assert_eq!(
+            to_vtt(&cues),
+            "WEBVTT\n\n\
+             00:00:01.000 --> 00:00:02.000\nThe Lord is my shepherd;\n\n\
+             00:00:02.000 --> 00:00:03.000\nJehová es mi pastor; nada me faltará. &lt;i&gt;\n\n\
+             00:00:03.000 --> 00:00:05.500\nI shall not want.\n\n\
+             01:02:05.000 --> 01:02:06.000\nAmen.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_ass_styles_each_speaker() {
+        let mut segments = source();
+        segments[0].speaker = Some("Pastor".to_string());
+        segments[1].speaker = Some("Reader, 2nd".to_string());
+        let style = SubtitleStyle {
+            speakers: vec![SpeakerStyle {
+                speaker: "Reader, 2nd".to_string(),
+                color: Color::rgb(0x6f, 0xe3, 0xff),
+                placement: Placement::Top,
+            }],
+            ..SubtitleStyle::default()
+        };
+        let cues = merge_bilingual(&segments, &[], BilingualLayout::Stacked);
+        let ass = to_ass(&cues, &style);
+
+        let styles: Vec<_> = ass.lines().filter(|l| l.starts_with("Style:")).collect();
+        assert_eq!(styles.len(), 3);
+        assert!(styles[0].starts_with("Style: Default,Arial,64,&H00FFFFFF,&H00FFFFFF,"));
+        assert!(styles[1].starts_with("Style: Pastor,Arial,64,&H003FD2FF,"));
+        assert!(styles[2].starts_with("Style: Reader  2nd,Arial,64,&H00FFE36F,"));
+        assert!(styles[2].contains(",1,3,0,8,80,80,80,1"));
+
+        let events: Vec<_> = ass.lines().filter(|l| l.starts_with("Dialogue:")).collect();
+        assert_eq!(
+            events,
+            vec![
+                "Dialogue: 0,0:00:01.00,0:00:03.00,Pastor,Pastor,0,0,0,,The Lord is my shepherd;",
+                "Dialogue: 0,0:00:03.00,0:00:05.50,Reader  2nd,Reader  2nd,0,0,0,,\
+                 I shall not want.",
+                "Dialogue: 0,1:02:05.00,1:02:06.00,Default,,0,0,0,,Amen.",
+            ]
+        );
+
+        let ssa = to_ssa(&cues, &style);
+        assert!(ssa.contains("Style: Reader  2nd,Arial,64,16769903,16769903,0,0,0,0,1,3,0,6,"));
+        assert!(ssa.contains("Dialogue: Marked=0,0:00:01.00,0:00:03.00,Pastor,Pastor,"));
+    }
+
+    #[test]
+    fn test_speaker_named_default_keeps_own_style() {
+        let mut segments = source();
+        segments[0].speaker = Some("Default".to_string());
+        segments[1].speaker = Some("DEFAULT".to_string());
+        let cues = merge_bilingual(&segments, &[], BilingualLayout::Stacked);
+        let ass = to_ass(&cues, &SubtitleStyle::default());
+
+        let styles: Vec<_> = ass.lines().filter(|l| l.starts_with("Style:")).collect();
+        // Names differing only in case would share a style in the player anyway
+        assert_eq!(styles.len(), 2);
+        assert!(styles[0].starts_with("Style: Default,Arial,64,&H00FFFFFF,"));
+        assert!(styles[1].starts_with("Style: Default (speaker),Arial,64,&H003FD2FF,"));
+
+        let events: Vec<_> = ass.lines().filter(|l| l.starts_with("Dialogue:")).collect();
+        assert!(events[0].contains(",Default (speaker),Default,"));
+        assert!(events[2].contains(",Default,,"));
+    }
+
+    #[test]
+    fn test_write_picks_format_by_extension() {
+        let dir = std::env::temp_dir();
+        let cues = cues(&[segment(0.0, 1.0, "  one\n\n two ")]);
+        assert_eq!(cues[0].lines, vec!["one", "two"]);
+
+        let path = dir.join("hermeneia_test_subtitles.vtt");
+        write_subtitles(&path, &cues, &SubtitleStyle::default()).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().starts_with("WEBVTT\n"));
+        std::fs::remove_file(path).ok();
+
+        let path = dir.join("hermeneia_test_subtitles.txt");
+        let result = write_subtitles(path, &cues, &SubtitleStyle::default());
+        assert!(matches!(result, Err(AudioError::UnsupportedFormat(_))));
+    }
+
+    #[test]
+    fn test_parse_srt() {
+        let srt = "\u{feff}1\r\n00:00:01,000 --> 00:00:03,250\r\n\
+                   {\\an8}<i>Grace</i> and peace\r\n\r\n\n\
+                   00:01:02,5 --> 00:01:04,000 X1:10 X2:20\nto you\nfrom God\n";
+        let cues = parse_srt(srt.trim_start_matches('\u{feff}')).unwrap();
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].lines, ["Grace and peace"]);
+        assert_eq!(cues[0].end, Timestamp::from_seconds(3.25));
+        assert_eq!(cues[1].start, Timestamp::from_seconds(62.5));
+        assert_eq!(cues[1].lines, ["to you", "from God"]);
+
+        let error = parse_srt("1\n00:00:01 -> 00:00:02\nBroken\n").unwrap_err();
+        assert_eq!(error.code_name(), "decode.subtitles");
+        assert!(parse_srt("1\n00:00:01,000 --> 00:61:00,000\nBad\n").is_err());
+    }
+
+    #[test]
+    fn test_strip_markup_keeps_stray_brackets() {
+        assert_eq!(strip_markup("if x < y then"), "if x < y then");
+        assert_eq!(strip_markup("x < y and y > z"), "x < y and y > z");
+        assert_eq!(strip_markup("<b>Bold</b> and <font color=\"red\">red</font>"), "Bold and red");
+        assert_eq!(strip_markup("<00:00:01.500>word <c.yellow>by</c> word"), "word by word");
+        assert_eq!(strip_markup("{\\an8}{\\i1}Top{\\i0}"), "Top");
+        assert_eq!(strip_markup("a {set} of <3 things <"), "a {set} of <3 things <");
+        assert_eq!(strip_markup("<i>open</i> <i never closed"), "open <i never closed");
+    }
+
+    #[test]
+    fn test_parse_vtt_and_round_trip() {
+        let vtt = "WEBVTT - from the archive\n\n\
+                   NOTE exported by hand\n\n\
+                   STYLE\n::cue { color: yellow }\n\n\
+                   intro\n00:01.000 --> 00:04.000 align:start\n\
+                   <v Pastor>Let us pray &amp; give thanks\n\n\
+                   00:00:05.000 --> 00:00:06.000\n<v.loud Choir>Amen</v>\n<v Pastor>Amen\n";
+        let cues = parse_vtt(vtt).unwrap();
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].speaker.as_deref(), Some("Pastor"));
+        assert_eq!(cues[0].lines, ["Let us pray & give thanks"]);
+        assert_eq!(cues[0].start, Timestamp::from_seconds(1.0));
+        assert_eq!(cues[1].speaker, None, "two voices");
+        assert!(parse_vtt("00:01.000 --> 00:02.000\nNo header\n").is_err());
+
+        let transcript = [segment(0.0, 2.0, "In the beginning"), segment(2.0, 4.5, "was the Word")];
+        let cues = super::cues(&transcript);
+        for text in [to_srt(&cues), to_vtt(&cues)] {
+            let parsed = if text.starts_with("WEBVTT") {
+                parse_vtt(&text)
+            } else {
+                parse_srt(&text)
+            };
+            assert_eq!(segments_from_cues(&parsed.unwrap()), transcript);
+        }
+    }
+
+    #[test]
+    fn test_wrapped_cues_split_long_segments() {
+        let long = "The Spirit of the Lord is upon me, because he hath anointed me to \
+                    preach the gospel to the poor";
+        let mut segments = vec![segment(10.0, 20.0, long), segment(20.0, 21.0, "Amen.")];
+        segments[0].speaker = Some("Reader".to_string());
+        segments[1].speaker = Some("Reader".to_string());
+        let limits = LineLimits {
+            max_chars: 32,
+            ..LineLimits::default()
+        };
+        let cues = wrapped_cues(&segments, &limits);
+        assert_eq!(cues.len(), 3);
+        assert_eq!(
+            cues[0].lines,
+            ["Reader: The Spirit of the Lord", "is upon me, because he hath"]
+        );
+        assert_eq!(cues[1].lines, ["anointed me to preach the gospel", "to the poor"]);
+        assert_eq!(cues[2].lines, ["Amen."], "same speaker, no label");
+        assert!(cues.iter().flat_map(|cue| &cue.lines).all(|line| line.chars().count() <= 32));
+        // Time shared out by characters: "anointed" starts 51 of 96 in
+        assert_eq!(cues[0].start, Timestamp::from_seconds(10.0));
+        assert_eq!(cues[0].end, cues[1].start);
+        assert!((cues[1].start.as_seconds() - (10.0 + 10.0 * 51.0 / 96.0)).abs() < 0.002);
+        assert_eq!(cues[1].end, Timestamp::from_seconds(20.0));
+
+        // With word timing the split follows the words
+        let mut timed = segment(0.0, 3.0, "Grace and peace");
+        timed.words = ["Grace", "and", "peace"]
+            .iter()
+            .enumerate()
+            .map(|(i, text)| Word {
+                start: Timestamp::from_seconds(i as f64 * 1.2),
+                end: Timestamp::from_seconds(i as f64 * 1.2 + 1.0),
+                text: format!(" {}", text),
+            })
+            .collect();
+        let one_word = LineLimits {
+            max_chars: 1,
+            max_lines: 1,
+            ..LineLimits::default()
+        };
+        let cues = wrapped_cues(&[timed], &one_word);
+        let starts: Vec<f64> = cues.iter().map(|cue| cue.start.as_seconds()).collect();
+        assert_eq!(starts, [0.0, 1.2, 2.4]);
+        assert_eq!(cues[1].lines, ["and"], "words longer than a line aren't broken");
+    }
+
+    #[test]
+    fn test_export_srt_and_vtt() {
+        let dir = std::env::temp_dir();
+        let mut segments = source();
+        segments[0].speaker = Some("Pastor".to_string());
+        let limits = LineLimits::default();
+
+        let srt = dir.join("hermeneia_test_export.srt");
+        export_srt(&segments, &srt, &limits).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&srt).unwrap(),
+            "1\n00:00:01,000 --> 00:00:03,000\nPastor: The Lord is my shepherd;\n\n\
+             2\n00:00:03,000 --> 00:00:05,500\nI shall not want.\n\n\
+             3\n01:02:05,000 --> 01:02:06,000\nAmen.\n\n"
+        );
+
+        let vtt = dir.join("hermeneia_test_export.vtt");
+        export_vtt(&segments, &vtt, &limits).unwrap();
+        let text = std::fs::read_to_string(&vtt).unwrap();
+        assert!(text.contains("--> 00:00:03.000\n<v Pastor>The Lord is my shepherd;\n"));
+        let cues = parse_vtt(&text).unwrap();
+        assert_eq!(cues[0].speaker.as_deref(), Some("Pastor"));
+        assert_eq!(cues[0].lines, ["The Lord is my shepherd;"]);
+        std::fs::remove_file(srt).ok();
+        std::fs::remove_file(vtt).ok();
+    }
+}
@@ -0,0 +1,649 @@
+// src-tauri/src/transcribe.rs
+// Chunked transcription of long files with overlap-and-merge
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::markers::Marker;
+use crate::audio::pipeline::{
+    Denoise, FileSource, Pipeline, Remix, Resample, Sink, Source, StreamSpec,
+};
+use crate::audio::time::{AudioDuration, Timestamp};
+use crate::audio::types::AudioData;
+use crate::error::{AudioError, Result};
+use crate::progress::ProgressSink;
+
+/// A piece of recognized text, timed from the start of the file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Segment {
+    pub start: Timestamp,
+    pub end: Timestamp,
+    pub text: String,
+    /// Timing of each word, from engines that report it; empty otherwise
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<Word>,
+    /// Who is talking, from engines that diarize or as labelled by the user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+}
+
+/// One word of a [`Segment`], timed from the start of the file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Word {
+    pub start: Timestamp,
+    pub end: Timestamp,
+    pub text: String,
+}
+
+impl Segment {
+    fn midpoint(&self) -> Timestamp {
+        self.start.midpoint(self.end)
+    }
+
+    /// A range marker titled with the text, e.g. for an Audacity label track
+    pub fn to_marker(&self) -> Marker {
+        Marker::new(self.start, self.text.trim()).with_end(self.end)
+    }
+
+    /// A segment with a marker's range and title as its text; word timing
+    /// and speaker are unknown, and a point marker gives an empty segment
+    pub fn from_marker(marker: &Marker) -> Self {
+        Self {
+            start: marker.start,
+            end: marker.end.unwrap_or(marker.start),
+            text: marker.title.clone(),
+            words: Vec::new(),
+            speaker: None,
+        }
+    }
+}
+
+/// Index of the segment being spoken at `time`, for highlighting the
+/// transcript during playback
+///
+/// `segments` must be sorted by start. When segments overlap the one that
+/// started last wins; between segments there is none.
+pub fn segment_at(segments: &[Segment], time: Timestamp) -> Option<usize> {
+    let started = segments.partition_point(|segment| segment.start <= time);
+    (0..started).rev().find(|&i| segments[i].end > time)
+}
+
+/// A speech-to-text engine that works on short windows of audio
+pub trait Transcriber {
+    /// Rate the engine expects; audio arrives mono at this rate
+    fn sample_rate(&self) -> u32 {
+        16000
+    }
+
+    /// Recognize one window; timestamps are relative to the window start
+    fn transcribe(&mut self, audio: &AudioData) -> Result<Vec<Segment>>;
+}
+
+/// How a long file is prepared and cut into windows
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ChunkPlan {
+    /// Length of each window in seconds
+    pub chunk_secs: f64,
+    /// Seconds shared by neighbouring windows, so words cut at one window's
+    /// edge are heard whole in the next
+    pub overlap_secs: f64,
+    /// Reduce background noise at this strength (0.0 to 1.0) before
+    /// transcribing; helps with noisy room recordings
+    #[serde(default)]
+    pub denoise: Option<f32>,
+}
+
+impl Default for ChunkPlan {
+    fn default() -> Self {
+        Self {
+            chunk_secs: 30.0,
+            overlap_secs: 5.0,
+            denoise: None,
+        }
+    }
+}
+
+impl ChunkPlan {
+    /// Windows `chunk_secs` long, sharing `overlap_secs` with their
+    /// neighbours
+    pub fn new(chunk_secs: f64, overlap_secs: f64) -> Self {
+        Self {
+            chunk_secs,
+            overlap_secs,
+            ..Self::default()
+        }
+    }
+
+    pub fn denoise(mut self, strength: f32) -> Self {
+        self.denoise = Some(strength);
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !(self.chunk_secs > 0.0 && self.chunk_secs.is_finite()) {
+            return Err(AudioError::InvalidParameter(format!(
+                "Chunk length must be positive (got {}s)",
+                self.chunk_secs
+            )));
+        }
+        if !(self.overlap_secs >= 0.0 && self.overlap_secs * 2.0 < self.chunk_secs) {
+            return Err(AudioError::InvalidParameter(format!(
+                "Overlap must be less than half the chunk length (got {}s of {}s)",
+                self.overlap_secs, self.chunk_secs
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Merged result of a chunked run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transcript {
+    pub segments: Vec<Segment>,
+    /// Windows transcribed successfully
+    pub chunks: usize,
+    /// Set when a window failed; `segments` holds everything before it
+    pub error: Option<String>,
+}
+
+/// Transcribe a file of any length in overlapping windows
+///
+/// The file is decoded, mixed to mono, resampled and optionally denoised
+/// as it streams, so memory holds one window of audio no matter how long
+/// the file is.
+///
+/// # Returns
+/// The merged transcript. If a window fails after others succeeded, the
+/// completed part is returned with [`Transcript::error`] set; if nothing
+/// was transcribed the error is returned instead. Cancelling through
+/// `progress` always returns [`AudioError::Cancelled`].
+pub fn transcribe_file<P: AsRef<Path>>(
+    path: P,
+    transcriber: &mut dyn Transcriber,
+    plan: ChunkPlan,
+    progress: &mut dyn ProgressSink,
+) -> Result<Transcript> {
+    transcribe_source(FileSource::open(path)?, transcriber, plan, progress)
+}
+
+/// Like [`transcribe_file`], from any pipeline source
+pub fn transcribe_source(
+    source: impl Source + 'static,
+    transcriber: &mut dyn Transcriber,
+    plan: ChunkPlan,
+    progress: &mut dyn ProgressSink,
+) -> Result<Transcript> {
+    plan.validate()?;
+    progress.stage("transcribe");
+    let sample_rate = transcriber.sample_rate();
+    let mut sink = ChunkSink::new(transcriber, plan, sample_rate);
+
+    let mut pipeline = Pipeline::new(source)
+        .then(Remix::new(1))
+        .then(Resample::new(sample_rate));
+    if let Some(strength) = plan.denoise {
+        pipeline = pipeline.then(Denoise::new(strength));
+    }
+    let result = pipeline.run_with_progress(&mut sink, progress);
+
+    let chunks = sink.chunks;
+    let segments = sink.merger.segments;
+    match result {
+        Ok(_) => Ok(Transcript {
+            segments,
+            chunks,
+            error: None,
+        }),
+        Err(e) if chunks > 0 && !matches!(e, AudioError::Cancelled) => {
+            tracing::warn!("Transcription stopped after {} chunk(s): {}", chunks, e);
+            Ok(Transcript {
+                segments,
+                chunks,
+                error: Some(e.to_string()),
+            })
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Collects mono audio into windows and transcribes each one as it fills
+struct ChunkSink<'a> {
+    transcriber: &'a mut dyn Transcriber,
+    sample_rate: u32,
+    chunk_frames: usize,
+    overlap_frames: usize,
+    buffer: Vec<f32>,
+    /// Frames at the front of `buffer` that the previous window already saw
+    seen: usize,
+    /// Position of `buffer[0]` in the file, in frames
+    offset: u64,
+    chunks: usize,
+    merger: Merger,
+}
+
+impl<'a> ChunkSink<'a> {
+    fn new(transcriber: &'a mut dyn Transcriber, plan: ChunkPlan, sample_rate: u32) -> Self {
+        let frames = |secs: f64| (secs * sample_rate as f64).round() as usize;
+        Self {
+            transcriber,
+            sample_rate,
+            chunk_frames: frames(plan.chunk_secs).max(1),
+            overlap_frames: frames(plan.overlap_secs),
+            buffer: Vec::new(),
+            seen: 0,
+            offset: 0,
+            chunks: 0,
+            merger: Merger::default(),
+        }
+    }
+
+    fn run_window(&mut self, frames: usize) -> Result<()> {
+        let window = AudioData {
+            samples: self.buffer[..frames].to_vec(),
+            sample_rate: self.sample_rate,
+            channels: 1,
+        };
+        let start = Timestamp::from_frames(self.offset, self.sample_rate);
+        let segments = self.transcriber.transcribe(&window)?;
+
+        // The previous window's words win before the middle of the overlap,
+        // this window's after it
+        let cut = start + AudioDuration::from_frames(self.seen as u64, self.sample_rate) / 2;
+        let end = start + AudioDuration::from_frames(frames as u64, self.sample_rate);
+        self.merger.add(start, end, cut, segments);
+        self.chunks += 1;
+        Ok(())
+    }
+}
+
+impl Sink for ChunkSink<'_> {
+    fn open(&mut self, spec: StreamSpec) -> Result<()> {
+        debug_assert_eq!(spec.channels, 1);
+        self.buffer.reserve(self.chunk_frames);
+        Ok(())
+    }
+
+    fn write(&mut self, chunk: &AudioData) -> Result<()> {
+        self.buffer.extend_from_slice(&chunk.samples);
+        while self.buffer.len() >= self.chunk_frames {
+            self.run_window(self.chunk_frames)?;
+            let advance = self.chunk_frames - self.overlap_frames;
+            self.buffer.drain(..advance);
+            self.offset += advance as u64;
+            self.seen = self.overlap_frames;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        // Only the tail that no window has heard yet needs another pass
+        if self.buffer.len() > self.seen {
+            self.run_window(self.buffer.len())?;
+        }
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+/// Stitches per-window segments into one timeline
+#[derive(Debug, Default)]
+struct Merger {
+    segments: Vec<Segment>,
+}
+
+impl Merger {
+    /// Merge a window that covers `start` to `end` of the file
+    ///
+    /// Segments already kept whose middle falls after `cut` are replaced by
+    /// the new window's, and the new window's before `cut` are dropped.
+    fn add(&mut self, start: Timestamp, end: Timestamp, cut: Timestamp, segments: Vec<Segment>) {
+        self.segments.retain(|s| s.midpoint() < cut);
+
+        let place = |time: Timestamp| (start + (time - Timestamp::ZERO)).min(end);
+        for segment in segments {
+            let words = segment
+                .words
+                .into_iter()
+                .map(|word| Word {
+                    start: place(word.start),
+                    end: place(word.end),
+                    text: word.text.trim().to_string(),
+                })
+                .filter(|word| !word.text.is_empty())
+                .collect();
+            let mut segment = Segment {
+                start: start + (segment.start - Timestamp::ZERO),
+                end: place(segment.end),
+                text: segment.text.trim().to_string(),
+                words,
+                speaker: segment.speaker,
+            };
+            if segment.text.is_empty() || segment.midpoint() < cut {
+                continue;
+            }
+            if let Some(last) = self.segments.last_mut() {
+                // The same words heard by both windows, split slightly
+                // differently around the cut
+                if segment.start < last.end && same_words(&last.text, &segment.text) {
+                    if segment.text.len() > last.text.len() {
+                        last.text = segment.text;
+                        last.words = segment.words;
+                    }
+                    last.end = last.end.max(segment.end);
+                    continue;
+                }
+                // Keep the timeline in order
+                segment.start = segment.start.max(last.end);
+                segment.end = segment.end.max(segment.start);
+                for word in &mut segment.words {
+                    word.start = word.start.max(segment.start);
+                    word.end = word.end.max(word.start);
+                }
+            }
+            self.segments.push(segment);
+        }
+    }
+}
+
+/// Whether one text's words run inside the other's, ignoring case and
+/// punctuation
+fn same_words(a: &str, b: &str) -> bool {
+    let words = |s: &str| {
+        s.to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+    let (a, b) = (words(a), words(b));
+    let (longer, shorter) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    !shorter.is_empty() && longer.windows(shorter.len()).any(|w| w == shorter.as_slice())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::pipeline::MemorySource;
+    use crate::error::AnalysisError;
+    use crate::progress::NoProgress;
+
+    /// Reports one "word" per second of audio, named after the second it
+    /// falls in, so windows that overlap hear the same words
+    struct Clock {
+        windows: Vec<(usize, f64)>,
+        fail_at: Option<usize>,
+        offset: f64,
+        step: f64,
+    }
+
+    impl Clock {
+        fn new(step: f64) -> Self {
+            Self {
+                windows: Vec::new(),
+                fail_at: None,
+                offset: 0.0,
+                step,
+            }
+        }
+    }
+
+    impl Transcriber for Clock {
+        fn transcribe(&mut self, audio: &AudioData) -> Result<Vec<Segment>> {
+            let index = self.windows.len();
+            if self.fail_at == Some(index) {
+                return Err(AnalysisError::Engine("engine crashed".to_string()).into());
+            }
+            let duration = audio.duration_seconds();
+            self.windows.push((audio.frame_count(), duration));
+
+            let offset = self.offset;
+            self.offset += self.step;
+            let mut segments = Vec::new();
+            let mut second = offset.ceil();
+            while second + 1.0 <= offset + duration + 1e-9 {
+                segments.push(Segment {
+                    start: Timestamp::from_seconds(second - offset),
+                    end: Timestamp::from_seconds(second + 1.0 - offset),
+                    text: format!("word{}", second as u64),
+                    words: Vec::new(),
+                    speaker: None,
+                });
+                second += 1.0;
+            }
+            Ok(segments)
+        }
+    }
+
+    fn silence(seconds: usize) -> AudioData {
+        AudioData {
+            samples: vec![0.0; seconds * 16000],
+            sample_rate: 16000,
+            channels: 1,
+        }
+    }
+
+    #[test]
+    fn test_overlapping_windows_merge_without_duplicates() {
+        let plan = ChunkPlan {
+            chunk_secs: 10.0,
+            overlap_secs: 2.0,
+            denoise: None,
+        };
+        let mut clock = Clock::new(8.0);
+        let transcript = transcribe_source(
+            MemorySource::new(silence(25), 4000),
+            &mut clock,
+            plan,
+            &mut NoProgress,
+        )
+        .unwrap();
+
+        let words: Vec<&str> = transcript.segments.iter().map(|s| s.text.as_str()).collect();
+        let expected: Vec<String> = (0..25).map(|i| format!("word{}", i)).collect();
+        assert_eq!(words, expected);
+        assert_eq!(transcript.chunks, 3);
+        assert!(transcript.error.is_none());
+        for pair in transcript.segments.windows(2) {
+            assert!(pair[0].end <= pair[1].start);
+        }
+    }
+
+    #[test]
+    fn test_denoising_keeps_the_timeline() {
+        let plan = ChunkPlan::new(10.0, 2.0).denoise(0.8);
+        assert_eq!(plan.denoise, Some(0.8));
+        let mut clock = Clock::new(8.0);
+        let transcript = transcribe_source(
+            MemorySource::new(silence(25), 4000),
+            &mut clock,
+            plan,
+            &mut NoProgress,
+        )
+        .unwrap();
+        assert_eq!(transcript.segments.len(), 25);
+        assert_eq!(transcript.chunks, 3);
+
+        let plan = plan.denoise(2.0);
+        let result = transcribe_source(
+            MemorySource::new(silence(25), 4000),
+            &mut Clock::new(8.0),
+            plan,
+            &mut NoProgress,
+        );
+        assert!(matches!(result, Err(AudioError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_failure_keeps_completed_chunks() {
+        let plan = ChunkPlan {
+            chunk_secs: 10.0,
+            overlap_secs: 2.0,
+            denoise: None,
+        };
+        let mut clock = Clock::new(8.0);
+        clock.fail_at = Some(2);
+        let transcript = transcribe_source(
+            MemorySource::new(silence(30), 4000),
+            &mut clock,
+            plan,
+            &mut NoProgress,
+        )
+        .unwrap();
+
+        assert_eq!(transcript.chunks, 2);
+        assert!(transcript.error.unwrap().contains("engine crashed"));
+        assert_eq!(transcript.segments.last().unwrap().text, "word17");
+    }
+
+    #[test]
+    fn test_failure_before_any_chunk_is_an_error() {
+        let mut clock = Clock::new(8.0);
+        clock.fail_at = Some(0);
+        let result = transcribe_source(
+            MemorySource::new(silence(3), 4000),
+            &mut clock,
+            ChunkPlan::default(),
+            &mut NoProgress,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_windows_stay_bounded() {
+        let plan = ChunkPlan {
+            chunk_secs: 4.0,
+            overlap_secs: 1.0,
+            denoise: None,
+        };
+        let mut clock = Clock::new(3.0);
+        transcribe_source(MemorySource::new(silence(20), 16000), &mut clock, plan, &mut NoProgress)
+            .unwrap();
+        assert!(clock.windows.iter().all(|&(frames, _)| frames <= 4 * 16000));
+    }
+
+    #[test]
+    fn test_repeated_text_across_cut_is_suppressed() {
+        let mut merger = Merger::default();
+        let secs = Timestamp::from_seconds;
+        merger.add(
+            secs(0.0),
+            secs(10.0),
+            secs(0.0),
+            vec![Segment {
+                start: secs(7.5),
+                end: secs(9.5),
+                text: "In the beginning".to_string(),
+                words: Vec::new(),
+                speaker: None,
+            }],
+        );
+        merger.add(
+            secs(8.0),
+            secs(18.0),
+            secs(9.0),
+            vec![Segment {
+                start: secs(0.6),
+                end: secs(2.5),
+                text: "in the beginning was the Word.".to_string(),
+                words: Vec::new(),
+                speaker: None,
+            }],
+        );
+        assert_eq!(merger.segments.len(), 1);
+        assert_eq!(merger.segments[0].text, "in the beginning was the Word.");
+        assert_eq!(merger.segments[0].end, secs(10.5));
+    }
+
+    #[test]
+    fn test_word_times_are_placed_in_the_file() {
+        let mut merger = Merger::default();
+        let secs = Timestamp::from_seconds;
+        let word = |start, end, text: &str| Word {
+            start: secs(start),
+            end: secs(end),
+            text: text.to_string(),
+        };
+        merger.add(
+            secs(20.0),
+            secs(30.0),
+            secs(20.0),
+            vec![Segment {
+                start: secs(1.0),
+                end: secs(12.0),
+                text: "Let there be light".to_string(),
+                words: vec![word(1.0, 1.5, " Let"), word(1.5, 1.7, ""), word(9.0, 12.0, "light")],
+                speaker: None,
+            }],
+        );
+        let words = &merger.segments[0].words;
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0], word(21.0, 21.5, "Let"));
+        // Clamped to the window like the segment itself
+        assert_eq!(words[1], word(29.0, 30.0, "light"));
+    }
+
+    #[test]
+    fn test_overlap_must_be_under_half_the_chunk() {
+        let plan = ChunkPlan {
+            chunk_secs: 10.0,
+            overlap_secs: 5.0,
+            denoise: None,
+        };
+        assert!(plan.validate().is_err());
+        assert!(ChunkPlan::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_segments_round_trip_through_markers() {
+        let segment = Segment {
+            start: Timestamp::from_seconds(4.0),
+            end: Timestamp::from_seconds(6.5),
+            text: " Let us pray.".to_string(),
+            words: Vec::new(),
+            speaker: Some("Pastor".to_string()),
+        };
+        let marker = segment.to_marker();
+        assert_eq!(marker.title, "Let us pray.");
+        assert_eq!(marker.end, Some(segment.end));
+
+        let back = Segment::from_marker(&marker);
+        assert_eq!((back.start, back.end), (segment.start, segment.end));
+        assert_eq!(back.speaker, None);
+        let point = Segment::from_marker(&Marker::new(segment.start, "Amen"));
+        assert_eq!(point.end, point.start);
+    }
+
+    #[test]
+    fn test_segment_at_playback_position() {
+        let at = |start: f64, end: f64| Segment {
+            start: Timestamp::from_seconds(start),
+            end: Timestamp::from_seconds(end),
+            text: String::new(),
+            words: Vec::new(),
+            speaker: None,
+        };
+        let segments = [at(1.0, 3.0), at(2.5, 4.0), at(6.0, 8.0)];
+        let find = |seconds: f64| segment_at(&segments, Timestamp::from_seconds(seconds));
+        assert_eq!(find(0.5), None);
+        assert_eq!(find(1.0), Some(0));
+        assert_eq!(find(2.7), Some(1), "the later of two overlapping segments");
+        assert_eq!(find(3.5), Some(1));
+        assert_eq!(find(5.0), None);
+        assert_eq!(find(7.9), Some(2));
+        assert_eq!(find(8.0), None);
+    }
+
+    #[test]
+    fn test_same_words_compares_whole_words() {
+        assert!(same_words("Let us pray.", "let us PRAY"));
+        assert!(same_words("and let us pray together", "Let us pray"));
+        assert!(same_words("us pray", "Let us pray."));
+        // "he" is inside "the", but isn't the same word
+        assert!(!same_words("the", "he"));
+        assert!(!same_words("Then the Lord spoke", "he Lord"));
+        assert!(!same_words("", "amen"));
+        assert!(!same_words("...", "--"));
+    }
+}
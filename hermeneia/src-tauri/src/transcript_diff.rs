@@ -0,0 +1,324 @@
+// src-tauri/src/transcript_diff.rs
+// Aligning two transcripts of the same audio and listing where they differ
+
+use serde::{Deserialize, Serialize};
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+
+use crate::audio::time::Timestamp;
+use crate::pacing::normalize;
+use crate::transcribe::Segment;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    /// Words only the candidate has
+    Insertion,
+    /// Words only the reference has
+    Deletion,
+    /// Words the two heard differently
+    Substitution,
+}
+
+/// One place where the transcripts disagree
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptChange {
+    pub kind: ChangeKind,
+    /// Span of the words involved, from either transcript
+    pub start: Timestamp,
+    pub end: Timestamp,
+    /// The reference's words, as written; empty for an insertion
+    pub reference: String,
+    /// The candidate's words, as written; empty for a deletion
+    pub candidate: String,
+    /// Indexes of the reference segments the words are in
+    pub reference_segments: Vec<usize>,
+    /// Indexes of the candidate segments the words are in
+    pub candidate_segments: Vec<usize>,
+}
+
+/// Word counts for a [`TranscriptDiff`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffStats {
+    pub reference_words: usize,
+    pub candidate_words: usize,
+    /// Words both transcripts agree on
+    pub matching: usize,
+    pub substitutions: usize,
+    pub deletions: usize,
+    pub insertions: usize,
+    /// Substitutions, deletions and insertions over the reference's words;
+    /// the word error rate if the reference is taken as correct
+    pub word_error_rate: f64,
+}
+
+/// Everything that differs between two transcripts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptDiff {
+    /// In time order
+    pub changes: Vec<TranscriptChange>,
+    pub stats: DiffStats,
+    /// Some segments had no word timing, so the times of changes inside
+    /// them were estimated by spreading their words evenly
+    pub estimated_timing: bool,
+}
+
+/// A word, normalized for matching, and where it came from
+struct DiffWord<'a> {
+    key: String,
+    text: &'a str,
+    start: Timestamp,
+    end: Timestamp,
+    segment: usize,
+}
+
+/// Align two transcripts of the same audio word by word and list their
+/// differences
+///
+/// Words are compared without regard to case or punctuation, so only
+/// differences in what was heard show up. Neighbouring differences form a
+/// single change: a deletion or insertion if only one transcript has
+/// words there, a substitution otherwise. Segment boundaries don't need
+/// to line up, so runs from different models or settings can be compared.
+///
+/// # Example
+/// ```
+/// use hermeneia_lib::audio::Timestamp;
+/// use hermeneia_lib::transcribe::Segment;
+/// use hermeneia_lib::transcript_diff::{diff_transcripts, ChangeKind};
+///
+/// let segment = |text: &str| Segment {
+///     start: Timestamp::from_seconds(0.0),
+///     end: Timestamp::from_seconds(4.0),
+///     text: text.to_string(),
+///     words: Vec::new(),
+///     speaker: None,
+/// };
+/// let base = [segment("Blessed are the cheese makers")];
+/// let large = [segment("Blessed are the peacemakers.")];
+/// let diff = diff_transcripts(&base, &large);
+///
+/// assert_eq!(diff.changes[0].kind, ChangeKind::Substitution);
+/// assert_eq!(diff.changes[0].reference, "cheese makers");
+/// assert_eq!(diff.stats.word_error_rate, 0.4);
+/// ```
+pub fn diff_transcripts(reference: &[Segment], candidate: &[Segment]) -> TranscriptDiff {
+    let (old, old_estimated) = words(reference);
+    let (new, new_estimated) = words(candidate);
+    let old_keys: Vec<&str> = old.iter().map(|word| word.key.as_str()).collect();
+    let new_keys: Vec<&str> = new.iter().map(|word| word.key.as_str()).collect();
+    let ops = capture_diff_slices(Algorithm::Myers, &old_keys, &new_keys);
+
+    let mut stats = DiffStats {
+        reference_words: old.len(),
+        candidate_words: new.len(),
+        matching: 0,
+        substitutions: 0,
+        deletions: 0,
+        insertions: 0,
+        word_error_rate: 0.0,
+    };
+    // Runs of edits, as ranges of reference and candidate words
+    let mut runs = Vec::new();
+    let mut pending: Option<(usize, usize)> = None;
+    for op in &ops {
+        match *op {
+            DiffOp::Equal {
+                old_index,
+                new_index,
+                len,
+            } => {
+                if let Some((old_start, new_start)) = pending.take() {
+                    runs.push((old_start..old_index, new_start..new_index));
+                }
+                stats.matching += len;
+            }
+            _ => {
+                let (old_range, new_range) = (op.old_range(), op.new_range());
+                pending.get_or_insert((old_range.start, new_range.start));
+            }
+        }
+    }
+    if let Some((old_start, new_start)) = pending {
+        runs.push((old_start..old.len(), new_start..new.len()));
+    }
+
+    let mut changes = Vec::with_capacity(runs.len());
+    for (old_run, new_run) in runs {
+        let substituted = old_run.len().min(new_run.len());
+        stats.substitutions += substituted;
+        stats.deletions += old_run.len() - substituted;
+        stats.insertions += new_run.len() - substituted;
+        changes.push(change(&old[old_run], &new[new_run]));
+    }
+    let errors = stats.substitutions + stats.deletions + stats.insertions;
+    stats.word_error_rate = errors as f64 / stats.reference_words.max(1) as f64;
+
+    TranscriptDiff {
+        changes,
+        stats,
+        estimated_timing: old_estimated || new_estimated,
+    }
+}
+
+/// Describe a run of reference words replaced by candidate words
+fn change(old: &[DiffWord], new: &[DiffWord]) -> TranscriptChange {
+    let kind = match (old.is_empty(), new.is_empty()) {
+        (false, true) => ChangeKind::Deletion,
+        (true, false) => ChangeKind::Insertion,
+        _ => ChangeKind::Substitution,
+    };
+    let all = || old.iter().chain(new);
+    let segments = |words: &[DiffWord]| {
+        let mut segments: Vec<usize> = words.iter().map(|word| word.segment).collect();
+        segments.dedup();
+        segments
+    };
+    let text = |words: &[DiffWord]| {
+        words.iter().map(|word| word.text).collect::<Vec<_>>().join(" ")
+    };
+    TranscriptChange {
+        kind,
+        start: all().map(|word| word.start).min().unwrap_or_default(),
+        end: all().map(|word| word.end).max().unwrap_or_default(),
+        reference: text(old),
+        candidate: text(new),
+        reference_segments: segments(old),
+        candidate_segments: segments(new),
+    }
+}
+
+/// Every word in time order, and whether any timing had to be estimated
+fn words(segments: &[Segment]) -> (Vec<DiffWord<'_>>, bool) {
+    let mut order: Vec<usize> = (0..segments.len()).collect();
+    order.sort_by_key(|&index| segments[index].start);
+
+    let mut words = Vec::new();
+    let mut estimated = false;
+    for index in order {
+        let segment = &segments[index];
+        if !segment.words.is_empty() {
+            words.extend(segment.words.iter().filter_map(|word| {
+                let key = normalize(&word.text);
+                (!key.is_empty()).then_some(DiffWord {
+                    key,
+                    text: word.text.trim(),
+                    start: word.start,
+                    end: word.end,
+                    segment: index,
+                })
+            }));
+            continue;
+        }
+        let texts: Vec<(&str, String)> = segment
+            .text
+            .split_whitespace()
+            .map(|text| (text, normalize(text)))
+            .filter(|(_, key)| !key.is_empty())
+            .collect();
+        if texts.is_empty() {
+            continue;
+        }
+        estimated = true;
+        let each = segment.end.saturating_since(segment.start) / texts.len() as u32;
+        let mut start = segment.start;
+        for (text, key) in texts {
+            words.push(DiffWord {
+                key,
+                text,
+                start,
+                end: start + each,
+                segment: index,
+            });
+            start += each;
+        }
+    }
+    (words, estimated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcribe::Word;
+
+    fn said(start: f64, end: f64, text: &str) -> Segment {
+        Segment {
+            start: Timestamp::from_seconds(start),
+            end: Timestamp::from_seconds(end),
+            text: text.to_string(),
+            words: Vec::new(),
+            speaker: None,
+        }
+    }
+
+    #[test]
+    fn test_changes_across_different_segmentation() {
+        let base = [
+            said(0.0, 4.0, "In the beginning was the word,"),
+            said(4.0, 8.0, "and the word was with God."),
+            said(8.0, 12.0, "Um, all things were made by him"),
+        ];
+        // Split differently, hears one word better and drops the filler
+        let large = [
+            said(0.0, 2.0, "In the beginning"),
+            said(2.0, 8.0, "was the Word, and the Word was with God"),
+            said(8.0, 12.0, "All things were made through him."),
+        ];
+        let diff = diff_transcripts(&base, &large);
+        assert!(diff.estimated_timing);
+        assert_eq!(diff.changes.len(), 2, "{:?}", diff.changes);
+
+        let filler = &diff.changes[0];
+        assert_eq!(filler.kind, ChangeKind::Deletion);
+        assert_eq!((filler.reference.as_str(), filler.candidate.as_str()), ("Um,", ""));
+        assert_eq!(filler.reference_segments, [2]);
+        assert!(filler.candidate_segments.is_empty());
+        assert_eq!(filler.start, Timestamp::from_seconds(8.0));
+
+        let by = &diff.changes[1];
+        assert_eq!(by.kind, ChangeKind::Substitution);
+        assert_eq!((by.reference.as_str(), by.candidate.as_str()), ("by", "through"));
+
+        let stats = &diff.stats;
+        assert_eq!((stats.reference_words, stats.candidate_words), (19, 18));
+        assert_eq!(stats.matching, 17);
+        assert_eq!((stats.substitutions, stats.deletions, stats.insertions), (1, 1, 0));
+        assert!((stats.word_error_rate - 2.0 / 19.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_insertions_use_word_timing() {
+        let word = |start: f64, text: &str| Word {
+            start: Timestamp::from_seconds(start),
+            end: Timestamp::from_seconds(start + 0.4),
+            text: format!(" {}", text),
+        };
+        let mut timed = said(0.0, 3.0, "Grace and peace to you");
+        timed.words = vec![word(0.0, "Grace"), word(0.5, "and"), word(1.0, "peace")];
+        timed.words.extend([word(2.0, "to"), word(2.5, "you")]);
+        let short = [said(0.0, 3.0, "grace and peace")];
+
+        let diff = diff_transcripts(&short, &[timed]);
+        assert!(diff.estimated_timing, "the reference has no word timing");
+        assert_eq!(diff.changes.len(), 1);
+        let change = &diff.changes[0];
+        assert_eq!(change.kind, ChangeKind::Insertion);
+        assert_eq!(change.candidate, "to you");
+        assert_eq!(change.start, Timestamp::from_seconds(2.0));
+        assert_eq!(change.end, Timestamp::from_seconds(2.9));
+        assert_eq!(diff.stats.insertions, 2);
+        assert!((diff.stats.word_error_rate - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_identical_and_empty_transcripts() {
+        let text = [said(0.0, 2.0, "Amen.")];
+        let same = diff_transcripts(&text, &[said(0.0, 2.5, "amen")]);
+        assert!(same.changes.is_empty());
+        assert_eq!(same.stats.word_error_rate, 0.0);
+
+        let nothing = diff_transcripts(&[], &[]);
+        assert!(nothing.changes.is_empty());
+        assert_eq!(nothing.stats.word_error_rate, 0.0);
+        assert_eq!(diff_transcripts(&text, &[]).stats.word_error_rate, 1.0);
+    }
+}
@@ -0,0 +1,395 @@
+// src-tauri/src/translation.rs
+// Translating transcripts with a pluggable engine
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio::chapters::read_audacity_labels;
+use crate::error::{AnalysisError, AudioError, Result};
+use crate::ingest::helper_command;
+use crate::progress::{check_cancelled, ProgressSink};
+use crate::runtime::{PoolLimits, RuntimeLease, RuntimePool};
+use crate::subtitles::{read_subtitles, segments_from_cues};
+use crate::transcribe::Segment;
+
+/// Longest a LibreTranslate request may take, in seconds, as curl wants it
+const REQUEST_TIMEOUT_SECS: &str = "120";
+
+/// Translators busy with one engine at once; a server or a local command
+/// gets slower rather than faster when given more
+const TRANSLATORS_PER_ENGINE: usize = 4;
+
+/// Translators busy with every engine together
+const TRANSLATORS_TOTAL: usize = 8;
+
+/// A machine translation engine that works on batches of short texts
+pub trait Translator: Send {
+    /// Translate each of `texts` from `source` to `target` (language codes
+    /// such as "en" or "es"), one result per text, in order
+    fn translate(&mut self, texts: &[String], source: &str, target: &str) -> Result<Vec<String>>;
+
+    /// Most texts sent in one call
+    fn batch_size(&self) -> usize {
+        20
+    }
+}
+
+/// Where translations come from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "engine", rename_all = "snake_case")]
+pub enum TranslationEngine {
+    /// A LibreTranslate server, e.g. `http://localhost:5000`; one running
+    /// on this machine translates offline with local models
+    LibreTranslate {
+        url: String,
+        #[serde(default)]
+        api_key: Option<String>,
+    },
+    /// A program that reads `{"source", "target", "texts"}` as JSON on
+    /// stdin and prints a JSON array with one translation per text
+    Command {
+        program: PathBuf,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+impl TranslationEngine {
+    pub fn translator(&self) -> Box<dyn Translator> {
+        match self {
+            TranslationEngine::LibreTranslate { url, api_key } => Box::new(LibreTranslate {
+                url: url.clone(),
+                api_key: api_key.clone(),
+            }),
+            TranslationEngine::Command { program, args } => Box::new(CommandTranslator {
+                program: program.clone(),
+                args: args.clone(),
+            }),
+        }
+    }
+}
+
+/// What a [`TranslationEngine::Command`] program gets on stdin
+#[derive(Serialize)]
+struct CommandRequest<'a> {
+    source: &'a str,
+    target: &'a str,
+    texts: &'a [String],
+}
+
+struct CommandTranslator {
+    program: PathBuf,
+    args: Vec<String>,
+}
+
+impl Translator for CommandTranslator {
+    fn translate(&mut self, texts: &[String], source: &str, target: &str) -> Result<Vec<String>> {
+        let name = self.program.display().to_string();
+        let mut child = helper_command(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| engine_error(format!("couldn't run {}: {}", name, e)))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            let request = CommandRequest {
+                source,
+                target,
+                texts,
+            };
+            serde_json::to_writer(&mut stdin, &request)
+                .map_err(|e| engine_error(e.to_string()))?;
+            stdin.flush()?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            let reason = String::from_utf8_lossy(&output.stderr);
+            return Err(engine_error(format!("{} failed: {}", name, reason.trim())));
+        }
+        serde_json::from_slice(&output.stdout).map_err(|e| {
+            engine_error(format!("{} printed something other than a list: {}", name, e))
+        })
+    }
+}
+
+struct LibreTranslate {
+    url: String,
+    api_key: Option<String>,
+}
+
+/// Body of a LibreTranslate `/translate` request
+#[derive(Serialize)]
+struct LibreTranslateRequest<'a> {
+    q: &'a [String],
+    source: &'a str,
+    target: &'a str,
+    format: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+}
+
+impl Translator for LibreTranslate {
+    fn translate(&mut self, texts: &[String], source: &str, target: &str) -> Result<Vec<String>> {
+        let endpoint = format!("{}/translate", self.url.trim_end_matches('/'));
+        let download_error = |reason: String| AudioError::Download {
+            url: endpoint.clone(),
+            reason,
+        };
+        let mut child = helper_command("curl")
+            .args(["--silent", "--show-error", "--location"])
+            .args(["--max-time", REQUEST_TIMEOUT_SECS])
+            .args(["--header", "Content-Type: application/json"])
+            .args(["--data-binary", "@-", "--", &endpoint])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| download_error(e.to_string()))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            let request = LibreTranslateRequest {
+                q: texts,
+                source,
+                target,
+                format: "text",
+                api_key: self.api_key.as_deref(),
+            };
+            serde_json::to_writer(&mut stdin, &request)
+                .map_err(|e| download_error(e.to_string()))?;
+            stdin.flush()?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            let reason = String::from_utf8_lossy(&output.stderr);
+            return Err(download_error(reason.trim().trim_start_matches("curl: ").to_string()));
+        }
+        parse_libretranslate(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+/// Borrow a translator for `engine` from those shared by background jobs
+///
+/// Waits while [`TRANSLATORS_PER_ENGINE`] jobs are using the engine, or
+/// until `progress` is cancelled. The translator goes back for the next
+/// job when the lease drops.
+pub fn lease_translator(
+    engine: &TranslationEngine,
+    progress: &dyn ProgressSink,
+) -> Result<RuntimeLease<Box<dyn Translator>>> {
+    static TRANSLATORS: OnceLock<RuntimePool<Box<dyn Translator>>> = OnceLock::new();
+    let pool = TRANSLATORS.get_or_init(|| {
+        RuntimePool::new(PoolLimits::new(TRANSLATORS_PER_ENGINE, TRANSLATORS_TOTAL))
+    });
+    let key = serde_json::to_string(engine).unwrap_or_default();
+    pool.lease(&key, progress, || Ok(engine.translator()))
+}
+
+/// Pull the translations out of a LibreTranslate response
+///
+/// The server answers `{"translatedText": [...]}` for a batch, or
+/// `{"error": "..."}` when it refuses the request.
+pub fn parse_libretranslate(body: &str) -> Result<Vec<String>> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Response {
+        translated_text: Option<Vec<String>>,
+        error: Option<String>,
+    }
+    let response: Response = serde_json::from_str(body)
+        .map_err(|_| engine_error(format!("unexpected response: {}", body.trim())))?;
+    match (response.translated_text, response.error) {
+        (_, Some(error)) => Err(engine_error(error)),
+        (Some(texts), None) => Ok(texts),
+        (None, None) => Err(engine_error("the response has no translations".to_string())),
+    }
+}
+
+fn engine_error(message: String) -> AudioError {
+    AnalysisError::Engine(format!("translation: {}", message)).into()
+}
+
+/// Translate a transcript segment by segment
+///
+/// Timing and speakers carry over; word timing doesn't, since the words
+/// are different ones. Segments without text stay empty and aren't sent.
+/// Reports the "translate" stage and the fraction of segments done.
+///
+/// # Errors
+/// [`AnalysisError::Engine`] if the engine fails or returns the wrong
+/// number of translations for a batch
+pub fn translate_segments(
+    segments: &[Segment],
+    source: &str,
+    target: &str,
+    translator: &mut dyn Translator,
+    progress: &mut dyn ProgressSink,
+) -> Result<Vec<Segment>> {
+    progress.stage("translate");
+    let mut translated: Vec<Segment> = segments
+        .iter()
+        .map(|segment| Segment {
+            text: String::new(),
+            words: Vec::new(),
+            ..segment.clone()
+        })
+        .collect();
+    let pending: Vec<usize> = (0..segments.len())
+        .filter(|&i| !segments[i].text.trim().is_empty())
+        .collect();
+
+    let mut done = 0;
+    for batch in pending.chunks(translator.batch_size().max(1)) {
+        check_cancelled(progress)?;
+        let texts: Vec<String> =
+            batch.iter().map(|&i| segments[i].text.trim().to_string()).collect();
+        let results = translator.translate(&texts, source, target)?;
+        if results.len() != texts.len() {
+            return Err(engine_error(format!(
+                "sent {} texts but got {} translations back",
+                texts.len(),
+                results.len()
+            )));
+        }
+        for (&index, text) in batch.iter().zip(results) {
+            translated[index].text = text;
+        }
+        done += batch.len();
+        progress.progress(done as f64 / pending.len() as f64);
+    }
+    progress.progress(1.0);
+    Ok(translated)
+}
+
+/// Where a job's transcript comes from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TranslationInput {
+    /// Segments sent along with the job
+    Transcript { segments: Vec<Segment> },
+    /// SRT or WebVTT subtitles, or an Audacity label track (`.txt`)
+    File { path: PathBuf },
+}
+
+impl TranslationInput {
+    /// The transcript to translate, reading the file for file inputs
+    pub fn segments(&self) -> Result<Vec<Segment>> {
+        match self {
+            TranslationInput::Transcript { segments } => Ok(segments.clone()),
+            TranslationInput::File { path } => read_transcript(path),
+        }
+    }
+}
+
+fn read_transcript(path: &Path) -> Result<Vec<Segment>> {
+    let labels = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("txt"));
+    if labels {
+        let markers = read_audacity_labels(path)?;
+        return Ok(markers.iter().map(Segment::from_marker).collect());
+    }
+    Ok(segments_from_cues(&read_subtitles(path)?))
+}
+
+/// What to translate, and between which languages
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranslationRequest {
+    pub source_language: String,
+    pub target_language: String,
+    pub input: TranslationInput,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::time::Timestamp;
+    use crate::progress::NoProgress;
+
+    /// Upper-cases text, counting its calls
+    struct Shouting {
+        calls: usize,
+    }
+
+    impl Translator for Shouting {
+        fn translate(&mut self, texts: &[String], _: &str, target: &str) -> Result<Vec<String>> {
+            self.calls += 1;
+            Ok(texts.iter().map(|t| format!("[{}] {}", target, t.to_uppercase())).collect())
+        }
+
+        fn batch_size(&self) -> usize {
+            2
+        }
+    }
+
+    fn said(start: f64, text: &str) -> Segment {
+        Segment {
+            start: Timestamp::from_seconds(start),
+            end: Timestamp::from_seconds(start + 1.0),
+            text: text.to_string(),
+            words: Vec::new(),
+            speaker: Some("Pastor".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_translate_segments_in_batches() {
+        let segments = [said(0.0, " Grace "), said(1.0, ""), said(2.0, "and"), said(3.0, "peace")];
+        let mut engine = Shouting { calls: 0 };
+        let mut fractions = Vec::new();
+        let translated =
+            translate_segments(&segments, "en", "es", &mut engine, &mut |f| fractions.push(f))
+                .unwrap();
+
+        let texts: Vec<&str> = translated.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, ["[es] GRACE", "", "[es] AND", "[es] PEACE"]);
+        assert_eq!(engine.calls, 2, "the empty segment isn't sent");
+        assert_eq!(translated[2].start, segments[2].start);
+        assert_eq!(translated[3].speaker.as_deref(), Some("Pastor"));
+        assert_eq!(fractions.last(), Some(&1.0));
+    }
+
+    #[test]
+    fn test_wrong_number_of_translations_fails() {
+        struct Lossy;
+        impl Translator for Lossy {
+            fn translate(&mut self, _: &[String], _: &str, _: &str) -> Result<Vec<String>> {
+                Ok(vec!["only one".to_string()])
+            }
+        }
+        let segments = [said(0.0, "one"), said(1.0, "two")];
+        assert!(matches!(
+            translate_segments(&segments, "en", "de", &mut Lossy, &mut NoProgress),
+            Err(AudioError::Analysis(AnalysisError::Engine(_)))
+        ));
+    }
+
+    #[test]
+    fn test_parse_libretranslate() {
+        let ok = parse_libretranslate(r#"{"translatedText": ["Hola", "mundo"]}"#).unwrap();
+        assert_eq!(ok, ["Hola", "mundo"]);
+        let refused = parse_libretranslate(r#"{"error": "Invalid API key"}"#).unwrap_err();
+        assert!(refused.to_string().contains("Invalid API key"));
+        assert!(parse_libretranslate("<html>502 Bad Gateway</html>").is_err());
+    }
+
+    #[test]
+    fn test_engine_json() {
+        let engine: TranslationEngine =
+            serde_json::from_str(r#"{"engine":"libre_translate","url":"http://localhost:5000"}"#)
+                .unwrap();
+        assert_eq!(
+            engine,
+            TranslationEngine::LibreTranslate {
+                url: "http://localhost:5000".to_string(),
+                api_key: None
+            }
+        );
+        let command: TranslationEngine =
+            serde_json::from_str(r#"{"engine":"command","program":"argos-json"}"#).unwrap();
+        assert!(matches!(command, TranslationEngine::Command { args, .. } if args.is_empty()));
+    }
+}
@@ -0,0 +1,46 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/**
+ * Common embedded tags, matching `TagSummary` in Rust
+ */
+export interface TagSummary {
+  title: string | null;
+  artist: string | null;
+  album: string | null;
+  album_artist: string | null;
+  date: string | null;
+  genre: string | null;
+  track_number: string | null;
+  comment: string | null;
+  /** Tags not summarized above */
+  other_tags: number;
+  /** Whether the file embeds a picture (e.g. cover art) */
+  has_picture: boolean;
+}
+
+/**
+ * What the file header shows, matching `AudioInfo` in Rust
+ */
+export interface AudioInfo {
+  duration_seconds: number;
+  sample_rate: number;
+  channels: number;
+  /** Codec name */
+  format: string;
+  bit_depth: number | null;
+  /** Container format, e.g. "WAV", "MP4", "Ogg" */
+  container: string | null;
+  /** Codec profile, e.g. "AAC LC", where the stream declares one */
+  codec_profile: string | null;
+  /** Average bitrate including container overhead, in kbit/s */
+  bitrate_kbps: number | null;
+  file_size_bytes: number;
+  tags: TagSummary;
+}
+
+/**
+ * Read an audio file's format, size and tags without decoding it
+ */
+export async function getAudioInfo(filePath: string): Promise<AudioInfo> {
+  return invoke<AudioInfo>('get_audio_info', { filePath });
+}
@@ -0,0 +1,50 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { FadeCurve, PipelineSummary } from './export';
+import type { OutputFormat } from './segments';
+
+/**
+ * One edit on a range of the source file, matching `EditOperation` in Rust;
+ * times in seconds
+ *
+ * `keep` ranges play in the order they were kept (the whole file when there
+ * are none), minus every `cut`; gains and fades over the same audio multiply.
+ */
+export type EditOperation =
+  | { op: 'keep'; start: number; end: number }
+  | { op: 'cut'; start: number; end: number }
+  | { op: 'gain'; start: number; end: number; gain_db: number }
+  | {
+      op: 'fade';
+      start: number;
+      end: number;
+      direction: 'in' | 'out';
+      /** Default 'linear' */
+      curve?: FadeCurve;
+    };
+
+/**
+ * A non-destructive edit of one file, matching `EditList` in Rust; undo by
+ * dropping the last operation
+ */
+export interface EditList {
+  operations: EditOperation[];
+}
+
+/**
+ * Render an edit list of a file to a new one; the file itself isn't changed
+ *
+ * Progress arrives as `progress` events with operation "render_edits".
+ */
+export async function renderEditList(
+  filePath: string,
+  edits: EditList,
+  outputPath: string,
+  format: OutputFormat
+): Promise<PipelineSummary> {
+  return await invoke<PipelineSummary>('render_edit_list', {
+    filePath,
+    edits,
+    outputPath,
+    format,
+  });
+}
@@ -0,0 +1,232 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { OutputFormat } from './segments';
+
+/**
+ * Loudness to normalize to, matching `LoudnessTarget` in Rust
+ */
+export interface LoudnessTarget {
+  integrated_lufs: number;
+  /** Highest true peak allowed, in dBTP */
+  true_peak_dbtp: number;
+  /** Limit peaks over the ceiling (default); false lowers the gain instead */
+  limiter?: boolean;
+}
+
+/**
+ * A named output format with optional loudness normalization
+ */
+export interface ExportPreset {
+  name: string;
+  format: OutputFormat;
+  loudness?: LoudnessTarget | null;
+}
+
+/**
+ * Levels of a piece of audio, matching `LoudnessMeasurement` in Rust
+ */
+export interface LoudnessMeasurement {
+  /** null for silence or audio under 400 ms */
+  integrated_lufs: number | null;
+  sample_peak: number;
+  sample_peak_dbfs: number;
+  true_peak_dbtp: number;
+}
+
+/**
+ * What normalizing found and did, matching `NormalizeReport` in Rust
+ */
+export interface NormalizeReport {
+  measured: LoudnessMeasurement;
+  gain_db: number;
+  /** Most the limiter took off a peak, in dB */
+  limited_db: number;
+  output: LoudnessMeasurement;
+}
+
+/**
+ * Built-in presets followed by the user's own
+ */
+export async function listExportPresets(): Promise<ExportPreset[]> {
+  return await invoke<ExportPreset[]>('list_export_presets');
+}
+
+/**
+ * Save a preset, replacing any with the same name
+ */
+export async function saveExportPreset(preset: ExportPreset): Promise<void> {
+  await invoke('save_export_preset', { preset });
+}
+
+export async function deleteExportPreset(name: string): Promise<void> {
+  await invoke('delete_export_preset', { name });
+}
+
+/**
+ * Export a file with a preset
+ *
+ * @returns The levels before and after, for presets that normalize loudness
+ */
+export async function exportAudio(
+  filePath: string,
+  outputPath: string,
+  preset: ExportPreset
+): Promise<NormalizeReport | null> {
+  return await invoke<NormalizeReport | null>('export_audio', { filePath, outputPath, preset });
+}
+
+/**
+ * Format to resample a file to, matching `ResampleSpec` in Rust; unset
+ * fields keep the input's
+ */
+export interface ResampleSpec {
+  sample_rate?: number | null;
+  channels?: number | null;
+  sample_format?: 'pcm16' | 'pcm24' | 'float32';
+}
+
+/** 16 kHz mono 16-bit WAV, what speech recognition tools expect */
+export const SPEECH_SPEC: ResampleSpec = {
+  sample_rate: 16000,
+  channels: 1,
+  sample_format: 'pcm16',
+};
+
+/**
+ * Frame counts from a streamed run, matching `PipelineSummary` in Rust
+ */
+export interface PipelineSummary {
+  output: { sample_rate: number; channels: number };
+  frames_in: number;
+  frames_out: number;
+}
+
+/**
+ * Write a WAV copy of a file at another sample rate, channel count or bit depth
+ */
+export async function resampleFile(
+  filePath: string,
+  outputPath: string,
+  spec: ResampleSpec
+): Promise<PipelineSummary> {
+  return await invoke<PipelineSummary>('resample_file', { filePath, outputPath, spec });
+}
+
+/**
+ * Write a copy of a file with its background noise turned down
+ *
+ * `strength` runs from 0 (unchanged) to 1 (full reduction). Progress
+ * arrives as `progress` events with operation "denoise".
+ */
+export async function denoiseFile(
+  filePath: string,
+  outputPath: string,
+  strength: number,
+  format: OutputFormat
+): Promise<PipelineSummary> {
+  return await invoke<PipelineSummary>('denoise_file', { filePath, outputPath, strength, format });
+}
+
+/**
+ * Objective scores for a processed file against its original, matching
+ * `QualityReport` in Rust; higher SNR and lower distance mean gentler
+ * processing
+ */
+export interface QualityReport {
+  offset_frames: number;
+  compared_frames: number;
+  /** null when the files match exactly */
+  snr_db: number | null;
+  /** Mean per-frame SNR, each frame clamped to -10..35 dB */
+  segmental_snr_db: number;
+  /** Mean log-spectral distance in dB */
+  spectral_distance_db: number;
+  scored_frames: number;
+}
+
+export interface QualityOptions {
+  max_offset_seconds: number;
+  frame_seconds: number;
+}
+
+/**
+ * Score how destructive processing was, e.g. a preset applied to a preview
+ */
+export async function measureQuality(
+  referencePath: string,
+  candidatePath: string,
+  options?: QualityOptions
+): Promise<QualityReport> {
+  return await invoke<QualityReport>('measure_quality', {
+    referencePath,
+    candidatePath,
+    options: options ?? null,
+  });
+}
+
+/**
+ * One end of a trim range, matching `TrimPoint` in Rust: seconds from the
+ * start, or seconds before the end (`{ before_end: 0 }` is the very end)
+ */
+export type TrimPoint = { at: number } | { before_end: number };
+
+export type FadeCurve = 'linear' | 'cosine';
+
+export interface TrimParams {
+  start: TrimPoint;
+  end: TrimPoint;
+  /** Fade in from silence over this long, so the clip doesn't click (default 0) */
+  fade_in_ms?: number;
+  /** Fade out to silence over this long (default 0) */
+  fade_out_ms?: number;
+  /** Default 'linear' */
+  fade_curve?: FadeCurve;
+}
+
+/**
+ * What `trimAudioFile` kept, with relative ends resolved to seconds
+ */
+export interface TrimSummary {
+  start: number;
+  end: number;
+  frames: number;
+}
+
+/**
+ * Cut a range out of a file without decoding the rest of it
+ *
+ * Progress arrives as `progress` events with operation "trim".
+ */
+export async function trimAudioFile(
+  filePath: string,
+  outputPath: string,
+  params: TrimParams,
+  format: OutputFormat
+): Promise<TrimSummary> {
+  return await invoke<TrimSummary>('trim_audio_file', {
+    filePath,
+    outputPath,
+    params,
+    format,
+  });
+}
+
+/**
+ * Cut several ranges out of a file and join them, in the order given, into one
+ *
+ * Progress arrives as `progress` events with operation "join".
+ *
+ * @returns What each range kept
+ */
+export async function joinAudioSegments(
+  filePath: string,
+  outputPath: string,
+  segments: TrimParams[],
+  format: OutputFormat
+): Promise<TrimSummary[]> {
+  return await invoke<TrimSummary[]>('join_audio_segments', {
+    filePath,
+    outputPath,
+    segments,
+    format,
+  });
+}
@@ -0,0 +1,126 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { AudioInfo } from './audioInfo';
+import type { TranscriptSegment } from './pacing';
+
+export type TranscriptKind = 'transcription' | 'translation';
+
+/**
+ * A transcript or translation to save, matching `NewTranscript` in Rust
+ */
+export interface NewTranscript {
+  kind: TranscriptKind;
+  /** The audio it was made from */
+  file_path?: string | null;
+  /** Language code of the text, e.g. "en" */
+  language?: string | null;
+  /** For a translation, the saved transcript it was translated from */
+  source_id?: number | null;
+  title?: string | null;
+  segments: TranscriptSegment[];
+}
+
+/**
+ * A saved transcript or translation, without its text
+ */
+export interface TranscriptSummary {
+  id: number;
+  kind: TranscriptKind;
+  file_path: string | null;
+  language: string | null;
+  /** null once the source has been deleted */
+  source_id: number | null;
+  title: string | null;
+  segment_count: number;
+  /** End of the last segment */
+  duration_seconds: number;
+  /** UTC, RFC 3339 */
+  created_at: string;
+}
+
+export interface TranscriptRecord extends TranscriptSummary {
+  segments: TranscriptSegment[];
+}
+
+export interface FileRecord {
+  path: string;
+  info: AudioInfo;
+  /** UTC, RFC 3339 */
+  recorded_at: string;
+}
+
+/**
+ * How a job finished, matching `JobRecord` in Rust
+ */
+export interface JobRecord {
+  id: number;
+  /** The command or tool, e.g. "export_audio" */
+  job: string;
+  outcome: 'completed' | 'failed';
+  inputs: string[];
+  outputs: string[];
+  failed: number;
+  error: string | null;
+  duration_seconds: number;
+  /** RFC 3339 time */
+  finished_at: string;
+}
+
+/**
+ * Keep a transcript or translation across restarts
+ *
+ * @returns Its id
+ */
+export async function saveTranscript(transcript: NewTranscript): Promise<number> {
+  return await invoke<number>('save_transcript', { transcript });
+}
+
+/**
+ * Saved transcripts and translations, newest first
+ */
+export async function listTranscripts(
+  kind?: TranscriptKind,
+  filePath?: string,
+): Promise<TranscriptSummary[]> {
+  return await invoke<TranscriptSummary[]>('list_transcripts', {
+    kind: kind ?? null,
+    filePath: filePath ?? null,
+  });
+}
+
+export async function getTranscript(id: number): Promise<TranscriptRecord> {
+  return await invoke<TranscriptRecord>('get_transcript', { id });
+}
+
+export async function deleteTranscript(id: number): Promise<boolean> {
+  return await invoke<boolean>('delete_transcript', { id });
+}
+
+/**
+ * Read a file's header and keep what it shows
+ */
+export async function rememberAudioFile(filePath: string): Promise<FileRecord> {
+  return await invoke<FileRecord>('remember_audio_file', { filePath });
+}
+
+export async function listAudioFiles(): Promise<FileRecord[]> {
+  return await invoke<FileRecord[]>('list_audio_files');
+}
+
+export async function forgetAudioFile(filePath: string): Promise<boolean> {
+  return await invoke<boolean>('forget_audio_file', { filePath });
+}
+
+/**
+ * Finished jobs, newest first; every long-running command is recorded
+ */
+export async function getJobHistory(limit?: number): Promise<JobRecord[]> {
+  return await invoke<JobRecord[]>('get_job_history', { limit: limit ?? null });
+}
+
+export async function deleteJobRecord(id: number): Promise<boolean> {
+  return await invoke<boolean>('delete_job_record', { id });
+}
+
+export async function clearJobHistory(): Promise<number> {
+  return await invoke<number>('clear_job_history');
+}
@@ -0,0 +1,50 @@
+/**
+ * A translatable message from the backend, matching `i18n::Message` in Rust
+ *
+ * Command errors and progress stage labels arrive in this shape.
+ */
+export interface Message {
+  /** Stable lookup key, e.g. `error.decode.no_audio_track` */
+  key: string;
+  /** Values for the translation's `{placeholders}` */
+  params: Record<string, string>;
+  /** English default */
+  text: string;
+}
+
+/** Translations for one locale, by message key */
+export type Catalog = Record<string, string>;
+
+/**
+ * Whether a value (e.g. a rejected `invoke`) is a backend message
+ */
+export function isMessage(value: unknown): value is Message {
+  return (
+    typeof value === 'object' &&
+    value !== null &&
+    typeof (value as Message).key === 'string' &&
+    typeof (value as Message).text === 'string'
+  );
+}
+
+/**
+ * Render a message in the catalog's language, or in English when the
+ * catalog has no entry for its key
+ */
+export function formatMessage(message: Message, catalog: Catalog = {}): string {
+  const template = catalog[message.key];
+  if (template === undefined) {
+    return message.text;
+  }
+  return template.replace(/\{(\w+)\}/g, (placeholder, name: string) =>
+    message.params[name] ?? placeholder
+  );
+}
+
+/**
+ * Text for anything thrown by a command: backend messages are translated,
+ * anything else is shown as is
+ */
+export function formatError(error: unknown, catalog: Catalog = {}): string {
+  return isMessage(error) ? formatMessage(error, catalog) : String(error);
+}
@@ -0,0 +1,112 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { Job, JobPriority } from './jobs';
+
+/**
+ * One episode of a podcast, matching `Episode` in Rust
+ */
+export interface Episode {
+  /** The item's guid, or its audio URL when it has none */
+  guid: string;
+  title: string;
+  /** pubDate as written in the feed */
+  published: string | null;
+  /** Day of `published`, YYYY-MM-DD */
+  date: string | null;
+  /** May contain HTML */
+  description: string | null;
+  duration_seconds: number | null;
+  audio_url: string;
+  mime_type: string | null;
+  size_bytes: number | null;
+  season: number | null;
+  episode: number | null;
+  image: string | null;
+}
+
+/**
+ * A podcast and its episodes, matching `Feed` in Rust
+ */
+export interface Feed {
+  title: string;
+  author: string | null;
+  /** May contain HTML */
+  description: string | null;
+  link: string | null;
+  image: string | null;
+  language: string | null;
+  /** Newest first */
+  episodes: Episode[];
+}
+
+/**
+ * Fetch a podcast's RSS feed and list its episodes
+ */
+export async function fetchPodcastFeed(url: string): Promise<Feed> {
+  return await invoke<Feed>('fetch_podcast_feed', { url });
+}
+
+/**
+ * Download episodes into the library, optionally queueing them for transcription
+ *
+ * @returns Where each episode was saved, in the order of `guids`
+ */
+export async function downloadPodcastEpisodes(
+  feed: Feed,
+  guids: string[],
+  transcribe = false
+): Promise<string[]> {
+  return await invoke<string[]>('download_podcast_episodes', { feed, guids, transcribe });
+}
+
+/**
+ * Transcriptions waiting on the background queue, in the order they'll run:
+ * highest priority first, oldest first within a priority unless moved.
+ * Reprioritize them with `setJobPriority` and reorder them with `moveJob`.
+ */
+export async function getTranscriptionQueue(): Promise<Job[]> {
+  return await invoke<Job[]>('get_transcription_queue');
+}
+
+/**
+ * Put a file on the background queue to be transcribed; a file already queued keeps its place
+ *
+ * @returns The waiting transcriptions after the change
+ */
+export async function queueTranscription(
+  path: string,
+  priority: JobPriority = 'normal'
+): Promise<Job[]> {
+  return await invoke<Job[]>('queue_transcription', { path, priority });
+}
+
+/**
+ * The media downloader (yt-dlp), matching `DownloaderInfo` in Rust
+ */
+export interface DownloaderInfo {
+  program: string;
+  /** null if it wouldn't run */
+  version: string | null;
+}
+
+/**
+ * Download the audio of a media URL into the library with yt-dlp
+ *
+ * @returns Where the file was saved
+ */
+export async function downloadMediaUrl(url: string, transcribe = false): Promise<string> {
+  return await invoke<string>('download_media_url', { url, transcribe });
+}
+
+/**
+ * The installed downloader, or null if yt-dlp can't be found
+ */
+export async function getDownloaderInfo(): Promise<DownloaderInfo | null> {
+  return await invoke<DownloaderInfo | null>('get_downloader_info');
+}
+
+/**
+ * Choose the yt-dlp executable; null looks for it on the PATH
+ */
+export async function setDownloaderPath(path: string | null): Promise<void> {
+  await invoke('set_downloader_path', { path });
+}
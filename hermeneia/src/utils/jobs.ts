@@ -0,0 +1,135 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type JobKind = 'transcription' | 'translation' | 'waveform';
+
+export type JobState = 'pending' | 'running' | 'completed' | 'failed' | 'cancelled';
+
+export type JobPriority = 'low' | 'normal' | 'high';
+
+/**
+ * Work for the background queue, matching `NewJob` in Rust
+ *
+ * Transcription jobs take `{ path, plan? }` and finish with the
+ * `Transcript`; they wait until a speech-to-text engine is registered.
+ * Waveform jobs take `{ path, options? }` and finish with the peaks.
+ * Translation jobs take a `TranslationRequest` plus an optional `engine`
+ * and finish with the translated segments.
+ */
+export interface NewJob {
+  kind: JobKind;
+  payload: unknown;
+  priority?: JobPriority;
+  /** Times to run the job again after it fails */
+  max_retries?: number;
+  /** Shown in the queue, e.g. the file name */
+  label?: string | null;
+}
+
+/**
+ * Why a job left the GPU for the CPU, matching `GpuFallback` in Rust; also
+ * sent as the `gpu-fallback` event
+ */
+export interface GpuFallback {
+  backend: 'cuda' | 'rocm' | 'metal' | 'vulkan';
+  device_index: number;
+  kind: 'driver_mismatch' | 'out_of_memory' | 'unsupported' | 'other';
+  /** Error message from the engine */
+  message: string;
+  /** The device is skipped for the rest of the session */
+  disabled: boolean;
+}
+
+/**
+ * A job on the queue; also sent as the `job-queue` event whenever one is
+ * added or changes
+ */
+export interface Job {
+  id: number;
+  kind: JobKind;
+  label: string | null;
+  payload: unknown;
+  priority: JobPriority;
+  state: JobState;
+  /** Runs started so far, retries included */
+  attempts: number;
+  max_retries: number;
+  /** Fraction of the current run done, 0 to 1 */
+  progress: number;
+  /** Why the last run failed; kept while a retry waits */
+  error: string | null;
+  result: unknown;
+  /** Set when a run couldn't start on the GPU and went on on the CPU */
+  fallback: GpuFallback | null;
+  /** RFC 3339 times */
+  submitted_at: string;
+  started_at: string | null;
+  finished_at: string | null;
+}
+
+/** Event emitted with a `Job` whenever one is added or changes */
+export const JOB_QUEUE_EVENT = 'job-queue';
+
+/** Event emitted with a `GpuFallback` when a running job moves to the CPU */
+export const GPU_FALLBACK_EVENT = 'gpu-fallback';
+
+export async function submitJob(job: NewJob): Promise<Job> {
+  return await invoke<Job>('submit_job', { job });
+}
+
+/**
+ * Every job on the queue, oldest first unless moved
+ */
+export async function getJobs(): Promise<Job[]> {
+  return await invoke<Job[]>('get_jobs');
+}
+
+export async function getJob(id: number): Promise<Job> {
+  return await invoke<Job>('get_job', { id });
+}
+
+/**
+ * Stop a job; false if it had already finished
+ */
+export async function cancelJob(id: number): Promise<boolean> {
+  return await invoke<boolean>('cancel_job', { id });
+}
+
+/**
+ * Queue a failed or cancelled job again
+ */
+export async function retryJob(id: number): Promise<boolean> {
+  return await invoke<boolean>('retry_job', { id });
+}
+
+/**
+ * Change a pending job's priority
+ */
+export async function setJobPriority(id: number, priority: JobPriority): Promise<boolean> {
+  return await invoke<boolean>('set_job_priority', { id, priority });
+}
+
+/**
+ * Move a pending job to `index` in the run order (drag-and-drop). Dropped
+ * among jobs of another priority, it takes on their priority.
+ */
+export async function moveJob(id: number, index: number): Promise<boolean> {
+  return await invoke<boolean>('move_job', { id, index });
+}
+
+export async function clearFinishedJobs(): Promise<number> {
+  return await invoke<number>('clear_finished_jobs');
+}
+
+/**
+ * How many jobs run at once
+ */
+export async function getBackgroundJobs(): Promise<number> {
+  return await invoke<number>('get_background_jobs');
+}
+
+/**
+ * Save how many jobs run at once; null goes back to one at a time
+ */
+export async function setBackgroundJobs(count: number | null): Promise<void> {
+  await invoke('set_background_jobs', { count });
+}
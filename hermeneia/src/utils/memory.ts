@@ -0,0 +1,28 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/**
+ * Low-memory mode and the budget it works within, matching `MemoryStatus` in Rust
+ *
+ * In low-memory mode every operation that can stream does, and the reader
+ * and probe caches are kept small, so multi-hour files stay workable on
+ * 8 GB machines.
+ */
+export interface MemoryStatus {
+  low_memory: boolean;
+  /** The saved setting; null follows the machine (on at 8 GB or less) */
+  setting: boolean | null;
+  /** Physical memory, when it could be read */
+  total_mb: number | null;
+  budget_mb: number;
+}
+
+export async function getMemoryStatus(): Promise<MemoryStatus> {
+  return await invoke<MemoryStatus>('get_memory_status');
+}
+
+/**
+ * Turn low-memory mode on or off, or pass null to follow the machine again
+ */
+export async function setLowMemory(enabled: boolean | null): Promise<MemoryStatus> {
+  return await invoke<MemoryStatus>('set_low_memory', { enabled });
+}
@@ -0,0 +1,102 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/**
+ * A model file and what it should contain, matching `ModelSpec` in Rust
+ */
+export interface ModelSpec {
+  /** Shown in messages, e.g. "whisper-base.en" */
+  name: string;
+  /** File name inside the models folder */
+  file_name: string;
+  /** Size in bytes */
+  size: number;
+  /** SHA-256 of the whole file, in hex */
+  sha256: string;
+  /** Where to download it again; null for models that can only be imported */
+  url: string | null;
+}
+
+export type ModelFormat = 'ggml' | 'gguf' | 'safetensors';
+
+/**
+ * A model in the models folder, as recorded in its registry
+ */
+export interface InstalledModel {
+  name: string;
+  file_name: string;
+  size: number;
+  sha256: string;
+  /** Where it was downloaded from */
+  url: string | null;
+  /** The local file it was copied from */
+  imported_from: string | null;
+  /** False when the checksum was taken from an imported file rather than a known model */
+  checksum_known: boolean;
+  format: ModelFormat | null;
+  /** Local time with offset, RFC 3339 */
+  installed_at: string;
+}
+
+/**
+ * What a check of a model file found
+ */
+export type ModelStatus =
+  | { state: 'ok' }
+  | { state: 'missing' }
+  | { state: 'truncated'; expected: number; actual: number }
+  | { state: 'oversized'; expected: number; actual: number }
+  | { state: 'checksum_mismatch'; expected: string; actual: string };
+
+/**
+ * Check an installed model's size and checksum; reports progress as "model_verify"
+ */
+export async function verifyModel(spec: ModelSpec): Promise<ModelStatus> {
+  return await invoke<ModelStatus>('verify_model', { spec });
+}
+
+/**
+ * Download a model again if it's missing or damaged, resolving to its path
+ */
+export async function repairModel(spec: ModelSpec): Promise<string> {
+  return await invoke<string>('repair_model', { spec });
+}
+
+/**
+ * Install a model file the user already has, rejecting if it doesn't match `spec`
+ */
+export async function importModel(spec: ModelSpec, sourcePath: string): Promise<string> {
+  return await invoke<string>('import_model', { spec, sourcePath });
+}
+
+export async function listInstalledModels(): Promise<InstalledModel[]> {
+  return await invoke<InstalledModel[]>('list_installed_models');
+}
+
+/**
+ * Files in a folder, e.g. on a USB stick, that look like models
+ */
+export async function findModelFiles(folder: string): Promise<string[]> {
+  return await invoke<string[]>('find_model_files', { folder });
+}
+
+/**
+ * Install and register a model file without the downloader
+ *
+ * A copy of one of `known` is checked against its checksum and installed as
+ * that model; anything else is installed as `name`, or under its file name.
+ * A model with the same name or file name is only replaced with `overwrite`.
+ * Reports progress as "model_import".
+ */
+export async function importLocalModel(
+  sourcePath: string,
+  known: ModelSpec[],
+  name?: string,
+  overwrite = false
+): Promise<InstalledModel> {
+  return await invoke<InstalledModel>('import_local_model', {
+    sourcePath,
+    name: name ?? null,
+    known,
+    overwrite,
+  });
+}
@@ -0,0 +1,49 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/**
+ * Who gets told when a long job finishes, matching `NotificationSettings` in Rust
+ */
+export interface NotificationSettings {
+  /** Show a desktop notification */
+  desktop: boolean;
+  /** URLs that get a JSON `JobEvent` POSTed to them */
+  webhooks: string[];
+  on_success: boolean;
+  on_failure: boolean;
+  /** Jobs quicker than this aren't announced (default 30 s) */
+  min_seconds: number;
+}
+
+/**
+ * The webhook payload for a finished job
+ */
+export interface JobEvent {
+  job: string;
+  outcome: 'completed' | 'failed';
+  inputs: string[];
+  /** Files written by the parts that succeeded */
+  outputs: string[];
+  failed: number;
+  /** The first failure's message */
+  error: string | null;
+  duration_seconds: number;
+  /** Local time with offset, RFC 3339 */
+  finished_at: string;
+}
+
+export async function getNotificationSettings(): Promise<NotificationSettings> {
+  return await invoke<NotificationSettings>('get_notification_settings');
+}
+
+export async function setNotificationSettings(
+  notifications: NotificationSettings
+): Promise<void> {
+  await invoke('set_notification_settings', { notifications });
+}
+
+/**
+ * Send a sample notification through each channel, rejecting if one fails
+ */
+export async function sendTestNotification(notifications: NotificationSettings): Promise<void> {
+  await invoke('send_test_notification', { notifications });
+}
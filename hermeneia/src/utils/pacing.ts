@@ -0,0 +1,75 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/**
+ * A word of a transcript, timed in seconds from the start of the file
+ */
+export interface TranscriptWord {
+  start: number;
+  end: number;
+  text: string;
+}
+
+/**
+ * A piece of a transcript, matching `transcribe::Segment` in Rust
+ */
+export interface TranscriptSegment {
+  start: number;
+  end: number;
+  text: string;
+  /** Word timings, when the engine reports them */
+  words?: TranscriptWord[];
+  /** Who is talking, from diarization or labelled by the user */
+  speaker?: string | null;
+}
+
+/**
+ * Settings for pacing analysis; every field is optional
+ */
+export interface PacingOptions {
+  /** Window each point of the rate curve counts words over (default 30 s) */
+  window_seconds?: number;
+  /** Time between points of the rate curve (default 10 s) */
+  step_seconds?: number;
+  /** Shortest gap between words that counts as a pause (default 0.5 s) */
+  min_pause_seconds?: number;
+  /** Words and phrases counted as fillers (default um, uh, you know, ...) */
+  fillers?: string[];
+}
+
+export interface RatePoint {
+  /** Middle of the window, in seconds */
+  time: number;
+  words_per_minute: number;
+  fillers: number;
+}
+
+/**
+ * Delivery statistics, matching `PacingReport` in Rust; times in seconds
+ */
+export interface PacingReport {
+  words: number;
+  speaking_span: number;
+  words_per_minute: number;
+  /** Words per minute with pauses left out */
+  articulation_rate: number;
+  rate: RatePoint[];
+  pauses: { start: number; duration: number }[];
+  pauses_per_minute: number;
+  mean_pause_seconds: number;
+  longest_pause: number;
+  /** Most frequent first */
+  fillers: { filler: string; count: number }[];
+  fillers_per_minute: number;
+  /** Some segments had no word timing, so their words were spread evenly */
+  estimated_timing: boolean;
+}
+
+/**
+ * Speaking rate over time, pauses and filler words of a finished transcript
+ */
+export async function analyzePacing(
+  segments: TranscriptSegment[],
+  options?: PacingOptions
+): Promise<PacingReport> {
+  return await invoke<PacingReport>('analyze_pacing', { segments, options: options ?? null });
+}
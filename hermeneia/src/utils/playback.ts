@@ -0,0 +1,121 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { TranscriptSegment } from './pacing';
+
+/**
+ * Position, state and format of the open file, matching `PlaybackSnapshot` in Rust
+ */
+export interface PlaybackSnapshot {
+  state: 'playing' | 'paused' | 'ended';
+  position_seconds: number;
+  /** null when the container doesn't report a length */
+  duration_seconds: number | null;
+  sample_rate: number;
+  channels: number;
+  volume: number;
+  /** Start and end of the A-B loop; null when not looping */
+  loop_seconds: [number, number] | null;
+}
+
+/**
+ * Playback state plus the review transcript segment being spoken
+ */
+export interface PlaybackPosition extends PlaybackSnapshot {
+  /** Index into the review transcript; null between segments */
+  segment: number | null;
+}
+
+/**
+ * Play a region over and over, e.g. while correcting a transcript segment
+ *
+ * Jumps to the start unless playback is already inside the region.
+ */
+export async function setLoopRegion(startSeconds: number, endSeconds: number): Promise<void> {
+  await invoke('set_loop_region', { startSeconds, endSeconds });
+}
+
+/**
+ * Stop looping and play on from where it is
+ */
+export async function clearLoopRegion(): Promise<void> {
+  await invoke('clear_loop_region');
+}
+
+/**
+ * An output device, matching `OutputDevice` in Rust
+ */
+export interface OutputDevice {
+  /** What `setOutputDevice` takes */
+  id: string;
+  name: string;
+  /** The system's default output */
+  is_default: boolean;
+}
+
+/**
+ * Output devices playback can use, the system default first
+ */
+export async function listOutputDevices(): Promise<OutputDevice[]> {
+  return await invoke<OutputDevice[]>('list_output_devices');
+}
+
+/**
+ * Play through another device, or the system default (null), and remember it
+ *
+ * The open file carries on from the same position.
+ */
+export async function setOutputDevice(deviceId: string | null): Promise<void> {
+  await invoke('set_output_device', { deviceId });
+}
+
+/**
+ * Event emitted with a `PlaybackPosition` several times a second while
+ * playing, and after every pause, seek or end
+ */
+export const PLAYBACK_POSITION_EVENT = 'playback-position';
+
+/**
+ * Save how many position events a second to send while playing; null goes back to 20
+ */
+export async function setPlaybackUpdateRate(hz: number | null): Promise<void> {
+  await invoke('set_playback_update_rate', { hz });
+}
+
+/**
+ * Read an SRT or WebVTT file as a transcript and follow it during playback
+ *
+ * @returns One segment per cue, sorted by start
+ */
+export async function importSubtitles(filePath: string): Promise<TranscriptSegment[]> {
+  return await invoke<TranscriptSegment[]>('import_subtitles', { filePath });
+}
+
+/**
+ * Follow a transcript during playback, e.g. one just transcribed or edited;
+ * it is saved, so it is still there after a restart
+ */
+export async function setReviewTranscript(segments: TranscriptSegment[]): Promise<void> {
+  await invoke('set_review_transcript', { segments });
+}
+
+/**
+ * The transcript followed during playback, as last set or imported
+ */
+export async function getReviewTranscript(): Promise<TranscriptSegment[]> {
+  return await invoke<TranscriptSegment[]>('get_review_transcript');
+}
+
+/**
+ * Where playback is right now; null when nothing is open
+ *
+ * Listen for `PLAYBACK_POSITION_EVENT` rather than polling this while playing.
+ */
+export async function getPlaybackPosition(): Promise<PlaybackPosition | null> {
+  return await invoke<PlaybackPosition | null>('get_playback_position');
+}
+
+/**
+ * Jump to the start of a review transcript segment, e.g. when it's clicked
+ */
+export async function seekToSegment(index: number): Promise<void> {
+  await invoke('seek_to_segment', { index });
+}
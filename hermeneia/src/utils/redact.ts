@@ -0,0 +1,68 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { Marker } from './waveform';
+import type { OutputFormat } from './segments';
+
+/**
+ * What redacted ranges are replaced with, matching `RedactionStyle` in Rust
+ */
+export type RedactionStyle =
+  | { style: 'silence' }
+  | { style: 'bleep'; frequency_hz: number; level_db: number };
+
+/** The usual 1 kHz censor tone */
+export const BLEEP: RedactionStyle = { style: 'bleep', frequency_hz: 1000, level_db: -12 };
+
+/**
+ * Settings for redaction; every field is optional
+ */
+export interface RedactOptions {
+  /** Default silence */
+  style?: RedactionStyle;
+  /** Ramps either side of each range, to avoid clicks (default 5 ms) */
+  fade_ms?: number;
+}
+
+/**
+ * A stretch of audio that was removed; times in seconds
+ */
+export interface RedactedRange {
+  start: number;
+  end: number;
+  /** Titles of the markers that asked for it */
+  reasons: string[];
+}
+
+/**
+ * The audit trail of a redaction, also saved as `<output>.redactions.json`
+ */
+export interface RedactionLog {
+  input: string;
+  output: string;
+  /** Local time with offset, RFC 3339 */
+  created_at: string;
+  style: RedactionStyle;
+  ranges: RedactedRange[];
+  /** Total seconds removed */
+  redacted: number;
+}
+
+/**
+ * Write a copy of a file with each marker's range silenced or bleeped
+ *
+ * Every marker needs an end; its title is logged as the reason.
+ */
+export async function redactFile(
+  filePath: string,
+  outputPath: string,
+  ranges: Marker[],
+  format: OutputFormat,
+  options?: RedactOptions
+): Promise<RedactionLog> {
+  return await invoke<RedactionLog>('redact_file', {
+    filePath,
+    outputPath,
+    ranges,
+    format,
+    options: options ?? null,
+  });
+}
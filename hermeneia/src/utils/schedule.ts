@@ -0,0 +1,47 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/**
+ * A daily stretch of local time as "HH:MM"; an end before the start runs past midnight
+ */
+export interface TimeWindow {
+  start: string;
+  end: string;
+}
+
+/**
+ * When queued heavy work (transcription) may run, matching `Schedule` in Rust
+ *
+ * With no windows and `when_idle` off, work runs whenever it's queued.
+ */
+export interface Schedule {
+  windows: TimeWindow[];
+  /** Also run once nobody has used the keyboard or mouse for `idle_minutes` */
+  when_idle: boolean;
+  idle_minutes: number;
+}
+
+/**
+ * Whether queued work may run now; also sent as the `schedule-status` event
+ */
+export interface ScheduleStatus {
+  gate: 'unrestricted' | 'in_window' | 'idle' | 'waiting';
+  /** Start of the next window ("HH:MM"), while waiting for one */
+  next_window: string | null;
+  /** Transcriptions waiting on the background queue */
+  pending: number;
+}
+
+/** Event emitted whenever the schedule status changes */
+export const SCHEDULE_STATUS_EVENT = 'schedule-status';
+
+export async function getSchedule(): Promise<Schedule> {
+  return await invoke<Schedule>('get_schedule');
+}
+
+export async function setSchedule(schedule: Schedule): Promise<void> {
+  await invoke('set_schedule', { schedule });
+}
+
+export async function getScheduleStatus(): Promise<ScheduleStatus> {
+  return await invoke<ScheduleStatus>('get_schedule_status');
+}
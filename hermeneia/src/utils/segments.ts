@@ -0,0 +1,52 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { Marker } from './waveform';
+
+/**
+ * Output codec and its settings, matching `OutputFormat` in Rust
+ */
+export type OutputFormat =
+  | { format: 'wav'; sample_format: 'pcm16' | 'pcm24' | 'float32' }
+  | { format: 'flac'; bits_per_sample: 16 | 24; compression_level?: number }
+  | { format: 'mp3'; bitrate_kbps: number }
+  | { format: 'opus'; bitrate_kbps: number; speech?: boolean };
+
+/**
+ * Read a file's chapters, a cue sheet's tracks or an Audacity label track
+ * (`.txt`) as markers
+ */
+export async function readMarkers(filePath: string): Promise<Marker[]> {
+  return await invoke<Marker[]>('read_markers', { filePath });
+}
+
+/**
+ * Save markers as an Audacity label track; markers without an end become point labels
+ */
+export async function exportAudacityLabels(outputPath: string, markers: Marker[]): Promise<void> {
+  await invoke('export_audacity_labels', { outputPath, markers });
+}
+
+/**
+ * Export one file per marker into `outputDir`
+ *
+ * The template can use {segment}, {index}, {title}, {stem} and {format};
+ * it defaults to the saved output template when that tells pieces apart,
+ * else "{segment} {title}.{format}". Existing files follow the saved
+ * collision policy.
+ *
+ * @returns The files written, in timeline order; skipped pieces are left out
+ */
+export async function exportSegments(
+  filePath: string,
+  markers: Marker[],
+  outputDir: string,
+  format: OutputFormat,
+  template?: string
+): Promise<string[]> {
+  return await invoke<string[]>('export_segments', {
+    filePath,
+    markers,
+    outputDir,
+    format,
+    template,
+  });
+}
@@ -0,0 +1,66 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { TranscriptSegment } from './pacing';
+
+/**
+ * Settings for talk-time analysis; every field is optional
+ */
+export interface TalkTimeOptions {
+  /** Least overlap with the other speaker that counts as an interruption (default 0.3 s) */
+  min_overlap_seconds?: number;
+}
+
+/**
+ * One speaker's share of a conversation; times in seconds
+ */
+export interface SpeakerStats {
+  speaker: string;
+  talk_time: number;
+  /** Fraction of all labelled talk time (0 to 1) */
+  share: number;
+  turns: number;
+  longest_turn: number;
+  longest_turn_start: number;
+  /** Turns started while someone else was still talking */
+  interruptions: number;
+  /** Times someone else started talking over this speaker */
+  interrupted: number;
+  words: number;
+}
+
+/**
+ * Who talked how much, matching `TalkTimeReport` in Rust
+ */
+export interface TalkTimeReport {
+  /** Most talk time first */
+  speakers: SpeakerStats[];
+  /** Talk in segments with no speaker label */
+  unlabelled: number;
+}
+
+/**
+ * Talk time, turns, interruptions and longest turn of each speaker
+ */
+export async function analyzeTalkTime(
+  segments: TranscriptSegment[],
+  options?: TalkTimeOptions
+): Promise<TalkTimeReport> {
+  return await invoke<TalkTimeReport>('analyze_talk_time', { segments, options: options ?? null });
+}
+
+/**
+ * Write a CSV with a row per speaker per transcript plus batch totals, and
+ * return the totals
+ *
+ * @param transcripts - Pairs of a name for the `file` column and its segments
+ */
+export async function exportTalkTime(
+  outputPath: string,
+  transcripts: [string, TranscriptSegment[]][],
+  options?: TalkTimeOptions
+): Promise<TalkTimeReport> {
+  return await invoke<TalkTimeReport>('export_talk_time', {
+    outputPath,
+    transcripts,
+    options: options ?? null,
+  });
+}
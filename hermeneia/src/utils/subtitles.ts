@@ -0,0 +1,109 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { TranscriptSegment } from './pacing';
+
+/**
+ * How bilingual subtitles share the screen: both languages in each cue, or
+ * the original then the translation within each cue's time
+ */
+export type BilingualLayout = 'stacked' | 'alternating';
+
+export type Placement =
+  | 'bottom-left'
+  | 'bottom'
+  | 'bottom-right'
+  | 'top-left'
+  | 'top'
+  | 'top-right';
+
+/**
+ * Look of one speaker's lines in ASS/SSA
+ */
+export interface SpeakerStyle {
+  speaker: string;
+  /** `#RRGGBB` */
+  color: string;
+  placement?: Placement;
+}
+
+/**
+ * Look of ASS/SSA subtitles, matching `SubtitleStyle` in Rust; every field
+ * is optional
+ */
+export interface SubtitleStyle {
+  font?: string;
+  font_size?: number;
+  outline?: string;
+  /** Video size the positions and font size refer to */
+  width?: number;
+  height?: number;
+  margin?: number;
+  /** Speakers not listed get a color each, at the bottom */
+  speakers?: SpeakerStyle[];
+}
+
+/**
+ * Write a transcript as SRT, WebVTT, ASS or SSA, chosen by the output's extension
+ *
+ * With a translation the subtitles are bilingual, timed from `segments`.
+ * ASS and SSA style each speaker label with `style`.
+ */
+export async function exportSubtitles(
+  outputPath: string,
+  segments: TranscriptSegment[],
+  translation?: TranscriptSegment[],
+  layout: BilingualLayout = 'stacked',
+  style?: SubtitleStyle
+): Promise<void> {
+  await invoke('export_subtitles', {
+    outputPath,
+    segments,
+    translation: translation ?? null,
+    layout,
+    style: style ?? null,
+  });
+}
+
+export type CaptionFormat = 'srt' | 'vtt';
+
+/**
+ * How much text a cue may hold, matching `LineLimits` in Rust; every field
+ * is optional
+ */
+export interface LineLimits {
+  /** Characters per line, 42 by default */
+  max_chars?: number;
+  /** Lines per cue, 2 by default; longer segments become several cues */
+  max_lines?: number;
+  /** Name speakers ("Name: " in SRT, voice tags in WebVTT); on by default */
+  speaker_labels?: boolean;
+}
+
+/**
+ * Write a transcript as SRT or WebVTT with lines rewrapped to `limits`
+ */
+export async function exportTranscript(
+  outputPath: string,
+  segments: TranscriptSegment[],
+  format: CaptionFormat,
+  limits?: LineLimits
+): Promise<void> {
+  await invoke('export_transcript', { outputPath, segments, format, limits: limits ?? null });
+}
+
+/**
+ * Save a transcript as an Audacity label track, one label per segment
+ */
+export async function exportTranscriptLabels(
+  outputPath: string,
+  segments: TranscriptSegment[]
+): Promise<void> {
+  await invoke('export_transcript_labels', { outputPath, segments });
+}
+
+/**
+ * Read an Audacity label track back as transcript segments, without word
+ * timing or speakers
+ */
+export async function importTranscriptLabels(filePath: string): Promise<TranscriptSegment[]> {
+  return await invoke<TranscriptSegment[]>('import_transcript_labels', { filePath });
+}
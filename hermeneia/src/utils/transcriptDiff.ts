@@ -0,0 +1,52 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { TranscriptSegment } from './pacing';
+
+export type ChangeKind = 'insertion' | 'deletion' | 'substitution';
+
+/**
+ * One place where two transcripts disagree; times in seconds
+ */
+export interface TranscriptChange {
+  kind: ChangeKind;
+  start: number;
+  end: number;
+  /** The reference's words, as written; empty for an insertion */
+  reference: string;
+  /** The candidate's words, as written; empty for a deletion */
+  candidate: string;
+  /** Indexes of the reference segments the words are in */
+  reference_segments: number[];
+  /** Indexes of the candidate segments the words are in */
+  candidate_segments: number[];
+}
+
+export interface DiffStats {
+  reference_words: number;
+  candidate_words: number;
+  /** Words both transcripts agree on */
+  matching: number;
+  substitutions: number;
+  deletions: number;
+  insertions: number;
+  /** Errors over the reference's words, taking the reference as correct */
+  word_error_rate: number;
+}
+
+export interface TranscriptDiff {
+  /** In time order */
+  changes: TranscriptChange[];
+  stats: DiffStats;
+  /** Some change times were estimated for lack of word timing */
+  estimated_timing: boolean;
+}
+
+/**
+ * Align two transcripts of the same audio word by word, ignoring case and
+ * punctuation, and list where they differ
+ */
+export async function diffTranscripts(
+  reference: TranscriptSegment[],
+  candidate: TranscriptSegment[]
+): Promise<TranscriptDiff> {
+  return await invoke<TranscriptDiff>('diff_transcripts', { reference, candidate });
+}
@@ -0,0 +1,105 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { JobPriority } from './jobs';
+import type { TranscriptSegment } from './pacing';
+
+/**
+ * Where translations come from, matching `TranslationEngine` in Rust
+ *
+ * A LibreTranslate server running on this machine translates offline. A
+ * command gets `{"source", "target", "texts"}` as JSON on stdin and prints
+ * a JSON array with one translation per text.
+ */
+export type TranslationEngine =
+  | { engine: 'libre_translate'; url: string; api_key?: string | null }
+  | { engine: 'command'; program: string; args?: string[] };
+
+/**
+ * A transcript sent with the job, or a subtitle / label track file to read
+ */
+export type TranslationInput =
+  | { kind: 'transcript'; segments: TranscriptSegment[] }
+  | { kind: 'file'; path: string };
+
+export interface TranslationRequest {
+  /** Language codes, e.g. "en" and "es" */
+  source_language: string;
+  target_language: string;
+  input: TranslationInput;
+}
+
+export type TranslationStatus = 'queued' | 'running' | 'completed' | 'failed' | 'cancelled';
+
+/**
+ * A translation on the background queue, matching `TranslationJob` in Rust
+ */
+export interface TranslationJob {
+  id: number;
+  source_language: string;
+  target_language: string;
+  input_path: string | null;
+  status: TranslationStatus;
+  /** Fraction of segments translated, 0 to 1 */
+  progress: number;
+  error: string | null;
+  /** RFC 3339 times */
+  submitted_at: string;
+  finished_at: string | null;
+}
+
+export async function getTranslationEngine(): Promise<TranslationEngine | null> {
+  return await invoke<TranslationEngine | null>('get_translation_engine');
+}
+
+export async function setTranslationEngine(engine: TranslationEngine | null): Promise<void> {
+  await invoke('set_translation_engine', { engine });
+}
+
+/**
+ * Put a translation on the background queue; without an engine the saved
+ * one is used. Follow it with `getTranslationJob` or the `job-queue` event.
+ */
+export async function submitTranslation(
+  request: TranslationRequest,
+  engine?: TranslationEngine,
+  priority?: JobPriority
+): Promise<TranslationJob> {
+  return await invoke<TranslationJob>('submit_translation', {
+    request,
+    engine: engine ?? null,
+    priority: priority ?? null,
+  });
+}
+
+/** Translations on the background queue, oldest first */
+export async function getTranslationJobs(): Promise<TranslationJob[]> {
+  return await invoke<TranslationJob[]>('get_translation_jobs');
+}
+
+export async function getTranslationJob(id: number): Promise<TranslationJob> {
+  return await invoke<TranslationJob>('get_translation_job', { id });
+}
+
+/**
+ * Stop a queued or running translation; resolves false if it had already finished
+ */
+export async function cancelTranslation(id: number): Promise<boolean> {
+  return await invoke<boolean>('cancel_translation', { id });
+}
+
+/** The translated transcript of a completed job */
+export async function getTranslationResult(id: number): Promise<TranscriptSegment[]> {
+  return await invoke<TranscriptSegment[]>('get_translation_result', { id });
+}
+
+/**
+ * Save a completed job as subtitles (.srt, .vtt, .ass, .ssa) or an
+ * Audacity label track (.txt)
+ */
+export async function saveTranslation(id: number, outputPath: string): Promise<void> {
+  await invoke('save_translation', { id, outputPath });
+}
+
+/** Drop finished translations and their results; resolves to how many were removed */
+export async function clearFinishedTranslations(): Promise<number> {
+  return await invoke<number>('clear_finished_translations');
+}
@@ -0,0 +1,194 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { Message } from './i18n';
+
+/**
+ * Waveform peak data for visualization, matching `WaveformPeaks` in Rust
+ */
+export interface WaveformPeaks {
+  /** Minimum amplitude per segment (-1.0 to 1.0) */
+  minPeaks: Float32Array;
+  /** Maximum amplitude per segment (-1.0 to 1.0) */
+  maxPeaks: Float32Array;
+  /** RMS level per segment, when requested */
+  rmsPeaks?: Float32Array;
+  /** The same peaks for each channel, when requested */
+  channelPeaks?: WaveformPeaks[];
+  numPeaks: number;
+  durationSeconds: number;
+  channels: number;
+  sampleRate: number;
+}
+
+// Must match WaveformPeaks::BINARY_HEADER_LEN and the FLAG_* constants
+const HEADER_LEN = 20;
+const FLAG_RMS = 1;
+const FLAG_PER_CHANNEL = 2;
+
+/**
+ * Read the binary layout written by `WaveformPeaks::to_bytes`
+ *
+ * The peak arrays are views into the buffer, not copies.
+ */
+export function decodeWaveformPeaks(buffer: ArrayBuffer): WaveformPeaks {
+  const view = new DataView(buffer);
+  const numPeaks = view.getUint32(12, true);
+  const channels = view.getUint16(16, true);
+  const flags = view.getUint16(18, true);
+
+  const hasRms = (flags & FLAG_RMS) !== 0;
+  const perChannel = (flags & FLAG_PER_CHANNEL) !== 0;
+  const arrays = hasRms ? 3 : 2;
+  const sets = perChannel ? 1 + channels : 1;
+  if (
+    (flags & ~(FLAG_RMS | FLAG_PER_CHANNEL)) !== 0 ||
+    buffer.byteLength !== HEADER_LEN + sets * arrays * numPeaks * 4
+  ) {
+    throw new Error('Malformed waveform peak data');
+  }
+
+  const header = {
+    numPeaks,
+    durationSeconds: view.getFloat64(0, true),
+    sampleRate: view.getUint32(8, true),
+  };
+  const readSet = (index: number, setChannels: number): WaveformPeaks => {
+    const offset = HEADER_LEN + index * arrays * numPeaks * 4;
+    const array = (n: number) => new Float32Array(buffer, offset + n * numPeaks * 4, numPeaks);
+    return {
+      ...header,
+      channels: setChannels,
+      minPeaks: array(0),
+      maxPeaks: array(1),
+      rmsPeaks: hasRms ? array(2) : undefined,
+    };
+  };
+
+  const peaks = readSet(0, channels);
+  if (perChannel) {
+    peaks.channelPeaks = Array.from({ length: channels }, (_, ch) => readSet(1 + ch, 1));
+  }
+  return peaks;
+}
+
+/**
+ * Extraction settings, matching `WaveformOptions` in Rust; every field is optional
+ */
+export interface WaveformOptions {
+  /** Number of peaks (default 2000) */
+  num_peaks?: number;
+  /** Also return each channel's peaks */
+  per_channel?: boolean;
+  /** Also return the RMS level of each segment */
+  rms?: boolean;
+  /** Return levels on a dB scale down to this floor (e.g. -60) */
+  db_floor?: number;
+  /** Range to cover, in seconds */
+  start?: number;
+  end?: number;
+}
+
+/**
+ * Extract waveform peaks for an audio file
+ */
+export async function getWaveformPeaks(
+  filePath: string,
+  options: WaveformOptions = {}
+): Promise<WaveformPeaks> {
+  const buffer = await invoke<ArrayBuffer>('get_waveform_peaks', { filePath, options });
+  return decodeWaveformPeaks(buffer);
+}
+
+/**
+ * Peaks as JSON, matching `WaveformPeaks` in Rust; `rms_peaks` and
+ * `channel_peaks` are left out unless requested
+ */
+export interface WaveformPeaksJson {
+  min_peaks: number[];
+  max_peaks: number[];
+  rms_peaks?: number[];
+  channel_peaks?: WaveformPeaksJson[];
+  num_peaks: number;
+  duration_seconds: number;
+  channels: number;
+  sample_rate: number;
+}
+
+/**
+ * Event emitted with a `WaveformStreamEvent` while `streamWaveformPeaks` runs
+ */
+export const WAVEFORM_STREAM_EVENT = 'waveform-stream';
+
+/**
+ * Payload of `WAVEFORM_STREAM_EVENT`; `id` is the one `streamWaveformPeaks`
+ * returned. Chunks arrive in order, then one `done` or `failed`.
+ */
+export type WaveformStreamEvent =
+  | {
+      kind: 'chunk';
+      id: number;
+      /** Index of the chunk's first peak in the whole waveform */
+      start: number;
+      /** `duration_seconds` is the chunk's own */
+      peaks: WaveformPeaksJson;
+      /** Share of all the peaks done, 0 to 1 */
+      fraction: number;
+    }
+  | { kind: 'done'; id: number }
+  | { kind: 'failed'; id: number; error: Message };
+
+/**
+ * Extract waveform peaks in the background, sending them as
+ * `WAVEFORM_STREAM_EVENT`s as they're done
+ *
+ * @returns The stream's id
+ */
+export async function streamWaveformPeaks(
+  filePath: string,
+  options: WaveformOptions = {}
+): Promise<number> {
+  return await invoke<number>('stream_waveform_peaks', { filePath, options });
+}
+
+/**
+ * Stop a waveform stream; false if it had already finished
+ */
+export async function cancelWaveformStream(id: number): Promise<boolean> {
+  return await invoke<boolean>('cancel_waveform_stream', { id });
+}
+
+/**
+ * A named point or range on the timeline, matching `Marker` in Rust; times in seconds
+ */
+export interface Marker {
+  start: number;
+  end?: number | null;
+  title: string;
+}
+
+/**
+ * Image settings, matching `RenderOptions` in Rust; every field is optional.
+ * Colors are `#RRGGBB` or `#RRGGBBAA`.
+ */
+export interface RenderOptions {
+  width?: number;
+  height?: number;
+  foreground?: string;
+  background?: string;
+  /** Drawn as vertical lines at their start */
+  markers?: Marker[];
+  marker_color?: string;
+  /** Highlighted [start, end] range, in seconds */
+  selection?: [number, number] | null;
+  selection_color?: string;
+}
+
+/**
+ * Render a file's waveform to a PNG or SVG image, chosen by the output extension
+ */
+export async function renderWaveformImage(
+  filePath: string,
+  outputPath: string,
+  options: RenderOptions = {}
+): Promise<void> {
+  await invoke('render_waveform_image', { filePath, outputPath, options });
+}